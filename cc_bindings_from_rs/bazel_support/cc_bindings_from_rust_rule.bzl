@@ -114,6 +114,10 @@ def _generate_bindings(ctx, basename, inputs, args, rustc_env):
         )
         outputs.append(error_report_output)
 
+    version_stamp = ctx.attr._version_stamp[BuildSettingInfo].value
+    if version_stamp:
+        crubit_args.add("--version-stamp", version_stamp)
+
     ctx.actions.run(
         outputs = outputs,
         inputs = depset(
@@ -341,6 +345,7 @@ cc_bindings_from_rust_aspect = aspect(
             doc = "Dependencies needed to build the C++ sources generated by cc_bindings_from_rs.",
             default = [
                 "//support/internal:bindings_support",
+                "//support/rs_std:map_view",
                 "//support/rs_std:rs_char",
             ],
         ),
@@ -373,6 +378,9 @@ cc_bindings_from_rust_aspect = aspect(
         "_generate_error_report": attr.label(
             default = "//cc_bindings_from_rs/bazel_support:generate_error_report",
         ),
+        "_version_stamp": attr.label(
+            default = "//cc_bindings_from_rs/bazel_support:version_stamp",
+        ),
     },
     toolchains = [
         "@rules_rust//rust:toolchain_type",
@@ -27,9 +27,9 @@ use rustc_hir::def::{DefKind, Res};
 use rustc_hir::{AssocItemKind, Item, ItemKind, Node, Safety, UseKind, UsePath};
 use rustc_infer::infer::TyCtxtInferExt;
 use rustc_middle::dep_graph::DepContext;
-use rustc_middle::mir::Mutability;
+use rustc_middle::mir::{self, Mutability};
 use rustc_middle::ty::{self, Ty, TyCtxt}; // See <internal link>/ty.html#import-conventions
-use rustc_span::def_id::{DefId, LocalDefId, LOCAL_CRATE};
+use rustc_span::def_id::{DefId, LocalDefId, CRATE_DEF_ID, LOCAL_CRATE};
 use rustc_span::symbol::{kw, sym, Symbol};
 use rustc_target::abi::{Abi, FieldsShape, Integer, Layout, Primitive, Scalar};
 use rustc_target::spec::PanicStrategy;
@@ -39,6 +39,7 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::iter::once;
 use std::ops::AddAssign;
+use std::path::Path;
 use std::rc::Rc;
 use std::slice;
 
@@ -72,6 +73,72 @@ memoized::query_group! {
         #[input]
         fn _features(&self) -> ();
 
+        /// Whether to generate, for each module, a C++ interface with virtual methods
+        /// mirroring the free functions of the module (so that C++ tests can substitute
+        /// fakes for the Rust implementation).
+        #[input]
+        fn generate_mockable_interfaces(&self) -> bool;
+
+        /// Whether to annotate the C++ code that calls into Rust thunks, to avoid
+        /// sanitizer false positives when mixing instrumented C++ with uninstrumented
+        /// Rust.
+        #[input]
+        fn sanitizer_annotations(&self) -> bool;
+
+        /// `clang-tidy` checks to suppress (via `NOLINTBEGIN` / `NOLINTEND`) in the
+        /// generated C++ header.  Empty if no checks should be suppressed.
+        #[input]
+        fn clang_tidy_nolint_checks(&self) -> Rc<[Rc<str>]>;
+
+        /// Naming style of the generated C++ functions and methods.
+        #[input]
+        fn cc_naming_style(&self) -> CcNamingStyle;
+
+        /// Namespace aliases to emit at the end of the generated C++ header, as
+        /// pairs of an alias name and a module path (relative to the crate root).
+        /// For example `("short", "long::nested")` results in
+        /// `namespace short = ::crate_name::long::nested;`.
+        #[input]
+        fn namespace_aliases(&self) -> Rc<[(Rc<str>, Rc<str>)]>;
+
+        /// If set, modules nested at least this deep get an automatic alias
+        /// (named after the innermost module) in the top-level namespace of the
+        /// crate.  For example, with a depth of 3, `::crate_name::a::b::c` can also
+        /// be spelled as `::crate_name::c`.
+        #[input]
+        fn auto_namespace_alias_min_depth(&self) -> Option<usize>;
+
+        /// Whether the generated C++ functions should capture the location of
+        /// their caller (via a defaulted `std::source_location` parameter) and
+        /// pass it to the Rust thunk, so that a Rust panic can report the C++
+        /// call site.
+        #[input]
+        fn cc_call_site_in_panics(&self) -> bool;
+
+        /// Whether to generate bindings that rely on experimental features of
+        /// Crubit (e.g. C++ function templates for generic Rust functions -
+        /// see `format_generic_fn`).
+        #[input]
+        fn experimental_features(&self) -> bool;
+
+        /// Whether the C++ functions generated for `#[inline(always)]` Rust
+        /// functions should be marked `[[gnu::always_inline]]` (see
+        /// `format_always_inline_attr`).
+        #[input]
+        fn always_inline_cc_wrappers(&self) -> bool;
+
+        /// Whether the C++ wrappers of trivial getters of `#[repr(C)]` structs
+        /// should read the field directly (in an inline, `constexpr` definition)
+        /// instead of calling a Rust thunk - see `get_trivial_field_getter`.
+        #[input]
+        fn inline_trivial_getters(&self) -> bool;
+
+        /// Version of Crubit and flags to embed (together with a hash of the
+        /// bindings) into the generated C++ header - see
+        /// `format_crubit_metadata`.  No metadata is generated if `None`.
+        #[input]
+        fn version_stamp(&self) -> Option<VersionStamp>;
+
         fn support_header(&self, suffix: &'tcx str) -> CcInclude;
 
         fn repr_attrs(&self, did: DefId) -> Rc<[rustc_attr::ReprAttr]>;
@@ -111,6 +178,28 @@ pub struct Output {
     pub rs_body: TokenStream,
 }
 
+/// Naming style of the generated C++ functions and methods.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CcNamingStyle {
+    /// Keep the Rust names (e.g. `get_value`).
+    #[default]
+    SnakeCase,
+
+    /// Convert the Rust names to `CamelCase` (e.g. `GetValue`), as expected
+    /// by the Google C++ style guide.
+    CamelCase,
+}
+
+/// Identifies how the bindings have been generated.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VersionStamp {
+    /// Version of Crubit (e.g. a revision stamped by the build system).
+    pub version: Rc<str>,
+
+    /// Space-separated flags that affect the generated bindings.
+    pub flags: Rc<str>,
+}
+
 pub fn generate_bindings(db: &Database) -> Result<Output> {
     let tcx = db.tcx();
     match tcx.sess().panic_strategy() {
@@ -133,6 +222,23 @@ pub fn generate_bindings(db: &Database) -> Result<Output> {
         Output { h_body: src.clone(), rs_body: src }
     });
 
+    let cc_metadata = match db.version_stamp() {
+        None => quote! {},
+        Some(version_stamp) => format_crubit_metadata(db, &version_stamp, &h_body, &rs_body)?,
+    };
+
+    let (nolint_begin, nolint_end) = {
+        let checks = db.clang_tidy_nolint_checks();
+        if checks.is_empty() {
+            (quote! {}, quote! {})
+        } else {
+            let checks = checks.iter().join(", ");
+            let begin = format!("NOLINTBEGIN({checks})");
+            let end = format!("NOLINTEND({checks})");
+            (quote! { __COMMENT__ #begin __NEWLINE__ }, quote! { __COMMENT__ #end __NEWLINE__ })
+        }
+    };
+
     let h_body = quote! {
         #top_comment
 
@@ -140,7 +246,16 @@ pub fn generate_bindings(db: &Database) -> Result<Output> {
         __HASH_TOKEN__ pragma once __NEWLINE__
         __NEWLINE__
 
+        #nolint_begin
         #h_body
+        #cc_metadata
+        #nolint_end
+    };
+
+    let rs_call_site_support = if db.cc_call_site_in_panics() {
+        format_rs_call_site_support()
+    } else {
+        quote! {}
     };
 
     let rs_body = quote! {
@@ -153,12 +268,119 @@ pub fn generate_bindings(db: &Database) -> Result<Output> {
 
         __NEWLINE__
 
+        #rs_call_site_support
         #rs_body
     };
 
     Ok(Output { h_body, rs_body })
 }
 
+/// Formats the `__crubit_cc_call_site` module that Rust thunks use (when
+/// `db.cc_call_site_in_panics()` is enabled) to record the C++ call site for the
+/// duration of a call.  A panic hook (installed when the first thunk is called)
+/// prints the recorded call site after the panic message.
+fn format_rs_call_site_support() -> TokenStream {
+    quote! {
+        #[allow(dead_code)]
+        mod __crubit_cc_call_site {
+            use ::core::cell::Cell;
+            use ::core::ffi::{c_char, CStr};
+
+            ::std::thread_local! {
+                static CALL_SITE: Cell<Option<(*const c_char, u32)>> = const { Cell::new(None) };
+            }
+
+            /// Records the C++ call site until dropped (restoring the call site of
+            /// the outer call, if any).
+            pub struct Guard(Option<(*const c_char, u32)>);
+
+            impl Guard {
+                /// `file` needs to be either null, or a pointer to a NUL-terminated
+                /// string that outlives the program (e.g. the result of
+                /// `std::source_location::file_name`).
+                pub fn new(file: *const c_char, line: u32) -> Self {
+                    static INSTALL_HOOK: ::std::sync::Once = ::std::sync::Once::new();
+                    INSTALL_HOOK.call_once(|| {
+                        let prev_hook = ::std::panic::take_hook();
+                        ::std::panic::set_hook(::std::boxed::Box::new(move |info| {
+                            prev_hook(info);
+                            if let Some((file, line)) = CALL_SITE.with(Cell::get) {
+                                // SAFETY: See the doc comment of `Guard::new`.
+                                let file = unsafe { CStr::from_ptr(file) }.to_string_lossy();
+                                ::std::eprintln!("note: called from C++ at {file}:{line}");
+                            }
+                        }));
+                    });
+                    let call_site = if file.is_null() { None } else { Some((file, line)) };
+                    Guard(CALL_SITE.with(|cell| cell.replace(call_site)))
+                }
+            }
+
+            impl Drop for Guard {
+                fn drop(&mut self) {
+                    CALL_SITE.with(|cell| cell.set(self.0));
+                }
+            }
+        }
+        __NEWLINE__
+    }
+}
+
+/// Formats the `__crubit_metadata` namespace of the C++ header, which
+/// identifies the Crubit `version_stamp` that generated the bindings, and a
+/// hash of the bindings themselves.  This lets binaries report which Crubit
+/// produced their bindings, and lets builds detect (e.g. via a `static_assert`)
+/// libraries whose bindings have been generated by mismatched versions of
+/// Crubit.
+///
+/// The metadata is only emitted into the C++ header: the generated Rust code
+/// is an implementation detail of the header, which no Rust crate depends on.
+///
+/// The metadata only depends on the inputs of the tool (rather than e.g. on
+/// the current time), so that the generated bindings stay reproducible.
+fn format_crubit_metadata(
+    db: &Database,
+    version_stamp: &VersionStamp,
+    h_body: &TokenStream,
+    rs_body: &TokenStream,
+) -> Result<TokenStream> {
+    let crate_name = format_cc_ident(db.tcx().crate_name(LOCAL_CRATE).as_str())?;
+    let VersionStamp { version, flags } = version_stamp;
+    let version = version.as_ref();
+    let flags = flags.as_ref();
+    let bindings_hash = {
+        let bindings = format!("{h_body}\n{rs_body}");
+        format!("{:016x}", fnv1a_64(bindings.as_bytes()))
+    };
+
+    Ok(quote! {
+        __NEWLINE__
+        namespace #crate_name::__crubit_metadata {
+            __NEWLINE__
+            __COMMENT__ "Identifies the Crubit that generated these bindings."
+            struct CrubitMetadata {
+                const char* version;
+                const char* flags;
+                const char* bindings_hash;
+            };
+            __NEWLINE__
+            inline constexpr CrubitMetadata kCrubitMetadata = {
+                #version, #flags, #bindings_hash
+            };
+            __NEWLINE__
+        }
+        __NEWLINE__
+    })
+}
+
+/// Returns the 64-bit FNV-1a hash of `bytes`.  Unlike `DefaultHasher`, the
+/// hash is guaranteed to be the same for every build of the tool.
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
 #[derive(Clone, Debug, Default)]
 struct CcPrerequisites {
     /// Set of `#include`s that a `CcSnippet` depends on.  For example if
@@ -262,11 +484,21 @@ struct FullyQualifiedName {
     /// For example, this would be `std` for `std::cmp::Ordering`.
     krate: Symbol,
 
-    /// Path to the module where the item is located.
+    /// C++ namespace path of the module where the item is located.
     /// For example, this would be `cmp` for `std::cmp::Ordering`.
     /// The path may contain multiple modules - e.g. `foo::bar::baz`.
+    ///
+    /// This is the logical path of the module (independent of `#[path]`
+    /// attributes and of the macros that may have generated the modules),
+    /// using the `#[__crubit::annotate(cpp_name="...")]` of each module
+    /// when present.
     mod_path: NamespaceQualifier,
 
+    /// Rust path of the module where the item is located.  This is the same
+    /// as `mod_path`, except that it always uses the Rust names of the
+    /// modules.
+    rs_mod_path: NamespaceQualifier,
+
     /// Name of the item.
     /// For example, this would be:
     /// * `Some("Ordering")` for `std::cmp::Ordering`.
@@ -293,18 +525,29 @@ impl FullyQualifiedName {
         // never" be malformed.
         let cc_type = crubit_attr::get(tcx, def_id).unwrap().cc_type;
 
-        let mut full_path = tcx.def_path(def_id).data; // mod_path + name
-        let name = full_path.pop().expect("At least the item's name should be present");
-        let name = name.data.get_opt_name();
-
-        let mod_path = NamespaceQualifier::new(
-            full_path
-                .into_iter()
-                .filter_map(|p| p.data.get_opt_name())
-                .map(|s| Rc::<str>::from(s.as_str())),
-        );
+        // `None` for the crate root.
+        let name = tcx.def_key(def_id).disambiguated_data.data.get_opt_name();
+
+        // Only the enclosing modules contribute to the path (and not e.g. the
+        // function whose body contains the item).
+        let mut rs_mod_names = vec![];
+        let mut cc_mod_names = vec![];
+        let mut ancestor = tcx.opt_parent(def_id);
+        while let Some(mod_def_id) = ancestor.filter(|id| !id.is_crate_root()) {
+            if tcx.def_kind(mod_def_id) == DefKind::Mod {
+                let rs_name = tcx.item_name(mod_def_id);
+                // Crash OK: see `cc_type` above.
+                let cc_name =
+                    crubit_attr::get(tcx, mod_def_id).unwrap().cpp_name.unwrap_or(rs_name);
+                rs_mod_names.push(Rc::<str>::from(rs_name.as_str()));
+                cc_mod_names.push(Rc::<str>::from(cc_name.as_str()));
+            }
+            ancestor = tcx.opt_parent(mod_def_id);
+        }
+        let mod_path = NamespaceQualifier::new(cc_mod_names.into_iter().rev());
+        let rs_mod_path = NamespaceQualifier::new(rs_mod_names.into_iter().rev());
 
-        Self { krate, mod_path, name, cc_type }
+        Self { krate, mod_path, rs_mod_path, name, cc_type }
     }
 
     fn format_for_cc(&self) -> Result<TokenStream> {
@@ -327,7 +570,7 @@ impl FullyQualifiedName {
             self.name.as_ref().expect("`format_for_rs` can't be called on name-less item kinds");
 
         let krate = make_rs_ident(self.krate.as_str());
-        let mod_path = self.mod_path.format_for_rs();
+        let mod_path = self.rs_mod_path.format_for_rs();
         let name = make_rs_ident(name.as_str());
         quote! { :: #krate :: #mod_path #name }
     }
@@ -335,7 +578,7 @@ impl FullyQualifiedName {
 
 /// Whether functions using `extern "C"` ABI can safely handle values of type
 /// `ty` (e.g. when passing by value arguments or return values of such type).
-fn is_c_abi_compatible_by_value(ty: Ty) -> bool {
+fn is_c_abi_compatible_by_value<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
     match ty.kind() {
         // `improper_ctypes_definitions` warning doesn't complain about the following types:
         ty::TyKind::Bool |
@@ -361,14 +604,20 @@ fn is_c_abi_compatible_by_value(ty: Ty) -> bool {
         // - To replicate field offsets, Crubit may insert explicit padding fields. These
         //   extra fields may also impact the ABI of the generated bindings.
         //
+        // `#[repr(C)]` structs are an exception - see `is_repr_c_struct_abi_compatible`.
+        //
         // TODO(lukasza): In the future, some additional performance gains may be realized by
-        // returning `true` in a few limited cases (this may require additional complexity to
-        // ensure that `format_adt` never injects explicit padding into such structs):
-        // - `#[repr(C)]` structs and unions,
+        // returning `true` in a few more limited cases (this may require additional complexity
+        // to ensure that `format_adt` never injects explicit padding into such types):
+        // - `#[repr(C)]` unions,
         // - `#[repr(transparent)]` struct that wraps an ABI-safe type,
         // - Discriminant-only enums (b/259984090).
-        ty::TyKind::Tuple{..} |  // An empty tuple (`()` - the unit type) is handled above.
-        ty::TyKind::Adt{..} => false,
+        ty::TyKind::Adt(adt_def, substs) => is_repr_c_struct_abi_compatible(tcx, *adt_def, *substs),
+        ty::TyKind::Tuple{..} => false,  // An empty tuple (`()` - the unit type) is handled above.
+
+        // Arrays can't be passed by value in C/C++ (and `improper_ctypes_definitions` warns
+        // about them), so `[T; N]` is passed by pointer (as `std::array<T, N>*` on the C++ side).
+        ty::TyKind::Array{..} => false,
 
         // These kinds of reference-related types are not implemented yet - `is_c_abi_compatible_by_value`
         // should never need to handle them, because `format_ty_for_cc` fails for such types.
@@ -380,7 +629,6 @@ fn is_c_abi_compatible_by_value(ty: Ty) -> bool {
         // - References to slices (`&[T]`) or strings (`&str`) rely on assumptions
         //   spelled out in `rust_builtin_type_abi_assumptions.md`..
         ty::TyKind::Str |
-        ty::TyKind::Array{..} |
         ty::TyKind::Slice{..} =>
             unimplemented!(),
 
@@ -391,6 +639,77 @@ fn is_c_abi_compatible_by_value(ty: Ty) -> bool {
     }
 }
 
+/// Whether a `#[repr(C)]` struct can be passed by value to/from `extern "C"`
+/// functions (without going through a `MaybeUninit` pointer).
+///
+/// This is only true when the generated C++ struct is guaranteed to have the
+/// same ABI as the Rust struct:
+/// - `format_adt` omits explicit padding for `#[repr(C)]` structs when the C++
+///   type of all the fields is known.  This is guaranteed by only accepting
+///   fields of scalar types that always have a C++ equivalent (and that don't
+///   get replaced with a blob of bytes).
+/// - The generated C++ struct is trivially copyable and trivially destructible
+///   when the Rust struct is `Copy` (which also means that the Rust side
+///   doesn't need to worry about moving out of the argument).
+/// - Other `repr` attributes (e.g. `packed` or `align(N)`) may affect the ABI
+///   (and may require explicit padding) and therefore are not supported.
+/// - `PhantomData` fields are ignored, because they are zero-sized and 1-byte
+///   aligned (and therefore don't affect the ABI), and because `format_fields`
+///   omits them from the C++ struct.  At least one other field is required,
+///   because an empty C++ struct is not zero-sized.
+fn is_repr_c_struct_abi_compatible<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    adt_def: ty::AdtDef<'tcx>,
+    substs: ty::GenericArgsRef<'tcx>,
+) -> bool {
+    let repr = adt_def.repr();
+    let field_tys = adt_def
+        .all_fields()
+        .map(|field| field.ty(tcx, substs))
+        .filter(|ty| !is_phantom_data(*ty))
+        .collect_vec();
+    if !adt_def.is_struct()
+        || !substs.is_empty()
+        || !repr.c()
+        || repr.packed()
+        || repr.align.is_some()
+        || repr.simd()
+        || field_tys.is_empty()
+    {
+        return false;
+    }
+    let param_env = tcx.param_env(adt_def.did());
+    let self_ty = tcx.type_of(adt_def.did()).instantiate_identity();
+    if !self_ty.is_copy_modulo_regions(tcx, param_env) {
+        return false;
+    }
+    field_tys.iter().all(|ty| {
+        matches!(
+            ty.kind(),
+            ty::TyKind::Bool
+                | ty::TyKind::Float(_)
+                | ty::TyKind::Int(
+                    ty::IntTy::Isize
+                        | ty::IntTy::I8
+                        | ty::IntTy::I16
+                        | ty::IntTy::I32
+                        | ty::IntTy::I64
+                )
+                | ty::TyKind::Uint(
+                    ty::UintTy::Usize
+                        | ty::UintTy::U8
+                        | ty::UintTy::U16
+                        | ty::UintTy::U32
+                        | ty::UintTy::U64
+                )
+        )
+    })
+}
+
+fn is_phantom_data(ty: Ty<'_>) -> bool {
+    matches!(ty.kind(), ty::TyKind::Adt(adt_def, _) if adt_def.is_phantom_data())
+}
+
 /// Location where a type is used.
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 enum TypeLocation {
@@ -583,6 +902,22 @@ fn format_ty_for_cc<'tcx>(
             CcSnippet { tokens: FullyQualifiedName::new(tcx, def_id).format_for_cc()?, prereqs }
         }
 
+        ty::TyKind::Array(element_ty, len) => {
+            let len = len
+                .try_eval_target_usize(tcx, ty::ParamEnv::empty())
+                .ok_or_else(|| anyhow!("Array length of `{ty}` can't be evaluated"))?;
+            // `std::array<T, 0>` is not a zero-sized type in C++ (unlike `[T; 0]` in Rust).
+            ensure!(len > 0, "Zero-length arrays are not supported yet (b/258259459)");
+            let CcSnippet { tokens: element_tokens, mut prereqs } = db
+                .format_ty_for_cc(*element_ty, TypeLocation::Other)
+                .with_context(|| {
+                    format!("Failed to format the element type of the array type `{ty}`")
+                })?;
+            prereqs.includes.insert(CcInclude::array());
+            let len = Literal::u64_unsuffixed(len);
+            CcSnippet { tokens: quote! { std::array<#element_tokens, #len> }, prereqs }
+        }
+
         ty::TyKind::RawPtr(pointee_ty, mutbl) => {
             format_pointer_or_reference_ty_for_cc(db, *pointee_ty, *mutbl, quote! { * })
                 .with_context(|| {
@@ -616,7 +951,7 @@ fn format_ty_for_cc<'tcx>(
                 Some(sig) => sig,
             };
             check_fn_sig(&sig)?;
-            is_thunk_required(&sig).context("Function pointers can't have a thunk")?;
+            is_thunk_required(tcx, &sig).context("Function pointers can't have a thunk")?;
 
             // `is_thunk_required` check above implies `extern "C"` (or `"C-unwind"`).
             // This assertion reinforces that the generated C++ code doesn't need
@@ -706,9 +1041,26 @@ fn format_ty_for_rs(tcx: TyCtxt, ty: Ty) -> Result<TokenStream> {
                 bail!("Tuples are not supported yet: {} (b/254099023)", ty);
             }
         }
-        ty::TyKind::Adt(adt, substs) => {
-            ensure!(substs.len() == 0, "Generic types are not supported yet (b/259749095)");
-            FullyQualifiedName::new(tcx, adt.did()).format_for_rs()
+        ty::TyKind::Adt(adt, substs) => match get_map_types(tcx, ty) {
+            Some(MapTypes { rs_path, key_ty, value_ty, .. }) => {
+                let key_ty = format_ty_for_rs(tcx, key_ty)?;
+                let value_ty = format_ty_for_rs(tcx, value_ty)?;
+                quote! { #rs_path<#key_ty, #value_ty> }
+            }
+            None => {
+                ensure!(substs.len() == 0, "Generic types are not supported yet (b/259749095)");
+                FullyQualifiedName::new(tcx, adt.did()).format_for_rs()
+            }
+        },
+        ty::TyKind::Array(element_ty, len) => {
+            let len = len
+                .try_eval_target_usize(tcx, ty::ParamEnv::empty())
+                .ok_or_else(|| anyhow!("Array length of `{ty}` can't be evaluated"))?;
+            let len = Literal::u64_unsuffixed(len);
+            let element_ty = format_ty_for_rs(tcx, *element_ty).with_context(|| {
+                format!("Failed to format the element type of the array type `{ty}`")
+            })?;
+            quote! { [#element_ty; #len] }
         }
         ty::TyKind::RawPtr(pointee_ty, mutbl) => {
             let qualifier = match mutbl {
@@ -807,12 +1159,91 @@ fn liberate_and_deanonymize_late_bound_regions<'tcx>(
     tcx.instantiate_bound_regions_uncached(sig, region_f)
 }
 
+/// Returns the signature of `fn_def_id`.  Early-bound lifetime parameters
+/// (e.g. lifetimes with outlives bounds, like `'a` in `fn f<'a: 'b, 'b>`)
+/// are replaced with named regions, so that they can be handled the same way
+/// as late-bound lifetimes.
 fn get_fn_sig(tcx: TyCtxt, fn_def_id: LocalDefId) -> ty::FnSig {
     let fn_def_id = fn_def_id.to_def_id(); // LocalDefId => DefId
-    let sig = tcx.fn_sig(fn_def_id).instantiate_identity();
+    let args = ty::GenericArgs::for_item(tcx, fn_def_id, |param, _| match param.kind {
+        ty::GenericParamDefKind::Lifetime => ty::Region::new_late_param(
+            tcx,
+            fn_def_id,
+            ty::BoundRegionKind::BrNamed(param.def_id, param.name),
+        )
+        .into(),
+        _ => tcx.mk_param_from_def(param),
+    });
+    let sig = tcx.fn_sig(fn_def_id).instantiate(tcx, args);
     liberate_and_deanonymize_late_bound_regions(tcx, sig, fn_def_id)
 }
 
+/// Returns `true` if `fn_def_id` has no generic parameters other than
+/// lifetimes declared on the function itself (i.e. lifetime parameters of
+/// the enclosing `impl` block are still considered generic).
+fn is_lifetime_generic_only(tcx: TyCtxt, fn_def_id: DefId) -> bool {
+    let generics = tcx.generics_of(fn_def_id);
+    generics.parent_count == 0
+        && generics
+            .own_params
+            .iter()
+            .all(|param| matches!(param.kind, ty::GenericParamDefKind::Lifetime))
+}
+
+/// Returns a `ty::Instance` for `fn_def_id`, which has to satisfy
+/// `is_lifetime_generic_only`.  Lifetimes don't affect symbol names, and
+/// therefore they are erased.
+fn instance_with_erased_lifetimes(tcx: TyCtxt, fn_def_id: DefId) -> ty::Instance {
+    assert!(is_lifetime_generic_only(tcx, fn_def_id));
+    let args = ty::GenericArgs::for_item(tcx, fn_def_id, |_, _| tcx.lifetimes.re_erased.into());
+    ty::Instance::new(fn_def_id, args)
+}
+
+/// Returns the `CRUBIT_INTERNAL_NO_SANITIZE` attribute for a C++ function that
+/// calls into a Rust thunk (or nothing if `--sanitizer-annotations` is not
+/// enabled).
+fn format_no_sanitize_attr<'tcx>(
+    db: &dyn BindingsGenerator<'tcx>,
+    prereqs: &mut CcPrerequisites,
+) -> TokenStream {
+    if !db.sanitizer_annotations() {
+        return quote! {};
+    }
+    prereqs.includes.insert(db.support_header("internal/sanitizers.h"));
+    quote! { CRUBIT_INTERNAL_NO_SANITIZE }
+}
+
+/// Returns `[[gnu::always_inline]]` if the Rust function `def_id` is
+/// `#[inline(always)]` and `--always-inline-cc-wrappers` is enabled (or nothing
+/// otherwise).  This keeps the call overhead of the C++ wrapper (which just
+/// forwards to the exported Rust symbol or to the Rust thunk) minimal in hot
+/// paths.
+fn format_always_inline_attr(db: &dyn BindingsGenerator<'_>, def_id: DefId) -> TokenStream {
+    if !db.always_inline_cc_wrappers() {
+        return quote! {};
+    }
+    match db.tcx().codegen_fn_attrs(def_id).inline {
+        rustc_attr::InlineAttr::Always => quote! { [[gnu::always_inline]] },
+        _ => quote! {},
+    }
+}
+
+/// Returns a C++ statement that marks `__ret_slot` (a `ReturnValueSlot` of
+/// `ret_type` that has been written by uninstrumented Rust code) as
+/// initialized for MSan (or nothing if `--sanitizer-annotations` is not
+/// enabled).
+fn format_msan_unpoison_ret_slot<'tcx>(
+    db: &dyn BindingsGenerator<'tcx>,
+    ret_type: &TokenStream,
+    prereqs: &mut CcPrerequisites,
+) -> TokenStream {
+    if !db.sanitizer_annotations() {
+        return quote! {};
+    }
+    prereqs.includes.insert(db.support_header("internal/sanitizers.h"));
+    quote! { crubit::MsanUnpoison(__ret_slot.Get(), sizeof(#ret_type)); }
+}
+
 /// Formats a C++ function declaration of a thunk that wraps a Rust function
 /// identified by `fn_def_id`.  `format_thunk_impl` may panic if `fn_def_id`
 /// doesn't identify a function.
@@ -821,11 +1252,21 @@ fn format_thunk_decl<'tcx>(
     fn_def_id: DefId,
     sig: &ty::FnSig<'tcx>,
     thunk_name: &TokenStream,
+    with_call_site: bool,
 ) -> Result<CcSnippet> {
     let tcx = db.tcx();
 
     let mut prereqs = CcPrerequisites::default();
-    let main_api_ret_type = format_ret_ty_for_cc(db, sig)?.into_tokens(&mut prereqs);
+    // The C++ function creates the `rs_std::MapView` from the map pointer returned by the thunk.
+    let main_api_ret_type = if get_referenced_map(tcx, sig.output()).is_some() {
+        quote! { const void* }
+    } else if is_static_str_ref(sig.output()) {
+        // The C++ function creates the `std::string_view` from the pointer returned by the thunk,
+        // and the number of characters written to `__ret_size`.
+        quote! { const char* }
+    } else {
+        format_ret_ty_for_cc(db, sig)?.into_tokens(&mut prereqs)
+    };
 
     let mut thunk_params = {
         let cc_types = format_param_types_for_cc(db, sig)?;
@@ -834,7 +1275,7 @@ fn format_thunk_decl<'tcx>(
             .zip(cc_types.into_iter())
             .map(|(&ty, cc_type)| -> Result<TokenStream> {
                 let cc_type = cc_type.into_tokens(&mut prereqs);
-                if is_c_abi_compatible_by_value(ty) {
+                if is_c_abi_compatible_by_value(tcx, ty) {
                     Ok(quote! { #cc_type })
                 } else {
                     // Rust thunk will move a value via memcpy - we need to `ensure` that
@@ -851,12 +1292,21 @@ fn format_thunk_decl<'tcx>(
     };
 
     let thunk_ret_type: TokenStream;
-    if is_c_abi_compatible_by_value(sig.output()) {
+    if is_c_abi_compatible_by_value(tcx, sig.output()) {
         thunk_ret_type = main_api_ret_type;
     } else {
         thunk_ret_type = quote! { void };
         thunk_params.push(quote! { #main_api_ret_type* __ret_ptr });
     };
+    if is_static_str_ref(sig.output()) {
+        prereqs.includes.insert(CcInclude::cstddef());
+        thunk_params.push(quote! { std::size_t* __ret_size });
+    }
+    if with_call_site {
+        prereqs.includes.insert(CcInclude::cstdint());
+        thunk_params.push(quote! { const char* __crubit_file });
+        thunk_params.push(quote! { std::uint32_t __crubit_line });
+    }
     Ok(CcSnippet {
         prereqs,
         tokens: quote! {
@@ -877,12 +1327,23 @@ fn format_thunk_decl<'tcx>(
 /// - `::crate_name::some_module::SomeStruct::method`
 /// - `<::crate_name::some_module::SomeStruct as
 ///   ::core::default::Default>::default`
+///
+/// `self_smart_pointer` (e.g. `::std::boxed::Box`) should be specified when
+/// the function expects a `self: Box<Self>` receiver (or `Rc`, `Arc`, etc.),
+/// but `sig` takes `self: Self` - in this case the thunk will wrap the `self`
+/// argument in a new smart pointer before calling the function.
+///
+/// `with_call_site` adds trailing `__crubit_file` and `__crubit_line`
+/// parameters, which are recorded (see `format_rs_call_site_support`) for the
+/// duration of the call.
 fn format_thunk_impl<'tcx>(
     tcx: TyCtxt<'tcx>,
     fn_def_id: DefId,
     sig: &ty::FnSig<'tcx>,
     thunk_name: &str,
     fully_qualified_fn_name: TokenStream,
+    self_smart_pointer: Option<TokenStream>,
+    with_call_site: bool,
 ) -> Result<TokenStream> {
     let param_names_and_types: Vec<(Ident, Ty)> = {
         let param_names = tcx.fn_arg_names(fn_def_id).iter().enumerate().map(|(i, ident)| {
@@ -903,7 +1364,7 @@ fn format_thunk_impl<'tcx>(
         .map(|(param_name, ty)| {
             let rs_type = format_ty_for_rs(tcx, *ty)
                 .with_context(|| format!("Error handling parameter `{param_name}`"))?;
-            Ok(if is_c_abi_compatible_by_value(*ty) {
+            Ok(if is_c_abi_compatible_by_value(tcx, *ty) {
                 quote! { #param_name: #rs_type }
             } else {
                 quote! { #param_name: &mut ::core::mem::MaybeUninit<#rs_type> }
@@ -911,16 +1372,27 @@ fn format_thunk_impl<'tcx>(
         })
         .collect::<Result<Vec<_>>>()?;
 
-    let mut thunk_ret_type = format_ty_for_rs(tcx, sig.output())?;
+    // A `&'static str` is returned as a pointer to the characters (and their number is written
+    // to `__ret_size`), since `str` isn't FFI-safe.
+    let returns_static_str = is_static_str_ref(sig.output());
+    let mut thunk_ret_type = if returns_static_str {
+        quote! { *const u8 }
+    } else {
+        format_ty_for_rs(tcx, sig.output())?
+    };
     let mut thunk_body = {
-        let fn_args = param_names_and_types.iter().map(|(rs_name, ty)| {
-            if is_c_abi_compatible_by_value(*ty) {
+        let fn_args = param_names_and_types.iter().enumerate().map(|(i, (rs_name, ty))| {
+            let arg = if is_c_abi_compatible_by_value(tcx, *ty) {
                 quote! { #rs_name }
             } else if let Safety::Unsafe = sig.safety {
                 // The whole call will be wrapped in `unsafe` below.
                 quote! { #rs_name.assume_init_read() }
             } else {
                 quote! { unsafe { #rs_name.assume_init_read() } }
+            };
+            match &self_smart_pointer {
+                Some(smart_pointer) if i == 0 => quote! { #smart_pointer::new(#arg) },
+                _ => arg,
             }
         });
         quote! {
@@ -932,13 +1404,30 @@ fn format_thunk_impl<'tcx>(
     if let Safety::Unsafe = sig.safety {
         thunk_body = quote! {unsafe {#thunk_body}};
     }
-    if !is_c_abi_compatible_by_value(sig.output()) {
+    if !is_c_abi_compatible_by_value(tcx, sig.output()) {
         thunk_params.push(quote! {
             __ret_slot: &mut ::core::mem::MaybeUninit<#thunk_ret_type>
         });
         thunk_ret_type = quote! { () };
         thunk_body = quote! { __ret_slot.write(#thunk_body); };
     };
+    if returns_static_str {
+        thunk_params.push(quote! { __ret_size: &mut usize });
+        thunk_body = quote! {
+            let __ret: &'static str = #thunk_body;
+            *__ret_size = __ret.len();
+            __ret.as_ptr()
+        };
+    }
+    if with_call_site {
+        thunk_params.push(quote! { __crubit_file: *const ::core::ffi::c_char });
+        thunk_params.push(quote! { __crubit_line: u32 });
+        thunk_body = quote! {
+            let __crubit_call_site =
+                crate::__crubit_cc_call_site::Guard::new(__crubit_file, __crubit_line);
+            #thunk_body
+        };
+    }
 
     let generic_params = {
         let regions = sig
@@ -972,6 +1461,42 @@ fn format_thunk_impl<'tcx>(
         }
     };
 
+    // Outlives bounds of the function (e.g. `'a: 'b` in `fn f<'a: 'b, 'b>(...)`) need to be
+    // replicated in the thunk - otherwise the thunk wouldn't be able to call the function.
+    let where_clause = {
+        let is_thunk_region = |region: &ty::Region| {
+            region.is_static()
+                || sig.inputs().iter().chain(once(&sig.output())).any(|ty| {
+                    ty.walk().any(|arg| match arg.unpack() {
+                        ty::GenericArgKind::Lifetime(r) => r.get_name() == region.get_name(),
+                        _ => false,
+                    })
+                })
+        };
+        let bounds = tcx
+            .predicates_of(fn_def_id)
+            .predicates
+            .iter()
+            .filter_map(|(clause, _span)| match clause.kind().skip_binder() {
+                ty::ClauseKind::RegionOutlives(ty::OutlivesPredicate(longer, shorter)) => {
+                    Some((longer, shorter))
+                }
+                _ => None,
+            })
+            .filter(|(longer, shorter)| is_thunk_region(longer) && is_thunk_region(shorter))
+            .map(|(longer, shorter)| {
+                let longer = format_region_as_rs_lifetime(&longer);
+                let shorter = format_region_as_rs_lifetime(&shorter);
+                quote! { #longer: #shorter }
+            })
+            .collect_vec();
+        if bounds.is_empty() {
+            quote! {}
+        } else {
+            quote! { where #( #bounds ),* }
+        }
+    };
+
     let thunk_name = make_rs_ident(thunk_name);
     let unsafe_qualifier = if let Safety::Unsafe = sig.safety {
         quote! {unsafe}
@@ -982,12 +1507,295 @@ fn format_thunk_impl<'tcx>(
         #[no_mangle]
         #unsafe_qualifier extern "C" fn #thunk_name #generic_params (
             #( #thunk_params ),*
-        ) -> #thunk_ret_type {
+        ) -> #thunk_ret_type #where_clause {
             #thunk_body
         }
     })
 }
 
+/// Returns the path of the smart pointer type (e.g. `::std::boxed::Box`) if
+/// `ty` is `Box<pointee_ty>`, `Rc<pointee_ty>`, or `Arc<pointee_ty>`.
+fn get_smart_pointer_to<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: Ty<'tcx>,
+    pointee_ty: Ty<'tcx>,
+) -> Option<TokenStream> {
+    let ty::TyKind::Adt(adt, substs) = ty.kind() else {
+        return None;
+    };
+    if substs.types().next() != Some(pointee_ty) {
+        return None;
+    }
+    if adt.is_box() {
+        Some(quote! { ::std::boxed::Box })
+    } else if tcx.is_diagnostic_item(sym::Rc, adt.did()) {
+        Some(quote! { ::std::rc::Rc })
+    } else if tcx.is_diagnostic_item(sym::Arc, adt.did()) {
+        Some(quote! { ::std::sync::Arc })
+    } else {
+        None
+    }
+}
+
+/// The key and value types of a `HashMap<K, V>` or a `BTreeMap<K, V>`.
+struct MapTypes<'tcx> {
+    /// The public path of the map type (e.g. `::std::collections::HashMap`).
+    rs_path: TokenStream,
+    /// The traits that the key type needs to implement for the lookups.
+    key_traits: &'static [Symbol],
+    key_ty: Ty<'tcx>,
+    value_ty: Ty<'tcx>,
+}
+
+/// Returns the `MapTypes` of `ty` if `ty` is a `HashMap<K, V>` or a
+/// `BTreeMap<K, V>`.  Maps with a custom hasher (or allocator) are not
+/// supported, because the public path of their type arguments isn't known.
+fn get_map_types<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<MapTypes<'tcx>> {
+    let ty::TyKind::Adt(adt, substs) = ty.kind() else {
+        return None;
+    };
+    let (rs_path, key_traits): (_, &'static [Symbol]) =
+        if tcx.is_diagnostic_item(sym::HashMap, adt.did()) {
+            (quote! { ::std::collections::HashMap }, &[sym::Hash, sym::Eq])
+        } else if tcx.is_diagnostic_item(sym::BTreeMap, adt.did()) {
+            (quote! { ::std::collections::BTreeMap }, &[sym::Ord])
+        } else {
+            return None;
+        };
+    let has_default_type_args =
+        tcx.generics_of(adt.did()).own_params.iter().skip(2).all(|param| match param.kind {
+            ty::GenericParamDefKind::Type { has_default: true, .. } => {
+                substs.type_at(param.index as usize)
+                    == tcx.type_of(param.def_id).instantiate(tcx, substs)
+            }
+            _ => false,
+        });
+    if !has_default_type_args {
+        return None;
+    }
+    Some(MapTypes { rs_path, key_traits, key_ty: substs.type_at(0), value_ty: substs.type_at(1) })
+}
+
+/// Returns the map type and its `MapTypes` if `ty` is a shared reference to a
+/// map (see `get_map_types`).  Such references are returned to C++ as a
+/// `rs_std::MapView<K, V>`.
+fn get_referenced_map<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<(Ty<'tcx>, MapTypes<'tcx>)> {
+    match ty.kind() {
+        ty::TyKind::Ref(_, referent_ty, Mutability::Not) => {
+            get_map_types(tcx, *referent_ty).map(|map_types| (*referent_ty, map_types))
+        }
+        _ => None,
+    }
+}
+
+/// Returns `true` if `ty` is `&'static str`.  Such references are returned to
+/// C++ as a `std::string_view` (annotated with the `static` lifetime), which
+/// the thunk passes as a pointer to the characters and their number.
+fn is_static_str_ref(ty: Ty) -> bool {
+    matches!(
+        ty.kind(),
+        ty::TyKind::Ref(region, referent_ty, Mutability::Not)
+            if region.is_static() && referent_ty.is_str()
+    )
+}
+
+/// Returns the C++ name of the field read by `def_id`, if the function is a
+/// trivial getter that can be defined in C++ by reading the field directly
+/// (with `--inline-trivial-getters`).  This is the case for `&self` methods of
+/// `#[repr(C)]` structs whose optimized MIR just copies a field of a primitive
+/// type into the return value - e.g. `fn get_x(&self) -> i32 { self.x }`.
+///
+/// The C++ offsets of all the fields are verified against their Rust offsets
+/// by the `static_assert`s generated by `format_fields`.
+fn get_trivial_field_getter<'tcx>(
+    db: &dyn BindingsGenerator<'tcx>,
+    def_id: DefId,
+    sig: &ty::FnSig<'tcx>,
+    self_ty: Ty<'tcx>,
+) -> Option<TokenStream> {
+    let tcx = db.tcx();
+    let ty::TyKind::Adt(adt_def, _) = self_ty.kind() else {
+        return None;
+    };
+    if !adt_def.is_struct() || !db.repr_attrs(adt_def.did()).contains(&rustc_attr::ReprC) {
+        return None;
+    }
+    let takes_only_self_by_shared_ref = tcx.fn_arg_names(def_id).first().map(|name| name.name)
+        == Some(kw::SelfLower)
+        && matches!(
+            sig.inputs(),
+            [param_ty] if matches!(
+                param_ty.kind(),
+                ty::TyKind::Ref(_, referent_ty, Mutability::Not) if *referent_ty == self_ty
+            )
+        );
+    let returns_primitive = matches!(
+        sig.output().kind(),
+        ty::TyKind::Bool | ty::TyKind::Int(_) | ty::TyKind::Uint(_) | ty::TyKind::Float(_)
+    );
+    if !takes_only_self_by_shared_ref || !returns_primitive {
+        return None;
+    }
+
+    // Expecting a single basic block with `_0 = copy ((*_1).<field>); return;`.
+    let body = tcx.optimized_mir(def_id);
+    if body.basic_blocks.len() != 1 {
+        return None;
+    }
+    let block = &body.basic_blocks[mir::START_BLOCK];
+    if !matches!(block.terminator().kind, mir::TerminatorKind::Return) {
+        return None;
+    }
+    let mut statements = block.statements.iter().filter(|statement| {
+        !matches!(
+            statement.kind,
+            mir::StatementKind::StorageLive(_)
+                | mir::StatementKind::StorageDead(_)
+                | mir::StatementKind::Nop
+        )
+    });
+    let (Some(statement), None) = (statements.next(), statements.next()) else {
+        return None;
+    };
+    let mir::StatementKind::Assign(assign) = &statement.kind else {
+        return None;
+    };
+    let (dest, mir::Rvalue::Use(mir::Operand::Copy(src))) = &**assign else {
+        return None;
+    };
+    if *dest != mir::Place::return_place() {
+        return None;
+    }
+    let mir::PlaceRef {
+        local,
+        projection: [mir::ProjectionElem::Deref, mir::ProjectionElem::Field(field_idx, _)],
+    } = src.as_ref()
+    else {
+        return None;
+    };
+    // `_1` is the first argument (i.e. `self`).
+    if local != mir::Local::from_u32(1) {
+        return None;
+    }
+    let field_def = &adt_def.non_enum_variant().fields[*field_idx];
+    Some(format_cc_field_name(field_def.name, field_idx.as_usize()))
+}
+
+/// Formats the `std::string_view` type that is used in C++ for `&'static str`
+/// (see `is_static_str_ref`).
+fn format_static_str_ref_ty_for_cc(ty: Ty) -> CcSnippet {
+    let ty::TyKind::Ref(region, _, _) = ty.kind() else {
+        panic!("`is_static_str_ref` should be checked by the caller");
+    };
+    let lifetime = format_region_as_cc_lifetime(region);
+    CcSnippet::with_include(quote! { std::string_view #lifetime }, CcInclude::string_view())
+}
+
+/// Formats the `rs_std::MapView<K, V>` type that is used in C++ for a shared
+/// reference to `map_ty`.
+fn format_map_view_ty_for_cc<'tcx>(
+    db: &dyn BindingsGenerator<'tcx>,
+    map_ty: Ty<'tcx>,
+    map_types: &MapTypes<'tcx>,
+) -> Result<CcSnippet> {
+    let tcx = db.tcx();
+    let MapTypes { key_traits, key_ty, value_ty, .. } = *map_types;
+    for &trait_name in key_traits {
+        let trait_id = tcx
+            .get_diagnostic_item(trait_name)
+            .ok_or_else(|| anyhow!("Couldn't find the `{trait_name}` trait"))?;
+        ensure!(
+            does_type_implement_trait(tcx, key_ty, trait_id),
+            "The map key type `{key_ty}` doesn't implement the `{trait_name}` trait"
+        );
+    }
+    let mut prereqs = CcPrerequisites::default();
+    prereqs.includes.insert(db.support_header("rs_std/map_view.h"));
+    let key_cc_type = db
+        .format_ty_for_cc(key_ty, TypeLocation::Other)
+        .with_context(|| format!("Failed to format the key type of `{map_ty}`"))?
+        .into_tokens(&mut prereqs);
+    let value_cc_type = db
+        .format_ty_for_cc(value_ty, TypeLocation::Other)
+        .with_context(|| format!("Failed to format the value type of `{map_ty}`"))?
+        .into_tokens(&mut prereqs);
+    Ok(CcSnippet { tokens: quote! { rs_std::MapView<#key_cc_type, #value_cc_type> }, prereqs })
+}
+
+/// Formats the thunks that implement the lookups of a `rs_std::MapView` of
+/// `map_ty`, and the `VTable` that points at them.  The names of the thunks
+/// and of the `VTable` are `thunk_name` with a `_contains`, `_get`,
+/// `_for_each` and `_vtable` suffix.
+///
+/// Returns the C++ declarations and the Rust thunk implementations.
+fn format_map_view_thunks<'tcx>(
+    db: &dyn BindingsGenerator<'tcx>,
+    map_ty: Ty<'tcx>,
+    map_types: &MapTypes<'tcx>,
+    thunk_name: &str,
+) -> Result<(CcSnippet, TokenStream)> {
+    let tcx = db.tcx();
+    let [contains, get, for_each] =
+        ["contains", "get", "for_each"].map(|suffix| format!("{thunk_name}_{suffix}"));
+
+    let mut prereqs = CcPrerequisites::default();
+    let map_view_cc_type =
+        format_map_view_ty_for_cc(db, map_ty, map_types)?.into_tokens(&mut prereqs);
+    let key_cc_type =
+        db.format_ty_for_cc(map_types.key_ty, TypeLocation::Other)?.into_tokens(&mut prereqs);
+    let value_cc_type =
+        db.format_ty_for_cc(map_types.value_ty, TypeLocation::Other)?.into_tokens(&mut prereqs);
+    let cc_thunk_decls = {
+        let contains = format_cc_ident(&contains)?;
+        let get = format_cc_ident(&get)?;
+        let for_each = format_cc_ident(&for_each)?;
+        let vtable = format_cc_ident(&format!("{thunk_name}_vtable"))?;
+        quote! {
+            namespace __crubit_internal {
+                extern "C" bool #contains(const void*, const #key_cc_type*);
+                extern "C" const #value_cc_type* #get(const void*, const #key_cc_type*);
+                extern "C" void #for_each(
+                    const void*, void*,
+                    void (*)(void*, const #key_cc_type*, const #value_cc_type*));
+                inline constexpr #map_view_cc_type::VTable #vtable = {
+                    &#contains, &#get, &#for_each
+                };
+            }
+        }
+    };
+
+    let map_rs_type = format_ty_for_rs(tcx, map_ty)?;
+    let key_rs_type = format_ty_for_rs(tcx, map_types.key_ty)?;
+    let value_rs_type = format_ty_for_rs(tcx, map_types.value_ty)?;
+    let contains = make_rs_ident(&contains);
+    let get = make_rs_ident(&get);
+    let for_each = make_rs_ident(&for_each);
+    let rs_thunk_impls = quote! {
+        #[no_mangle]
+        extern "C" fn #contains(__map: &#map_rs_type, __key: &#key_rs_type) -> bool {
+            __map.contains_key(__key)
+        }
+        #[no_mangle]
+        extern "C" fn #get<'__map>(
+            __map: &'__map #map_rs_type,
+            __key: &#key_rs_type
+        ) -> ::core::option::Option<&'__map #value_rs_type> {
+            __map.get(__key)
+        }
+        #[no_mangle]
+        extern "C" fn #for_each(
+            __map: &#map_rs_type,
+            __callback: *mut ::core::ffi::c_void,
+            __visit: unsafe extern "C" fn(*mut ::core::ffi::c_void, &#key_rs_type, &#value_rs_type)
+        ) {
+            for (__key, __value) in __map {
+                unsafe { __visit(__callback, __key, __value) }
+            }
+        }
+    };
+    Ok((CcSnippet { tokens: cc_thunk_decls, prereqs }, rs_thunk_impls))
+}
+
 fn check_fn_sig(sig: &ty::FnSig) -> Result<()> {
     if sig.c_variadic {
         // TODO(b/254097223): Add support for variadic functions.
@@ -999,7 +1807,7 @@ fn check_fn_sig(sig: &ty::FnSig) -> Result<()> {
 
 /// Returns `Ok(())` if no thunk is required.
 /// Otherwise returns an error the describes why the thunk is needed.
-fn is_thunk_required(sig: &ty::FnSig) -> Result<()> {
+fn is_thunk_required<'tcx>(tcx: TyCtxt<'tcx>, sig: &ty::FnSig<'tcx>) -> Result<()> {
     match sig.abi {
         // "C" ABI is okay: Before https://rust-lang.github.io/rfcs/2945-c-unwind-abi.html a
         // Rust panic that "escapes" a "C" ABI function leads to Undefined Behavior.  This is
@@ -1022,9 +1830,12 @@ fn is_thunk_required(sig: &ty::FnSig) -> Result<()> {
         _ => bail!("Calling convention other than `extern \"C\"` requires a thunk"),
     };
 
-    ensure!(is_c_abi_compatible_by_value(sig.output()), "Return type requires a thunk");
+    ensure!(is_c_abi_compatible_by_value(tcx, sig.output()), "Return type requires a thunk");
     for (i, param_ty) in sig.inputs().iter().enumerate() {
-        ensure!(is_c_abi_compatible_by_value(*param_ty), "Type of parameter #{i} requires a thunk");
+        ensure!(
+            is_c_abi_compatible_by_value(tcx, *param_ty),
+            "Type of parameter #{i} requires a thunk"
+        );
     }
 
     Ok(())
@@ -1130,14 +1941,12 @@ fn format_use(
             } else {
                 bail!("Unsupported checking for external function");
             }
-            let fully_qualified_fn_name = FullyQualifiedName::new(tcx, def_id);
-            let unqualified_rust_fn_name =
-                fully_qualified_fn_name.name.expect("Functions are assumed to always have a name");
+            let mut fully_qualified_fn_name = FullyQualifiedName::new(tcx, def_id);
+            let cc_fn_name = get_cc_fn_name(db, def_id);
+            fully_qualified_fn_name.name = Some(cc_fn_name);
             let formatted_fully_qualified_fn_name = fully_qualified_fn_name.format_for_cc()?;
-            let cpp_name = crubit_attr::get(tcx, def_id).unwrap().cpp_name;
             let main_api_fn_name =
-                format_cc_ident(cpp_name.unwrap_or(unqualified_rust_fn_name).as_str())
-                    .context("Error formatting function name")?;
+                format_cc_ident(cc_fn_name.as_str()).context("Error formatting function name")?;
             let using_name = format_cc_ident(using_name).context("Error formatting using name")?;
 
             prereqs.defs.insert(def_id.expect_local());
@@ -1193,6 +2002,36 @@ fn create_type_alias<'tcx>(
     })
 }
 
+/// Returns the name of the C++ function generated for the Rust function
+/// `def_id`: either the name given by `#[__crubit::annotate(cpp_name=...)]`,
+/// or the Rust name adapted to `db.cc_naming_style()`.
+fn get_cc_fn_name(db: &dyn BindingsGenerator<'_>, def_id: DefId) -> Symbol {
+    let tcx = db.tcx();
+    if let Some(cpp_name) = crubit_attr::get(tcx, def_id).unwrap().cpp_name {
+        return cpp_name;
+    }
+    let rust_name = tcx.item_name(def_id);
+    match db.cc_naming_style() {
+        CcNamingStyle::SnakeCase => rust_name,
+        CcNamingStyle::CamelCase => Symbol::intern(&snake_case_to_camel_case(rust_name.as_str())),
+    }
+}
+
+/// Converts a `snake_case` name into `CamelCase` (e.g. `get_value` into
+/// `GetValue`).  Leading underscores are preserved.
+fn snake_case_to_camel_case(name: &str) -> String {
+    let words = name.trim_start_matches('_');
+    let mut result = name[..name.len() - words.len()].to_string();
+    for word in words.split('_') {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            result.extend(first.to_uppercase());
+            result.push_str(chars.as_str());
+        }
+    }
+    result
+}
+
 /// Formats a function with the given `local_def_id`.
 ///
 /// Will panic if `local_def_id`
@@ -1202,41 +2041,110 @@ fn format_fn(db: &dyn BindingsGenerator<'_>, local_def_id: LocalDefId) -> Result
     let tcx = db.tcx();
     let def_id: DefId = local_def_id.to_def_id(); // Convert LocalDefId to DefId.
 
-    ensure!(
-        tcx.generics_of(def_id).count() == 0,
-        "Generic functions are not supported yet (b/259749023)"
-    );
+    if !is_lifetime_generic_only(tcx, def_id) {
+        ensure!(
+            db.experimental_features(),
+            "Generic functions are not supported yet (b/259749023)"
+        );
+        return format_generic_fn(db, local_def_id);
+    }
 
     let sig = get_fn_sig(tcx, local_def_id);
     check_fn_sig(&sig)?;
-    // TODO(b/262904507): Don't require thunks for mangled extern "C" functions.
-    let needs_thunk = is_thunk_required(&sig).is_err()
-        || (tcx.get_attr(def_id, rustc_span::symbol::sym::no_mangle).is_none()
-            && tcx.get_attr(def_id, rustc_span::symbol::sym::export_name).is_none());
-    let thunk_name = {
-        let symbol_name = {
-            // Call to `mono` is ok - `generics_of` have been checked above.
-            let instance = ty::Instance::mono(tcx, def_id);
-            tcx.symbol_name(instance).name
-        };
-        if needs_thunk {
-            format!("__crubit_thunk_{}", &escape_non_identifier_chars(symbol_name))
-        } else {
-            symbol_name.to_string()
+
+    let self_ty: Option<Ty> = match tcx.impl_of_method(def_id) {
+        Some(impl_id) => match tcx.impl_subject(impl_id).instantiate_identity() {
+            ty::ImplSubject::Inherent(ty) => Some(ty),
+            ty::ImplSubject::Trait(_) => panic!("Trait methods should be filtered by caller"),
+        },
+        None => None,
+    };
+    ensure!(
+        self_ty.is_none() || !crubit_attr::is_export_macro_shim(tcx, def_id)?,
+        "`#[__crubit::export_macro_shim]` is only supported on free functions"
+    );
+    ensure!(
+        !is_macro_shim(tcx, def_id) || !macros_namespace_collides(tcx, def_id),
+        "The `macros` namespace of `#[__crubit::export_macro_shim]` functions collides with \
+         another item named `macros` in the same module"
+    );
+
+    // `self: Box<Self>`, `self: Rc<Self>`, and `self: Arc<Self>` receivers are exposed to C++
+    // the same way as `self: Self` (i.e. as a `&&`-qualified method that consumes `*this`).
+    // The Rust thunk moves the C++ object into a new smart pointer before calling the method.
+    let self_smart_pointer = match (self_ty, sig.inputs().first()) {
+        (Some(self_ty), Some(&first_param_ty))
+            if tcx.fn_arg_names(def_id).first().map(|name| name.name) == Some(kw::SelfLower) =>
+        {
+            get_smart_pointer_to(tcx, first_param_ty, self_ty)
+        }
+        _ => None,
+    };
+    let sig = match self_smart_pointer {
+        None => sig,
+        Some(_) => {
+            let self_ty = self_ty.expect("`self_smart_pointer` implies non-None `self_ty`");
+            let inputs_and_output = std::iter::once(self_ty)
+                .chain(sig.inputs().iter().skip(1).copied())
+                .chain(std::iter::once(sig.output()));
+            ty::FnSig { inputs_and_output: tcx.mk_type_list_from_iter(inputs_and_output), ..sig }
+        }
+    };
+
+    // A shared reference to a `HashMap` or a `BTreeMap` is returned to C++ as a
+    // `rs_std::MapView`, which calls into additional thunks for the lookups.
+    let referenced_map = get_referenced_map(tcx, sig.output());
+    // A `&'static str` is returned to C++ as a `std::string_view`, which the thunk passes as a
+    // pointer and a length.
+    let returns_static_str = is_static_str_ref(sig.output());
+    // With `--inline-trivial-getters`, a trivial getter is defined in C++ by reading the
+    // field directly, without calling into Rust.
+    let trivial_getter_field = match self_ty {
+        Some(self_ty) if db.inline_trivial_getters() => {
+            get_trivial_field_getter(db, def_id, &sig, self_ty)
+        }
+        _ => None,
+    };
+
+    // TODO(b/262904507): Don't require thunks for mangled extern "C" functions.
+    let needs_thunk = trivial_getter_field.is_none()
+        && (is_thunk_required(tcx, &sig).is_err()
+            || self_smart_pointer.is_some()
+            || referenced_map.is_some()
+            || returns_static_str
+            || (tcx.get_attr(def_id, rustc_span::symbol::sym::no_mangle).is_none()
+                && tcx.get_attr(def_id, rustc_span::symbol::sym::export_name).is_none()));
+    let thunk_name = {
+        let symbol_name = {
+            // `is_lifetime_generic_only` has been checked above.
+            let instance = instance_with_erased_lifetimes(tcx, def_id);
+            tcx.symbol_name(instance).name
+        };
+        if needs_thunk {
+            format!("__crubit_thunk_{}", &escape_non_identifier_chars(symbol_name))
+        } else {
+            symbol_name.to_string()
         }
     };
 
     let fully_qualified_fn_name = FullyQualifiedName::new(tcx, def_id);
     let unqualified_rust_fn_name =
         fully_qualified_fn_name.name.expect("Functions are assumed to always have a name");
-    let attribute = crubit_attr::get(tcx, def_id).unwrap();
-    let cpp_name = attribute.cpp_name;
-    // The generated C++ function name.
-    let main_api_fn_name = format_cc_ident(cpp_name.unwrap_or(unqualified_rust_fn_name).as_str())
-        .context("Error formatting function name")?;
+    let cc_fn_name = get_cc_fn_name(db, def_id);
+    // The generated C++ function name.  Formatting errors are reported below, once it is known
+    // whether the function can still be exposed as a C++ constructor (e.g. `new` is a C++
+    // keyword).
+    let main_api_fn_name =
+        format_cc_ident(cc_fn_name.as_str()).context("Error formatting function name");
 
     let mut main_api_prereqs = CcPrerequisites::default();
-    let main_api_ret_type = format_ret_ty_for_cc(db, &sig)?.into_tokens(&mut main_api_prereqs);
+    let main_api_ret_type = match &referenced_map {
+        Some((map_ty, map_types)) => format_map_view_ty_for_cc(db, *map_ty, map_types)
+            .context("Error formatting function return type")?,
+        None if returns_static_str => format_static_str_ref_ty_for_cc(sig.output()),
+        None => format_ret_ty_for_cc(db, &sig)?,
+    }
+    .into_tokens(&mut main_api_prereqs);
 
     struct Param<'tcx> {
         cc_name: TokenStream,
@@ -1259,14 +2167,6 @@ fn format_fn(db: &dyn BindingsGenerator<'_>, local_def_id: LocalDefId) -> Result
             .collect_vec()
     };
 
-    let self_ty: Option<Ty> = match tcx.impl_of_method(def_id) {
-        Some(impl_id) => match tcx.impl_subject(impl_id).instantiate_identity() {
-            ty::ImplSubject::Inherent(ty) => Some(ty),
-            ty::ImplSubject::Trait(_) => panic!("Trait methods should be filtered by caller"),
-        },
-        None => None,
-    };
-
     let method_kind = match tcx.hir_node_by_def_id(local_def_id) {
         Node::Item(_) => FunctionKind::Free,
         Node::ImplItem(_) => match tcx.fn_arg_names(def_id).first() {
@@ -1313,12 +2213,66 @@ fn format_fn(db: &dyn BindingsGenerator<'_>, local_def_id: LocalDefId) -> Result
         },
         None => None,
     };
-    let needs_definition = unqualified_rust_fn_name.as_str() != thunk_name;
+    let needs_definition = trivial_getter_field.is_some() || cc_fn_name.as_str() != thunk_name;
     let main_api_params = params
         .iter()
         .skip(if method_kind.has_self_param() { 1 } else { 0 })
         .map(|Param { cc_name, cc_type, .. }| quote! { #cc_type #cc_name })
         .collect_vec();
+
+    // With `db.cc_call_site_in_panics()`, the C++ function gets an additional, defaulted
+    // `std::source_location` parameter, so that the caller's location can be passed to the
+    // Rust thunk.  (The C++ constructor below doesn't have a call site to pass.)
+    let with_call_site = db.cc_call_site_in_panics() && needs_thunk;
+    let (fn_decl_params, fn_def_params) = if with_call_site {
+        main_api_prereqs.includes.insert(CcInclude::source_location());
+        let decl_param = quote! {
+            std::source_location __crubit_call_site = std::source_location::current()
+        };
+        let def_param = quote! { std::source_location __crubit_call_site };
+        (
+            main_api_params.iter().cloned().chain(once(decl_param)).collect_vec(),
+            main_api_params.iter().cloned().chain(once(def_param)).collect_vec(),
+        )
+    } else {
+        (main_api_params.clone(), main_api_params.clone())
+    };
+
+    // `pub fn new(...) -> Self` is additionally exposed as a C++ constructor with the same
+    // parameters (all the generated C++ classes are trivially relocatable, so the constructor
+    // can just let the thunk write the result into `this`).  Constructors that would clash with
+    // the default, copy, or move constructor are skipped.
+    let is_new_ctor = method_kind == FunctionKind::StaticMethod
+        && needs_definition
+        && unqualified_rust_fn_name.as_str() == "new"
+        && self_ty == Some(sig.output())
+        && match params.as_slice() {
+            [] => false,
+            [Param { ty, .. }] => {
+                let self_ty = self_ty.expect("`is_new_ctor` implies non-None `self_ty`");
+                let is_self_ref = matches!(
+                    ty.kind(),
+                    ty::TyKind::Ref(_, referent_ty, _) if *referent_ty == self_ty
+                );
+                *ty != self_ty && !is_self_ref
+            }
+            _ => true,
+        };
+    let main_api_fn_name = match main_api_fn_name {
+        Ok(name) => Some(name),
+        Err(_) if is_new_ctor => None,
+        Err(err) => return Err(err),
+    };
+    let cc_struct_short_name = match struct_name.as_ref() {
+        None => None,
+        Some(fully_qualified_name) => {
+            let name = fully_qualified_name.name.expect("Structs always have a name");
+            Some(
+                format_cc_ident(name.as_str())
+                    .expect("Caller of format_fn should verify struct via format_adt_core"),
+            )
+        }
+    };
     let main_api = {
         let doc_comment = {
             let doc_comment = format_doc_comment(tcx, local_def_id);
@@ -1338,6 +2292,11 @@ fn format_fn(db: &dyn BindingsGenerator<'_>, local_def_id: LocalDefId) -> Result
         } else {
             quote! {}
         };
+        let constexpr = if trivial_getter_field.is_some() {
+            quote! { constexpr }
+        } else {
+            quote! {}
+        };
 
         let mut attributes = vec![];
         // Attribute: must_use
@@ -1363,56 +2322,149 @@ fn format_fn(db: &dyn BindingsGenerator<'_>, local_def_id: LocalDefId) -> Result
             }
         }
 
-        CcSnippet {
-            prereqs,
-            tokens: quote! {
+        let fn_decl = match main_api_fn_name.as_ref() {
+            None => quote! {},
+            Some(main_api_fn_name) => quote! {
                 __NEWLINE__
                 #doc_comment
-                #extern_c #(#attributes)* #static_
+                #extern_c #(#attributes)* #static_ #constexpr
                     #main_api_ret_type #main_api_fn_name (
-                        #( #main_api_params ),*
+                        #( #fn_decl_params ),*
                     ) #method_qualifiers;
                 __NEWLINE__
             },
+        };
+        let ctor_decl = if is_new_ctor {
+            let cc_struct_name = cc_struct_short_name.as_ref().expect("`new` is a static method");
+            let explicit = if main_api_params.len() == 1 { quote! { explicit } } else { quote! {} };
+            quote! {
+                __NEWLINE__
+                #doc_comment
+                #explicit #cc_struct_name ( #( #main_api_params ),* );
+                __NEWLINE__
+            }
+        } else {
+            quote! {}
+        };
+        CcSnippet {
+            prereqs,
+            tokens: quote! {
+                #fn_decl
+                #ctor_decl
+            },
+        }
+    };
+    let map_view_thunks = match &referenced_map {
+        None => None,
+        Some((map_ty, map_types)) => {
+            Some(format_map_view_thunks(db, *map_ty, map_types, &thunk_name)?)
         }
     };
     let cc_details = if !needs_definition {
         CcSnippet::default()
+    } else if let Some(field_name) = &trivial_getter_field {
+        let struct_name = cc_struct_short_name.as_ref().expect("Getters are methods");
+        let main_api_fn_name = main_api_fn_name.as_ref().expect("Getters are not constructors");
+        let always_inline_attr = format_always_inline_attr(db, def_id);
+        CcSnippet {
+            prereqs: main_api_prereqs,
+            tokens: quote! {
+                __NEWLINE__
+                #always_inline_attr
+                constexpr #main_api_ret_type #struct_name :: #main_api_fn_name ()
+                    #method_qualifiers {
+                    return this -> #field_name;
+                }
+                __NEWLINE__
+            },
+        }
     } else {
+        let map_view_vtable = match &map_view_thunks {
+            None => None,
+            Some(_) => Some(format_cc_ident(&format!("{thunk_name}_vtable"))?),
+        };
         let thunk_name = format_cc_ident(&thunk_name).context("Error formatting thunk name")?;
-        let struct_name = match struct_name.as_ref() {
+        let struct_name = match cc_struct_short_name.as_ref() {
             None => quote! {},
-            Some(fully_qualified_name) => {
-                let name = fully_qualified_name.name.expect("Structs always have a name");
-                let name = format_cc_ident(name.as_str())
-                    .expect("Caller of format_fn should verify struct via format_adt_core");
-                quote! { #name :: }
-            }
+            Some(name) => quote! { #name :: },
         };
 
         let mut prereqs = main_api_prereqs;
-        let thunk_decl =
-            format_thunk_decl(db, def_id, &sig, &thunk_name)?.into_tokens(&mut prereqs);
+        let thunk_decl = format_thunk_decl(db, def_id, &sig, &thunk_name, with_call_site)?
+            .into_tokens(&mut prereqs);
 
         let mut thunk_args = params
             .iter()
             .enumerate()
             .map(|(i, Param { cc_name, ty, .. })| {
                 if i == 0 && method_kind.has_self_param() {
-                    if method_kind == FunctionKind::MethodTakingSelfByValue {
+                    if method_kind == FunctionKind::MethodTakingSelfByValue
+                        && !is_c_abi_compatible_by_value(tcx, *ty)
+                    {
                         quote! { this }
                     } else {
                         quote! { *this }
                     }
-                } else if is_c_abi_compatible_by_value(*ty) {
+                } else if is_c_abi_compatible_by_value(tcx, *ty) {
                     quote! { #cc_name }
                 } else {
                     quote! { & #cc_name }
                 }
             })
             .collect_vec();
+        let (ctor_call_site_args, fn_call_site_args) = if with_call_site {
+            (
+                vec![quote! { nullptr }, quote! { 0 }],
+                vec![
+                    quote! { __crubit_call_site.file_name() },
+                    quote! { __crubit_call_site.line() },
+                ],
+            )
+        } else {
+            (vec![], vec![])
+        };
+        let always_inline_attr = format_always_inline_attr(db, def_id);
+        let ctor_def = if is_new_ctor {
+            let cc_struct_name = cc_struct_short_name.as_ref().expect("`new` is a static method");
+            let ctor_body = if is_c_abi_compatible_by_value(tcx, sig.output()) {
+                let args = thunk_args.iter().chain(ctor_call_site_args.iter());
+                quote! { *this = __crubit_internal :: #thunk_name( #( #args ),* ); }
+            } else {
+                let args = thunk_args.iter().chain(&ctor_call_site_args);
+                quote! { __crubit_internal :: #thunk_name( #( #args, )* this); }
+            };
+            quote! {
+                #always_inline_attr
+                inline #cc_struct_name :: #cc_struct_name ( #( #main_api_params ),* ) {
+                    #ctor_body
+                }
+                __NEWLINE__
+            }
+        } else {
+            quote! {}
+        };
+        let map_view_thunk_decls = match &map_view_thunks {
+            None => quote! {},
+            Some((cc_thunk_decls, _)) => cc_thunk_decls.clone().into_tokens(&mut prereqs),
+        };
         let impl_body: TokenStream;
-        if is_c_abi_compatible_by_value(sig.output()) {
+        if let Some(vtable) = &map_view_vtable {
+            thunk_args.extend(fn_call_site_args);
+            impl_body = quote! {
+                return #main_api_ret_type(
+                    __crubit_internal :: #thunk_name( #( #thunk_args ),* ),
+                    &__crubit_internal :: #vtable);
+            };
+        } else if returns_static_str {
+            thunk_args.push(quote! { &__ret_size });
+            thunk_args.extend(fn_call_site_args);
+            impl_body = quote! {
+                std::size_t __ret_size;
+                const char* __ret_data = __crubit_internal :: #thunk_name( #( #thunk_args ),* );
+                return std::string_view(__ret_data, __ret_size);
+            };
+        } else if is_c_abi_compatible_by_value(tcx, sig.output()) {
+            thunk_args.extend(fn_call_site_args);
             impl_body = quote! {
                 return __crubit_internal :: #thunk_name( #( #thunk_args ),* );
             };
@@ -1424,24 +2476,39 @@ fn format_fn(db: &dyn BindingsGenerator<'_>, local_def_id: LocalDefId) -> Result
                 })?;
             }
             thunk_args.push(quote! { __ret_slot.Get() });
+            thunk_args.extend(fn_call_site_args);
+            let msan_unpoison =
+                format_msan_unpoison_ret_slot(db, &main_api_ret_type, &mut prereqs);
             impl_body = quote! {
                 crubit::ReturnValueSlot<#main_api_ret_type> __ret_slot;
                 __crubit_internal :: #thunk_name( #( #thunk_args ),* );
+                #msan_unpoison
                 return std::move(__ret_slot).AssumeInitAndTakeValue();
             };
             prereqs.includes.insert(CcInclude::utility()); // for `std::move`
             prereqs.includes.insert(db.support_header("internal/return_value_slot.h"));
         };
+        let no_sanitize_attr = format_no_sanitize_attr(db, &mut prereqs);
+        let fn_def = match main_api_fn_name.as_ref() {
+            None => quote! {},
+            Some(main_api_fn_name) => quote! {
+                #always_inline_attr
+                #no_sanitize_attr
+                inline #main_api_ret_type #struct_name #main_api_fn_name (
+                        #( #fn_def_params ),* ) #method_qualifiers {
+                    #impl_body
+                }
+                __NEWLINE__
+            },
+        };
         CcSnippet {
             prereqs,
             tokens: quote! {
                 __NEWLINE__
                 #thunk_decl
-                inline #main_api_ret_type #struct_name #main_api_fn_name (
-                        #( #main_api_params ),* ) #method_qualifiers {
-                    #impl_body
-                }
-                __NEWLINE__
+                #map_view_thunk_decls
+                #fn_def
+                #ctor_def
             },
         }
     };
@@ -1457,138 +2524,613 @@ fn format_fn(db: &dyn BindingsGenerator<'_>, local_def_id: LocalDefId) -> Result
                 quote! { #struct_name :: #fn_name }
             }
         };
-        format_thunk_impl(tcx, def_id, &sig, &thunk_name, fully_qualified_fn_name)?
+        let thunk_impl = format_thunk_impl(
+            tcx,
+            def_id,
+            &sig,
+            &thunk_name,
+            fully_qualified_fn_name,
+            self_smart_pointer,
+            with_call_site,
+        )?;
+        let map_view_thunk_impls = match map_view_thunks {
+            None => quote! {},
+            Some((_, rs_thunk_impls)) => rs_thunk_impls,
+        };
+        quote! {
+            #thunk_impl
+            #map_view_thunk_impls
+        }
     };
     Ok(ApiSnippets { main_api, cc_details, rs_details })
 }
 
-/// Represents bindings for the "core" part of an algebraic data type (an ADT -
-/// a struct, an enum, or a union) in a way that supports later injecting the
-/// other parts like so:
-///
-/// ```
-/// quote! {
-///     #keyword #alignment #name final {
-///         #core
-///         #decls_of_other_parts  // (e.g. struct fields, methods, etc.)
-///     }
-/// }
-/// ```
-///
-/// `keyword`, `name` are stored separately, to support formatting them as a
-/// forward declaration - e.g. `struct SomeStruct`.
-#[derive(Clone)]
-struct AdtCoreBindings<'tcx> {
-    /// DefId of the ADT.
-    def_id: DefId,
-
-    /// C++ tag - e.g. `struct`, `class`, `enum`, or `union`.  This isn't always
-    /// a direct mapping from Rust (e.g. a Rust `enum` might end up being
-    /// represented as an opaque C++ `struct`).
-    keyword: TokenStream,
-
-    /// C++ translation of the ADT identifier - e.g. `SomeStruct`.
-    ///
-    /// A _short_ name is sufficient (i.e. there is no need to use a
-    /// namespace-qualified name), for `CcSnippet`s that are emitted into
-    /// the same namespace as the ADT.  (This seems to be all the snippets
-    /// today.)
-    cc_short_name: TokenStream,
-
-    /// Rust spelling of the ADT type - e.g.
-    /// `::some_crate::some_module::SomeStruct`.
-    rs_fully_qualified_name: TokenStream,
-
-    self_ty: Ty<'tcx>,
-    alignment_in_bytes: u64,
-    size_in_bytes: u64,
+/// Returns `true` if `ty` refers to any lifetimes (e.g. `&'a i32`).
+fn has_lifetimes(ty: Ty) -> bool {
+    ty.walk().any(|arg| matches!(arg.unpack(), ty::GenericArgKind::Lifetime(_)))
 }
 
-// AdtCoreBindings are a pure (and memoized...) function of the def_id.
-impl<'tcx> PartialEq for AdtCoreBindings<'tcx> {
-    fn eq(&self, other: &Self) -> bool {
-        self.def_id == other.def_id
-    }
+/// Returns the path of `def_id` relative to the crate root, with `.` as the
+/// separator - e.g. `some_module.SomeTrait`.  Once escaped with
+/// `escape_non_identifier_chars`, the path can be used in the names of
+/// generated items that would otherwise clash for items with the same name
+/// in different modules.
+fn dotted_def_path(tcx: TyCtxt, def_id: DefId) -> String {
+    let FullyQualifiedName { rs_mod_path, name, .. } = FullyQualifiedName::new(tcx, def_id);
+    let name = name.expect("`dotted_def_path` can't be called on name-less item kinds");
+    rs_mod_path.namespaces.iter().map(|module| module.as_ref()).chain(once(name.as_str())).join(".")
 }
 
-impl<'tcx> Eq for AdtCoreBindings<'tcx> {}
-impl<'tcx> Hash for AdtCoreBindings<'tcx> {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.def_id.hash(state);
-    }
-}
+/// A method of a trait that can be called (through the vtable generated by
+/// `format_trait`) on a C++ object satisfying the concept generated for the
+/// trait.
+struct TraitBridgeMethod<'tcx> {
+    def_id: DefId,
 
-impl<'tcx> AdtCoreBindings<'tcx> {
-    fn needs_drop(&self, tcx: TyCtxt<'tcx>) -> bool {
-        self.self_ty.needs_drop(tcx, tcx.param_env(self.def_id))
-    }
-}
+    /// Mutability of the `self` reference (i.e. `&self` or `&mut self`).
+    self_mutability: Mutability,
 
-/// Like `TyCtxt::is_directly_public`, but works not only with `LocalDefId`, but
-/// also with `DefId`.
-fn is_directly_public(tcx: TyCtxt, def_id: DefId) -> bool {
-    match def_id.as_local() {
-        None => {
-            // This mimics the checks in `try_print_visible_def_path_recur` in
-            // `compiler/rustc_middle/src/ty/print/pretty.rs`.
-            let actual_parent = tcx.opt_parent(def_id);
-            let visible_parent = tcx.visible_parent_map(()).get(&def_id).copied();
-            actual_parent == visible_parent
-        }
-        Some(local_def_id) => tcx.effective_visibilities(()).is_directly_public(local_def_id),
-    }
+    /// Names and types of the parameters other than `self`.
+    params: Vec<(Symbol, Ty<'tcx>)>,
+
+    output: Ty<'tcx>,
 }
 
-fn get_layout<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Result<Layout<'tcx>> {
-    let param_env = match ty.ty_adt_def() {
-        None => ty::ParamEnv::empty(),
-        Some(adt_def) => tcx.param_env(adt_def.did()),
+/// Returns the methods of the `trait_id` trait, or an error if the trait is not
+/// supported by `format_trait`.  For now, the trait has to:
+/// - be defined in the current crate, and be neither generic nor `unsafe`,
+/// - have no supertraits, and no associated types or constants,
+/// - have only safe, non-generic methods that take `&self` or `&mut self`, and
+///   whose other parameters and return type are C-ABI-compatible types without
+///   lifetimes.
+fn get_trait_bridge_methods<'tcx>(
+    db: &dyn BindingsGenerator<'tcx>,
+    trait_id: DefId,
+) -> Result<Vec<TraitBridgeMethod<'tcx>>> {
+    let tcx = db.tcx();
+    let Some(local_trait_id) = trait_id.as_local() else {
+        bail!("Traits from other crates are not supported yet");
     };
+    let ItemKind::Trait(_, safety, generics, bounds, _) =
+        tcx.hir().expect_item(local_trait_id).kind
+    else {
+        panic!("`get_trait_bridge_methods` should only be called for traits");
+    };
+    ensure!(matches!(safety, Safety::Safe), "Unsafe traits are not supported yet");
+    ensure!(
+        generics.params.is_empty() && generics.predicates.is_empty(),
+        "Generic traits are not supported yet (b/286941486)"
+    );
+    ensure!(bounds.is_empty(), "Traits with supertraits are not supported yet");
 
-    tcx.layout_of(param_env.and(ty)).map(|ty_and_layout| ty_and_layout.layout).map_err(
-        |layout_err| {
-            // Have to use `.map_err`, because `LayoutError` doesn't satisfy the
-            // `anyhow::context::ext::StdError` trait bound.
-            anyhow!("Error computing the layout: {layout_err}")
-        },
-    )
+    tcx.associated_items(trait_id)
+        .in_definition_order()
+        .map(|item| {
+            let name = item.name;
+            ensure!(
+                item.kind == ty::AssocKind::Fn,
+                "Associated types and constants are not supported yet: `{name}`"
+            );
+            ensure!(
+                tcx.generics_of(item.def_id).own_params.is_empty(),
+                "Generic methods are not supported yet: `{name}`"
+            );
+            let sig = tcx.fn_sig(item.def_id).instantiate_identity().skip_binder();
+            ensure!(
+                matches!(sig.safety, Safety::Safe)
+                    && sig.abi == rustc_target::spec::abi::Abi::Rust
+                    && !sig.c_variadic,
+                "Only safe methods with the default calling convention are supported: `{name}`"
+            );
+            let (self_ty, param_tys) = match sig.inputs().split_first() {
+                Some(inputs) if item.fn_has_self_parameter => inputs,
+                _ => bail!("Methods without a `self` parameter are not supported yet: `{name}`"),
+            };
+            let self_mutability = match self_ty.kind() {
+                ty::TyKind::Ref(_, referent_ty, mutability) if referent_ty.is_param(0) => {
+                    *mutability
+                }
+                _ => bail!("Only `&self` and `&mut self` methods are supported: `{name}`"),
+            };
+            let param_tys_and_locations = param_tys.iter().map(|&ty| (ty, TypeLocation::FnParam));
+            let output_ty_and_location = (sig.output(), TypeLocation::FnReturn);
+            for (ty, location) in param_tys_and_locations.chain(once(output_ty_and_location)) {
+                let unsupported_type_error = || {
+                    anyhow!(
+                        "Unsupported type in the signature of `{name}`: `{ty}` \
+                         (only C-ABI-compatible types without lifetimes are supported)"
+                    )
+                };
+                if has_lifetimes(ty) {
+                    return Err(unsupported_type_error());
+                }
+                db.format_ty_for_cc(ty, location).map_err(|_| unsupported_type_error())?;
+                if !is_c_abi_compatible_by_value(tcx, ty) {
+                    return Err(unsupported_type_error());
+                }
+            }
+            let params = tcx
+                .fn_arg_names(item.def_id)
+                .iter()
+                .skip(1)
+                .map(|ident| ident.name)
+                .zip(param_tys.iter().copied())
+                .collect();
+            Ok(TraitBridgeMethod {
+                def_id: item.def_id,
+                self_mutability,
+                params,
+                output: sig.output(),
+            })
+        })
+        .collect()
 }
 
-/// Formats the core of an algebraic data type (an ADT - a struct, an enum, or a
-/// union) represented by `def_id`.
+/// Formats a trait as a C++ concept that requires the methods of the trait
+/// (e.g. `concept SomeTrait = requires { ... };`).
 ///
-/// The "core" means things that are necessary for a succesful binding (e.g.
-/// inability to generate a correct C++ destructor means that the ADT cannot
-/// have any bindings).  "core" excludes things that are A) infallible (e.g.
-/// struct or union fields which can always be translated into private, opaque
-/// blobs of bytes) or B) optional (e.g. a problematic instance method
-/// can just be ignored, unlike a problematic destructor).  The split between
-/// fallible "core" and non-fallible "rest" is motivated by the need to avoid
-/// cycles / infinite recursion (e.g. when processing fields that refer back to
-/// the struct type, possible with an indirection of a pointer).
+/// Generic functions bounded by the trait are exposed as C++ templates
+/// constrained by the concept (see `format_generic_fn`).  To let them call the
+/// Rust function, `format_trait` also generates:
+/// - in C++: a "vtable" struct (with a pointer to a function calling the C++
+///   method for each trait method), and a variable template with the vtable
+///   of each C++ type,
+/// - in Rust: a "bridge" type that holds a pointer to the C++ object and its
+///   vtable, and implements the trait by calling through the vtable.
 ///
-/// `format_adt_core` is used both to 1) format bindings for the core of an ADT,
-/// and 2) check if formatting would have succeeded (e.g. when called from
-/// `format_ty`).  The 2nd case is needed for ADTs defined in any crate - this
-/// is why the `def_id` parameter is a DefId rather than LocalDefId.
-fn format_adt_core<'tcx>(
-    db: &dyn BindingsGenerator<'tcx>,
-    def_id: DefId,
-) -> Result<Rc<AdtCoreBindings<'tcx>>> {
+/// This is an experimental feature (see
+/// `BindingsGenerator::experimental_features`).
+fn format_trait(db: &dyn BindingsGenerator<'_>, local_def_id: LocalDefId) -> Result<ApiSnippets> {
     let tcx = db.tcx();
-    let self_ty = tcx.type_of(def_id).instantiate_identity();
-    assert!(self_ty.is_adt());
-    assert!(is_directly_public(tcx, def_id), "Caller should verify");
+    let def_id = local_def_id.to_def_id();
+    let methods = get_trait_bridge_methods(db, def_id)?;
 
-    let item_name = tcx.item_name(def_id);
-    let rs_fully_qualified_name = format_ty_for_rs(tcx, self_ty)?;
-    let cc_short_name =
-        format_cc_ident(item_name.as_str()).context("Error formatting item name")?;
+    let concept_name =
+        format_cc_ident(tcx.item_name(def_id).as_str()).context("Error formatting trait name")?;
+    let name_suffix = escape_non_identifier_chars(&dotted_def_path(tcx, def_id));
+    let cc_vtable_name = format_cc_ident(&format!("__crubit_vtable_{name_suffix}"))?;
+    let cc_vtable_for_name = format_cc_ident(&format!("__crubit_vtable_for_{name_suffix}"))?;
+    let rs_vtable_name = format_ident!("__crubit_vtable_{name_suffix}");
+    let rs_bridge_name = format_ident!("__crubit_bridge_{name_suffix}");
 
-    // The check below ensures that `format_trait_thunks` will succeed for the
-    // `Drop`, `Default`, and/or `Clone` trait. Ideally we would directly check
-    // if `format_trait_thunks` or `format_ty_for_cc(..., self_ty, ...)`
-    // succeeds, but this would lead to infinite recursion, so we only replicate
+    let mut prereqs = CcPrerequisites::default();
+    prereqs.includes.insert(CcInclude::concepts());
+    prereqs.includes.insert(CcInclude::utility()); // for `std::declval`
+    let mut requirements = vec![];
+    let mut cc_vtable_fields = vec![];
+    let mut cc_vtable_entries = vec![];
+    let mut rs_vtable_fields = vec![];
+    let mut rs_method_impls = vec![];
+    for method in methods.iter() {
+        let method_name = tcx.item_name(method.def_id);
+        let cc_method_name = format_cc_ident(get_cc_fn_name(db, method.def_id).as_str())
+            .with_context(|| format!("Error formatting the name of `{method_name}`"))?;
+        let rs_method_name = make_rs_ident(method_name.as_str());
+
+        let cc_ret_type = db
+            .format_ty_for_cc(method.output, TypeLocation::FnReturn)
+            .with_context(|| format!("Error formatting the return type of `{method_name}`"))?
+            .into_tokens(&mut prereqs);
+        let rs_ret_type = format_ty_for_rs(tcx, method.output)?;
+        let mut cc_param_types = vec![];
+        let mut cc_param_names = vec![];
+        let mut rs_param_types = vec![];
+        let mut rs_param_names = vec![];
+        for (i, (name, ty)) in method.params.iter().enumerate() {
+            let cc_type = db
+                .format_ty_for_cc(*ty, TypeLocation::FnParam)
+                .with_context(|| format!("Error handling parameter #{i} of `{method_name}`"))?;
+            cc_param_types.push(cc_type.into_tokens(&mut prereqs));
+            cc_param_names.push(
+                format_cc_ident(name.as_str())
+                    .unwrap_or_else(|_err| format_cc_ident(&format!("__param_{i}")).unwrap()),
+            );
+            rs_param_types.push(format_ty_for_rs(tcx, *ty)?);
+            rs_param_names.push(if name.as_str().is_empty() || *name == kw::Underscore {
+                format_ident!("__param_{i}")
+            } else {
+                make_rs_ident(name.as_str())
+            });
+        }
+
+        let (cc_const, rs_self, rs_ptr_mutability) = match method.self_mutability {
+            Mutability::Not => (quote! { const }, quote! { &self }, quote! { const }),
+            Mutability::Mut => (quote! {}, quote! { &mut self }, quote! { mut }),
+        };
+        let call = quote! {
+            std::declval<#cc_const T&>().#cc_method_name( #( std::declval<#cc_param_types>() ),* )
+        };
+        requirements.push(if method.output.is_unit() {
+            quote! { #call; }
+        } else {
+            quote! { { #call } -> std::convertible_to<#cc_ret_type>; }
+        });
+        cc_vtable_fields.push(quote! {
+            #cc_ret_type (*#cc_method_name)(#cc_const void* #( , #cc_param_types )*);
+        });
+        cc_vtable_entries.push(quote! {
+            +[](#cc_const void* __self #( , #cc_param_types #cc_param_names )*) -> #cc_ret_type {
+                return static_cast<#cc_const T*>(__self)->#cc_method_name( #( #cc_param_names ),* );
+            }
+        });
+        rs_vtable_fields.push(quote! {
+            #rs_method_name: unsafe extern "C" fn(
+                * #rs_ptr_mutability ::core::ffi::c_void #( , #rs_param_types )*
+            ) -> #rs_ret_type
+        });
+        // The `unsafe` call is sound, because the bridge is only created by the thunks called
+        // from the C++ templates generated by `format_generic_fn`, which pass a pointer to a C++
+        // object together with the vtable for its type.
+        rs_method_impls.push(quote! {
+            fn #rs_method_name(#rs_self #( , #rs_param_names: #rs_param_types )*) -> #rs_ret_type {
+                unsafe { (self.vtable.#rs_method_name)(self.obj #( , #rs_param_names )*) }
+            }
+        });
+    }
+
+    let main_api = {
+        let doc_comment = format_doc_comment(tcx, local_def_id);
+        // A requires-expression needs at least one requirement.
+        let constraint = if requirements.is_empty() {
+            quote! { true }
+        } else {
+            quote! { requires { #( #requirements )* } }
+        };
+        CcSnippet {
+            prereqs,
+            tokens: quote! {
+                __NEWLINE__ #doc_comment
+                template <typename T>
+                concept #concept_name = #constraint;
+                __NEWLINE__
+                namespace __crubit_internal {
+                    struct #cc_vtable_name final {
+                        #( #cc_vtable_fields )*
+                    };
+                    template <typename T>
+                    inline constexpr #cc_vtable_name #cc_vtable_for_name = {
+                        #( #cc_vtable_entries ),*
+                    };
+                }
+                __NEWLINE__
+            },
+        }
+    };
+    let rs_details = {
+        let rs_trait_name = FullyQualifiedName::new(tcx, def_id).format_for_rs();
+        quote! {
+            #[repr(C)]
+            pub struct #rs_vtable_name {
+                #( #rs_vtable_fields ),*
+            }
+            pub struct #rs_bridge_name<'a> {
+                obj: *mut ::core::ffi::c_void,
+                vtable: &'a #rs_vtable_name,
+                _marker: ::core::marker::PhantomData<&'a mut ()>,
+            }
+            impl<'a> #rs_trait_name for #rs_bridge_name<'a> {
+                #( #rs_method_impls )*
+            }
+        }
+    };
+    Ok(ApiSnippets { main_api, cc_details: CcSnippet::default(), rs_details })
+}
+
+/// Formats a generic function whose only type parameter is bounded by a single
+/// trait (e.g. `fn f(x: impl SomeTrait)`), as a C++ function template
+/// constrained by the concept generated for the trait (see `format_trait`).
+/// The template passes a pointer to the C++ object (and the vtable for its
+/// type) to a Rust thunk, which calls the function with the bridge type of the
+/// trait.  The type parameter can only be used as the type of parameters.
+///
+/// This is an experimental feature (see
+/// `BindingsGenerator::experimental_features`).
+fn format_generic_fn<'tcx>(
+    db: &dyn BindingsGenerator<'tcx>,
+    local_def_id: LocalDefId,
+) -> Result<ApiSnippets> {
+    let tcx = db.tcx();
+    let def_id = local_def_id.to_def_id();
+    ensure!(
+        matches!(tcx.hir_node_by_def_id(local_def_id), Node::Item(_)),
+        "Generic methods are not supported yet (b/259749023)"
+    );
+    let type_param = match tcx.generics_of(def_id).own_params.as_slice() {
+        [param] if matches!(param.kind, ty::GenericParamDefKind::Type { .. }) => param,
+        _ => bail!(
+            "Only generic functions with a single type parameter (and no lifetime or const \
+             parameters) are supported (b/259749023)"
+        ),
+    };
+    let param_ty = tcx.mk_param_from_def(type_param).expect_ty();
+    let uses_param_ty = |ty: Ty<'tcx>| ty.walk().any(|arg| arg.as_type() == Some(param_ty));
+
+    let sized_trait_id = tcx.lang_items().sized_trait();
+    let bounds = tcx
+        .predicates_of(def_id)
+        .predicates
+        .iter()
+        .map(|(clause, _span)| clause.kind().skip_binder())
+        .filter(|clause| {
+            !matches!(clause, ty::ClauseKind::Trait(pred) if Some(pred.def_id()) == sized_trait_id)
+        })
+        .collect_vec();
+    let trait_id = match bounds.as_slice() {
+        [ty::ClauseKind::Trait(pred)] if pred.self_ty() == param_ty => pred.def_id(),
+        _ => bail!("The type parameter of a generic function has to be bounded by a single trait"),
+    };
+    let trait_name = tcx.item_name(trait_id);
+    let Some(trait_local_def_id) = trait_id.as_local() else {
+        bail!("Bounds on traits from other crates are not supported yet: `{trait_name}`");
+    };
+    db.format_item(trait_local_def_id)
+        .with_context(|| format!("Error formatting the `{trait_name}` trait"))?
+        .ok_or_else(|| anyhow!("The `{trait_name}` trait has no C++ bindings"))?;
+
+    let sig = get_fn_sig(tcx, local_def_id);
+    ensure!(
+        matches!(sig.safety, Safety::Safe) && !sig.c_variadic,
+        "Unsafe or variadic generic functions are not supported yet"
+    );
+    ensure!(
+        !sig.inputs_and_output.iter().any(has_lifetimes),
+        "Generic functions with lifetimes are not supported yet"
+    );
+    let unsupported_return_type_error = || anyhow!("Unsupported return type: `{}`", sig.output());
+    if uses_param_ty(sig.output()) {
+        return Err(unsupported_return_type_error());
+    }
+    let mut prereqs = CcPrerequisites::default();
+    let cc_ret_type = format_ret_ty_for_cc(db, &sig)?.into_tokens(&mut prereqs);
+    if !is_c_abi_compatible_by_value(tcx, sig.output()) {
+        return Err(unsupported_return_type_error());
+    }
+
+    let trait_fully_qualified_name = FullyQualifiedName::new(tcx, trait_id);
+    let cc_concept_name = trait_fully_qualified_name.format_for_cc()?;
+    let cc_internal_ns = {
+        let krate = format_cc_ident(trait_fully_qualified_name.krate.as_str())?;
+        let mod_path = trait_fully_qualified_name.mod_path.format_for_cc()?;
+        quote! { :: #krate :: #mod_path __crubit_internal }
+    };
+    let name_suffix = escape_non_identifier_chars(&dotted_def_path(tcx, trait_id));
+    let cc_vtable_name = format_cc_ident(&format!("__crubit_vtable_{name_suffix}"))?;
+    let cc_vtable_for_name = format_cc_ident(&format!("__crubit_vtable_for_{name_suffix}"))?;
+    let rs_vtable_name = format_ident!("__crubit_vtable_{name_suffix}");
+    let rs_bridge_name = format_ident!("__crubit_bridge_{name_suffix}");
+
+    // Only instantiations of a generic function have a symbol name, so the name of the thunk
+    // is derived from the path of the function instead.
+    let thunk_name = {
+        let path = format!("{}.{}", tcx.crate_name(LOCAL_CRATE), dotted_def_path(tcx, def_id));
+        format!("__crubit_thunk_{}", escape_non_identifier_chars(&path))
+    };
+
+    let rs_ret_type = format_ty_for_rs(tcx, sig.output())?;
+    let mut cc_params = vec![];
+    let mut cc_thunk_params = vec![];
+    let mut cc_thunk_args = vec![];
+    let mut rs_thunk_params = vec![];
+    let mut rs_call_args = vec![];
+    for (i, (name, &ty)) in tcx.fn_arg_names(def_id).iter().zip(sig.inputs()).enumerate() {
+        let cc_name = format_cc_ident(name.as_str())
+            .unwrap_or_else(|_err| format_cc_ident(&format!("__param_{i}")).unwrap());
+        let rs_name = format_ident!("__param_{i}");
+        if ty == param_ty {
+            let rs_vtable_param_name = format_ident!("__param_{i}_vtable");
+            cc_params.push(quote! { T& #cc_name });
+            cc_thunk_params.push(quote! { void* });
+            cc_thunk_params.push(quote! { const #cc_internal_ns :: #cc_vtable_name* });
+            cc_thunk_args.push(quote! { &#cc_name });
+            cc_thunk_args.push(quote! { &#cc_internal_ns :: #cc_vtable_for_name<T> });
+            rs_thunk_params.push(quote! { #rs_name: *mut ::core::ffi::c_void });
+            rs_thunk_params.push(quote! { #rs_vtable_param_name: &'static crate::#rs_vtable_name });
+            rs_call_args.push(quote! {
+                crate::#rs_bridge_name {
+                    obj: #rs_name,
+                    vtable: #rs_vtable_param_name,
+                    _marker: ::core::marker::PhantomData,
+                }
+            });
+        } else {
+            ensure!(!uses_param_ty(ty), "Unsupported type of parameter #{i}: `{ty}`");
+            let cc_type = db
+                .format_ty_for_cc(ty, TypeLocation::FnParam)
+                .with_context(|| format!("Error handling parameter #{i}"))?
+                .into_tokens(&mut prereqs);
+            ensure!(
+                is_c_abi_compatible_by_value(tcx, ty),
+                "Unsupported type of parameter #{i}: `{ty}` \
+                 (only C-ABI-compatible types are supported)"
+            );
+            let rs_type = format_ty_for_rs(tcx, ty)
+                .with_context(|| format!("Error handling parameter #{i}"))?;
+            cc_params.push(quote! { #cc_type #cc_name });
+            cc_thunk_params.push(cc_type);
+            cc_thunk_args.push(quote! { #cc_name });
+            rs_thunk_params.push(quote! { #rs_name: #rs_type });
+            rs_call_args.push(quote! { #rs_name });
+        }
+    }
+
+    let cc_fn_name = format_cc_ident(get_cc_fn_name(db, def_id).as_str())
+        .context("Error formatting function name")?;
+    let template_header = quote! { template <typename T> requires #cc_concept_name<T> };
+    let main_api = {
+        let doc_comment = format_doc_comment(tcx, local_def_id);
+        let mut prereqs = prereqs.clone();
+        prereqs.move_defs_to_fwd_decls();
+        // The concept has to be defined before it is used.
+        prereqs.defs.insert(trait_local_def_id);
+        CcSnippet {
+            prereqs,
+            tokens: quote! {
+                __NEWLINE__ #doc_comment
+                #template_header
+                #cc_ret_type #cc_fn_name( #( #cc_params ),* );
+                __NEWLINE__
+            },
+        }
+    };
+    let cc_details = {
+        let cc_thunk_name = format_cc_ident(&thunk_name).context("Error formatting thunk name")?;
+        let no_sanitize_attr = format_no_sanitize_attr(db, &mut prereqs);
+        CcSnippet {
+            tokens: quote! {
+                __NEWLINE__
+                namespace __crubit_internal {
+                    extern "C" #cc_ret_type #cc_thunk_name( #( #cc_thunk_params ),* );
+                }
+                #template_header
+                #no_sanitize_attr
+                inline #cc_ret_type #cc_fn_name( #( #cc_params ),* ) {
+                    return __crubit_internal :: #cc_thunk_name( #( #cc_thunk_args ),* );
+                }
+                __NEWLINE__
+            },
+            prereqs,
+        }
+    };
+    let rs_details = {
+        let rs_thunk_name = make_rs_ident(&thunk_name);
+        let fully_qualified_fn_name = FullyQualifiedName::new(tcx, def_id).format_for_rs();
+        quote! {
+            #[no_mangle]
+            extern "C" fn #rs_thunk_name( #( #rs_thunk_params ),* ) -> #rs_ret_type {
+                #fully_qualified_fn_name( #( #rs_call_args ),* )
+            }
+        }
+    };
+    Ok(ApiSnippets { main_api, cc_details, rs_details })
+}
+
+/// Represents bindings for the "core" part of an algebraic data type (an ADT -
+/// a struct, an enum, or a union) in a way that supports later injecting the
+/// other parts like so:
+///
+/// ```
+/// quote! {
+///     #keyword #alignment #name final {
+///         #core
+///         #decls_of_other_parts  // (e.g. struct fields, methods, etc.)
+///     }
+/// }
+/// ```
+///
+/// `keyword`, `name` are stored separately, to support formatting them as a
+/// forward declaration - e.g. `struct SomeStruct`.
+#[derive(Clone)]
+struct AdtCoreBindings<'tcx> {
+    /// DefId of the ADT.
+    def_id: DefId,
+
+    /// C++ tag - e.g. `struct`, `class`, `enum`, or `union`.  This isn't always
+    /// a direct mapping from Rust (e.g. a Rust `enum` might end up being
+    /// represented as an opaque C++ `struct`).
+    keyword: TokenStream,
+
+    /// C++ translation of the ADT identifier - e.g. `SomeStruct`.
+    ///
+    /// A _short_ name is sufficient (i.e. there is no need to use a
+    /// namespace-qualified name), for `CcSnippet`s that are emitted into
+    /// the same namespace as the ADT.  (This seems to be all the snippets
+    /// today.)
+    cc_short_name: TokenStream,
+
+    /// Rust spelling of the ADT type - e.g.
+    /// `::some_crate::some_module::SomeStruct`.
+    rs_fully_qualified_name: TokenStream,
+
+    self_ty: Ty<'tcx>,
+    alignment_in_bytes: u64,
+    size_in_bytes: u64,
+}
+
+// AdtCoreBindings are a pure (and memoized...) function of the def_id.
+impl<'tcx> PartialEq for AdtCoreBindings<'tcx> {
+    fn eq(&self, other: &Self) -> bool {
+        self.def_id == other.def_id
+    }
+}
+
+impl<'tcx> Eq for AdtCoreBindings<'tcx> {}
+impl<'tcx> Hash for AdtCoreBindings<'tcx> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.def_id.hash(state);
+    }
+}
+
+impl<'tcx> AdtCoreBindings<'tcx> {
+    fn needs_drop(&self, tcx: TyCtxt<'tcx>) -> bool {
+        self.self_ty.needs_drop(tcx, tcx.param_env(self.def_id))
+    }
+}
+
+/// Like `TyCtxt::is_directly_public`, but works not only with `LocalDefId`, but
+/// also with `DefId`.
+fn is_directly_public(tcx: TyCtxt, def_id: DefId) -> bool {
+    match def_id.as_local() {
+        None => {
+            // This mimics the checks in `try_print_visible_def_path_recur` in
+            // `compiler/rustc_middle/src/ty/print/pretty.rs`.
+            let actual_parent = tcx.opt_parent(def_id);
+            let visible_parent = tcx.visible_parent_map(()).get(&def_id).copied();
+            actual_parent == visible_parent
+        }
+        Some(local_def_id) => tcx.effective_visibilities(()).is_directly_public(local_def_id),
+    }
+}
+
+fn get_layout<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Result<Layout<'tcx>> {
+    let param_env = match ty.ty_adt_def() {
+        None => ty::ParamEnv::empty(),
+        Some(adt_def) => tcx.param_env(adt_def.did()),
+    };
+
+    tcx.layout_of(param_env.and(ty)).map(|ty_and_layout| ty_and_layout.layout).map_err(
+        |layout_err| {
+            // Have to use `.map_err`, because `LayoutError` doesn't satisfy the
+            // `anyhow::context::ext::StdError` trait bound.
+            anyhow!("Error computing the layout: {layout_err}")
+        },
+    )
+}
+
+/// Formats the core of an algebraic data type (an ADT - a struct, an enum, or a
+/// union) represented by `def_id`.
+///
+/// The "core" means things that are necessary for a succesful binding (e.g.
+/// inability to generate a correct C++ destructor means that the ADT cannot
+/// have any bindings).  "core" excludes things that are A) infallible (e.g.
+/// struct or union fields which can always be translated into private, opaque
+/// blobs of bytes) or B) optional (e.g. a problematic instance method
+/// can just be ignored, unlike a problematic destructor).  The split between
+/// fallible "core" and non-fallible "rest" is motivated by the need to avoid
+/// cycles / infinite recursion (e.g. when processing fields that refer back to
+/// the struct type, possible with an indirection of a pointer).
+///
+/// `format_adt_core` is used both to 1) format bindings for the core of an ADT,
+/// and 2) check if formatting would have succeeded (e.g. when called from
+/// `format_ty`).  The 2nd case is needed for ADTs defined in any crate - this
+/// is why the `def_id` parameter is a DefId rather than LocalDefId.
+fn format_adt_core<'tcx>(
+    db: &dyn BindingsGenerator<'tcx>,
+    def_id: DefId,
+) -> Result<Rc<AdtCoreBindings<'tcx>>> {
+    let tcx = db.tcx();
+    let self_ty = tcx.type_of(def_id).instantiate_identity();
+    assert!(self_ty.is_adt());
+    assert!(is_directly_public(tcx, def_id), "Caller should verify");
+
+    let item_name = tcx.item_name(def_id);
+    let rs_fully_qualified_name = format_ty_for_rs(tcx, self_ty)?;
+    let cc_short_name =
+        format_cc_ident(item_name.as_str()).context("Error formatting item name")?;
+
+    // The check below ensures that `format_trait_thunks` will succeed for the
+    // `Drop`, `Default`, and/or `Clone` trait. Ideally we would directly check
+    // if `format_trait_thunks` or `format_ty_for_cc(..., self_ty, ...)`
+    // succeeds, but this would lead to infinite recursion, so we only replicate
     // `format_ty_for_cc` / `TyKind::Adt` checks that are outside of
     // `format_adt_core`.
     FullyQualifiedName::new(tcx, def_id).format_for_cc().with_context(|| {
@@ -1612,7 +3154,7 @@ fn format_adt_core<'tcx>(
         layout.align().abi.bytes()
     };
     let size_in_bytes = layout.size().bytes();
-    ensure!(size_in_bytes != 0, "Zero-sized types (ZSTs) are not supported (b/258259459)");
+    ensure!(!layout.abi().is_uninhabited(), "Bindings for uninhabited types are not supported");
 
     Ok(Rc::new(AdtCoreBindings {
         def_id,
@@ -1634,6 +3176,14 @@ fn repr_attrs(db: &dyn BindingsGenerator<'_>, def_id: DefId) -> Rc<[rustc_attr::
     attrs.into()
 }
 
+/// Formats the name of the C++ member variable for the `index`-th field (in
+/// declaration order) of a struct.  Fields of tuple structs (e.g. `0`) become
+/// `__field0`.
+fn format_cc_field_name(name: Symbol, index: usize) -> TokenStream {
+    format_cc_ident(name.as_str())
+        .unwrap_or_else(|_err| format_ident!("__field{index}").into_token_stream())
+}
+
 fn format_fields<'tcx>(
     db: &dyn BindingsGenerator<'tcx>,
     core: &AdtCoreBindings<'tcx>,
@@ -1645,6 +3195,8 @@ fn format_fields<'tcx>(
 
     struct FieldTypeInfo {
         size: u64,
+        align: u64,
+        is_phantom_data: bool,
         cc_type: CcSnippet,
     }
     struct Field {
@@ -1670,7 +3222,10 @@ fn format_fields<'tcx>(
     let layout = get_layout(tcx, core.self_ty)
         .expect("Layout should be already verified by `format_adt_core`");
     let adt_def = core.self_ty.ty_adt_def().expect("`core.def_id` needs to identify an ADT");
-    let fields: Vec<Field> = if core.self_ty.is_enum() {
+    let fields: Vec<Field> = if core.self_ty.is_enum() && core.size_in_bytes == 0 {
+        // A zero-sized `enum` has a single, fieldless variant.
+        vec![]
+    } else if core.self_ty.is_enum() {
         vec![Field {
             type_info: Err(anyhow!("No support for bindings of individual `enum` fields")),
             cc_name: quote! { __opaque_blob_of_bytes },
@@ -1692,16 +3247,21 @@ fn format_fields<'tcx>(
             .enumerate()
             .map(|(index, field_def)| {
                 let field_ty = field_def.ty(tcx, substs_ref);
-                let size = get_layout(tcx, field_ty).map(|layout| layout.size().bytes());
-                let type_info = size.and_then(|size| {
-                    Ok(FieldTypeInfo {
-                        size,
-                        cc_type: db.format_ty_for_cc(field_ty, TypeLocation::Other)?,
-                    })
+                let type_info = get_layout(tcx, field_ty).and_then(|layout| {
+                    let size = layout.size().bytes();
+                    let align = layout.align().abi.bytes();
+                    // ZST fields have no C++ counterpart (see the `main_api` below), so
+                    // their C++ type is never needed.
+                    let cc_type = if size == 0 {
+                        CcSnippet::default()
+                    } else {
+                        db.format_ty_for_cc(field_ty, TypeLocation::Other)?
+                    };
+                    let is_phantom_data = is_phantom_data(field_ty);
+                    Ok(FieldTypeInfo { size, align, is_phantom_data, cc_type })
                 });
                 let name = field_def.ident(tcx);
-                let cc_name = format_cc_ident(name.as_str())
-                    .unwrap_or_else(|_err| format_ident!("__field{index}").into_token_stream());
+                let cc_name = format_cc_field_name(name.name, index);
                 let rs_name = {
                     let name_starts_with_digit = name
                         .as_str()
@@ -1789,7 +3349,7 @@ fn format_fields<'tcx>(
         let adt_cc_name = &core.cc_short_name;
         let cc_assertions: TokenStream = fields
             .iter()
-            // TODO(b/298660437): Add support for ZST fields.
+            // ZST fields don't exist on the C++ side.
             .filter(|field| field.size() != 0)
             .map(|Field { cc_name, offset, .. }| {
                 let offset = Literal::u64_unsuffixed(*offset);
@@ -1809,10 +3369,8 @@ fn format_fields<'tcx>(
         let adt_rs_name = &core.rs_fully_qualified_name;
         fields
             .iter()
-            // TODO(b/298660437): Even though we don't generate bindings for ZST fields, we'd still
-            // like to make sure we computed the offset of ZST fields correctly on the Rust side,
-            // so we still emit offset assertions for ZST fields here.
-            // TODO(b/298660437): Remove the comment above when ZST fields are supported.
+            // Even though ZST fields don't exist on the C++ side, we still verify their offsets
+            // here, because they determine the explicit padding emitted into the C++ struct.
             .filter(|field| field.is_public)
             .map(|Field { rs_name, offset, .. }| {
                 let expected_offset = Literal::u64_unsuffixed(*offset);
@@ -1839,9 +3397,15 @@ fn format_fields<'tcx>(
         // Foo(i8);` there are four different places the `i8` could be.
         // If it was placed in the second byte, for any reason, then we would need
         // explicit padding bytes.
+        //
+        // A ZST field doesn't affect the natural padding, unless it is over-aligned.
         let repr_attrs = db.repr_attrs(core.def_id);
         let always_omit_padding = repr_attrs.contains(&rustc_attr::ReprC)
-            && fields.iter().all(|field| field.type_info.is_ok());
+            && fields.iter().all(|field| match field.type_info {
+                Ok(FieldTypeInfo { size: 0, align, .. }) => align == 1,
+                Ok(_) => true,
+                Err(_) => false,
+            });
 
         let mut prereqs = CcPrerequisites::default();
         let fields: TokenStream = fields
@@ -1851,11 +3415,12 @@ fn format_fields<'tcx>(
                 match field.type_info {
                     Err(ref err) => {
                         let size = field.size();
-                        let msg =
-                            format!("Field type has been replaced with a blob of bytes: {err:#}");
 
                         // Empty arrays are ill-formed, but also unnecessary for padding.
                         if size > 0 {
+                            let msg = format!(
+                                "Field type has been replaced with a blob of bytes: {err:#}"
+                            );
                             let size = Literal::u64_unsuffixed(size);
                             quote! {
                                 private: __NEWLINE__
@@ -1863,21 +3428,52 @@ fn format_fields<'tcx>(
                                     unsigned char #cc_name[#size];
                             }
                         } else {
-                            // TODO(b/258259459): Generate bindings for ZST fields.
-                            let msg = format!(
-                                "Skipped bindings for field `{cc_name}`: \
-                               ZST fields are not supported (b/258259459)"
-                            );
+                            let msg = format!("Skipped bindings for field `{cc_name}`: {err:#}");
                             quote! {__NEWLINE__ __COMMENT__ #msg}
                         }
                     }
-                    Ok(FieldTypeInfo { cc_type, size }) => {
-                        // Only structs require no overlaps.
-                        let padding = match adt_def.adt_kind() {
-                            ty::AdtKind::Struct => {
-                                assert!((field.offset + size) <= field.offset_of_next_field);
-                                field.offset_of_next_field - field.offset - size
-                            }
+                    Ok(FieldTypeInfo { size: 0, is_phantom_data, .. }) => {
+                        // A C++ member can't be zero-sized, and a `[[no_unique_address]]` member
+                        // would still run the C++ constructors and destructor of the ZST (see
+                        // also b/288138612).  Since the field holds no data, it is omitted, and
+                        // only the padding that follows it (if any) is emitted.
+                        let msg = if is_phantom_data {
+                            let adt_cc_name = &core.cc_short_name;
+                            format!(
+                                "Field `{cc_name}` is a `PhantomData` marker, and has no C++ \
+                                 representation.  In Rust, it only affects the variance, the \
+                                 auto traits (e.g. `Send` and `Sync`), and the drop check of \
+                                 `{adt_cc_name}`"
+                            )
+                        } else {
+                            format!(
+                                "Field `{cc_name}` is zero-sized in Rust, and has no C++ \
+                                 representation"
+                            )
+                        };
+                        let padding = field.offset_of_next_field - field.offset;
+                        let padding = if always_omit_padding
+                            || padding == 0
+                            || !adt_def.is_struct()
+                        {
+                            quote! {}
+                        } else {
+                            let padding = Literal::u64_unsuffixed(padding);
+                            let ident = format_ident!("__padding{}", field.index);
+                            quote! { private: unsigned char #ident[#padding]; }
+                        };
+                        quote! {
+                            __NEWLINE__ __COMMENT__ #msg
+                            #padding
+                        }
+                    }
+                    Ok(FieldTypeInfo { cc_type, size, .. }) => {
+                        // Only structs require no overlaps.
+                        let padding = match adt_def.adt_kind() {
+                            ty::AdtKind::Struct => {
+                                assert!((field.offset + size) <= field.offset_of_next_field);
+                                field.offset_of_next_field - field.offset - size
+                            }
                             ty::AdtKind::Union => field.offset,
                             ty::AdtKind::Enum => todo!(),
                         };
@@ -2039,7 +3635,13 @@ fn format_trait_thunks<'tcx>(
 
         cc_thunk_decls.add_assign({
             let thunk_name = format_cc_ident(&thunk_name)?;
-            format_thunk_decl(db, method.def_id, &sig, &thunk_name)?
+            format_thunk_decl(
+                db,
+                method.def_id,
+                &sig,
+                &thunk_name,
+                /* with_call_site= */ false,
+            )?
         });
 
         rs_thunk_impls.extend({
@@ -2063,7 +3665,15 @@ fn format_trait_thunks<'tcx>(
                     let method_name = make_rs_ident(method.name.as_str());
                     quote! { <#struct_name as #fully_qualified_trait_name>::#method_name }
                 };
-                format_thunk_impl(tcx, method.def_id, &sig, &thunk_name, fully_qualified_fn_name)?
+                format_thunk_impl(
+                    tcx,
+                    method.def_id,
+                    &sig,
+                    &thunk_name,
+                    fully_qualified_fn_name,
+                    /* self_smart_pointer= */ None,
+                    /* with_call_site= */ false,
+                )?
             }
         });
     }
@@ -2107,10 +3717,15 @@ fn format_default_ctor<'tcx>(
             let mut prereqs = CcPrerequisites::default();
             let cc_thunk_decls = cc_thunk_decls.into_tokens(&mut prereqs);
 
+            let ctor_body = if is_c_abi_compatible_by_value(tcx, core.self_ty) {
+                quote! { *this = __crubit_internal::#thunk_name(); }
+            } else {
+                quote! { __crubit_internal::#thunk_name(this); }
+            };
             let tokens = quote! {
                 #cc_thunk_decls
                 inline #cc_struct_name::#cc_struct_name() {
-                    __crubit_internal::#thunk_name(this);
+                    #ctor_body
                 }
             };
             CcSnippet { tokens, prereqs }
@@ -2336,6 +3951,325 @@ fn format_move_ctor_and_assignment_operator<'tcx>(
     })
 }
 
+/// Well-known traits whose methods are exposed in C++ as static member
+/// functions of the implementing ADT.  Each entry specifies the diagnostic
+/// item of the trait, the public Rust path of the trait (the path from
+/// `FullyQualifiedName` may go through private modules - e.g.
+/// `core::str::traits::FromStr`), the name of the trait method, and the name
+/// of the generated C++ member function.
+const SURFACED_TRAIT_METHODS: &[(Symbol, &str, &str, &str)] = &[
+    (sym::From, "::core::convert::From", "from", "From"),
+    (sym::TryFrom, "::core::convert::TryFrom", "try_from", "TryFrom"),
+    (sym::FromStr, "::core::str::FromStr", "from_str", "Parse"),
+];
+
+/// Describes how the return value of a trait method from
+/// `SURFACED_TRAIT_METHODS` is bridged to C++.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum TraitMethodReturnKind {
+    /// `-> Self` is returned by value.
+    SelfByValue,
+
+    /// `-> Option<Self>` is returned as `std::optional<...>`.
+    OptionOfSelf,
+
+    /// `-> Result<Self, E>` is returned as `std::optional<...>` (the error
+    /// value is dropped on the Rust side).
+    ResultOfSelf,
+}
+
+/// Formats the trait methods from `SURFACED_TRAIT_METHODS` that are
+/// implemented for the ADT represented by `core`.  For example, `impl FromStr
+/// for SomeStruct` is exposed as `static std::optional<SomeStruct>
+/// Parse(std::string_view s)`.
+fn format_trait_methods<'tcx>(
+    db: &dyn BindingsGenerator<'tcx>,
+    core: Rc<AdtCoreBindings<'tcx>>,
+) -> ApiSnippets {
+    let tcx = db.tcx();
+    let self_ty = core.self_ty;
+    SURFACED_TRAIT_METHODS
+        .iter()
+        .filter_map(|&(trait_name, rs_trait_path, method_name, cc_name)| {
+            let trait_id = tcx.get_diagnostic_item(trait_name)?;
+            Some((trait_id, rs_trait_path, method_name, cc_name))
+        })
+        .flat_map(|(trait_id, rs_trait_path, method_name, cc_name)| {
+            tcx.all_local_trait_impls(())
+                .get(&trait_id)
+                .into_iter()
+                .flatten()
+                .filter(move |impl_id| tcx.type_of(**impl_id).instantiate_identity() == self_ty)
+                .filter_map(move |impl_id| {
+                    let method = tcx
+                        .associated_items(impl_id.to_def_id())
+                        .in_definition_order()
+                        .find(|item| {
+                            item.kind == ty::AssocKind::Fn && item.name.as_str() == method_name
+                        })?;
+                    Some((trait_id, rs_trait_path, method.def_id.expect_local(), cc_name))
+                })
+        })
+        .sorted_by_key(|(_, _, method_id, _)| tcx.def_span(*method_id))
+        .map(|(trait_id, rs_trait_path, method_id, cc_name)| {
+            format_trait_method(db, core.clone(), trait_id, rs_trait_path, method_id, cc_name)
+                .unwrap_or_else(|err| format_unsupported_def(db, method_id, err))
+        })
+        .collect()
+}
+
+/// Formats a single trait method (see `format_trait_methods`) as a static
+/// member function named `cc_name`.
+fn format_trait_method<'tcx>(
+    db: &dyn BindingsGenerator<'tcx>,
+    core: Rc<AdtCoreBindings<'tcx>>,
+    trait_id: DefId,
+    rs_trait_path: &str,
+    method_id: LocalDefId,
+    cc_name: &str,
+) -> Result<ApiSnippets> {
+    let tcx = db.tcx();
+    let def_id = method_id.to_def_id();
+    ensure!(
+        tcx.generics_of(def_id).count() == 0,
+        "Generic trait impls are not supported yet (b/259749023)"
+    );
+
+    let sig = {
+        let sig = tcx.fn_sig(def_id).instantiate_identity();
+        let sig = tcx.instantiate_bound_regions_with_erased(sig);
+        // Normalization resolves associated types like `<Self as FromStr>::Err`.
+        tcx.normalize_erasing_regions(tcx.param_env(def_id), sig)
+    };
+    check_fn_sig(&sig)?;
+
+    let output = sig.output();
+    let return_kind = if output == core.self_ty {
+        TraitMethodReturnKind::SelfByValue
+    } else {
+        match output.kind() {
+            ty::TyKind::Adt(adt, args) if args.types().next() == Some(core.self_ty) => {
+                if tcx.is_diagnostic_item(sym::Option, adt.did()) {
+                    TraitMethodReturnKind::OptionOfSelf
+                } else if tcx.is_diagnostic_item(sym::Result, adt.did()) {
+                    TraitMethodReturnKind::ResultOfSelf
+                } else {
+                    bail!("Unsupported return type `{output}`")
+                }
+            }
+            _ => bail!(
+                "Unsupported return type `{output}` \
+                 (expecting `Self`, `Option<Self>`, or `Result<Self, E>`)"
+            ),
+        }
+    };
+    db.format_move_ctor_and_assignment_operator(core.clone()).map_err(|_| {
+        anyhow!("Can't return `{}` by value without a move constructor", core.self_ty)
+    })?;
+
+    let mut main_api_prereqs = CcPrerequisites::default();
+    let mut main_api_params = vec![];
+    let mut cc_thunk_params = vec![];
+    let mut cc_thunk_args = vec![];
+    let mut rs_thunk_params = vec![];
+    let mut rs_thunk_prologue = quote! {};
+    let mut rs_call_args = vec![];
+    for (i, (ident, &ty)) in tcx.fn_arg_names(def_id).iter().zip(sig.inputs()).enumerate() {
+        let name = if ident.as_str().is_empty() {
+            format!("__param_{i}")
+        } else {
+            ident.as_str().to_string()
+        };
+        let cc_name = format_cc_ident(&name)?;
+        let rs_name = make_rs_ident(&name);
+        match ty.kind() {
+            ty::TyKind::Ref(_, referent, Mutability::Not) if referent.is_str() => {
+                // `&str` is passed over the FFI boundary as a pointer + length pair.  Invalid
+                // UTF-8 is reported through the fallible return type.
+                ensure!(
+                    return_kind != TraitMethodReturnKind::SelfByValue,
+                    "`&str` parameters are only supported when the return type is \
+                     `Option<Self>` or `Result<Self, E>`"
+                );
+                main_api_prereqs.includes.insert(CcInclude::string_view());
+                main_api_prereqs.includes.insert(CcInclude::cstddef());
+                main_api_params.push(quote! { std::string_view #cc_name });
+                cc_thunk_params.push(quote! { const char*, std::size_t });
+                cc_thunk_args.push(quote! { #cc_name.data(), #cc_name.size() });
+                let ptr = format_ident!("__{name}_ptr");
+                let len = format_ident!("__{name}_len");
+                rs_thunk_params.push(quote! { #ptr: *const u8, #len: usize });
+                // `std::string_view().data()` is null, which `from_raw_parts` doesn't allow
+                // (even for an empty slice).
+                rs_thunk_prologue.extend(quote! {
+                    let #rs_name: &[u8] = if #len == 0 {
+                        &[]
+                    } else {
+                        unsafe { ::core::slice::from_raw_parts(#ptr, #len) }
+                    };
+                    let Ok(#rs_name) = ::core::str::from_utf8(#rs_name) else {
+                        return false;
+                    };
+                });
+            }
+            _ => {
+                ensure!(
+                    is_c_abi_compatible_by_value(tcx, ty) && !ty.is_ref(),
+                    "Unsupported type of parameter `{name}`: `{ty}` \
+                     (only `&str` and C-ABI-compatible types are supported)"
+                );
+                let cc_type = db
+                    .format_ty_for_cc(ty, TypeLocation::FnParam)
+                    .with_context(|| format!("Error handling parameter `{name}`"))?
+                    .into_tokens(&mut main_api_prereqs);
+                let rs_type = format_ty_for_rs(tcx, ty)
+                    .with_context(|| format!("Error handling parameter `{name}`"))?;
+                main_api_params.push(quote! { #cc_type #cc_name });
+                cc_thunk_params.push(cc_type);
+                cc_thunk_args.push(quote! { #cc_name });
+                rs_thunk_params.push(quote! { #rs_name: #rs_type });
+            }
+        }
+        rs_call_args.push(rs_name);
+    }
+
+    let thunk_name = {
+        // Call to `mono` is ok - `generics_of` have been checked above.
+        let instance = ty::Instance::mono(tcx, def_id);
+        format!("__crubit_thunk_{}", &escape_non_identifier_chars(tcx.symbol_name(instance).name))
+    };
+
+    let adt_cc_name = &core.cc_short_name;
+    let main_api_fn_name = format_cc_ident(cc_name)?;
+    let main_api_ret_type = match return_kind {
+        TraitMethodReturnKind::SelfByValue => quote! { #adt_cc_name },
+        TraitMethodReturnKind::OptionOfSelf | TraitMethodReturnKind::ResultOfSelf => {
+            main_api_prereqs.includes.insert(CcInclude::optional());
+            quote! { std::optional<#adt_cc_name> }
+        }
+    };
+
+    let main_api = {
+        let trait_method_name = format!("{}::{}", tcx.item_name(trait_id), tcx.item_name(def_id));
+        let mut prereqs = main_api_prereqs.clone();
+        prereqs.move_defs_to_fwd_decls();
+        CcSnippet {
+            prereqs,
+            tokens: quote! {
+                __NEWLINE__ __COMMENT__ #trait_method_name
+                static #main_api_ret_type #main_api_fn_name( #( #main_api_params ),* );
+                __NEWLINE__
+            },
+        }
+    };
+
+    let cc_details = {
+        let thunk_name = format_cc_ident(&thunk_name).context("Error formatting thunk name")?;
+        let mut prereqs = main_api_prereqs;
+        prereqs.includes.insert(CcInclude::utility()); // for `std::move`
+        prereqs.includes.insert(db.support_header("internal/return_value_slot.h"));
+        let no_sanitize_attr = format_no_sanitize_attr(db, &mut prereqs);
+        let msan_unpoison = format_msan_unpoison_ret_slot(db, adt_cc_name, &mut prereqs);
+        let (thunk_ret_type, thunk_call) = match return_kind {
+            TraitMethodReturnKind::SelfByValue => (
+                quote! { void },
+                quote! { __crubit_internal::#thunk_name( #( #cc_thunk_args, )* __ret_slot.Get()); },
+            ),
+            TraitMethodReturnKind::OptionOfSelf | TraitMethodReturnKind::ResultOfSelf => (
+                quote! { bool },
+                quote! {
+                    if (!__crubit_internal::#thunk_name( #( #cc_thunk_args, )* __ret_slot.Get())) {
+                        return std::nullopt;
+                    }
+                },
+            ),
+        };
+        CcSnippet {
+            prereqs,
+            tokens: quote! {
+                __NEWLINE__
+                namespace __crubit_internal {
+                    extern "C" #thunk_ret_type #thunk_name(
+                        #( #cc_thunk_params, )* #adt_cc_name* __ret_ptr);
+                }
+                #no_sanitize_attr
+                inline #main_api_ret_type #adt_cc_name::#main_api_fn_name(
+                        #( #main_api_params ),* ) {
+                    crubit::ReturnValueSlot<#adt_cc_name> __ret_slot;
+                    #thunk_call
+                    #msan_unpoison
+                    return std::move(__ret_slot).AssumeInitAndTakeValue();
+                }
+                __NEWLINE__
+            },
+        }
+    };
+
+    let rs_details = {
+        let thunk_name = make_rs_ident(&thunk_name);
+        let struct_name = &core.rs_fully_qualified_name;
+        let trait_name: TokenStream =
+            rs_trait_path.parse().expect("`SURFACED_TRAIT_METHODS` should contain valid paths");
+        let trait_args = {
+            let impl_id = tcx.parent(def_id);
+            let trait_ref = tcx
+                .impl_trait_ref(impl_id)
+                .expect("`format_trait_methods` only looks at trait impls")
+                .instantiate_identity();
+            let trait_args = trait_ref
+                .args
+                .types()
+                .skip(1) // Skipping `Self`.
+                .map(|ty| format_ty_for_rs(tcx, ty))
+                .collect::<Result<Vec<_>>>()?;
+            if trait_args.is_empty() {
+                quote! {}
+            } else {
+                quote! { < #( #trait_args ),* > }
+            }
+        };
+        let method_name = make_rs_ident(tcx.item_name(def_id).as_str());
+        let call = quote! {
+            <#struct_name as #trait_name #trait_args>::#method_name( #( #rs_call_args ),* )
+        };
+        let (thunk_ret_type, thunk_body) = match return_kind {
+            TraitMethodReturnKind::SelfByValue => {
+                (quote! { () }, quote! { __ret_slot.write(#call); })
+            }
+            TraitMethodReturnKind::OptionOfSelf => (
+                quote! { bool },
+                quote! {
+                    match #call {
+                        Some(value) => { __ret_slot.write(value); true }
+                        None => false,
+                    }
+                },
+            ),
+            TraitMethodReturnKind::ResultOfSelf => (
+                quote! { bool },
+                quote! {
+                    match #call {
+                        Ok(value) => { __ret_slot.write(value); true }
+                        Err(_) => false,
+                    }
+                },
+            ),
+        };
+        quote! {
+            #[no_mangle]
+            extern "C" fn #thunk_name(
+                #( #rs_thunk_params, )*
+                __ret_slot: &mut ::core::mem::MaybeUninit<#struct_name>
+            ) -> #thunk_ret_type {
+                #rs_thunk_prologue
+                #thunk_body
+            }
+        }
+    };
+
+    Ok(ApiSnippets { main_api, cc_details, rs_details })
+}
+
 /// Formats an algebraic data type (an ADT - a struct, an enum, or a union)
 /// represented by `core`.  This function is infallible - after
 /// `format_adt_core` returns success we have committed to emitting C++ bindings
@@ -2426,6 +4360,8 @@ fn format_adt<'tcx>(
         })
         .collect();
 
+    let trait_methods_snippets = format_trait_methods(db, core.clone());
+
     let ApiSnippets {
         main_api: public_functions_main_api,
         cc_details: public_functions_cc_details,
@@ -2436,6 +4372,7 @@ fn format_adt<'tcx>(
         move_ctor_and_assignment_snippets,
         copy_ctor_and_assignment_snippets,
         impl_items_snippets,
+        trait_methods_snippets,
     ]
     .into_iter()
     .collect();
@@ -2448,6 +4385,12 @@ fn format_adt<'tcx>(
 
     let alignment = Literal::u64_unsuffixed(core.alignment_in_bytes);
     let size = Literal::u64_unsuffixed(core.size_in_bytes);
+    // C++ objects can't be zero-sized, so the C++ struct for a zero-sized Rust type
+    // is empty, and its size is its alignment instead.  This is fine, because the
+    // thunks pass such structs by pointer, and Rust never reads or writes any of
+    // their bytes.
+    let is_zst = core.size_in_bytes == 0;
+    let cc_size = if is_zst { alignment.clone() } else { size.clone() };
     let main_api = {
         let rs_type = core.rs_fully_qualified_name.to_string();
         let mut attributes = vec![
@@ -2479,7 +4422,15 @@ fn format_adt<'tcx>(
             attributes.push(cc_deprecated_tag);
         }
 
-        let doc_comment = format_doc_comment(tcx, core.def_id.expect_local());
+        let mut doc_comment = format_doc_comment(tcx, core.def_id.expect_local());
+        if is_zst {
+            let msg = format!(
+                "`{}` is a zero-sized type in Rust. It is represented by an empty \
+                 C++ struct, whose `sizeof` is {} (rather than 0), and which holds no data.",
+                core.cc_short_name, core.alignment_in_bytes,
+            );
+            doc_comment.extend(quote! { __COMMENT__ #msg });
+        }
         let keyword = &core.keyword;
 
         let mut prereqs = CcPrerequisites::default();
@@ -2511,7 +4462,7 @@ fn format_adt<'tcx>(
             tokens: quote! {
                 __NEWLINE__
                 static_assert(
-                    sizeof(#adt_cc_name) == #size,
+                    sizeof(#adt_cc_name) == #cc_size,
                     "Verify that ADT layout didn't change since this header got generated");
                 static_assert(
                     alignof(#adt_cc_name) == #alignment,
@@ -2575,25 +4526,71 @@ fn format_source_location(tcx: TyCtxt, local_def_id: LocalDefId) -> String {
 /// Formats the doc comment (if any) associated with the item identified by
 /// `local_def_id`, and appends the source location at which the item is
 /// defined.
+///
+/// The doc comment of a macro shim (see `crubit_attr::is_export_macro_shim`)
+/// also points at the macro that it wraps.
 fn format_doc_comment(tcx: TyCtxt, local_def_id: LocalDefId) -> TokenStream {
     let hir_id = tcx.local_def_id_to_hir_id(local_def_id);
-    let doc_comment = tcx
+    let rustdoc = tcx
         .hir()
         .attrs(hir_id)
         .iter()
         .filter_map(|attr| attr.doc_str())
         .map(|symbol| symbol.to_string())
+        .collect_vec();
+    let macro_shim_note = is_macro_shim(tcx, local_def_id.to_def_id())
+        .then(|| format!("Wraps the Rust macro `{}!`.", tcx.item_name(local_def_id.to_def_id())));
+    let doc_comment = (!rustdoc.is_empty())
+        .then(|| rustdoc_to_doxygen::rustdoc_to_doxygen(&rustdoc.join("\n\n")))
+        .into_iter()
+        .chain(macro_shim_note)
         .chain(once(format!("Generated from: {}", format_source_location(tcx, local_def_id))))
         .join("\n\n");
     quote! { __COMMENT__ #doc_comment}
 }
 
+/// Whether `def_id` is a function annotated with
+/// `#[__crubit::export_macro_shim]`.  Invalid attributes are reported by
+/// `format_fn`.
+fn is_macro_shim(tcx: TyCtxt, def_id: DefId) -> bool {
+    crubit_attr::is_export_macro_shim(tcx, def_id).unwrap_or(false)
+}
+
+/// Whether the module of `def_id` contains another item named `macros`, whose
+/// bindings would collide with the namespace of the macro shims of the module
+/// (see `cc_mod_path`).
+fn macros_namespace_collides(tcx: TyCtxt, def_id: DefId) -> bool {
+    let mod_def_id = tcx.opt_parent(def_id);
+    tcx.hir()
+        .items()
+        .map(|item_id| item_id.owner_id.to_def_id())
+        .filter(|item_def_id| tcx.opt_parent(*item_def_id) == mod_def_id)
+        .filter_map(|item_def_id| tcx.opt_item_name(item_def_id))
+        .any(|name| name.as_str() == "macros")
+}
+
+/// Returns the C++ namespace of the bindings for `def_id`, relative to the
+/// namespace of the crate.  This is the path of the Rust module of the item,
+/// except that macro shims are grouped in a nested `macros` namespace, so that
+/// C++ code can find the behavior of the macros of a module in one place.
+fn cc_mod_path(tcx: TyCtxt, def_id: DefId) -> NamespaceQualifier {
+    let mut mod_path = FullyQualifiedName::new(tcx, def_id).mod_path;
+    if is_macro_shim(tcx, def_id) {
+        mod_path.namespaces.push("macros".into());
+    }
+    mod_path
+}
+
 /// Formats a HIR item idenfied by `def_id`.  Returns `None` if the item
 /// can be ignored. Returns an `Err` if the definition couldn't be formatted.
 ///
 /// Will panic if `def_id` is invalid (i.e. doesn't identify a HIR item).
 fn format_item(db: &dyn BindingsGenerator<'_>, def_id: LocalDefId) -> Result<Option<ApiSnippets>> {
     let tcx = db.tcx();
+    ensure!(
+        crubit_attr::get(tcx, def_id)?.cpp_epilogue.is_none(),
+        "`#[__crubit::annotate(cpp_epilogue = ...)]` is only supported on the crate root"
+    );
     // TODO(b/262052635): When adding support for re-exports we may need to change
     // `is_directly_public` below into `is_exported`.  (OTOH such change *alone* is
     // undesirable, because it would mean exposing items from a private module.
@@ -2616,6 +4613,8 @@ fn format_item(db: &dyn BindingsGenerator<'_>, def_id: LocalDefId) -> Result<Opt
             db.format_adt_core(def_id.to_def_id())
                 .map(|core| Some(format_adt(db, core))),
         Item { kind: ItemKind::TyAlias(..), ..} => format_type_alias(db, def_id).map(Some),
+        Item { kind: ItemKind::Trait(..), .. } if db.experimental_features() =>
+            format_trait(db, def_id).map(Some),
         Item { ident, kind: ItemKind::Use(use_path, use_kind), ..} => {
             format_use(db, ident.as_str(), use_path, use_kind).map(Some)
         },
@@ -2702,7 +4701,7 @@ pub fn format_namespace_bound_cc_tokens(
                 }
             }
             ns.format_with_cc_body(tokens, ns_attributes).unwrap_or_else(|err| {
-                let name = ns.0.iter().join("::");
+                let name = ns.namespaces.iter().join("::");
                 let err = format!("Failed to format namespace name `{name}`: {err}");
                 quote! { __COMMENT__ #err }
             })
@@ -2719,6 +4718,235 @@ pub fn format_namespace_bound_cc_tokens(
     iter.collect()
 }
 
+/// Formats a pure virtual method that mirrors the free function `local_def_id`
+/// (for the C++ interface generated by `format_mockable_interfaces`), together
+/// with an `override` of this method that calls the real bindings.
+fn format_mockable_method(
+    db: &dyn BindingsGenerator<'_>,
+    local_def_id: LocalDefId,
+) -> Result<(CcSnippet, CcSnippet)> {
+    let tcx = db.tcx();
+    let def_id = local_def_id.to_def_id();
+    let sig = get_fn_sig(tcx, local_def_id);
+
+    let mut fully_qualified_fn_name = FullyQualifiedName::new(tcx, def_id);
+    fully_qualified_fn_name.name = Some(get_cc_fn_name(db, def_id));
+    let method_name = format_cc_ident(
+        fully_qualified_fn_name.name.expect("Functions are assumed to always have a name").as_str(),
+    )?;
+
+    let mut prereqs = CcPrerequisites::default();
+    let ret_type = format_ret_ty_for_cc(db, &sig)?.into_tokens(&mut prereqs);
+    let mut params = vec![];
+    let mut args = vec![];
+    for (i, ((name, &ty), cc_type)) in tcx
+        .fn_arg_names(def_id)
+        .iter()
+        .zip(sig.inputs().iter())
+        .zip(format_param_types_for_cc(db, &sig)?)
+        .enumerate()
+    {
+        let cc_name = format_cc_ident(name.as_str())
+            .unwrap_or_else(|_err| format_cc_ident(&format!("__param_{i}")).unwrap());
+        let cc_type = cc_type.into_tokens(&mut prereqs);
+        params.push(quote! { #cc_type #cc_name });
+        if is_c_abi_compatible_by_value(tcx, ty) {
+            args.push(quote! { #cc_name });
+        } else {
+            prereqs.includes.insert(CcInclude::utility()); // for `std::move`
+            args.push(quote! { std::move(#cc_name) });
+        }
+    }
+
+    let fn_name = fully_qualified_fn_name.format_for_cc()?;
+    let decl = CcSnippet {
+        prereqs: prereqs.clone(),
+        tokens: quote! {
+            virtual #ret_type #method_name( #( #params ),* ) = 0;
+        },
+    };
+    let default_impl = CcSnippet {
+        prereqs,
+        tokens: quote! {
+            #ret_type #method_name( #( #params ),* ) override {
+                return #fn_name( #( #args ),* );
+            }
+        },
+    };
+    Ok((decl, default_impl))
+}
+
+/// Returns the name of the first class of the mockable interfaces (see
+/// `format_mockable_interfaces`) which collides with another item of the module
+/// `mod_def_id`, if any.
+fn mockable_interfaces_collision(tcx: TyCtxt, mod_def_id: Option<DefId>) -> Option<&'static str> {
+    let names: HashSet<Symbol> = tcx
+        .hir()
+        .items()
+        .map(|item_id| item_id.owner_id.to_def_id())
+        .filter(|item_def_id| tcx.opt_parent(*item_def_id) == mod_def_id)
+        .filter_map(|item_def_id| tcx.opt_item_name(item_def_id))
+        .collect();
+    ["Functions", "DefaultFunctions"].into_iter().find(|name| names.contains(&Symbol::intern(name)))
+}
+
+/// Formats mockable C++ interfaces for the free functions in `fn_def_ids`.
+///
+/// For each module this generates an abstract `Functions` class with a pure
+/// virtual method per free function, and a `DefaultFunctions` class that
+/// implements these methods by calling the real bindings.  C++ code can depend
+/// on `Functions&` and tests can substitute a gMock-based fake for it.
+///
+/// Functions that can't be formatted are silently skipped (the main API will
+/// already contain a comment explaining why there are no bindings for them).
+/// The interfaces of a module which contains another item named `Functions` or
+/// `DefaultFunctions` are replaced with a comment explaining the problem.
+fn format_mockable_interfaces(
+    db: &dyn BindingsGenerator<'_>,
+    fn_def_ids: &[LocalDefId],
+) -> Result<Vec<(Option<DefId>, NamespaceQualifier, CcSnippet)>> {
+    let tcx = db.tcx();
+    let fns_by_mod = fn_def_ids
+        .iter()
+        .copied()
+        .sorted_by_key(|def_id| tcx.def_span(*def_id))
+        .map(|def_id| (FullyQualifiedName::new(tcx, def_id.to_def_id()).mod_path, def_id))
+        .into_group_map();
+    let mut result = vec![];
+    for (mod_path, def_ids) in fns_by_mod.into_iter().sorted_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs)) {
+        let mut prereqs = CcPrerequisites::default();
+        let mut decls = vec![];
+        let mut default_impls = vec![];
+        for def_id in def_ids.iter().copied() {
+            let Ok((decl, default_impl)) = format_mockable_method(db, def_id) else {
+                continue;
+            };
+            decls.push(decl.into_tokens(&mut prereqs));
+            default_impls.push(default_impl.into_tokens(&mut prereqs));
+        }
+        if decls.is_empty() {
+            continue;
+        }
+        let ns_def_id = tcx.opt_parent(def_ids[0].to_def_id());
+        if let Some(name) = mockable_interfaces_collision(tcx, ns_def_id) {
+            let err = anyhow!(
+                "The `{name}` class of the mockable interfaces collides with another item named \
+                 `{name}` in the same module"
+            );
+            db.errors().insert(&err);
+            let msg = format!("Error generating the mockable interfaces: {err:#}");
+            let tokens = quote! { __NEWLINE__ __COMMENT__ #msg __NEWLINE__ };
+            result.push((ns_def_id, mod_path, CcSnippet::new(tokens)));
+            continue;
+        }
+        let tokens = quote! {
+            __NEWLINE__
+            __COMMENT__ "Mockable interface for the free functions in this namespace."
+            class Functions {
+                public:
+                    virtual ~Functions() = default;
+                    #( #decls )*
+            };
+            __NEWLINE__
+            __COMMENT__ "Implementation of `Functions` that calls the real bindings."
+            class DefaultFunctions : public Functions {
+                public:
+                    #( #default_impls )*
+            };
+            __NEWLINE__
+        };
+        result.push((ns_def_id, mod_path, CcSnippet { tokens, prereqs }));
+    }
+    Ok(result)
+}
+
+/// Formats the aliases requested by `db.namespace_aliases()`.  The aliases
+/// are emitted in the global namespace, after the namespace of the crate.
+///
+/// An alias that refers to a module without any bindings (and therefore
+/// without a C++ namespace) is replaced with a comment explaining the problem.
+fn format_namespace_aliases(
+    db: &dyn BindingsGenerator<'_>,
+    namespaces: &BTreeSet<NamespaceQualifier>,
+) -> TokenStream {
+    let crate_name = db.tcx().crate_name(LOCAL_CRATE);
+    db.namespace_aliases()
+        .iter()
+        .map(|(alias, mod_path)| {
+            let target = NamespaceQualifier::new(mod_path.split("::"));
+            let result = if !namespaces.contains(&target) {
+                Err(anyhow!("There are no bindings in the `{mod_path}` module"))
+            } else {
+                format_cc_ident(alias).and_then(|alias| {
+                    let crate_name = format_cc_ident(crate_name.as_str())?;
+                    let target = target.cc_idents()?;
+                    Ok(quote! { namespace #alias = :: #crate_name #( :: #target )*; __NEWLINE__ })
+                })
+            };
+            result.unwrap_or_else(|err| {
+                let txt = format!("Failed to generate namespace alias `{alias}`: {err:#}");
+                quote! { __COMMENT__ #txt }
+            })
+        })
+        .collect()
+}
+
+/// Formats the aliases requested by `db.auto_namespace_alias_min_depth()`.
+/// The aliases are emitted in the namespace of the crate.
+///
+/// An alias is only generated if it is unambiguous: if no other deep module has
+/// the same name, and if the crate root doesn't contain an item or a module
+/// with the same name.
+fn format_auto_namespace_aliases(
+    db: &dyn BindingsGenerator<'_>,
+    namespaces: &BTreeSet<NamespaceQualifier>,
+) -> Result<TokenStream> {
+    let Some(min_depth) = db.auto_namespace_alias_min_depth() else {
+        return Ok(quote! {});
+    };
+    let tcx = db.tcx();
+    let root_names: HashSet<Rc<str>> = tcx
+        .hir()
+        .items()
+        .map(|item_id| item_id.owner_id.to_def_id())
+        .filter(|def_id| tcx.opt_parent(*def_id) == Some(LOCAL_CRATE.as_def_id()))
+        .filter_map(|def_id| tcx.opt_item_name(def_id))
+        .map(|name| name.as_str().into())
+        .collect();
+    let candidates = namespaces
+        .iter()
+        .filter(|ns| ns.namespaces.len() >= min_depth.max(2))
+        .map(|ns| (ns.namespaces.last().unwrap().clone(), ns))
+        .into_group_map();
+    let mut aliases = vec![];
+    for (alias, targets) in candidates.into_iter().sorted_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs)) {
+        if targets.len() != 1 || root_names.contains(&alias) {
+            continue;
+        }
+        let alias = format_cc_ident(&alias)?;
+        let target = targets[0].cc_idents()?;
+        aliases.push(quote! { namespace #alias = #( #target )::*; __NEWLINE__ });
+    }
+    Ok(quote! { #( #aliases )* })
+}
+
+/// Formats the hand-written C++ code from the file referenced by the
+/// `#![__crubit::annotate(cpp_epilogue = "...")]` attribute of the crate (if
+/// any).  The code is emitted as-is at the end of the namespace of the crate,
+/// so it can refer to all the generated C++ declarations.
+fn format_cpp_epilogue(tcx: TyCtxt) -> Result<TokenStream> {
+    let Some(path) = crubit_attr::get(tcx, CRATE_DEF_ID)?.cpp_epilogue else {
+        return Ok(quote! {});
+    };
+    // Like `include_str!`, the path is relative to the directory of the crate root.
+    let crate_root = tcx.sess().source_map().span_to_filename(tcx.def_span(CRATE_DEF_ID));
+    let crate_root = crate_root.prefer_local().to_string();
+    let path = Path::new(&crate_root).parent().unwrap_or(Path::new("")).join(path.as_str());
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read the `cpp_epilogue` file {}", path.display()))?;
+    Ok(quote! { __NEWLINE__ __VERBATIM__ #contents __NEWLINE__ })
+}
+
 /// Formats all public items from the Rust crate being compiled.
 fn format_crate(db: &Database) -> Result<Output> {
     let tcx = db.tcx();
@@ -2726,6 +4954,7 @@ fn format_crate(db: &Database) -> Result<Output> {
     let mut cc_details: Vec<(LocalDefId, TokenStream)> = vec![];
     let mut rs_body = TokenStream::default();
     let mut main_apis = HashMap::<LocalDefId, CcSnippet>::new();
+    let mut mockable_fns: Vec<LocalDefId> = vec![];
     let formatted_items = tcx
         .hir()
         .items()
@@ -2746,7 +4975,21 @@ fn format_crate(db: &Database) -> Result<Output> {
         // meets the prerequisites.
         cc_details.push((def_id, api_snippets.cc_details.into_tokens(&mut cc_details_prereqs)));
         rs_body.extend(api_snippets.rs_details);
+        if db.generate_mockable_interfaces()
+            && matches!(tcx.hir().expect_item(def_id).kind, ItemKind::Fn(..))
+            && !is_macro_shim(tcx, def_id.to_def_id())
+            && matches!(db.format_item(def_id), Ok(Some(_)))
+        {
+            mockable_fns.push(def_id);
+        }
     }
+    // The interfaces call the real bindings, so they go after all the `cc_details`.
+    let mockable_interfaces = format_mockable_interfaces(db, &mockable_fns)?
+        .into_iter()
+        .map(|(ns_def_id, mod_path, snippet)| {
+            (ns_def_id, mod_path, snippet.into_tokens(&mut cc_details_prereqs))
+        })
+        .collect_vec();
 
     // Find the order of `main_apis` that 1) meets the requirements of
     // `CcPrerequisites::defs` and 2) makes a best effort attempt to keep the
@@ -2809,9 +5052,10 @@ fn format_crate(db: &Database) -> Result<Output> {
             .chain(cc_details)
             .map(|(local_def_id, tokens)| {
                 let ns_def_id = tcx.opt_parent(local_def_id.to_def_id());
-                let mod_path = FullyQualifiedName::new(tcx, local_def_id.to_def_id()).mod_path;
+                let mod_path = cc_mod_path(tcx, local_def_id.to_def_id());
                 (ns_def_id, mod_path, tokens)
             })
+            .chain(mockable_interfaces)
             .collect_vec();
 
         (includes, ordered_cc)
@@ -2824,6 +5068,15 @@ fn format_crate(db: &Database) -> Result<Output> {
         // unique + ergonomic).
         let crate_name = format_cc_ident(tcx.crate_name(LOCAL_CRATE).as_str())?;
 
+        let namespaces: BTreeSet<NamespaceQualifier> = ordered_cc
+            .iter()
+            .map(|(_, mod_path, _)| mod_path.clone())
+            .filter(|mod_path| !mod_path.namespaces.is_empty())
+            .collect();
+        let auto_namespace_aliases = format_auto_namespace_aliases(db, &namespaces)?;
+        let namespace_aliases = format_namespace_aliases(db, &namespaces);
+        let cpp_epilogue = format_cpp_epilogue(tcx)?;
+
         let includes = format_cc_includes(&includes);
         let ordered_cc = format_namespace_bound_cc_tokens(ordered_cc, tcx);
         quote! {
@@ -2833,8 +5086,11 @@ fn format_crate(db: &Database) -> Result<Output> {
                 __NEWLINE__
                 #ordered_cc
                 __NEWLINE__
+                #auto_namespace_aliases
+                #cpp_epilogue
             }
             __NEWLINE__
+            #namespace_aliases
         }
     };
 
@@ -2906,8 +5162,1182 @@ pub mod tests {
                         }
                     }
                 }
-            );
-        });
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_mockable_interfaces() {
+        let test_src = r#"
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+
+                pub mod submodule {
+                    pub struct S(pub i32);
+                    pub fn consume(s: S) -> i32 { s.0 }
+                }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let db = Database::new(
+                tcx,
+                /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+                /* crate_name_to_include_paths= */ Default::default(),
+                /* errors = */ Rc::new(IgnoreErrors),
+                /* _features= */ (),
+                /* generate_mockable_interfaces= */ true,
+                /* sanitizer_annotations= */ false,
+                /* clang_tidy_nolint_checks= */ Rc::from([]),
+                /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+                /* namespace_aliases= */ Rc::from([]),
+                /* auto_namespace_alias_min_depth= */ None,
+                /* cc_call_site_in_panics= */ false,
+                /* experimental_features= */ false,
+                /* always_inline_cc_wrappers= */ false,
+                /* inline_trivial_getters= */ false,
+                /* version_stamp= */ None,
+            );
+            let bindings = generate_bindings(&db).unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace rust_out {
+                        ...
+                        class Functions {
+                            public:
+                                virtual ~Functions() = default;
+                                virtual std::int32_t add(std::int32_t x, std::int32_t y) = 0;
+                        };
+                        ...
+                        class DefaultFunctions : public Functions {
+                            public:
+                                std::int32_t add(std::int32_t x, std::int32_t y) override {
+                                    return ::rust_out::add(x, y);
+                                }
+                        };
+                    }
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace submodule {
+                        ...
+                        class Functions {
+                            public:
+                                virtual ~Functions() = default;
+                                virtual std::int32_t consume(::rust_out::submodule::S s) = 0;
+                        };
+                        ...
+                        class DefaultFunctions : public Functions {
+                            public:
+                                std::int32_t consume(::rust_out::submodule::S s) override {
+                                    return ::rust_out::submodule::consume(std::move(s));
+                                }
+                        };
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_mockable_interfaces_colliding_name() {
+        let test_src = r#"
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+
+                pub mod submodule {
+                    pub struct DefaultFunctions(pub i32);
+                    pub fn get(s: &DefaultFunctions) -> i32 { s.0 }
+                }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let db = Database::new(
+                tcx,
+                /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+                /* crate_name_to_include_paths= */ Default::default(),
+                /* errors = */ Rc::new(IgnoreErrors),
+                /* _features= */ (),
+                /* generate_mockable_interfaces= */ true,
+                /* sanitizer_annotations= */ false,
+                /* clang_tidy_nolint_checks= */ Rc::from([]),
+                /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+                /* namespace_aliases= */ Rc::from([]),
+                /* auto_namespace_alias_min_depth= */ None,
+                /* cc_call_site_in_panics= */ false,
+                /* experimental_features= */ false,
+                /* always_inline_cc_wrappers= */ false,
+                /* inline_trivial_getters= */ false,
+                /* version_stamp= */ None,
+            );
+            let bindings = generate_bindings(&db).unwrap();
+            // The interfaces of the crate root are unaffected.
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    class DefaultFunctions : public Functions {
+                        public:
+                            std::int32_t add(std::int32_t x, std::int32_t y) override {
+                                return ::rust_out::add(x, y);
+                            }
+                    };
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace submodule {
+                        ...
+                        __COMMENT__ "Error generating the mockable interfaces: The `DefaultFunctions` \
+                                     class of the mockable interfaces collides with another item named \
+                                     `DefaultFunctions` in the same module"
+                        ...
+                    }
+                }
+            );
+            assert_cc_not_matches!(bindings.h_body, quote! { virtual std::int32_t get });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_macro_shim() {
+        let test_src = r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+
+                macro_rules! twice {
+                    ($x:expr) => { 2 * $x };
+                }
+
+                /// Doubles `x`.
+                #[__crubit::export_macro_shim]
+                pub fn twice(x: i32) -> i32 { twice!(x) }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace rust_out {
+                        ...
+                        namespace macros {
+                            ...
+                            __COMMENT__ " Doubles `x`.\n\nWraps the Rust macro `twice!`.\n\nGenerated from: <crubit_unittests.rs>;l=11"
+                            std::int32_t twice(std::int32_t x);
+                            ...
+                            inline std::int32_t twice(std::int32_t x) {
+                                return __crubit_internal::...(x);
+                            }
+                        }
+                    }
+                }
+            );
+            assert_rs_matches!(bindings.rs_body, quote! { ::rust_out::twice(x) });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_macro_shim_method() {
+        let test_src = r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+
+                pub struct S(i32);
+
+                impl S {
+                    #[__crubit::export_macro_shim]
+                    pub fn get(&self) -> i32 { self.0 }
+                }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    __COMMENT__ "Error generating bindings for `S::get` defined at <crubit_unittests.rs>;l=9: \
+                                 `#[__crubit::export_macro_shim]` is only supported on free functions"
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_macro_shim_colliding_namespace() {
+        let test_src = r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+
+                pub mod macros {}
+
+                #[__crubit::export_macro_shim]
+                pub fn twice(x: i32) -> i32 { 2 * x }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    __COMMENT__ "Error generating bindings for `twice` defined at <crubit_unittests.rs>;l=8: \
+                                 The `macros` namespace of `#[__crubit::export_macro_shim]` functions \
+                                 collides with another item named `macros` in the same module"
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_no_mockable_interfaces_by_default() {
+        let test_src = r#"
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_not_matches!(bindings.h_body, quote! { class Functions });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_sanitizer_annotations() {
+        let test_src = r#"
+                pub struct S(pub i32);
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+                pub fn create(i: i32) -> S { S(i) }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let db = Database::new(
+                tcx,
+                /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+                /* crate_name_to_include_paths= */ Default::default(),
+                /* errors = */ Rc::new(IgnoreErrors),
+                /* _features= */ (),
+                /* generate_mockable_interfaces= */ false,
+                /* sanitizer_annotations= */ true,
+                /* clang_tidy_nolint_checks= */ Rc::from([]),
+                /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+                /* namespace_aliases= */ Rc::from([]),
+                /* auto_namespace_alias_min_depth= */ None,
+                /* cc_call_site_in_panics= */ false,
+                /* experimental_features= */ false,
+                /* always_inline_cc_wrappers= */ false,
+                /* inline_trivial_getters= */ false,
+                /* version_stamp= */ None,
+            );
+            let bindings = generate_bindings(&db).unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    __HASH_TOKEN__ include <crubit/support/for/tests/internal/sanitizers.h>
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    CRUBIT_INTERNAL_NO_SANITIZE
+                    inline std::int32_t add(std::int32_t x, std::int32_t y) {
+                        return __crubit_internal::...(x, y);
+                    }
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    CRUBIT_INTERNAL_NO_SANITIZE
+                    inline ::rust_out::S create(std::int32_t i) {
+                        crubit::ReturnValueSlot<::rust_out::S> __ret_slot;
+                        __crubit_internal::...(i, __ret_slot.Get());
+                        crubit::MsanUnpoison(__ret_slot.Get(), sizeof(::rust_out::S));
+                        return std::move(__ret_slot).AssumeInitAndTakeValue();
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_no_sanitizer_annotations_by_default() {
+        let test_src = r#"
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_not_matches!(bindings.h_body, quote! { CRUBIT_INTERNAL_NO_SANITIZE });
+            assert_cc_not_matches!(bindings.h_body, quote! { MsanUnpoison });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_always_inline_cc_wrappers() {
+        let test_src = r#"
+                #[inline(always)]
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+
+                #[inline(always)]
+                #[export_name = "export_name"]
+                pub extern "C" fn public_function(x: f64, y: f64) -> f64 { x + y }
+
+                #[inline]
+                pub fn sub(x: i32, y: i32) -> i32 { x - y }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let db = Database::new(
+                tcx,
+                /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+                /* crate_name_to_include_paths= */ Default::default(),
+                /* errors = */ Rc::new(IgnoreErrors),
+                /* _features= */ (),
+                /* generate_mockable_interfaces= */ false,
+                /* sanitizer_annotations= */ false,
+                /* clang_tidy_nolint_checks= */ Rc::from([]),
+                /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+                /* namespace_aliases= */ Rc::from([]),
+                /* auto_namespace_alias_min_depth= */ None,
+                /* cc_call_site_in_panics= */ false,
+                /* experimental_features= */ false,
+                /* always_inline_cc_wrappers= */ true,
+                /* inline_trivial_getters= */ false,
+                /* version_stamp= */ None,
+            );
+            let bindings = generate_bindings(&db).unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    [[gnu::always_inline]]
+                    inline std::int32_t add(std::int32_t x, std::int32_t y) {
+                        return __crubit_internal::...(x, y);
+                    }
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    [[gnu::always_inline]]
+                    inline double public_function(double x, double y) {
+                        return __crubit_internal::export_name(x, y);
+                    }
+                }
+            );
+            // Only `#[inline(always)]` is propagated - `#[inline]` is just a hint.
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! { inline std::int32_t sub(std::int32_t x, std::int32_t y) { ... } }
+            );
+            assert_cc_not_matches!(
+                bindings.h_body,
+                quote! { [[gnu::always_inline]] inline std::int32_t sub }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_no_always_inline_cc_wrappers_by_default() {
+        let test_src = r#"
+                #[inline(always)]
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_not_matches!(bindings.h_body, quote! { always_inline });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_inline_trivial_getters() {
+        let test_src = r#"
+                #[repr(C)]
+                pub struct Point {
+                    x: i32,
+                    pub y: f64,
+                }
+
+                impl Point {
+                    pub fn get_x(&self) -> i32 { self.x }
+                    pub fn get_y(&self) -> f64 { self.y }
+                    pub fn sum(&self) -> f64 { self.x as f64 + self.y }
+                    pub fn set_x(&mut self, x: i32) { self.x = x; }
+                }
+
+                #[repr(C)]
+                pub struct TupleStruct(pub u8);
+
+                impl TupleStruct {
+                    pub fn get(&self) -> u8 { self.0 }
+                }
+
+                pub struct NotReprC {
+                    x: i32,
+                }
+
+                impl NotReprC {
+                    pub fn get_x(&self) -> i32 { self.x }
+                }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let db = Database::new(
+                tcx,
+                /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+                /* crate_name_to_include_paths= */ Default::default(),
+                /* errors = */ Rc::new(IgnoreErrors),
+                /* _features= */ (),
+                /* generate_mockable_interfaces= */ false,
+                /* sanitizer_annotations= */ false,
+                /* clang_tidy_nolint_checks= */ Rc::from([]),
+                /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+                /* namespace_aliases= */ Rc::from([]),
+                /* auto_namespace_alias_min_depth= */ None,
+                /* cc_call_site_in_panics= */ false,
+                /* experimental_features= */ false,
+                /* always_inline_cc_wrappers= */ false,
+                /* inline_trivial_getters= */ true,
+                /* version_stamp= */ None,
+            );
+            let bindings = generate_bindings(&db).unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    constexpr std::int32_t get_x() const
+                        [[clang::annotate_type("lifetime", "__anon1")]];
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    constexpr std::int32_t Point::get_x() const
+                        [[clang::annotate_type("lifetime", "__anon1")]] {
+                        return this->x;
+                    }
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    constexpr double Point::get_y() const
+                        [[clang::annotate_type("lifetime", "__anon1")]] {
+                        return this->y;
+                    }
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    constexpr std::uint8_t TupleStruct::get() const
+                        [[clang::annotate_type("lifetime", "__anon1")]] {
+                        return this->__field0;
+                    }
+                }
+            );
+            // The offsets read by the getters are verified by the field offset assertions.
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    static_assert(0 == offsetof(Point, x));
+                    static_assert(8 == offsetof(Point, y));
+                }
+            );
+            assert_rs_not_matches!(bindings.rs_body, quote! { ::rust_out::Point::get_x });
+            assert_rs_not_matches!(bindings.rs_body, quote! { ::rust_out::TupleStruct::get });
+
+            // Methods that don't just read a field still call into Rust.
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    inline double Point::sum() const ... {
+                        return __crubit_internal::...(*this);
+                    }
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! { inline void Point::set_x(std::int32_t x) ... { ... } }
+            );
+            // The C++ layout of structs that are not `#[repr(C)]` isn't stable enough.
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    inline std::int32_t NotReprC::get_x() const ... {
+                        return __crubit_internal::...(*this);
+                    }
+                }
+            );
+            assert_rs_matches!(bindings.rs_body, quote! { ::rust_out::NotReprC::get_x(__self) });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_no_inline_trivial_getters_by_default() {
+        let test_src = r#"
+                #[repr(C)]
+                pub struct Point {
+                    x: i32,
+                }
+
+                impl Point {
+                    pub fn get_x(&self) -> i32 { self.x }
+                }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_not_matches!(bindings.h_body, quote! { constexpr std::int32_t });
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    inline std::int32_t Point::get_x() const ... {
+                        return __crubit_internal::...(*this);
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_version_stamp() {
+        let test_src = r#"
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let db = Database::new(
+                tcx,
+                /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+                /* crate_name_to_include_paths= */ Default::default(),
+                /* errors = */ Rc::new(IgnoreErrors),
+                /* _features= */ (),
+                /* generate_mockable_interfaces= */ false,
+                /* sanitizer_annotations= */ false,
+                /* clang_tidy_nolint_checks= */ Rc::from([]),
+                /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+                /* namespace_aliases= */ Rc::from([]),
+                /* auto_namespace_alias_min_depth= */ None,
+                /* cc_call_site_in_panics= */ false,
+                /* experimental_features= */ false,
+                /* always_inline_cc_wrappers= */ false,
+                /* inline_trivial_getters= */ false,
+                /* version_stamp= */
+                Some(VersionStamp {
+                    version: "1.2.3".into(),
+                    flags: "--sanitizer-annotations".into(),
+                }),
+            );
+            let bindings = generate_bindings(&db).unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace rust_out::__crubit_metadata {
+                        struct CrubitMetadata {
+                            const char* version;
+                            const char* flags;
+                            const char* bindings_hash;
+                        };
+                        inline constexpr CrubitMetadata kCrubitMetadata = {
+                            "1.2.3", "--sanitizer-annotations", ...
+                        };
+                    }
+                }
+            );
+            // The Rust side of the bindings isn't a crate that other Rust code can depend on.
+            assert_rs_not_matches!(bindings.rs_body, quote! { crubit_metadata });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_no_version_stamp_by_default() {
+        let test_src = r#"
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_not_matches!(bindings.h_body, quote! { __crubit_metadata });
+            assert_rs_not_matches!(bindings.rs_body, quote! { __crubit_metadata });
+            assert_rs_not_matches!(bindings.rs_body, quote! { crubit_metadata });
+        });
+    }
+
+    #[test]
+    fn test_fnv1a_64() {
+        assert_eq!(fnv1a_64(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a_64(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a_64(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn test_generated_bindings_clang_tidy_nolint() {
+        let test_src = r#"
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let db = Database::new(
+                tcx,
+                /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+                /* crate_name_to_include_paths= */ Default::default(),
+                /* errors = */ Rc::new(IgnoreErrors),
+                /* _features= */ (),
+                /* generate_mockable_interfaces= */ false,
+                /* sanitizer_annotations= */ false,
+                /* clang_tidy_nolint_checks= */
+                Rc::from(["google-runtime-int".into(), "readability-identifier-naming".into()]),
+                /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+                /* namespace_aliases= */ Rc::from([]),
+                /* auto_namespace_alias_min_depth= */ None,
+                /* cc_call_site_in_panics= */ false,
+                /* experimental_features= */ false,
+                /* always_inline_cc_wrappers= */ false,
+                /* inline_trivial_getters= */ false,
+                /* version_stamp= */ None,
+            );
+            let bindings = generate_bindings(&db).unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    __HASH_TOKEN__ pragma once
+                    __COMMENT__ "NOLINTBEGIN(google-runtime-int, readability-identifier-naming)"
+                    ...
+                    namespace rust_out {
+                        ...
+                    }
+                    __COMMENT__ "NOLINTEND(google-runtime-int, readability-identifier-naming)"
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_no_clang_tidy_nolint_by_default() {
+        let test_src = r#"
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_not_matches!(bindings.h_body, quote! { __COMMENT__ "NOLINTBEGIN" });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_camel_case_naming_style() {
+        let test_src = r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+
+                pub struct SomeStruct(pub i32);
+
+                impl SomeStruct {
+                    pub fn get_value(&self) -> i32 { self.0 }
+                }
+
+                pub fn add_values(x: i32, y: i32) -> i32 { x + y }
+
+                #[__crubit::annotate(cpp_name="explicit_name")]
+                pub fn renamed_fn() {}
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let db = Database::new(
+                tcx,
+                /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+                /* crate_name_to_include_paths= */ Default::default(),
+                /* errors = */ Rc::new(IgnoreErrors),
+                /* _features= */ (),
+                /* generate_mockable_interfaces= */ false,
+                /* sanitizer_annotations= */ false,
+                /* clang_tidy_nolint_checks= */ Rc::from([]),
+                /* cc_naming_style= */ CcNamingStyle::CamelCase,
+                /* namespace_aliases= */ Rc::from([]),
+                /* auto_namespace_alias_min_depth= */ None,
+                /* cc_call_site_in_panics= */ false,
+                /* experimental_features= */ false,
+                /* always_inline_cc_wrappers= */ false,
+                /* inline_trivial_getters= */ false,
+                /* version_stamp= */ None,
+            );
+            let bindings = generate_bindings(&db).unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    std::int32_t GetValue() const [[clang::annotate_type("lifetime", "__anon1")]];
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    inline std::int32_t AddValues(std::int32_t x, std::int32_t y) {
+                        return __crubit_internal::...(x, y);
+                    }
+                }
+            );
+            // `cpp_name` takes precedence over the naming style.
+            assert_cc_matches!(bindings.h_body, quote! { void explicit_name(); });
+            assert_cc_not_matches!(bindings.h_body, quote! { add_values });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_namespace_aliases() {
+        let test_src = r#"
+                pub mod outer {
+                    pub mod inner {
+                        pub fn f() {}
+                    }
+                    pub mod a {
+                        pub mod ambiguous {
+                            pub fn g1() {}
+                        }
+                    }
+                    pub mod b {
+                        pub mod ambiguous {
+                            pub fn g2() {}
+                        }
+                    }
+                    pub mod deep {
+                        pub mod nested {
+                            pub fn h() {}
+                        }
+                    }
+                }
+                pub mod empty {}
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let db = Database::new(
+                tcx,
+                /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+                /* crate_name_to_include_paths= */ Default::default(),
+                /* errors = */ Rc::new(IgnoreErrors),
+                /* _features= */ (),
+                /* generate_mockable_interfaces= */ false,
+                /* sanitizer_annotations= */ false,
+                /* clang_tidy_nolint_checks= */ Rc::from([]),
+                /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+                /* namespace_aliases= */
+                Rc::from([("oi".into(), "outer::inner".into()), ("e".into(), "empty".into())]),
+                /* auto_namespace_alias_min_depth= */ Some(3),
+                /* cc_call_site_in_panics= */ false,
+                /* experimental_features= */ false,
+                /* always_inline_cc_wrappers= */ false,
+                /* inline_trivial_getters= */ false,
+                /* version_stamp= */ None,
+            );
+            let bindings = generate_bindings(&db).unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace rust_out {
+                        ...
+                        namespace nested = outer::deep::nested;
+                    }
+                    namespace oi = ::rust_out::outer::inner;
+                    __COMMENT__ "Failed to generate namespace alias `e`: \
+                                 There are no bindings in the `empty` module"
+                }
+            );
+            // `outer::inner` is not deep enough to get an automatic alias.
+            assert_cc_not_matches!(bindings.h_body, quote! { namespace inner = });
+            // `outer::a::ambiguous` and `outer::b::ambiguous` would get the same alias.
+            assert_cc_not_matches!(bindings.h_body, quote! { namespace ambiguous = });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_cpp_epilogue() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("epilogue.h");
+        let contents = "inline std::int32_t twice(std::int32_t x) { return add(x, x); }";
+        std::fs::write(&path, contents).unwrap();
+        let test_src = format!(
+            r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+                #![__crubit::annotate(cpp_epilogue = "{}")]
+                pub fn add(x: i32, y: i32) -> i32 {{ x + y }}
+            "#,
+            path.display()
+        );
+        test_generated_bindings(&test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace rust_out {
+                        ...
+                        std::int32_t add(std::int32_t x, std::int32_t y);
+                        ...
+                        __VERBATIM__ #contents
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_cpp_epilogue_missing_file() {
+        let test_src = r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+                #![__crubit::annotate(cpp_epilogue = "/does/not/exist.h")]
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    __COMMENT__ "Failed to generate bindings for the crate: \
+                                 Failed to read the `cpp_epilogue` file /does/not/exist.h"
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_cpp_epilogue_on_item() {
+        let test_src = r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+                #[__crubit::annotate(cpp_epilogue = "epilogue.h")]
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    __COMMENT__ "Error generating bindings for `add` defined at <crubit_unittests.rs>;l=5: \
+                                 `#[__crubit::annotate(cpp_epilogue = ...)]` is only supported on the crate root"
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_cc_call_site_in_panics() {
+        let test_src = r#"
+                pub struct S(pub i32);
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+                pub fn create(i: i32) -> S { S(i) }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let db = Database::new(
+                tcx,
+                /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+                /* crate_name_to_include_paths= */ Default::default(),
+                /* errors = */ Rc::new(IgnoreErrors),
+                /* _features= */ (),
+                /* generate_mockable_interfaces= */ false,
+                /* sanitizer_annotations= */ false,
+                /* clang_tidy_nolint_checks= */ Rc::from([]),
+                /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+                /* namespace_aliases= */ Rc::from([]),
+                /* auto_namespace_alias_min_depth= */ None,
+                /* cc_call_site_in_panics= */ true,
+                /* experimental_features= */ false,
+                /* always_inline_cc_wrappers= */ false,
+                /* inline_trivial_getters= */ false,
+                /* version_stamp= */ None,
+            );
+            let bindings = generate_bindings(&db).unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! { __HASH_TOKEN__ include <source_location> }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    std::int32_t add(
+                        std::int32_t x,
+                        std::int32_t y,
+                        std::source_location __crubit_call_site = std::source_location::current());
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" std::int32_t ...(
+                            std::int32_t, std::int32_t,
+                            const char* __crubit_file, std::uint32_t __crubit_line);
+                    }
+                    inline std::int32_t add(
+                            std::int32_t x,
+                            std::int32_t y,
+                            std::source_location __crubit_call_site) {
+                        return __crubit_internal::...(
+                            x, y, __crubit_call_site.file_name(), __crubit_call_site.line());
+                    }
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    __crubit_internal::...(
+                        i, __ret_slot.Get(),
+                        __crubit_call_site.file_name(), __crubit_call_site.line());
+                }
+            );
+            assert_rs_matches!(
+                bindings.rs_body,
+                quote! {
+                    mod __crubit_cc_call_site { ... }
+                }
+            );
+            assert_rs_matches!(
+                bindings.rs_body,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...(
+                        x: i32,
+                        y: i32,
+                        __crubit_file: *const ::core::ffi::c_char,
+                        __crubit_line: u32
+                    ) -> i32 {
+                        let __crubit_call_site =
+                            crate::__crubit_cc_call_site::Guard::new(__crubit_file, __crubit_line);
+                        ::rust_out::add(x, y)
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_no_cc_call_site_by_default() {
+        let test_src = r#"
+                pub fn add(x: i32, y: i32) -> i32 { x + y }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_not_matches!(bindings.h_body, quote! { source_location });
+            assert_rs_not_matches!(bindings.rs_body, quote! { __crubit_cc_call_site });
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_impl_trait_param() {
+        let test_src = r#"
+                /// Doc comment of the trait.
+                pub trait Shape {
+                    fn area(&self) -> f64;
+                    fn scale(&mut self, factor: f64);
+                }
+
+                pub fn scaled_area(mut shape: impl Shape, factor: f64) -> f64 {
+                    shape.scale(factor);
+                    shape.area()
+                }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let bindings = generate_bindings(&experimental_bindings_db_for_tests(tcx)).unwrap();
+            assert_cc_matches!(bindings.h_body, quote! { __HASH_TOKEN__ include <concepts> });
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    __COMMENT__ " Doc comment of the trait.\n\nGenerated from: <crubit_unittests.rs>;l=3"
+                    template <typename T>
+                    concept Shape = requires {
+                        { std::declval<const T&>().area() } -> std::convertible_to<double>;
+                        std::declval<T&>().scale(std::declval<double>());
+                    };
+                    namespace __crubit_internal {
+                        struct __crubit_vtable_Shape final {
+                            double (*area)(const void*);
+                            void (*scale)(void*, double);
+                        };
+                        template <typename T>
+                        inline constexpr __crubit_vtable_Shape __crubit_vtable_for_Shape = {
+                            +[](const void* __self) -> double {
+                                return static_cast<const T*>(__self)->area();
+                            },
+                            +[](void* __self, double factor) -> void {
+                                return static_cast<T*>(__self)->scale(factor);
+                            }
+                        };
+                    }
+                    ...
+                    template <typename T> requires ::rust_out::Shape<T>
+                    double scaled_area(T& shape, double factor);
+                }
+            );
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" double __crubit_thunk_rust_uout_pscaled_uarea(
+                            void*,
+                            const ::rust_out::__crubit_internal::__crubit_vtable_Shape*,
+                            double);
+                    }
+                    template <typename T> requires ::rust_out::Shape<T>
+                    inline double scaled_area(T& shape, double factor) {
+                        return __crubit_internal::__crubit_thunk_rust_uout_pscaled_uarea(
+                            &shape,
+                            &::rust_out::__crubit_internal::__crubit_vtable_for_Shape<T>,
+                            factor);
+                    }
+                }
+            );
+            assert_rs_matches!(
+                bindings.rs_body,
+                quote! {
+                    #[repr(C)]
+                    pub struct __crubit_vtable_Shape {
+                        area: unsafe extern "C" fn(*const ::core::ffi::c_void) -> f64,
+                        scale: unsafe extern "C" fn(*mut ::core::ffi::c_void, f64) -> ()
+                    }
+                    pub struct __crubit_bridge_Shape<'a> {
+                        obj: *mut ::core::ffi::c_void,
+                        vtable: &'a __crubit_vtable_Shape,
+                        _marker: ::core::marker::PhantomData<&'a mut ()>,
+                    }
+                    impl<'a> ::rust_out::Shape for __crubit_bridge_Shape<'a> {
+                        fn area(&self) -> f64 {
+                            unsafe { (self.vtable.area)(self.obj) }
+                        }
+                        fn scale(&mut self, factor: f64) -> () {
+                            unsafe { (self.vtable.scale)(self.obj, factor) }
+                        }
+                    }
+                }
+            );
+            assert_rs_matches!(
+                bindings.rs_body,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn __crubit_thunk_rust_uout_pscaled_uarea(
+                        __param_0: *mut ::core::ffi::c_void,
+                        __param_0_vtable: &'static crate::__crubit_vtable_Shape,
+                        __param_1: f64
+                    ) -> f64 {
+                        ::rust_out::scaled_area(
+                            crate::__crubit_bridge_Shape {
+                                obj: __param_0,
+                                vtable: __param_0_vtable,
+                                _marker: ::core::marker::PhantomData,
+                            },
+                            __param_1
+                        )
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_impl_trait_param_requires_experimental_features() {
+        let test_src = r#"
+                pub trait Shape {
+                    fn area(&self) -> f64;
+                }
+
+                pub fn area(shape: impl Shape) -> f64 {
+                    shape.area()
+                }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_not_matches!(bindings.h_body, quote! { concept });
+            let expected_comment_txt = "Error generating bindings for `area` \
+                 defined at <crubit_unittests.rs>;l=6: \
+                 Generic functions are not supported yet (b/259749023)";
+            assert_cc_matches!(bindings.h_body, quote! { __COMMENT__ #expected_comment_txt });
+        });
+    }
+
+    #[test]
+    fn test_format_item_generic_fn_with_explicit_type_param() {
+        let test_src = r#"
+                pub trait Counter {
+                    fn increment(&mut self);
+                }
+
+                pub fn increment_twice<C: Counter>(counter: C) {
+                    let mut counter = counter;
+                    counter.increment();
+                    counter.increment();
+                }
+            "#;
+        test_format_item_with_experimental_features(test_src, "increment_twice", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_eq!(1, main_api.prereqs.defs.len());
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    template <typename T> requires ::rust_out::Counter<T>
+                    void increment_twice(T& counter);
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_generic_fn_with_unsupported_bounds() {
+        let test_src = r#"
+                pub trait Shape {
+                    fn area(&self) -> f64;
+                }
+
+                pub fn no_bounds<T>(_: T) {}
+                pub fn two_bounds<T: Shape + Clone>(_: T) {}
+                pub fn two_type_params<T: Shape, U: Shape>(_: T, _: U) {}
+                pub fn foreign_trait(_: impl Clone) {}
+                pub fn returns_param<T: Shape>(t: T) -> T { t }
+            "#;
+        let expected_errors = [
+            (
+                "no_bounds",
+                "The type parameter of a generic function has to be bounded by a single trait",
+            ),
+            (
+                "two_bounds",
+                "The type parameter of a generic function has to be bounded by a single trait",
+            ),
+            (
+                "two_type_params",
+                "Only generic functions with a single type parameter (and no lifetime or const \
+                 parameters) are supported (b/259749023)",
+            ),
+            ("foreign_trait", "Bounds on traits from other crates are not supported yet: `Clone`"),
+            ("returns_param", "Unsupported return type: `T`"),
+        ];
+        for (name, expected_error) in expected_errors {
+            test_format_item_with_experimental_features(test_src, name, |result| {
+                assert_eq!(result.unwrap_err(), expected_error, "Unexpected error for `{name}`");
+            });
+        }
+    }
+
+    #[test]
+    fn test_format_item_trait_unsupported_for_generic_fns() {
+        let test_src = r#"
+                pub trait WithAssocType {
+                    type Output;
+                }
+                pub trait WithStaticMethod {
+                    fn create() -> i32;
+                }
+                pub trait WithRefParam {
+                    fn get(&self, x: &i32) -> i32;
+                }
+                pub trait WithSupertrait: Clone {}
+            "#;
+        let expected_errors = [
+            ("WithAssocType", "Associated types and constants are not supported yet: `Output`"),
+            (
+                "WithStaticMethod",
+                "Methods without a `self` parameter are not supported yet: `create`",
+            ),
+            (
+                "WithRefParam",
+                "Unsupported type in the signature of `get`: `&i32` \
+                 (only C-ABI-compatible types without lifetimes are supported)",
+            ),
+            ("WithSupertrait", "Traits with supertraits are not supported yet"),
+        ];
+        for (name, expected_error) in expected_errors {
+            test_format_item_with_experimental_features(test_src, name, |result| {
+                assert_eq!(result.unwrap_err(), expected_error, "Unexpected error for `{name}`");
+            });
+        }
+    }
+
+    #[test]
+    fn test_snake_case_to_camel_case() {
+        assert_eq!("GetValue", snake_case_to_camel_case("get_value"));
+        assert_eq!("Foo", snake_case_to_camel_case("foo"));
+        assert_eq!("FooBar", snake_case_to_camel_case("foo__bar_"));
+        assert_eq!("_PrivateFn", snake_case_to_camel_case("_private_fn"));
+        assert_eq!("GetX2", snake_case_to_camel_case("get_x2"));
     }
 
     /// The `test_generated_bindings_struct` test covers only a single example
@@ -3373,6 +6803,129 @@ pub mod tests {
         });
     }
 
+    #[test]
+    fn test_generated_bindings_module_cpp_name() {
+        let test_src = r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+
+                #[__crubit::annotate(cpp_name = "cast")]
+                pub mod reinterpret_cast {
+                    pub mod inner {
+                        pub fn some_func() {}
+                    }
+                }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace rust_out {
+                        namespace cast::inner {
+                            ...
+                            void some_func();
+                            ...
+                        }  // namespace cast::inner
+                    }  // namespace rust_out
+                }
+            );
+            assert_cc_not_matches!(bindings.h_body, quote! { reinterpret_cast });
+            // The Rust thunk still refers to the function through its Rust path.
+            assert_rs_matches!(
+                bindings.rs_body,
+                quote! {
+                    #[no_mangle]
+                    extern "C"
+                    fn ...() -> () {
+                        ::rust_out::reinterpret_cast::inner::some_func()
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_module_with_path_attribute() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("some_file_name.rs");
+        std::fs::write(&path, "pub fn some_func() {}").unwrap();
+        let test_src = format!(
+            r#"
+                #[path = "{}"]
+                pub mod some_module;
+            "#,
+            path.display()
+        );
+        test_generated_bindings(&test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace rust_out {
+                        namespace some_module {
+                            ...
+                            void some_func();
+                            ...
+                        }  // namespace some_module
+                    }  // namespace rust_out
+                }
+            );
+            assert_rs_matches!(
+                bindings.rs_body,
+                quote! {
+                    #[no_mangle]
+                    extern "C"
+                    fn ...() -> () {
+                        ::rust_out::some_module::some_func()
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_generated_bindings_module_generated_by_macro() {
+        let test_src = r#"
+                macro_rules! define_module {
+                    ($name:ident) => {
+                        pub mod $name {
+                            pub fn some_func() {}
+                        }
+                    };
+                }
+
+                pub mod outer {
+                    define_module!(generated);
+                }
+            "#;
+        test_generated_bindings(test_src, |bindings| {
+            let bindings = bindings.unwrap();
+            assert_cc_matches!(
+                bindings.h_body,
+                quote! {
+                    namespace rust_out {
+                        namespace outer::generated {
+                            ...
+                            void some_func();
+                            ...
+                        }  // namespace outer::generated
+                    }  // namespace rust_out
+                }
+            );
+            assert_rs_matches!(
+                bindings.rs_body,
+                quote! {
+                    #[no_mangle]
+                    extern "C"
+                    fn ...() -> () {
+                        ::rust_out::outer::generated::some_func()
+                    }
+                }
+            );
+        });
+    }
+
     /// `test_generated_bindings_non_pub_items` verifies that non-public items
     /// are not present/propagated into the generated bindings.
     #[test]
@@ -4059,94 +7612,339 @@ pub mod tests {
             ]
             .join("\n");
             assert_cc_matches!(
-                main_api.tokens,
+                main_api.tokens,
+                quote! {
+                    __COMMENT__ #doc_comments
+                    extern "C" void fn_with_doc_comment_with_unmangled_name();
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_fn_with_inner_doc_comment_with_unmangled_name() {
+        let test_src = r#"
+            /// Outer doc comment.
+            #[no_mangle]
+            pub extern "C" fn fn_with_inner_doc_comment_with_unmangled_name() {
+                //! Inner doc comment.
+            }
+          "#;
+        test_format_item(test_src, "fn_with_inner_doc_comment_with_unmangled_name", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert!(main_api.prereqs.is_empty());
+            let doc_comments = [
+                " Outer doc comment.",
+                " Inner doc comment.",
+                "Generated from: <crubit_unittests.rs>;l=4",
+            ]
+            .join("\n\n");
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    __COMMENT__ #doc_comments
+                    extern "C" void fn_with_inner_doc_comment_with_unmangled_name();
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_fn_with_doc_comment_with_mangled_name() {
+        let test_src = r#"
+                /// Doc comment of a function with mangled name.
+                pub extern "C" fn fn_with_doc_comment_with_mangled_name() {}
+            "#;
+        test_format_item(test_src, "fn_with_doc_comment_with_mangled_name", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert!(main_api.prereqs.is_empty());
+            let comment = " Doc comment of a function with mangled name.\n\n\
+                           Generated from: <crubit_unittests.rs>;l=3";
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    __COMMENT__ #comment
+                    void fn_with_doc_comment_with_mangled_name();
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_unsupported_fn_name_is_reserved_cpp_keyword() {
+        let test_src = r#"
+                #[no_mangle]
+                pub extern "C" fn reinterpret_cast() -> () {}
+            "#;
+        test_format_item(test_src, "reinterpret_cast", |result| {
+            let err = result.unwrap_err();
+            assert_eq!(
+                err,
+                "Error formatting function name: \
+                       `reinterpret_cast` is a C++ reserved keyword \
+                       and can't be used as a C++ identifier"
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_unsupported_fn_ret_type() {
+        let test_src = r#"
+                pub fn foo() -> (i32, i32) { (123, 456) }
+            "#;
+        test_format_item(test_src, "foo", |result| {
+            let err = result.unwrap_err();
+            assert_eq!(
+                err,
+                "Error formatting function return type: \
+                       Tuples are not supported yet: (i32, i32) (b/254099023)"
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_fn_returning_hash_map_ref() {
+        let test_src = r#"
+                use std::collections::HashMap;
+                use std::sync::OnceLock;
+
+                pub fn lookup_table() -> &'static HashMap<i32, f32> {
+                    static TABLE: OnceLock<HashMap<i32, f32>> = OnceLock::new();
+                    TABLE.get_or_init(|| HashMap::from([(1, 1.5)]))
+                }
+            "#;
+        test_format_item(test_src, "lookup_table", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                format_cc_includes(&main_api.prereqs.includes),
+                quote! { include <crubit/support/for/tests/rs_std/map_view.h> }
+            );
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    rs_std::MapView<std::int32_t, float> lookup_table();
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" const void* ...();
+                    }
+                    namespace __crubit_internal {
+                        extern "C" bool ...(const void*, const std::int32_t*);
+                        extern "C" const float* ...(const void*, const std::int32_t*);
+                        extern "C" void ...(
+                            const void*, void*,
+                            void (*)(void*, const std::int32_t*, const float*));
+                        inline constexpr rs_std::MapView<std::int32_t, float>::VTable ... = {
+                            &..., &..., &...
+                        };
+                    }
+                    inline rs_std::MapView<std::int32_t, float> lookup_table() {
+                        return rs_std::MapView<std::int32_t, float>(
+                            __crubit_internal::...(), &__crubit_internal::...);
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...() -> &'static ::std::collections::HashMap<i32, f32> {
+                        ::rust_out::lookup_table()
+                    }
+                    #[no_mangle]
+                    extern "C" fn ...(
+                        __map: &::std::collections::HashMap<i32, f32>,
+                        __key: &i32
+                    ) -> bool {
+                        __map.contains_key(__key)
+                    }
+                    #[no_mangle]
+                    extern "C" fn ...<'__map>(
+                        __map: &'__map ::std::collections::HashMap<i32, f32>,
+                        __key: &i32
+                    ) -> ::core::option::Option<&'__map f32> {
+                        __map.get(__key)
+                    }
+                    #[no_mangle]
+                    extern "C" fn ...(
+                        __map: &::std::collections::HashMap<i32, f32>,
+                        __callback: *mut ::core::ffi::c_void,
+                        __visit: unsafe extern "C" fn(*mut ::core::ffi::c_void, &i32, &f32)
+                    ) {
+                        for (__key, __value) in __map {
+                            unsafe { __visit(__callback, __key, __value) }
+                        }
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_method_returning_btree_map_ref() {
+        let test_src = r#"
+                use std::collections::BTreeMap;
+
+                #[derive(Clone, Copy, Default)]
+                pub struct Point {
+                    pub x: i32,
+                    pub y: i32,
+                }
+
+                #[derive(Default)]
+                pub struct Registry {
+                    points: BTreeMap<u64, Point>,
+                }
+
+                impl Registry {
+                    pub fn points(&self) -> &BTreeMap<u64, Point> {
+                        &self.points
+                    }
+                }
+            "#;
+        test_format_item(test_src, "Registry", |result| {
+            let result = result.unwrap().unwrap();
+            assert_cc_matches!(
+                result.main_api.tokens,
                 quote! {
-                    __COMMENT__ #doc_comments
-                    extern "C" void fn_with_doc_comment_with_unmangled_name();
+                    ...
+                    rs_std::MapView<std::uint64_t, ::rust_out::Point> points() const ...;
+                    ...
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    inline rs_std::MapView<std::uint64_t, ::rust_out::Point> Registry::points()
+                    const ... {
+                        return rs_std::MapView<std::uint64_t, ::rust_out::Point>(
+                            __crubit_internal::...(*this), &__crubit_internal::...);
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...(
+                        __map: &::std::collections::BTreeMap<u64, ::rust_out::Point>,
+                        __key: &u64
+                    ) -> bool {
+                        __map.contains_key(__key)
+                    }
                 }
             );
         });
     }
 
     #[test]
-    fn test_format_item_fn_with_inner_doc_comment_with_unmangled_name() {
+    fn test_format_item_fn_returning_hash_map_ref_with_unhashable_key() {
         let test_src = r#"
-            /// Outer doc comment.
-            #[no_mangle]
-            pub extern "C" fn fn_with_inner_doc_comment_with_unmangled_name() {
-                //! Inner doc comment.
-            }
-          "#;
-        test_format_item(test_src, "fn_with_inner_doc_comment_with_unmangled_name", |result| {
-            let result = result.unwrap().unwrap();
-            let main_api = &result.main_api;
-            assert!(main_api.prereqs.is_empty());
-            let doc_comments = [
-                " Outer doc comment.",
-                " Inner doc comment.",
-                "Generated from: <crubit_unittests.rs>;l=4",
-            ]
-            .join("\n\n");
-            assert_cc_matches!(
-                main_api.tokens,
-                quote! {
-                    __COMMENT__ #doc_comments
-                    extern "C" void fn_with_inner_doc_comment_with_unmangled_name();
+                use std::collections::HashMap;
+
+                pub struct Key(pub i32);
+
+                pub fn get_map(map: &HashMap<Key, i32>) -> &HashMap<Key, i32> {
+                    map
                 }
+            "#;
+        test_format_item(test_src, "get_map", |result| {
+            let err = result.unwrap_err();
+            assert_eq!(
+                err,
+                "Error formatting function return type: \
+                 The map key type `Key` doesn't implement the `Hash` trait"
             );
         });
     }
 
     #[test]
-    fn test_format_item_fn_with_doc_comment_with_mangled_name() {
+    fn test_format_item_fn_returning_static_str() {
         let test_src = r#"
-                /// Doc comment of a function with mangled name.
-                pub extern "C" fn fn_with_doc_comment_with_mangled_name() {}
+                pub fn greeting() -> &'static str {
+                    "Hello, world!"
+                }
             "#;
-        test_format_item(test_src, "fn_with_doc_comment_with_mangled_name", |result| {
+        test_format_item(test_src, "greeting", |result| {
             let result = result.unwrap().unwrap();
             let main_api = &result.main_api;
-            assert!(main_api.prereqs.is_empty());
-            let comment = " Doc comment of a function with mangled name.\n\n\
-                           Generated from: <crubit_unittests.rs>;l=3";
+            assert_cc_matches!(
+                format_cc_includes(&main_api.prereqs.includes),
+                quote! { include <string_view> }
+            );
             assert_cc_matches!(
                 main_api.tokens,
                 quote! {
-                    __COMMENT__ #comment
-                    void fn_with_doc_comment_with_mangled_name();
+                    std::string_view [[clang::annotate_type("lifetime", "static")]] greeting();
                 }
             );
-        });
-    }
-
-    #[test]
-    fn test_format_item_unsupported_fn_name_is_reserved_cpp_keyword() {
-        let test_src = r#"
-                #[no_mangle]
-                pub extern "C" fn reinterpret_cast() -> () {}
-            "#;
-        test_format_item(test_src, "reinterpret_cast", |result| {
-            let err = result.unwrap_err();
-            assert_eq!(
-                err,
-                "Error formatting function name: \
-                       `reinterpret_cast` is a C++ reserved keyword \
-                       and can't be used as a C++ identifier"
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" const char* ...(std::size_t* __ret_size);
+                    }
+                    inline std::string_view [[clang::annotate_type("lifetime", "static")]]
+                    greeting() {
+                        std::size_t __ret_size;
+                        const char* __ret_data = __crubit_internal::...(&__ret_size);
+                        return std::string_view(__ret_data, __ret_size);
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...(__ret_size: &mut usize) -> *const u8 {
+                        let __ret: &'static str = ::rust_out::greeting();
+                        *__ret_size = __ret.len();
+                        __ret.as_ptr()
+                    }
+                }
             );
         });
     }
 
     #[test]
-    fn test_format_item_unsupported_fn_ret_type() {
+    fn test_format_item_method_returning_static_str() {
         let test_src = r#"
-                pub fn foo() -> (i32, i32) { (123, 456) }
+                pub struct Color {
+                    pub index: u8,
+                }
+
+                impl Color {
+                    pub fn name(&self) -> &'static str {
+                        match self.index {
+                            0 => "red",
+                            1 => "green",
+                            _ => "blue",
+                        }
+                    }
+                }
             "#;
-        test_format_item(test_src, "foo", |result| {
-            let err = result.unwrap_err();
-            assert_eq!(
-                err,
-                "Error formatting function return type: \
-                       Tuples are not supported yet: (i32, i32) (b/254099023)"
+        test_format_item(test_src, "Color", |result| {
+            let result = result.unwrap().unwrap();
+            assert_cc_matches!(
+                result.main_api.tokens,
+                quote! {
+                    ...
+                    std::string_view [[clang::annotate_type("lifetime", "static")]] name() const ...;
+                    ...
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    const char* __ret_data = __crubit_internal::...(*this, &__ret_size);
+                    return std::string_view(__ret_data, __ret_size);
+                }
             );
         });
     }
@@ -4292,10 +8090,9 @@ pub mod tests {
     /// Test of lifetime-generic function with a `where` clause.
     ///
     /// The `where` constraint below is a bit silly (why not just use `'static`
-    /// directly), but it seems prudent to test and confirm that we disable
-    /// generation of bindings for generic functions with `where` clauses
-    /// (because it is unclear if such constraints can be replicated
-    /// in C++).
+    /// directly), but it seems prudent to test and confirm that the constraint
+    /// is replicated in the thunk (it can't be replicated in C++, where the
+    /// lifetime annotations don't support outlives bounds).
     #[test]
     fn test_format_item_lifetime_generic_fn_with_where_clause() {
         let test_src = r#"
@@ -4304,8 +8101,76 @@ pub mod tests {
                 }
             "#;
         test_format_item(test_src, "foo", |result| {
-            let err = result.unwrap_err();
-            assert_eq!(err, "Generic functions are not supported yet (b/259749023)");
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    void foo(std::int32_t const& [[clang::annotate_type("lifetime", "a")]] arg);
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...<'a>(arg: &'a i32) -> () where 'a: 'static {
+                        ::rust_out::foo(arg)
+                    }
+                }
+            );
+        });
+    }
+
+    /// Test of lifetime-generic function with outlives bounds between its
+    /// lifetime parameters.  Such lifetimes are early-bound (unlike the
+    /// lifetimes in `test_format_item_lifetime_generic_fn_with_various_lifetimes`).
+    #[test]
+    fn test_format_item_lifetime_generic_fn_with_outlives_bounds() {
+        let test_src = r#"
+                pub fn foo<'a: 'b, 'b>(arg1: &'a i32, arg2: &'b i32) -> &'b i32 {
+                    if *arg1 > *arg2 { arg1 } else { arg2 }
+                }
+            "#;
+        test_format_item(test_src, "foo", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    std::int32_t const& [[clang::annotate_type("lifetime", "b")]]
+                    foo(
+                        std::int32_t const& [[clang::annotate_type("lifetime", "a")]] arg1,
+                        std::int32_t const& [[clang::annotate_type("lifetime", "b")]] arg2);
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                    extern "C"
+                    std::int32_t const& [[clang::annotate_type("lifetime", "b")]] ...(
+                        std::int32_t const& [[clang::annotate_type("lifetime", "a")]],
+                        std::int32_t const& [[clang::annotate_type("lifetime", "b")]]);
+                    }
+                    inline
+                    std::int32_t const& [[clang::annotate_type("lifetime", "b")]]
+                    foo(
+                        std::int32_t const& [[clang::annotate_type("lifetime", "a")]] arg1,
+                        std::int32_t const& [[clang::annotate_type("lifetime", "b")]] arg2) {
+                      return __crubit_internal::...(arg1, arg2);
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...<'a, 'b>(arg1: &'a i32, arg2: &'b i32) -> &'b i32
+                    where 'a: 'b {
+                        ::rust_out::foo(arg1, arg2)
+                    }
+                }
+            );
         });
     }
 
@@ -4483,73 +8348,152 @@ pub mod tests {
     }
 
     #[test]
-    fn test_format_item_fn_rust_abi_with_param_taking_struct_by_value() {
+    fn test_format_item_fn_rust_abi_with_param_taking_struct_by_value() {
+        let test_src = r#"
+                pub struct S(i32);
+                pub fn into_i32(s: S) -> i32 { s.0 }
+            "#;
+        test_format_item(test_src, "into_i32", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    std::int32_t into_i32(::rust_out::S s);
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" std::int32_t ...(::rust_out::S*);
+                    }
+                    ...
+                    inline std::int32_t into_i32(::rust_out::S s) {
+                        return __crubit_internal::...(&s);
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C"
+                    fn ...(s: &mut ::core::mem::MaybeUninit<::rust_out::S>) -> i32 {
+                        ::rust_out::into_i32(unsafe { s.assume_init_read() })
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_fn_rust_abi_returning_struct_by_value() {
+        let test_src = r#"
+                #![allow(dead_code)]
+
+                pub struct S(i32);
+                pub fn create(i: i32) -> S { S(i) }
+            "#;
+        test_format_item(test_src, "create", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    ::rust_out::S create(std::int32_t i);
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" void ...(std::int32_t, ::rust_out::S* __ret_ptr);
+                    }
+                    ...
+                    inline ::rust_out::S create(std::int32_t i) {
+                        crubit::ReturnValueSlot<::rust_out::S> __ret_slot;
+                        __crubit_internal::...(i, __ret_slot.Get());
+                        return std::move(__ret_slot).AssumeInitAndTakeValue();
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C"
+                    fn ...(
+                        i: i32,
+                        __ret_slot: &mut ::core::mem::MaybeUninit<::rust_out::S>
+                    ) -> () {
+                        __ret_slot.write(::rust_out::create(i));
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_fn_rust_abi_with_repr_c_struct_by_value() {
         let test_src = r#"
-                pub struct S(i32);
-                pub fn into_i32(s: S) -> i32 { s.0 }
+                #[derive(Clone, Copy)]
+                #[repr(C)]
+                pub struct Point {
+                    pub x: f32,
+                    pub y: f32,
+                }
+                pub fn flip(p: Point) -> Point { Point { x: p.y, y: p.x } }
             "#;
-        test_format_item(test_src, "into_i32", |result| {
+        test_format_item(test_src, "flip", |result| {
             let result = result.unwrap().unwrap();
-            let main_api = &result.main_api;
-            assert_cc_matches!(
-                main_api.tokens,
-                quote! {
-                    std::int32_t into_i32(::rust_out::S s);
-                }
-            );
             assert_cc_matches!(
                 result.cc_details.tokens,
                 quote! {
                     namespace __crubit_internal {
-                        extern "C" std::int32_t ...(::rust_out::S*);
+                        extern "C" ::rust_out::Point ...(::rust_out::Point);
                     }
                     ...
-                    inline std::int32_t into_i32(::rust_out::S s) {
-                        return __crubit_internal::...(&s);
+                    inline ::rust_out::Point flip(::rust_out::Point p) {
+                        return __crubit_internal::...(p);
                     }
                 }
             );
+            assert_cc_not_matches!(result.cc_details.tokens, quote! { ReturnValueSlot });
             assert_rs_matches!(
                 result.rs_details,
                 quote! {
                     #[no_mangle]
                     extern "C"
-                    fn ...(s: &mut ::core::mem::MaybeUninit<::rust_out::S>) -> i32 {
-                        ::rust_out::into_i32(unsafe { s.assume_init_read() })
+                    fn ...(p: ::rust_out::Point) -> ::rust_out::Point {
+                        ::rust_out::flip(p)
                     }
                 }
             );
         });
     }
 
+    /// `PhantomData` fields don't prevent passing a `#[repr(C)]` struct by
+    /// value.
     #[test]
-    fn test_format_item_fn_rust_abi_returning_struct_by_value() {
+    fn test_format_item_fn_rust_abi_with_repr_c_struct_with_phantom_data_by_value() {
         let test_src = r#"
-                #![allow(dead_code)]
-
-                pub struct S(i32);
-                pub fn create(i: i32) -> S { S(i) }
+                use std::marker::PhantomData;
+                #[derive(Clone, Copy)]
+                #[repr(C)]
+                pub struct Meters {
+                    pub value: f32,
+                    pub unit: PhantomData<*const u8>,
+                }
+                pub fn double(m: Meters) -> Meters { Meters { value: m.value * 2.0, unit: m.unit } }
             "#;
-        test_format_item(test_src, "create", |result| {
+        test_format_item(test_src, "double", |result| {
             let result = result.unwrap().unwrap();
-            let main_api = &result.main_api;
-            assert_cc_matches!(
-                main_api.tokens,
-                quote! {
-                    ::rust_out::S create(std::int32_t i);
-                }
-            );
             assert_cc_matches!(
                 result.cc_details.tokens,
                 quote! {
                     namespace __crubit_internal {
-                        extern "C" void ...(std::int32_t, ::rust_out::S* __ret_ptr);
-                    }
-                    ...
-                    inline ::rust_out::S create(std::int32_t i) {
-                        crubit::ReturnValueSlot<::rust_out::S> __ret_slot;
-                        __crubit_internal::...(i, __ret_slot.Get());
-                        return std::move(__ret_slot).AssumeInitAndTakeValue();
+                        extern "C" ::rust_out::Meters ...(::rust_out::Meters);
                     }
                 }
             );
@@ -4558,17 +8502,74 @@ pub mod tests {
                 quote! {
                     #[no_mangle]
                     extern "C"
-                    fn ...(
-                        i: i32,
-                        __ret_slot: &mut ::core::mem::MaybeUninit<::rust_out::S>
-                    ) -> () {
-                        __ret_slot.write(::rust_out::create(i));
+                    fn ...(m: ::rust_out::Meters) -> ::rust_out::Meters {
+                        ::rust_out::double(m)
                     }
                 }
             );
         });
     }
 
+    /// Only `Copy`, `#[repr(C)]` structs with scalar fields can be passed by
+    /// value - other structs still go through `MaybeUninit` pointers.
+    #[test]
+    fn test_format_item_fn_rust_abi_with_non_abi_compatible_struct_by_value() {
+        let test_srcs = [
+            // Not `Copy`.
+            r#"
+                #[repr(C)]
+                pub struct S { pub x: i32 }
+                pub fn into_i32(s: S) -> i32 { s.x }
+            "#,
+            // Not `#[repr(C)]`.
+            r#"
+                #[derive(Clone, Copy)]
+                pub struct S { pub x: i32 }
+                pub fn into_i32(s: S) -> i32 { s.x }
+            "#,
+            // Extra `#[repr(align(...))]`.
+            r#"
+                #[derive(Clone, Copy)]
+                #[repr(C, align(16))]
+                pub struct S { pub x: i32 }
+                pub fn into_i32(s: S) -> i32 { s.x }
+            "#,
+            // Non-scalar field.
+            r#"
+                #[derive(Clone, Copy)]
+                #[repr(C)]
+                pub struct S { pub x: [i32; 2] }
+                pub fn into_i32(s: S) -> i32 { s.x[0] }
+            "#,
+            // Only `PhantomData` fields (i.e. a ZST).
+            r#"
+                #[derive(Clone, Copy)]
+                #[repr(C)]
+                pub struct S { pub marker: std::marker::PhantomData<i32> }
+                pub fn into_i32(_s: S) -> i32 { 0 }
+            "#,
+        ];
+        for test_src in test_srcs {
+            test_format_item(test_src, "into_i32", |result| {
+                let result = result.unwrap().unwrap();
+                assert_cc_matches!(
+                    result.cc_details.tokens,
+                    quote! {
+                        namespace __crubit_internal {
+                            extern "C" std::int32_t ...(::rust_out::S*);
+                        }
+                    }
+                );
+                assert_rs_matches!(
+                    result.rs_details,
+                    quote! {
+                        fn ...(s: &mut ::core::mem::MaybeUninit<::rust_out::S>) -> i32 { ... }
+                    }
+                );
+            });
+        }
+    }
+
     /// `test_format_item_fn_rust_abi` tests a function call that is not a
     /// C-ABI, and is not the default Rust ABI.  It can't use `"stdcall"`,
     /// because it is not supported on the targets where Crubit's tests run.
@@ -4723,6 +8724,105 @@ pub mod tests {
         });
     }
 
+    #[test]
+    fn test_format_item_fn_with_array_param_and_return_type() {
+        let test_src = r#"
+                pub fn reverse(arr: [u8; 4]) -> [u8; 4] {
+                    [arr[3], arr[2], arr[1], arr[0]]
+                }
+            "#;
+        test_format_item(test_src, "reverse", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    std::array<std::uint8_t, 4> reverse(std::array<std::uint8_t, 4> arr);
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" void ...(
+                            std::array<std::uint8_t, 4>* arr,
+                            std::array<std::uint8_t, 4>* __ret_ptr);
+                    }
+                    inline std::array<std::uint8_t, 4> reverse(std::array<std::uint8_t, 4> arr) {
+                        crubit::ReturnValueSlot<std::array<std::uint8_t, 4>> __ret_slot;
+                        __crubit_internal::...(&arr, __ret_slot.Get());
+                        return std::move(__ret_slot).AssumeInitAndTakeValue();
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...(
+                        arr: &mut ::core::mem::MaybeUninit<[u8; 4]>,
+                        __ret_slot: &mut ::core::mem::MaybeUninit<[u8; 4]>
+                    ) -> () {
+                        __ret_slot.write(::rust_out::reverse(unsafe { arr.assume_init_read() }));
+                    }
+                }
+            );
+        });
+    }
+
+    /// `&mut T` out-parameters are passed as a C++ reference (i.e. as a pointer
+    /// at the ABI level), even if `T` can't be passed by value over the FFI
+    /// boundary.  The Rust thunk just forwards the reference, so the referent
+    /// doesn't need to be trivially movable or trivially destructible.
+    #[test]
+    fn test_format_item_fn_with_mut_ref_to_struct_param() {
+        let test_src = r#"
+                pub struct S {
+                    pub name: String,
+                }
+
+                pub fn set_name(s: &mut S, len: usize) {
+                    s.name = "x".repeat(len);
+                }
+            "#;
+        test_format_item(test_src, "set_name", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    void set_name(
+                        ::rust_out::S& [[clang::annotate_type("lifetime", "__anon1")]] s,
+                        std::uintptr_t len);
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" void ...(
+                            ::rust_out::S& [[clang::annotate_type("lifetime", "__anon1")]],
+                            std::uintptr_t);
+                    }
+                    inline void set_name(
+                            ::rust_out::S& [[clang::annotate_type("lifetime", "__anon1")]] s,
+                            std::uintptr_t len) {
+                        return __crubit_internal::...(s, len);
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...<'__anon1>(s: &'__anon1 mut ::rust_out::S, len: usize) -> () {
+                        ::rust_out::set_name(s, len)
+                    }
+                }
+            );
+        });
+    }
+
     #[test]
     fn test_format_item_fn_with_destructuring_parameter_name() {
         let test_src = r#"
@@ -4952,6 +9052,50 @@ pub mod tests {
         });
     }
 
+    #[test]
+    fn test_format_item_struct_with_array_field() {
+        let test_src = r#"
+                #[repr(C)]
+                pub struct SomeStruct {
+                    pub len: u32,
+                    pub buf: [u8; 12],
+                }
+
+                const _: () = assert!(std::mem::size_of::<SomeStruct>() == 16);
+                const _: () = assert!(std::mem::align_of::<SomeStruct>() == 4);
+            "#;
+        test_format_item(test_src, "SomeStruct", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert!(main_api.prereqs.includes.contains(&CcInclude::array()));
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    ...
+                    struct CRUBIT_INTERNAL_RUST_TYPE(...) alignas(4) [[clang::trivial_abi]] SomeStruct final {
+                        ...
+                        public: union { ... std::uint32_t len; };
+                        public: union { ... std::array<std::uint8_t, 12> buf; };
+                        private:
+                            static void __crubit_field_offset_assertions();
+                    };
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    static_assert(sizeof(SomeStruct) == 16, ...);
+                    static_assert(alignof(SomeStruct) == 4, ...);
+                    ...
+                    inline void SomeStruct::__crubit_field_offset_assertions() {
+                      static_assert(0 == offsetof(SomeStruct, len));
+                      static_assert(4 == offsetof(SomeStruct, buf));
+                    }
+                }
+            );
+        });
+    }
+
     /// This test the scenario where Rust lays out field in a different order
     /// than the source order.
     #[test]
@@ -5116,45 +9260,147 @@ pub mod tests {
                     }
                 }
             );
-            assert_rs_matches!(
-                result.rs_details,
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    const _: () = assert!(::std::mem::size_of::<::rust_out::SomeStruct>() == 8);
+                    const _: () = assert!(::std::mem::align_of::<::rust_out::SomeStruct>() == 4);
+                    const _: () = assert!( ::core::mem::offset_of!(::rust_out::SomeStruct, f2) == 0);
+                    const _: () = assert!( ::core::mem::offset_of!(::rust_out::SomeStruct, f1) == 4);
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_static_method() {
+        let test_src = r#"
+                #![allow(dead_code)]
+
+                /// No-op `f32` placeholder is used, because ZSTs are not supported
+                /// (b/258259459).
+                pub struct Math(f32);
+
+                impl Math {
+                    pub fn add_i32(x: f32, y: f32) -> f32 {
+                        x + y
+                    }
+                }
+            "#;
+        test_format_item(test_src, "Math", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    ...
+                    struct ... Math final {
+                        ...
+                        public:
+                          ...
+                          static float add_i32(float x, float y);
+                        ...
+                    };
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" float ... (float, float);
+                    }
+                    inline float Math::add_i32(float x, float y) {
+                      return __crubit_internal::...(x, y);
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...(x: f32, y: f32) -> f32 {
+                        ::rust_out::Math::add_i32(x, y)
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_new_as_constructor() {
+        let test_src = r#"
+                pub struct Point {
+                    x: i32,
+                    y: i32,
+                }
+
+                impl Point {
+                    pub fn new(x: i32, y: i32) -> Self {
+                        Self { x, y }
+                    }
+                }
+            "#;
+        test_format_item(test_src, "Point", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    ...
+                    struct ... Point final {
+                        ...
+                        public:
+                          ...
+                          Point(std::int32_t x, std::int32_t y);
+                        ...
+                    };
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
                 quote! {
-                    const _: () = assert!(::std::mem::size_of::<::rust_out::SomeStruct>() == 8);
-                    const _: () = assert!(::std::mem::align_of::<::rust_out::SomeStruct>() == 4);
-                    const _: () = assert!( ::core::mem::offset_of!(::rust_out::SomeStruct, f2) == 0);
-                    const _: () = assert!( ::core::mem::offset_of!(::rust_out::SomeStruct, f1) == 4);
+                    namespace __crubit_internal {
+                        extern "C" void ... (std::int32_t, std::int32_t, ::rust_out::Point* __ret_ptr);
+                    }
+                    inline Point::Point(std::int32_t x, std::int32_t y) {
+                        __crubit_internal::...(x, y, this);
+                    }
                 }
             );
         });
     }
 
     #[test]
-    fn test_format_item_static_method() {
+    fn test_format_item_new_as_constructor_with_cpp_name() {
         let test_src = r#"
-                #![allow(dead_code)]
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
 
-                /// No-op `f32` placeholder is used, because ZSTs are not supported
-                /// (b/258259459).
-                pub struct Math(f32);
+                #[derive(Clone, Copy)]
+                #[repr(C)]
+                pub struct Meters(f64);
 
-                impl Math {
-                    pub fn add_i32(x: f32, y: f32) -> f32 {
-                        x + y
+                impl Meters {
+                    #[__crubit::annotate(cpp_name="Create")]
+                    pub fn new(value: f64) -> Self {
+                        Self(value)
                     }
                 }
             "#;
-        test_format_item(test_src, "Math", |result| {
+        test_format_item(test_src, "Meters", |result| {
             let result = result.unwrap().unwrap();
             let main_api = &result.main_api;
             assert_cc_matches!(
                 main_api.tokens,
                 quote! {
                     ...
-                    struct ... Math final {
+                    struct ... Meters final {
                         ...
                         public:
                           ...
-                          static float add_i32(float x, float y);
+                          static ... Create(double value);
+                          ...
+                          explicit Meters(double value);
                         ...
                     };
                 }
@@ -5162,22 +9408,46 @@ pub mod tests {
             assert_cc_matches!(
                 result.cc_details.tokens,
                 quote! {
-                    namespace __crubit_internal {
-                        extern "C" float ... (float, float);
-                    }
-                    inline float Math::add_i32(float x, float y) {
-                      return __crubit_internal::...(x, y);
+                    inline Meters::Meters(double value) {
+                        *this = __crubit_internal::...(value);
                     }
                 }
             );
-            assert_rs_matches!(
-                result.rs_details,
-                quote! {
-                    #[no_mangle]
-                    extern "C" fn ...(x: f32, y: f32) -> f32 {
-                        ::rust_out::Math::add_i32(x, y)
+        });
+    }
+
+    #[test]
+    fn test_format_item_new_without_params_is_not_a_constructor() {
+        let test_src = r#"
+                pub struct Point {
+                    x: i32,
+                }
+
+                impl Point {
+                    pub fn new() -> Self {
+                        Self { x: 0 }
                     }
                 }
+            "#;
+        test_format_item(test_src, "Point", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            let unsupported_msg = "Error generating bindings for `Point::new` \
+                                   defined at <crubit_unittests.rs>;l=7: \
+                                   Error formatting function name: \
+                                   `new` is a C++ reserved keyword \
+                                   and can't be used as a C++ identifier";
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    ...
+                    struct ... Point final {
+                        ...
+                        __COMMENT__ #unsupported_msg
+                        ...
+                    };
+                    ...
+                }
             );
         });
     }
@@ -5535,6 +9805,79 @@ pub mod tests {
         test_format_item_method_taking_self_by_mutable_ref(test_src);
     }
 
+    fn test_format_item_method_taking_self_by_smart_pointer(test_src: &str, smart_pointer: &str) {
+        let smart_pointer: TokenStream = smart_pointer.parse().unwrap();
+        test_format_item(test_src, "SomeStruct", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    ...
+                    struct ... SomeStruct final {
+                        ...
+                        float get_f32() &&;
+                        ...
+                    };
+                    ...
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                    extern "C" float ...(::rust_out::SomeStruct*);
+                    }
+                    inline float SomeStruct::get_f32() && {
+                      return __crubit_internal::...(this);
+                    }
+                },
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    ...
+                    #[no_mangle]
+                    extern "C" fn ...(__self: &mut ::core::mem::MaybeUninit<::rust_out::SomeStruct>) -> f32 {
+                        ::rust_out::SomeStruct::get_f32(
+                            #smart_pointer::new(unsafe { __self.assume_init_read() }))
+                    }
+                    ...
+                },
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_method_taking_self_by_box() {
+        let test_src = r#"
+                pub struct SomeStruct(pub f32);
+
+                impl SomeStruct {
+                    pub fn get_f32(self: Box<Self>) -> f32 {
+                        self.0
+                    }
+                }
+            "#;
+        test_format_item_method_taking_self_by_smart_pointer(test_src, "::std::boxed::Box");
+    }
+
+    #[test]
+    fn test_format_item_method_taking_self_by_rc() {
+        let test_src = r#"
+                use std::rc::Rc;
+
+                pub struct SomeStruct(pub f32);
+
+                impl SomeStruct {
+                    pub fn get_f32(self: Rc<Self>) -> f32 {
+                        self.0
+                    }
+                }
+            "#;
+        test_format_item_method_taking_self_by_smart_pointer(test_src, "::std::rc::Rc");
+    }
+
     #[test]
     fn test_format_item_method_taking_self_by_arc() {
         let test_src = r#"
@@ -5548,12 +9891,26 @@ pub mod tests {
                     }
                 }
             "#;
+        test_format_item_method_taking_self_by_smart_pointer(test_src, "::std::sync::Arc");
+    }
+
+    #[test]
+    fn test_format_item_method_taking_self_by_box_of_other_type() {
+        let test_src = r#"
+                pub struct SomeStruct(pub f32);
+
+                impl SomeStruct {
+                    pub fn get_f32(self: Box<Self>, other: Box<SomeStruct>) -> f32 {
+                        self.0 + other.0
+                    }
+                }
+            "#;
         test_format_item(test_src, "SomeStruct", |result| {
             let result = result.unwrap().unwrap();
             let main_api = &result.main_api;
             let unsupported_msg = "Error generating bindings for `SomeStruct::get_f32` \
-                                   defined at <crubit_unittests.rs>;l=7: \
-                                   Error handling parameter #0: \
+                                   defined at <crubit_unittests.rs>;l=5: \
+                                   Error handling parameter #1: \
                                    Generic types are not supported yet (b/259749095)";
             assert_cc_matches!(
                 main_api.tokens,
@@ -5626,9 +9983,164 @@ pub mod tests {
                     ...
                     struct ... Point final {
                         ...
-                        public:
-                          __COMMENT__ "Default::default"
-                          Point();
+                        public:
+                          __COMMENT__ "Default::default"
+                          Point();
+                        ...
+                    };
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" void ...(::rust_out::Point* __ret_ptr);
+                    }
+                    inline Point::Point() {
+                        ...(this);
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                   #[no_mangle]
+                   extern "C" fn ...(
+                       __ret_slot: &mut ::core::mem::MaybeUninit<::rust_out::Point>
+                   ) -> () {
+                       __ret_slot.write(<::rust_out::Point as ::core::default::Default>::default());
+                   }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_repr_c_struct_with_default_constructor() {
+        let test_src = r#"
+                #[derive(Clone, Copy, Default)]
+                #[repr(C)]
+                pub struct Point(i32, i32);
+            "#;
+        test_format_item(test_src, "Point", |result| {
+            let result = result.unwrap().unwrap();
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" ::rust_out::Point ...();
+                    }
+                    inline Point::Point() {
+                        *this = __crubit_internal::...();
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                   #[no_mangle]
+                   extern "C" fn ...() -> ::rust_out::Point {
+                       <::rust_out::Point as ::core::default::Default>::default()
+                   }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_struct_with_from_str_trait() {
+        let test_src = r#"
+                pub struct Point(i32, i32);
+
+                impl std::str::FromStr for Point {
+                    type Err = ();
+                    fn from_str(s: &str) -> Result<Self, ()> {
+                        let (x, y) = s.split_once(',').ok_or(())?;
+                        Ok(Point(x.parse().map_err(|_| ())?, y.parse().map_err(|_| ())?))
+                    }
+                }
+            "#;
+        test_format_item(test_src, "Point", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert!(main_api.prereqs.includes.contains(&CcInclude::optional()));
+            assert!(main_api.prereqs.includes.contains(&CcInclude::string_view()));
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    ...
+                    struct ... Point final {
+                        ...
+                        __COMMENT__ "FromStr::from_str"
+                        static std::optional<Point> Parse(std::string_view s);
+                        ...
+                    };
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" bool ...(const char*, std::size_t, Point* __ret_ptr);
+                    }
+                    inline std::optional<Point> Point::Parse(std::string_view s) {
+                        crubit::ReturnValueSlot<Point> __ret_slot;
+                        if (!__crubit_internal::...(s.data(), s.size(), __ret_slot.Get())) {
+                            return std::nullopt;
+                        }
+                        return std::move(__ret_slot).AssumeInitAndTakeValue();
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...(
+                        __s_ptr: *const u8,
+                        __s_len: usize,
+                        __ret_slot: &mut ::core::mem::MaybeUninit<::rust_out::Point>
+                    ) -> bool {
+                        let s: &[u8] = if __s_len == 0 {
+                            &[]
+                        } else {
+                            unsafe { ::core::slice::from_raw_parts(__s_ptr, __s_len) }
+                        };
+                        let Ok(s) = ::core::str::from_utf8(s) else {
+                            return false;
+                        };
+                        match <::rust_out::Point as ::core::str::FromStr>::from_str(s) {
+                            Ok(value) => { __ret_slot.write(value); true }
+                            Err(_) => false,
+                        }
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_struct_with_from_trait() {
+        let test_src = r#"
+                pub struct Point(i32, i32);
+
+                impl From<i32> for Point {
+                    fn from(i: i32) -> Self {
+                        Point(i, i)
+                    }
+                }
+            "#;
+        test_format_item(test_src, "Point", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    ...
+                    struct ... Point final {
+                        ...
+                        __COMMENT__ "From::from"
+                        static Point From(std::int32_t i);
                         ...
                     };
                 }
@@ -5637,24 +10149,62 @@ pub mod tests {
                 result.cc_details.tokens,
                 quote! {
                     namespace __crubit_internal {
-                        extern "C" void ...(::rust_out::Point* __ret_ptr);
+                        extern "C" void ...(std::int32_t, Point* __ret_ptr);
                     }
-                    inline Point::Point() {
-                        ...(this);
+                    inline Point Point::From(std::int32_t i) {
+                        crubit::ReturnValueSlot<Point> __ret_slot;
+                        __crubit_internal::...(i, __ret_slot.Get());
+                        return std::move(__ret_slot).AssumeInitAndTakeValue();
                     }
                 }
             );
             assert_rs_matches!(
                 result.rs_details,
                 quote! {
-                   #[no_mangle]
-                   extern "C" fn ...(
-                       __ret_slot: &mut ::core::mem::MaybeUninit<::rust_out::Point>
-                   ) -> () {
-                       __ret_slot.write(<::rust_out::Point as ::core::default::Default>::default());
-                   }
+                    #[no_mangle]
+                    extern "C" fn ...(
+                        i: i32,
+                        __ret_slot: &mut ::core::mem::MaybeUninit<::rust_out::Point>
+                    ) -> () {
+                        __ret_slot.write(<::rust_out::Point as ::core::convert::From<i32> >::from(i));
+                    }
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_struct_with_unsupported_trait_method() {
+        let test_src = r#"
+                pub struct Point(i32, i32);
+
+                impl TryFrom<(i32, i32)> for Point {
+                    type Error = ();
+                    fn try_from(xy: (i32, i32)) -> Result<Self, ()> {
+                        Ok(Point(xy.0, xy.1))
+                    }
+                }
+            "#;
+        test_format_item(test_src, "Point", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            let unsupported_msg = "Error generating bindings for \
+                                   `<Point as std::convert::TryFrom<(i32, i32)>>::try_from` \
+                                   defined at <crubit_unittests.rs>;l=6: \
+                                   Unsupported type of parameter `xy`: `(i32, i32)` \
+                                   (only `&str` and C-ABI-compatible types are supported)";
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    ...
+                    struct ... Point final {
+                        ...
+                        __COMMENT__ #unsupported_msg
+                        ...
+                    };
                 }
             );
+            assert_cc_not_matches!(main_api.tokens, quote! { TryFrom(...) });
         });
     }
 
@@ -6406,7 +10956,7 @@ pub mod tests {
     /// https://doc.rust-lang.org/reference/items/structs.html refers to this kind of struct as a
     /// "unit-like struct".
     #[test]
-    fn test_format_item_unsupported_struct_zero_sized_type_with_no_fields() {
+    fn test_format_item_struct_zero_sized_type_with_no_fields() {
         let test_src = r#"
                 pub struct ZeroSizedType1;
                 pub struct ZeroSizedType2();
@@ -6414,14 +10964,103 @@ pub mod tests {
             "#;
         for name in ["ZeroSizedType1", "ZeroSizedType2", "ZeroSizedType3"] {
             test_format_item(test_src, name, |result| {
-                let err = result.unwrap_err();
-                assert_eq!(err, "Zero-sized types (ZSTs) are not supported (b/258259459)");
+                let result = result.unwrap().unwrap();
+                let ident = format_ident!("{name}");
+                let zst_msg = format!(
+                    "`{name}` is a zero-sized type in Rust. It is represented by an empty C++ \
+                     struct, whose `sizeof` is 1 (rather than 0), and which holds no data."
+                );
+                assert_cc_matches!(
+                    result.main_api.tokens,
+                    quote! {
+                        __COMMENT__ #zst_msg
+                        struct CRUBIT_INTERNAL_RUST_TYPE(...) alignas(1) [[clang::trivial_abi]]
+                            #ident final {
+                            ...
+                        };
+                    }
+                );
+                assert_cc_matches!(
+                    result.cc_details.tokens,
+                    quote! {
+                        static_assert(sizeof(#ident) == 1, ...);
+                        static_assert(alignof(#ident) == 1, ...);
+                    }
+                );
+                assert_rs_matches!(
+                    result.rs_details,
+                    quote! {
+                        const _: () = assert!(::std::mem::size_of::<::rust_out::#ident>() == 0);
+                        const _: () = assert!(::std::mem::align_of::<::rust_out::#ident>() == 1);
+                    }
+                );
             });
         }
     }
 
+    /// C++ gives an empty struct the size of its alignment, so that `alignas` is
+    /// still respected.
+    #[test]
+    fn test_format_item_struct_zero_sized_type_with_alignment() {
+        let test_src = r#"
+                #[repr(align(8))]
+                pub struct AlignedZst;
+            "#;
+        test_format_item(test_src, "AlignedZst", |result| {
+            let result = result.unwrap().unwrap();
+            assert_cc_matches!(
+                result.main_api.tokens,
+                quote! { struct CRUBIT_INTERNAL_RUST_TYPE(...) alignas(8) [[clang::trivial_abi]] AlignedZst final }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    static_assert(sizeof(AlignedZst) == 8, ...);
+                    static_assert(alignof(AlignedZst) == 8, ...);
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! { const _: () = assert!(::std::mem::size_of::<::rust_out::AlignedZst>() == 0); }
+            );
+        });
+    }
+
+    /// ZSTs are passed to and returned from the thunks by pointer, so no data is
+    /// actually passed.
+    #[test]
+    fn test_format_item_fn_with_zero_sized_type_param_and_return_value() {
+        let test_src = r#"
+                pub struct Marker;
+                pub fn roundtrip(m: Marker) -> Marker { m }
+            "#;
+        test_format_item(test_src, "roundtrip", |result| {
+            let result = result.unwrap().unwrap();
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    namespace __crubit_internal {
+                        extern "C" void ...(::rust_out::Marker*, ::rust_out::Marker* __ret_ptr);
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    #[no_mangle]
+                    extern "C" fn ...(
+                        m: &mut ::core::mem::MaybeUninit<::rust_out::Marker>,
+                        __ret_slot: &mut ::core::mem::MaybeUninit<::rust_out::Marker>
+                    ) -> () {
+                        __ret_slot.write(::rust_out::roundtrip(unsafe { m.assume_init_read() }));
+                    }
+                }
+            );
+        });
+    }
+
     #[test]
-    fn test_format_item_unsupported_struct_with_only_zero_sized_type_fields() {
+    fn test_format_item_struct_with_only_zero_sized_type_fields() {
         let test_src = r#"
                 pub struct ZeroSizedType;
                 pub struct SomeStruct {
@@ -6430,13 +11069,30 @@ pub mod tests {
                 }
             "#;
         test_format_item(test_src, "SomeStruct", |result| {
-            let err = result.unwrap_err();
-            assert_eq!(err, "Zero-sized types (ZSTs) are not supported (b/258259459)",);
+            let result = result.unwrap().unwrap();
+            let zst1_msg = "Field `zst1` is zero-sized in Rust, and has no C++ representation";
+            let zst2_msg = "Field `zst2` is zero-sized in Rust, and has no C++ representation";
+            assert_cc_matches!(
+                result.main_api.tokens,
+                quote! {
+                    struct ... SomeStruct final {
+                        ...
+                        __COMMENT__ #zst1_msg
+                        __COMMENT__ #zst2_msg
+                        private:
+                            static void __crubit_field_offset_assertions();
+                    };
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! { static_assert(sizeof(SomeStruct) == 1, ...); }
+            );
         });
     }
 
     #[test]
-    fn test_format_item_unsupported_struct_with_some_zero_sized_type_fields() {
+    fn test_format_item_struct_with_some_zero_sized_type_fields() {
         let test_src = r#"
                 pub struct ZeroSizedType;
                 pub struct SomeStruct {
@@ -6448,10 +11104,8 @@ pub mod tests {
         test_format_item(test_src, "SomeStruct", |result| {
             let result = result.unwrap().unwrap();
             let main_api = &result.main_api;
-            let broken_field_msg_zst1 =
-                "Skipped bindings for field `zst1`: ZST fields are not supported (b/258259459)";
-            let broken_field_msg_zst2 =
-                "Skipped bindings for field `zst2`: ZST fields are not supported (b/258259459)";
+            let zst1_msg = "Field `zst1` is zero-sized in Rust, and has no C++ representation";
+            let zst2_msg = "Field `zst2` is zero-sized in Rust, and has no C++ representation";
 
             assert_cc_matches!(
                 main_api.tokens,
@@ -6461,8 +11115,8 @@ pub mod tests {
                         ...
                         public:
                             union { ... std::int32_t successful_field; };
-                        __COMMENT__ #broken_field_msg_zst1
-                        __COMMENT__ #broken_field_msg_zst2
+                        __COMMENT__ #zst1_msg
+                        __COMMENT__ #zst2_msg
                         private:
                             static void __crubit_field_offset_assertions();
                     };
@@ -6498,13 +11152,146 @@ pub mod tests {
         });
     }
 
+    /// A ZST field (with the default alignment of 1) doesn't prevent a
+    /// `#[repr(C)]` struct from relying on the natural C++ padding.
+    #[test]
+    fn test_format_item_repr_c_struct_with_zero_sized_type_field() {
+        let test_src = r#"
+                pub struct ZeroSizedType;
+                #[repr(C)]
+                pub struct SomeStruct {
+                    pub x: u8,
+                    pub zst: ZeroSizedType,
+                    pub y: i32,
+                }
+            "#;
+        test_format_item(test_src, "SomeStruct", |result| {
+            let result = result.unwrap().unwrap();
+            let zst_msg = "Field `zst` is zero-sized in Rust, and has no C++ representation";
+            assert_cc_matches!(
+                result.main_api.tokens,
+                quote! {
+                    struct ... SomeStruct final {
+                        ...
+                        public:
+                            union { ... std::uint8_t x; };
+                        __COMMENT__ #zst_msg
+                        public:
+                            union { ... std::int32_t y; };
+                        private:
+                            static void __crubit_field_offset_assertions();
+                    };
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    inline void SomeStruct::__crubit_field_offset_assertions() {
+                        static_assert(0 == offsetof(SomeStruct, x));
+                        static_assert(4 == offsetof(SomeStruct, y));
+                    }
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    const _: () = assert!(::core::mem::offset_of!(::rust_out::SomeStruct, x) == 0);
+                    const _: () = assert!(::core::mem::offset_of!(::rust_out::SomeStruct, zst) == 1);
+                    const _: () = assert!(::core::mem::offset_of!(::rust_out::SomeStruct, y) == 4);
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_item_struct_with_phantom_data_field() {
+        let test_src = r#"
+                use std::marker::PhantomData;
+                pub struct SomeStruct {
+                    pub value: i32,
+                    pub marker: PhantomData<*const u8>,
+                }
+            "#;
+        test_format_item(test_src, "SomeStruct", |result| {
+            let result = result.unwrap().unwrap();
+            let marker_msg = "Field `marker` is a `PhantomData` marker, and has no C++ \
+                              representation.  In Rust, it only affects the variance, the \
+                              auto traits (e.g. `Send` and `Sync`), and the drop check of \
+                              `SomeStruct`";
+            assert_cc_matches!(
+                result.main_api.tokens,
+                quote! {
+                    struct ... SomeStruct final {
+                        ...
+                        public:
+                            union { ... std::int32_t value; };
+                        __COMMENT__ #marker_msg
+                        private:
+                            static void __crubit_field_offset_assertions();
+                    };
+                }
+            );
+            assert_rs_matches!(
+                result.rs_details,
+                quote! {
+                    const _: () = assert!(::core::mem::offset_of!(::rust_out::SomeStruct, value) == 0);
+                    const _: () = assert!(::core::mem::offset_of!(::rust_out::SomeStruct, marker) == 4);
+                }
+            );
+        });
+    }
+
+    /// An over-aligned ZST field affects the offsets of the fields that follow
+    /// it, so explicit padding is emitted in its place.
+    #[test]
+    fn test_format_item_repr_c_struct_with_over_aligned_zero_sized_type_field() {
+        let test_src = r#"
+                #[repr(align(4))]
+                pub struct AlignedZst;
+                #[repr(C)]
+                pub struct SomeStruct {
+                    pub x: u8,
+                    pub zst: AlignedZst,
+                    pub y: u8,
+                }
+            "#;
+        test_format_item(test_src, "SomeStruct", |result| {
+            let result = result.unwrap().unwrap();
+            let zst_msg = "Field `zst` is zero-sized in Rust, and has no C++ representation";
+            assert_cc_matches!(
+                result.main_api.tokens,
+                quote! {
+                    struct ... SomeStruct final {
+                        ...
+                        public:
+                            union { ... std::uint8_t x; };
+                        private: unsigned char __padding0[3];
+                        __COMMENT__ #zst_msg
+                        public:
+                            union { ... std::uint8_t y; };
+                        private: unsigned char __padding2[3];
+                        private:
+                            static void __crubit_field_offset_assertions();
+                    };
+                }
+            );
+            assert_cc_matches!(
+                result.cc_details.tokens,
+                quote! {
+                    static_assert(sizeof(SomeStruct) == 8, ...);
+                    static_assert(alignof(SomeStruct) == 4, ...);
+                }
+            );
+        });
+    }
+
     #[test]
     fn test_format_item_struct_with_dynamically_sized_field() {
         let test_src = r#"
                 #![allow(dead_code)]
                 pub struct DynamicallySizedStruct {
-                    /// Having a non-ZST field avoids hitting the following error:
-                    /// "Zero-sized types (ZSTs) are not supported (b/258259459)"
+                    /// Having a non-ZST field keeps this test independent from how ZSTs
+                    /// are handled.
                     _non_zst_field: f32,
                     _dynamically_sized_field: [i32],
                 }
@@ -6687,7 +11474,7 @@ pub mod tests {
             "#;
         test_format_item(test_src, "ZeroVariantEnum", |result| {
             let err = result.unwrap_err();
-            assert_eq!(err, "Zero-sized types (ZSTs) are not supported (b/258259459)");
+            assert_eq!(err, "Bindings for uninhabited types are not supported");
         });
     }
 
@@ -6838,6 +11625,35 @@ pub mod tests {
         });
     }
 
+    #[test]
+    fn test_format_item_doc_comments_with_rustdoc_markup() {
+        let test_src = r#"
+            /// Returns [`Foo`].
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// let x = f();
+            /// ```
+            pub fn f() -> i32 { 0 }
+        "#;
+        test_format_item(test_src, "f", |result| {
+            let result = result.unwrap().unwrap();
+            let main_api = &result.main_api;
+            let comment = " Returns @ref Foo.\n\n\n\n \
+                           @par Examples\n\n\n\n \
+                           @code{.rs}\n\n let x = f();\n\n @endcode\n\n\
+                           Generated from: <crubit_unittests.rs>;l=9";
+            assert_cc_matches!(
+                main_api.tokens,
+                quote! {
+                    __COMMENT__ #comment
+                    std::int32_t f();
+                }
+            );
+        });
+    }
+
     #[test]
     fn test_format_item_doc_comments_tuple_struct() {
         let test_src = r#"
@@ -7250,6 +12066,16 @@ pub mod tests {
                     "",
                 ),
             ),
+            ("[i32; 42]", ("std::array<std::int32_t, 42>", "<array>", "", "")),
+            (
+                "[SomeStruct; 2]",
+                ("std::array<::rust_out::SomeStruct, 2>", "<array>", "SomeStruct", ""),
+            ),
+            // `SomeStruct` is a `fwd_decls` prerequisite (not `defs` prerequisite):
+            (
+                "[*const SomeStruct; 3]",
+                ("std::array<::rust_out::SomeStruct const*, 3>", "<array>", "", "SomeStruct"),
+            ),
             // Extra parens/sugar are expected to be ignored:
             ("(bool)", ("bool", "", "", "")),
         ];
@@ -7381,8 +12207,14 @@ pub mod tests {
                 "Generic functions are not supported yet (b/259749023)",
             ),
             (
-                "[i32; 42]", // TyKind::Array
-                "The following Rust type is not supported yet: [i32; 42]",
+                "[i32; 0]", // TyKind::Array (zero-length)
+                "Zero-length arrays are not supported yet (b/258259459)",
+            ),
+            (
+                "[&'static i32; 2]", // TyKind::Array (nested reference - array element)
+                "Failed to format the element type of the array type `[&'static i32; 2]`: \
+                 Can't format `&'static i32`, because references are only supported \
+                 in function parameter types and return types (b/286256327)",
             ),
             (
                 "&'static [i32]", // TyKind::Slice (nested underneath TyKind::Ref)
@@ -7530,6 +12362,15 @@ pub mod tests {
             // Pointer to an ADT:
             ("*mut SomeStruct", "* mut :: rust_out :: SomeStruct"),
             ("extern \"C\" fn(i32) -> i32", "extern \"C\" fn(i32) -> i32"),
+            // Arrays:
+            ("[i32; 42]", "[i32; 42]"),
+            ("[SomeStruct; 2]", "[::rust_out::SomeStruct; 2]"),
+            // Maps:
+            (
+                "std::collections::HashMap<i32, SomeStruct>",
+                "::std::collections::HashMap<i32, ::rust_out::SomeStruct>",
+            ),
+            ("std::collections::BTreeMap<u8, f32>", "::std::collections::BTreeMap<u8, f32>"),
         ];
         let preamble = quote! {
             #![feature(never_type)]
@@ -7564,10 +12405,6 @@ pub mod tests {
                 "(i32, i32)", // Non-empty TyKind::Tuple
                 "Tuples are not supported yet: (i32, i32) (b/254099023)",
             ),
-            (
-                "[i32; 42]", // TyKind::Array
-                "The following Rust type is not supported yet: [i32; 42]",
-            ),
             (
                 "&'static [i32]", // TyKind::Slice (nested underneath TyKind::Ref)
                 "Failed to format the referent of the reference type `&'static [i32]`: \
@@ -7586,6 +12423,12 @@ pub mod tests {
                 "Option<i8>", // TyKind::Adt - generic + different crate
                 "Generic types are not supported yet (b/259749095)",
             ),
+            (
+                // A map with a custom hasher.
+                "std::collections::HashMap<i32, i32, \
+                 std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>>",
+                "Generic types are not supported yet (b/259749095)",
+            ),
         ];
         let preamble = quote! {};
         test_ty(TypeLocation::FnParam, &testcases, preamble, |desc, tcx, ty, expected_err| {
@@ -8538,6 +13381,24 @@ pub mod tests {
         })
     }
 
+    /// Like `test_format_item`, but with `experimental_features` enabled.
+    fn test_format_item_with_experimental_features<F, T>(
+        source: &str,
+        name: &str,
+        test_function: F,
+    ) -> T
+    where
+        F: FnOnce(Result<Option<ApiSnippets>, String>) -> T + Send,
+        T: Send,
+    {
+        run_compiler_for_testing(source, |tcx| {
+            let def_id = find_def_id_by_name(tcx, name);
+            let result = experimental_bindings_db_for_tests(tcx).format_item(def_id);
+            let result = result.map_err(|anyhow_err| format!("{anyhow_err:#}"));
+            test_function(result)
+        })
+    }
+
     fn bindings_db_for_tests(tcx: TyCtxt) -> Database {
         Database::new(
             tcx,
@@ -8545,6 +13406,38 @@ pub mod tests {
             /* crate_name_to_include_paths= */ Default::default(),
             /* errors = */ Rc::new(IgnoreErrors),
             /* _features= */ (),
+            /* generate_mockable_interfaces= */ false,
+            /* sanitizer_annotations= */ false,
+            /* clang_tidy_nolint_checks= */ Rc::from([]),
+            /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+            /* namespace_aliases= */ Rc::from([]),
+            /* auto_namespace_alias_min_depth= */ None,
+            /* cc_call_site_in_panics= */ false,
+            /* experimental_features= */ false,
+            /* always_inline_cc_wrappers= */ false,
+            /* inline_trivial_getters= */ false,
+            /* version_stamp= */ None,
+        )
+    }
+
+    fn experimental_bindings_db_for_tests(tcx: TyCtxt) -> Database {
+        Database::new(
+            tcx,
+            /* crubit_support_path_format= */ "<crubit/support/for/tests/{header}>".into(),
+            /* crate_name_to_include_paths= */ Default::default(),
+            /* errors = */ Rc::new(IgnoreErrors),
+            /* _features= */ (),
+            /* generate_mockable_interfaces= */ false,
+            /* sanitizer_annotations= */ false,
+            /* clang_tidy_nolint_checks= */ Rc::from([]),
+            /* cc_naming_style= */ CcNamingStyle::SnakeCase,
+            /* namespace_aliases= */ Rc::from([]),
+            /* auto_namespace_alias_min_depth= */ None,
+            /* cc_call_site_in_panics= */ false,
+            /* experimental_features= */ true,
+            /* always_inline_cc_wrappers= */ false,
+            /* inline_trivial_getters= */ false,
+            /* version_stamp= */ None,
         )
     }
 
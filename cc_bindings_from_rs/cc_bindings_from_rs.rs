@@ -15,7 +15,7 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::rc::Rc;
 
-use bindings::Database;
+use bindings::{CcNamingStyle, Database, VersionStamp};
 use cmdline::Cmdline;
 use code_gen_utils::CcInclude;
 use error_report::{ErrorReport, ErrorReporting, IgnoreErrors};
@@ -48,6 +48,27 @@ fn new_db<'tcx>(
         crate_name_to_include_paths.into(),
         errors,
         /* _features= */ (),
+        cmdline.generate_mockable_interfaces,
+        cmdline.sanitizer_annotations,
+        cmdline.clang_tidy_nolint_checks.iter().map(|check| check.as_str().into()).collect(),
+        match cmdline.cc_naming_style {
+            None | Some(cmdline::CcNamingStyle::SnakeCase) => CcNamingStyle::SnakeCase,
+            Some(cmdline::CcNamingStyle::CamelCase) => CcNamingStyle::CamelCase,
+        },
+        cmdline
+            .namespace_aliases
+            .iter()
+            .map(|(alias, mod_path)| (alias.as_str().into(), mod_path.as_str().into()))
+            .collect(),
+        cmdline.auto_namespace_alias_min_depth,
+        cmdline.cc_call_site_in_panics,
+        cmdline.experimental_features,
+        cmdline.always_inline_cc_wrappers,
+        cmdline.inline_trivial_getters,
+        cmdline.version_stamp.as_deref().map(|version| VersionStamp {
+            version: version.into(),
+            flags: cmdline.codegen_flags().join(" ").into(),
+        }),
     )
 }
 
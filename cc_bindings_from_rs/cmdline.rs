@@ -9,11 +9,20 @@ extern crate rustc_driver;
 extern crate rustc_session;
 
 use anyhow::{bail, ensure, Result};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use rustc_session::config::ErrorOutputType;
 use rustc_session::EarlyDiagCtxt;
 use std::path::PathBuf;
 
+/// Naming style of the generated C++ functions and methods.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum CcNamingStyle {
+    #[value(name = "snake_case")]
+    SnakeCase,
+    #[value(name = "CamelCase")]
+    CamelCase,
+}
+
 #[derive(Debug, Parser)]
 #[clap(name = "cc_bindings_from_rs")]
 #[clap(about = "Generates C++ bindings for a Rust crate", long_about = None)]
@@ -64,6 +73,71 @@ pub struct Cmdline {
     /// Path to the error reporting output file.
     #[clap(long, value_parser, value_name = "FILE")]
     pub error_report_out: Option<PathBuf>,
+
+    /// Generate, for each module, an abstract C++ class with virtual methods
+    /// mirroring the free functions of the module (plus a default
+    /// implementation that calls the real bindings).
+    #[clap(long)]
+    pub generate_mockable_interfaces: bool,
+
+    /// Annotate the generated C++ code that calls into Rust thunks, to avoid
+    /// sanitizer false positives when mixing instrumented C++ with
+    /// uninstrumented Rust (e.g. mark return values written by Rust as
+    /// initialized for MSan).
+    #[clap(long)]
+    pub sanitizer_annotations: bool,
+
+    /// `clang-tidy` check to suppress (via `NOLINTBEGIN` / `NOLINTEND`) in the
+    /// generated C++ header. Example: "--clang-tidy-nolint=google-runtime-int".
+    #[clap(long = "clang-tidy-nolint", value_parser, value_name = "CHECK")]
+    pub clang_tidy_nolint_checks: Vec<String>,
+
+    /// Naming style of the generated C++ functions and methods: `snake_case`
+    /// (the default - keeps the Rust names) or `CamelCase`.
+    #[clap(long, value_enum, value_name = "STYLE")]
+    pub cc_naming_style: Option<CcNamingStyle>,
+
+    /// Namespace alias to emit at the end of the generated C++ header.
+    /// Example: "--namespace-alias=short=long::nested" results in `namespace
+    /// short = ::crate_name::long::nested;`.
+    #[clap(long = "namespace-alias", value_parser = parse_namespace_alias,
+           value_name = "ALIAS=MODULE_PATH")]
+    pub namespace_aliases: Vec<(String, String)>,
+
+    /// Generate an alias (in the top-level namespace of the crate) for each
+    /// module nested at least this deep, named after the innermost module.
+    #[clap(long, value_parser, value_name = "DEPTH")]
+    pub auto_namespace_alias_min_depth: Option<usize>,
+
+    /// Pass the location of the C++ caller (captured via `std::source_location`)
+    /// into Rust thunks, so that Rust panics can report the C++ call site.
+    /// Requires C++20.
+    #[clap(long)]
+    pub cc_call_site_in_panics: bool,
+
+    /// Generate bindings that rely on experimental features of Crubit (e.g.
+    /// C++ function templates for Rust functions with an `impl Trait`
+    /// parameter). The generated code may change in incompatible ways.
+    #[clap(long)]
+    pub experimental_features: bool,
+
+    /// Mark the generated C++ functions `[[gnu::always_inline]]` when the
+    /// corresponding Rust functions are `#[inline(always)]`.
+    #[clap(long)]
+    pub always_inline_cc_wrappers: bool,
+
+    /// Define the C++ wrappers of trivial Rust getters (`&self` methods of
+    /// `#[repr(C)]` structs that just return a field, e.g. `self.x`) inline,
+    /// reading the field directly instead of calling into Rust. The wrappers
+    /// are also marked `constexpr`.
+    #[clap(long)]
+    pub inline_trivial_getters: bool,
+
+    /// Version of Crubit (e.g. a revision stamped by the build system) to
+    /// embed into the generated C++ header, together with the flags that
+    /// affect the bindings and a hash of the bindings.
+    #[clap(long, value_parser, value_name = "STRING")]
+    pub version_stamp: Option<String>,
 }
 
 impl Cmdline {
@@ -98,6 +172,46 @@ impl Cmdline {
 
         Ok(cmdline)
     }
+
+    /// Returns the flags that affect the contents of the generated bindings
+    /// (in a canonical form and order).  Flags that only name input or output
+    /// files are omitted, so that the result doesn't depend on the layout of
+    /// the build directory.
+    pub fn codegen_flags(&self) -> Vec<String> {
+        let mut flags = vec![];
+        if self.generate_mockable_interfaces {
+            flags.push("--generate-mockable-interfaces".to_string());
+        }
+        if self.sanitizer_annotations {
+            flags.push("--sanitizer-annotations".to_string());
+        }
+        for check in &self.clang_tidy_nolint_checks {
+            flags.push(format!("--clang-tidy-nolint={check}"));
+        }
+        if let Some(cc_naming_style) = &self.cc_naming_style {
+            let cc_naming_style = cc_naming_style.to_possible_value().unwrap();
+            flags.push(format!("--cc-naming-style={}", cc_naming_style.get_name()));
+        }
+        for (alias, mod_path) in &self.namespace_aliases {
+            flags.push(format!("--namespace-alias={alias}={mod_path}"));
+        }
+        if let Some(depth) = self.auto_namespace_alias_min_depth {
+            flags.push(format!("--auto-namespace-alias-min-depth={depth}"));
+        }
+        if self.cc_call_site_in_panics {
+            flags.push("--cc-call-site-in-panics".to_string());
+        }
+        if self.experimental_features {
+            flags.push("--experimental-features".to_string());
+        }
+        if self.always_inline_cc_wrappers {
+            flags.push("--always-inline-cc-wrappers".to_string());
+        }
+        if self.inline_trivial_getters {
+            flags.push("--inline-trivial-getters".to_string());
+        }
+        flags
+    }
 }
 
 fn validate_crubit_support_path_format(s: &str) -> Result<String> {
@@ -105,6 +219,19 @@ fn validate_crubit_support_path_format(s: &str) -> Result<String> {
     Ok(s.to_string())
 }
 
+/// Parse cmdline arguments of the following form:`"alias=some::module::path"`.
+fn parse_namespace_alias(s: &str) -> Result<(String, String)> {
+    let Some((alias, mod_path)) = s.split_once('=') else {
+        bail!("Expected ALIAS=MODULE_PATH syntax but no `=` found in `{s}`");
+    };
+    ensure!(!alias.is_empty(), "Empty namespace aliases are invalid");
+    ensure!(
+        !mod_path.is_empty() && mod_path.split("::").all(|name| !name.is_empty()),
+        "Invalid module path `{mod_path}`"
+    );
+    Ok((alias.to_string(), mod_path.to_string()))
+}
+
 /// Parse cmdline arguments of the following form:`"crateName=includePath"`.
 ///
 /// Adapted from
@@ -159,6 +286,18 @@ mod tests {
         assert_eq!(Path::new("rustfmt.exe"), cmdline.rustfmt_exe_path);
         assert!(cmdline.bindings_from_dependencies.is_empty());
         assert!(cmdline.rustfmt_config_path.is_none());
+        assert!(!cmdline.generate_mockable_interfaces);
+        assert!(!cmdline.sanitizer_annotations);
+        assert!(cmdline.clang_tidy_nolint_checks.is_empty());
+        assert!(cmdline.cc_naming_style.is_none());
+        assert!(cmdline.namespace_aliases.is_empty());
+        assert!(cmdline.auto_namespace_alias_min_depth.is_none());
+        assert!(!cmdline.cc_call_site_in_panics);
+        assert!(!cmdline.experimental_features);
+        assert!(!cmdline.always_inline_cc_wrappers);
+        assert!(!cmdline.inline_trivial_getters);
+        assert!(cmdline.version_stamp.is_none());
+        assert!(cmdline.codegen_flags().is_empty());
         // Ignoring `rustc_args` in this test - they are covered in a separate
         // test below: `test_rustc_args_happy_path`.
     }
@@ -235,6 +374,28 @@ Options:
           Path to a rustfmt.toml file that should replace the default formatting of the .rs files generated by the tool
       --error-report-out <FILE>
           Path to the error reporting output file
+      --generate-mockable-interfaces
+          Generate, for each module, an abstract C++ class with virtual methods mirroring the free functions of the module (plus a default implementation that calls the real bindings)
+      --sanitizer-annotations
+          Annotate the generated C++ code that calls into Rust thunks, to avoid sanitizer false positives when mixing instrumented C++ with uninstrumented Rust (e.g. mark return values written by Rust as initialized for MSan)
+      --clang-tidy-nolint <CHECK>
+          `clang-tidy` check to suppress (via `NOLINTBEGIN` / `NOLINTEND`) in the generated C++ header. Example: "--clang-tidy-nolint=google-runtime-int"
+      --cc-naming-style <STYLE>
+          Naming style of the generated C++ functions and methods: `snake_case` (the default - keeps the Rust names) or `CamelCase`
+      --namespace-alias <ALIAS=MODULE_PATH>
+          Namespace alias to emit at the end of the generated C++ header. Example: "--namespace-alias=short=long::nested" results in `namespace short = ::crate_name::long::nested;`
+      --auto-namespace-alias-min-depth <DEPTH>
+          Generate an alias (in the top-level namespace of the crate) for each module nested at least this deep, named after the innermost module
+      --cc-call-site-in-panics
+          Pass the location of the C++ caller (captured via `std::source_location`) into Rust thunks, so that Rust panics can report the C++ call site. Requires C++20
+      --experimental-features
+          Generate bindings that rely on experimental features of Crubit (e.g. C++ function templates for Rust functions with an `impl Trait` parameter). The generated code may change in incompatible ways
+      --always-inline-cc-wrappers
+          Mark the generated C++ functions `[[gnu::always_inline]]` when the corresponding Rust functions are `#[inline(always)]`
+      --inline-trivial-getters
+          Define the C++ wrappers of trivial Rust getters (`&self` methods of `#[repr(C)]` structs that just return a field, e.g. `self.x`) inline, reading the field directly instead of calling into Rust. The wrappers are also marked `constexpr`
+      --version-stamp <STRING>
+          Version of Crubit (e.g. a revision stamped by the build system) to embed into the generated bindings, together with the flags that affect the bindings and a hash of the bindings
   -h, --help
           Print help
 "#;
@@ -325,6 +486,26 @@ Options:
         );
     }
 
+    #[test]
+    fn test_parse_namespace_alias() {
+        assert_eq!(
+            parse_namespace_alias("short=long::nested").unwrap(),
+            ("short".into(), "long::nested".into()),
+        );
+        assert_eq!(
+            parse_namespace_alias("no-equal-char").unwrap_err().to_string(),
+            "Expected ALIAS=MODULE_PATH syntax but no `=` found in `no-equal-char`",
+        );
+        assert_eq!(
+            parse_namespace_alias("=long").unwrap_err().to_string(),
+            "Empty namespace aliases are invalid",
+        );
+        assert_eq!(
+            parse_namespace_alias("short=long::").unwrap_err().to_string(),
+            "Invalid module path `long::`",
+        );
+    }
+
     #[test]
     fn test_crubit_support_path_format_arg_happy_path() {
         let cmdline = new_cmdline([
@@ -355,4 +536,72 @@ Options:
         let expected_msg = "Cannot find placeholder `{header}`";
         assert!(clap_err.to_string().contains(expected_msg));
     }
+
+    #[test]
+    fn test_clang_tidy_nolint_and_cc_naming_style() {
+        let cmdline = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path-format=<crubit/support/{header}>",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--clang-tidy-nolint=google-runtime-int",
+            "--clang-tidy-nolint=readability-identifier-naming",
+            "--cc-naming-style=CamelCase",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            vec!["google-runtime-int", "readability-identifier-naming"],
+            cmdline.clang_tidy_nolint_checks
+        );
+        assert_eq!(Some(CcNamingStyle::CamelCase), cmdline.cc_naming_style);
+    }
+
+    #[test]
+    fn test_version_stamp_and_codegen_flags() {
+        let cmdline = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path-format=<crubit/support/{header}>",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--bindings-from-dependency=dep1=path1",
+            "--version-stamp=1.2.3",
+            "--always-inline-cc-wrappers",
+            "--inline-trivial-getters",
+            "--cc-naming-style=CamelCase",
+            "--clang-tidy-nolint=google-runtime-int",
+            "--namespace-alias=short=long::nested",
+        ])
+        .unwrap();
+
+        assert_eq!(Some("1.2.3"), cmdline.version_stamp.as_deref());
+        assert_eq!(
+            vec![
+                "--clang-tidy-nolint=google-runtime-int",
+                "--cc-naming-style=CamelCase",
+                "--namespace-alias=short=long::nested",
+                "--always-inline-cc-wrappers",
+                "--inline-trivial-getters",
+            ],
+            cmdline.codegen_flags()
+        );
+    }
+
+    #[test]
+    fn test_cc_naming_style_unknown() {
+        let anyhow_err = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path-format=<crubit/support/{header}>",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--cc-naming-style=kebab-case",
+        ])
+        .expect_err("unknown naming styles should trigger an error");
+        let clap_err = anyhow_err.downcast::<clap::Error>().unwrap();
+        let expected_msg = "invalid value 'kebab-case' for '--cc-naming-style <STYLE>'";
+        assert!(clap_err.to_string().contains(expected_msg));
+    }
 }
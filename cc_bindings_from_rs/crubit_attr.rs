@@ -39,8 +39,18 @@ pub struct CrubitAttr {
     // pub fn new() -> i32 {...}
     // ```
     //
-    // will rename `new` in Rust to `Create` in C++.
+    // will rename `new` in Rust to `Create` in C++. On a module, this is the name of the C++
+    // namespace of the module.
     pub cpp_name: Option<Symbol>,
+    // The path of a file with hand-written C++ code, which is appended to the generated C++
+    // header, inside the namespace of the crate. Only valid on the crate root, e.g.
+    //
+    // ```
+    // #![__crubit::annotate(cpp_epilogue="epilogue.h")]
+    // ```
+    //
+    // The path is relative to the directory of the crate root.
+    pub cpp_epilogue: Option<Symbol>,
 }
 
 /// Gets the `#[__crubit::annotate(...)]` attribute(s) applied to a definition.
@@ -53,6 +63,7 @@ pub fn get(tcx: TyCtxt, did: impl Into<DefId>) -> Result<CrubitAttr> {
     let crubit_annotate = &[Symbol::intern("__crubit"), Symbol::intern("annotate")];
     let cc_type = Symbol::intern("cc_type");
     let cpp_name = Symbol::intern("cpp_name");
+    let cpp_epilogue = Symbol::intern("cpp_epilogue");
 
     let mut crubit_attr = CrubitAttr::default();
     // A quick note: the parsing logic is unfortunate, but such is life. We don't
@@ -102,12 +113,52 @@ pub fn get(tcx: TyCtxt, did: impl Into<DefId>) -> Result<CrubitAttr> {
                     "Unexpected duplicate #[__crubit::annotate(cpp_name=...)]"
                 );
                 crubit_attr.cpp_name = Some(s);
+            } else if arg.path == cpp_epilogue {
+                let MetaItemKind::NameValue(value) = &arg.kind else {
+                    bail!(
+                        "Invalid #[__crubit::annotate(cpp_epilogue=...)] attribute (expected =...)"
+                    );
+                };
+                let LitKind::Str(s, _raw) = value.kind else {
+                    bail!(
+                        "Invalid #[__crubit::annotate(cpp_epilogue=...)] attribute (expected =\"...\")"
+                    );
+                };
+                ensure!(
+                    crubit_attr.cpp_epilogue.is_none(),
+                    "Unexpected duplicate #[__crubit::annotate(cpp_epilogue=...)]"
+                );
+                crubit_attr.cpp_epilogue = Some(s);
             }
         }
     }
     Ok(crubit_attr)
 }
 
+/// Returns whether a function is annotated with `#[__crubit::export_macro_shim]`.
+///
+/// Unlike `#[__crubit::annotate(...)]`, this attribute is written by users: it
+/// marks a function which wraps the macro with the same name, so that the
+/// behavior of the macro can be used from C++. For example:
+///
+/// ```
+/// #[__crubit::export_macro_shim]
+/// pub fn greeting(name: &str) -> String { greeting!(name) }
+/// ```
+pub fn is_export_macro_shim(tcx: TyCtxt, did: impl Into<DefId>) -> Result<bool> {
+    let export_macro_shim = &[Symbol::intern("__crubit"), Symbol::intern("export_macro_shim")];
+    let mut attrs = tcx.get_attrs_by_path(did.into(), export_macro_shim);
+    let Some(attr) = attrs.next() else {
+        return Ok(false);
+    };
+    ensure!(
+        attr.meta().is_some_and(|meta| matches!(meta.kind, MetaItemKind::Word)),
+        "Invalid #[__crubit::export_macro_shim] attribute (expected no arguments)"
+    );
+    ensure!(attrs.next().is_none(), "Unexpected duplicate #[__crubit::export_macro_shim]");
+    Ok(true)
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -180,6 +231,49 @@ pub mod tests {
         });
     }
 
+    #[test]
+    fn test_cpp_epilogue() {
+        let test_src = r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+                #![__crubit::annotate(cpp_epilogue = "epilogue.h")]
+                pub fn foo() {}
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            let attr = get(tcx, rustc_span::def_id::CRATE_DEF_ID).unwrap();
+            assert_eq!(attr.cpp_epilogue.unwrap(), Symbol::intern("epilogue.h"));
+        });
+    }
+
+    #[test]
+    fn test_export_macro_shim() {
+        let test_src = r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+                #[__crubit::export_macro_shim]
+                pub fn double(x: i32) -> i32 { 2 * x }
+
+                pub fn triple(x: i32) -> i32 { 3 * x }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            assert!(is_export_macro_shim(tcx, find_def_id_by_name(tcx, "double")).unwrap());
+            assert!(!is_export_macro_shim(tcx, find_def_id_by_name(tcx, "triple")).unwrap());
+        });
+    }
+
+    #[test]
+    fn test_export_macro_shim_with_arguments() {
+        let test_src = r#"
+                #![feature(register_tool)]
+                #![register_tool(__crubit)]
+                #[__crubit::export_macro_shim(double)]
+                pub fn double(x: i32) -> i32 { 2 * x }
+            "#;
+        run_compiler_for_testing(test_src, |tcx| {
+            assert!(is_export_macro_shim(tcx, find_def_id_by_name(tcx, "double")).is_err());
+        });
+    }
+
     #[test]
     fn test_cc_type_multi() {
         let test_src = r#"
@@ -0,0 +1,212 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! Translation of rustdoc Markdown into Doxygen (Javadoc-style) markup, so
+//! that C++ documentation tooling can render the doc comments that
+//! `cc_bindings_from_rs` copies from Rust into the generated C++ headers.
+//!
+//! The following constructs are translated:
+//! - Markdown headers (e.g. `# Safety`) become `@par` paragraphs,
+//! - code fences become `@code` / `@endcode` (or `@verbatim` /
+//!   `@endverbatim` for ```` ```text ```` blocks),
+//! - intra-doc links (e.g. ``[`Foo::bar`]`` or `[text](crate::Foo)`) become
+//!   `@ref` commands.
+//!
+//! Everything else (including regular Markdown links with a URL) is left
+//! as-is.
+
+/// Translates rustdoc Markdown in `doc` into Doxygen markup.  Lines that don't
+/// use any of the supported constructs are returned unchanged.
+pub fn rustdoc_to_doxygen(doc: &str) -> String {
+    let mut open_block: Option<&'static str> = None;
+    doc.split('\n')
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let indent = &line[..line.len() - trimmed.len()];
+            if let Some(end_command) = open_block {
+                if trimmed.starts_with("```") {
+                    open_block = None;
+                    return format!("{indent}{end_command}");
+                }
+                return line.to_string();
+            }
+            if let Some(info_string) = trimmed.strip_prefix("```") {
+                let (start_command, end_command) = format_code_block_commands(info_string);
+                open_block = Some(end_command);
+                return format!("{indent}{start_command}");
+            }
+            if let Some(title) = parse_header(trimmed) {
+                return format!("{indent}@par {title}");
+            }
+            translate_intra_doc_links(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the Doxygen commands that should start and end a code block with
+/// the given Markdown `info_string` (e.g. `rust,no_run` in ```` ```rust,no_run ````).
+fn format_code_block_commands(info_string: &str) -> (String, &'static str) {
+    // See https://doc.rust-lang.org/rustdoc/write-documentation/documentation-tests.html#attributes
+    const RUST_ATTRIBUTES: &[&str] =
+        &["rust", "ignore", "no_run", "should_panic", "compile_fail", "test_harness"];
+    let attributes = info_string
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|attr| !attr.is_empty())
+        .collect::<Vec<_>>();
+    let is_rust = attributes
+        .iter()
+        .all(|attr| RUST_ATTRIBUTES.contains(attr) || attr.starts_with("edition"));
+    if is_rust {
+        return ("@code{.rs}".to_string(), "@endcode");
+    }
+    match attributes[0] {
+        "text" => ("@verbatim".to_string(), "@endverbatim"),
+        "c++" | "cc" | "cpp" => ("@code{.cpp}".to_string(), "@endcode"),
+        lang => (format!("@code{{.{lang}}}"), "@endcode"),
+    }
+}
+
+/// If `line` is a Markdown ATX header (e.g. `## Examples`), returns its title.
+fn parse_header(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let title = line[level..].strip_prefix(' ')?.trim();
+    if title.is_empty() {
+        return None;
+    }
+    Some(title.trim_end_matches('#').trim_end())
+}
+
+/// Rewrites intra-doc links in `line` into `@ref` commands.
+fn translate_intra_doc_links(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+    let mut in_code_span = false;
+    while let Some(pos) = rest.find(['[', '`']) {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if rest.starts_with('`') {
+            in_code_span = !in_code_span;
+            result.push('`');
+            rest = &rest[1..];
+            continue;
+        }
+        if !in_code_span {
+            if let Some((translated, len)) = parse_intra_doc_link(rest) {
+                result.push_str(&translated);
+                rest = &rest[len..];
+                continue;
+            }
+        }
+        result.push('[');
+        rest = &rest[1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Parses an intra-doc link at the start of `text` (which has to start with
+/// `[`).  Returns the translated link and the number of bytes it spans in
+/// `text`.
+fn parse_intra_doc_link(text: &str) -> Option<(String, usize)> {
+    let close = text.find(']')?;
+    let label = &text[1..close];
+    let after = &text[close + 1..];
+    if let Some(target) = after.strip_prefix('(') {
+        // `[label](target)`
+        let target_len = target.find(')')?;
+        let target = &target[..target_len];
+        if target.contains("://") || target.starts_with('#') {
+            return None;
+        }
+        let target = format_link_target(target)?;
+        let label = label.replace('"', "'");
+        return Some((format!("@ref {target} \"{label}\""), close + 1 + 1 + target_len + 1));
+    }
+    if after.starts_with('[') {
+        // Reference-style links are left as-is.
+        return None;
+    }
+    // `[path]` or ``[`path`]``
+    let path = label.strip_prefix('`').and_then(|l| l.strip_suffix('`')).unwrap_or(label);
+    let target = format_link_target(path)?;
+    Some((format!("@ref {target}"), close + 1))
+}
+
+/// Converts a rustdoc link target (e.g. `crate::Foo::bar()` or `struct@Foo`)
+/// into a name that can be used in `@ref`.  Returns `None` if `target` doesn't
+/// look like a Rust path.
+fn format_link_target(target: &str) -> Option<&str> {
+    // Disambiguators - see https://doc.rust-lang.org/rustdoc/write-documentation/linking-to-items-by-name.html#namespaces-and-disambiguators
+    let target = target.split_once('@').map_or(target, |(_, path)| path);
+    let target = target.trim_end_matches("()").trim_end_matches('!');
+    let target = ["crate::", "self::", "super::"]
+        .iter()
+        .fold(target, |target, prefix| target.strip_prefix(prefix).unwrap_or(target));
+    let is_path = !target.is_empty()
+        && target.split("::").all(|segment| {
+            !segment.is_empty()
+                && !segment.starts_with(|c: char| c.is_ascii_digit())
+                && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+        });
+    is_path.then_some(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_is_unchanged() {
+        let doc = " Some doc comment.\n\n With `code` and a [link](https://example.com).";
+        assert_eq!(rustdoc_to_doxygen(doc), doc);
+    }
+
+    #[test]
+    fn test_headers() {
+        assert_eq!(rustdoc_to_doxygen(" # Safety"), " @par Safety");
+        assert_eq!(rustdoc_to_doxygen("## Examples ##"), "@par Examples");
+        assert_eq!(rustdoc_to_doxygen("#not_a_header"), "#not_a_header");
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        let doc = " ```\n # Not a header\n let x = [y];\n ```";
+        assert_eq!(
+            rustdoc_to_doxygen(doc),
+            " @code{.rs}\n # Not a header\n let x = [y];\n @endcode"
+        );
+
+        let doc = "```rust,no_run\nfoo();\n```";
+        assert_eq!(rustdoc_to_doxygen(doc), "@code{.rs}\nfoo();\n@endcode");
+
+        let doc = "```text\nfoo\n```";
+        assert_eq!(rustdoc_to_doxygen(doc), "@verbatim\nfoo\n@endverbatim");
+
+        let doc = "```c++\nfoo();\n```";
+        assert_eq!(rustdoc_to_doxygen(doc), "@code{.cpp}\nfoo();\n@endcode");
+    }
+
+    #[test]
+    fn test_intra_doc_links() {
+        assert_eq!(rustdoc_to_doxygen("See [`Foo`]."), "See @ref Foo.");
+        assert_eq!(rustdoc_to_doxygen("See [Foo::bar()]."), "See @ref Foo::bar.");
+        assert_eq!(rustdoc_to_doxygen("See [`crate::m::Foo`]."), "See @ref m::Foo.");
+        assert_eq!(rustdoc_to_doxygen("See [`struct@Foo`]."), "See @ref Foo.");
+        assert_eq!(
+            rustdoc_to_doxygen("See [the `Foo` type](crate::Foo)."),
+            "See @ref Foo \"the `Foo` type\"."
+        );
+    }
+
+    #[test]
+    fn test_non_links_are_unchanged() {
+        let doc = "Indexing `a[i]`, arrays [1, 2], [reference][1] and [empty]() links.";
+        assert_eq!(rustdoc_to_doxygen(doc), doc);
+    }
+}
@@ -94,6 +94,18 @@ pub mod fn_param_ty_tests {
     }
 }
 
+/// APIs for testing functions that return `&'static str` (which is returned as
+/// a `std::string_view` in C++).
+pub mod str_ret_ty_tests {
+    pub fn get_greeting() -> &'static str {
+        "Hello, world!"
+    }
+
+    pub fn get_empty_str() -> &'static str {
+        ""
+    }
+}
+
 /// APIs for testing functions that return the unit / `()` / `void` type.
 pub mod unit_ret_ty_tests {
     use std::sync::Mutex;
@@ -4,6 +4,7 @@
 
 #include <cstdint>
 #include <optional>
+#include <string_view>
 
 #include "gmock/gmock.h"
 #include "gtest/gtest.h"
@@ -94,6 +95,12 @@ TEST(FnParamTyTest, FnPtr) {
   EXPECT_EQ(product, 56 * 78);
 }
 
+TEST(FnRetTyTest, StaticStr) {
+  namespace tests = functions::str_ret_ty_tests;
+  EXPECT_EQ(std::string_view("Hello, world!"), tests::get_greeting());
+  EXPECT_TRUE(tests::get_empty_str().empty());
+}
+
 TEST(OtherFnTest, VoidReturningFunction) {
   namespace tests = functions::unit_ret_ty_tests;
   tests::set_global_i32_via_extern_c_with_export_name(123);
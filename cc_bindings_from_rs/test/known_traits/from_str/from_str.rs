@@ -0,0 +1,31 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! This crate is used as a test input for `cc_bindings_from_rs` and the
+//! generated C++ bindings are then tested via `from_str_test.cc`.
+
+use std::str::FromStr;
+
+/// Parses the number of `'x'` characters in a string (so that the empty
+/// string is a valid input).
+#[derive(Clone, Copy, Default)]
+pub struct XCount(i32);
+
+impl FromStr for XCount {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        if s.chars().all(|c| c == 'x') {
+            Ok(XCount(s.len() as i32))
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl XCount {
+    pub fn count(&self) -> i32 {
+        self.0
+    }
+}
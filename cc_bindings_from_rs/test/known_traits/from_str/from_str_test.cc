@@ -0,0 +1,38 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#include <optional>
+#include <string_view>
+
+#include "gtest/gtest.h"
+#include "cc_bindings_from_rs/test/known_traits/from_str/from_str_cc_api.h"
+
+namespace crubit {
+namespace {
+
+TEST(FromStrTest, Parse) {
+  std::optional<from_str::XCount> count = from_str::XCount::Parse("xxx");
+  ASSERT_TRUE(count.has_value());
+  EXPECT_EQ(3, count->count());
+}
+
+TEST(FromStrTest, ParseError) {
+  EXPECT_FALSE(from_str::XCount::Parse("xyz").has_value());
+  EXPECT_FALSE(from_str::XCount::Parse("\xff").has_value());
+}
+
+TEST(FromStrTest, ParseEmptyStringView) {
+  // The `data()` of a default-constructed `std::string_view` is null.
+  std::optional<from_str::XCount> count =
+      from_str::XCount::Parse(std::string_view());
+  ASSERT_TRUE(count.has_value());
+  EXPECT_EQ(0, count->count());
+
+  count = from_str::XCount::Parse("");
+  ASSERT_TRUE(count.has_value());
+  EXPECT_EQ(0, count->count());
+}
+
+}  // namespace
+}  // namespace crubit
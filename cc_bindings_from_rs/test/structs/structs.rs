@@ -42,6 +42,10 @@ pub mod default_repr {
     pub fn get_x(p: Point) -> i32 {
         p.x
     }
+
+    pub fn set_x(p: &mut Point, x: i32) {
+        p.x = x;
+    }
 }
 
 /// Test for a struct containing zero-sized fields.
@@ -65,6 +69,34 @@ pub mod zst_fields {
     pub fn get_value(x: ZstFields) -> i32 {
         x.value
     }
+
+    pub fn create_zst1() -> Zst1 {
+        Zst1
+    }
+
+    pub fn consume_zst1(_: Zst1) -> i32 {
+        123
+    }
+}
+
+/// Test for a struct containing a `PhantomData` field.
+pub mod phantom_data {
+    use std::marker::PhantomData;
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    pub struct Meters {
+        pub value: f32,
+        pub unit: PhantomData<*const u8>,
+    }
+
+    pub fn create(value: f32) -> Meters {
+        Meters { value, unit: PhantomData }
+    }
+
+    pub fn get_value(m: Meters) -> f32 {
+        m.value
+    }
 }
 
 /// Test of ABI classification.
@@ -246,8 +278,8 @@ pub mod struct_by_float_passing_with_no_thunk {
 /// build fine.
 pub mod dynamically_sized_type {
     pub struct DynamicallySizedStruct {
-        /// Having a non-ZST field avoids hitting the following error:
-        /// "Zero-sized types (ZSTs) are not supported (b/258259459)"
+        /// Having a non-ZST field keeps this test independent from how ZSTs
+        /// are handled.
         _non_zst_field: f32,
         _dynamically_sized_field: [i32],
     }
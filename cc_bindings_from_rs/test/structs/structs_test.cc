@@ -25,6 +25,19 @@ TEST(StructsTest, ZstFieldsReturnedOrTakenByValue) {
   EXPECT_EQ(structs::zst_fields::get_value(std::move(x)), 42);
 }
 
+TEST(StructsTest, ZstReturnedOrTakenByValue) {
+  static_assert(sizeof(structs::zst_fields::Zst1) == 1);
+  structs::zst_fields::Zst1 zst = structs::zst_fields::create_zst1();
+  EXPECT_EQ(structs::zst_fields::consume_zst1(std::move(zst)), 123);
+}
+
+TEST(StructsTest, PhantomDataFieldReturnedOrTakenByValue) {
+  static_assert(sizeof(structs::phantom_data::Meters) == sizeof(float));
+  structs::phantom_data::Meters m = structs::phantom_data::create(12.5);
+  EXPECT_EQ(12.5, m.value);
+  EXPECT_EQ(12.5, structs::phantom_data::get_value(m));
+}
+
 TEST(StructsTest, DefaultReprPointReturnedOrTakenByValue) {
   structs::default_repr::Point p = structs::default_repr::create(123, 456);
   EXPECT_EQ(123, p.x);
@@ -32,6 +45,13 @@ TEST(StructsTest, DefaultReprPointReturnedOrTakenByValue) {
   EXPECT_EQ(123, structs::default_repr::get_x(std::move(p)));
 }
 
+TEST(StructsTest, DefaultReprPointTakenByMutableReference) {
+  structs::default_repr::Point p = structs::default_repr::create(123, 456);
+  structs::default_repr::set_x(p, 789);
+  EXPECT_EQ(789, p.x);
+  EXPECT_EQ(456, p.y);
+}
+
 TEST(StructsTest, StructInteger) {
   namespace test = structs::abi_classification;
   test::StructInteger x = test::StructInteger::create(123);
@@ -105,7 +105,13 @@ pub fn escape_non_identifier_chars(symbol: &str) -> String {
 /// Representation of `foo::bar::baz` where each component is either the name
 /// of a C++ namespace, or the name of a Rust module.
 #[derive(Debug, PartialEq, Eq, Clone, Hash, PartialOrd, Ord)]
-pub struct NamespaceQualifier(pub Vec<Rc<str>>);
+pub struct NamespaceQualifier {
+    pub namespaces: Vec<Rc<str>>,
+    /// The records enclosing a nested type, innermost last: the C++ name of
+    /// each record, and the name of the Rust module holding the bindings for
+    /// the types nested inside it.
+    pub nested_records: Vec<(Rc<str>, Rc<str>)>,
+}
 
 impl NamespaceQualifier {
     /// Constructs a new `NamespaceQualifier` from a sequence of names.
@@ -116,19 +122,31 @@ impl NamespaceQualifier {
         // - Report an error early if any strings are C++ reserved keywords
         // This may make `format_for_cc`, `format_with_cc_body`, and
         // `format_namespace_bound_cc_tokens` infallible.
-        Self(iter.into_iter().map(Into::into).collect())
+        Self { namespaces: iter.into_iter().map(Into::into).collect(), nested_records: vec![] }
     }
 
     /// Returns `foo::bar::baz::` (escaping Rust keywords as needed).
+    ///
+    /// Enclosing records are represented by their modules, e.g. `foo::outer::`
+    /// for `foo::Outer::`.
     pub fn format_for_rs(&self) -> TokenStream {
-        let namespace_rs_idents = self.0.iter().map(|ns| make_rs_ident(ns));
+        let namespace_rs_idents = self
+            .namespaces
+            .iter()
+            .chain(self.nested_records.iter().map(|(_, module_name)| module_name))
+            .map(|ns| make_rs_ident(ns));
         quote! { #(#namespace_rs_idents::)* }
     }
 
     /// Returns `foo::bar::baz::` (reporting errors for C++ keywords).
     pub fn format_for_cc(&self) -> Result<TokenStream> {
         let namespace_cc_idents = self.cc_idents()?;
-        Ok(quote! { #(#namespace_cc_idents::)* })
+        let record_cc_idents = self
+            .nested_records
+            .iter()
+            .map(|(cc_name, _)| format_cc_ident(cc_name))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(quote! { #(#namespace_cc_idents::)* #(#record_cc_idents::)* })
     }
 
     pub fn format_with_cc_body(
@@ -136,7 +154,7 @@ impl NamespaceQualifier {
         body: TokenStream,
         attributes: Vec<TokenStream>,
     ) -> Result<TokenStream> {
-        if self.0.is_empty() {
+        if self.namespaces.is_empty() {
             Ok(body)
         } else {
             let namespace_cc_idents = self.cc_idents()?;
@@ -149,7 +167,7 @@ impl NamespaceQualifier {
     }
 
     pub fn cc_idents(&self) -> Result<Vec<TokenStream>> {
-        self.0.iter().map(|ns| format_cc_ident(ns)).collect()
+        self.namespaces.iter().map(|ns| format_cc_ident(ns)).collect()
     }
 }
 
@@ -175,6 +193,20 @@ impl CcInclude {
         Self::SystemHeader("cstddef")
     }
 
+    /// Creates a `CcInclude` that represents `#include <array>` and provides
+    /// the C++ `std::array` type.  See
+    /// https://en.cppreference.com/w/cpp/header/array
+    pub fn array() -> Self {
+        Self::SystemHeader("array")
+    }
+
+    /// Creates a `CcInclude` that represents `#include <concepts>` and provides
+    /// C++ concepts like `std::convertible_to`.  See
+    /// https://en.cppreference.com/w/cpp/header/concepts
+    pub fn concepts() -> Self {
+        Self::SystemHeader("concepts")
+    }
+
     /// Creates a `CcInclude` that represents `#include <cstdint>` and provides
     /// C++ types like `std::int16_t` or `std::uint32_t`.  See
     /// https://en.cppreference.com/w/cpp/header/cstdint
@@ -188,6 +220,27 @@ impl CcInclude {
         Self::SystemHeader("memory")
     }
 
+    /// Creates a `CcInclude` that represents `#include <optional>` and provides
+    /// the C++ `std::optional` type.  See
+    /// https://en.cppreference.com/w/cpp/header/optional
+    pub fn optional() -> Self {
+        Self::SystemHeader("optional")
+    }
+
+    /// Creates a `CcInclude` that represents `#include <source_location>` and
+    /// provides the C++ `std::source_location` type.  See
+    /// https://en.cppreference.com/w/cpp/header/source_location
+    pub fn source_location() -> Self {
+        Self::SystemHeader("source_location")
+    }
+
+    /// Creates a `CcInclude` that represents `#include <string_view>` and
+    /// provides the C++ `std::string_view` type.  See
+    /// https://en.cppreference.com/w/cpp/header/string_view
+    pub fn string_view() -> Self {
+        Self::SystemHeader("string_view")
+    }
+
     /// Creates a `CcInclude` that represents `#include <utility>` and provides
     /// C++ functions like `std::move` and C++ types like `std::tuple`.
     /// See https://en.cppreference.com/w/cpp/header/utility
@@ -592,6 +645,21 @@ pub mod tests {
         assert!(msg.contains("C++ reserved keyword"));
     }
 
+    #[test]
+    fn test_namespace_qualifier_nested_records() {
+        let ns = NamespaceQualifier {
+            namespaces: vec!["foo".into()],
+            nested_records: vec![
+                ("Outer".into(), "outer".into()),
+                ("Inner".into(), "inner".into()),
+            ],
+        };
+        let actual_rs = ns.format_for_rs();
+        assert_rs_matches!(actual_rs, quote! { foo::outer::inner:: });
+        let actual_cc = ns.format_for_cc().unwrap();
+        assert_cc_matches!(actual_cc, quote! { foo::Outer::Inner:: });
+    }
+
     #[test]
     fn test_namespace_qualifier_format_with_cc_body_top_level_namespace() {
         let ns = NamespaceQualifier::new::<&str>([]);
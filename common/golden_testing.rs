@@ -0,0 +1,207 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! Golden-file tests of generated source code.
+//!
+//! `assert_rs_golden` and `assert_cc_golden` format the generated tokens and
+//! compare the result with a checked-in golden file. On mismatch, the failure
+//! shows a line diff, which is much easier to review than a token matcher
+//! failure when a change affects a lot of the generated code.
+//!
+//! Golden paths are relative to the root of the workspace, and the golden files
+//! have to be in the `data` of the test. To update them, run the test with
+//! `WRITE_GOLDENS` set and without the sandbox (so that the runfiles are
+//! symlinks into the source tree), e.g.:
+//!
+//! ```sh
+//! bazel test --test_strategy=local --test_env=WRITE_GOLDENS=1 //foo:bar_test
+//! ```
+//!
+//! Tests tagged `crubit_golden_test` are updated this way by
+//! `rs_bindings_from_cc/test/golden/update.sh`.
+
+use proc_macro2::TokenStream;
+use std::fs;
+use std::path::Path;
+use token_stream_printer::{
+    cc_tokens_to_formatted_string_for_tests, rs_tokens_to_formatted_string_for_tests,
+};
+
+/// Number of unchanged lines shown around the changed lines in a diff.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Formats `tokens` as Rust code and compares it with the golden file at
+/// `golden_path`.
+pub fn assert_rs_golden(tokens: TokenStream, golden_path: &str) {
+    let actual = rs_tokens_to_formatted_string_for_tests(tokens)
+        .expect("Failed to format the generated Rust code");
+    assert_golden(&actual, golden_path);
+}
+
+/// Formats `tokens` as C++ code and compares it with the golden file at
+/// `golden_path`.
+pub fn assert_cc_golden(tokens: TokenStream, golden_path: &str) {
+    let actual = cc_tokens_to_formatted_string_for_tests(tokens)
+        .expect("Failed to format the generated C++ code");
+    assert_golden(&actual, golden_path);
+}
+
+/// Compares `actual` with the golden file at `golden_path` (both normalized
+/// with `normalize`), or overwrites the golden file if `WRITE_GOLDENS` is set.
+pub fn assert_golden(actual: &str, golden_path: &str) {
+    let write_goldens = std::env::var_os("WRITE_GOLDENS").is_some();
+    if let Err(message) = check_golden(actual, Path::new(golden_path), write_goldens) {
+        panic!("{message}");
+    }
+}
+
+/// Normalizes generated source code, so that golden files don't depend on
+/// insignificant whitespace: line endings become `\n`, trailing whitespace and
+/// leading/trailing blank lines are removed, and the text ends with a newline.
+pub fn normalize(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let first = lines.iter().position(|line| !line.is_empty());
+    let last = lines.iter().rposition(|line| !line.is_empty());
+    match (first, last) {
+        (Some(first), Some(last)) => lines[first..=last].join("\n") + "\n",
+        _ => String::new(),
+    }
+}
+
+fn check_golden(actual: &str, golden_path: &Path, write_goldens: bool) -> Result<(), String> {
+    let actual = normalize(actual);
+    if write_goldens {
+        return fs::write(golden_path, actual)
+            .map_err(|err| format!("Failed to write {}: {err}", golden_path.display()));
+    }
+    let golden = fs::read_to_string(golden_path).map_err(|err| {
+        format!(
+            "Failed to read the golden file {} (is it in the `data` of the test?): {err}\n\
+             To create it, run the test with `WRITE_GOLDENS=1`.",
+            golden_path.display()
+        )
+    })?;
+    let golden = normalize(&golden);
+    if golden == actual {
+        return Ok(());
+    }
+    Err(format!(
+        "The generated code doesn't match the golden file {}:\n\n{}\n\
+         If the change is expected, run the test with `WRITE_GOLDENS=1` to update the golden file.",
+        golden_path.display(),
+        line_diff(&golden, &actual)
+    ))
+}
+
+/// Returns a diff of `expected` and `actual` with a single hunk, which spans
+/// from the first to the last differing line.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let common_prefix = expected.iter().zip(&actual).take_while(|(e, a)| e == a).count();
+    let common_suffix = expected[common_prefix..]
+        .iter()
+        .rev()
+        .zip(actual[common_prefix..].iter().rev())
+        .take_while(|(e, a)| e == a)
+        .count();
+    let context_start = common_prefix.saturating_sub(DIFF_CONTEXT_LINES);
+    let context_end = common_suffix.min(DIFF_CONTEXT_LINES);
+
+    let mut diff =
+        format!("--- golden\n+++ actual\n@@ -{} +{} @@\n", context_start + 1, context_start + 1);
+    for line in &expected[context_start..common_prefix] {
+        diff += &format!(" {line}\n");
+    }
+    for line in &expected[common_prefix..expected.len() - common_suffix] {
+        diff += &format!("-{line}\n");
+    }
+    for line in &actual[common_prefix..actual.len() - common_suffix] {
+        diff += &format!("+{line}\n");
+    }
+    let suffix_start = expected.len() - common_suffix;
+    for line in &expected[suffix_start..suffix_start + context_end] {
+        diff += &format!(" {line}\n");
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("\n\nfn f() {}  \r\n\n  let x;\t\n\n"), "fn f() {}\n\n  let x;\n");
+        assert_eq!(normalize(" \n\n"), "");
+    }
+
+    #[test]
+    fn test_check_golden_matches() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("golden.rs");
+        fs::write(&path, "fn f() {}\n").unwrap();
+        assert_eq!(check_golden("fn f() {}   \n\n", &path, false), Ok(()));
+    }
+
+    #[test]
+    fn test_check_golden_mismatch() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("golden.rs");
+        fs::write(&path, "a\nb\nc\nd\ne\nf\ng\nh\n").unwrap();
+        let message = check_golden("a\nb\nc\nd\nE\nf\ng\nh\n", &path, false).unwrap_err();
+        assert!(
+            message
+                .contains("--- golden\n+++ actual\n@@ -2 +2 @@\n b\n c\n d\n-e\n+E\n f\n g\n h\n"),
+            "{message}"
+        );
+        assert!(message.contains("WRITE_GOLDENS=1"), "{message}");
+    }
+
+    #[test]
+    fn test_check_golden_missing_file() {
+        let dir = tempdir().unwrap();
+        let message = check_golden("fn f() {}", &dir.path().join("missing.rs"), false).unwrap_err();
+        assert!(message.contains("Failed to read the golden file"), "{message}");
+    }
+
+    #[test]
+    fn test_check_golden_write_goldens() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("golden.rs");
+        fs::write(&path, "outdated\n").unwrap();
+        assert_eq!(check_golden("fn f() {}  \n", &path, true), Ok(()));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fn f() {}\n");
+    }
+
+    #[test]
+    fn test_line_diff_insertion_at_end() {
+        assert_eq!(
+            line_diff("a\nb\n", "a\nb\nc\n"),
+            "--- golden\n+++ actual\n@@ -1 +1 @@\n a\n b\n+c\n"
+        );
+    }
+
+    #[test]
+    fn test_line_diff_removal_at_start() {
+        assert_eq!(line_diff("a\nb\n", "b\n"), "--- golden\n+++ actual\n@@ -1 +1 @@\n-a\n b\n");
+    }
+
+    #[test]
+    fn test_assert_rs_golden() {
+        assert_rs_golden(
+            quote! {
+                pub struct SomeStruct {
+                    pub field: i32,
+                }
+                impl SomeStruct {
+                    pub fn method(&self) -> i32 { self.field }
+                }
+            },
+            "common/golden_testing_test.rs.golden",
+        );
+    }
+}
@@ -119,6 +119,9 @@ pub fn cc_tokens_to_formatted_string_for_tests(tokens: TokenStream) -> Result<St
 ///   placeholder `__SPACE__`.
 /// * `TokenStream` cannot encode comments, so we use the placeholder
 ///   `__COMMENT__`, followed by a string literal.
+/// * Source code that can't be represented as a `TokenStream` (e.g.
+///   hand-written C++ code) can be inserted as-is with the placeholder
+///   `__VERBATIM__`, followed by a string literal.
 pub fn write_unformatted_tokens(
     result: &mut impl std::fmt::Write,
     tokens: TokenStream,
@@ -142,6 +145,14 @@ pub fn write_unformatted_tokens(
                     bail!("__COMMENT__ must be followed by a literal")
                 }
             }
+            TokenTree::Ident(ref tt) if tt == "__VERBATIM__" => {
+                if let Some(TokenTree::Literal(lit)) = it.next() {
+                    let text = syn::parse_str::<syn::LitStr>(&lit.to_string())?.value();
+                    writeln!(result, "{}", text.trim_end())?;
+                } else {
+                    bail!("__VERBATIM__ must be followed by a string literal")
+                }
+            }
             TokenTree::Group(ref tt) => {
                 let (open_delimiter, closed_delimiter) = match tt.delimiter() {
                     Delimiter::Parenthesis => ("(", ")"),
@@ -378,6 +389,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_verbatim() -> Result<()> {
+        let token_stream = quote! { a __NEWLINE__ __VERBATIM__ "#define  X \"x\"\n" b };
+        assert_eq!("a\n#define  X \"x\"\nb", tokens_to_string(token_stream)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_verbatim() -> Result<()> {
+        assert!(tokens_to_string(quote! { __VERBATIM__ }).is_err());
+        assert!(tokens_to_string(quote! { __VERBATIM__ ident }).is_err());
+        assert!(tokens_to_string(quote! { __VERBATIM__ 123 }).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_doc_comment() -> Result<()> {
         // token_stream_printer (and rustfmt) don't put a space between /// and the doc
@@ -0,0 +1,385 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! The TOML config file of the `crubit` command-line tool.
+//!
+//! Example:
+//!
+//! ```toml
+//! [tools]
+//! rs_bindings_from_cc = "bazel-bin/rs_bindings_from_cc/rs_bindings_from_cc"
+//!
+//! [rs_from_cc]
+//! target = "//foo:bar"
+//! headers = ["foo/bar.h"]
+//! clang_args = ["-I."]
+//! rs_out = "out/bar_rs_api.rs"
+//! cc_out = "out/bar_rs_api_impl.cc"
+//!
+//! [cc_from_rs]
+//! crate_root = "foo/lib.rs"
+//! rustc_args = ["--edition=2021"]
+//! h_out = "out/foo_cc_api.h"
+//! rs_out = "out/foo_cc_api_impl.rs"
+//! ```
+//!
+//! Relative paths in the config are relative to the directory of the config
+//! file.
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// The default of `crubit_support_path_format` (see `--help` of the
+/// generators).
+const DEFAULT_CRUBIT_SUPPORT_PATH_FORMAT: &str = "\"crubit/support/{header}\"";
+
+/// The features that can be enabled for a target of `rs_bindings_from_cc`
+/// (`CrubitFeature` in `ir.rs`).
+const RS_FROM_CC_FEATURES: &[&str] = &["supported", "experimental"];
+
+/// The features that can be enabled for `cc_bindings_from_rs`.
+const CC_FROM_RS_FEATURES: &[&str] = &["experimental"];
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The executables run by `crubit`.
+    #[serde(default)]
+    pub tools: Tools,
+
+    /// The inputs and outputs of `crubit rs-from-cc`.
+    pub rs_from_cc: Option<RsFromCcConfig>,
+
+    /// The inputs and outputs of `crubit cc-from-rs`.
+    pub cc_from_rs: Option<CcFromRsConfig>,
+}
+
+/// The executables run by `crubit`. Executables that are not specified are
+/// looked up in `PATH`.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tools {
+    pub rs_bindings_from_cc: Option<PathBuf>,
+    pub cc_bindings_from_rs: Option<PathBuf>,
+    pub clang_format: Option<PathBuf>,
+    pub rustfmt: Option<PathBuf>,
+
+    /// The `rustfmt.toml` used to format the generated Rust code.
+    pub rustfmt_config: Option<PathBuf>,
+}
+
+/// Bindings for a C++ library, generated by `rs_bindings_from_cc`.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RsFromCcConfig {
+    /// The label of the C++ library, e.g. `//foo:bar`. It doesn't have to be
+    /// a Bazel target, but it determines the name of the generated crate.
+    pub target: String,
+
+    /// The public headers of the library, as they are `#include`d.
+    pub headers: Vec<String>,
+
+    /// The arguments used to parse the headers (e.g. `-I` and `-D` flags).
+    #[serde(default)]
+    pub clang_args: Vec<String>,
+
+    /// The Crubit features enabled for the library (e.g. `experimental`).
+    #[serde(default = "default_rs_from_cc_features")]
+    pub features: Vec<String>,
+
+    #[serde(default = "default_crubit_support_path_format")]
+    pub crubit_support_path_format: String,
+
+    pub rs_out: PathBuf,
+    pub cc_out: PathBuf,
+    pub ir_out: Option<PathBuf>,
+    pub error_report_out: Option<PathBuf>,
+}
+
+/// Bindings for a Rust crate, generated by `cc_bindings_from_rs`.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CcFromRsConfig {
+    /// The root source file of the crate (e.g. `lib.rs`).
+    pub crate_root: PathBuf,
+
+    /// Additional `rustc` arguments used to compile the crate (e.g.
+    /// `--extern` flags and the `--sysroot`).
+    #[serde(default)]
+    pub rustc_args: Vec<String>,
+
+    /// The Crubit features enabled for the crate (e.g. `experimental`).
+    #[serde(default)]
+    pub features: Vec<String>,
+
+    #[serde(default = "default_crubit_support_path_format")]
+    pub crubit_support_path_format: String,
+
+    pub h_out: PathBuf,
+    pub rs_out: PathBuf,
+    pub error_report_out: Option<PathBuf>,
+}
+
+fn default_rs_from_cc_features() -> Vec<String> {
+    vec!["supported".to_string()]
+}
+
+fn default_crubit_support_path_format() -> String {
+    DEFAULT_CRUBIT_SUPPORT_PATH_FORMAT.to_string()
+}
+
+impl Config {
+    /// Reads the config from `path`, with relative paths resolved against the
+    /// directory of `path`.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the config file {}", path.display()))?;
+        let config = Self::parse(&text)
+            .with_context(|| format!("Invalid config file {}", path.display()))?;
+        Ok(config.relative_to(path.parent().unwrap_or(Path::new(""))))
+    }
+
+    /// Parses and validates the text of a config file.
+    pub fn parse(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text)?;
+        if let Some(rs_from_cc) = &config.rs_from_cc {
+            ensure!(!rs_from_cc.target.is_empty(), "`rs_from_cc.target` must not be empty");
+            ensure!(!rs_from_cc.headers.is_empty(), "`rs_from_cc.headers` must not be empty");
+            validate_features("rs_from_cc", &rs_from_cc.features, RS_FROM_CC_FEATURES)?;
+            validate_crubit_support_path_format(
+                "rs_from_cc",
+                &rs_from_cc.crubit_support_path_format,
+            )?;
+        }
+        if let Some(cc_from_rs) = &config.cc_from_rs {
+            validate_features("cc_from_rs", &cc_from_rs.features, CC_FROM_RS_FEATURES)?;
+            validate_crubit_support_path_format(
+                "cc_from_rs",
+                &cc_from_rs.crubit_support_path_format,
+            )?;
+        }
+        Ok(config)
+    }
+
+    /// Resolves the relative paths in the config against `dir`.
+    fn relative_to(mut self, dir: &Path) -> Self {
+        let resolve = |path: &mut PathBuf| *path = dir.join(&*path);
+        let resolve_opt = |path: &mut Option<PathBuf>| path.iter_mut().for_each(resolve);
+        // Tools given by name rather than by path are looked up in `PATH`.
+        let resolve_tool = |path: &mut Option<PathBuf>| {
+            if path.as_ref().is_some_and(|path| path.components().count() > 1) {
+                resolve_opt(path);
+            }
+        };
+        resolve_tool(&mut self.tools.rs_bindings_from_cc);
+        resolve_tool(&mut self.tools.cc_bindings_from_rs);
+        resolve_tool(&mut self.tools.clang_format);
+        resolve_tool(&mut self.tools.rustfmt);
+        resolve_opt(&mut self.tools.rustfmt_config);
+        if let Some(rs_from_cc) = &mut self.rs_from_cc {
+            resolve(&mut rs_from_cc.rs_out);
+            resolve(&mut rs_from_cc.cc_out);
+            resolve_opt(&mut rs_from_cc.ir_out);
+            resolve_opt(&mut rs_from_cc.error_report_out);
+        }
+        if let Some(cc_from_rs) = &mut self.cc_from_rs {
+            resolve(&mut cc_from_rs.crate_root);
+            resolve(&mut cc_from_rs.h_out);
+            resolve(&mut cc_from_rs.rs_out);
+            resolve_opt(&mut cc_from_rs.error_report_out);
+        }
+        self
+    }
+}
+
+fn validate_features(section: &str, features: &[String], known_features: &[&str]) -> Result<()> {
+    for feature in features {
+        if !known_features.contains(&feature.as_str()) {
+            bail!(
+                "Unknown feature `{feature}` in `{section}.features` (expected one of: {})",
+                known_features.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+fn validate_crubit_support_path_format(section: &str, format: &str) -> Result<()> {
+    ensure!(
+        format.contains("{header}"),
+        "Cannot find placeholder `{{header}}` in `{section}.crubit_support_path_format`"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_rs_from_cc_defaults() {
+        let config = Config::parse(
+            r#"
+            [rs_from_cc]
+            target = "//foo:bar"
+            headers = ["foo/bar.h"]
+            rs_out = "bar_rs_api.rs"
+            cc_out = "bar_rs_api_impl.cc"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.rs_from_cc.unwrap(),
+            RsFromCcConfig {
+                target: "//foo:bar".to_string(),
+                headers: vec!["foo/bar.h".to_string()],
+                clang_args: vec![],
+                features: vec!["supported".to_string()],
+                crubit_support_path_format: "\"crubit/support/{header}\"".to_string(),
+                rs_out: PathBuf::from("bar_rs_api.rs"),
+                cc_out: PathBuf::from("bar_rs_api_impl.cc"),
+                ir_out: None,
+                error_report_out: None,
+            }
+        );
+        assert_eq!(config.cc_from_rs, None);
+    }
+
+    #[test]
+    fn test_cc_from_rs_defaults() {
+        let config = Config::parse(
+            r#"
+            [cc_from_rs]
+            crate_root = "lib.rs"
+            h_out = "foo_cc_api.h"
+            rs_out = "foo_cc_api_impl.rs"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.cc_from_rs.unwrap(),
+            CcFromRsConfig {
+                crate_root: PathBuf::from("lib.rs"),
+                rustc_args: vec![],
+                features: vec![],
+                crubit_support_path_format: "\"crubit/support/{header}\"".to_string(),
+                h_out: PathBuf::from("foo_cc_api.h"),
+                rs_out: PathBuf::from("foo_cc_api_impl.rs"),
+                error_report_out: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_unknown_key() {
+        let err = Config::parse("[tools]\nclang = \"clang\"").unwrap_err();
+        assert!(format!("{err:#}").contains("unknown field `clang`"), "{err:#}");
+    }
+
+    #[test]
+    fn test_missing_output() {
+        let err = Config::parse(
+            r#"
+            [rs_from_cc]
+            target = "//foo:bar"
+            headers = ["foo/bar.h"]
+            rs_out = "bar_rs_api.rs"
+            "#,
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("missing field `cc_out`"), "{err:#}");
+    }
+
+    #[test]
+    fn test_no_headers() {
+        let err = Config::parse(
+            r#"
+            [rs_from_cc]
+            target = "//foo:bar"
+            headers = []
+            rs_out = "bar_rs_api.rs"
+            cc_out = "bar_rs_api_impl.cc"
+            "#,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "`rs_from_cc.headers` must not be empty");
+    }
+
+    #[test]
+    fn test_unknown_feature() {
+        let err = Config::parse(
+            r#"
+            [cc_from_rs]
+            crate_root = "lib.rs"
+            features = ["supported"]
+            h_out = "foo_cc_api.h"
+            rs_out = "foo_cc_api_impl.rs"
+            "#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown feature `supported` in `cc_from_rs.features` (expected one of: experimental)"
+        );
+    }
+
+    #[test]
+    fn test_invalid_crubit_support_path_format() {
+        let err = Config::parse(
+            r#"
+            [cc_from_rs]
+            crate_root = "lib.rs"
+            crubit_support_path_format = "<crubit/support>"
+            h_out = "foo_cc_api.h"
+            rs_out = "foo_cc_api_impl.rs"
+            "#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot find placeholder `{header}` in `cc_from_rs.crubit_support_path_format`"
+        );
+    }
+
+    #[test]
+    fn test_relative_paths() {
+        let config = Config::parse(
+            r#"
+            [tools]
+            rs_bindings_from_cc = "bin/rs_bindings_from_cc"
+            rustfmt = "rustfmt"
+            rustfmt_config = "rustfmt.toml"
+
+            [rs_from_cc]
+            target = "//foo:bar"
+            headers = ["foo/bar.h"]
+            rs_out = "bar_rs_api.rs"
+            cc_out = "/tmp/bar_rs_api_impl.cc"
+            "#,
+        )
+        .unwrap()
+        .relative_to(Path::new("/work"));
+        assert_eq!(
+            config.tools,
+            Tools {
+                rs_bindings_from_cc: Some(PathBuf::from("/work/bin/rs_bindings_from_cc")),
+                cc_bindings_from_rs: None,
+                clang_format: None,
+                rustfmt: Some(PathBuf::from("rustfmt")),
+                rustfmt_config: Some(PathBuf::from("/work/rustfmt.toml")),
+            }
+        );
+        let rs_from_cc = config.rs_from_cc.unwrap();
+        assert_eq!(rs_from_cc.rs_out, PathBuf::from("/work/bar_rs_api.rs"));
+        assert_eq!(rs_from_cc.cc_out, PathBuf::from("/tmp/bar_rs_api_impl.cc"));
+        // Headers are resolved by Clang, using the include paths.
+        assert_eq!(rs_from_cc.headers, vec!["foo/bar.h".to_string()]);
+    }
+}
@@ -0,0 +1,274 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! `crubit` runs the bindings generators outside of Bazel, with the inputs and
+//! outputs described in a TOML config file (see `config.rs`):
+//!
+//! * `crubit rs-from-cc` generates Rust bindings for C++ headers (using
+//!   `rs_bindings_from_cc`),
+//! * `crubit cc-from-rs` generates C++ bindings for a Rust crate (using
+//!   `cc_bindings_from_rs`).
+//!
+//! The generators link against Clang and `rustc` respectively, so they are run
+//! as subprocesses, with the command-line flags that the Bazel rules would
+//! pass to them.
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use config::{CcFromRsConfig, Config, RsFromCcConfig, Tools};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process;
+
+#[derive(Debug, Parser)]
+#[clap(name = "crubit")]
+#[clap(about = "Generates C++/Rust bindings outside of Bazel", long_about = None)]
+struct Cmdline {
+    /// Path to the config file.
+    #[clap(long, value_parser, value_name = "FILE", default_value = "crubit.toml")]
+    config: PathBuf,
+
+    /// Print the command line of the generator, instead of running it.
+    #[clap(long)]
+    dry_run: bool,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generates Rust bindings for the C++ headers in the `[rs_from_cc]` section
+    /// of the config.
+    RsFromCc,
+
+    /// Generates C++ bindings for the Rust crate in the `[cc_from_rs]` section
+    /// of the config.
+    CcFromRs,
+}
+
+/// The command line of a generator.
+#[derive(Debug, PartialEq)]
+struct GeneratorCommand {
+    program: PathBuf,
+    args: Vec<OsString>,
+}
+
+impl GeneratorCommand {
+    fn new(program: &Option<PathBuf>, default_program: &str) -> Self {
+        let program = program.clone().unwrap_or_else(|| PathBuf::from(default_program));
+        Self { program, args: vec![] }
+    }
+
+    fn arg(&mut self, arg: impl Into<OsString>) {
+        self.args.push(arg.into());
+    }
+
+    /// Adds `--{name}={value}`.
+    fn flag(&mut self, name: &str, value: impl AsRef<Path>) {
+        let mut arg = OsString::from(format!("--{name}="));
+        arg.push(value.as_ref());
+        self.arg(arg);
+    }
+
+    fn run(&self) -> Result<()> {
+        let status = process::Command::new(&self.program)
+            .args(&self.args)
+            .status()
+            .with_context(|| format!("Failed to run {}", self.program.display()))?;
+        if !status.success() {
+            bail!("{} failed ({status})", self.program.display());
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for GeneratorCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.program.display())?;
+        for arg in &self.args {
+            write!(f, " {}", arg.to_string_lossy())?;
+        }
+        Ok(())
+    }
+}
+
+fn clang_format(tools: &Tools) -> PathBuf {
+    tools.clang_format.clone().unwrap_or_else(|| PathBuf::from("clang-format"))
+}
+
+fn rustfmt(tools: &Tools) -> PathBuf {
+    tools.rustfmt.clone().unwrap_or_else(|| PathBuf::from("rustfmt"))
+}
+
+fn rs_from_cc_command(tools: &Tools, config: &RsFromCcConfig) -> GeneratorCommand {
+    let mut command = GeneratorCommand::new(&tools.rs_bindings_from_cc, "rs_bindings_from_cc");
+    let target_args = serde_json::json!([{
+        "t": config.target,
+        "h": config.headers,
+        "f": config.features,
+    }]);
+    command.flag("target", &config.target);
+    command.flag("target_args", target_args.to_string());
+    command.flag("public_headers", config.headers.join(","));
+    command.flag("rs_out", &config.rs_out);
+    command.flag("cc_out", &config.cc_out);
+    if let Some(ir_out) = &config.ir_out {
+        command.flag("ir_out", ir_out);
+    }
+    if let Some(error_report_out) = &config.error_report_out {
+        command.flag("error_report_out", error_report_out);
+    }
+    command.flag("crubit_support_path_format", &config.crubit_support_path_format);
+    command.flag("clang_format_exe_path", clang_format(tools));
+    command.flag("rustfmt_exe_path", rustfmt(tools));
+    if let Some(rustfmt_config) = &tools.rustfmt_config {
+        command.flag("rustfmt_config_path", rustfmt_config);
+    }
+    // The remaining arguments are passed to Clang.
+    command.arg("--");
+    command.args.extend(config.clang_args.iter().map(OsString::from));
+    command
+}
+
+fn cc_from_rs_command(tools: &Tools, config: &CcFromRsConfig) -> GeneratorCommand {
+    let mut command = GeneratorCommand::new(&tools.cc_bindings_from_rs, "cc_bindings_from_rs");
+    command.flag("h-out", &config.h_out);
+    command.flag("rs-out", &config.rs_out);
+    if let Some(error_report_out) = &config.error_report_out {
+        command.flag("error-report-out", error_report_out);
+    }
+    command.flag("crubit-support-path-format", &config.crubit_support_path_format);
+    command.flag("clang-format-exe-path", clang_format(tools));
+    command.flag("rustfmt-exe-path", rustfmt(tools));
+    if let Some(rustfmt_config) = &tools.rustfmt_config {
+        command.flag("rustfmt-config-path", rustfmt_config);
+    }
+    if config.features.iter().any(|feature| feature == "experimental") {
+        command.arg("--experimental-features");
+    }
+    // The remaining arguments are passed to `rustc`.
+    command.arg("--");
+    command.arg("--crate-type=lib");
+    command.args.extend(config.rustc_args.iter().map(OsString::from));
+    command.arg(&config.crate_root);
+    command
+}
+
+fn generator_command(cmdline: &Cmdline, config: &Config) -> Result<GeneratorCommand> {
+    Ok(match cmdline.command {
+        Command::RsFromCc => {
+            let Some(rs_from_cc) = &config.rs_from_cc else {
+                bail!("The config has no `[rs_from_cc]` section");
+            };
+            rs_from_cc_command(&config.tools, rs_from_cc)
+        }
+        Command::CcFromRs => {
+            let Some(cc_from_rs) = &config.cc_from_rs else {
+                bail!("The config has no `[cc_from_rs]` section");
+            };
+            cc_from_rs_command(&config.tools, cc_from_rs)
+        }
+    })
+}
+
+fn main() -> Result<()> {
+    let cmdline = Cmdline::parse();
+    let config = Config::from_file(&cmdline.config)?;
+    let command = generator_command(&cmdline, &config)?;
+    if cmdline.dry_run {
+        println!("{command}");
+        return Ok(());
+    }
+    command.run()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command_line(cmdline: &[&str], config: &str) -> Result<String> {
+        let cmdline = Cmdline::try_parse_from(cmdline)?;
+        let command = generator_command(&cmdline, &Config::parse(config)?)?;
+        Ok(command.to_string())
+    }
+
+    #[test]
+    fn test_rs_from_cc() {
+        let config = r#"
+            [tools]
+            rs_bindings_from_cc = "/bin/rs_bindings_from_cc"
+            clang_format = "/bin/clang-format"
+
+            [rs_from_cc]
+            target = "//foo:bar"
+            headers = ["foo/bar.h", "foo/baz.h"]
+            clang_args = ["-I.", "-DNDEBUG"]
+            features = ["supported", "experimental"]
+            rs_out = "/out/bar_rs_api.rs"
+            cc_out = "/out/bar_rs_api_impl.cc"
+            error_report_out = "/out/errors.json"
+        "#;
+        assert_eq!(
+            command_line(&["crubit", "rs-from-cc"], config).unwrap(),
+            "/bin/rs_bindings_from_cc \
+             --target=//foo:bar \
+             --target_args=[{\"f\":[\"supported\",\"experimental\"],\"h\":[\"foo/bar.h\",\"foo/baz.h\"],\"t\":\"//foo:bar\"}] \
+             --public_headers=foo/bar.h,foo/baz.h \
+             --rs_out=/out/bar_rs_api.rs \
+             --cc_out=/out/bar_rs_api_impl.cc \
+             --error_report_out=/out/errors.json \
+             --crubit_support_path_format=\"crubit/support/{header}\" \
+             --clang_format_exe_path=/bin/clang-format \
+             --rustfmt_exe_path=rustfmt \
+             -- -I. -DNDEBUG"
+        );
+    }
+
+    #[test]
+    fn test_cc_from_rs() {
+        let config = r#"
+            [tools]
+            rustfmt_config = "/etc/rustfmt.toml"
+
+            [cc_from_rs]
+            crate_root = "/src/lib.rs"
+            rustc_args = ["--edition=2021"]
+            features = ["experimental"]
+            crubit_support_path_format = "<crubit/support/{header}>"
+            h_out = "/out/foo_cc_api.h"
+            rs_out = "/out/foo_cc_api_impl.rs"
+        "#;
+        assert_eq!(
+            command_line(&["crubit", "cc-from-rs"], config).unwrap(),
+            "cc_bindings_from_rs \
+             --h-out=/out/foo_cc_api.h \
+             --rs-out=/out/foo_cc_api_impl.rs \
+             --crubit-support-path-format=<crubit/support/{header}> \
+             --clang-format-exe-path=clang-format \
+             --rustfmt-exe-path=rustfmt \
+             --rustfmt-config-path=/etc/rustfmt.toml \
+             --experimental-features \
+             -- --crate-type=lib --edition=2021 /src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn test_missing_section() {
+        let err = command_line(&["crubit", "cc-from-rs"], "").unwrap_err();
+        assert_eq!(err.to_string(), "The config has no `[cc_from_rs]` section");
+    }
+
+    #[test]
+    fn test_missing_subcommand() {
+        assert!(command_line(&["crubit"], "").is_err());
+    }
+
+    #[test]
+    fn test_failing_generator() {
+        let command = GeneratorCommand { program: PathBuf::from("false"), args: vec![] };
+        let err = command.run().unwrap_err();
+        assert!(err.to_string().starts_with("false failed"), "{err}");
+    }
+}
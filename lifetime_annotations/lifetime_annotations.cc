@@ -36,6 +36,7 @@
 #include "clang/Lex/Pragma.h"
 #include "clang/Lex/Preprocessor.h"
 #include "llvm/ADT/DenseSet.h"
+#include "llvm/ADT/STLExtras.h"
 #include "llvm/ADT/SmallVector.h"
 #include "llvm/ADT/StringRef.h"
 #include "llvm/Support/Error.h"
@@ -129,6 +130,36 @@ llvm::Expected<FunctionLifetimes> ParseLifetimeAnnotations(
   return ParseLifetimeAnnotations(func, symbol_table, lifetimes.str());
 }
 
+// Returns whether the implicit object parameter of `func` is annotated with
+// `[[clang::lifetimebound]]` (which appertains to the function type).
+bool ImplicitObjectParamIsLifetimeBound(const clang::FunctionDecl* func) {
+  const clang::TypeSourceInfo* type_source_info = func->getTypeSourceInfo();
+  if (type_source_info == nullptr) return false;
+  for (clang::TypeLoc type_loc = type_source_info->getTypeLoc();;) {
+    auto attributed_type_loc = type_loc.getAs<clang::AttributedTypeLoc>();
+    if (!attributed_type_loc) return false;
+    if (attributed_type_loc.getAttrAs<clang::LifetimeBoundAttr>()) return true;
+    type_loc = attributed_type_loc.getModifiedLoc();
+  }
+}
+
+// Returns whether `func` or any of its parameters is annotated with
+// `[[clang::lifetimebound]]`.
+bool HasLifetimeBoundInputs(const clang::FunctionDecl* func) {
+  if (ImplicitObjectParamIsLifetimeBound(func)) return true;
+  for (const clang::ParmVarDecl* param : func->parameters()) {
+    if (param->hasAttr<clang::LifetimeBoundAttr>()) return true;
+  }
+  return false;
+}
+
+// Returns whether `type` is a pointer or reference to an object (rather than to
+// a function).
+bool IsObjectPointerLike(clang::QualType type) {
+  clang::QualType pointee_type = PointeeType(type);
+  return !pointee_type.isNull() && !pointee_type->isFunctionType();
+}
+
 llvm::Expected<FunctionLifetimes> GetLifetimeAnnotationsInternal(
     const clang::FunctionDecl* func, LifetimeSymbolTable& symbol_table,
     bool elision_enabled) {
@@ -153,9 +184,10 @@ llvm::Expected<FunctionLifetimes> GetLifetimeAnnotationsInternal(
 
   class Factory : public FunctionLifetimeFactory {
    public:
-    Factory(bool elision_enabled, const clang::FunctionDecl* func,
-            LifetimeSymbolTable& symbol_table)
+    Factory(bool elision_enabled, bool lifetimebound,
+            const clang::FunctionDecl* func, LifetimeSymbolTable& symbol_table)
         : elision_enabled_(elision_enabled),
+          lifetimebound_(lifetimebound),
           func_(func),
           symbol_table_(symbol_table) {}
 
@@ -185,7 +217,10 @@ llvm::Expected<FunctionLifetimes> GetLifetimeAnnotationsInternal(
         // correct in this case: the object must be valid for the duration
         // of the call, or else the behavior is undefined. So we can infer
         // safely even if elision is disabled.
-        if (!elision_enabled_ &&
+        //
+        // Functions with `[[clang::lifetimebound]]` inputs get fresh input
+        // lifetimes as well, as if elision was enabled for them.
+        if (!elision_enabled_ && !lifetimebound_ &&
             func_->getDeclName().getNameKind() !=
                 clang::DeclarationName::CXXDestructorName) {
           return llvm::make_error<LifetimeError>(
@@ -267,6 +302,29 @@ llvm::Expected<FunctionLifetimes> GetLifetimeAnnotationsInternal(
       }
     }
 
+    // Returns the lifetimes of the objects that the inputs annotated with
+    // `[[clang::lifetimebound]]` refer to. Annotated inputs which are not
+    // pointers or references are ignored.
+    llvm::SmallVector<Lifetime> GetLifetimeBoundInputLifetimes(
+        const llvm::SmallVector<ValueLifetimes>& param_lifetimes,
+        const std::optional<ValueLifetimes>& this_lifetimes) const {
+      llvm::SmallVector<Lifetime> result;
+      auto add = [&result](Lifetime lifetime) {
+        if (!llvm::is_contained(result, lifetime)) result.push_back(lifetime);
+      };
+      if (this_lifetimes.has_value() &&
+          ImplicitObjectParamIsLifetimeBound(func_)) {
+        add(this_lifetimes->GetPointeeLifetimes().GetLifetime());
+      }
+      for (unsigned i = 0; i < func_->getNumParams(); ++i) {
+        if (func_->getParamDecl(i)->hasAttr<clang::LifetimeBoundAttr>() &&
+            IsObjectPointerLike(param_lifetimes[i].Type())) {
+          add(param_lifetimes[i].GetPointeeLifetimes().GetLifetime());
+        }
+      }
+      return result;
+    }
+
     llvm::Expected<ValueLifetimes> CreateReturnLifetimes(
         clang::QualType return_type, clang::TypeLoc return_type_loc,
         const llvm::SmallVector<ValueLifetimes>& param_lifetimes,
@@ -275,10 +333,16 @@ llvm::Expected<FunctionLifetimes> GetLifetimeAnnotationsInternal(
 
       std::optional<Lifetime> input_lifetime =
           GetSingleInputLifetime(param_lifetimes, this_lifetimes);
+      // `[[clang::lifetimebound]]` takes precedence over the elision rules.
+      llvm::SmallVector<Lifetime> lifetimebound_lifetimes;
+      if (lifetimebound_) {
+        lifetimebound_lifetimes =
+            GetLifetimeBoundInputLifetimes(param_lifetimes, this_lifetimes);
+      }
 
       return ValueLifetimes::Create(
           return_type, return_type_loc,
-          [&input_lifetime,
+          [&input_lifetime, &lifetimebound_lifetimes,
            this](const clang::Expr* name) -> llvm::Expected<Lifetime> {
             if (name) {
               Lifetime lifetime;
@@ -288,6 +352,21 @@ llvm::Expected<FunctionLifetimes> GetLifetimeAnnotationsInternal(
               return lifetime;
             }
 
+            if (lifetimebound_lifetimes.size() == 1) {
+              return lifetimebound_lifetimes.front();
+            }
+            if (lifetimebound_lifetimes.size() > 1) {
+              // The output would have to be bound to all of the inputs, which
+              // we can't express with a single lifetime that the inputs don't
+              // share.
+              return llvm::make_error<LifetimeError>(
+                  LifetimeError::Type::CannotElideOutputLifetimes,
+                  absl::StrCat("Cannot elide output lifetimes for '",
+                               func_->getNameAsString(),
+                               "' because it has more than one "
+                               "lifetimebound input"));
+            }
+
             if (!elision_enabled_) {
               return llvm::make_error<LifetimeError>(
                   LifetimeError::Type::ElisionNotEnabled,
@@ -313,11 +392,19 @@ llvm::Expected<FunctionLifetimes> GetLifetimeAnnotationsInternal(
     }
 
     bool elision_enabled_;
+    bool lifetimebound_;
     const clang::FunctionDecl* func_;
     LifetimeSymbolTable& symbol_table_;
   };
 
-  Factory factory(elision_enabled, func, symbol_table);
+  // `[[clang::lifetimebound]]` says which inputs the output lifetimes come
+  // from, so functions using it get lifetimes even without the
+  // `lifetime_elision` pragma. This doesn't apply to constructors, whose
+  // lifetimebound parameters are bound to the constructed object (which a
+  // function lifetime can't express).
+  bool lifetimebound = !clang::isa<clang::CXXConstructorDecl>(func) &&
+                       HasLifetimeBoundInputs(func);
+  Factory factory(elision_enabled, lifetimebound, func, symbol_table);
   return FunctionLifetimes::CreateForDecl(func, factory);
 }
 }  // namespace
@@ -30,6 +30,11 @@ struct LifetimeAnnotationContext {
 // If the file containing the function definition specifies the
 // `lifetime_elision` pragma, lifetime elision rules are used to determine
 // any unannotated lifetimes.
+// If the function (other than a constructor) has a parameter or an implicit
+// object parameter annotated with `[[clang::lifetimebound]]`, unannotated
+// lifetimes are determined like with lifetime elision, even if the pragma was
+// not specified, except that the output lifetimes are the lifetime of the
+// object that the annotated parameter refers to.
 // Returns an error if the function contains unannotated lifetimes that could
 // not be determined through lifetime elision, either because the
 // `lifetime_elision`pragma was not specified or because the lifetime elision
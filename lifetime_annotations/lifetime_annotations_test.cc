@@ -426,6 +426,57 @@ TEST_F(LifetimeAnnotationsTest, LifetimeElision_FailureTooManyInputLifetimes) {
                     "that does not have exactly one input lifetime"}})));
 }
 
+TEST_F(LifetimeAnnotationsTest, LifetimeBound_Param) {
+  EXPECT_THAT(GetNamedLifetimeAnnotations(R"(
+        int* f(int* a [[clang::lifetimebound]], int* b);
+        int* g(int* a, int* b [[clang::lifetimebound]]);
+  )"),
+              IsOkAndHolds(
+                  LifetimesAre({{"f", "a, b -> a"}, {"g", "a, b -> b"}})));
+}
+
+TEST_F(LifetimeAnnotationsTest, LifetimeBound_ImplicitObjectParam) {
+  EXPECT_THAT(GetNamedLifetimeAnnotations(R"(
+        struct S {
+          int* method(int* p) [[clang::lifetimebound]];
+        };
+  )"),
+              IsOkAndHolds(LifetimesAre({{"S::method", "a: b -> a"}})));
+}
+
+TEST_F(LifetimeAnnotationsTest, LifetimeBound_TakesPrecedenceOverElision) {
+  EXPECT_THAT(GetNamedLifetimeAnnotations(R"(
+        #pragma clang lifetime_elision
+        struct S {
+          int* method(int* p [[clang::lifetimebound]]);
+        };
+  )"),
+              IsOkAndHolds(LifetimesAre({{"S::method", "a: b -> b"}})));
+}
+
+TEST_F(LifetimeAnnotationsTest, LifetimeBound_FailureMultipleInputs) {
+  EXPECT_THAT(GetNamedLifetimeAnnotations(R"(
+        int* f(int* a [[clang::lifetimebound]],
+               int* b [[clang::lifetimebound]]);
+  )"),
+              IsOkAndHolds(LifetimesAre(
+                  {{"f",
+                    "ERROR(CannotElideOutputLifetimes): Cannot elide output "
+                    "lifetimes for 'f' because it has more than one "
+                    "lifetimebound input"}})));
+}
+
+TEST_F(LifetimeAnnotationsTest, LifetimeBound_FailureConstructor) {
+  EXPECT_THAT(GetNamedLifetimeAnnotations(R"(
+        struct S {
+          S(int* p [[clang::lifetimebound]]);
+        };
+  )"),
+              IsOkAndHolds(LifetimesAre({{"S::S",
+                                          "ERROR(ElisionNotEnabled): Lifetime "
+                                          "elision not enabled for 'S'"}})));
+}
+
 TEST_F(LifetimeAnnotationsTest, LifetimeAnnotation_NoLifetimes) {
   EXPECT_THAT(GetNamedLifetimeAnnotations(R"_(
         [[clang::annotate("lifetimes", "()")]]
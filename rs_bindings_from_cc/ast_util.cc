@@ -7,15 +7,22 @@
 #include <optional>
 #include <string>
 
+#include "absl/algorithm/container.h"
 #include "absl/functional/function_ref.h"
+#include "absl/status/status.h"
+#include "absl/status/statusor.h"
+#include "absl/strings/ascii.h"
 #include "absl/strings/str_cat.h"
 #include "absl/strings/string_view.h"
+#include "clang/AST/ASTContext.h"
 #include "clang/AST/Attr.h"
 #include "clang/AST/DeclBase.h"
 #include "clang/AST/DeclTemplate.h"
+#include "clang/AST/Expr.h"
 #include "clang/AST/Type.h"
 #include "clang/Basic/AttrKinds.h"
 #include "clang/Basic/LLVM.h"
+#include "llvm/ADT/StringRef.h"
 
 namespace crubit {
 
@@ -96,4 +103,73 @@ std::optional<std::string> CollectUnknownTypeAttrs(
   return unknown_attr;
 }
 
+// Copied from lifetime_annotations/type_lifetimes.cc, which is expected to move
+// into ClangTidy. See:
+// https://discourse.llvm.org/t/rfc-lifetime-annotations-for-c/61377
+absl::StatusOr<absl::string_view> EvaluateAsStringLiteral(
+    const clang::Expr& expr, const clang::ASTContext& ast_context) {
+  auto error = []() {
+    return absl::InvalidArgumentError(
+        "cannot evaluate argument as a string literal");
+  };
+
+  clang::Expr::EvalResult eval_result;
+  if (!expr.EvaluateAsConstantExpr(eval_result, ast_context) ||
+      !eval_result.Val.isLValue()) {
+    return error();
+  }
+
+  const auto* eval_result_expr =
+      eval_result.Val.getLValueBase().dyn_cast<const clang::Expr*>();
+  if (!eval_result_expr) {
+    return error();
+  }
+
+  const auto* string_literal =
+      clang::dyn_cast<clang::StringLiteral>(eval_result_expr);
+  if (!string_literal) {
+    return error();
+  }
+
+  return {string_literal->getString()};
+}
+
+absl::StatusOr<const clang::AnnotateAttr*> GetAnnotateAttr(
+    const clang::Decl* decl, absl::string_view attribute) {
+  const clang::AnnotateAttr* found_attr = nullptr;
+  for (clang::AnnotateAttr* attr :
+       decl->specific_attrs<clang::AnnotateAttr>()) {
+    if (attr->getAnnotation() != llvm::StringRef(attribute)) continue;
+
+    if (found_attr != nullptr)
+      return absl::InvalidArgumentError(
+          absl::StrCat("Only one `", attribute,
+                       "` attribute may be placed on a declaration."));
+    found_attr = attr;
+  }
+  return found_attr;
+}
+
+absl::StatusOr<std::optional<std::string>> GetRenamedFromAttribute(
+    const clang::Decl* decl) {
+  absl::StatusOr<const clang::AnnotateAttr*> attr =
+      GetAnnotateAttr(decl, "crubit_renamed_from");
+  if (!attr.ok()) return attr.status();
+  if (*attr == nullptr) return std::nullopt;
+  if ((*attr)->args_size() != 1)
+    return absl::InvalidArgumentError(
+        "The `crubit_renamed_from` attribute requires a single string literal "
+        "argument, the previous name of the declaration.");
+  absl::StatusOr<absl::string_view> name =
+      EvaluateAsStringLiteral(**(*attr)->args_begin(), decl->getASTContext());
+  if (!name.ok()) return name.status();
+  if (name->empty() || absl::ascii_isdigit(name->front()) ||
+      !absl::c_all_of(*name, [](char c) {
+        return absl::ascii_isalnum(c) || c == '_';
+      }))
+    return absl::InvalidArgumentError(
+        absl::StrCat("\"", *name, "\" is not a valid identifier."));
+  return std::string(*name);
+}
+
 }  // namespace crubit
@@ -9,8 +9,12 @@
 #include <string>
 
 #include "absl/functional/function_ref.h"
+#include "absl/status/statusor.h"
+#include "absl/strings/string_view.h"
+#include "clang/AST/ASTContext.h"
 #include "clang/AST/Attr.h"
 #include "clang/AST/DeclBase.h"
+#include "clang/AST/Expr.h"
 #include "clang/Basic/AttrKinds.h"
 
 namespace crubit {
@@ -37,6 +41,26 @@ std::optional<std::string> CollectUnknownTypeAttrs(
     const clang::Type& t, absl::FunctionRef<bool(clang::attr::Kind)> is_known =
                               [](clang::attr::Kind attr) { return false; });
 
+// Evaluates `expr` as a constant expression, and returns the string literal
+// that it evaluates to.
+absl::StatusOr<absl::string_view> EvaluateAsStringLiteral(
+    const clang::Expr& expr, const clang::ASTContext& ast_context);
+
+// Gets the `clang::annotate` attribute named `attribute` for `decl`, or
+// nullptr if there is none. Returns an error if there is more than one.
+//
+// `decl` must not be null.
+absl::StatusOr<const clang::AnnotateAttr*> GetAnnotateAttr(
+    const clang::Decl* decl, absl::string_view attribute);
+
+// Gets the `crubit_renamed_from` attribute of `decl`: the name that `decl` had
+// before it was renamed, or nullopt if there is no such attribute.
+//
+// The attribute takes a single string literal argument, which must be a valid
+// identifier.
+absl::StatusOr<std::optional<std::string>> GetRenamedFromAttribute(
+    const clang::Decl* decl);
+
 }  // namespace crubit
 
 #endif  // CRUBIT_RS_BINDINGS_FROM_CC_AST_UTIL_H_
@@ -31,9 +31,31 @@ def _deps_for_bindings_impl(ctx):
         DepsForBindingsInfo(
             deps_for_rs_file = dep_variant_infos,
             deps_for_cc_file = [dep[CcInfo] for dep in ctx.attr.deps_for_generated_cc_file],
+            used_with = [dep.label for dep in ctx.attr.used_with],
+            conditional_deps = [dep[DepsForBindingsInfo] for dep in ctx.attr.conditional_deps],
         ),
     ]
 
+def get_deps_for_bindings(deps_for_bindings_info, cc_deps):
+    """Returns the dependencies needed to compile the bindings of a C++ target.
+
+    Args:
+      deps_for_bindings_info: The DepsForBindingsInfo of the `deps_for_bindings` target.
+      cc_deps: The direct dependencies of the C++ target.
+
+    Returns:
+      tuple(deps_for_cc_file, deps_for_rs_file): The dependencies of the generated files,
+      including the `conditional_deps` whose `used_with` targets are in `cc_deps`.
+    """
+    cc_dep_labels = {dep.label: True for dep in cc_deps}
+    deps_for_cc_file = list(deps_for_bindings_info.deps_for_cc_file)
+    deps_for_rs_file = list(deps_for_bindings_info.deps_for_rs_file)
+    for conditional_deps in deps_for_bindings_info.conditional_deps:
+        if any([label in cc_dep_labels for label in conditional_deps.used_with]):
+            deps_for_cc_file += conditional_deps.deps_for_cc_file
+            deps_for_rs_file += conditional_deps.deps_for_rs_file
+    return (deps_for_cc_file, deps_for_rs_file)
+
 deps_for_bindings = rule(
     attrs = {
         "deps_for_generated_rs_file": attr.label_list(
@@ -44,6 +66,19 @@ deps_for_bindings = rule(
             doc = "C++ dependencies that are needed to compile the generated .cc file.",
             default = [],
         ),
+        "used_with": attr.label_list(
+            doc = """If not empty, the dependencies are only needed by the bindings of C++ targets
+                  that directly depend on one of these targets (e.g. the libraries that the
+                  bindings of `absl::Time` need are only needed for targets that depend on
+                  `@abseil-cpp//absl/time`).""",
+            default = [],
+        ),
+        "conditional_deps": attr.label_list(
+            doc = "`deps_for_bindings` targets with `used_with`, whose dependencies are added " +
+                  "for the C++ targets that use them.",
+            providers = [DepsForBindingsInfo],
+            default = [],
+        ),
     },
     implementation = _deps_for_bindings_impl,
 )
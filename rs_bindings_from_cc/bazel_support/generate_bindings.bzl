@@ -102,6 +102,12 @@ def generate_bindings(
             "--error_report_out",
             error_report_output.path,
         ]
+    version_stamp = ctx.attr._version_stamp[BuildSettingInfo].value
+    if version_stamp:
+        rs_bindings_from_cc_flags += [
+            "--version_stamp",
+            version_stamp,
+        ]
 
     # TODO(b/324159705): Remove this workaround and fix
     # built_in_include_directories logic once we switch to libc++ runtimes on
@@ -40,5 +40,9 @@ DepsForBindingsInfo = provider(
     fields = {
         "deps_for_rs_file": "list[DepVariantInfo]",
         "deps_for_cc_file": "list[CcInfo]",
+        "used_with": "list[Label]: If not empty, the dependencies are only needed by the " +
+                     "bindings of C++ targets that directly depend on one of these targets.",
+        "conditional_deps": "list[DepsForBindingsInfo]: Additional dependencies, with a " +
+                            "non-empty `used_with`.",
     },
 )
@@ -15,6 +15,10 @@ load(
     "@@//rs_bindings_from_cc/bazel_support:crubit_feature_hint.bzl",
     "find_crubit_features",
 )
+load(
+    "@@//rs_bindings_from_cc/bazel_support:deps_for_bindings.bzl",
+    "get_deps_for_bindings",
+)
 load(
     "@@//rs_bindings_from_cc/bazel_support:providers.bzl",
     "DepsForBindingsInfo",
@@ -199,6 +203,11 @@ def _rust_bindings_from_cc_aspect_impl(target, ctx):
         header_includes.append("-include")
         header_includes.append(hdr.path)
 
+    deps_for_cc_file, deps_for_rs_file = get_deps_for_bindings(
+        ctx.attr._deps_for_bindings[DepsForBindingsInfo],
+        all_deps,
+    )
+
     return generate_and_compile_bindings(
         ctx,
         ctx.rule.attr,
@@ -218,12 +227,12 @@ def _rust_bindings_from_cc_aspect_impl(target, ctx):
             for dep in all_deps
             if RustBindingsFromCcInfo in dep and
                dep[RustBindingsFromCcInfo].cc_info
-        ] + ctx.attr._deps_for_bindings[DepsForBindingsInfo].deps_for_cc_file,
+        ] + deps_for_cc_file,
         deps_for_rs_file = [
             dep[RustBindingsFromCcInfo].dep_variant_info
             for dep in all_deps
             if RustBindingsFromCcInfo in dep
-        ] + ctx.attr._deps_for_bindings[DepsForBindingsInfo].deps_for_rs_file,
+        ] + deps_for_rs_file,
         extra_cc_compilation_action_inputs = extra_cc_compilation_action_inputs,
         extra_rs_bindings_from_cc_cli_flags = collect_rust_bindings_from_cc_cli_flags(target, ctx),
     )
@@ -198,4 +198,7 @@ bindings_attrs = {
     "_generate_error_report": attr.label(
         default = "@@//rs_bindings_from_cc/bazel_support:generate_error_report",
     ),
+    "_version_stamp": attr.label(
+        default = "@@//rs_bindings_from_cc/bazel_support:version_stamp",
+    ),
 }
@@ -7,6 +7,7 @@
 #include <algorithm>
 #include <fstream>
 #include <iterator>
+#include <map>
 #include <sstream>
 #include <string>
 #include <utility>
@@ -67,13 +68,17 @@ ABSL_FLAG(std::vector<std::string>, public_headers, std::vector<std::string>(),
 ABSL_FLAG(std::string, target, "", "The target to generate bindings for.");
 ABSL_FLAG(std::string, target_args, "",
           "Per-target Crubit arguments, encoded as a JSON array. This contains "
-          "both the list of headers assigned to the target (h), and the set of "
-          "enabled features (f). For example:"
+          "both the list of headers assigned to the target (h), the set of "
+          "enabled features (f), optionally a target-specific "
+          "--crubit_support_path_format (s), and optionally the name of the "
+          "C++20 module exported by the target (m). For example:"
           "[\n"
           "  {\n"
           "     \"t\": \"//foo/bar:baz\",\n"
           "     \"h\": [\"foo/bar/header1.h\", \"foo/bar/header2.h\"],\n"
-          "     \"f\": [\"supported\"]\n"
+          "     \"f\": [\"supported\"],\n"
+          "     \"s\": \"<vendored/crubit/{header}>\",\n"
+          "     \"m\": \"foo.bar.baz\"\n"
           "  },\n"
           "...\n"
           "]");
@@ -92,9 +97,151 @@ ABSL_FLAG(std::string, namespaces_out, "",
           "namespace hierarchy.");
 ABSL_FLAG(std::string, error_report_out, "",
           "(optional) output path for the JSON error report");
+ABSL_FLAG(std::string, item_spans_out, "",
+          "(optional) output path for the JSON file mapping the IDs of the IR "
+          "items to the byte ranges of their bindings in the generated Rust "
+          "source code");
+ABSL_FLAG(std::string, bindings_status_out, "",
+          "(optional) output path for the JSON file listing whether each item "
+          "of the target gets bindings");
+ABSL_FLAG(std::string, stable_thunks_out, "",
+          "(optional) output path for the JSON file mapping the symbol names "
+          "of the thunks pinned with `CRUBIT_STABLE_THUNK` to their C++ "
+          "signatures");
+ABSL_FLAG(std::string, stable_thunks_baseline, "",
+          "(optional) path of a JSON file written to `--stable_thunks_out` by "
+          "a previous run. Bindings generation fails if a thunk pinned in the "
+          "file was removed or its signature changed, which would break "
+          "prebuilt `rs_api_impl` objects");
 ABSL_FLAG(bool, generate_source_location_in_doc_comment, true,
           "add the source code location from which the binding originates in"
           "the doc comment of the binding");
+ABSL_FLAG(bool, generate_raw_thunks_module, false,
+          "expose the `extern \"C\"` thunks used by the generated bindings "
+          "in a public `raw` submodule of the generated crate");
+ABSL_FLAG(bool, generate_mock_thunks, false,
+          "replace the `extern \"C\"` thunks with pure-Rust stubs that "
+          "dispatch to a `mock::CcThunks` implementation installed at runtime "
+          "(so that Rust tests can run without linking the C++ code)");
+ABSL_FLAG(std::string, thunk_export_macro, "",
+          "[optional] name of a macro (e.g. `MYLIB_EXPORT`) that the generated "
+          "C++ thunks should be annotated with. If empty, the thunks get "
+          "hidden visibility");
+ABSL_FLAG(bool, hash_thunk_names, false,
+          "prefix the names of the generated thunks with a hash of the target "
+          "label, to avoid symbol collisions when multiple versions of "
+          "bindings for the same header are linked into a single binary");
+ABSL_FLAG(bool, generate_layout_verification, false,
+          "generate a `crubit_verify_layouts()` function that re-checks the "
+          "layouts of the C++ records at runtime, against the layouts that "
+          "the C++ side of the bindings was compiled with");
+ABSL_FLAG(bool, generate_bytemuck_impls, false,
+          "implement `bytemuck::Zeroable` and `bytemuck::Pod` for the records "
+          "which are plain old data (trivially copyable, without padding, and "
+          "with only integer and floating point fields). The crate must then "
+          "depend on `bytemuck`");
+ABSL_FLAG(bool, generate_prelude_module, false,
+          "generate a `prelude` module re-exporting the records, enums and "
+          "free functions from all the namespaces of the target, so that they "
+          "can be imported with a single `use ...::prelude::*`. Items whose "
+          "names collide are not re-exported");
+ABSL_FLAG(bool, import_public_headers, false,
+          "make the generated C++ thunks `import` the public headers of the "
+          "target as C++20 header units, rather than `#include` them. If the "
+          "`--target_args` of the target name a C++20 module (m), the module "
+          "is imported instead of its headers");
+ABSL_FLAG(bool, generate_line_directives, false,
+          "precede the generated C++ thunks with `#line` directives pointing "
+          "at the declarations of the wrapped functions, so that compiler "
+          "errors in the thunks point at the original headers");
+ABSL_FLAG(bool, generate_cxx_extern_types, false,
+          "generate a `cxx_extern_types` module implementing "
+          "`cxx::ExternType` for the records and enums of the target, so that "
+          "they can be used in `#[cxx::bridge]` modules (e.g. while migrating "
+          "from `cxx`). The crate must then depend on `cxx`");
+ABSL_FLAG(std::string, bindgen_allowlist_file, "",
+          "[optional] regex matching the paths of the headers whose "
+          "namespaced records, enums, type aliases and functions should also "
+          "be exported from the crate root under `bindgen`-style names (e.g. "
+          "`foo_bar_Baz` for `foo::bar::Baz`), like `bindgen "
+          "--allowlist-file`. This eases migrating from `bindgen`");
+ABSL_FLAG(bool, minimize_public_header_includes, false,
+          "make the generated C++ thunks only include the public headers "
+          "declaring the functions and types that they use, rather than all "
+          "the public headers of the target, to reduce their compile time");
+ABSL_FLAG(bool, lazy_ir, false,
+          "only materialize the IR items that bindings generation actually "
+          "visits (e.g. not the records of dependencies that are never used), "
+          "to reduce the peak memory usage for very large IR inputs");
+ABSL_FLAG(std::string, thunk_exception_note, "",
+          "[optional] if set, the C++ thunks of the functions that aren't "
+          "`noexcept` catch all the exceptions and abort the program with "
+          "this note (in which `{function}` is replaced with the name of the "
+          "function that threw), rather than let the exceptions unwind into "
+          "Rust, which is undefined behavior. Requires C++ exceptions to be "
+          "enabled when compiling the thunks");
+ABSL_FLAG(crubit::OverloadNaming, overload_naming,
+          crubit::OverloadNaming::kNone,
+          "how to name the Rust bindings of overloaded functions: `none` "
+          "doesn't generate bindings for them, `param_types` suffixes their "
+          "names with the names of their parameter types (e.g. `f_c_int`), "
+          "and `ordinal` suffixes them with their ordinal among the overloads "
+          "except for the first one, like `bindgen` (e.g. `f`, `f1`)");
+ABSL_FLAG(std::string, rs_api_lints, "deny(warnings)",
+          "comma-separated lint attributes to apply to the generated Rust "
+          "crate, as `deny(...)`, `warn(...)` and `allow(...)` (e.g. "
+          "`warn(warnings),allow(dead_code)`). This can be set to an empty "
+          "string to compile the bindings without `#![deny(warnings)]`, e.g. "
+          "when newer versions of rustc add warnings");
+ABSL_FLAG(std::string, detail_module_name, "",
+          "[optional] name of the generated private module holding the "
+          "`extern \"C\"` thunks. Defaults to `detail`. This can be used to "
+          "avoid collisions with user code that re-exports into the crate "
+          "root");
+ABSL_FLAG(bool, strict_pointer_constness, false,
+          "whether functions with pointer or reference types whose Rust "
+          "bindings would not keep the `const` qualifiers of their C++ "
+          "pointees should not receive bindings (and be reported as errors)");
+ABSL_FLAG(bool, compact_layout_assertions, false,
+          "whether to generate the layout assertions of each record as a "
+          "single array-driven check in Rust and a single function of "
+          "`static_assert`s in C++, rather than one assertion per field. This "
+          "makes the generated files smaller, but the Rust failures don't name "
+          "the mismatched field");
+ABSL_FLAG(bool, abi_hash_symbols, false,
+          "whether to define a symbol encoding a hash of the layout of each "
+          "record in the generated C++ code, and to reference the symbols of "
+          "the records of the dependencies, so that linking bindings generated "
+          "from mismatched versions of a header fails");
+ABSL_FLAG(bool, convert_string_views, false,
+          "whether `std::string_view` and `absl::string_view` parameters and "
+          "return values should be converted into Rust byte slices, rather "
+          "than use the bindings of the string view classes");
+ABSL_FLAG(bool, string_view_params_as_str, false,
+          "whether `std::string_view` and `absl::string_view` parameters "
+          "should be bound as Rust `&str` rather than `&[u8]`");
+ABSL_FLAG(bool, record_ext_traits, false,
+          "whether to generate an extension trait for each record (e.g. "
+          "`SomeStructExt`), declaring its methods and implemented for the "
+          "record, so that Rust code can be written against the trait "
+          "rather than the generated type (e.g. to mock it)");
+ABSL_FLAG(bool, constructor_functions, false,
+          "whether `explicit` single-argument constructors and constructors "
+          "taking more than one argument should be bound as associated "
+          "functions (`from_<param>` and `new`) returning `Self`");
+ABSL_FLAG(std::string, attribute_translations, "",
+          "[optional] JSON object mapping the names of C++ attributes that "
+          "Crubit doesn't otherwise understand (e.g. `nodiscard` on a class) "
+          "to the Rust attribute that they translate to (e.g. `must_use`), "
+          "or to an empty string if they should be ignored (e.g. "
+          "`gnu::hot`). Items with such attributes don't require the "
+          "experimental Crubit features. These entries are added to (and "
+          "override) the built-in translations");
+ABSL_FLAG(std::string, version_stamp, "",
+          "[optional] version of Crubit (e.g. a revision stamped by the build "
+          "system) to embed into the generated Rust bindings as "
+          "`__crubit_metadata`, together with the generator options and a "
+          "hash of the IR. No metadata is generated if empty");
 
 namespace crubit {
 
@@ -104,6 +251,8 @@ struct TargetArgs {
   std::string target;
   std::vector<std::string> headers;
   std::vector<std::string> features;
+  std::string support_path_format;
+  std::string cc_module_name;
 };
 
 bool fromJSON(const llvm::json::Value& json, TargetArgs& out,
@@ -111,7 +260,9 @@ bool fromJSON(const llvm::json::Value& json, TargetArgs& out,
   llvm::json::ObjectMapper mapper(json, path);
   return mapper && mapper.map("t", out.target) &&
          mapper.mapOptional("h", out.headers) &&
-         mapper.mapOptional("f", out.features);
+         mapper.mapOptional("f", out.features) &&
+         mapper.mapOptional("s", out.support_path_format) &&
+         mapper.mapOptional("m", out.cc_module_name);
 }
 
 std::vector<HeaderName> PublicHeaders() {
@@ -172,6 +323,42 @@ absl::Status ParseTargetArgs(absl::string_view target_args_str,
       }
       args.target_to_features[BazelLabel(target)].insert(feature);
     }
+    if (!it.support_path_format.empty()) {
+      if (!absl::StrContains(it.support_path_format, "{header}")) {
+        return absl::InvalidArgumentError(
+            "Expected `s` (support path format) fields of `--target_args` to "
+            "contain the `{header}` placeholder");
+      }
+      args.target_to_crubit_support_path_format[BazelLabel(target)] =
+          it.support_path_format;
+    }
+    if (!it.cc_module_name.empty()) {
+      args.target_to_cc_module_name[BazelLabel(target)] = it.cc_module_name;
+    }
+  }
+  return absl::OkStatus();
+}
+
+absl::Status ParseAttributeTranslations(
+    absl::string_view attribute_translations_str, CmdlineArgs& args) {
+  if (attribute_translations_str.empty()) {
+    return absl::OkStatus();
+  }
+  auto attribute_translations =
+      llvm::json::parse<std::map<std::string, std::string>>(
+          attribute_translations_str);
+  if (auto err = attribute_translations.takeError()) {
+    return absl::InvalidArgumentError(
+        absl::StrCat("Malformed `--attribute_translations` argument: ",
+                     toString(std::move(err))));
+  }
+  for (auto& [cc_attribute, rs_attribute] : *attribute_translations) {
+    if (cc_attribute.empty()) {
+      return absl::InvalidArgumentError(
+          "Expected the keys of `--attribute_translations` to be non-empty "
+          "attribute names");
+    }
+    args.attribute_translations[cc_attribute] = std::move(rs_attribute);
   }
   return absl::OkStatus();
 }
@@ -191,11 +378,46 @@ absl::StatusOr<Cmdline> Cmdline::FromFlags() {
       .rustfmt_exe_path = absl::GetFlag(FLAGS_rustfmt_exe_path),
       .rustfmt_config_path = absl::GetFlag(FLAGS_rustfmt_config_path),
       .error_report_out = absl::GetFlag(FLAGS_error_report_out),
+      .item_spans_out = absl::GetFlag(FLAGS_item_spans_out),
+      .bindings_status_out = absl::GetFlag(FLAGS_bindings_status_out),
+      .stable_thunks_out = absl::GetFlag(FLAGS_stable_thunks_out),
+      .stable_thunks_baseline = absl::GetFlag(FLAGS_stable_thunks_baseline),
       .do_nothing = absl::GetFlag(FLAGS_do_nothing),
       .generate_source_location_in_doc_comment =
           absl::GetFlag(FLAGS_generate_source_location_in_doc_comment)
               ? SourceLocationDocComment::Enabled
               : SourceLocationDocComment::Disabled,
+      .generate_raw_thunks_module =
+          absl::GetFlag(FLAGS_generate_raw_thunks_module),
+      .generate_mock_thunks = absl::GetFlag(FLAGS_generate_mock_thunks),
+      .thunk_export_macro = absl::GetFlag(FLAGS_thunk_export_macro),
+      .hash_thunk_names = absl::GetFlag(FLAGS_hash_thunk_names),
+      .generate_layout_verification =
+          absl::GetFlag(FLAGS_generate_layout_verification),
+      .generate_bytemuck_impls = absl::GetFlag(FLAGS_generate_bytemuck_impls),
+      .generate_prelude_module = absl::GetFlag(FLAGS_generate_prelude_module),
+      .import_public_headers = absl::GetFlag(FLAGS_import_public_headers),
+      .generate_line_directives = absl::GetFlag(FLAGS_generate_line_directives),
+      .generate_cxx_extern_types =
+          absl::GetFlag(FLAGS_generate_cxx_extern_types),
+      .bindgen_allowlist_file = absl::GetFlag(FLAGS_bindgen_allowlist_file),
+      .minimize_public_header_includes =
+          absl::GetFlag(FLAGS_minimize_public_header_includes),
+      .lazy_ir = absl::GetFlag(FLAGS_lazy_ir),
+      .thunk_exception_note = absl::GetFlag(FLAGS_thunk_exception_note),
+      .overload_naming = absl::GetFlag(FLAGS_overload_naming),
+      .rs_api_lints = absl::GetFlag(FLAGS_rs_api_lints),
+      .detail_module_name = absl::GetFlag(FLAGS_detail_module_name),
+      .strict_pointer_constness = absl::GetFlag(FLAGS_strict_pointer_constness),
+      .compact_layout_assertions =
+          absl::GetFlag(FLAGS_compact_layout_assertions),
+      .abi_hash_symbols = absl::GetFlag(FLAGS_abi_hash_symbols),
+      .convert_string_views = absl::GetFlag(FLAGS_convert_string_views),
+      .string_view_params_as_str =
+          absl::GetFlag(FLAGS_string_view_params_as_str),
+      .record_ext_traits = absl::GetFlag(FLAGS_record_ext_traits),
+      .constructor_functions = absl::GetFlag(FLAGS_constructor_functions),
+      .version_stamp = absl::GetFlag(FLAGS_version_stamp),
       .public_headers = PublicHeaders(),
       .extra_rs_srcs = absl::GetFlag(FLAGS_extra_rs_srcs),
       .srcs_to_scan_for_instantiations =
@@ -203,6 +425,10 @@ absl::StatusOr<Cmdline> Cmdline::FromFlags() {
       .instantiations_out = absl::GetFlag(FLAGS_instantiations_out)};
   absl::Status parse_target_args_status =
       internal::ParseTargetArgs(absl::GetFlag(FLAGS_target_args), args);
+  if (parse_target_args_status.ok()) {
+    parse_target_args_status = internal::ParseAttributeTranslations(
+        absl::GetFlag(FLAGS_attribute_translations), args);
+  }
   absl::StatusOr<Cmdline> cmdline = Cmdline::Create(std::move(args));
   if (!parse_target_args_status.ok() || !cmdline.ok()) {
     return absl::InvalidArgumentError(
@@ -17,6 +17,7 @@
 #include "common/ffi_types.h"
 #include "rs_bindings_from_cc/bazel_types.h"
 #include "rs_bindings_from_cc/ir.h"
+#include "rs_bindings_from_cc/src_code_gen.h"
 
 namespace crubit {
 
@@ -32,9 +33,41 @@ struct CmdlineArgs {
   std::string rustfmt_exe_path;
   std::string rustfmt_config_path;
   std::string error_report_out;
+  std::string item_spans_out;
+  std::string bindings_status_out;
+  std::string stable_thunks_out;
+  std::string stable_thunks_baseline;
   bool do_nothing = true;
   SourceLocationDocComment generate_source_location_in_doc_comment =
       SourceLocationDocComment::Enabled;
+  bool generate_raw_thunks_module = false;
+  bool generate_mock_thunks = false;
+  std::string thunk_export_macro;
+  bool hash_thunk_names = false;
+  bool generate_layout_verification = false;
+  bool generate_bytemuck_impls = false;
+  bool generate_prelude_module = false;
+  bool import_public_headers = false;
+  bool generate_line_directives = false;
+  bool generate_cxx_extern_types = false;
+  std::string bindgen_allowlist_file;
+  bool minimize_public_header_includes = false;
+  bool lazy_ir = false;
+  std::string thunk_exception_note;
+  OverloadNaming overload_naming = OverloadNaming::kNone;
+  std::string rs_api_lints = "deny(warnings)";
+  std::string detail_module_name;
+  bool strict_pointer_constness = false;
+  bool compact_layout_assertions = false;
+  bool abi_hash_symbols = false;
+  bool convert_string_views = false;
+  bool string_view_params_as_str = false;
+  bool record_ext_traits = false;
+  bool constructor_functions = false;
+  std::string version_stamp;
+  // Maps C++ attribute names to Rust attributes (or to an empty string, if the
+  // attribute should be ignored).
+  absl::flat_hash_map<std::string, std::string> attribute_translations;
 
   std::vector<HeaderName> public_headers;
   absl::flat_hash_map<HeaderName, BazelLabel> headers_to_targets;
@@ -46,6 +79,9 @@ struct CmdlineArgs {
 
   absl::flat_hash_map<BazelLabel, absl::flat_hash_set<std::string>>
       target_to_features;
+  absl::flat_hash_map<BazelLabel, std::string>
+      target_to_crubit_support_path_format;
+  absl::flat_hash_map<BazelLabel, std::string> target_to_cc_module_name;
 };
 
 // A valid command line invocation.
@@ -72,6 +108,10 @@ namespace internal {
 // Parses --target_args into CmdlineArgs. Only exposed so it can be unit tested.
 absl::Status ParseTargetArgs(absl::string_view target_args_str,
                              CmdlineArgs& args);
+// Parses --attribute_translations into CmdlineArgs. Only exposed so it can be
+// unit tested.
+absl::Status ParseAttributeTranslations(
+    absl::string_view attribute_translations_str, CmdlineArgs& args);
 }  // namespace internal
 
 // Expands paramfiles (@path/to/file) in-place in argv.
@@ -8,6 +8,7 @@
 #include <vector>
 
 #include "absl/flags/declare.h"
+#include "rs_bindings_from_cc/src_code_gen.h"
 
 ABSL_DECLARE_FLAG(bool, do_nothing);
 ABSL_DECLARE_FLAG(std::string, rs_out);
@@ -25,6 +26,36 @@ ABSL_DECLARE_FLAG(std::vector<std::string>, srcs_to_scan_for_instantiations);
 ABSL_DECLARE_FLAG(std::string, instantiations_out);
 ABSL_DECLARE_FLAG(std::string, namespaces_out);
 ABSL_DECLARE_FLAG(std::string, error_report_out);
+ABSL_DECLARE_FLAG(std::string, item_spans_out);
+ABSL_DECLARE_FLAG(std::string, bindings_status_out);
+ABSL_DECLARE_FLAG(std::string, stable_thunks_out);
+ABSL_DECLARE_FLAG(std::string, stable_thunks_baseline);
 ABSL_DECLARE_FLAG(bool, generate_source_location_in_doc_comment);
+ABSL_DECLARE_FLAG(bool, generate_raw_thunks_module);
+ABSL_DECLARE_FLAG(bool, generate_mock_thunks);
+ABSL_DECLARE_FLAG(std::string, thunk_export_macro);
+ABSL_DECLARE_FLAG(bool, hash_thunk_names);
+ABSL_DECLARE_FLAG(bool, generate_layout_verification);
+ABSL_DECLARE_FLAG(bool, generate_bytemuck_impls);
+ABSL_DECLARE_FLAG(bool, generate_prelude_module);
+ABSL_DECLARE_FLAG(bool, import_public_headers);
+ABSL_DECLARE_FLAG(bool, generate_line_directives);
+ABSL_DECLARE_FLAG(bool, generate_cxx_extern_types);
+ABSL_DECLARE_FLAG(std::string, bindgen_allowlist_file);
+ABSL_DECLARE_FLAG(bool, minimize_public_header_includes);
+ABSL_DECLARE_FLAG(bool, lazy_ir);
+ABSL_DECLARE_FLAG(std::string, thunk_exception_note);
+ABSL_DECLARE_FLAG(crubit::OverloadNaming, overload_naming);
+ABSL_DECLARE_FLAG(std::string, rs_api_lints);
+ABSL_DECLARE_FLAG(std::string, detail_module_name);
+ABSL_DECLARE_FLAG(bool, strict_pointer_constness);
+ABSL_DECLARE_FLAG(bool, compact_layout_assertions);
+ABSL_DECLARE_FLAG(bool, abi_hash_symbols);
+ABSL_DECLARE_FLAG(bool, convert_string_views);
+ABSL_DECLARE_FLAG(bool, string_view_params_as_str);
+ABSL_DECLARE_FLAG(bool, record_ext_traits);
+ABSL_DECLARE_FLAG(bool, constructor_functions);
+ABSL_DECLARE_FLAG(std::string, attribute_translations);
+ABSL_DECLARE_FLAG(std::string, version_stamp);
 
 #endif  // THIRD_PARTY_CRUBIT_RS_BINDINGS_FROM_CC_CMDLINE_FLAGS_H_
@@ -30,6 +30,7 @@ namespace {
 using ::testing::AllOf;
 using ::testing::ElementsAre;
 using ::testing::HasSubstr;
+using ::testing::IsEmpty;
 using ::testing::Pair;
 using ::testing::UnorderedElementsAre;
 
@@ -90,8 +91,30 @@ TEST(CmdlineTest, BasicCorrectInput) {
   absl::SetFlag(&FLAGS_instantiations_out, "instantiations_out");
   absl::SetFlag(&FLAGS_namespaces_out, "namespaces_out");
   absl::SetFlag(&FLAGS_error_report_out, "error_report_out");
+  absl::SetFlag(&FLAGS_item_spans_out, "item_spans_out");
+  absl::SetFlag(&FLAGS_bindings_status_out, "bindings_status_out");
+  absl::SetFlag(&FLAGS_stable_thunks_out, "stable_thunks_out");
+  absl::SetFlag(&FLAGS_stable_thunks_baseline, "stable_thunks_baseline");
   absl::SetFlag(&FLAGS_generate_source_location_in_doc_comment,
                 SourceLocationDocComment::Disabled);
+  absl::SetFlag(&FLAGS_generate_raw_thunks_module, true);
+  absl::SetFlag(&FLAGS_generate_mock_thunks, true);
+  absl::SetFlag(&FLAGS_thunk_export_macro, "MYLIB_EXPORT");
+  absl::SetFlag(&FLAGS_hash_thunk_names, true);
+  absl::SetFlag(&FLAGS_generate_layout_verification, true);
+  absl::SetFlag(&FLAGS_generate_bytemuck_impls, true);
+  absl::SetFlag(&FLAGS_generate_prelude_module, true);
+  absl::SetFlag(&FLAGS_import_public_headers, true);
+  absl::SetFlag(&FLAGS_generate_line_directives, true);
+  absl::SetFlag(&FLAGS_generate_cxx_extern_types, true);
+  absl::SetFlag(&FLAGS_bindgen_allowlist_file, ".*\\.h");
+  absl::SetFlag(&FLAGS_minimize_public_header_includes, true);
+  absl::SetFlag(&FLAGS_lazy_ir, true);
+  absl::SetFlag(&FLAGS_thunk_exception_note, "thrown by {function}");
+  absl::SetFlag(&FLAGS_overload_naming, OverloadNaming::kOrdinal);
+  absl::SetFlag(&FLAGS_rs_api_lints, "warn(warnings)");
+  absl::SetFlag(&FLAGS_detail_module_name, "__crubit_detail");
+  absl::SetFlag(&FLAGS_version_stamp, "1.2.3");
   ASSERT_OK_AND_ASSIGN(Cmdline cmdline, Cmdline::FromFlags());
   const CmdlineArgs& args = cmdline.args();
   EXPECT_EQ(args.cc_out, "cc_out");
@@ -104,6 +127,10 @@ TEST(CmdlineTest, BasicCorrectInput) {
   EXPECT_EQ(args.rustfmt_config_path, "rustfmt_config_path");
   EXPECT_EQ(args.instantiations_out, "instantiations_out");
   EXPECT_EQ(args.error_report_out, "error_report_out");
+  EXPECT_EQ(args.item_spans_out, "item_spans_out");
+  EXPECT_EQ(args.bindings_status_out, "bindings_status_out");
+  EXPECT_EQ(args.stable_thunks_out, "stable_thunks_out");
+  EXPECT_EQ(args.stable_thunks_baseline, "stable_thunks_baseline");
   EXPECT_EQ(args.do_nothing, false);
   EXPECT_EQ(args.current_target.value(), "//:t1");
   EXPECT_THAT(args.public_headers, ElementsAre(HeaderName("h1")));
@@ -116,6 +143,24 @@ TEST(CmdlineTest, BasicCorrectInput) {
                            Pair(HeaderName("h2"), BazelLabel("//:t1"))));
   EXPECT_EQ(args.generate_source_location_in_doc_comment,
             SourceLocationDocComment::Disabled);
+  EXPECT_EQ(args.generate_raw_thunks_module, true);
+  EXPECT_EQ(args.generate_mock_thunks, true);
+  EXPECT_EQ(args.thunk_export_macro, "MYLIB_EXPORT");
+  EXPECT_EQ(args.hash_thunk_names, true);
+  EXPECT_EQ(args.generate_layout_verification, true);
+  EXPECT_EQ(args.generate_bytemuck_impls, true);
+  EXPECT_EQ(args.generate_prelude_module, true);
+  EXPECT_EQ(args.import_public_headers, true);
+  EXPECT_EQ(args.generate_line_directives, true);
+  EXPECT_EQ(args.generate_cxx_extern_types, true);
+  EXPECT_EQ(args.bindgen_allowlist_file, ".*\\.h");
+  EXPECT_EQ(args.minimize_public_header_includes, true);
+  EXPECT_EQ(args.lazy_ir, true);
+  EXPECT_EQ(args.thunk_exception_note, "thrown by {function}");
+  EXPECT_EQ(args.overload_naming, OverloadNaming::kOrdinal);
+  EXPECT_EQ(args.rs_api_lints, "warn(warnings)");
+  EXPECT_EQ(args.detail_module_name, "__crubit_detail");
+  EXPECT_EQ(args.version_stamp, "1.2.3");
 }
 
 TEST(CmdlineTest, TargetArgsEmpty) {
@@ -286,6 +331,64 @@ TEST(CmdlineTest, TargetArgsIntInsteadOfFeature) {
                      HasSubstr("string"))));
 }
 
+TEST(CmdlineTest, TargetArgsSupportPathFormat) {
+  ASSERT_OK_AND_ASSIGN(
+      CmdlineArgs args,
+      TestCmdlineArgs({"h1"}, R"([
+        {"t": "//:target", "h": ["h1"], "s": "<vendored/crubit/{header}>"},
+        {"t": "//:other"}
+      ])"));
+  EXPECT_THAT(args.target_to_crubit_support_path_format,
+              UnorderedElementsAre(Pair(BazelLabel("//:target"),
+                                        "<vendored/crubit/{header}>")));
+}
+
+TEST(CmdlineTest, TargetArgsSupportPathFormatWithoutPlaceholder) {
+  ASSERT_THAT(
+      TestCmdlineArgs({"h1"}, R"([{"t": "t1", "s": "<vendored/crubit>"}])"),
+      StatusIs(absl::StatusCode::kInvalidArgument,
+               AllOf(HasSubstr("--target_args"), HasSubstr("`s`"),
+                     HasSubstr("{header}"))));
+}
+
+TEST(CmdlineTest, TargetArgsCcModuleName) {
+  ASSERT_OK_AND_ASSIGN(CmdlineArgs args, TestCmdlineArgs({"h1"}, R"([
+        {"t": "//:target", "h": ["h1"], "m": "foo.target"},
+        {"t": "//:other"}
+      ])"));
+  EXPECT_THAT(args.target_to_cc_module_name,
+              UnorderedElementsAre(Pair(BazelLabel("//:target"), "foo.target")));
+}
+
+TEST(CmdlineTest, AttributeTranslations) {
+  CmdlineArgs args;
+  ASSERT_OK(internal::ParseAttributeTranslations(
+      R"({"gnu::hot": "", "gnu::pure": "must_use"})", args));
+  EXPECT_THAT(args.attribute_translations,
+              UnorderedElementsAre(Pair("gnu::hot", ""),
+                                   Pair("gnu::pure", "must_use")));
+}
+
+TEST(CmdlineTest, AttributeTranslationsEmpty) {
+  CmdlineArgs args;
+  ASSERT_OK(internal::ParseAttributeTranslations("", args));
+  EXPECT_THAT(args.attribute_translations, IsEmpty());
+}
+
+TEST(CmdlineTest, AttributeTranslationsNotAnObject) {
+  CmdlineArgs args;
+  EXPECT_THAT(internal::ParseAttributeTranslations(R"(["gnu::hot"])", args),
+              StatusIs(absl::StatusCode::kInvalidArgument,
+                       HasSubstr("--attribute_translations")));
+}
+
+TEST(CmdlineTest, AttributeTranslationsEmptyName) {
+  CmdlineArgs args;
+  EXPECT_THAT(internal::ParseAttributeTranslations(R"({"": "must_use"})", args),
+              StatusIs(absl::StatusCode::kInvalidArgument,
+                       HasSubstr("--attribute_translations")));
+}
+
 TEST(CmdlineTest, InstantiationsOutEmpty) {
   ASSERT_OK_AND_ASSIGN(CmdlineArgs args, TestCmdlineArgs());
   args.srcs_to_scan_for_instantiations = {"lib.rs"};
@@ -368,6 +471,17 @@ TEST(CmdlineTest, SupportPathNoPlaceholder) {
                                  "crubit_support_path_format")));
 }
 
+TEST(CmdlineTest, OverloadNaming) {
+  OverloadNaming overload_naming = OverloadNaming::kNone;
+  std::string error;
+  EXPECT_TRUE(AbslParseFlag("param_types", &overload_naming, &error));
+  EXPECT_EQ(overload_naming, OverloadNaming::kParamTypes);
+  EXPECT_EQ(AbslUnparseFlag(overload_naming), "param_types");
+  EXPECT_FALSE(AbslParseFlag("mangled", &overload_naming, &error));
+  EXPECT_THAT(error,
+              HasSubstr("must be one of `none`, `param_types` or `ordinal`"));
+}
+
 // A mutable test argv, which doesn't leak memory.
 class Args {
  public:
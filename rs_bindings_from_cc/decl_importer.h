@@ -31,11 +31,13 @@ namespace crubit {
 class Invocation {
  public:
   Invocation(BazelLabel target, absl::Span<const HeaderName> public_headers,
-             const absl::flat_hash_map<HeaderName, BazelLabel>& header_targets)
+             const absl::flat_hash_map<HeaderName, BazelLabel>& header_targets,
+             bool convert_string_views = false)
       : target_(target),
         public_headers_(public_headers),
         lifetime_context_(std::make_shared<
                           clang::tidy::lifetimes::LifetimeAnnotationContext>()),
+        convert_string_views_(convert_string_views),
         header_targets_(header_targets) {
     // Caller should verify that the inputs are non-empty.
     CHECK(!public_headers_.empty());
@@ -63,6 +65,10 @@ class Invocation {
   const std::shared_ptr<clang::tidy::lifetimes::LifetimeAnnotationContext>
       lifetime_context_;
 
+  // Whether string view parameters and return values are converted into Rust
+  // byte slices (see `ImportContext::ConvertStringViewType`).
+  const bool convert_string_views_;
+
   // The main output of the import process
   IR ir_;
 
@@ -181,6 +187,73 @@ class ImportContext {
       std::optional<clang::RefQualifierKind> ref_qualifier_kind,
       bool nullable = true) = 0;
 
+  // Converts `qual_type` into a Rust byte slice, if it is `std::string_view` or
+  // `absl::string_view` and `--convert_string_views` is passed. The lifetime of
+  // the slice is taken from `lifetimes` (if non-null), if the string view class
+  // has a lifetime parameter. Returns `std::nullopt` otherwise.
+  //
+  // Unlike the types converted by `ConvertQualType`, string views are only
+  // converted when they are passed to or returned from a function by value:
+  // pointers to string views, fields, etc. use the bindings of the string view
+  // class itself.
+  virtual std::optional<MappedType> ConvertStringViewType(
+      clang::QualType qual_type,
+      const clang::tidy::lifetimes::ValueLifetimes* lifetimes) = 0;
+
+  // Converts `qual_type` into an owned Rust string, if it is `std::string` (by
+  // value, or, if `is_param`, by const reference). Returns `std::nullopt`
+  // otherwise.
+  //
+  // Like string views, strings are only converted when they are passed to or
+  // returned from a function: in particular, methods returning a
+  // `const std::string&` still return a reference to the string class.
+  virtual std::optional<MappedType> ConvertStdStringType(
+      clang::QualType qual_type, bool is_param) = 0;
+
+  // Converts `qual_type` into a Rust slice, if it is an `absl::Span<T>` or a
+  // `std::span<T>` (with a dynamic extent) of a scalar or record type `T`.
+  // Returns `std::nullopt` otherwise.
+  //
+  // Spans are only converted when they are passed to a function by value.
+  virtual std::optional<MappedType> ConvertSpanType(
+      clang::QualType qual_type) = 0;
+
+  // Converts `qual_type` into a Rust `Result`, if it is an `absl::Status` or an
+  // `absl::StatusOr<T>` of a scalar or record type `T`. Returns `std::nullopt`
+  // otherwise.
+  //
+  // Statuses are only converted when they are returned from a function by
+  // value.
+  virtual std::optional<MappedType> ConvertStatusType(
+      clang::QualType qual_type) = 0;
+
+  // Converts `qual_type` into a Rust `Option<cc_unique_ptr::UniquePtr<T>>`, if
+  // it is a `std::unique_ptr<T>` (with the default deleter) of a scalar or
+  // record type `T`. Returns `std::nullopt` otherwise.
+  //
+  // Unique pointers are only converted when they are passed to or returned
+  // from a function by value.
+  virtual std::optional<MappedType> ConvertUniquePtrType(
+      clang::QualType qual_type) = 0;
+
+  // Converts `qual_type` into a Rust `Option<cc_shared_ptr::SharedPtr<T>>`, if
+  // it is a `std::shared_ptr<T>` of a scalar or record type `T`. Returns
+  // `std::nullopt` otherwise.
+  //
+  // Shared pointers are only converted when they are passed to or returned
+  // from a function by value.
+  virtual std::optional<MappedType> ConvertSharedPtrType(
+      clang::QualType qual_type) = 0;
+
+  // Converts `qual_type` into a Rust `impl Fn(Args...) -> R + 'static`, if it
+  // is a `std::function<R(Args...)>` whose return and parameter types can be
+  // converted. Returns `std::nullopt` otherwise.
+  //
+  // `std::function`s are only converted when they are passed to a function by
+  // value.
+  virtual std::optional<MappedType> ConvertStdFunctionType(
+      clang::QualType qual_type) = 0;
+
   // Marks `decl` as successfully imported.  Other pieces of code can check
   // HasBeenAlreadySuccessfullyImported to avoid introducing dangling ItemIds
   // that refer to an unimportable `decl`.
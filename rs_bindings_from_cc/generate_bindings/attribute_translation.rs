@@ -0,0 +1,200 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! Translation of the C++ attributes which Crubit doesn't otherwise
+//! understand (the `unknown_attr` of the IR).
+//!
+//! An item with an unknown attribute only gets bindings with the experimental
+//! Crubit features, because the attribute might change the meaning of the item
+//! in a way that the bindings don't reflect. Attributes which are known to be
+//! benign can instead be ignored, or translated into a Rust attribute. The
+//! built-in translations below can be extended (and overridden) with
+//! `--attribute_translations`.
+
+use crate::BindingsGenerator;
+use arc_anyhow::{anyhow, ensure, Result};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The built-in translations, from the C++ attribute name to the Rust
+/// attribute (without `#[...]`), or to an empty string if the C++ attribute is
+/// ignored.
+const DEFAULT_ATTRIBUTE_TRANSLATIONS: &[(&str, &str)] = &[
+    ("maybe_unused", ""),
+    ("unused", ""),
+    ("gnu::unused", ""),
+    // These are only unknown on types (e.g. `class [[nodiscard]] Error`), as
+    // they are understood on functions.
+    ("nodiscard", "must_use"),
+    ("warn_unused_result", "must_use"),
+    ("clang::warn_unused_result", "must_use"),
+    ("gnu::warn_unused_result", "must_use"),
+];
+
+/// The Rust attributes that C++ attributes can be translated to. Other
+/// attributes (e.g. `inline` or `repr`) could change the meaning of the
+/// bindings, so they are rejected by `check_attribute_translations`.
+const ALLOWED_RUST_ATTRIBUTES: &[&str] = &["must_use", "deprecated", "doc", "cfg_attr"];
+
+/// The result of translating the unknown attributes of an item.
+#[derive(Clone, Debug, Default)]
+pub struct TranslatedAttrs {
+    /// The Rust attributes that the C++ attributes were translated to.
+    pub rust_attrs: Vec<TokenStream>,
+    /// The attributes which have no translation, in the same format as
+    /// `unknown_attr`, or `None` if all the attributes were translated.
+    pub unknown_attr: Option<Rc<str>>,
+}
+
+impl ToTokens for TranslatedAttrs {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let rust_attrs = &self.rust_attrs;
+        quote! { #( #[#rust_attrs] )* }.to_tokens(tokens)
+    }
+}
+
+/// Returns the translation of the C++ attribute `name`, if there is one.
+fn attribute_translation<'a>(
+    translations: &'a HashMap<Rc<str>, Rc<str>>,
+    name: &str,
+) -> Option<&'a str> {
+    match translations.get(name) {
+        Some(translation) => Some(translation),
+        None => DEFAULT_ATTRIBUTE_TRANSLATIONS
+            .iter()
+            .find(|(cc_attr, _)| *cc_attr == name)
+            .map(|(_, rs_attr)| *rs_attr),
+    }
+}
+
+/// Translates `unknown_attr`, the comma-separated names of the attributes of
+/// an item which the importer didn't understand.
+///
+/// The Rust attributes are only emitted for functions, records and enums.
+/// Elsewhere (e.g. on fields and parameters), an attribute with a translation
+/// is accepted, but has no effect on the bindings.
+pub fn translate_attrs(db: &dyn BindingsGenerator, unknown_attr: Option<&str>) -> TranslatedAttrs {
+    let Some(unknown_attr) = unknown_attr else {
+        return TranslatedAttrs::default();
+    };
+    let options = db.options();
+    let mut rust_attrs = vec![];
+    let mut untranslated = vec![];
+    for name in unknown_attr.split(", ") {
+        // Invalid translations are rejected by `check_attribute_translations`,
+        // before bindings are generated.
+        match attribute_translation(&options.attribute_translations, name)
+            .map(str::parse::<TokenStream>)
+        {
+            Some(Ok(rust_attr)) if rust_attr.is_empty() => {}
+            Some(Ok(rust_attr)) => rust_attrs.push(rust_attr),
+            Some(Err(_)) | None => untranslated.push(name),
+        }
+    }
+    let unknown_attr =
+        if untranslated.is_empty() { None } else { Some(untranslated.join(", ").into()) };
+    TranslatedAttrs { rust_attrs, unknown_attr }
+}
+
+/// Returns the attributes in `unknown_attr` which have no translation, or
+/// `None` if all of them are translated.
+pub fn untranslated_attrs(
+    db: &dyn BindingsGenerator,
+    unknown_attr: Option<&str>,
+) -> Option<Rc<str>> {
+    translate_attrs(db, unknown_attr).unknown_attr
+}
+
+/// Checks that the Rust attributes of `--attribute_translations` are valid, and
+/// in `ALLOWED_RUST_ATTRIBUTES`.
+pub fn check_attribute_translations(translations: &HashMap<Rc<str>, Rc<str>>) -> Result<()> {
+    for (cc_attr, rs_attr) in translations {
+        if rs_attr.is_empty() {
+            continue;
+        }
+        let meta = syn::parse_str::<syn::Meta>(rs_attr).map_err(|err| {
+            anyhow!(
+                "Invalid Rust attribute `{rs_attr}` for the C++ attribute `{cc_attr}` in \
+                 `--attribute_translations`: {err}"
+            )
+        })?;
+        ensure!(
+            ALLOWED_RUST_ATTRIBUTES.iter().any(|allowed| meta.path().is_ident(allowed)),
+            "The Rust attribute `{rs_attr}` for the C++ attribute `{cc_attr}` in \
+             `--attribute_translations` is not supported. Supported attributes: {}",
+            ALLOWED_RUST_ATTRIBUTES.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Database, GeneratorOptions};
+    use error_report::IgnoreErrors;
+    use ffi_types::SourceLocationDocComment;
+    use ir_testing::make_ir_from_items;
+    use token_stream_matchers::assert_rs_matches;
+
+    fn db_with_translations(translations: &[(&str, &str)]) -> Database {
+        let attribute_translations =
+            translations.iter().map(|(cc_attr, rs_attr)| ((*cc_attr).into(), (*rs_attr).into()));
+        Database::new(
+            Rc::new(make_ir_from_items([])),
+            Rc::new(IgnoreErrors),
+            SourceLocationDocComment::Enabled,
+            Rc::new(GeneratorOptions {
+                attribute_translations: attribute_translations.collect(),
+                ..Default::default()
+            }),
+        )
+    }
+
+    #[test]
+    fn test_translate_attrs() -> Result<()> {
+        let db = db_with_translations(&[("gnu::pure", "must_use"), ("gnu::hot", "")]);
+
+        let translated = translate_attrs(&db, Some("maybe_unused, gnu::pure, gnu::hot, gnu::cold"));
+        assert_rs_matches!(quote! { #translated }, quote! { #[must_use] });
+        assert_eq!(translated.unknown_attr.as_deref(), Some("gnu::cold"));
+
+        assert!(translate_attrs(&db, Some("gnu::unused, gnu::hot")).unknown_attr.is_none());
+        assert!(translate_attrs(&db, None).rust_attrs.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_override_default_attribute_translation() -> Result<()> {
+        let db = db_with_translations(&[("nodiscard", "")]);
+        let translated = translate_attrs(&db, Some("nodiscard"));
+        assert!(translated.rust_attrs.is_empty());
+        assert!(translated.unknown_attr.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_attribute_translations() -> Result<()> {
+        let mut translations = HashMap::new();
+        translations.insert("gnu::pure".into(), "must_use".into());
+        check_attribute_translations(&translations)?;
+
+        translations.insert("acme::broken".into(), "must_use(".into());
+        let error = check_attribute_translations(&translations).unwrap_err();
+        assert!(error.to_string().contains("acme::broken"), "{error}");
+
+        let mut translations = HashMap::new();
+        translations.insert("gnu::hot".into(), "inline".into());
+        let error = check_attribute_translations(&translations).unwrap_err();
+        assert!(error.to_string().contains("is not supported"), "{error}");
+
+        let mut translations = HashMap::new();
+        translations.insert("deprecated".into(), r#"deprecated(note = "Use Bar")"#.into());
+        translations.insert("acme::doc".into(), r#"doc = "Documented""#.into());
+        check_attribute_translations(&translations)?;
+        Ok(())
+    }
+}
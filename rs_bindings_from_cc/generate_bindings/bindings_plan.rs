@@ -0,0 +1,371 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! The analysis phase of bindings generation.
+//!
+//! Generating bindings happens in two phases: `plan_bindings` decides which
+//! top-level items of the current target get bindings (and why the others
+//! don't), and `render_bindings` emits the bindings of the items listed in the
+//! resulting `BindingsPlan`. Both phases take the same `GeneratorOptions`,
+//! since the options can affect which items get bindings. The plan is serializable, so that it can
+//! be cached and inspected by the build system, or post-processed by tools
+//! (e.g. to drop items) before any code is emitted.
+//!
+//! `query_bindings` exposes the same decisions for all the items of the
+//! current target (including nested ones), so that build rule generators can
+//! tell whether an item gets bindings without generating them.
+
+use crate::{
+    has_bindings, required_crubit_features, BindingsGenerator, Database, GeneratorOptions,
+    HasBindings, RequiredCrubitFeature,
+};
+use arc_anyhow::{Context, Error, Result};
+use error_report::IgnoreErrors;
+use ffi_types::SourceLocationDocComment;
+use ir::*;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The result of the analysis phase: the top-level items whose bindings
+/// should be rendered, in order.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BindingsPlan {
+    /// The target that the bindings are generated for.
+    pub target: BazelLabel,
+    pub items: Vec<PlannedItem>,
+}
+
+/// A top-level item of a `BindingsPlan`.
+///
+/// Nested items (e.g. the members of a namespace or of a record) are rendered
+/// together with their parent, and aren't listed separately.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedItem {
+    pub id: ItemId,
+    pub kind: PlannedItemKind,
+    pub name: Rc<str>,
+    pub bindings: PlannedBindings,
+    /// The Crubit features that the item needs, but which aren't enabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_features: Vec<PlannedFeatureRequirement>,
+}
+
+/// The kind of bindings that an item gets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlannedItemKind {
+    Function,
+    ForwardDeclaration,
+    Record,
+    Enum,
+    TypeAlias,
+    Variable,
+    Namespace,
+    TypeMapOverride,
+    UseMod,
+    Comment,
+    Unsupported,
+}
+
+/// Whether an item gets bindings (see `HasBindings`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlannedBindings {
+    /// The item is guaranteed to have bindings.
+    Yes,
+    /// The item gets bindings, unless they fail to be generated (e.g. because
+    /// of an unsupported parameter type of a function).
+    Maybe,
+    /// The item doesn't get bindings. The rendered bindings contain a comment
+    /// with the `reason` instead.
+    No { category: NoBindingsCategory, reason: String },
+}
+
+/// Why an item doesn't get bindings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoBindingsCategory {
+    /// The item needs Crubit features which aren't enabled on its target.
+    MissingFeatures,
+    /// A dependency of the item (e.g. a parameter type, or the enclosing
+    /// record) doesn't get bindings.
+    DependencyFailed,
+    /// The item itself is unsupported.
+    Unsupported,
+}
+
+/// Whether an item gets bindings, as returned by `query_bindings`. This is
+/// `PlannedBindings` without the human-readable reason.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BindingsStatus {
+    Yes,
+    Maybe,
+    No(NoBindingsCategory),
+}
+
+impl From<&PlannedBindings> for BindingsStatus {
+    fn from(bindings: &PlannedBindings) -> Self {
+        match bindings {
+            PlannedBindings::Yes => BindingsStatus::Yes,
+            PlannedBindings::Maybe => BindingsStatus::Maybe,
+            PlannedBindings::No { category, .. } => BindingsStatus::No(*category),
+        }
+    }
+}
+
+/// A set of alternative Crubit features, any of which would enable a
+/// capability of an item.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedFeatureRequirement {
+    pub target: BazelLabel,
+    /// Short names of the features (e.g. "experimental").
+    pub features: Vec<String>,
+    pub capability: Rc<str>,
+}
+
+impl From<RequiredCrubitFeature> for PlannedFeatureRequirement {
+    fn from(requirement: RequiredCrubitFeature) -> Self {
+        let RequiredCrubitFeature { target, item: _, missing_features, capability_description } =
+            requirement;
+        PlannedFeatureRequirement {
+            target,
+            features: missing_features
+                .into_iter()
+                .map(|feature| feature.short_name().to_string())
+                .collect(),
+            capability: capability_description,
+        }
+    }
+}
+
+/// Runs the analysis phase of bindings generation for `ir`, with the same
+/// `options` as the bindings will be rendered with.
+pub fn plan_bindings(ir: Rc<IR>, options: Rc<GeneratorOptions>) -> Result<BindingsPlan> {
+    plan_bindings_impl(&analysis_database(ir, options))
+}
+
+/// The database used for the analysis phase.
+///
+/// Errors aren't reported, since the analysis doesn't generate any bindings.
+fn analysis_database(ir: Rc<IR>, options: Rc<GeneratorOptions>) -> Database {
+    Database::new(ir, Rc::new(IgnoreErrors), SourceLocationDocComment::Disabled, options)
+}
+
+/// Returns whether each item of the current target of `ir` gets bindings when
+/// they are generated with `options`.
+///
+/// Unlike `plan_bindings`, this includes nested items (e.g. methods), but not
+/// comments.
+pub fn query_bindings(
+    ir: Rc<IR>,
+    options: Rc<GeneratorOptions>,
+) -> Result<HashMap<ItemId, BindingsStatus>> {
+    let db = analysis_database(ir, options);
+    let ir = db.ir();
+    Ok(ir
+        .items()
+        .filter(|item| !matches!(item, Item::Comment(_) | Item::UseMod(_)))
+        .filter(|item| item.owning_target().is_none_or(|target| ir.is_current_target(target)))
+        .map(|item| (item.id(), BindingsStatus::from(&item_bindings(&db, item))))
+        .collect())
+}
+
+pub(crate) fn plan_bindings_impl(db: &dyn BindingsGenerator) -> Result<BindingsPlan> {
+    let ir = db.ir();
+    let items = ir
+        .top_level_item_ids()
+        .map(|id| {
+            let item = ir.find_decl(*id).context("Failed to look up ir.top_level_item_ids")?;
+            Ok(plan_item(db, item))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(BindingsPlan { target: ir.current_target().clone(), items })
+}
+
+fn plan_item(db: &dyn BindingsGenerator, item: &Item) -> PlannedItem {
+    let ir = db.ir();
+    let bindings = item_bindings(db, item);
+    let missing_features = required_crubit_features(db, item)
+        .unwrap_or_default()
+        .into_iter()
+        .map(PlannedFeatureRequirement::from)
+        .collect();
+    PlannedItem {
+        id: item.id(),
+        kind: item_kind(item),
+        name: item.debug_name(&ir),
+        bindings,
+        missing_features,
+    }
+}
+
+fn item_bindings(db: &dyn BindingsGenerator, item: &Item) -> PlannedBindings {
+    match item {
+        Item::UnsupportedItem(unsupported) => PlannedBindings::No {
+            category: NoBindingsCategory::Unsupported,
+            reason: unsupported.errors.iter().map(|error| &error.message).join("\n"),
+        },
+        _ => match has_bindings(db, item) {
+            HasBindings::Yes => PlannedBindings::Yes,
+            HasBindings::Maybe => PlannedBindings::Maybe,
+            HasBindings::No(reason) => PlannedBindings::No {
+                category: reason.category(),
+                reason: format!("{:#}", Error::from(reason)),
+            },
+        },
+    }
+}
+
+fn item_kind(item: &Item) -> PlannedItemKind {
+    match item {
+        Item::Func(_) => PlannedItemKind::Function,
+        Item::IncompleteRecord(_) => PlannedItemKind::ForwardDeclaration,
+        Item::Record(_) => PlannedItemKind::Record,
+        Item::Enum(_) => PlannedItemKind::Enum,
+        Item::TypeAlias(_) => PlannedItemKind::TypeAlias,
+        Item::GlobalVar(_) => PlannedItemKind::Variable,
+        Item::Namespace(_) => PlannedItemKind::Namespace,
+        Item::TypeMapOverride(_) => PlannedItemKind::TypeMapOverride,
+        Item::UseMod(_) => PlannedItemKind::UseMod,
+        Item::Comment(_) => PlannedItemKind::Comment,
+        Item::UnsupportedItem(_) => PlannedItemKind::Unsupported,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use arc_anyhow::Result;
+    use quote::quote;
+    use token_stream_matchers::{assert_rs_matches, assert_rs_not_matches};
+
+    #[test]
+    fn test_plan_bindings() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"
+            struct S { int x; };
+            extern "C" void ExternC();
+            void NonExternC();
+            "#,
+        )?;
+        let target = ir.current_target().clone();
+        *ir.target_crubit_features_mut(&target) = CrubitFeature::Supported.into();
+        let plan = plan_bindings(Rc::new(ir), Rc::default())?;
+        assert_eq!(plan.target, target);
+        let summary = plan
+            .items
+            .iter()
+            .filter(|item| item.kind != PlannedItemKind::Comment)
+            .map(|item| {
+                (item.kind, &*item.name, matches!(item.bindings, PlannedBindings::No { .. }))
+            })
+            .collect_vec();
+        assert_eq!(
+            summary,
+            [
+                (PlannedItemKind::Record, "S", false),
+                (PlannedItemKind::Function, "ExternC", false),
+                (PlannedItemKind::Function, "NonExternC", true),
+            ]
+        );
+        let non_extern_c = plan.items.iter().find(|item| &*item.name == "NonExternC").unwrap();
+        assert_eq!(
+            non_extern_c.missing_features.iter().map(|r| &r.features).collect_vec(),
+            [&vec!["non_extern_c_functions".to_string(), "experimental".to_string()]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_bindings_json_round_trip() -> Result<()> {
+        let mut ir = ir_from_cc("void NonExternC();")?;
+        let target = ir.current_target().clone();
+        *ir.target_crubit_features_mut(&target) = CrubitFeature::Supported.into();
+        let plan = plan_bindings(Rc::new(ir), Rc::default())?;
+        let json = serde_json::to_string(&plan)?;
+        assert!(json.contains(r#""kind":"function""#), "{json}");
+        assert!(json.contains(r#""features":["non_extern_c_functions","experimental"]"#), "{json}");
+        assert_eq!(serde_json::from_str::<BindingsPlan>(&json)?, plan);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_bindings() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"
+            struct S { int Method() const; };
+            extern "C" void ExternC();
+            void NonExternC();
+            "#,
+        )?;
+        let target = ir.current_target().clone();
+        *ir.target_crubit_features_mut(&target) = CrubitFeature::Supported.into();
+        let ir = Rc::new(ir);
+        let statuses = query_bindings(ir.clone(), Rc::default())?;
+        let status = |name: &str| {
+            let item = ir.items().find(|item| &*item.debug_name(&ir) == name).unwrap();
+            statuses[&item.id()]
+        };
+        assert_eq!(status("S"), BindingsStatus::Yes);
+        assert_eq!(status("ExternC"), BindingsStatus::Maybe);
+        assert_eq!(status("NonExternC"), BindingsStatus::No(NoBindingsCategory::MissingFeatures));
+        assert_eq!(status("S::Method"), BindingsStatus::No(NoBindingsCategory::MissingFeatures));
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_bindings_uses_options() -> Result<()> {
+        let mut ir = ir_from_cc(r#"[[gnu::hot]] extern "C" void Hot();"#)?;
+        let target = ir.current_target().clone();
+        *ir.target_crubit_features_mut(&target) = CrubitFeature::Supported.into();
+        let ir = Rc::new(ir);
+        let hot = ir.items().find(|item| &*item.debug_name(&ir) == "Hot").unwrap().id();
+
+        let statuses = query_bindings(ir.clone(), Rc::default())?;
+        assert_eq!(statuses[&hot], BindingsStatus::No(NoBindingsCategory::MissingFeatures));
+
+        let options = GeneratorOptions {
+            attribute_translations: HashMap::from([("gnu::hot".into(), "".into())]),
+            ..Default::default()
+        };
+        let statuses = query_bindings(ir, Rc::new(options))?;
+        assert_eq!(statuses[&hot], BindingsStatus::Maybe);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bindings_status_json() -> Result<()> {
+        assert_eq!(serde_json::to_string(&BindingsStatus::Maybe)?, r#""maybe""#);
+        assert_eq!(
+            serde_json::to_string(&BindingsStatus::No(NoBindingsCategory::DependencyFailed))?,
+            r#"{"no":"dependency_failed"}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_post_processed_plan() -> Result<()> {
+        let ir = Rc::new(ir_from_cc("struct Kept {}; struct Dropped {};")?);
+        let mut plan = plan_bindings(ir.clone(), Rc::default())?;
+        plan.items.retain(|item| &*item.name != "Dropped");
+        let rs_api = render_bindings_tokens_for_plan(ir, &plan, Rc::default())?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub struct Kept });
+        assert_rs_not_matches!(rs_api, quote! { pub struct Dropped });
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_plan_for_another_target() -> Result<()> {
+        let ir = Rc::new(ir_from_cc("struct S {};")?);
+        let mut plan = plan_bindings(ir.clone(), Rc::default())?;
+        plan.target = "//other:target".into();
+        let error = render_bindings_tokens_for_plan(ir, &plan, Rc::default()).err().unwrap();
+        assert!(error.to_string().contains("//other:target"), "{error}");
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! Translation of common Doxygen markup in C++ comments into rustdoc-flavoured
+//! Markdown.
+//!
+//! Only a small, commonly used subset of Doxygen is recognized:
+//! - `\brief` / `@brief` (the command is dropped, the text is kept),
+//! - `\param` / `@param` (collected into an `# Arguments` section),
+//! - `\return` / `\returns` / `@return` / `@returns` (collected into a
+//!   `# Returns` section),
+//! - `\code` ... `\endcode` (turned into a fenced code block),
+//! - `\p` / `\c` inline commands (turned into inline code).
+//!
+//! Other commands are left as-is.
+
+use std::borrow::Cow;
+
+/// Returns `comment` with the supported Doxygen commands rewritten into
+/// Markdown. Comments without any Doxygen commands are returned unchanged.
+pub fn doxygen_to_rustdoc(comment: &str) -> Cow<str> {
+    if !comment.contains(['@', '\\']) {
+        return Cow::Borrowed(comment);
+    }
+
+    let mut description: Vec<String> = vec![];
+    let mut params: Vec<(String, String)> = vec![];
+    let mut returns: Vec<String> = vec![];
+    let mut in_code_block = false;
+    // Where continuation lines (non-empty lines following a block command) go.
+    let mut continuation = Continuation::Description;
+
+    for line in comment.lines() {
+        if in_code_block {
+            if parse_command(line, &["endcode"]).is_some() {
+                description.push("```".to_string());
+                in_code_block = false;
+            } else {
+                description.push(line.to_string());
+            }
+            continue;
+        }
+
+        if let Some(rest) = parse_command(line, &["code"]) {
+            // Doxygen allows specifying the language as `\code{.cpp}`.
+            let lang = match rest.strip_prefix('{') {
+                Some(rest) => rest.split_once('}').map_or("", |(lang, _)| lang),
+                None => "",
+            };
+            let lang = match lang.trim_start_matches('.') {
+                "" | "cpp" | "cc" | "cxx" | "c++" => "c++",
+                other => other,
+            };
+            description.push(format!("```{lang}"));
+            in_code_block = true;
+            continuation = Continuation::Description;
+        } else if let Some(rest) = parse_command(line, &["brief", "short"]) {
+            description.push(translate_inline_commands(rest));
+            continuation = Continuation::Description;
+        } else if let Some(rest) = parse_command(line, &["param"]) {
+            // Strip the optional direction: `\param[in]`, `\param[out]`, `\param[in,out]`.
+            let rest = match rest.strip_prefix('[') {
+                Some(rest) => rest.split_once(']').map_or(rest, |(_, rest)| rest).trim_start(),
+                None => rest,
+            };
+            let (name, desc) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            params.push((name.to_string(), translate_inline_commands(desc.trim())));
+            continuation = Continuation::Param;
+        } else if let Some(rest) = parse_command(line, &["return", "returns", "result"]) {
+            returns.push(translate_inline_commands(rest));
+            continuation = Continuation::Returns;
+        } else if line.trim().is_empty() {
+            description.push(String::new());
+            continuation = Continuation::Description;
+        } else {
+            let text = translate_inline_commands(line);
+            match continuation {
+                Continuation::Description => description.push(text),
+                Continuation::Param => {
+                    let (_, desc) = params.last_mut().expect("Param continuation implies a param");
+                    append_continuation(desc, &text);
+                }
+                Continuation::Returns => {
+                    let desc = returns.last_mut().expect("Returns continuation implies a return");
+                    append_continuation(desc, &text);
+                }
+            }
+        }
+    }
+    if in_code_block {
+        // Unterminated `\code` block - close the fence so that the rest of the generated docs
+        // are not swallowed.
+        description.push("```".to_string());
+    }
+
+    let mut sections = vec![];
+    let description = description.join("\n");
+    let description = description.trim_matches('\n');
+    if !description.is_empty() {
+        sections.push(description.to_string());
+    }
+    if !params.is_empty() {
+        let params = params
+            .iter()
+            .map(|(name, desc)| {
+                if desc.is_empty() {
+                    format!("* `{name}`")
+                } else {
+                    format!("* `{name}` - {desc}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        sections.push(format!("# Arguments\n\n{params}"));
+    }
+    if !returns.is_empty() {
+        sections.push(format!("# Returns\n\n{}", returns.join("\n\n")));
+    }
+    Cow::Owned(sections.join("\n\n"))
+}
+
+#[derive(Clone, Copy)]
+enum Continuation {
+    Description,
+    Param,
+    Returns,
+}
+
+/// If `line` starts with one of the block `commands` (prefixed with either `\`
+/// or `@`), returns the rest of the line (with whitespace trimmed).
+fn parse_command<'a>(line: &'a str, commands: &[&str]) -> Option<&'a str> {
+    let line = line.trim_start();
+    let line = line.strip_prefix('\\').or_else(|| line.strip_prefix('@'))?;
+    commands.iter().find_map(|command| {
+        let rest = line.strip_prefix(command)?;
+        match rest.chars().next() {
+            None => Some(""),
+            Some(c) if c.is_whitespace() || c == '[' || c == '{' => Some(rest.trim()),
+            Some(_) => None,
+        }
+    })
+}
+
+/// Rewrites `\p word` and `\c word` (or `@p word` / `@c word`) into `` `word` ``.
+fn translate_inline_commands(text: &str) -> String {
+    let mut words = text.split(' ');
+    let mut result = vec![];
+    while let Some(word) = words.next() {
+        if matches!(word, "\\p" | "\\c" | "@p" | "@c") {
+            if let Some(next) = words.next() {
+                // Keep trailing punctuation outside of the inline code.
+                let end = next.trim_end_matches([',', '.', ';', ':', ')']).len();
+                let (code, punctuation) = next.split_at(end);
+                result.push(format!("`{code}`{punctuation}"));
+                continue;
+            }
+        }
+        result.push(word.to_string());
+    }
+    result.join(" ")
+}
+
+fn append_continuation(desc: &mut String, text: &str) {
+    let text = text.trim();
+    if !desc.is_empty() {
+        desc.push(' ');
+    }
+    desc.push_str(text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_doxygen() {
+        let comment = "Just a comment.\n\nWith two paragraphs.";
+        assert!(matches!(doxygen_to_rustdoc(comment), Cow::Borrowed(_)));
+
+        let comment = "Just a comment.\n\nWith an email@example.com address.";
+        assert_eq!(doxygen_to_rustdoc(comment), comment);
+    }
+
+    #[test]
+    fn test_brief() {
+        assert_eq!(
+            doxygen_to_rustdoc("\\brief Does things.\n\nMore details."),
+            "Does things.\n\nMore details."
+        );
+        assert_eq!(doxygen_to_rustdoc("@brief Does things."), "Does things.");
+    }
+
+    #[test]
+    fn test_params_and_return() {
+        let comment = "Adds two numbers.\n\
+                       @param[in] a the first number\n\
+                       @param b the second number, which\n\
+                       \x20 spans two lines\n\
+                       @return the sum of \\p a and \\p b.";
+        assert_eq!(
+            doxygen_to_rustdoc(comment),
+            "Adds two numbers.\n\
+             \n\
+             # Arguments\n\
+             \n\
+             * `a` - the first number\n\
+             * `b` - the second number, which spans two lines\n\
+             \n\
+             # Returns\n\
+             \n\
+             the sum of `a` and `b`."
+        );
+    }
+
+    #[test]
+    fn test_code_block() {
+        let comment = "Example:\n\
+                       \\code{.cpp}\n\
+                       \x20 @param is not a command here\n\
+                       \\endcode";
+        assert_eq!(
+            doxygen_to_rustdoc(comment),
+            "Example:\n```c++\n  @param is not a command here\n```"
+        );
+    }
+
+    #[test]
+    fn test_unterminated_code_block() {
+        assert_eq!(doxygen_to_rustdoc("@code\nfoo();"), "```c++\nfoo();\n```");
+    }
+
+    #[test]
+    fn test_unknown_commands_are_preserved() {
+        let comment = "\\deprecated Use something else.\n@returns nothing";
+        assert_eq!(
+            doxygen_to_rustdoc(comment),
+            "\\deprecated Use something else.\n\n# Returns\n\nnothing"
+        );
+    }
+
+    #[test]
+    fn test_command_prefix_is_not_a_command() {
+        // `\parameter` and `@returned` are not `\param` and `@return`.
+        let comment = "\\parameter x\n@returned y";
+        assert_eq!(doxygen_to_rustdoc(comment), comment);
+    }
+}
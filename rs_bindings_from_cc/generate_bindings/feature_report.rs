@@ -0,0 +1,269 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! Analysis of the Crubit features needed by the items of the current target.
+//!
+//! Bindings for an item are silently suppressed if a target doesn't enable the
+//! Crubit features that the item requires (see `required_crubit_features`).
+//! The report produced here aggregates these requirements per target, so that
+//! target owners can see what enabling a feature would buy them (e.g.
+//! "enabling [//features:experimental] on //foo:bar would unlock 23 more
+//! functions").
+
+use crate::{
+    required_crubit_features, BindingsGenerator, Database, GeneratorOptions, RequiredCrubitFeature,
+};
+use error_report::IgnoreErrors;
+use ffi_types::SourceLocationDocComment;
+use ir::*;
+use itertools::Itertools;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+/// The result of `generate_crubit_features_report`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CrubitFeaturesReport {
+    /// Suggested feature sets, sorted by target and then by features.
+    pub suggestions: Vec<FeatureSuggestion>,
+
+    /// Items that need features to be enabled on more than one target (e.g. a
+    /// template instantiated in this target, but defined in another one).
+    pub multi_target_items: Vec<Rc<str>>,
+}
+
+/// A set of features that could be enabled on a target, and the items that
+/// would get bindings as a result.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeatureSuggestion {
+    pub target: BazelLabel,
+    pub features: flagset::FlagSet<CrubitFeature>,
+    pub unlocked_items: Vec<UnlockedItem>,
+}
+
+/// An item that is currently missing bindings because of missing features.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnlockedItem {
+    /// The kind of the item, e.g. "function".
+    pub kind: ItemKind,
+    pub name: Rc<str>,
+}
+
+/// The singular and plural names of a kind of item, e.g. "function" and "functions".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ItemKind {
+    pub singular: &'static str,
+    pub plural: &'static str,
+}
+
+impl ItemKind {
+    const fn new(singular: &'static str, plural: &'static str) -> Self {
+        Self { singular, plural }
+    }
+
+    fn with_count(&self, count: usize) -> &'static str {
+        if count == 1 {
+            self.singular
+        } else {
+            self.plural
+        }
+    }
+}
+
+impl Display for FeatureSuggestion {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let Self { target, features, unlocked_items } = self;
+        let features = features.into_iter().map(|feature| feature.aspect_hint()).join(", ");
+        let mut counts = BTreeMap::<ItemKind, usize>::new();
+        for item in unlocked_items {
+            *counts.entry(item.kind).or_default() += 1;
+        }
+        let counts = counts
+            .iter()
+            .map(|(kind, count)| format!("{count} more {}", kind.with_count(*count)))
+            .join(", ");
+        write!(f, "enabling [{features}] on {target} would unlock {counts}")
+    }
+}
+
+impl Display for CrubitFeaturesReport {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for suggestion in &self.suggestions {
+            writeln!(f, "{suggestion}")?;
+        }
+        if !self.multi_target_items.is_empty() {
+            writeln!(
+                f,
+                "{} more item(s) need features on multiple targets: {}",
+                self.multi_target_items.len(),
+                self.multi_target_items.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes which Crubit features the items of the current target of `ir`
+/// would need in order to get bindings, and aggregates them into per-target
+/// suggestions.
+///
+/// Only missing features are taken into account: an item counted as
+/// "unlocked" may still fail to get bindings for other reasons (e.g. an
+/// unsupported parameter type). The `options` should be the ones that the
+/// bindings are generated with, since they can affect which features an item
+/// needs (e.g. `attribute_translations`).
+pub fn generate_crubit_features_report(
+    ir: Rc<IR>,
+    options: Rc<GeneratorOptions>,
+) -> CrubitFeaturesReport {
+    let db = Database::new(ir, Rc::new(IgnoreErrors), SourceLocationDocComment::Disabled, options);
+    crubit_features_report(&db)
+}
+
+fn crubit_features_report(db: &dyn BindingsGenerator) -> CrubitFeaturesReport {
+    let ir = db.ir();
+    let blocked_items: Vec<(&Item, Vec<RequiredCrubitFeature>)> = ir
+        .current_target_items()
+        // Items that fail for reasons other than missing features can't be unlocked.
+        .filter_map(|item| Some((item, required_crubit_features(db, item).ok()?)))
+        .filter(|(_, requirements)| !requirements.is_empty())
+        .collect();
+
+    let mut report = CrubitFeaturesReport::default();
+    let mut candidates: Vec<(BazelLabel, flagset::FlagSet<CrubitFeature>)> = vec![];
+    for (item, requirements) in &blocked_items {
+        match requirements.iter().map(|r| &r.target).unique().collect_vec()[..] {
+            [target] => {
+                let candidate = (target.clone(), minimal_features(requirements));
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
+            }
+            _ => report.multi_target_items.push(item.debug_name(&ir)),
+        }
+    }
+
+    for (target, features) in candidates {
+        // A feature set also unlocks the items that need only a subset of it.
+        let unlocked_items = blocked_items
+            .iter()
+            .filter(|(_, requirements)| {
+                requirements.iter().all(|requirement| {
+                    requirement.target == target
+                        && !(requirement.missing_features & features).is_empty()
+                })
+            })
+            .map(|(item, _)| UnlockedItem { kind: item_kind(item), name: item.debug_name(&ir) })
+            .collect();
+        report.suggestions.push(FeatureSuggestion { target, features, unlocked_items });
+    }
+    report.suggestions.sort_by(|a, b| {
+        (&a.target.0, a.features.bits()).cmp(&(&b.target.0, b.features.bits()))
+    });
+    report
+}
+
+/// Returns the smallest set of features that satisfies all `requirements`.
+///
+/// Each requirement lists alternative features, any of which is sufficient.
+/// Requirements with fewer alternatives are handled first, so that e.g.
+/// `[experimental]` together with `[non_extern_c_functions, experimental]`
+/// only needs `experimental`.
+fn minimal_features(requirements: &[RequiredCrubitFeature]) -> flagset::FlagSet<CrubitFeature> {
+    let mut features = flagset::FlagSet::<CrubitFeature>::default();
+    for alternatives in requirements
+        .iter()
+        .map(|requirement| requirement.missing_features)
+        .sorted_by_key(|alternatives| alternatives.into_iter().count())
+    {
+        if (alternatives & features).is_empty() {
+            if let Some(feature) = alternatives.into_iter().next() {
+                features |= feature;
+            }
+        }
+    }
+    features
+}
+
+fn item_kind(item: &Item) -> ItemKind {
+    match item {
+        Item::Func(_) => ItemKind::new("function", "functions"),
+        Item::IncompleteRecord(_) => ItemKind::new("forward declaration", "forward declarations"),
+        Item::Record(_) => ItemKind::new("record", "records"),
+        Item::Enum(_) => ItemKind::new("enum", "enums"),
+        Item::TypeAlias(_) => ItemKind::new("type alias", "type aliases"),
+        Item::GlobalVar(_) => ItemKind::new("variable", "variables"),
+        Item::Namespace(_) => ItemKind::new("namespace", "namespaces"),
+        Item::TypeMapOverride(_) => ItemKind::new("type map override", "type map overrides"),
+        Item::UnsupportedItem(_) | Item::Comment(_) | Item::UseMod(_) => {
+            ItemKind::new("other item", "other items")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use arc_anyhow::Result;
+
+    #[test]
+    fn test_crubit_features_report() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"
+            extern "C" void ExternC();
+            void NonExternC();
+            [[nodiscard]] int Nodiscard();
+            "#,
+        )?;
+        let target = ir.current_target().clone();
+        *ir.target_crubit_features_mut(&target) = CrubitFeature::Supported.into();
+        let report = generate_crubit_features_report(Rc::new(ir), Rc::default());
+        let unlocked = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| UnlockedItem {
+                    kind: ItemKind::new("function", "functions"),
+                    name: (*name).into(),
+                })
+                .collect_vec()
+        };
+        assert_eq!(
+            report,
+            CrubitFeaturesReport {
+                suggestions: vec![
+                    FeatureSuggestion {
+                        target: target.clone(),
+                        features: CrubitFeature::NonExternCFunctions.into(),
+                        unlocked_items: unlocked(&["NonExternC"]),
+                    },
+                    FeatureSuggestion {
+                        target: target.clone(),
+                        features: CrubitFeature::Experimental.into(),
+                        unlocked_items: unlocked(&["NonExternC", "Nodiscard"]),
+                    },
+                ],
+                multi_target_items: vec![],
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            format!(
+                "enabling [//features:non_extern_c_functions] on {target} would unlock 1 more function\n\
+                 enabling [//features:experimental] on {target} would unlock 2 more functions\n"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_crubit_features_report_nothing_to_unlock() -> Result<()> {
+        let ir = ir_from_cc("void NonExternC();")?;
+        assert_eq!(
+            generate_crubit_features_report(Rc::new(ir), Rc::default()),
+            CrubitFeaturesReport::default()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,342 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! Entry points for fuzzing the bindings generator.
+//!
+//! The IR is normally produced by our own importer, so the generator trusts it
+//! to a large extent. The functions here let a fuzzer (see
+//! `generate_bindings_fuzzer.rs`) feed it arbitrary IR instead, to find the
+//! places where malformed IR crashes the generator rather than being reported
+//! as an error.
+
+use crate::{generate_bindings_tokens, BindingsTokens, GeneratorOptions, OverloadNaming};
+use arc_anyhow::Error;
+use error_report::{ErrorReport, ErrorReporting};
+use ffi_types::SourceLocationDocComment;
+use ir::deserialize_ir;
+use serde_json::Value;
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+use token_stream_printer::write_unformatted_tokens;
+
+/// The result of `fuzz_generate_bindings`.
+#[derive(Debug)]
+pub enum FuzzOutcome {
+    /// The input is not valid IR: either it can't be deserialized, or it is
+    /// rejected by the consistency checks of `deserialize_ir` (which panic, so
+    /// the panic message is reported here).
+    InvalidIr(String),
+    /// Bindings were generated (possibly with errors reported for some items).
+    Generated,
+    /// The bindings generator returned an error.
+    Failed(Error),
+    /// The bindings generator panicked.  This is a bug, either in the
+    /// generator or in the consistency checks of `deserialize_ir`.
+    Panicked(String),
+}
+
+/// Deserializes `ir_json` and generates bindings for it, without letting any
+/// panic escape.
+///
+/// All the optional parts of the bindings are enabled, to cover as much of the
+/// generator as possible. The generated source code is printed, but not
+/// formatted (formatting would require running `rustfmt` and `clang-format`).
+pub fn fuzz_generate_bindings(ir_json: &[u8]) -> FuzzOutcome {
+    let ir = match catch_unwind(|| deserialize_ir(ir_json)) {
+        Ok(Ok(ir)) => Rc::new(ir),
+        Ok(Err(err)) => return FuzzOutcome::InvalidIr(err.to_string()),
+        Err(payload) => return FuzzOutcome::InvalidIr(panic_message(payload)),
+    };
+    let generate = AssertUnwindSafe(|| -> arc_anyhow::Result<()> {
+        let errors = Rc::new(ErrorReport::new());
+        let options = GeneratorOptions {
+            generate_raw_thunks_module: true,
+            hash_thunk_names: true,
+            generate_layout_verification: true,
+            generate_bytemuck_impls: true,
+            generate_prelude_module: true,
+            generate_item_spans: true,
+            rs_api_impl_path: "rs_api_impl.cc".into(),
+            generate_cxx_extern_types: true,
+            bindgen_allowlist_file: ".*".into(),
+            minimize_public_header_includes: true,
+            overload_naming: OverloadNaming::ParamTypes,
+            ..Default::default()
+        };
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(
+            ir,
+            "crubit/support/{header}",
+            errors.clone(),
+            SourceLocationDocComment::Enabled,
+            Rc::new(options),
+        )?;
+        write_unformatted_tokens(&mut String::new(), rs_api)?;
+        write_unformatted_tokens(&mut String::new(), rs_api_impl)?;
+        errors.serialize_to_vec()?;
+        Ok(())
+    });
+    match catch_unwind(generate) {
+        Ok(Ok(())) => FuzzOutcome::Generated,
+        Ok(Err(err)) => FuzzOutcome::Failed(err),
+        Err(payload) => FuzzOutcome::Panicked(panic_message(payload)),
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<non-string panic payload>".to_string()
+    }
+}
+
+/// Applies a random structural mutation to the IR in `ir_json`, e.g. removes
+/// an item, or makes an item refer to the ID of another item.
+///
+/// Unlike byte-level mutations, which mostly produce invalid JSON, these
+/// produce IR which is well-formed but inconsistent, which is what reaches the
+/// generator. The mutation is fully determined by `seed`.
+///
+/// Returns `None` if `ir_json` is not valid JSON.
+pub fn mutate_ir_json(ir_json: &[u8], seed: u64) -> Option<Vec<u8>> {
+    let mut ir: Value = serde_json::from_slice(ir_json).ok()?;
+    let mut rng = XorShift::new(seed);
+    match rng.below(4) {
+        0 => remove_item(&mut ir, &mut rng),
+        1 => retarget_item_id(&mut ir, &mut rng),
+        2 => remove_field(&mut ir, &mut rng),
+        _ => replace_scalar(&mut ir, &mut rng),
+    }
+    serde_json::to_vec(&ir).ok()
+}
+
+/// Removes a random element of `items`, leaving any references to it dangling.
+fn remove_item(ir: &mut Value, rng: &mut XorShift) {
+    if let Some(items) = ir.get_mut("items").and_then(Value::as_array_mut) {
+        if !items.is_empty() {
+            items.remove(rng.below(items.len()));
+        }
+    }
+}
+
+/// Replaces a random ID field (e.g. `id`, `enclosing_item_id`, or an element
+/// of `child_item_ids`) with the ID of a random item.
+fn retarget_item_id(ir: &mut Value, rng: &mut XorShift) {
+    let mut item_ids = vec![];
+    visit(ir, None, &mut |key, value| {
+        if key == Some("id") && value.is_u64() {
+            item_ids.push(value.clone());
+        }
+    });
+    if item_ids.is_empty() {
+        return;
+    }
+    let new_id = item_ids[rng.below(item_ids.len())].clone();
+    let mut id_count = 0;
+    visit(ir, None, &mut |key, value| {
+        if is_id_key(key) && value.is_u64() {
+            id_count += 1;
+        }
+    });
+    if id_count == 0 {
+        return;
+    }
+    let mut index = rng.below(id_count);
+    visit(ir, None, &mut |key, value| {
+        if is_id_key(key) && value.is_u64() {
+            if index == 0 {
+                *value = new_id.clone();
+            }
+            index = index.wrapping_sub(1);
+        }
+    });
+}
+
+fn is_id_key(key: Option<&str>) -> bool {
+    key.is_some_and(|key| key == "id" || key.ends_with("_id") || key.ends_with("_ids"))
+}
+
+/// Removes a random field of a random object.
+fn remove_field(ir: &mut Value, rng: &mut XorShift) {
+    let mut object_count = 0;
+    visit(ir, None, &mut |_, value| {
+        if value.as_object().is_some_and(|object| !object.is_empty()) {
+            object_count += 1;
+        }
+    });
+    if object_count == 0 {
+        return;
+    }
+    let mut index = rng.below(object_count);
+    let field_choice = rng.next();
+    visit(ir, None, &mut |_, value| {
+        if let Some(object) = value.as_object_mut().filter(|object| !object.is_empty()) {
+            if index == 0 {
+                let key = object.keys().nth(field_choice as usize % object.len()).cloned();
+                object.remove(&key.unwrap());
+            }
+            index = index.wrapping_sub(1);
+        }
+    });
+}
+
+/// Replaces a random number, string or bool with a value which is likely to
+/// hit an edge case.
+fn replace_scalar(ir: &mut Value, rng: &mut XorShift) {
+    let mut scalar_count = 0;
+    visit(ir, None, &mut |_, value| {
+        if !value.is_array() && !value.is_object() {
+            scalar_count += 1;
+        }
+    });
+    if scalar_count == 0 {
+        return;
+    }
+    let mut index = rng.below(scalar_count);
+    let replacement_choice = rng.next();
+    visit(ir, None, &mut |_, value| {
+        if !value.is_array() && !value.is_object() {
+            if index == 0 {
+                *value = interesting_scalar(value, replacement_choice);
+            }
+            index = index.wrapping_sub(1);
+        }
+    });
+}
+
+fn interesting_scalar(value: &Value, choice: u64) -> Value {
+    match value {
+        Value::Number(_) => {
+            let numbers = [0, 1, u8::MAX as u64, u32::MAX as u64, i64::MAX as u64, u64::MAX];
+            numbers[choice as usize % numbers.len()].into()
+        }
+        Value::String(_) => {
+            let strings = ["", "0", "::", "r#type", "operator", "'a", "std::string", "\u{0}"];
+            strings[choice as usize % strings.len()].into()
+        }
+        Value::Bool(b) => (!b).into(),
+        _ => Value::Null,
+    }
+}
+
+/// Calls `f` on `value` and all the values nested in it (in pre-order), along
+/// with the key of the value in its parent object, if any.
+///
+/// Elements of arrays get the key of the array, so that e.g. the elements of
+/// `child_item_ids` can be recognized as IDs.
+fn visit(value: &mut Value, key: Option<&str>, f: &mut dyn FnMut(Option<&str>, &mut Value)) {
+    f(key, value);
+    match value {
+        Value::Array(elements) => {
+            for element in elements {
+                visit(element, key, f);
+            }
+        }
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                visit(value, Some(key), f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A minimal pseudo-random number generator, so that the mutations are
+/// reproducible from the seed given by the fuzzer.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // The state must not be zero.
+        Self(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns a number in `0..n`. `n` must be non-zero.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IR_JSON: &str = r#"
+    {
+        "public_headers": [{ "name": "foo/bar.h" }],
+        "current_target": "//foo:bar",
+        "items": [
+            { "Namespace": {
+                "name": { "identifier": "ns" },
+                "id": 1,
+                "canonical_namespace_id": 1,
+                "unknown_attr": null,
+                "owning_target": "//foo:bar",
+                "child_item_ids": [2],
+                "enclosing_item_id": null,
+                "is_inline": false
+            } },
+            { "Comment": { "text": "Some comment", "id": 2 } }
+        ],
+        "top_level_item_ids": [1]
+    }
+    "#;
+
+    #[test]
+    fn test_fuzz_generate_bindings_valid_ir() {
+        let outcome = fuzz_generate_bindings(IR_JSON.as_bytes());
+        assert!(matches!(outcome, FuzzOutcome::Generated), "{outcome:?}");
+    }
+
+    #[test]
+    fn test_fuzz_generate_bindings_malformed_json() {
+        let outcome = fuzz_generate_bindings(b"{ \"items\": [");
+        assert!(matches!(outcome, FuzzOutcome::InvalidIr(_)), "{outcome:?}");
+    }
+
+    #[test]
+    fn test_fuzz_generate_bindings_contains_deserialization_panics() {
+        let ir_json =
+            IR_JSON.replace(r#""text": "Some comment", "id": 2"#, r#""text": "", "id": 1"#);
+        let outcome = fuzz_generate_bindings(ir_json.as_bytes());
+        assert!(
+            matches!(&outcome, FuzzOutcome::InvalidIr(message) if message.contains("Duplicate decl_id")),
+            "{outcome:?}"
+        );
+    }
+
+    #[test]
+    fn test_mutate_ir_json_is_deterministic() {
+        for seed in 0..64 {
+            let mutated = mutate_ir_json(IR_JSON.as_bytes(), seed).unwrap();
+            assert_eq!(mutated, mutate_ir_json(IR_JSON.as_bytes(), seed).unwrap());
+            assert!(serde_json::from_slice::<Value>(&mutated).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_mutate_ir_json_changes_ir() {
+        let original: Value = serde_json::from_str(IR_JSON).unwrap();
+        let changed = (0..64)
+            .map(|seed| mutate_ir_json(IR_JSON.as_bytes(), seed).unwrap())
+            .filter(|mutated| serde_json::from_slice::<Value>(mutated).unwrap() != original)
+            .count();
+        assert!(changed > 32, "Only {changed} out of 64 mutations changed the IR");
+    }
+
+    #[test]
+    fn test_mutate_ir_json_invalid_json() {
+        assert_eq!(mutate_ir_json(b"not json", 0), None);
+    }
+}
@@ -0,0 +1,56 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! libFuzzer target for the bindings generator. The inputs are IR JSON files
+//! (as written by `rs_bindings_from_cc --ir_out`), which are mutated both by
+//! libFuzzer itself and structurally, by `mutate_ir_json`.
+//!
+//! libFuzzer provides `main`, so this target has to be linked with
+//! `-fsanitize=fuzzer` (as done by OSS-Fuzz).
+
+#![no_main]
+
+use generate_bindings::{fuzz_generate_bindings, mutate_ir_json, FuzzOutcome};
+use std::ffi::{c_int, c_uint};
+
+extern "C" {
+    fn LLVMFuzzerMutate(data: *mut u8, size: usize, max_size: usize) -> usize;
+}
+
+/// # Safety
+///
+/// `data` must point to `size` readable bytes (as guaranteed by libFuzzer).
+#[no_mangle]
+pub unsafe extern "C" fn LLVMFuzzerTestOneInput(data: *const u8, size: usize) -> c_int {
+    let input = if size == 0 { &[] } else { std::slice::from_raw_parts(data, size) };
+    if let FuzzOutcome::Panicked(message) = fuzz_generate_bindings(input) {
+        // Panicking across the FFI boundary is not allowed, so report the crash
+        // to libFuzzer by aborting.
+        eprintln!("Bindings generation panicked: {message}");
+        std::process::abort();
+    }
+    0
+}
+
+/// # Safety
+///
+/// `data` must point to `max_size` writable bytes, the first `size` of which
+/// are initialized (as guaranteed by libFuzzer).
+#[no_mangle]
+pub unsafe extern "C" fn LLVMFuzzerCustomMutator(
+    data: *mut u8,
+    size: usize,
+    max_size: usize,
+    seed: c_uint,
+) -> usize {
+    let input = if size == 0 { &[] } else { std::slice::from_raw_parts(data, size) };
+    match mutate_ir_json(input, seed.into()) {
+        Some(mutated) if mutated.len() <= max_size => {
+            std::ptr::copy_nonoverlapping(mutated.as_ptr(), data, mutated.len());
+            mutated.len()
+        }
+        // Not JSON (yet, or anymore), or too big: fall back to byte-level mutations.
+        _ => LLVMFuzzerMutate(data, size, max_size),
+    }
+}
@@ -2,7 +2,10 @@
 // Exceptions. See /LICENSE for license information.
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 
-use crate::{BindingsGenerator, GeneratedItem};
+use crate::attribute_translation::translate_attrs;
+use crate::generate_record::std_vector_methods;
+use crate::std_type_mapping::{SharedPtr, StdFunction, UniquePtr};
+use crate::{generate_thunk_decl, BindingsGenerator, GeneratedItem, StableThunk};
 
 use crate::rs_snippet::{
     check_by_value, format_generic_params, format_generic_params_replacing_by_self,
@@ -14,10 +17,12 @@ use error_report::{anyhow, bail, ensure};
 use ir::*;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Literal, TokenStream, TokenTree};
 use quote::{format_ident, quote, ToTokens};
+use serde::Deserialize;
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::Write as _;
+use std::iter;
 use std::ptr;
 use std::rc::Rc;
 
@@ -62,9 +67,13 @@ fn can_skip_cc_thunk(db: &dyn BindingsGenerator, func: &Func) -> bool {
     // In terms of runtime performance, since this only occurs for virtual function
     // calls, which are already slow, it may not be such a big deal. We can
     // benchmark it later. :)
+    //
+    // A virtual method which can't be overridden (because it, or its class, is
+    // `final`) is the only implementation there is, so it can be called directly.
+    // Unless it is pure virtual, in which case it may not be defined at all.
     if let Some(meta) = &func.member_func_metadata {
         if let Some(inst_meta) = &meta.instance_method_metadata {
-            if inst_meta.is_virtual {
+            if inst_meta.is_virtual && (!inst_meta.is_final || inst_meta.is_pure_virtual) {
                 return false;
             }
         }
@@ -79,6 +88,35 @@ fn can_skip_cc_thunk(db: &dyn BindingsGenerator, func: &Func) -> bool {
     if !func.has_c_calling_convention {
         return false;
     }
+    // ## Out parameters.
+    //
+    // The value of a `crubit_out_param` is constructed and (on failure)
+    // destroyed by the thunk.
+    if func.out_param.is_some() {
+        return false;
+    }
+    // ## Stable thunks.
+    //
+    // A thunk whose name is pinned by `crubit_stable_thunk` is part of the ABI
+    // of the prebuilt `rs_api_impl` object, so it has to exist even when it
+    // isn't otherwise needed.
+    if func.stable_thunk_name.is_some() {
+        return false;
+    }
+    // ## Exception notes.
+    //
+    // With a `thunk_exception_note`, exceptions thrown by functions which
+    // aren't `noexcept` are caught by the thunk, before they reach Rust.
+    if !func.is_noexcept && !db.options().thunk_exception_note.is_empty() {
+        return false;
+    }
+    // ## Default arguments.
+    //
+    // Default arguments are evaluated by the caller, so a wrapper that omits
+    // them has to call the function from C++.
+    if func.num_omitted_default_args > 0 {
+        return false;
+    }
 
     // ## Returning structs by value.
     //
@@ -93,6 +131,20 @@ fn can_skip_cc_thunk(db: &dyn BindingsGenerator, func: &Func) -> bool {
         if !return_type.is_c_abi_compatible_by_value() {
             return false;
         }
+        // ## Bridge types.
+        //
+        // Types like `absl::Duration` are converted from their ABI representation
+        // in the thunk.
+        if return_type.as_bridge_type().is_some()
+            || return_type.as_std_optional().is_some()
+            || return_type.as_string_view().is_some()
+            || return_type.as_std_string().is_some()
+            || return_type.as_absl_status().is_some()
+            || return_type.as_unique_ptr().is_some()
+            || return_type.as_shared_ptr().is_some()
+        {
+            return false;
+        }
     }
     // ## Nontrivial parameter types.
     //
@@ -110,7 +162,17 @@ fn can_skip_cc_thunk(db: &dyn BindingsGenerator, func: &Func) -> bool {
     // ABI-agnostic.)
     for param in &func.params {
         if let Ok(param_type) = db.rs_type_kind(param.type_.rs_type.clone()) {
-            if !param_type.is_c_abi_compatible_by_value() {
+            if !param_type.is_c_abi_compatible_by_value()
+                || param_type.as_bridge_type().is_some()
+                || param_type.as_std_optional().is_some()
+                || param_type.as_initializer_list().is_some()
+                || param_type.as_span().is_some()
+                || param_type.as_string_view().is_some()
+                || param_type.as_std_string().is_some()
+                || param_type.as_unique_ptr().is_some()
+                || param_type.as_shared_ptr().is_some()
+                || param_type.as_std_function().is_some()
+            {
                 return false;
             }
         }
@@ -131,6 +193,17 @@ pub struct FunctionId {
     function_path: syn::Path,
 }
 
+impl FunctionId {
+    /// Returns the ID of the same function, but named `name`.
+    fn renamed(&self, name: &str) -> FunctionId {
+        let mut renamed = self.clone();
+        if let Some(segment) = renamed.function_path.segments.last_mut() {
+            segment.ident = make_rs_ident(name);
+        }
+        renamed
+    }
+}
+
 /// The name of a one-function trait, with extra entries for
 /// specially-understood traits and families of traits.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -175,6 +248,9 @@ pub enum TraitName {
     PartialEq { params: Rc<[RsTypeKind]> },
     /// The PartialOrd trait.
     PartialOrd { params: Rc<[RsTypeKind]> },
+    /// The Index trait, with the index type as its single param, and the
+    /// referent of the returned reference as its `Output`.
+    Index { params: Rc<[RsTypeKind]>, output: RsTypeKind },
     /// Any other trait, e.g. Eq.
     Other { name: Rc<str>, params: Rc<[RsTypeKind]>, is_unsafe_fn: bool },
 }
@@ -187,6 +263,7 @@ impl TraitName {
             | Self::UnpinConstructor { params, .. }
             | Self::PartialEq { params }
             | Self::PartialOrd { params }
+            | Self::Index { params, .. }
             | Self::Other { params, .. } => params.iter(),
         }
     }
@@ -234,6 +311,11 @@ impl TraitName {
                     format_tuple_except_singleton_replacing_by_self(arg_types, trait_record);
                 quote! { ::ctor::CtorNew < #formatted_arg_types > }
             }
+            Self::Index { params, .. } => {
+                let formatted_params =
+                    format_generic_params_replacing_by_self(&**params, trait_record);
+                quote! { ::core::ops::Index #formatted_params }
+            }
         }
     }
 }
@@ -422,6 +504,12 @@ impl OperatorMetadataEntry {
     }
 }
 
+/// The maximum number of elements that can be passed as a `std::initializer_list`.
+///
+/// Must match `crubit::kMaxInitializerListSize` in
+/// support/internal/initializer_list.h.
+const MAX_INITIALIZER_LIST_SIZE: usize = 16;
+
 static OPERATOR_METADATA: Lazy<OperatorMetadata> = Lazy::new(|| {
     const ENTRIES: &[OperatorMetadataEntry] = &[
         OperatorMetadataEntry::unary("-", "Neg", "neg"),
@@ -456,6 +544,192 @@ static OPERATOR_METADATA: Lazy<OperatorMetadata> = Lazy::new(|| {
     }
 });
 
+/// A `crubit_buffer` pointer/length parameter pair, which is exposed as a
+/// single slice parameter in the Rust API.
+struct BufferParamIndices {
+    /// The index of the pointer parameter, which becomes the slice parameter.
+    ptr: usize,
+    /// The index of the length parameter, which is removed from the Rust API.
+    len: usize,
+    /// The element type of the slice (`u8` or `i8`).
+    element_type: PrimitiveType,
+    mutability: Mutability,
+    /// Whether the function returns the number of bytes it wrote to the buffer.
+    returns_len: bool,
+}
+
+impl BufferParamIndices {
+    /// Converts `len`, the result of the thunk call, into the `usize` returned
+    /// by the Rust API function, checking (in debug builds) that it is within
+    /// the bounds of the slice `ident`.
+    fn format_return_value(&self, ident: &Ident, len: TokenStream) -> TokenStream {
+        let message = format!("the returned length exceeds the length of `{ident}`");
+        quote! {
+            let __len = #len;
+            ::core::debug_assert!((__len as u64) <= (#ident.len() as u64), #message);
+            __len as usize
+        }
+    }
+}
+
+/// Resolves the `crubit_buffer` parameters of `func` to parameter indices, and
+/// checks that their types are supported.
+fn buffer_params(func: &Func, param_types: &[RsTypeKind]) -> Result<Vec<BufferParamIndices>> {
+    let find_param = |name: &str| {
+        func.params
+            .iter()
+            .position(|param| param.identifier.identifier.as_ref() == name)
+            .ok_or_else(|| anyhow!("`crubit_buffer` parameter `{name}` doesn't exist"))
+    };
+    func.buffer_params
+        .iter()
+        .map(|buffer_param| {
+            let ptr = find_param(&buffer_param.ptr)?;
+            let len = find_param(&buffer_param.len)?;
+            let RsTypeKind::Pointer { pointee, mutability } = param_types[ptr].unalias() else {
+                bail!("`crubit_buffer` parameter `{}` must be a pointer", buffer_param.ptr);
+            };
+            let element_type = match pointee.unalias() {
+                RsTypeKind::Primitive(PrimitiveType::i8 | PrimitiveType::c_schar) => {
+                    PrimitiveType::i8
+                }
+                RsTypeKind::Primitive(
+                    PrimitiveType::u8 | PrimitiveType::c_uchar | PrimitiveType::Unit,
+                ) => PrimitiveType::u8,
+                RsTypeKind::Other { name, .. } if name.as_ref() == "::core::ffi::c_char" => {
+                    PrimitiveType::u8
+                }
+                _ => bail!("`crubit_buffer` parameter `{}` must point to bytes", buffer_param.ptr),
+            };
+            let is_integer = match param_types[len].unalias() {
+                RsTypeKind::Primitive(primitive) => !matches!(
+                    primitive,
+                    PrimitiveType::Unit
+                        | PrimitiveType::bool
+                        | PrimitiveType::f32
+                        | PrimitiveType::f64
+                ),
+                _ => false,
+            };
+            ensure!(
+                is_integer,
+                "`crubit_buffer` parameter `{}` must be an integer",
+                buffer_param.len
+            );
+            ensure!(
+                !buffer_param.returns_len || *mutability == Mutability::Mut,
+                "`crubit_buffer` parameter `{}` must point to non-const bytes, since the function \
+                 returns the number of bytes written to it",
+                buffer_param.ptr
+            );
+            Ok(BufferParamIndices {
+                ptr,
+                len,
+                element_type,
+                mutability: *mutability,
+                returns_len: buffer_param.returns_len,
+            })
+        })
+        .collect()
+}
+
+/// A `crubit_out_param` parameter, which is removed from the Rust API. The
+/// value written through it is returned instead, wrapped in an `Option` (if the
+/// function returns `bool`) or in a `Result` (if the function returns an error
+/// enum).
+struct OutParam {
+    /// The index of the out parameter.
+    index: usize,
+    /// The type of the value written through the out parameter.
+    value_type: RsTypeKind,
+    /// The error enum returned by the function, or `None` if it returns `bool`.
+    error_type: Option<RsTypeKind>,
+}
+
+impl OutParam {
+    /// The return type of the Rust API function.
+    fn format_return_type(&self) -> TokenStream {
+        let value_type = &self.value_type;
+        match &self.error_type {
+            None => quote! { ::core::option::Option<#value_type> },
+            Some(error_type) => quote! { ::core::result::Result<#value_type, #error_type> },
+        }
+    }
+
+    /// Converts `status`, the result of the thunk call, into the return value
+    /// of the Rust API function.
+    fn format_return_value(&self, status: TokenStream) -> TokenStream {
+        match &self.error_type {
+            None => quote! {
+                if #status {
+                    ::core::option::Option::Some(__out.assume_init())
+                } else {
+                    ::core::option::Option::None
+                }
+            },
+            Some(error_type) => quote! {
+                let __status = #status;
+                if __status == <#error_type>::from(0) {
+                    ::core::result::Result::Ok(__out.assume_init())
+                } else {
+                    ::core::result::Result::Err(__status)
+                }
+            },
+        }
+    }
+}
+
+/// Resolves the `crubit_out_param` parameter of `func`, and checks that its
+/// type and the return type of `func` are supported.
+fn out_param(
+    db: &dyn BindingsGenerator,
+    func: &Func,
+    param_types: &[RsTypeKind],
+) -> Result<Option<OutParam>> {
+    let Some(name) = &func.out_param else {
+        return Ok(None);
+    };
+    let index = func
+        .params
+        .iter()
+        .position(|param| param.identifier.identifier == *name)
+        .ok_or_else(|| anyhow!("`crubit_out_param` parameter `{name}` doesn't exist"))?;
+    let RsTypeKind::Pointer { pointee, mutability: Mutability::Mut } = param_types[index].unalias()
+    else {
+        bail!("`crubit_out_param` parameter `{name}` must be a pointer to a non-const value");
+    };
+    let value_type = (**pointee).clone();
+    ensure!(
+        value_type.is_unpin() && !matches!(value_type, RsTypeKind::Primitive(PrimitiveType::Unit)),
+        "`crubit_out_param` parameter `{name}` must point to an Unpin value"
+    );
+    if let RsTypeKind::Record { record, .. } = value_type.unalias() {
+        let has_default_constructor =
+            db.ir().get_functions_by_name(&UnqualifiedIdentifier::Constructor).any(|ctor| {
+                ctor.params.len() == 1
+                    && ctor.member_func_metadata.as_ref().map(|meta| meta.record_id)
+                        == Some(record.id)
+            });
+        ensure!(
+            has_default_constructor,
+            "`crubit_out_param` parameter `{name}` must point to a default-constructible value"
+        );
+    }
+    let return_type = db.rs_type_kind(func.return_type.rs_type.clone())?;
+    let error_type = match return_type.unalias() {
+        RsTypeKind::Primitive(PrimitiveType::bool) => None,
+        // Enums annotated with `crubit_rust_enum` can't be converted from `0`.
+        RsTypeKind::Enum { enum_, .. }
+            if !enum_.is_rust_enum
+                && !db.rs_type_kind(enum_.underlying_type.rs_type.clone())?.is_bool() =>
+        {
+            Some(return_type)
+        }
+        _ => bail!("Functions with a `crubit_out_param` must return `bool` or an enum"),
+    };
+    Ok(Some(OutParam { index, value_type, error_type }))
+}
+
 /// Returns the shape of the generated Rust API for a given function definition.
 ///
 /// If the shape is a trait, this also mutates the parameter types to be
@@ -486,7 +760,15 @@ fn api_func_shape(
         Some(Err(_)) => return Ok(None),
     };
 
-    let is_unsafe = param_types.iter().any(|p| p.is_unsafe());
+    // `crubit_buffer` pointers are exposed as slices, and `crubit_out_param`
+    // pointers are hidden, so neither makes the function unsafe.
+    let hidden_ptrs: HashSet<usize> = buffer_params(func, param_types)?
+        .iter()
+        .map(|buffer_param| buffer_param.ptr)
+        .chain(out_param(db, func, param_types)?.map(|out_param| out_param.index))
+        .collect();
+    let is_unsafe =
+        param_types.iter().enumerate().any(|(i, p)| p.is_unsafe() && !hidden_ptrs.contains(&i));
     let impl_kind: ImplKind;
     let func_name: syn::Ident;
 
@@ -659,6 +941,96 @@ fn api_func_shape(
                 }
             };
         }
+        UnqualifiedIdentifier::Operator(op) if op.name.as_ref() == "[]" => {
+            let record =
+                maybe_record.ok_or_else(|| anyhow!("operator[] must be a member function."))?;
+            let return_type = db.rs_type_kind(func.return_type.rs_type.clone())?;
+            if let Some((index, output)) =
+                index_trait_types(record, Mutability::Const, param_types, &return_type)
+            {
+                func_name = make_rs_ident("index");
+                impl_kind = ImplKind::new_trait(
+                    TraitName::Index { params: Rc::new([index]), output },
+                    record.clone(),
+                    /* format_first_param_as_self= */ true,
+                    /* force_const_reference_params= */ false,
+                )?;
+            } else if let Some((index, output)) =
+                index_trait_types(record, Mutability::Mut, param_types, &return_type)
+            {
+                // `IndexMut` requires `Index` with the same index and output types.
+                ensure!(
+                    has_index_impl(db, record, &index, &output),
+                    "operator[] can only implement `IndexMut` if a const operator[] implements \
+                     `Index` with the same parameter and return types"
+                );
+                func_name = make_rs_ident("index_mut");
+                impl_kind = ImplKind::new_trait(
+                    TraitName::Other {
+                        name: Rc::from("::core::ops::IndexMut"),
+                        params: Rc::new([index]),
+                        is_unsafe_fn: false,
+                    },
+                    record.clone(),
+                    /* format_first_param_as_self= */ true,
+                    /* force_const_reference_params= */ false,
+                )?;
+            } else {
+                bail!(
+                    "operator[] can only implement `Index` or `IndexMut` if it returns a \
+                     reference with the lifetime of `this`"
+                );
+            }
+        }
+        UnqualifiedIdentifier::Operator(op) if is_pointer_operator(func) => {
+            let record = maybe_record
+                .ok_or_else(|| anyhow!("operator{} must be a member function.", op.name))?;
+            if op.name.as_ref() == "*" {
+                let arrow_operator =
+                    UnqualifiedIdentifier::Operator(Operator { name: Rc::from("->") });
+                let has_arrow_operator_binding =
+                    ir.get_functions_by_name(&arrow_operator).any(|f| {
+                        f.member_func_metadata.as_ref().map(|meta| meta.record_id)
+                            == Some(record.id)
+                            && matches!(db.generate_func(f.clone()), Ok(Some(_)))
+                    });
+                if has_arrow_operator_binding {
+                    // `get` is already generated for `operator->`.
+                    return Ok(None);
+                }
+            }
+            ensure!(
+                !has_method_named(&ir, record.id, "get"),
+                "operator{} can't be bound as `get`, because it collides with a method of `{}`",
+                op.name,
+                record.rs_name
+            );
+            impl_kind = ImplKind::Struct {
+                record: record.clone(),
+                format_first_param_as_self: param_types[0].is_ref_to(record),
+                is_unsafe,
+            };
+            func_name = make_rs_ident("get");
+        }
+        UnqualifiedIdentifier::Operator(op) if op.name.as_ref() == "()" => {
+            let record = maybe_record
+                .ok_or_else(|| anyhow!("operator() must be a member function."))?;
+            ensure!(func.is_instance_method(), "Static operator() is not supported");
+            ensure!(
+                !has_method_named(&ir, record.id, "call"),
+                "operator() can't be bound as `call`, because it collides with a method of `{}`",
+                record.rs_name
+            );
+            let first_param = param_types.first().ok_or_else(|| {
+                anyhow!("Missing `__this` parameter in an instance method: {:?}", func)
+            })?;
+            impl_kind = ImplKind::Struct {
+                record: record.clone(),
+                format_first_param_as_self: first_param.is_ref_to(record),
+                is_unsafe,
+            };
+            func_name = make_rs_ident("call");
+        }
         UnqualifiedIdentifier::Operator(op) => match op_meta
             .by_cc_name_and_params
             .get(&(&op.name, param_types.len()))
@@ -850,6 +1222,9 @@ fn api_func_shape(
                     }
                 }
             } else {
+                // Explicit and multi-argument constructors are bound as associated functions
+                // (rather than `From` impls, or not at all) with `--constructor_functions`.
+                let ctor_fns = db.options().constructor_functions;
                 match func.params.len() {
                     0 => bail!("Missing `__this` parameter in a constructor: {:?}", func),
                     1 => {
@@ -881,6 +1256,15 @@ fn api_func_shape(
                                 )?;
                                 func_name = make_rs_ident("clone");
                             }
+                        } else if ctor_fns && func.is_explicit_ctor {
+                            let name = format!("from_{}", explicit_ctor_name_suffix(db, func)?);
+                            ensure_no_method_named(&ir, record, &name)?;
+                            impl_kind = ImplKind::Struct {
+                                record: record.clone(),
+                                format_first_param_as_self: false,
+                                is_unsafe,
+                            };
+                            func_name = make_rs_ident(&name);
                         } else {
                             let param_type = &param_types[1];
                             impl_kind = ImplKind::new_trait(
@@ -896,6 +1280,16 @@ fn api_func_shape(
                             func_name = make_rs_ident("from");
                         }
                     }
+                    // (`new` is a C++ keyword, so there is no method to collide with. The names
+                    // of overloads are checked by `overload_rs_names`.)
+                    _ if ctor_fns => {
+                        impl_kind = ImplKind::Struct {
+                            record: record.clone(),
+                            format_first_param_as_self: false,
+                            is_unsafe,
+                        };
+                        func_name = make_rs_ident("new");
+                    }
                     _ => {
                         // TODO(b/216648347): Support bindings for other constructors.
                         bail!("More than 1 constructor parameter is not supported yet",);
@@ -907,6 +1301,157 @@ fn api_func_shape(
     Ok(Some((func_name, impl_kind)))
 }
 
+/// Fails if `record` has a method named `name`, which a constructor can't be
+/// bound as (the method would otherwise be renamed as one of its overloads).
+fn ensure_no_method_named(ir: &IR, record: &Record, name: &str) -> Result<()> {
+    ensure!(
+        !has_method_named(ir, record.id, name),
+        "The constructor can't be bound as `{name}`, because it collides with a method of `{}`",
+        record.rs_name
+    );
+    Ok(())
+}
+
+/// Returns whether the record with the ID `record_id` has a method named
+/// `name`.
+pub(crate) fn has_method_named(ir: &IR, record_id: ItemId, name: &str) -> bool {
+    ir.get_functions_by_name(&UnqualifiedIdentifier::Identifier(Identifier {
+        identifier: Rc::from(name),
+    }))
+    .any(|f| f.member_func_metadata.as_ref().map(|meta| meta.record_id) == Some(record_id))
+}
+
+/// Returns the suffix of the `from_*` associated function that an explicit
+/// single-argument constructor is bound as: the name of the parameter, or the
+/// names of its type if it is unnamed (e.g. `from_seconds` for
+/// `explicit Duration(int seconds)`, and `from_c_int` for
+/// `explicit Duration(int)`).
+fn explicit_ctor_name_suffix(db: &dyn BindingsGenerator, func: &Func) -> Result<String> {
+    let param = func.params.get(1).ok_or_else(|| anyhow!("Missing constructor parameter"))?;
+    let name = &param.identifier.identifier;
+    if !name.starts_with("__param_") {
+        return Ok(name.to_string());
+    }
+    overload_suffix_for_params(db, func)
+        .ok_or_else(|| anyhow!("Failed to format the type of the constructor parameter"))
+}
+
+/// Returns the name of the method that a member operator with the given
+/// parameter types is bound as when it can't implement a Rust trait, e.g.
+/// `add_assign` for `operator+=`.
+fn operator_method_name(op: &Operator, param_types: &[RsTypeKind]) -> Option<&'static str> {
+    let method_name = match (op.name.as_ref(), param_types.len()) {
+        ("==", 2) => "eq",
+        ("<", 2) => "lt",
+        ("[]", 2) => match param_types[0] {
+            RsTypeKind::Reference { mutability: Mutability::Const, .. }
+            | RsTypeKind::Pointer { mutability: Mutability::Const, .. } => "index",
+            _ => "index_mut",
+        },
+        (name, n) => OPERATOR_METADATA.by_cc_name_and_params.get(&(name, n))?.method_name,
+    };
+    Some(method_name)
+}
+
+/// Returns the shape of the named method that a member operator is bound as
+/// when `api_func_shape` can't map it to a Rust trait (e.g. `operator+=` of a
+/// non-`Unpin` type becomes an `add_assign` method).
+///
+/// Returns `None` if there is no such method, or if its name would collide with
+/// another method of the record.
+fn operator_method_shape(
+    db: &dyn BindingsGenerator,
+    func: &Func,
+    param_types: &[RsTypeKind],
+) -> Option<(Ident, ImplKind)> {
+    let UnqualifiedIdentifier::Operator(op) = &func.name else {
+        return None;
+    };
+    if !func.is_instance_method() {
+        return None;
+    }
+    let ir = db.ir();
+    let record: &Rc<Record> = ir.record_for_member_func(func)?.try_into().ok()?;
+    let method_name = operator_method_name(op, param_types)?;
+    let is_member_of_record = |f: &&Rc<Func>| {
+        f.member_func_metadata.as_ref().map(|meta| meta.record_id) == Some(record.id)
+    };
+    let collides_with_method = ir
+        .get_functions_by_name(&UnqualifiedIdentifier::Identifier(Identifier {
+            identifier: Rc::from(method_name),
+        }))
+        .any(|f| is_member_of_record(&f));
+    let collides_with_overload =
+        ir.get_functions_by_name(&func.name).filter(is_member_of_record).any(|f| {
+            let Ok(other_param_types) = f
+                .params
+                .iter()
+                .map(|p| db.rs_type_kind(p.type_.rs_type.clone()))
+                .collect::<Result<Vec<_>>>()
+            else {
+                return false;
+            };
+            f.id != func.id && operator_method_name(op, &other_param_types) == Some(method_name)
+        });
+    if collides_with_method || collides_with_overload {
+        return None;
+    }
+    let impl_kind = ImplKind::Struct {
+        record: record.clone(),
+        format_first_param_as_self: param_types[0].is_ref_to(record),
+        is_unsafe: param_types.iter().any(|p| p.is_unsafe()),
+    };
+    Some((make_rs_ident(method_name), impl_kind))
+}
+
+/// Returns the doc comment of the bindings for `func`. The doc comment of a
+/// method which can be overridden notes that the call is dispatched
+/// dynamically, and the doc comment of a function returning the length of a
+/// `crubit_buffer` notes its bounds.
+fn func_doc_comment(func: &Func, impl_kind: &ImplKind) -> Option<String> {
+    let mut notes = vec![];
+    if let ImplKind::Struct { record, .. } = impl_kind {
+        let is_overridable = func
+            .member_func_metadata
+            .as_ref()
+            .and_then(|meta| meta.instance_method_metadata.as_ref())
+            .is_some_and(|inst_meta| inst_meta.is_virtual && !inst_meta.is_final);
+        if is_overridable && matches!(func.name, UnqualifiedIdentifier::Identifier(_)) {
+            notes.push(format!(
+                "This method is virtual: the call is dispatched to the implementation for the\n\
+                 dynamic type of the object, which may be a class derived from `{}`.",
+                record.cc_name
+            ));
+        }
+    }
+    if let Some(buffer_param) = func.buffer_params.iter().find(|b| b.returns_len) {
+        let ident = make_rs_ident(&buffer_param.ptr);
+        notes.push(format!(
+            "Returns the number of bytes written to the start of `{ident}`, which is at most\n\
+             `{ident}.len()`."
+        ));
+    }
+    func.doc_comment
+        .iter()
+        .map(|doc_comment| doc_comment.to_string())
+        .chain(notes)
+        .reduce(|doc_comment, note| format!("{doc_comment}\n\n{note}"))
+}
+
+/// Returns the inlining attributes of the Rust API function of `func`.
+///
+/// The API function only forwards to the thunk, so it is always inlined, unless
+/// the C++ function is `[[gnu::cold]]` (and so the calls shouldn't be inlined
+/// into hot code) or `[[gnu::noinline]]`.
+fn generate_inline_attrs(func: &Func) -> TokenStream {
+    match (func.is_cold, func.is_noinline) {
+        (false, false) => quote! { #[inline(always)] },
+        (true, false) => quote! { #[cold] },
+        (false, true) => quote! { #[inline(never)] },
+        (true, true) => quote! { #[cold] #[inline(never)] },
+    }
+}
+
 /// Returns the generated bindings for a function with the given name and param
 /// types. If none exists, returns None.
 pub fn get_binding(
@@ -995,6 +1540,42 @@ fn materialize_ctor_in_caller(func: &Func, params: &mut [RsTypeKind]) {
     }
 }
 
+/// Returns an error if a pointer or reference in `rs_type` (or in its pointee,
+/// etc.) is mutable but points to a `const` type in `cc_type`, or vice versa.
+///
+/// `rs_type` is the type that the bindings use for `cc_type`, as returned by
+/// `rs_type_kind`, rather than the Rust type recorded by the importer.
+///
+/// Only used with `--strict_pointer_constness`.
+fn check_pointer_constness(cc_type: &CcType, rs_type: &RsTypeKind, ir: &IR) -> Result<()> {
+    let mut cc_pointer = cc_type;
+    let mut rs_pointer = rs_type;
+    loop {
+        // Nullable pointers with lifetimes are wrapped in an `Option`.
+        if let RsTypeKind::Option(value_type) = rs_pointer {
+            rs_pointer = value_type;
+        }
+        let (rs_pointee, mutability) = match rs_pointer {
+            RsTypeKind::Pointer { pointee, mutability } => (pointee, mutability),
+            RsTypeKind::Reference { referent, mutability, .. }
+            | RsTypeKind::RvalueReference { referent, mutability, .. } => (referent, mutability),
+            _ => return Ok(()),
+        };
+        let Some(cc_pointee) = cc_pointer.type_args.first() else {
+            return Ok(());
+        };
+        if cc_pointee.is_const != (*mutability == Mutability::Const) {
+            let cc_type = crate::format_cc_type(cc_type, ir)?;
+            bail!(
+                "The Rust type of `{cc_type}` doesn't preserve the constness of its pointees \
+                (--strict_pointer_constness)"
+            );
+        }
+        cc_pointer = cc_pointee;
+        rs_pointer = rs_pointee;
+    }
+}
+
 /// Generates Rust source code for a given `Func`.
 ///
 /// Returns:
@@ -1007,9 +1588,160 @@ fn materialize_ctor_in_caller(func: &Func, params: &mut [RsTypeKind]) {
 pub fn generate_func(
     db: &dyn BindingsGenerator,
     func: Rc<Func>,
+) -> Result<Option<(Rc<GeneratedItem>, Rc<FunctionId>)>> {
+    generate_func_impl(db, func, /* rs_name= */ None)
+}
+
+/// Like `generate_func`, but names the Rust function `rs_name`, which
+/// disambiguates it from the other overloads of the function (see
+/// `overload_rs_names`).
+pub fn generate_renamed_func(
+    db: &dyn BindingsGenerator,
+    func: Rc<Func>,
+    rs_name: &str,
+) -> Result<Option<(Rc<GeneratedItem>, Rc<FunctionId>)>> {
+    generate_func_impl(db, func, Some(rs_name))
+}
+
+/// Generates a wrapper for each number of arguments that `func` can be called
+/// with, when some of its trailing parameters have default arguments. For
+/// example, for `void f(int a, int b = 3, int c = 4)` bound as `f`, this
+/// generates `f_1(a)` and `f_2(a, b)`, which call `f` from C++ (so that the
+/// default arguments are evaluated there).
+///
+/// `function_id` is the ID of the bindings for `func` itself. Wrappers are
+/// only generated for functions and methods with a plain name, and not for
+/// numbers of arguments that would omit a `crubit_buffer` or
+/// `crubit_out_param` parameter. A wrapper whose name is already taken by
+/// another function (or method) is replaced with an error comment.
+pub fn generate_default_arg_wrappers(
+    db: &dyn BindingsGenerator,
+    func: &Func,
+    function_id: &FunctionId,
+) -> Result<Vec<Rc<GeneratedItem>>> {
+    if function_id.self_type.is_some() || !matches!(func.name, UnqualifiedIdentifier::Identifier(_))
+    {
+        return Ok(vec![]);
+    }
+    let Some(rs_name) = function_id.function_path.segments.last() else {
+        return Ok(vec![]);
+    };
+    let rs_name = syn::ext::IdentExt::unraw(&rs_name.ident);
+    let num_this_params = usize::from(func.is_instance_method());
+    let num_default_args =
+        func.params.iter().rev().take_while(|p| p.default_argument.is_some()).count();
+    let mut wrappers = vec![];
+    for num_omitted in 1..=num_default_args {
+        let (params, omitted) = func.params.split_at(func.params.len() - num_omitted);
+        let is_omitted =
+            |name: &str| omitted.iter().any(|p| p.identifier.identifier.as_ref() == name);
+        if func.buffer_params.iter().any(|b| is_omitted(&b.ptr) || is_omitted(&b.len))
+            || func.out_param.as_deref().is_some_and(is_omitted)
+        {
+            continue;
+        }
+        let defaults = omitted
+            .iter()
+            .map(|p| {
+                format!(
+                    "`{} = {}`",
+                    p.identifier.identifier,
+                    p.default_argument.as_deref().unwrap_or_default()
+                )
+            })
+            .join(", ");
+        let wrapper = Func {
+            params: params.to_vec(),
+            doc_comment: Some(
+                format!("Like `{rs_name}`, but with the default arguments {defaults}.").into(),
+            ),
+            stable_thunk_name: None,
+            num_omitted_default_args: num_omitted,
+            ..func.clone()
+        };
+        let wrapper_name = format!("{rs_name}_{}", params.len() - num_this_params);
+        let collides = match &func.member_func_metadata {
+            Some(meta) => has_method_named(&db.ir(), meta.record_id, &wrapper_name),
+            None => {
+                db.module_item_names(db.ir().namespace_qualifier(func)?).contains(&*wrapper_name)
+            }
+        };
+        if collides {
+            let err = anyhow!(
+                "The wrapper `{wrapper_name}` of `{rs_name}` for its default arguments is not \
+                 generated, because it collides with another item"
+            );
+            db.errors().insert(&err);
+            let msg = err.to_string();
+            wrappers.push(Rc::new(quote! { __COMMENT__ #msg }.into()));
+            continue;
+        }
+        if let Some((generated_item, _)) =
+            generate_renamed_func(db, Rc::new(wrapper), &wrapper_name)?
+        {
+            wrappers.push(generated_item);
+        }
+    }
+    Ok(wrappers)
+}
+
+/// Generates a deprecated alias of `func` named after its previous name, as
+/// specified by the `crubit_renamed_from` attribute. The alias calls the same
+/// thunk as the bindings for `func` itself, so only the Rust function is
+/// returned.
+///
+/// `function_id` is the ID of the bindings for `func` itself. Aliases are only
+/// generated for functions at namespace scope, and not if the previous name is
+/// taken by another item.
+pub fn generate_renamed_from_alias(
+    db: &dyn BindingsGenerator,
+    func: &Func,
+    function_id: &FunctionId,
+) -> Result<Option<GeneratedItem>> {
+    let Some(old_name) = &func.renamed_from else {
+        return Ok(None);
+    };
+    if function_id.self_type.is_some() || !matches!(func.name, UnqualifiedIdentifier::Identifier(_))
+    {
+        return Ok(None);
+    }
+    let Some(rs_name) = function_id.function_path.segments.last() else {
+        return Ok(None);
+    };
+    let rs_name = syn::ext::IdentExt::unraw(&rs_name.ident);
+    let namespace_qualifier = db.ir().namespace_qualifier(func)?;
+    if db.module_item_names(namespace_qualifier).contains(&**old_name) {
+        bail!(
+            "The alias `{old_name}` of `{rs_name}` is not generated, because it collides with \
+             another item"
+        );
+    }
+    let note = format!("`{old_name}` was renamed to `{rs_name}`");
+    let alias = Func {
+        doc_comment: Some(format!("Deprecated alias of `{rs_name}`.").into()),
+        renamed_from: None,
+        ..func.clone()
+    };
+    let Some((generated_item, _)) = generate_renamed_func(db, Rc::new(alias), old_name)? else {
+        return Ok(None);
+    };
+    let item = &generated_item.item;
+    Ok(Some(GeneratedItem {
+        item: quote! {
+            #[deprecated = #note]
+            #item
+        },
+        ..Default::default()
+    }))
+}
+
+fn generate_func_impl(
+    db: &dyn BindingsGenerator,
+    func: Rc<Func>,
+    rs_name: Option<&str>,
 ) -> Result<Option<(Rc<GeneratedItem>, Rc<FunctionId>)>> {
     let ir = db.ir();
-    let crate_root_path = crate::crate_root_path_tokens(&ir);
+    let detail_module_path = crate::detail_module_path_tokens(db);
     let mut features = BTreeSet::new();
     let mut param_types = func
         .params
@@ -1020,22 +1752,54 @@ pub fn generate_func(
                 .with_context(|| format!("Failed to format type of parameter {i}"))
         })
         .collect::<Result<Vec<_>>>()?;
+    if db.options().strict_pointer_constness {
+        for (i, (p, rs_type)) in func.params.iter().zip(&param_types).enumerate() {
+            check_pointer_constness(&p.type_.cc_type, rs_type, &ir)
+                .with_context(|| format!("Failed to format type of parameter {i}"))?;
+        }
+    }
 
-    let (func_name, mut impl_kind) =
-        if let Some(values) = api_func_shape(db, &func, &mut param_types)? {
-            values
-        } else {
-            return Ok(None);
-        };
+    let original_param_types = param_types.clone();
+    let (mut func_name, mut impl_kind) = match api_func_shape(db, &func, &mut param_types) {
+        Ok(Some(values)) => values,
+        Ok(None) => return Ok(None),
+        // Member operators which can't implement a trait are bound as named methods instead.
+        Err(err) => {
+            param_types = original_param_types;
+            operator_method_shape(db, &func, &param_types).ok_or(err)?
+        }
+    };
+    if let Some(rs_name) = rs_name {
+        func_name = make_rs_ident(rs_name);
+    }
+    if let ImplKind::Struct { record, .. } = &impl_kind {
+        if std_vector_methods(db, record).contains(&func_name.to_string().as_str()) {
+            bail!("`{func_name}` is already provided by the bindings of `std::vector`");
+        }
+    }
     let namespace_qualifier = ir.namespace_qualifier(&func)?.format_for_rs();
 
     let mut return_type = db
         .rs_type_kind(func.return_type.rs_type.clone())
         .with_context(|| "Failed to format return type")?;
+    if db.options().strict_pointer_constness {
+        check_pointer_constness(&func.return_type.cc_type, &return_type, &ir)
+            .with_context(|| "Failed to format return type")?;
+    }
+    if func.returns_static {
+        return_type = static_reference_type(&return_type)?;
+    }
     return_type.check_by_value()?;
+    ensure!(
+        return_type.as_initializer_list().is_none(),
+        "std::initializer_list can't be returned, since it doesn't own its elements"
+    );
     let param_idents =
         func.params.iter().map(|p| make_rs_ident(&p.identifier.identifier)).collect_vec();
     let thunk = generate_func_thunk(db, &func, &param_idents, &param_types, &return_type)?;
+    let out_param = out_param(db, &func, &param_types)?;
+    let returned_len_buffer =
+        buffer_params(&func, &param_types)?.into_iter().find(|buffer| buffer.returns_len);
 
     // If the Rust trait require a function to take the params by const reference
     // and the thunk takes some of its params by value then we should add a const
@@ -1095,12 +1859,17 @@ pub fn generate_func(
         &param_idents,
         &mut param_types,
         &mut return_type,
+        out_param.as_ref(),
+        db.options().string_view_params_as_str,
     )?;
 
-    let api_func_def = {
-        let thunk_ident = thunk_ident(&func);
+    let (api_func_def, ext_trait_method) = {
+        let thunk_ident = thunk_ident(db, &func);
         let func_body = match &impl_kind {
-            ImplKind::Trait { trait_name: TraitName::UnpinConstructor { .. }, .. } => {
+            ImplKind::Trait { trait_name: TraitName::UnpinConstructor { .. }, .. }
+            | ImplKind::Struct { .. }
+                if func.name == UnqualifiedIdentifier::Constructor =>
+            {
                 // SAFETY: A user-defined constructor is not guaranteed to
                 // initialize all the fields. To make the `assume_init()` call
                 // below safe, the memory is zero-initialized first. This is a
@@ -1112,7 +1881,7 @@ pub fn generate_func(
                 quote! {
                     let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
                     unsafe {
-                        #crate_root_path::detail::#thunk_ident( &mut tmp #( , #thunk_args )* );
+                        #detail_module_path::#thunk_ident( &mut tmp #( , #thunk_args )* );
                         tmp.assume_init()
                     }
                 }
@@ -1123,9 +1892,81 @@ pub fn generate_func(
                 // not generate the thunk at all, but this would be a bit of extra work.
                 //
                 // TODO(jeanpierreda): separately handle non-Unpin and non-trivial types.
-                let mut body = if return_type.is_c_abi_compatible_by_value() {
+                let mut body = if let Some(value_type) = return_type.as_std_optional() {
+                    // The thunk returns whether the `std::optional` has a value, and if so,
+                    // moves the value into `__return`.
+                    quote! {
+                        let mut __return = ::core::mem::MaybeUninit::<#value_type>::uninit();
+                        if #detail_module_path::#thunk_ident(
+                            &mut __return
+                            #( , #clone_prefixes #thunk_args #clone_suffixes )*
+                        ) {
+                            Some(__return.assume_init())
+                        } else {
+                            None
+                        }
+                    }
+                } else if let Some(string_view) = return_type.as_string_view() {
+                    // The thunk returns the pointer to the characters, and their number
+                    // through `__return_size`.
+                    let from_abi = string_view
+                        .format_rs_from_abi(quote! { __return_data }, quote! { __return_size });
+                    quote! {
+                        let mut __return_size = 0usize;
+                        let __return_data = #detail_module_path::#thunk_ident(
+                            &mut __return_size
+                            #( , #clone_prefixes #thunk_args #clone_suffixes )*
+                        );
+                        #from_abi
+                    }
+                } else if let Some(std_string) = return_type.as_std_string() {
+                    // The thunk appends the characters of the returned string to `__return`.
+                    std_string.format_rs_return(
+                        quote! { #detail_module_path::#thunk_ident },
+                        quote! { #( , #clone_prefixes #thunk_args #clone_suffixes )* },
+                    )
+                } else if let Some(absl_status) = return_type.as_absl_status() {
+                    // The thunk reports an error through `__return_error`, and moves the value
+                    // of an OK `absl::StatusOr<T>` into `__return`.
+                    absl_status.format_rs_return(
+                        quote! { #detail_module_path::#thunk_ident },
+                        quote! { #( , #clone_prefixes #thunk_args #clone_suffixes )* },
+                    )
+                } else if return_type.as_unique_ptr().is_some() {
+                    // The thunk releases the returned pointer, which is deleted by the deleter
+                    // thunk when the `UniquePtr` is dropped.
+                    let deleter_ident = unique_ptr_deleter_ident(db, &func);
+                    UniquePtr::format_rs_return(
+                        quote! {
+                            #detail_module_path::#thunk_ident(
+                                #( #clone_prefixes #thunk_args #clone_suffixes ),*
+                            )
+                        },
+                        quote! { #detail_module_path::#deleter_ident },
+                    )
+                } else if return_type.as_shared_ptr().is_some() {
+                    // The thunk returns a handle to a copy of the returned `std::shared_ptr`,
+                    // which is accessed through additional thunks.
+                    let [get_ident, clone_ident, delete_ident] = shared_ptr_thunk_idents(db, &func);
+                    SharedPtr::format_rs_return(
+                        quote! {
+                            #detail_module_path::#thunk_ident(
+                                #( #clone_prefixes #thunk_args #clone_suffixes ),*
+                            )
+                        },
+                        quote! { #detail_module_path::#get_ident },
+                        quote! { #detail_module_path::#clone_ident },
+                        quote! { #detail_module_path::#delete_ident },
+                    )
+                } else if let Some(bridge_type) = return_type.as_bridge_type() {
+                    bridge_type.format_rs_from_abi(quote! {
+                        #detail_module_path::#thunk_ident(
+                            #( #clone_prefixes #thunk_args #clone_suffixes ),*
+                        )
+                    })
+                } else if return_type.is_c_abi_compatible_by_value() {
                     quote! {
-                        #crate_root_path::detail::#thunk_ident(
+                        #detail_module_path::#thunk_ident(
                             #( #clone_prefixes #thunk_args #clone_suffixes ),*
                         )
                     }
@@ -1144,7 +1985,7 @@ pub fn generate_func(
                         quote! {
                             let mut __return =
                                 ::core::mem::MaybeUninit::<#return_type_or_self>::uninit();
-                            #crate_root_path::detail::#thunk_ident(
+                            #detail_module_path::#thunk_ident(
                                 &mut __return
                                 #( , #clone_prefixes #thunk_args #clone_suffixes )*
                             );
@@ -1159,7 +2000,7 @@ pub fn generate_func(
                             ::ctor::FnCtor::new(
                                 move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<
                                                                         #return_type_or_self>>| {
-                                #crate_root_path::detail::#thunk_ident(
+                                #detail_module_path::#thunk_ident(
                                     ::core::pin::Pin::into_inner_unchecked(dest)
                                     #( , #thunk_args )*
                                 );
@@ -1167,6 +2008,12 @@ pub fn generate_func(
                         }
                     }
                 };
+                if let Some(out_param) = &out_param {
+                    body = out_param.format_return_value(body);
+                }
+                if let Some(buffer) = &returned_len_buffer {
+                    body = buffer.format_return_value(&param_idents[buffer.ptr], body);
+                }
                 // Discard the return value if requested (for example, when calling a C++
                 // operator that returns a value from a Rust trait that returns
                 // unit).
@@ -1249,22 +2096,56 @@ pub fn generate_func(
             quote! {}
         };
 
-        quote! {
-            #[inline(always)]
+        let inline_attrs = generate_inline_attrs(&func);
+        // Attributes like `#[must_use]` have no effect on the methods of trait impls.
+        let translated_attrs = match &impl_kind {
+            ImplKind::Trait { .. } => quote! {},
+            _ => translate_attrs(db, func.unknown_attr.as_deref()).into_token_stream(),
+        };
+        let ext_trait_method = match &impl_kind {
+            ImplKind::Struct { record, format_first_param_as_self: true, .. }
+                if db.options().record_ext_traits =>
+            {
+                let record_name = make_rs_ident(record.rs_name.as_ref());
+                ext_trait_method_signature(&record_name, &api_params, &function_return_type).map(
+                    |(params, args, return_type)| {
+                        let signature = quote! {
+                            #unsafe_ fn #func_name #fn_generic_params(
+                                #( #params ),* ) #arrow #return_type
+                        };
+                        let body = quote! {
+                            #inline_attrs
+                            #signature {
+                                #record_name::#func_name( #( #args ),* )
+                            }
+                        };
+                        (quote! { #signature; }, body)
+                    },
+                )
+            }
+            _ => None,
+        };
+        let api_func_def = quote! {
+            #translated_attrs
+            #inline_attrs
             #pub_ #unsafe_ fn #func_name #fn_generic_params(
                     #( #api_params ),* ) #arrow #function_return_type {
                 #func_body
             }
-        }
+        };
+        (api_func_def, ext_trait_method)
     };
 
     let doc_comment = crate::generate_doc_comment(
-        func.doc_comment.as_deref(),
+        func_doc_comment(&func, &impl_kind).as_deref(),
         Some(&func.source_loc),
         db.generate_source_loc_doc_comment(),
     );
     let api_func: TokenStream;
     let function_id: FunctionId;
+    let mut string_accessors = None;
+    let mut ext_trait_decls = quote! {};
+    let mut ext_trait_impls = quote! {};
     match impl_kind {
         ImplKind::None { .. } => {
             api_func = quote! { #doc_comment #api_func_def };
@@ -1275,7 +2156,59 @@ pub fn generate_func(
         }
         ImplKind::Struct { record, .. } => {
             let record_name = make_rs_ident(record.rs_name.as_ref());
-            api_func = quote! { impl #record_name { #doc_comment #api_func_def } };
+            let crubit_features = db.ir().target_crubit_features(&func.owning_target);
+            let fn_adapter = if !is_call_operator(&func)
+                || !crubit_features.contains(ir::CrubitFeature::Experimental)
+            {
+                quote! {}
+            } else if has_method_named(&db.ir(), record.id, "as_fn") {
+                let msg = format!(
+                    "The `as_fn` adapter of operator() is not generated, because it collides \
+                     with a method of `{}`",
+                    record.rs_name
+                );
+                db.errors().insert(&anyhow!("{msg}"));
+                quote! { __COMMENT__ #msg }
+            } else {
+                generate_call_operator_fn_adapter(
+                    &record,
+                    &param_idents,
+                    &param_types,
+                    &return_type,
+                    &quoted_return_type,
+                )
+            };
+            let as_ref = if !is_pointer_operator(&func) {
+                quote! {}
+            } else if has_method_named(&db.ir(), record.id, "as_ref") {
+                let msg = format!(
+                    "The `as_ref` accessor of `get` is not generated, because it collides with \
+                     a method of `{}`",
+                    record.rs_name
+                );
+                db.errors().insert(&anyhow!("{msg}"));
+                quote! { __COMMENT__ #msg }
+            } else {
+                generate_pointer_operator_as_ref(&record, &param_types, &return_type)
+            };
+            string_accessors = generate_string_accessors(
+                db,
+                &func,
+                &record,
+                &func_name,
+                &param_types,
+                &return_type,
+            )?;
+            let string_accessors_api = string_accessors.as_ref().map(|accessors| &accessors.api);
+            if let Some((decl, impl_)) = ext_trait_method {
+                ext_trait_decls = quote! { #doc_comment #decl };
+                ext_trait_impls = impl_;
+            }
+            api_func = quote! {
+                impl #record_name {
+                    #doc_comment #api_func_def #fn_adapter #as_ref #string_accessors_api
+                }
+            };
             function_id = FunctionId {
                 self_type: None,
                 function_path: syn::parse2(quote! {
@@ -1301,6 +2234,11 @@ pub fn generate_func(
                 quote! {
                     type #name = #quoted_return_type;
                 }
+            } else if let TraitName::Index { ref output, .. } = trait_name {
+                let quoted_output = output.to_token_stream_replacing_by_self(Some(&trait_record));
+                quote! {
+                    type Output = #quoted_output;
+                }
             } else if let TraitName::PartialOrd { ref params } = trait_name {
                 let param = params.get(0).ok_or_else(|| anyhow!("No parameter to PartialOrd"))?;
                 let quoted_param_or_self = match impl_for {
@@ -1382,16 +2320,122 @@ pub fn generate_func(
         }
     }
 
+    let mut thunks = thunk.thunks;
+    let mut mock_methods = thunk.mock_methods;
+    let mut thunk_impls = generate_func_thunk_impl(db, &func)?;
+    if let Some(accessors) = string_accessors {
+        thunks.extend(accessors.thunk.thunks);
+        mock_methods.extend(accessors.thunk.mock_methods);
+        thunk_impls.extend(accessors.thunk_impl);
+    }
+    if let Some((deleter_thunk, deleter_thunk_impl)) =
+        generate_unique_ptr_deleter(db, &func, &return_type)?
+    {
+        thunks.extend(deleter_thunk.thunks);
+        mock_methods.extend(deleter_thunk.mock_methods);
+        thunk_impls.extend(deleter_thunk_impl);
+    }
+    if let Some((shared_ptr_thunks, shared_ptr_thunk_impls)) =
+        generate_shared_ptr_thunks(db, &func, &return_type)?
+    {
+        thunks.extend(shared_ptr_thunks.thunks);
+        mock_methods.extend(shared_ptr_thunks.mock_methods);
+        thunk_impls.extend(shared_ptr_thunk_impls);
+    }
+    let required_headers = if thunk_impls.is_empty() {
+        BTreeSet::new()
+    } else {
+        let decl_ids = iter::once(func.id)
+            .chain(func.member_func_metadata.as_ref().map(|meta| meta.record_id))
+            .chain(
+                func.params
+                    .iter()
+                    .map(|param| &param.type_.cc_type)
+                    .chain(iter::once(&func.return_type.cc_type))
+                    .flat_map(crate::cc_type_decl_ids),
+            );
+        crate::required_public_headers(&ir, decl_ids)
+    };
+    let std_array_types = if thunk_impls.is_empty() {
+        vec![]
+    } else {
+        func.params
+            .iter()
+            .map(|param| &param.type_.cc_type)
+            .chain(iter::once(&func.return_type.cc_type))
+            .flat_map(crate::cc_std_array_types)
+            .collect()
+    };
     let generated_item = GeneratedItem {
         item: api_func,
-        thunks: thunk,
+        thunks,
+        mock_methods,
         features,
-        thunk_impls: generate_func_thunk_impl(db, &func)?,
+        thunk_impls,
+        stable_thunks: stable_thunk(db, &func)?.into_iter().collect(),
+        required_headers,
+        std_array_types,
+        ext_trait_decls,
+        ext_trait_impls,
         ..Default::default()
     };
     Ok(Some((Rc::new(generated_item), Rc::new(function_id))))
 }
 
+/// Returns the parameters, the arguments and the return type of the method of
+/// the record's extension trait (see `generate_record::generate_record_ext_trait`)
+/// which delegates to the method with the parameters `api_params`.
+///
+/// `Self` is replaced with `record_name`, except in the `self` parameter, so
+/// that the trait can be implemented for other types than the record (e.g. for
+/// mocks). Returns `None` if a parameter isn't a plain `self` or identifier.
+fn ext_trait_method_signature(
+    record_name: &Ident,
+    api_params: &[TokenStream],
+    return_type: &TokenStream,
+) -> Option<(Vec<TokenStream>, Vec<TokenStream>, TokenStream)> {
+    let mut params = Vec::with_capacity(api_params.len());
+    let mut args = Vec::with_capacity(api_params.len());
+    for param in api_params {
+        match syn::parse2::<syn::FnArg>(param.clone()).ok()? {
+            syn::FnArg::Receiver(receiver) => {
+                params.push(receiver.into_token_stream());
+                args.push(quote! { self });
+            }
+            syn::FnArg::Typed(syn::PatType { pat, ty, .. }) => {
+                // `mut` isn't allowed in the parameters of the trait declaration, and isn't
+                // needed by the delegating implementation.
+                let syn::Pat::Ident(syn::PatIdent { ident, subpat: None, .. }) = *pat else {
+                    return None;
+                };
+                let ty = replace_self(ty.into_token_stream(), record_name);
+                params.push(quote! { #ident: #ty });
+                args.push(ident.into_token_stream());
+            }
+        }
+    }
+    Some((params, args, replace_self(return_type.clone(), record_name)))
+}
+
+/// Replaces the `Self` keyword in `tokens` with `record_name`.
+fn replace_self(tokens: TokenStream, record_name: &Ident) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|tt| match tt {
+            TokenTree::Ident(ident) if ident == "Self" => TokenTree::Ident(record_name.clone()),
+            TokenTree::Group(group) => {
+                let mut new_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    replace_self(group.stream(), record_name),
+                );
+                new_group.set_span(group.span());
+                TokenTree::Group(new_group)
+            }
+            tt => tt,
+        })
+        .collect()
+}
+
 /// The function signature for a function's bindings.
 struct BindingsSignature {
     /// The lifetime parameters for the Rust function.
@@ -1434,6 +2478,8 @@ fn function_signature(
     param_idents: &[Ident],
     param_types: &mut Vec<RsTypeKind>,
     return_type: &mut RsTypeKind,
+    out_param: Option<&OutParam>,
+    string_view_params_as_str: bool,
 ) -> Result<BindingsSignature> {
     let mut api_params = Vec::with_capacity(func.params.len());
     let mut thunk_args = Vec::with_capacity(func.params.len());
@@ -1474,7 +2520,66 @@ fn function_signature(
             } else {
                 quote! {#type_}
             };
-            if type_.is_c_abi_compatible_by_value() {
+            if let Some(bridge_type) = type_.as_bridge_type() {
+                api_params.push(quote! {#ident: #quoted_type_or_self});
+                thunk_args.push(bridge_type.format_rs_to_abi(quote! {#ident}));
+            } else if type_.as_std_optional().is_some() {
+                // `None` is passed as a null pointer.
+                api_params.push(quote! {#ident: #quoted_type_or_self});
+                thunk_args.push(quote! {
+                    #ident.as_ref().map_or(::core::ptr::null(), |value| value as *const _)
+                });
+            } else if type_.as_initializer_list().is_some() {
+                // The elements are copied into a `std::initializer_list` by the thunk, which
+                // can only create lists of up to `MAX_INITIALIZER_LIST_SIZE` elements.
+                let max_size = Literal::usize_unsuffixed(MAX_INITIALIZER_LIST_SIZE);
+                let message = format!(
+                    "`{ident}` is passed as a `std::initializer_list`, which can hold at most \
+                     {MAX_INITIALIZER_LIST_SIZE} elements"
+                );
+                thunk_prepare.extend(quote! {
+                    assert!(#ident.len() <= #max_size, #message);
+                });
+                api_params.push(quote! {#ident: #quoted_type_or_self});
+                thunk_args.push(quote! {#ident.as_ptr(), #ident.len()});
+            } else if let Some((_, mutability)) = type_.as_span() {
+                // The span is created by the thunk, pointing at the elements of the slice.
+                let as_ptr = match mutability {
+                    Mutability::Const => quote! {as_ptr},
+                    Mutability::Mut => quote! {as_mut_ptr},
+                };
+                api_params.push(quote! {#ident: #quoted_type_or_self});
+                thunk_args.push(quote! {#ident.#as_ptr(), #ident.len()});
+            } else if let Some(string_view) = type_.as_string_view() {
+                // The string view is created by the thunk.
+                let param_type = string_view.format_param_type(string_view_params_as_str);
+                api_params.push(quote! {#ident: #param_type});
+                thunk_args.push(quote! {#ident.as_ptr(), #ident.len()});
+            } else if let Some(std_string) = type_.as_std_string() {
+                // The characters are copied into a `std::string` by the thunk.
+                if let ImplKind::Trait { .. } = impl_kind {
+                    bail!("`std::string` parameters are not supported on functions that implement a trait");
+                }
+                let param_type = std_string.format_param_type();
+                api_params.push(quote! {#ident: #param_type});
+                thunk_args.push(quote! {#ident.as_ptr(), #ident.len()});
+            } else if type_.as_unique_ptr().is_some() {
+                // The pointer is released, and owned by a `std::unique_ptr` again in the thunk.
+                api_params.push(quote! {#ident: #quoted_type_or_self});
+                thunk_args.push(UniquePtr::format_thunk_arg(ident));
+            } else if type_.as_shared_ptr().is_some() {
+                // The handle is released, and the `std::shared_ptr` is taken out of it in the
+                // thunk.
+                api_params.push(quote! {#ident: #quoted_type_or_self});
+                thunk_args.push(SharedPtr::format_thunk_arg(ident));
+            } else if let Some(std_function) = type_.as_std_function() {
+                // The closure is boxed, and wrapped into a `std::function` by the thunk.
+                if let ImplKind::Trait { .. } = impl_kind {
+                    bail!("`std::function` parameters are not supported on functions that implement a trait");
+                }
+                api_params.push(quote! {#ident: #quoted_type_or_self});
+                thunk_args.push(std_function.format_thunk_args(ident));
+            } else if type_.is_c_abi_compatible_by_value() {
                 api_params.push(quote! {#ident: #quoted_type_or_self});
                 thunk_args.push(quote! {#ident});
             } else {
@@ -1484,14 +2589,106 @@ fn function_signature(
         }
     }
 
-    let mut lifetimes: Vec<Lifetime> = unique_lifetimes(&*param_types).collect();
+    // Replace each `crubit_buffer` pointer with a slice, and drop its length
+    // from the API. The thunk still receives both.
+    let buffer_params = buffer_params(func, param_types)?;
+    let mut removed_api_params = HashSet::new();
+    if !buffer_params.is_empty() {
+        if let ImplKind::Trait { .. } = impl_kind {
+            bail!("`crubit_buffer` is not supported on functions that implement a trait");
+        }
+        for buffer_param in &buffer_params {
+            let ident = &param_idents[buffer_param.ptr];
+            let element_type = buffer_param.element_type;
+            let len_type = &param_types[buffer_param.len];
+            let (slice_type, as_ptr) = match buffer_param.mutability {
+                Mutability::Const => (quote! {&[#element_type]}, quote! {as_ptr}),
+                Mutability::Mut => (quote! {&mut [#element_type]}, quote! {as_mut_ptr}),
+            };
+            api_params[buffer_param.ptr] = quote! {#ident: #slice_type};
+            thunk_args[buffer_param.ptr] = quote! {#ident.#as_ptr().cast()};
+            thunk_args[buffer_param.len] =
+                if let RsTypeKind::Primitive(PrimitiveType::usize) = len_type.unalias() {
+                    quote! {#ident.len()}
+                } else {
+                    quote! {
+                        <#len_type as ::core::convert::TryFrom<usize>>::try_from(#ident.len())
+                            .expect("the buffer is too large for its length parameter")
+                    }
+                };
+        }
+        removed_api_params.extend(buffer_params.iter().map(|buffer_param| buffer_param.len));
+    }
 
+    // Drop the `crubit_out_param` from the API, and pass uninitialized memory
+    // for the thunk to construct the value in.
     let mut quoted_return_type = None;
-    if let ImplKind::Trait {
-        trait_name: trait_name @ (TraitName::UnpinConstructor { .. } | TraitName::CtorNew(..)),
-        ..
-    } = &impl_kind
-    {
+    if let Some(out_param) = out_param {
+        if let ImplKind::Trait { .. } = impl_kind {
+            bail!("`crubit_out_param` is not supported on functions that implement a trait");
+        }
+        let value_type = &out_param.value_type;
+        thunk_prepare.extend(quote! {
+            let mut __out = ::core::mem::MaybeUninit::<#value_type>::uninit();
+        });
+        thunk_args[out_param.index] = quote! {__out.as_mut_ptr()};
+        removed_api_params.insert(out_param.index);
+        quoted_return_type = Some(out_param.format_return_type());
+    }
+
+    // A buffer which returns its length makes the function return a `usize`.
+    if let Some(buffer_param) = buffer_params.iter().find(|b| b.returns_len) {
+        ensure!(
+            out_param.is_none(),
+            "`crubit_buffer` can't return the length of `{}` from a function with a \
+             `crubit_out_param`",
+            param_idents[buffer_param.ptr]
+        );
+        let is_unsigned_integer = matches!(
+            return_type.unalias(),
+            RsTypeKind::Primitive(
+                PrimitiveType::u8
+                    | PrimitiveType::u16
+                    | PrimitiveType::u32
+                    | PrimitiveType::u64
+                    | PrimitiveType::usize
+                    | PrimitiveType::c_uchar
+                    | PrimitiveType::c_ushort
+                    | PrimitiveType::c_uint
+                    | PrimitiveType::c_ulong
+                    | PrimitiveType::c_ulonglong
+            )
+        );
+        ensure!(
+            is_unsigned_integer,
+            "Functions returning the length of the `crubit_buffer` `{}` must return an unsigned \
+             integer",
+            param_idents[buffer_param.ptr]
+        );
+        quoted_return_type = Some(quote! {usize});
+    }
+
+    if !removed_api_params.is_empty() {
+        api_params = api_params
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !removed_api_params.contains(i))
+            .map(|(_, api_param)| api_param)
+            .collect();
+    }
+
+    let mut lifetimes: Vec<Lifetime> = unique_lifetimes(&*param_types).collect();
+
+    let is_constructor = match &impl_kind {
+        ImplKind::Trait {
+            trait_name: TraitName::UnpinConstructor { .. } | TraitName::CtorNew(..),
+            ..
+        } => true,
+        // Constructors bound as associated functions (e.g. `new`).
+        ImplKind::Struct { .. } => func.name == UnqualifiedIdentifier::Constructor,
+        ImplKind::None { .. } | ImplKind::Trait { .. } => false,
+    };
+    if is_constructor {
         // For constructors, we move the output parameter to be the return value.
         // The return value is "really" void.
         ensure!(
@@ -1531,7 +2728,7 @@ fn function_signature(
         }
 
         // CtorNew groups parameters into a tuple.
-        if let TraitName::CtorNew(args_type) = trait_name {
+        if let ImplKind::Trait { trait_name: TraitName::CtorNew(args_type), .. } = &impl_kind {
             let args_type = if let Some(impl_record) = impl_kind_record {
                 format_tuple_except_singleton_replacing_by_self(args_type, Some(impl_record))
             } else {
@@ -1611,7 +2808,7 @@ fn generate_func_thunk(
     param_idents: &[Ident],
     param_types: &[RsTypeKind],
     return_type: &RsTypeKind,
-) -> Result<TokenStream> {
+) -> Result<GeneratedItem> {
     let thunk_attr = if can_skip_cc_thunk(db, func) {
         let mangled_name = func.mangled_name.as_ref();
         quote! {#[link_name = #mangled_name]}
@@ -1638,6 +2835,14 @@ fn generate_func_thunk(
             )
         })?);
         out_param_ident = Some(param_idents.next().unwrap().clone());
+    } else if let Some(value_type) = return_type.as_std_optional() {
+        // The value (if any) is returned through an out parameter, and the thunk returns
+        // whether there is one.
+        out_param = Some(quote! {
+            &mut ::core::mem::MaybeUninit< #value_type >
+        });
+        out_param_ident = Some(make_rs_ident("__return"));
+        return_type_fragment = quote! { -> bool };
     } else if !return_type.is_c_abi_compatible_by_value() {
         // For return types that can't be passed by value, create a new out parameter.
         // The lifetime doesn't matter, so we can insert a new anonymous lifetime here.
@@ -1646,25 +2851,93 @@ fn generate_func_thunk(
         });
         out_param_ident = Some(make_rs_ident("__return"));
         return_type_fragment = quote! {};
+    } else if return_type.as_string_view().is_some() {
+        // The number of characters is returned through an out parameter, and the thunk
+        // returns the pointer to them.
+        out_param = Some(quote! { &mut usize });
+        out_param_ident = Some(make_rs_ident("__return_size"));
+        return_type_fragment = quote! { -> *const u8 };
+    } else if return_type.as_std_string().is_some() {
+        // The string is passed back through a callback (see `format_return_thunk_params`).
+        return_type_fragment = quote! {};
+    } else if return_type.as_absl_status().is_some() {
+        // The status is passed back through a callback (see `format_return_thunk_params`).
+        return_type_fragment = quote! {};
+    } else if let Some(unique_ptr) = return_type.as_unique_ptr() {
+        let thunk_type = unique_ptr.format_thunk_type();
+        return_type_fragment = quote! { -> #thunk_type };
+    } else if return_type.as_shared_ptr().is_some() {
+        let thunk_type = SharedPtr::format_thunk_type();
+        return_type_fragment = quote! { -> #thunk_type };
+    } else if let Some(bridge_type) = return_type.as_bridge_type() {
+        let abi_type = bridge_type.format_rs_abi_type();
+        return_type_fragment = quote! { -> #abi_type };
     }
 
-    let thunk_ident = thunk_ident(func);
+    let thunk_ident = thunk_ident(db, func);
 
     let generic_params = format_generic_params(&lifetimes, std::iter::empty::<syn::Ident>());
-    let param_idents = out_param_ident.as_ref().into_iter().chain(param_idents);
-    let param_types = out_param.into_iter().chain(param_types.map(|t| {
-        if !t.is_c_abi_compatible_by_value() {
+    let mut params = out_param_ident.into_iter().zip(out_param).collect_vec();
+    if let Some(std_string) = return_type.as_std_string() {
+        params.extend(std_string.format_return_thunk_params());
+    }
+    if let Some(absl_status) = return_type.as_absl_status() {
+        params.extend(absl_status.format_return_thunk_params());
+    }
+    for (ident, t) in param_idents.zip(param_types) {
+        let param_type = if let Some(bridge_type) = t.as_bridge_type() {
+            bridge_type.format_rs_abi_type()
+        } else if let Some(value_type) = t.as_std_optional() {
+            quote! {*const #value_type}
+        } else if let Some(unique_ptr) = t.as_unique_ptr() {
+            unique_ptr.format_thunk_type()
+        } else if t.as_shared_ptr().is_some() {
+            SharedPtr::format_thunk_type()
+        } else if let Some(std_function) = t.as_std_function() {
+            // The closure is passed as a handle, followed by the functions calling and dropping it.
+            params.extend(std_function.format_thunk_params(ident));
+            continue;
+        } else if let Some(element_type) = t.as_initializer_list() {
+            // The slice is passed as a pointer to its elements, followed by its length.
+            params.push((ident.clone(), quote! {*const #element_type}));
+            params.push((format_ident!("__{}_size", ident), quote! {usize}));
+            continue;
+        } else if let Some((element_type, mutability)) = t.as_span() {
+            // The slice is passed as a pointer to its elements, followed by its length.
+            let mutability = mutability.format_for_pointer();
+            params.push((ident.clone(), quote! {*#mutability #element_type}));
+            params.push((format_ident!("__{}_size", ident), quote! {usize}));
+            continue;
+        } else if t.as_string_view().is_some() || t.as_std_string().is_some() {
+            // The string (view) is passed as a pointer to its characters, followed by their
+            // number.
+            params.push((ident.clone(), quote! {*const u8}));
+            params.push((format_ident!("__{}_size", ident), quote! {usize}));
+            continue;
+        } else if !t.is_c_abi_compatible_by_value() {
             quote! {&mut #t}
         } else {
             quote! {#t}
-        }
-    }));
+        };
+        params.push((ident.clone(), param_type));
+    }
 
-    Ok(quote! {
-        #thunk_attr
-        pub(crate) fn #thunk_ident #generic_params( #( #param_idents: #param_types ),*
-        ) #return_type_fragment ;
-    })
+    // Thunks are only visible outside of the generated crate when they are
+    // re-exported through the `raw` submodule.
+    let thunk_visibility = if db.options().generate_raw_thunks_module {
+        quote! { pub }
+    } else {
+        quote! { pub(crate) }
+    };
+    Ok(generate_thunk_decl(
+        db,
+        thunk_attr,
+        thunk_visibility,
+        &thunk_ident,
+        generic_params,
+        &params,
+        return_type_fragment,
+    ))
 }
 
 /// Formats singletons as themselves, and collections of n!=1 items as a tuple.
@@ -1708,9 +2981,38 @@ fn format_tuple_except_singleton_replacing_by_self(
     }
 }
 
-/// Identifies all functions having overloads that we can't import (yet).
-///
-/// TODO(b/213280424): Implement support for overloaded functions.
+/// How the Rust bindings of overloaded functions are named, so that each
+/// overload gets a distinct name.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverloadNaming {
+    /// Overloaded functions don't get bindings.
+    #[default]
+    None,
+    /// The names of the overloads are suffixed with the names of their
+    /// parameter types, e.g. `f_c_int` for `f(int)`. An overload without
+    /// parameters keeps its name.
+    ParamTypes,
+    /// Like `bindgen`, the first overload keeps its name, and the names of the
+    /// other overloads are suffixed with their ordinal, e.g. `f1`, `f2`.
+    Ordinal,
+}
+
+impl std::str::FromStr for OverloadNaming {
+    type Err = arc_anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "" | "none" => Self::None,
+            "param_types" => Self::ParamTypes,
+            "ordinal" => Self::Ordinal,
+            _ => bail!("Unknown overload naming policy: {s:?}"),
+        })
+    }
+}
+
+/// Identifies all functions having overloads, which can only be imported if
+/// they are renamed (see `overload_rs_names`).
 pub fn overloaded_funcs(db: &dyn BindingsGenerator) -> Rc<HashSet<Rc<FunctionId>>> {
     let mut seen_funcs = HashSet::new();
     let mut overloaded_funcs = HashSet::new();
@@ -1725,6 +3027,144 @@ pub fn overloaded_funcs(db: &dyn BindingsGenerator) -> Rc<HashSet<Rc<FunctionId>
     Rc::new(overloaded_funcs)
 }
 
+/// Returns the Rust names of the overloaded functions, which disambiguate them
+/// according to `db.options().overload_naming`, by the IDs of the functions.
+///
+/// Overloads are left out if they can't be renamed, e.g. because they are
+/// operators, or because the new names would still collide.
+///
+/// Constructors bound as associated functions (e.g. `new`) are disambiguated
+/// with `OverloadNaming::ParamTypes` unless another policy is selected, since
+/// these bindings are only generated on request.
+pub fn overload_rs_names(db: &dyn BindingsGenerator) -> Rc<HashMap<ItemId, Rc<str>>> {
+    let overloaded_funcs = db.overloaded_funcs();
+    let mut function_ids = HashSet::new();
+    let mut overloads: HashMap<Rc<FunctionId>, Vec<Rc<Func>>> = HashMap::new();
+    for func in db.ir().functions() {
+        if let Ok(Some((_, function_id))) = db.generate_func(func.clone()) {
+            if overloaded_funcs.contains(&function_id) {
+                overloads.entry(function_id.clone()).or_default().push(func.clone());
+            }
+            function_ids.insert(function_id);
+        }
+    }
+
+    let mut candidates = vec![];
+    for (function_id, funcs) in overloads {
+        // Trait methods (e.g. operators) have to keep their names.
+        if function_id.self_type.is_some() {
+            continue;
+        }
+        let is_ctor_fn = funcs.iter().all(|func| func.name == UnqualifiedIdentifier::Constructor);
+        let overload_naming = match db.options().overload_naming {
+            OverloadNaming::None if is_ctor_fn => OverloadNaming::ParamTypes,
+            overload_naming => overload_naming,
+        };
+        let Some(names) = funcs
+            .iter()
+            .enumerate()
+            .map(|(ordinal, func)| {
+                let name = match &func.name {
+                    UnqualifiedIdentifier::Identifier(id) => id.identifier.to_string(),
+                    UnqualifiedIdentifier::Constructor => {
+                        let segment = function_id.function_path.segments.last()?;
+                        syn::ext::IdentExt::unraw(&segment.ident).to_string()
+                    }
+                    _ => return None,
+                };
+                match overload_naming {
+                    OverloadNaming::None => None,
+                    OverloadNaming::ParamTypes => {
+                        let suffix = overload_suffix_for_params(db, func)?;
+                        Some(if suffix.is_empty() { name } else { format!("{name}_{suffix}") })
+                    }
+                    OverloadNaming::Ordinal if ordinal == 0 => Some(name),
+                    OverloadNaming::Ordinal => Some(format!("{name}{ordinal}")),
+                }
+            })
+            .collect::<Option<Vec<String>>>()
+        else {
+            continue;
+        };
+        let renamed_ids =
+            names.iter().map(|name| function_id.renamed(name)).collect::<HashSet<_>>();
+        candidates.push((function_id, funcs, names, renamed_ids));
+    }
+
+    // The new names have to be distinct from each other, from the names of the
+    // other functions, and from the new names of the other overload sets (e.g.
+    // `f1` of the overloads of `f`, and `f1` of the overloads of `f1`).
+    let mut renamed_id_counts: HashMap<FunctionId, usize> = HashMap::new();
+    for (.., renamed_ids) in &candidates {
+        for renamed_id in renamed_ids {
+            *renamed_id_counts.entry(renamed_id.clone()).or_default() += 1;
+        }
+    }
+    let mut rs_names = HashMap::new();
+    for (function_id, funcs, names, renamed_ids) in candidates {
+        let is_unambiguous = renamed_ids.len() == names.len()
+            && renamed_ids.iter().all(|renamed_id| {
+                renamed_id_counts[renamed_id] == 1
+                    && (*renamed_id == *function_id || !function_ids.contains(renamed_id))
+            });
+        if !is_unambiguous {
+            continue;
+        }
+        for (func, name) in funcs.iter().zip(names) {
+            rs_names.insert(func.id, Rc::from(name));
+        }
+    }
+    Rc::new(rs_names)
+}
+
+/// Returns the suffix that `OverloadNaming::ParamTypes` appends to the name of
+/// `func`, e.g. `c_int_ptr_const_c_char` for `f(int, const char*)`, or `None`
+/// if the types of the parameters can't be formatted.
+fn overload_suffix_for_params(db: &dyn BindingsGenerator, func: &Func) -> Option<String> {
+    let params = if func.is_instance_method() { &func.params[1..] } else { &func.params[..] };
+    let mut words = vec![];
+    for param in params {
+        let rs_type_kind = db.rs_type_kind(param.type_.rs_type.clone()).ok()?;
+        type_name_words(rs_type_kind.to_token_stream(), &mut words);
+    }
+    Some(words.join("_"))
+}
+
+/// Appends the words naming the type formatted as `tokens` to `words`: the
+/// last segments of paths, `ptr` for pointers, `ref` for references and the
+/// `const`/`mut` qualifiers, but not the lifetimes.
+fn type_name_words(tokens: TokenStream, words: &mut Vec<String>) {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) => {
+                let word = ident.to_string();
+                // Keywords may be followed by a path starting with `::`, e.g. in `*const
+                // ::core::ffi::c_int`.
+                let is_keyword = matches!(word.as_str(), "const" | "mut" | "dyn" | "impl");
+                let is_path_prefix = matches!(
+                    tokens.peek(),
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == ':'
+                );
+                if is_keyword || !is_path_prefix {
+                    words.push(word.trim_start_matches("r#").to_string());
+                }
+            }
+            TokenTree::Punct(punct) => match punct.as_char() {
+                '*' => words.push("ptr".to_string()),
+                '&' => words.push("ref".to_string()),
+                '\'' => {
+                    // Skip the name of the lifetime.
+                    tokens.next();
+                }
+                _ => {}
+            },
+            TokenTree::Group(group) => type_name_words(group.stream(), words),
+            TokenTree::Literal(literal) => words.push(literal.to_string()),
+        }
+    }
+}
+
 fn unique_lifetimes<'a>(
     types: impl IntoIterator<Item = &'a RsTypeKind> + 'a,
 ) -> impl Iterator<Item = Lifetime> + 'a {
@@ -1735,104 +3175,695 @@ fn unique_lifetimes<'a>(
         .filter(move |lifetime| unordered_lifetimes.insert(lifetime.clone()))
 }
 
-fn thunk_ident(func: &Func) -> Ident {
-    let odr_suffix = if func.is_member_or_descendant_of_class_template {
-        func.owning_target.convert_to_cc_identifier()
-    } else {
-        String::new()
-    };
-    format_ident!("__rust_thunk__{}{odr_suffix}", func.mangled_name.as_ref())
+/// Whether `func` is a member `operator->` or a (unary) member `operator*`.
+fn is_pointer_operator(func: &Func) -> bool {
+    let is_pointer_operator_name = matches!(
+        &func.name,
+        UnqualifiedIdentifier::Operator(op) if matches!(op.name.as_ref(), "->" | "*")
+    );
+    is_pointer_operator_name && func.is_instance_method() && func.params.len() == 1
 }
 
-fn generate_func_thunk_impl(db: &dyn BindingsGenerator, func: &Func) -> Result<TokenStream> {
-    if can_skip_cc_thunk(db, func) {
-        return Ok(quote! {});
+/// Generates an `as_ref` method that exposes the result of a (const)
+/// `operator->` or `operator*` (bound as the `get` method) as an
+/// `Option<&T>`.
+///
+/// This is only possible when the lifetime of the result is known (e.g. via
+/// lifetime annotations or `#pragma clang lifetime_elision`) - otherwise `get`
+/// returns a raw pointer and no `as_ref` method is generated.
+fn generate_pointer_operator_as_ref(
+    record: &Record,
+    param_types: &[RsTypeKind],
+    return_type: &RsTypeKind,
+) -> TokenStream {
+    if !param_types.first().is_some_and(|this| this.is_shared_ref_to(record)) {
+        return quote! {};
     }
-    let ir = db.ir();
-    let thunk_ident = thunk_ident(func);
-    let implementation_function = match &func.name {
-        UnqualifiedIdentifier::Operator(op) => {
-            let name = syn::parse_str::<TokenStream>(&op.name)?;
-            quote! { operator #name }
-        }
-        UnqualifiedIdentifier::Identifier(id) => {
-            let fn_ident = crate::format_cc_ident(&id.identifier);
-            match func.member_func_metadata.as_ref() {
-                Some(meta) => {
-                    if meta.instance_method_metadata.is_some() {
-                        quote! { #fn_ident }
-                    } else {
-                        let record: &Rc<Record> = ir.find_decl(meta.record_id)?;
-                        let record_ident = crate::format_cc_ident(record.cc_name.as_ref());
-                        let namespace_qualifier =
-                            ir.namespace_qualifier(record)?.format_for_cc()?;
-                        quote! { #namespace_qualifier #record_ident :: #fn_ident }
-                    }
-                }
-                None => {
-                    let namespace_qualifier = ir.namespace_qualifier(func)?.format_for_cc()?;
-                    quote! { #namespace_qualifier #fn_ident }
+    match return_type {
+        RsTypeKind::Option(inner) => match &**inner {
+            RsTypeKind::Reference { referent, .. } => quote! {
+                __NEWLINE__
+                /// Returns a reference to the pointee of `get()` (or `None` if it is null).
+                #[inline(always)]
+                pub fn as_ref(&self) -> Option<& #referent> {
+                    self.get().map(|r| &*r)
                 }
+            },
+            _ => quote! {},
+        },
+        RsTypeKind::Reference { referent, .. } => quote! {
+            __NEWLINE__
+            /// Returns a reference to the pointee of `get()`.
+            #[inline(always)]
+            pub fn as_ref(&self) -> Option<& #referent> {
+                Some(&*self.get())
             }
-        }
-        // Use `destroy_at` to avoid needing to spell out the class name. Destructor identiifers
-        // use the name of the type itself, without namespace qualification, template
-        // parameters, or aliases. We do not need to use that naming scheme anywhere else in
-        // the bindings, and it can be difficult (impossible?) to spell in the general case. By
-        // using destroy_at, we avoid needing to determine or remember what the correct spelling
-        // is. Similar arguments apply to `construct_at`.
-        UnqualifiedIdentifier::Constructor => {
-            quote! { crubit::construct_at }
-        }
-        UnqualifiedIdentifier::Destructor => quote! {std::destroy_at},
-    };
-
-    let mut param_idents =
-        func.params.iter().map(|p| crate::format_cc_ident(&p.identifier.identifier)).collect_vec();
+        },
+        _ => quote! {},
+    }
+}
 
-    let mut param_types = func
-        .params
-        .iter()
-        .map(|p| {
-            let formatted = crate::format_cc_type(&p.type_.cc_type, &ir)?;
-            if !db.rs_type_kind(p.type_.rs_type.clone())?.is_c_abi_compatible_by_value() {
-                // non-Unpin types are wrapped by a pointer in the thunk.
-                Ok(quote! {#formatted *})
-            } else {
-                Ok(formatted)
-            }
-        })
-        .collect::<Result<Vec<_>>>()?;
+/// Returns the index type and the `Output` type of the `Index` (or, if
+/// `mutability` is `Mut`, `IndexMut`) impl for a member `operator[]` with the
+/// given parameter and return types, or `None` if the operator can't implement
+/// the trait.
+///
+/// The operator must take `this` and the index, and return a reference to the
+/// output with the same mutability and lifetime as `this`.
+fn index_trait_types(
+    record: &Record,
+    mutability: Mutability,
+    param_types: &[RsTypeKind],
+    return_type: &RsTypeKind,
+) -> Option<(RsTypeKind, RsTypeKind)> {
+    let [this, index] = param_types else {
+        return None;
+    };
+    let (
+        RsTypeKind::Reference { referent: this_referent, mutability: this_mutability, lifetime },
+        RsTypeKind::Reference {
+            referent: output,
+            mutability: output_mutability,
+            lifetime: output_lifetime,
+        },
+    ) = (this, return_type)
+    else {
+        return None;
+    };
+    if !this_referent.is_record(record)
+        || *this_mutability != mutability
+        || *output_mutability != mutability
+        || output_lifetime != lifetime
+        || !index.is_unpin()
+        || index.lifetimes().next().is_some()
+        || output.lifetimes().next().is_some()
+    {
+        return None;
+    }
+    // `index_mut` takes `&mut self` and returns `&mut Self::Output`, rather
+    // than pinned references.
+    if mutability == Mutability::Mut && (!record.is_unpin() || !output.is_unpin()) {
+        return None;
+    }
+    Some((index.clone(), (**output).clone()))
+}
 
-    let arg_expressions = func
-        .params
-        .iter()
-        .map(|p| {
-            let ident = crate::format_cc_ident(&p.identifier.identifier);
-            match p.type_.cc_type.name.as_deref() {
-                Some("&") => Ok(quote! { * #ident }),
-                Some("&&") => Ok(quote! { std::move(* #ident) }),
-                _ => {
-                    // non-Unpin types are wrapped by a pointer in the thunk.
-                    if !db.rs_type_kind(p.type_.rs_type.clone())?.is_c_abi_compatible_by_value() {
-                        Ok(quote! { std::move(* #ident) })
-                    } else {
-                        Ok(quote! { #ident })
-                    }
-                }
-            }
-        })
-        .collect::<Result<Vec<_>>>()?;
+/// Whether a const member `operator[]` of `record` implements `Index` with the
+/// given index and `Output` types.
+fn has_index_impl(
+    db: &dyn BindingsGenerator,
+    record: &Record,
+    index: &RsTypeKind,
+    output: &RsTypeKind,
+) -> bool {
+    let ir = db.ir();
+    let index_operator = UnqualifiedIdentifier::Operator(Operator { name: Rc::from("[]") });
+    let has_index_impl = ir
+        .get_functions_by_name(&index_operator)
+        .filter(|f| f.member_func_metadata.as_ref().map(|meta| meta.record_id) == Some(record.id))
+        .any(|f| {
+            let Ok(param_types) = f
+                .params
+                .iter()
+                .map(|p| db.rs_type_kind(p.type_.rs_type.clone()))
+                .collect::<Result<Vec<_>>>()
+            else {
+                return false;
+            };
+            let Ok(return_type) = db.rs_type_kind(f.return_type.rs_type.clone()) else {
+                return false;
+            };
+            index_trait_types(record, Mutability::Const, &param_types, &return_type)
+                == Some((index.clone(), output.clone()))
+        });
+    has_index_impl
+}
 
-    // Here, we add a `__return` parameter if the return type can't be passed by
-    // value across `extern "C"` ABI.  (We do this after the arg_expressions
-    // computation, so that it's only in the parameter list, not the argument
-    // list.)
-    let is_return_value_c_abi_compatible =
-        db.rs_type_kind(func.return_type.rs_type.clone())?.is_c_abi_compatible_by_value();
+fn is_call_operator(func: &Func) -> bool {
+    matches!(&func.name, UnqualifiedIdentifier::Operator(op) if op.name.as_ref() == "()")
+}
 
-    let return_type_name = if !is_return_value_c_abi_compatible {
-        param_idents.insert(0, crate::format_cc_ident("__return"));
+/// Generates an `as_fn` method that adapts `&Record` into an `impl Fn(...)`
+/// closure that invokes the (const) `operator()` bound as the `call` method.
+///
+/// Returns an empty `TokenStream` if the signature of `operator()` can't be
+/// easily expressed as a closure (e.g. when it requires a mutable `this`, or
+/// when it uses lifetimes other than the lifetime of `this`).
+fn generate_call_operator_fn_adapter(
+    record: &Record,
+    param_idents: &[Ident],
+    param_types: &[RsTypeKind],
+    return_type: &RsTypeKind,
+    quoted_return_type: &TokenStream,
+) -> TokenStream {
+    let (Some(this_type), Some(arg_idents), Some(arg_types)) =
+        (param_types.first(), param_idents.get(1..), param_types.get(1..))
+    else {
+        return quote! {};
+    };
+    let is_closure_compatible = |ty: &RsTypeKind| ty.is_unpin() && ty.lifetimes().next().is_none();
+    if !this_type.is_shared_ref_to(record)
+        || !arg_types.iter().all(is_closure_compatible)
+        || !is_closure_compatible(return_type)
+    {
+        return quote! {};
+    }
+    let arrow = if quoted_return_type.is_empty() { quote! {} } else { quote! { -> } };
+    quote! {
+        __NEWLINE__
+        /// Returns a closure that calls `operator()` (see `call`).
+        #[inline(always)]
+        pub fn as_fn(&self) -> impl Fn( #( #arg_types ),* ) #arrow #quoted_return_type + '_ {
+            move | #( #arg_idents ),* | self.call( #( #arg_idents ),* )
+        }
+    }
+}
+
+/// Whether `record` is `std::string`, i.e. `std::basic_string<char>` with the
+/// default traits and allocator.
+fn is_std_string(record: &Record) -> bool {
+    let cc_name = record.cc_name.as_ref();
+    cc_name == "std::basic_string<char>"
+        || cc_name.starts_with("std::basic_string<char, std::char_traits<char>,")
+}
+
+/// The accessors generated for a method returning `const std::string&` (see
+/// `generate_string_accessors`).
+struct StringAccessors {
+    /// The `..._bytes` and `..._to_owned` methods.
+    api: TokenStream,
+    /// The declaration of the thunk returning the contents of the string.
+    thunk: GeneratedItem,
+    /// The C++ implementation of that thunk.
+    thunk_impl: TokenStream,
+}
+
+/// Generates a pair of accessors for a (const, parameterless) method returning
+/// `const std::string&`, bound as `func_name`:
+///
+/// * `..._bytes` borrows the contents of the string as `&[u8]` (or as
+///   `*const [u8]` if the lifetime of the result is unknown),
+/// * `..._to_owned` copies the contents of the string into a `Vec<u8>`.
+///
+/// Returns `None` for any other method. If the name of an accessor is taken
+/// by another method of `record`, the accessors are replaced by a comment, and
+/// an error is reported.
+fn generate_string_accessors(
+    db: &dyn BindingsGenerator,
+    func: &Func,
+    record: &Record,
+    func_name: &Ident,
+    param_types: &[RsTypeKind],
+    return_type: &RsTypeKind,
+) -> Result<Option<StringAccessors>> {
+    if !matches!(func.name, UnqualifiedIdentifier::Identifier(_))
+        || !func.is_instance_method()
+        || func.out_param.is_some()
+    {
+        return Ok(None);
+    }
+    let [this_type] = param_types else {
+        return Ok(None);
+    };
+    let is_string = |ty: &RsTypeKind| match ty.unalias() {
+        RsTypeKind::Record { record, .. } => is_std_string(record),
+        _ => false,
+    };
+    let detail_module_path = crate::detail_module_path_tokens(db);
+    let thunk_ident = format_ident!("{}__bytes", thunk_ident(db, func));
+    let bytes_name = format_ident!("{}_bytes", func_name);
+    let to_owned_name = format_ident!("{}_to_owned", func_name);
+    let doc_bytes = format!(" Returns the contents of the string returned by `{func_name}`.");
+    let doc_to_owned =
+        format!(" Returns a copy of the contents of the string returned by `{func_name}`.");
+    let string_type;
+    let api;
+    match (this_type, return_type) {
+        (
+            RsTypeKind::Reference { lifetime: this_lifetime, .. },
+            RsTypeKind::Reference { referent, mutability: Mutability::Const, lifetime },
+        ) if this_type.is_shared_ref_to(record) && is_string(referent) => {
+            string_type = referent;
+            // The borrowing accessor is only generated if the string is known to
+            // live as long as `self`.
+            let bytes = if lifetime == this_lifetime {
+                quote! {
+                    __NEWLINE__
+                    #[doc = #doc_bytes]
+                    #[inline(always)]
+                    pub fn #bytes_name(&self) -> &[u8] {
+                        let mut __size = 0usize;
+                        unsafe {
+                            let __data = #detail_module_path::#thunk_ident(
+                                self.#func_name(), &mut __size);
+                            ::core::slice::from_raw_parts(__data, __size)
+                        }
+                    }
+                }
+            } else {
+                quote! {}
+            };
+            api = quote! {
+                #bytes
+                __NEWLINE__
+                #[doc = #doc_to_owned]
+                #[inline(always)]
+                pub fn #to_owned_name(&self) -> ::std::vec::Vec<u8> {
+                    let mut __size = 0usize;
+                    unsafe {
+                        let __data = #detail_module_path::#thunk_ident(
+                            self.#func_name(), &mut __size);
+                        ::core::slice::from_raw_parts(__data, __size).to_vec()
+                    }
+                }
+            };
+        }
+        (
+            RsTypeKind::Pointer { pointee: this_pointee, mutability: Mutability::Const },
+            RsTypeKind::Pointer { pointee, mutability: Mutability::Const },
+        ) if this_pointee.is_record(record) && is_string(pointee) => {
+            string_type = pointee;
+            api = quote! {
+                __NEWLINE__
+                #[doc = #doc_bytes]
+                #[inline(always)]
+                pub unsafe fn #bytes_name(__this: *const Self) -> *const [u8] {
+                    let mut __size = 0usize;
+                    let __data =
+                        #detail_module_path::#thunk_ident(Self::#func_name(__this), &mut __size);
+                    ::core::ptr::slice_from_raw_parts(__data, __size)
+                }
+                __NEWLINE__
+                #[doc = #doc_to_owned]
+                #[inline(always)]
+                pub unsafe fn #to_owned_name(__this: *const Self) -> ::std::vec::Vec<u8> {
+                    (*Self::#bytes_name(__this)).to_vec()
+                }
+            };
+        }
+        _ => return Ok(None),
+    }
+    if let Some(name) = [&bytes_name, &to_owned_name]
+        .into_iter()
+        .find(|name| has_method_named(&db.ir(), record.id, &name.to_string()))
+    {
+        let msg = format!(
+            "The accessors of the string returned by `{func_name}` are not generated, because \
+             `{name}` collides with a method of `{}`",
+            record.rs_name
+        );
+        db.errors().insert(&anyhow!("{msg}"));
+        return Ok(Some(StringAccessors {
+            api: quote! { __COMMENT__ #msg },
+            thunk: GeneratedItem::default(),
+            thunk_impl: quote! {},
+        }));
+    }
+
+    let thunk_visibility = if db.options().generate_raw_thunks_module {
+        quote! { pub }
+    } else {
+        quote! { pub(crate) }
+    };
+    let thunk = generate_thunk_decl(
+        db,
+        quote! {},
+        thunk_visibility,
+        &thunk_ident,
+        quote! {},
+        &[
+            (make_rs_ident("__s"), quote! { *const #string_type }),
+            (make_rs_ident("__size"), quote! { *mut usize }),
+        ],
+        quote! { -> *const u8 },
+    );
+
+    let cc_string_type = crate::format_cc_type(&func.return_type.cc_type, &db.ir())?;
+    let export_macro = if db.options().thunk_export_macro.is_empty() {
+        quote! {}
+    } else {
+        syn::parse_str::<TokenStream>(&db.options().thunk_export_macro)?
+    };
+    let thunk_impl = quote! {
+        extern "C" #export_macro const char* #thunk_ident(#cc_string_type __s, std::size_t* __size) {
+            *__size = __s->size();
+            return __s->data();
+        }
+    };
+    Ok(Some(StringAccessors { api, thunk, thunk_impl }))
+}
+
+/// Returns the name of the thunk deleting the object owned by the
+/// `std::unique_ptr` returned by `func`.
+fn unique_ptr_deleter_ident(db: &dyn BindingsGenerator, func: &Func) -> Ident {
+    format_ident!("{}__delete", thunk_ident(db, func))
+}
+
+/// Generates the declaration and the C++ implementation of the thunk which
+/// deletes the object owned by the `std::unique_ptr` returned by `func`, when
+/// the `UniquePtr` holding it is dropped.
+///
+/// Returns `None` if `func` doesn't return a `std::unique_ptr`.
+fn generate_unique_ptr_deleter(
+    db: &dyn BindingsGenerator,
+    func: &Func,
+    return_type: &RsTypeKind,
+) -> Result<Option<(GeneratedItem, TokenStream)>> {
+    let Some(unique_ptr) = return_type.as_unique_ptr() else {
+        return Ok(None);
+    };
+    let thunk_ident = unique_ptr_deleter_ident(db, func);
+    let thunk_visibility = if db.options().generate_raw_thunks_module {
+        quote! { pub }
+    } else {
+        quote! { pub(crate) }
+    };
+    let thunk = generate_thunk_decl(
+        db,
+        quote! {},
+        thunk_visibility,
+        &thunk_ident,
+        quote! {},
+        &[(make_rs_ident("__ptr"), unique_ptr.format_thunk_type())],
+        quote! {},
+    );
+
+    let element_type = format_cc_unique_ptr_element_type(&func.return_type.cc_type, &db.ir())?;
+    let export_macro = if db.options().thunk_export_macro.is_empty() {
+        quote! {}
+    } else {
+        syn::parse_str::<TokenStream>(&db.options().thunk_export_macro)?
+    };
+    let thunk_impl = quote! {
+        extern "C" #export_macro void #thunk_ident(#element_type* __ptr) {
+            delete __ptr;
+        }
+    };
+    Ok(Some((thunk, thunk_impl)))
+}
+
+/// Returns the names of the thunks accessing the handle of the
+/// `std::shared_ptr` returned by `func`: `get`, `clone`, and `delete` (see
+/// `cc_shared_ptr::SharedPtrOps`).
+fn shared_ptr_thunk_idents(db: &dyn BindingsGenerator, func: &Func) -> [Ident; 3] {
+    let thunk_ident = thunk_ident(db, func);
+    ["get", "clone", "delete"].map(|op| format_ident!("{thunk_ident}__{op}"))
+}
+
+/// Generates the declarations and the C++ implementations of the thunks which
+/// access the handle of the `std::shared_ptr` returned by `func`, when the
+/// `SharedPtr` holding it is dereferenced, cloned, or dropped.
+///
+/// Returns `None` if `func` doesn't return a `std::shared_ptr`.
+fn generate_shared_ptr_thunks(
+    db: &dyn BindingsGenerator,
+    func: &Func,
+    return_type: &RsTypeKind,
+) -> Result<Option<(GeneratedItem, TokenStream)>> {
+    let Some(shared_ptr) = return_type.as_shared_ptr() else {
+        return Ok(None);
+    };
+    let [get_ident, clone_ident, delete_ident] = shared_ptr_thunk_idents(db, func);
+    let thunk_visibility = if db.options().generate_raw_thunks_module {
+        quote! { pub }
+    } else {
+        quote! { pub(crate) }
+    };
+    let handle_type = SharedPtr::format_thunk_type();
+    let handle_params = [(make_rs_ident("__handle"), handle_type.clone())];
+    let pointee_type = &shared_ptr.pointee_type;
+    let mut thunks = GeneratedItem::default();
+    for (thunk_ident, return_type_fragment) in [
+        (&get_ident, quote! { -> *mut #pointee_type }),
+        (&clone_ident, quote! { -> #handle_type }),
+        (&delete_ident, quote! {}),
+    ] {
+        let thunk = generate_thunk_decl(
+            db,
+            quote! {},
+            thunk_visibility.clone(),
+            thunk_ident,
+            quote! {},
+            &handle_params,
+            return_type_fragment,
+        );
+        thunks.thunks.extend(thunk.thunks);
+        thunks.mock_methods.extend(thunk.mock_methods);
+    }
+
+    let mut shared_ptr_type = func.return_type.cc_type.clone();
+    shared_ptr_type.is_const = false;
+    let shared_ptr_type = crate::format_cc_type(&shared_ptr_type, &db.ir())?;
+    let export_macro = if db.options().thunk_export_macro.is_empty() {
+        quote! {}
+    } else {
+        syn::parse_str::<TokenStream>(&db.options().thunk_export_macro)?
+    };
+    let thunk_impls = quote! {
+        extern "C" #export_macro #shared_ptr_type::element_type* #get_ident(void* __handle) {
+            return crubit::SharedPtrHandleGet<#shared_ptr_type>(__handle);
+        }
+        extern "C" #export_macro void* #clone_ident(void* __handle) {
+            return crubit::SharedPtrHandleClone<#shared_ptr_type>(__handle);
+        }
+        extern "C" #export_macro void #delete_ident(void* __handle) {
+            crubit::SharedPtrHandleDelete<#shared_ptr_type>(__handle);
+        }
+    };
+    Ok(Some((thunks, thunk_impls)))
+}
+
+/// Returns the `&'static` reference type which is returned instead of
+/// `return_type` by a function annotated with `crubit_returns_static`.
+///
+/// The C++ reference must be `const`: a shared `&'static` reference to an
+/// object which C++ can mutate would be unsound.
+fn static_reference_type(return_type: &RsTypeKind) -> Result<RsTypeKind> {
+    match return_type.unalias() {
+        RsTypeKind::Pointer { pointee: referent, mutability }
+        | RsTypeKind::Reference { referent, mutability, .. } => {
+            ensure!(
+                *mutability == Mutability::Const,
+                "`crubit_returns_static` requires a function returning a `const` reference"
+            );
+            Ok(RsTypeKind::Reference {
+                referent: referent.clone(),
+                mutability: Mutability::Const,
+                lifetime: Lifetime::new("static"),
+            })
+        }
+        _ => bail!("`crubit_returns_static` requires a function returning a reference"),
+    }
+}
+
+fn thunk_ident(db: &dyn BindingsGenerator, func: &Func) -> Ident {
+    if let Some(stable_thunk_name) = &func.stable_thunk_name {
+        return make_rs_ident(stable_thunk_name);
+    }
+    let odr_suffix = if func.is_member_or_descendant_of_class_template {
+        func.owning_target.convert_to_cc_identifier()
+    } else {
+        String::new()
+    };
+    let defaults_suffix = if func.num_omitted_default_args > 0 {
+        format!("__defaults{}", func.num_omitted_default_args)
+    } else {
+        String::new()
+    };
+    if db.options().hash_thunk_names {
+        let target_hash = fnv1a_32(func.owning_target.0.as_bytes());
+        format_ident!(
+            "__rust_thunk_{target_hash:08x}__{}{odr_suffix}{defaults_suffix}",
+            func.mangled_name.as_ref()
+        )
+    } else {
+        format_ident!("__rust_thunk__{}{odr_suffix}{defaults_suffix}", func.mangled_name.as_ref())
+    }
+}
+
+/// Returns the 32-bit FNV-1a hash of `bytes`.
+///
+/// Used (instead of e.g. `DefaultHasher`) because the result has to be stable
+/// across compiler versions and platforms: it becomes part of a symbol name.
+pub(crate) fn fnv1a_32(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0x811c9dc5_u32, |hash, byte| (hash ^ u32::from(*byte)).wrapping_mul(0x01000193))
+}
+
+/// Returns the C++ type of the value held by the `std::optional` type `ty`
+/// (which may be spelled using an alias).
+fn format_cc_std_optional_value_type(ty: &CcType, ir: &IR) -> Result<TokenStream> {
+    let mut optional_type = ty.clone();
+    optional_type.is_const = false;
+    let optional_type = crate::format_cc_type(&optional_type, ir)?;
+    Ok(quote! { #optional_type::value_type })
+}
+
+/// Returns the C++ type of the value of the `absl::StatusOr` type `ty`.
+fn format_cc_status_or_value_type(ty: &CcType, ir: &IR) -> Result<TokenStream> {
+    let mut status_or_type = ty.clone();
+    status_or_type.is_const = false;
+    let status_or_type = crate::format_cc_type(&status_or_type, ir)?;
+    Ok(quote! { #status_or_type::value_type })
+}
+
+/// Returns the C++ type of the object owned by the `std::unique_ptr` type `ty`.
+fn format_cc_unique_ptr_element_type(ty: &CcType, ir: &IR) -> Result<TokenStream> {
+    let mut unique_ptr_type = ty.clone();
+    unique_ptr_type.is_const = false;
+    let unique_ptr_type = crate::format_cc_type(&unique_ptr_type, ir)?;
+    Ok(quote! { #unique_ptr_type::element_type })
+}
+
+/// Returns the C++ type of the elements of the `std::initializer_list` type `ty`.
+fn format_cc_initializer_list_element_type(ty: &CcType, ir: &IR) -> Result<TokenStream> {
+    let mut list_type = ty.clone();
+    list_type.is_const = false;
+    let list_type = crate::format_cc_type(&list_type, ir)?;
+    Ok(quote! { #list_type::value_type })
+}
+
+/// Returns the C++ type of the elements of the span type `ty` (including their
+/// `const`).
+fn format_cc_span_element_type(ty: &CcType, ir: &IR) -> Result<TokenStream> {
+    let [element_type] = ty.type_args.as_slice() else {
+        bail!("Invalid span type (need exactly 1 type argument): {:?}", ty);
+    };
+    crate::format_cc_type(element_type, ir)
+}
+
+/// Returns the name of the C++ thunk parameter holding the number of elements
+/// of the `std::initializer_list` or span (or characters of the string view)
+/// parameter `param`.
+fn format_cc_param_size_ident(param: &FuncParam) -> TokenStream {
+    crate::format_cc_ident(&format!("__{}_size", param.identifier.identifier))
+}
+
+/// Returns the name of the C++ thunk parameter holding the function which calls
+/// the closure passed as the `std::function` parameter `param`.
+fn format_cc_param_call_ident(param: &FuncParam) -> TokenStream {
+    crate::format_cc_ident(&format!("__{}_call", param.identifier.identifier))
+}
+
+/// Returns the name of the C++ thunk parameter holding the function which drops
+/// the closure passed as the `std::function` parameter `param`.
+fn format_cc_param_drop_ident(param: &FuncParam) -> TokenStream {
+    crate::format_cc_ident(&format!("__{}_drop", param.identifier.identifier))
+}
+
+/// The signature of the C++ thunk of a function.
+struct CcThunkSignature {
+    return_type_name: TokenStream,
+    param_types: Vec<TokenStream>,
+    param_idents: Vec<TokenStream>,
+}
+
+fn cc_thunk_signature(db: &dyn BindingsGenerator, func: &Func) -> Result<CcThunkSignature> {
+    let ir = db.ir();
+    let mut param_idents = vec![];
+    let mut param_types = vec![];
+    for p in &func.params {
+        let ident = crate::format_cc_ident(&p.identifier.identifier);
+        let formatted = crate::format_cc_type(&p.type_.cc_type, &ir)?;
+        let type_ = db.rs_type_kind(p.type_.rs_type.clone())?;
+        let param_type = if let Some(bridge_type) = type_.as_bridge_type() {
+            bridge_type.format_cc_abi_type()
+        } else if type_.as_std_optional().is_some() {
+            // `std::nullopt` is passed as a null pointer.
+            let value_type = format_cc_std_optional_value_type(&p.type_.cc_type, &ir)?;
+            quote! {#value_type const*}
+        } else if type_.as_unique_ptr().is_some() {
+            // The ownership of the object is passed as a raw pointer.
+            let element_type = format_cc_unique_ptr_element_type(&p.type_.cc_type, &ir)?;
+            quote! {#element_type*}
+        } else if type_.as_shared_ptr().is_some() {
+            // The `std::shared_ptr` is passed as a handle (see `SharedPtr`).
+            quote! {void*}
+        } else if type_.as_std_function().is_some() {
+            // The closure is passed as a handle, followed by the functions calling and dropping it.
+            let [handle_type, call_type, drop_type] =
+                StdFunction::format_cc_thunk_param_types(&formatted);
+            param_idents.push(ident);
+            param_types.push(handle_type);
+            param_idents.push(format_cc_param_call_ident(p));
+            param_types.push(call_type);
+            param_idents.push(format_cc_param_drop_ident(p));
+            param_types.push(drop_type);
+            continue;
+        } else if type_.as_initializer_list().is_some() {
+            // The elements are passed as a pointer and their number.
+            let element_type = format_cc_initializer_list_element_type(&p.type_.cc_type, &ir)?;
+            param_idents.push(ident);
+            param_types.push(quote! {#element_type const*});
+            param_idents.push(format_cc_param_size_ident(p));
+            param_types.push(quote! {size_t});
+            continue;
+        } else if type_.as_span().is_some() {
+            // The elements are passed as a pointer and their number.
+            let element_type = format_cc_span_element_type(&p.type_.cc_type, &ir)?;
+            param_idents.push(ident);
+            param_types.push(quote! {#element_type*});
+            param_idents.push(format_cc_param_size_ident(p));
+            param_types.push(quote! {size_t});
+            continue;
+        } else if type_.as_string_view().is_some() || type_.as_std_string().is_some() {
+            // The characters are passed as a pointer and their number.
+            param_idents.push(ident);
+            param_types.push(quote! {char const*});
+            param_idents.push(format_cc_param_size_ident(p));
+            param_types.push(quote! {size_t});
+            continue;
+        } else if !type_.is_c_abi_compatible_by_value() {
+            // non-Unpin types are wrapped by a pointer in the thunk.
+            quote! {#formatted *}
+        } else {
+            formatted
+        };
+        param_idents.push(ident);
+        param_types.push(param_type);
+    }
+
+    // Here, we add a `__return` parameter if the return type can't be passed by
+    // value across `extern "C"` ABI.  (It is only in the parameter list, not the
+    // argument list.)
+    let rs_return_type = db.rs_type_kind(func.return_type.rs_type.clone())?;
+    let return_type_name = if let Some(bridge_type) = rs_return_type.as_bridge_type() {
+        bridge_type.format_cc_abi_type()
+    } else if rs_return_type.as_std_optional().is_some() {
+        param_idents.insert(0, crate::format_cc_ident("__return"));
+        let value_type = format_cc_std_optional_value_type(&func.return_type.cc_type, &ir)?;
+        param_types.insert(0, quote! {#value_type *});
+        quote! {bool}
+    } else if rs_return_type.as_string_view().is_some() {
+        param_idents.insert(0, crate::format_cc_ident("__return_size"));
+        param_types.insert(0, quote! {size_t *});
+        quote! {char const*}
+    } else if let Some(std_string) = rs_return_type.as_std_string() {
+        for (ident, type_) in std_string.format_cc_return_thunk_params().into_iter().rev() {
+            param_idents.insert(0, ident);
+            param_types.insert(0, type_);
+        }
+        quote! {void}
+    } else if let Some(absl_status) = rs_return_type.as_absl_status() {
+        let cc_value_type = match absl_status.value_type {
+            Some(_) => Some(format_cc_status_or_value_type(&func.return_type.cc_type, &ir)?),
+            None => None,
+        };
+        for (ident, type_) in
+            absl_status.format_cc_return_thunk_params(cc_value_type).into_iter().rev()
+        {
+            param_idents.insert(0, ident);
+            param_types.insert(0, type_);
+        }
+        quote! {void}
+    } else if rs_return_type.as_unique_ptr().is_some() {
+        let element_type = format_cc_unique_ptr_element_type(&func.return_type.cc_type, &ir)?;
+        quote! {#element_type*}
+    } else if rs_return_type.as_shared_ptr().is_some() {
+        quote! {void*}
+    } else if !rs_return_type.is_c_abi_compatible_by_value() {
+        param_idents.insert(0, crate::format_cc_ident("__return"));
         // In order to be modified, the return type can't be const.
         let mut cc_return_type = func.return_type.cc_type.clone();
         cc_return_type.is_const = false;
@@ -1842,6 +3873,142 @@ fn generate_func_thunk_impl(db: &dyn BindingsGenerator, func: &Func) -> Result<T
     } else {
         crate::format_cc_type(&func.return_type.cc_type, &ir)?
     };
+    Ok(CcThunkSignature { return_type_name, param_types, param_idents })
+}
+
+/// Returns the symbol name and the C++ signature (formatted from tokens, e.g.
+/// `int (int , int)`) of the thunk of `func`, if its name is pinned by the
+/// `crubit_stable_thunk` attribute.
+///
+/// Prebuilt `rs_api_impl` objects can only be linked against bindings in
+/// which the pinned thunks have the same signatures (see
+/// `check_stable_thunks`).
+fn stable_thunk(db: &dyn BindingsGenerator, func: &Func) -> Result<Option<StableThunk>> {
+    let Some(symbol) = &func.stable_thunk_name else {
+        return Ok(None);
+    };
+    let CcThunkSignature { return_type_name, param_types, .. } = cc_thunk_signature(db, func)?;
+    let signature = quote! { #return_type_name ( #( #param_types ),* ) };
+    Ok(Some(StableThunk { symbol: symbol.to_string(), signature: signature.to_string() }))
+}
+
+fn generate_func_thunk_impl(db: &dyn BindingsGenerator, func: &Func) -> Result<TokenStream> {
+    if can_skip_cc_thunk(db, func) {
+        return Ok(quote! {});
+    }
+    let ir = db.ir();
+    let thunk_ident = thunk_ident(db, func);
+    let implementation_function = match &func.name {
+        UnqualifiedIdentifier::Operator(op) => {
+            let name = syn::parse_str::<TokenStream>(&op.name)?;
+            quote! { operator #name }
+        }
+        UnqualifiedIdentifier::Identifier(id) => {
+            let fn_ident = crate::format_cc_ident(&id.identifier);
+            match func.member_func_metadata.as_ref() {
+                Some(meta) => {
+                    if meta.instance_method_metadata.is_some() {
+                        quote! { #fn_ident }
+                    } else {
+                        let record: &Rc<Record> = ir.find_decl(meta.record_id)?;
+                        let record_ident = crate::format_cc_ident(record.cc_name.as_ref());
+                        let namespace_qualifier =
+                            ir.namespace_qualifier(record)?.format_for_cc()?;
+                        quote! { #namespace_qualifier #record_ident :: #fn_ident }
+                    }
+                }
+                None => {
+                    let namespace_qualifier = ir.namespace_qualifier(func)?.format_for_cc()?;
+                    quote! { #namespace_qualifier #fn_ident }
+                }
+            }
+        }
+        // Use `destroy_at` to avoid needing to spell out the class name. Destructor identiifers
+        // use the name of the type itself, without namespace qualification, template
+        // parameters, or aliases. We do not need to use that naming scheme anywhere else in
+        // the bindings, and it can be difficult (impossible?) to spell in the general case. By
+        // using destroy_at, we avoid needing to determine or remember what the correct spelling
+        // is. Similar arguments apply to `construct_at`.
+        UnqualifiedIdentifier::Constructor => {
+            quote! { crubit::construct_at }
+        }
+        UnqualifiedIdentifier::Destructor => quote! {std::destroy_at},
+    };
+
+    let CcThunkSignature { return_type_name, param_types, param_idents } =
+        cc_thunk_signature(db, func)?;
+
+    let arg_expressions = func
+        .params
+        .iter()
+        .map(|p| {
+            let ident = crate::format_cc_ident(&p.identifier.identifier);
+            match p.type_.cc_type.name.as_deref() {
+                Some("&") => Ok(quote! { * #ident }),
+                Some("&&") => Ok(quote! { std::move(* #ident) }),
+                _ => {
+                    let type_ = db.rs_type_kind(p.type_.rs_type.clone())?;
+                    if let Some(bridge_type) = type_.as_bridge_type() {
+                        Ok(bridge_type.format_cc_from_abi(quote! { #ident }))
+                    } else if type_.as_std_optional().is_some() {
+                        let mut optional_type = p.type_.cc_type.clone();
+                        optional_type.is_const = false;
+                        let optional_type = crate::format_cc_type(&optional_type, &ir)?;
+                        Ok(quote! {
+                            #ident == nullptr ? #optional_type() : #optional_type(* #ident)
+                        })
+                    } else if type_.as_unique_ptr().is_some() {
+                        // The `std::unique_ptr` takes the ownership of the object back.
+                        let mut unique_ptr_type = p.type_.cc_type.clone();
+                        unique_ptr_type.is_const = false;
+                        let unique_ptr_type = crate::format_cc_type(&unique_ptr_type, &ir)?;
+                        Ok(UniquePtr::format_cc_thunk_arg(unique_ptr_type, quote! { #ident }))
+                    } else if type_.as_shared_ptr().is_some() {
+                        // The `std::shared_ptr` is taken out of the handle (which is deleted).
+                        let mut shared_ptr_type = p.type_.cc_type.clone();
+                        shared_ptr_type.is_const = false;
+                        let shared_ptr_type = crate::format_cc_type(&shared_ptr_type, &ir)?;
+                        Ok(SharedPtr::format_cc_thunk_arg(shared_ptr_type, quote! { #ident }))
+                    } else if type_.as_std_function().is_some() {
+                        // The closure is wrapped into a `std::function`.
+                        let mut function_type = p.type_.cc_type.clone();
+                        function_type.is_const = false;
+                        let function_type = crate::format_cc_type(&function_type, &ir)?;
+                        Ok(StdFunction::format_cc_thunk_arg(
+                            function_type,
+                            quote! { #ident },
+                            format_cc_param_call_ident(p),
+                            format_cc_param_drop_ident(p),
+                        ))
+                    } else if type_.as_initializer_list().is_some() {
+                        // The list is created by `crubit::CallWithInitializerList` below.
+                        let list_ident =
+                            crate::format_cc_ident(&format!("__{}_list", p.identifier.identifier));
+                        Ok(quote! { #list_ident })
+                    } else if type_.as_span().is_some()
+                        || type_.as_string_view().is_some()
+                        || type_.as_std_string().is_some()
+                    {
+                        // The span (or string) is constructed from the pointer and the size.
+                        let mut string_type = p.type_.cc_type.clone();
+                        string_type.is_const = false;
+                        let string_type = crate::format_cc_type(&string_type, &ir)?;
+                        let size_ident = format_cc_param_size_ident(p);
+                        Ok(quote! { #string_type(#ident, #size_ident) })
+                    } else if !type_.is_c_abi_compatible_by_value() {
+                        // non-Unpin types are wrapped by a pointer in the thunk.
+                        Ok(quote! { std::move(* #ident) })
+                    } else {
+                        Ok(quote! { #ident })
+                    }
+                }
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let rs_return_type = db.rs_type_kind(func.return_type.rs_type.clone())?;
+    let is_return_value_c_abi_compatible = rs_return_type.is_c_abi_compatible_by_value();
+    let return_bridge_type = rs_return_type.as_bridge_type();
 
     let this_ref_qualification =
         func.member_func_metadata.as_ref().and_then(|meta| match &func.name {
@@ -1872,8 +4039,73 @@ fn generate_func_thunk_impl(db: &dyn BindingsGenerator, func: &Func) -> Result<T
             (implementation_function, arg_expressions)
         };
 
-    let return_expr = quote! {#implementation_function( #( #arg_expressions ),* )};
-    let return_stmt = if !is_return_value_c_abi_compatible {
+    let mut return_expr = quote! {#implementation_function( #( #arg_expressions ),* )};
+    // A `std::initializer_list` can't be created from a runtime number of elements, so
+    // the call is made from a lambda which receives the list.
+    for p in func.params.iter().rev() {
+        if db.rs_type_kind(p.type_.rs_type.clone())?.as_initializer_list().is_none() {
+            continue;
+        }
+        let ident = crate::format_cc_ident(&p.identifier.identifier);
+        let size_ident = format_cc_param_size_ident(p);
+        let list_ident = crate::format_cc_ident(&format!("__{}_list", p.identifier.identifier));
+        let mut list_type = p.type_.cc_type.clone();
+        list_type.is_const = false;
+        let list_type = crate::format_cc_type(&list_type, &ir)?;
+        return_expr = quote! {
+            crubit::CallWithInitializerList(
+                #ident, #size_ident,
+                [&](#list_type #list_ident) -> decltype(auto) {
+                    return #return_expr;
+                })
+        };
+    }
+    let return_stmt = if let Some(out_param) = &func.out_param {
+        // The out value is default-constructed in the (uninitialized) memory
+        // provided by Rust, and destroyed again if the call fails.
+        let out_param = crate::format_cc_ident(out_param);
+        let failed = if rs_return_type.is_bool() {
+            quote! { !__status }
+        } else {
+            quote! { __status != decltype(__status){} }
+        };
+        quote! {
+            crubit::construct_at(#out_param);
+            auto __status = #return_expr;
+            if (#failed) std::destroy_at(#out_param);
+            return __status
+        }
+    } else if let Some(bridge_type) = return_bridge_type {
+        let return_expr = bridge_type.format_cc_to_abi(return_expr);
+        quote! { return #return_expr }
+    } else if rs_return_type.as_std_optional().is_some() {
+        let out_param = &param_idents[0];
+        quote! {
+            auto __optional = #return_expr;
+            if (!__optional.has_value()) return false;
+            new(#out_param) auto(*std::move(__optional));
+            return true
+        }
+    } else if rs_return_type.as_string_view().is_some() {
+        let out_param = &param_idents[0];
+        quote! {
+            auto __string_view = #return_expr;
+            *#out_param = __string_view.size();
+            return __string_view.data()
+        }
+    } else if rs_return_type.as_std_string().is_some() {
+        let (return_param, append_param) = (&param_idents[0], &param_idents[1]);
+        quote! {
+            auto __string = #return_expr;
+            #append_param(#return_param, __string.data(), __string.size())
+        }
+    } else if let Some(absl_status) = rs_return_type.as_absl_status() {
+        absl_status.format_cc_return(return_expr)
+    } else if rs_return_type.as_unique_ptr().is_some() {
+        UniquePtr::format_cc_return(return_expr)
+    } else if rs_return_type.as_shared_ptr().is_some() {
+        SharedPtr::format_cc_return(return_expr)
+    } else if !is_return_value_c_abi_compatible {
         // Explicitly use placement `new` so that we get guaranteed copy elision in
         // C++17.
         let out_param = &param_idents[0];
@@ -1901,18 +4133,68 @@ fn generate_func_thunk_impl(db: &dyn BindingsGenerator, func: &Func) -> Result<T
         }
     };
 
-    Ok(quote! {
-        extern "C" #return_type_name #thunk_ident( #( #param_types #param_idents ),* ) {
-            #return_stmt;
+    let thunk_body = if func.is_noexcept || db.options().thunk_exception_note.is_empty() {
+        quote! { #return_stmt; }
+    } else {
+        // An exception unwinding into Rust would be UB, so it is turned into a crash that names
+        // the function which threw it.
+        let note = db.options().thunk_exception_note.replace("{function}", &func.debug_name(&ir));
+        quote! {
+            try {
+                #return_stmt;
+            } catch (...) {
+                crubit::TerminateWithNote(#note);
+            }
         }
-    })
+    };
+
+    let export_macro = if db.options().thunk_export_macro.is_empty() {
+        quote! {}
+    } else {
+        syn::parse_str::<TokenStream>(&db.options().thunk_export_macro)?
+    };
+
+    let thunk_impl = quote! {
+        extern "C" #export_macro #return_type_name #thunk_ident( #( #param_types #param_idents ),* ) {
+            #thunk_body
+        }
+    };
+    let thunk_impl = match parse_source_loc(&func.source_loc) {
+        Some((file, line)) if db.options().generate_line_directives() => {
+            // Compiler errors in the thunk (e.g. when the function can't be called the way the
+            // thunk calls it) then point at the declaration of the function in the header. The
+            // second directive is replaced with one pointing back at the generated file, once its
+            // line numbers are known (see `resolve_line_directives`).
+            let line = Literal::usize_unsuffixed(line);
+            quote! {
+                __NEWLINE__ __HASH_TOKEN__ line #line #file __NEWLINE__
+                #thunk_impl
+                __NEWLINE__ __HASH_TOKEN__ line __CRUBIT_GENERATED_LINE__ __NEWLINE__
+            }
+        }
+        _ => thunk_impl,
+    };
+
+    Ok(thunk_impl)
+}
+
+/// Returns the file and the line of the declaration from the `source_loc` of an
+/// item, as formatted by `Importer::ConvertSourceLocation` (e.g.
+/// `Generated from: google3/foo/bar.h;l=12`).  The file is returned without the
+/// `google3/` prefix, i.e. as it was seen by Clang.
+pub(crate) fn parse_source_loc(source_loc: &str) -> Option<(&str, usize)> {
+    let source_loc = source_loc.lines().next()?.strip_prefix("Generated from: ")?;
+    let (file, line) = source_loc.rsplit_once(";l=")?;
+    Some((file.strip_prefix("google3/").unwrap_or(file), line.parse().ok()?))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tests::*;
-    use crate::BindingsTokens;
+    use crate::{BindingsTokens, Database, GeneratorOptions};
+    use error_report::IgnoreErrors;
+    use ffi_types::SourceLocationDocComment;
     use ir_testing::{retrieve_func, with_lifetime_macros};
     use token_stream_matchers::{
         assert_cc_matches, assert_cc_not_matches, assert_rs_matches, assert_rs_not_matches,
@@ -1922,7 +4204,7 @@ mod tests {
     #[test]
     fn test_simple_function() -> Result<()> {
         let ir = ir_from_cc("int Add(int a, int b);")?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -1954,7 +4236,7 @@ mod tests {
     #[test]
     fn test_inline_function() -> Result<()> {
         let ir = ir_from_cc("inline int Add(int a, int b);")?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -1995,7 +4277,7 @@ mod tests {
             "struct ReturnStruct final {}; struct ParamStruct final {};",
         )?;
 
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -2040,44 +4322,2458 @@ mod tests {
     }
 
     #[test]
-    fn test_ref_to_struct_in_thunk_impls() -> Result<()> {
-        let ir = ir_from_cc("struct S{}; inline void foo(S& s) {} ")?;
-        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+    fn test_std_array_by_value() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace std {
+            template <typename T, decltype(sizeof(0)) N>
+            struct array { T elems[N]; };
+            }
+            std::array<int, 4> Reverse(std::array<int, 4> a);"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Reverse(mut a: [::core::ffi::c_int; 4]) -> [::core::ffi::c_int; 4] {
+                     unsafe {
+                         let mut __return =
+                             ::core::mem::MaybeUninit::<[::core::ffi::c_int; 4]>::uninit();
+                         crate::detail::... (&mut __return, &mut a);
+                         __return.assume_init()
+                     }
+                }
+            }
+        );
         assert_cc_matches!(
             rs_api_impl,
             quote! {
-                extern "C" void __rust_thunk___Z3fooR1S(struct S* s) {
-                    foo(*s);
+                static_assert(sizeof(std::array<int, 4>) == 4 * sizeof(int));
+                static_assert(alignof(std::array<int, 4>) == alignof(int));
+                ...
+                extern "C" void ... (std::array<int, 4>* __return, std::array<int, 4>* a) {
+                    new (__return) auto(Reverse(std::move(*a)));
                 }
             }
         );
+        // The layout of each distinct `std::array` type is asserted once.
+        assert_cc_not_matches!(
+            rs_api_impl,
+            quote! {
+                static_assert(sizeof(std::array<int, 4>) == 4 * sizeof(int));
+                ...
+                static_assert(sizeof(std::array<int, 4>) == 4 * sizeof(int));
+            }
+        );
         Ok(())
     }
 
     #[test]
-    fn test_const_ref_to_struct_in_thunk_impls() -> Result<()> {
-        let ir = ir_from_cc("struct S{}; inline void foo(const S& s) {} ")?;
-        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+    fn test_std_array_of_records_is_not_an_array() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace std {
+            template <typename T, decltype(sizeof(0)) N>
+            struct array { T elems[N]; };
+            }
+            struct S final { int x; };
+            void Consume(std::array<S, 4>* a);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { [crate::S; 4] });
+        Ok(())
+    }
+
+    #[test]
+    fn test_array_reference_params() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            #pragma clang lifetime_elision
+            inline void Fill(int (&arr)[4]) {}
+            inline int Sum(const int (&arr)[4]) { return 0; }"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn Fill<'a>(arr: &'a mut [::core::ffi::c_int; 4]) }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn Sum<'a>(arr: &'a [::core::ffi::c_int; 4]) -> ::core::ffi::c_int }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void ...(crubit::type_identity_t<int[4]>* arr) {
+                    Fill(*arr);
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int ...(crubit::type_identity_t<int const[4]>* arr) {
+                    return Sum(*arr);
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_std_optional_by_value() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace std {
+            template <typename T>
+            class optional {
+             public:
+              using value_type = T;
+             private:
+              bool engaged_;
+              T value_;
+            };
+            }
+            std::optional<int> Half(std::optional<int> x);"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Half(x: Option<::core::ffi::c_int>) -> Option<::core::ffi::c_int> {
+                    unsafe {
+                        let mut __return = ::core::mem::MaybeUninit::<::core::ffi::c_int>::uninit();
+                        if crate::detail::__rust_thunk___Z4HalfSt8optionalIiE(
+                            &mut __return,
+                            x.as_ref().map_or(::core::ptr::null(), |value| value as *const _)
+                        ) {
+                            Some(__return.assume_init())
+                        } else {
+                            None
+                        }
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___Z4HalfSt8optionalIiE(
+                    __return: &mut ::core::mem::MaybeUninit<::core::ffi::c_int>,
+                    x: *const ::core::ffi::c_int
+                ) -> bool;
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" bool __rust_thunk___Z4HalfSt8optionalIiE(
+                        std::optional<int>::value_type* __return,
+                        std::optional<int>::value_type const* x) {
+                    auto __optional = Half(
+                        x == nullptr ? std::optional<int>() : std::optional<int>(*x));
+                    if (!__optional.has_value()) return false;
+                    new (__return) auto(*std::move(__optional));
+                    return true;
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_std_optional_by_pointer_is_not_supported() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace std {
+            template <typename T>
+            class optional { bool engaged_; T value_; };
+            }
+            void TakesPointer(std::optional<int>* x);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn TakesPointer });
+        Ok(())
+    }
+
+    #[test]
+    fn test_initializer_list() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace std {
+            template <typename T>
+            class initializer_list {
+             public:
+              using value_type = T;
+             private:
+              const T* begin_;
+              decltype(sizeof(0)) size_;
+            };
+            }
+            int Sum(std::initializer_list<int> values);"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Sum(values: &[::core::ffi::c_int]) -> ::core::ffi::c_int {
+                    assert!(
+                        values.len() <= 16,
+                        "`values` is passed as a `std::initializer_list`, which can hold at most 16 elements"
+                    );
+                    unsafe {
+                        crate::detail::__rust_thunk___Z3SumSt16initializer_listIiE(
+                            values.as_ptr(),
+                            values.len()
+                        )
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___Z3SumSt16initializer_listIiE(
+                    values: *const ::core::ffi::c_int,
+                    __values_size: usize
+                ) -> ::core::ffi::c_int;
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___Z3SumSt16initializer_listIiE(
+                        std::initializer_list<int>::value_type const* values,
+                        size_t __values_size) {
+                    return crubit::CallWithInitializerList(
+                        values, __values_size,
+                        [&](std::initializer_list<int> __values_list) -> decltype(auto) {
+                            return Sum(__values_list);
+                        });
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                __HASH_TOKEN__ include "crubit/rs_bindings_support/internal/initializer_list.h"
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_initializer_list_return_type_is_not_supported() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace std {
+            template <typename T>
+            class initializer_list { const T* begin_; decltype(sizeof(0)) size_; };
+            }
+            std::initializer_list<int> Values();"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn Values });
+        Ok(())
+    }
+
+    /// Minimal `absl::Span` and `std::span`, for tests which don't use the
+    /// standard library.
+    const SPANS_FOR_TEST: &str = r#"
+        namespace absl {
+        template <typename T>
+        class Span {
+         public:
+          Span(T* data, decltype(sizeof(0)) size);
+         private:
+          T* data_;
+          decltype(sizeof(0)) size_;
+        };
+        }
+        namespace std {
+        inline constexpr decltype(sizeof(0)) dynamic_extent = -1;
+        template <typename T, decltype(sizeof(0)) Extent = dynamic_extent>
+        class span {
+         public:
+          span(T* data, decltype(sizeof(0)) size);
+         private:
+          T* data_;
+          decltype(sizeof(0)) size_;
+        };
+        }
+    "#;
+
+    #[test]
+    fn test_absl_span_of_const_param() -> Result<()> {
+        let ir = ir_from_cc(&format!("{SPANS_FOR_TEST} int Sum(absl::Span<const int> values);"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Sum(values: &[::core::ffi::c_int]) -> ::core::ffi::c_int {
+                    assert!(
+                        values.len() <= 16,
+                        "`values` is passed as a `std::initializer_list`, which can hold at most 16 elements"
+                    );
+                    unsafe {
+                        crate::detail::__rust_thunk___Z3SumN4absl4SpanIKiEE(
+                            values.as_ptr(),
+                            values.len()
+                        )
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___Z3SumN4absl4SpanIKiEE(
+                    values: *const ::core::ffi::c_int,
+                    __values_size: usize
+                ) -> ::core::ffi::c_int;
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___Z3SumN4absl4SpanIKiEE(
+                        int const* values, size_t __values_size) {
+                    return Sum(absl::Span<int const>(values, __values_size));
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_std_span_of_mutable_param() -> Result<()> {
+        let ir = ir_from_cc(&format!("{SPANS_FOR_TEST} void Fill(std::span<int> values);"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Fill(values: &mut [::core::ffi::c_int]) {
+                    unsafe {
+                        crate::detail::__rust_thunk___Z4FillSt4spanIiLm18446744073709551615EE(
+                            values.as_mut_ptr(),
+                            values.len()
+                        )
+                    }
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void __rust_thunk___Z4FillSt4spanIiLm18446744073709551615EE(
+                        int* values, size_t __values_size) {
+                    Fill(std::span<int>(values, __values_size));
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_param_requires_experimental_features() -> Result<()> {
+        let mut ir =
+            ir_from_cc(&format!("{SPANS_FOR_TEST} int Sum(absl::Span<const int> values);"))?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn Sum });
+        Ok(())
+    }
+
+    /// Minimal `absl::Status` and `absl::StatusOr`, for tests which don't
+    /// depend on Abseil.
+    const ABSL_STATUS_FOR_TEST: &str = r#"
+        namespace absl {
+        class Status {
+         public:
+          Status();
+          ~Status();
+          bool ok() const;
+         private:
+          void* rep_;
+        };
+        template <typename T>
+        class StatusOr {
+         public:
+          using value_type = T;
+          ~StatusOr();
+          const Status& status() const;
+         private:
+          Status status_;
+          T value_;
+        };
+        }
+    "#;
+
+    #[test]
+    fn test_absl_status_return_type() -> Result<()> {
+        let ir = ir_from_cc(&format!("{ABSL_STATUS_FOR_TEST} absl::Status Save();"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Save() -> ::core::result::Result<(), ::cc_status::Status> {
+                    unsafe {
+                        let mut __error: ::core::option::Option<::cc_status::Status> = None;
+                        crate::detail::...(
+                            &mut __error as *mut ::core::option::Option<::cc_status::Status>
+                                as *mut ::core::ffi::c_void,
+                            ::cc_status::internal::set_status_error
+                        );
+                        match __error {
+                            None => Ok(()),
+                            Some(__error) => Err(__error),
+                        }
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                (
+                    __return_error: *mut ::core::ffi::c_void,
+                    __set_error: unsafe extern "C" fn(
+                        *mut ::core::ffi::c_void, ::core::ffi::c_int, *const u8, usize)
+                );
+            }
+        );
         assert_cc_matches!(
             rs_api_impl,
             quote! {
-                extern "C" void __rust_thunk___Z3fooRK1S(const struct S* s) {
-                    foo(*s);
+                extern "C" void ...(void* __return_error, crubit::SetStatusError* __set_error) {
+                    crubit::ReportStatus(Save(), __return_error, __set_error);
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                __HASH_TOKEN__ include "crubit/rs_bindings_support/internal/absl_status.h"
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_absl_status_or_return_type() -> Result<()> {
+        let ir = ir_from_cc(&format!("{ABSL_STATUS_FOR_TEST} absl::StatusOr<int> Count();"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Count() -> ::core::result::Result<::core::ffi::c_int, ::cc_status::Status> {
+                    unsafe {
+                        let mut __error: ::core::option::Option<::cc_status::Status> = None;
+                        let mut __return = ::core::mem::MaybeUninit::<::core::ffi::c_int>::uninit();
+                        crate::detail::...(
+                            __return.as_mut_ptr(),
+                            &mut __error as *mut ::core::option::Option<::cc_status::Status>
+                                as *mut ::core::ffi::c_void,
+                            ::cc_status::internal::set_status_error
+                        );
+                        match __error {
+                            None => Ok(__return.assume_init()),
+                            Some(__error) => Err(__error),
+                        }
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                (
+                    __return: *mut ::core::ffi::c_int,
+                    __return_error: *mut ::core::ffi::c_void,
+                    __set_error: unsafe extern "C" fn(
+                        *mut ::core::ffi::c_void, ::core::ffi::c_int, *const u8, usize)
+                );
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void ...(
+                    absl::StatusOr<int>::value_type* __return,
+                    void* __return_error,
+                    crubit::SetStatusError* __set_error) {
+                    auto __status_or = Count();
+                    if (crubit::ReportStatus(__status_or.status(), __return_error, __set_error))
+                        new (__return) auto(*std::move(__status_or));
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_absl_status_return_type_requires_experimental_features() -> Result<()> {
+        let mut ir = ir_from_cc(&format!("{ABSL_STATUS_FOR_TEST} absl::Status Save();"))?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn Save });
+        Ok(())
+    }
+
+    /// A minimal `std::unique_ptr`, for tests which don't use the standard
+    /// library.
+    const UNIQUE_PTR_FOR_TEST: &str = r#"
+        namespace std {
+        template <typename T>
+        struct default_delete {
+          void operator()(T* ptr) const { delete ptr; }
+        };
+        template <typename T, typename Deleter = default_delete<T>>
+        class unique_ptr {
+         public:
+          using element_type = T;
+          explicit unique_ptr(T* ptr);
+          ~unique_ptr();
+          T* release();
+         private:
+          T* ptr_;
+        };
+        }
+    "#;
+
+    #[test]
+    fn test_unique_ptr_return_type() -> Result<()> {
+        let ir = ir_from_cc(&format!("{UNIQUE_PTR_FOR_TEST} std::unique_ptr<int> Make();"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Make() -> ::core::option::Option<
+                    ::cc_unique_ptr::UniquePtr<::core::ffi::c_int>
+                > {
+                    unsafe {
+                        ::cc_unique_ptr::UniquePtr::from_raw(
+                            crate::detail::__rust_thunk___Z4Makev(),
+                            |__ptr| crate::detail::__rust_thunk___Z4Makev__delete(__ptr)
+                        )
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___Z4Makev() -> *mut ::core::ffi::c_int;
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___Z4Makev__delete(__ptr: *mut ::core::ffi::c_int);
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" std::unique_ptr<int>::element_type* __rust_thunk___Z4Makev() {
+                    return Make().release();
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void __rust_thunk___Z4Makev__delete(
+                        std::unique_ptr<int>::element_type* __ptr) {
+                    delete __ptr;
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unique_ptr_param() -> Result<()> {
+        let ir = ir_from_cc(&format!("{UNIQUE_PTR_FOR_TEST} void Take(std::unique_ptr<int> p);"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Take(
+                    p: ::core::option::Option<::cc_unique_ptr::UniquePtr<::core::ffi::c_int>>
+                ) {
+                    unsafe {
+                        crate::detail::...(::cc_unique_ptr::internal::into_raw_or_null(p))
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(rs_api, quote! { (p: *mut ::core::ffi::c_int); });
+        assert_rs_not_matches!(rs_api, quote! { __delete });
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void ...(std::unique_ptr<int>::element_type* p) {
+                    Take(std::unique_ptr<int>(p));
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unique_ptr_requires_experimental_features() -> Result<()> {
+        let mut ir = ir_from_cc(&format!(
+            "{UNIQUE_PTR_FOR_TEST} std::unique_ptr<int> Make(); void Take(std::unique_ptr<int> p);"
+        ))?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn Make });
+        assert_rs_not_matches!(rs_api, quote! { fn Take });
+        Ok(())
+    }
+
+    /// A minimal `std::shared_ptr`, for tests which don't use the standard
+    /// library.
+    const SHARED_PTR_FOR_TEST: &str = r#"
+        namespace std {
+        template <typename T>
+        class shared_ptr {
+         public:
+          using element_type = T;
+          shared_ptr();
+          shared_ptr(const shared_ptr&);
+          ~shared_ptr();
+          T* get() const;
+         private:
+          T* ptr_;
+          void* control_block_;
+        };
+        }
+    "#;
+
+    #[test]
+    fn test_shared_ptr_return_type() -> Result<()> {
+        let ir = ir_from_cc(&format!("{SHARED_PTR_FOR_TEST} std::shared_ptr<int> Share();"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Share() -> ::core::option::Option<
+                    ::cc_shared_ptr::SharedPtr<::core::ffi::c_int>
+                > {
+                    unsafe {
+                        ::cc_shared_ptr::SharedPtr::from_raw(
+                            crate::detail::__rust_thunk___Z5Sharev(),
+                            ::cc_shared_ptr::SharedPtrOps {
+                                get: |__handle| crate::detail::__rust_thunk___Z5Sharev__get(
+                                    __handle
+                                ),
+                                clone: |__handle| crate::detail::__rust_thunk___Z5Sharev__clone(
+                                    __handle
+                                ),
+                                delete: |__handle| crate::detail::__rust_thunk___Z5Sharev__delete(
+                                    __handle
+                                ),
+                            },
+                        )
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___Z5Sharev() -> *mut ::core::ffi::c_void;
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___Z5Sharev__get(
+                    __handle: *mut ::core::ffi::c_void
+                ) -> *mut ::core::ffi::c_int;
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___Z5Sharev__clone(
+                    __handle: *mut ::core::ffi::c_void
+                ) -> *mut ::core::ffi::c_void;
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___Z5Sharev__delete(__handle: *mut ::core::ffi::c_void);
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void* __rust_thunk___Z5Sharev() {
+                    return crubit::NewSharedPtrHandle(Share());
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" std::shared_ptr<int>::element_type* __rust_thunk___Z5Sharev__get(
+                        void* __handle) {
+                    return crubit::SharedPtrHandleGet<std::shared_ptr<int>>(__handle);
+                }
+                extern "C" void* __rust_thunk___Z5Sharev__clone(void* __handle) {
+                    return crubit::SharedPtrHandleClone<std::shared_ptr<int>>(__handle);
+                }
+                extern "C" void __rust_thunk___Z5Sharev__delete(void* __handle) {
+                    crubit::SharedPtrHandleDelete<std::shared_ptr<int>>(__handle);
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                __HASH_TOKEN__ include "crubit/rs_bindings_support/internal/shared_ptr.h"
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared_ptr_param() -> Result<()> {
+        let ir = ir_from_cc(&format!("{SHARED_PTR_FOR_TEST} void Keep(std::shared_ptr<int> p);"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Keep(
+                    p: ::core::option::Option<::cc_shared_ptr::SharedPtr<::core::ffi::c_int>>
+                ) {
+                    unsafe {
+                        crate::detail::...(::cc_shared_ptr::internal::into_raw_or_null(p))
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(rs_api, quote! { (p: *mut ::core::ffi::c_void); });
+        assert_rs_not_matches!(rs_api, quote! { __clone });
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void ...(void* p) {
+                    Keep(crubit::TakeSharedPtrHandle<std::shared_ptr<int>>(p));
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                __HASH_TOKEN__ include "crubit/rs_bindings_support/internal/shared_ptr.h"
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared_ptr_requires_experimental_features() -> Result<()> {
+        let mut ir = ir_from_cc(&format!(
+            "{SHARED_PTR_FOR_TEST} std::shared_ptr<int> Share(); void Keep(std::shared_ptr<int> p);"
+        ))?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn Share });
+        assert_rs_not_matches!(rs_api, quote! { fn Keep });
+        Ok(())
+    }
+
+    /// A minimal `std::function`, for tests which don't use the standard
+    /// library.
+    const FUNCTION_FOR_TEST: &str = r#"
+        namespace std {
+        template <typename Signature>
+        class function;
+        template <typename R, typename... Args>
+        class function<R(Args...)> {
+         public:
+          function();
+          function(const function&);
+          ~function();
+          R operator()(Args... args) const;
+         private:
+          void* callable_[4];
+        };
+        }
+    "#;
+
+    #[test]
+    fn test_std_function_param() -> Result<()> {
+        let ir = ir_from_cc(&format!(
+            "{FUNCTION_FOR_TEST} void Register(std::function<int(int)> callback);"
+        ))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Register(
+                    callback: impl Fn(::core::ffi::c_int) -> ::core::ffi::c_int + Send + Sync + 'static
+                ) {
+                    unsafe {
+                        crate::detail::...(
+                            ::cc_function::internal::into_raw::<
+                                dyn Fn(::core::ffi::c_int) -> ::core::ffi::c_int + Send + Sync + 'static
+                            >(::std::boxed::Box::new(callback)),
+                            ...
+                            ::cc_function::internal::drop_raw::<
+                                dyn Fn(::core::ffi::c_int) -> ::core::ffi::c_int + Send + Sync + 'static
+                            >,
+                        )
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                (
+                    callback: *mut ::core::ffi::c_void,
+                    __callback_call: unsafe extern "C" fn(
+                        *mut ::core::ffi::c_void,
+                        ::core::ffi::c_int
+                    ) -> ::core::ffi::c_int,
+                    __callback_drop: unsafe extern "C" fn(*mut ::core::ffi::c_void),
+                );
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void ...(
+                    void* callback,
+                    crubit::RustFunctionCall<std::function<int(int)>> __callback_call,
+                    crubit::RustFunctionDrop __callback_drop) {
+                    Register(crubit::MakeRustFunction<std::function<int(int)>>(
+                        callback, __callback_call, __callback_drop));
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                __HASH_TOKEN__ include "crubit/rs_bindings_support/internal/function.h"
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_std_function_requires_experimental_features() -> Result<()> {
+        let mut ir = ir_from_cc(&format!(
+            "{FUNCTION_FOR_TEST} void Register(std::function<int(int)> callback);"
+        ))?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn Register });
+        Ok(())
+    }
+
+    /// A minimal `std::string_view`, for tests which don't use the standard
+    /// library.
+    const STRING_VIEW_FOR_TEST: &str = r#"
+        namespace std {
+        template <typename CharT>
+        class basic_string_view {
+         public:
+          basic_string_view(const CharT* data, decltype(sizeof(0)) size);
+          const CharT* data() const;
+          decltype(sizeof(0)) size() const;
+         private:
+          const CharT* data_;
+          decltype(sizeof(0)) size_;
+        };
+        using string_view = basic_string_view<char>;
+        }"#;
+
+    #[test]
+    fn test_string_view_param() -> Result<()> {
+        let ir = ir_from_cc(&format!("{STRING_VIEW_FOR_TEST} int Len(std::string_view s);"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Len(s: &[u8]) -> ::core::ffi::c_int {
+                    unsafe { crate::detail::... (s.as_ptr(), s.len()) }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { (s: *const u8, __s_size: usize) -> ::core::ffi::c_int; }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int ...(char const* s, size_t __s_size) {
+                    return Len(std::string_view(s, __s_size));
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_view_param_as_str() -> Result<()> {
+        let ir = ir_from_cc(&format!("{STRING_VIEW_FOR_TEST} int Len(std::string_view s);"))?;
+
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { string_view_params_as_str: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(rs_api, quote! { pub fn Len(s: &str) -> ::core::ffi::c_int });
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_view_return_type_without_lifetime() -> Result<()> {
+        let ir = ir_from_cc(&format!("{STRING_VIEW_FOR_TEST} std::string_view Name();"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Name() -> *const [u8] {
+                    unsafe {
+                        let mut __return_size = 0usize;
+                        let __return_data = crate::detail::...(&mut __return_size);
+                        ::core::ptr::slice_from_raw_parts(
+                            if __return_data.is_null() {
+                                ::core::ptr::NonNull::dangling().as_ptr()
+                            } else {
+                                __return_data
+                            },
+                            __return_size,
+                        )
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(rs_api, quote! { (__return_size: &mut usize) -> *const u8; });
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" char const* ...(size_t* __return_size) {
+                    auto __string_view = Name();
+                    *__return_size = __string_view.size();
+                    return __string_view.data();
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_view_pointer_is_not_converted() -> Result<()> {
+        let ir = ir_from_cc(&format!("{STRING_VIEW_FOR_TEST} void Clear(std::string_view* s);"))?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub unsafe fn Clear(s: *mut ...) });
+        assert_rs_not_matches!(rs_api, quote! { [u8] });
+        Ok(())
+    }
+
+    #[test]
+    fn test_std_string_param() -> Result<()> {
+        let ir = ir_from_cc(&format!("{STD_STRING_CC} int Len(const std::string& s);"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Len(s: &[u8]) -> ::core::ffi::c_int {
+                    unsafe { crate::detail::... (s.as_ptr(), s.len()) }
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int ...(char const* s, size_t __s_size) {
+                    return Len(std::string(s, __s_size));
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_std_string_return_type() -> Result<()> {
+        let ir = ir_from_cc(&format!("{STD_STRING_CC} std::string Name();"))?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Name() -> ::std::vec::Vec<u8> {
+                    unsafe {
+                        unsafe extern "C" fn __return_append(
+                            __return: *mut ::core::ffi::c_void, __data: *const u8, __size: usize,
+                        ) {
+                            unsafe {
+                                (*(__return as *mut ::std::vec::Vec<u8>))
+                                    .extend_from_slice(::core::slice::from_raw_parts(__data, __size));
+                            }
+                        }
+                        let mut __return = ::std::vec::Vec::<u8>::new();
+                        crate::detail::...(
+                            &mut __return as *mut ::std::vec::Vec<u8> as *mut ::core::ffi::c_void,
+                            __return_append
+                        );
+                        __return
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                (
+                    __return: *mut ::core::ffi::c_void,
+                    __return_append: unsafe extern "C" fn(*mut ::core::ffi::c_void, *const u8, usize)
+                );
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void ...(
+                    void* __return,
+                    crubit::type_identity_t<void(void*, char const*, size_t)>* __return_append) {
+                    auto __string = Name();
+                    __return_append(__return, __string.data(), __string.size());
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_std_string_mutable_reference_is_not_converted() -> Result<()> {
+        let ir = ir_from_cc(&format!("{STD_STRING_CC} void Clear(std::string& s);"))?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub unsafe fn Clear(s: *mut ...) });
+        assert_rs_not_matches!(rs_api, quote! { [u8] });
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_arguments() -> Result<()> {
+        let ir = ir_from_cc("int Add(int a, int b = 3, int c = 4);")?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Add(
+                    a: ::core::ffi::c_int,
+                    b: ::core::ffi::c_int,
+                    c: ::core::ffi::c_int
+                ) -> ::core::ffi::c_int { ... }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[doc = " Like `Add`, but with the default arguments `b = 3`, `c = 4`." ...]
+                #[inline(always)]
+                pub fn Add_1(a: ::core::ffi::c_int) -> ::core::ffi::c_int {
+                    unsafe { crate::detail::__rust_thunk___Z3Addiii__defaults2(a) }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[doc = " Like `Add`, but with the default arguments `c = 4`." ...]
+                #[inline(always)]
+                pub fn Add_2(a: ::core::ffi::c_int, b: ::core::ffi::c_int) -> ::core::ffi::c_int {
+                    unsafe { crate::detail::__rust_thunk___Z3Addiii__defaults1(a, b) }
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___Z3Addiii__defaults2(int a) {
+                    return Add(a);
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___Z3Addiii__defaults1(int a, int b) {
+                    return Add(a, b);
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_arguments_wrapper_collision() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            int Add(int a, int b = 3, int c = 4);
+            int Add_1(int a);
+            struct S final {
+              int Get(int i = 0) const;
+              int Get_0() const;
+            };"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                __COMMENT__ "The wrapper `Add_1` of `Add` for its default arguments is not generated, because it collides with another item"
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn Add_1(a: ::core::ffi::c_int) -> ::core::ffi::c_int { ... __rust_thunk___Z5Add_1i ... } }
+        );
+        assert_rs_matches!(rs_api, quote! { pub fn Add_2 });
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                __COMMENT__ "The wrapper `Get_0` of `Get` for its default arguments is not generated, because it collides with another item"
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { __rust_thunk___ZNK1S3GetEi__defaults1 });
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_arguments_of_method() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct S final {
+              int Get(int i = 0) const;
+            };"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub unsafe fn Get_0(__this: *const Self) -> ::core::ffi::c_int {
+                    crate::detail::__rust_thunk___ZNK1S3GetEi__defaults1(__this)
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___ZNK1S3GetEi__defaults1(
+                        const struct S* __this) {
+                    return __this->Get();
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_pointer_constness() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            const int* const* ConstConst(int* const* p);
+            const int** InnerConst(int** p);
+            struct S final {
+              const int* Get() const;
+              int* GetMut();
+            };
+            #pragma clang lifetime_elision
+            struct T final {
+              const int& Get() const;
+              int* const& GetPtr() const;
+            };"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { strict_pointer_constness: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub unsafe fn ConstConst(p: *const *mut ::core::ffi::c_int)
+                    -> *const *const ::core::ffi::c_int
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub unsafe fn InnerConst(p: *mut *mut ::core::ffi::c_int)
+                    -> *mut *const ::core::ffi::c_int
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub unsafe fn Get(__this: *const Self) -> *const ::core::ffi::c_int }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub unsafe fn GetMut(__this: *mut Self) -> *mut ::core::ffi::c_int }
+        );
+        assert_rs_matches!(rs_api, quote! { pub fn Get<'a>(&'a self) -> &'a ::core::ffi::c_int });
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn GetPtr<'a>(&'a self) -> &'a *mut ::core::ffi::c_int }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_pointer_constness_rejects_mismatched_constness() -> Result<()> {
+        let mut ir = ir_from_cc("const int* const* Get();")?;
+        // Simulate an importer bug which maps the C++ `int* const*` to `*const *const c_int`.
+        for item in ir.items_mut() {
+            if let Item::Func(func) = item {
+                let return_type = &mut Rc::make_mut(func).return_type.cc_type;
+                return_type.type_args[0].type_args[0].is_const = false;
+            }
+        }
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { strict_pointer_constness: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn Get });
+        Ok(())
+    }
+
+    /// Returns the `CcType` of a C++ pointer to `pointee`.
+    fn cc_pointer_type(pointee: CcType, is_const: bool) -> CcType {
+        CcType { name: Some("*".into()), is_const, type_args: vec![pointee], decl_id: None }
+    }
+
+    /// Returns the `RsTypeKind` of a Rust pointer to `pointee`.
+    fn rs_pointer_type(pointee: RsTypeKind, mutability: Mutability) -> RsTypeKind {
+        RsTypeKind::Pointer { pointee: Rc::new(pointee), mutability }
+    }
+
+    #[test]
+    fn test_check_pointer_constness() -> Result<()> {
+        let ir = ir_testing::make_ir_from_items([]);
+        let cc_int = |is_const| CcType {
+            name: Some("int".into()),
+            is_const,
+            type_args: vec![],
+            decl_id: None,
+        };
+        let rs_int = RsTypeKind::Primitive(PrimitiveType::c_int);
+
+        // `const int* const*` -> `*const *const c_int`
+        let cc_type = cc_pointer_type(cc_pointer_type(cc_int(true), true), false);
+        let rs_type =
+            rs_pointer_type(rs_pointer_type(rs_int.clone(), Mutability::Const), Mutability::Const);
+        check_pointer_constness(&cc_type, &rs_type, &ir)?;
+
+        // `int* const*` -> `*const *mut c_int`
+        let cc_type = cc_pointer_type(cc_pointer_type(cc_int(false), true), false);
+        let rs_type =
+            rs_pointer_type(rs_pointer_type(rs_int.clone(), Mutability::Mut), Mutability::Const);
+        check_pointer_constness(&cc_type, &rs_type, &ir)?;
+
+        // `const int*` -> `Option<&c_int>`
+        let cc_type = cc_pointer_type(cc_int(true), false);
+        let rs_type = RsTypeKind::Option(Rc::new(RsTypeKind::Reference {
+            referent: Rc::new(rs_int.clone()),
+            mutability: Mutability::Const,
+            lifetime: Lifetime::new("a"),
+        }));
+        check_pointer_constness(&cc_type, &rs_type, &ir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_pointer_constness_mismatch() {
+        let ir = ir_testing::make_ir_from_items([]);
+        let cc_int = |is_const| CcType {
+            name: Some("int".into()),
+            is_const,
+            type_args: vec![],
+            decl_id: None,
+        };
+        let rs_int = RsTypeKind::Primitive(PrimitiveType::c_int);
+
+        // `const int*` -> `*mut c_int`
+        let cc_type = cc_pointer_type(cc_int(true), false);
+        let rs_type = rs_pointer_type(rs_int.clone(), Mutability::Mut);
+        let err = check_pointer_constness(&cc_type, &rs_type, &ir).unwrap_err();
+        assert!(
+            err.to_string().contains("doesn't preserve the constness of its pointees"),
+            "{err}"
+        );
+
+        // `int*` -> `*const c_int`
+        let cc_type = cc_pointer_type(cc_int(false), false);
+        let rs_type = rs_pointer_type(rs_int.clone(), Mutability::Const);
+        assert!(check_pointer_constness(&cc_type, &rs_type, &ir).is_err());
+
+        // `const int* const*` -> `*const *mut c_int`: only the inner pointer is wrong.
+        let cc_type = cc_pointer_type(cc_pointer_type(cc_int(true), true), false);
+        let rs_type =
+            rs_pointer_type(rs_pointer_type(rs_int.clone(), Mutability::Mut), Mutability::Const);
+        assert!(check_pointer_constness(&cc_type, &rs_type, &ir).is_err());
+
+        // `int&` -> `&c_int`
+        let cc_type = CcType { name: Some("&".into()), ..cc_pointer_type(cc_int(false), false) };
+        let rs_type = RsTypeKind::Reference {
+            referent: Rc::new(rs_int),
+            mutability: Mutability::Const,
+            lifetime: Lifetime::new("a"),
+        };
+        assert!(check_pointer_constness(&cc_type, &rs_type, &ir).is_err());
+    }
+
+    #[test]
+    fn test_absl_time_types_by_value() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace absl {
+            class Duration { long long rep_hi_; unsigned rep_lo_; };
+            class Time { Duration rep_; };
+            }
+            absl::Duration Twice(absl::Duration d);
+            absl::Time Later(absl::Time t, absl::Duration d);"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Twice(d: ::core::time::Duration) -> ::core::time::Duration {
+                    unsafe {
+                        ::absl_time::internal::duration_from_nanos(crate::detail::...(
+                            ::absl_time::internal::duration_to_nanos(d)
+                        ))
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Later(t: ::absl_time::Time, d: ::core::time::Duration) -> ::absl_time::Time {
+                    ...
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___Z5TwiceN4absl8DurationE(d: i64) -> i64;
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" std::int64_t __rust_thunk___Z5TwiceN4absl8DurationE(std::int64_t d) {
+                    return crubit::AbslDurationToNanos(Twice(crubit::AbslDurationFromNanos(d)));
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                return crubit::AbslTimeToUnixNanos(Later(
+                    crubit::AbslTimeFromUnixNanos(t), crubit::AbslDurationFromNanos(d)));
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_absl_time_types_by_pointer_are_not_supported() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace absl { class Duration { long long rep_hi_; unsigned rep_lo_; }; }
+            void TakesPointer(absl::Duration* d);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn TakesPointer });
+        Ok(())
+    }
+
+    #[test]
+    fn test_chrono_types_by_value() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace std {
+            template <long long N, long long D = 1> struct ratio {};
+            namespace chrono {
+            template <class Rep, class Period = ratio<1>> class duration { Rep rep_; };
+            using milliseconds = duration<long long, ratio<1, 1000>>;
+            struct system_clock {};
+            template <class Clock, class Duration> class time_point { Duration d_; };
+            }
+            }
+            std::chrono::milliseconds Twice(std::chrono::milliseconds d);
+            std::chrono::time_point<std::chrono::system_clock, std::chrono::milliseconds>
+                Later(std::chrono::time_point<std::chrono::system_clock, std::chrono::milliseconds> t);"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Twice(d: ::core::time::Duration) -> ::core::time::Duration {
+                    unsafe {
+                        ::cc_chrono::internal::duration_from_ticks::<1000000>(crate::detail::...(
+                            ::cc_chrono::internal::duration_to_ticks::<1000000>(d)
+                        ))
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Later(t: ::cc_chrono::SystemTime) -> ::cc_chrono::SystemTime {
+                    ...
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                return Twice(std::chrono::milliseconds(d)).count();
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                return Later(
+                    std::chrono::time_point<std::chrono::system_clock, std::chrono::milliseconds>(
+                        std::chrono::milliseconds(t)))
+                    .time_since_epoch()
+                    .count();
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_chrono_durations_with_unsupported_periods_are_not_mapped() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace std {
+            template <long long N, long long D = 1> struct ratio {};
+            namespace chrono {
+            template <class Rep, class Period = ratio<1>> class duration { Rep rep_; };
+            using minutes = duration<long long, ratio<60>>;
+            }
+            }
+            std::chrono::minutes Twice(std::chrono::minutes d);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { ::core::time::Duration });
+        Ok(())
+    }
+
+    #[test]
+    fn test_buffer_params() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            typedef decltype(sizeof(0)) size_t;
+            [[clang::annotate("crubit_buffer", "ptr=data", "len=size")]]
+            void Write(const unsigned char* data, size_t size);
+            [[clang::annotate("crubit_buffer", "ptr=out", "len=out_len")]]
+            int Read(int fd, signed char* out, int out_len);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Write(data: &[u8]) {
+                    unsafe { crate::detail::...(data.as_ptr().cast(), data.len()) }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Read(fd: ::core::ffi::c_int, out: &mut [i8]) -> ::core::ffi::c_int {
+                    unsafe {
+                        crate::detail::...(
+                            fd,
+                            out.as_mut_ptr().cast(),
+                            <::core::ffi::c_int as ::core::convert::TryFrom<usize>>::try_from(
+                                out.len()
+                            )
+                            .expect("the buffer is too large for its length parameter")
+                        )
+                    }
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_buffer_params_must_point_to_bytes() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            [[clang::annotate("crubit_buffer", "ptr=values", "len=count")]]
+            int Sum(const int* values, int count);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn Sum });
+        Ok(())
+    }
+
+    #[test]
+    fn test_buffer_params_returning_len() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            typedef decltype(sizeof(0)) size_t;
+            [[clang::annotate("crubit_buffer", "ptr=buf", "len=len", "returns_len")]]
+            size_t ReadInto(char* buf, size_t len);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[doc = " Returns the number of bytes written to the start of `buf`, which is at most\n `buf.len()`." ...]
+                #[inline(always)]
+                pub fn ReadInto(buf: &mut [u8]) -> usize {
+                    unsafe {
+                        let __len = crate::detail::...(buf.as_mut_ptr().cast(), buf.len());
+                        ::core::debug_assert!(
+                            (__len as u64) <= (buf.len() as u64),
+                            "the returned length exceeds the length of `buf`"
+                        );
+                        __len as usize
+                    }
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_buffer_params_returning_len_must_be_mutable() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            typedef decltype(sizeof(0)) size_t;
+            [[clang::annotate("crubit_buffer", "ptr=buf", "len=len", "returns_len")]]
+            size_t ReadInto(const char* buf, size_t len);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn ReadInto });
+        Ok(())
+    }
+
+    #[test]
+    fn test_buffer_params_returning_len_must_return_unsigned_integer() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            typedef decltype(sizeof(0)) size_t;
+            [[clang::annotate("crubit_buffer", "ptr=buf", "len=len", "returns_len")]]
+            int ReadInto(char* buf, size_t len);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn ReadInto });
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_param_with_bool_return() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct Point { int x; int y; };
+            [[clang::annotate("crubit_out_param", "out")]]
+            bool Lookup(int key, Point* out);"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Lookup(key: ::core::ffi::c_int) -> ::core::option::Option<crate::Point> {
+                    let mut __out = ::core::mem::MaybeUninit::<crate::Point>::uninit();
+                    unsafe {
+                        if crate::detail::__rust_thunk___Z6LookupiP5Point(key, __out.as_mut_ptr()) {
+                            ::core::option::Option::Some(__out.assume_init())
+                        } else {
+                            ::core::option::Option::None
+                        }
+                    }
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" bool __rust_thunk___Z6LookupiP5Point(int key, struct Point* out) {
+                    crubit::construct_at(out);
+                    auto __status = Lookup(key, out);
+                    if (!__status) std::destroy_at(out);
+                    return __status;
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_param_with_enum_return() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            enum class Status : int { kOk = 0, kNotFound = 1 };
+            [[clang::annotate("crubit_out_param", "out")]]
+            Status Lookup(int key, int* out);"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn Lookup(
+                    key: ::core::ffi::c_int
+                ) -> ::core::result::Result<::core::ffi::c_int, crate::Status> {
+                    let mut __out = ::core::mem::MaybeUninit::<::core::ffi::c_int>::uninit();
+                    unsafe {
+                        let __status = crate::detail::...(key, __out.as_mut_ptr());
+                        if __status == <crate::Status>::from(0) {
+                            ::core::result::Result::Ok(__out.assume_init())
+                        } else {
+                            ::core::result::Result::Err(__status)
+                        }
+                    }
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                if (__status != decltype(__status){}) std::destroy_at(out);
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_param_requires_bool_or_enum_return() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            [[clang::annotate("crubit_out_param", "out")]]
+            int Lookup(int key, int* out);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn Lookup });
+        Ok(())
+    }
+
+    #[test]
+    fn test_stable_thunk() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            [[clang::annotate("crubit_stable_thunk", "mylib_add_v1")]]
+            int Add(int a, int b);"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, stable_thunks } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Add(a: ::core::ffi::c_int, b: ::core::ffi::c_int) -> ::core::ffi::c_int {
+                    unsafe { crate::detail::mylib_add_v1(a, b) }
+                }
+            }
+        );
+        // The thunk isn't skipped, even though `Add` could be called directly.
+        assert_rs_not_matches!(rs_api, quote! { link_name });
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn mylib_add_v1(a: ::core::ffi::c_int, b: ::core::ffi::c_int) -> ::core::ffi::c_int;
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int mylib_add_v1(int a, int b) {
+                    return Add(a, b);
+                }
+            }
+        );
+        assert_eq!(
+            stable_thunks,
+            vec![StableThunk {
+                symbol: "mylib_add_v1".to_string(),
+                signature: quote! { int (int, int) }.to_string(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_renamed_from() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            [[clang::annotate("crubit_renamed_from", "ComputeSum")]]
+            int Sum(int a, int b);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[deprecated = "`ComputeSum` was renamed to `Sum`"]
+                #[doc = " Deprecated alias of `Sum`." ...]
+                #[inline(always)]
+                pub fn ComputeSum(a: ::core::ffi::c_int, b: ::core::ffi::c_int) -> ::core::ffi::c_int {
+                    unsafe { crate::detail::__rust_thunk___Z3Sumii(a, b) }
+                }
+            }
+        );
+        // The alias calls the thunk of `Sum`, rather than declaring its own.
+        assert_rs_not_matches!(
+            rs_api,
+            quote! { fn __rust_thunk___Z3Sumii ... fn __rust_thunk___Z3Sumii }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_renamed_from_collision() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            [[clang::annotate("crubit_renamed_from", "ComputeSum")]]
+            int Sum(int a, int b);
+            int ComputeSum(int a, int b, int c);"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn Sum(a: ::core::ffi::c_int, b: ::core::ffi::c_int) }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                __COMMENT__ "The alias `ComputeSum` of `Sum` is not generated, because it collides with another item"
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { deprecated });
+        assert_rs_not_matches!(
+            rs_api,
+            quote! { pub fn ComputeSum(a: ::core::ffi::c_int, b: ::core::ffi::c_int) -> }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_returns_static() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct Config final { int value; };
+            [[clang::annotate("crubit_returns_static")]]
+            const Config& DefaultConfig();
+            [[clang::annotate("crubit_returns_static")]]
+            int& Counter();"#,
+        )?;
+
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub fn DefaultConfig() -> &'static crate::Config });
+        assert_rs_not_matches!(rs_api, quote! { -> *const crate::Config });
+        // A non-`const` referent could be mutated by C++ while Rust holds a shared reference.
+        assert_rs_not_matches!(rs_api, quote! { fn Counter });
+        Ok(())
+    }
+
+    #[test]
+    fn test_stable_thunk_signature_with_return_value_slot() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct S { int x; };
+            [[clang::annotate("crubit_stable_thunk", "mylib_make_s_v1")]]
+            S MakeS();"#,
+        )?;
+
+        let stable_thunks = generate_bindings_tokens(ir)?.stable_thunks;
+        assert_eq!(
+            stable_thunks,
+            vec![StableThunk {
+                symbol: "mylib_make_s_v1".to_string(),
+                signature: quote! { void (struct S *) }.to_string(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ref_to_struct_in_thunk_impls() -> Result<()> {
+        let ir = ir_from_cc("struct S{}; inline void foo(S& s) {} ")?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void __rust_thunk___Z3fooR1S(struct S* s) {
+                    foo(*s);
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_const_ref_to_struct_in_thunk_impls() -> Result<()> {
+        let ir = ir_from_cc("struct S{}; inline void foo(const S& s) {} ")?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void __rust_thunk___Z3fooRK1S(const struct S* s) {
+                    foo(*s);
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsigned_int_in_thunk_impls() -> Result<()> {
+        let ir = ir_from_cc("inline void foo(unsigned int i) {} ")?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void __rust_thunk___Z3fooj(unsigned int i) {
+                    foo(i);
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_static_methods_qualify_call_in_thunk() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct SomeStruct {
+                static inline int some_func() { return 42; }
+            }; "#,
+        )?;
+
+        assert_cc_matches!(
+            generate_bindings_tokens(ir)?.rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___ZN10SomeStruct9some_funcEv() {
+                    return SomeStruct::some_func();
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_instance_methods_deref_this_in_thunk() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct SomeStruct {
+                inline int some_func(int arg) const { return 42 + arg; }
+            }; "#,
+        )?;
+
+        assert_cc_matches!(
+            generate_bindings_tokens(ir)?.rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___ZNK10SomeStruct9some_funcEi(
+                        const struct SomeStruct* __this, int arg) {
+                    return __this->some_func(arg);
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ptr_func() -> Result<()> {
+        let ir = ir_from_cc(r#" inline int* Deref(int*const* p); "#)?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub unsafe fn Deref(p: *const *mut ::core::ffi::c_int) -> *mut ::core::ffi::c_int {
+                    crate::detail::__rust_thunk___Z5DerefPKPi(p)
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                mod detail {
+                    #[allow(unused_imports)]
+                    use super::*;
+                    extern "C" {
+                        pub(crate) fn __rust_thunk___Z5DerefPKPi(p: *const *mut ::core::ffi::c_int) -> *mut ::core::ffi::c_int;
+                    }
+                }
+            }
+        );
+
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int* __rust_thunk___Z5DerefPKPi(int* const * p) {
+                    return Deref(p);
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_const_char_ptr_func() -> Result<()> {
+        // This is a regression test: We used to include the "const" in the name
+        // of the CcType, which caused a panic in the code generator
+        // ('"const char" is not a valid Ident').
+        // It's therefore important that f() is inline so that we need to
+        // generate a thunk for it (where we then process the CcType).
+        let ir = ir_from_cc(r#" inline void f(const signed char *str); "#)?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub unsafe fn f(str: *const ::core::ffi::c_schar) {
+                    crate::detail::__rust_thunk___Z1fPKa(str)
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                extern "C" {
+                    pub(crate) fn __rust_thunk___Z1fPKa(str: *const ::core::ffi::c_schar);
+                }
+            }
+        );
+
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void __rust_thunk___Z1fPKa(signed char const * str){ f(str); }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_func_ptr_thunk() -> Result<()> {
+        // Using an `inline` keyword forces generation of a C++ thunk in
+        // `rs_api_impl` (i.e. exercises `format_cc_type` and similar code).
+        let ir = ir_from_cc(
+            r#"
+            int multiply(int x, int y);
+            inline int (*inline_get_pointer_to_function())(int, int) {
+                return multiply;
+            }
+        "#,
+        )?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" crubit::type_identity_t<int(int , int)>*
+                __rust_thunk___Z30inline_get_pointer_to_functionv() {
+                    return inline_get_pointer_to_function();
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_doc_comment_func() -> Result<()> {
+        let ir = ir_from_cc(
+            "
+        // Doc Comment
+        // with two lines
+        int func();",
+        )?;
+
+        assert_rs_matches!(
+            generate_bindings_tokens(ir)?.rs_api,
+            // leading space is intentional so there is a space between /// and the text of the
+            // comment
+            quote! {
+                #[doc = " Doc Comment\n with two lines\n \n Generated from: google3/ir_from_cc_virtual_header.h;l=6"]
+                #[inline(always)]
+                pub fn func
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Trivial types (at least those that are mapped to Copy rust types) do not
+    /// get a Drop impl.
+    #[test]
+    fn test_impl_drop_trivial() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"struct Trivial final {
+                ~Trivial() = default;
+                int x;
+            };"#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_not_matches!(rs_api, quote! {impl Drop});
+        assert_rs_not_matches!(rs_api, quote! {impl ::ctor::PinnedDrop});
+        assert_rs_matches!(rs_api, quote! {pub x: ::core::ffi::c_int});
+        assert_cc_not_matches!(rs_api_impl, quote! { std::destroy_at });
+        Ok(())
+    }
+
+    #[test]
+    fn test_impl_default_explicitly_defaulted_constructor() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct DefaultedConstructor final {
+                DefaultedConstructor() = default;
+            };"#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl Default for DefaultedConstructor {
+                    #[inline(always)]
+                    fn default() -> Self {
+                        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+                        unsafe {
+                            crate::detail::__rust_thunk___ZN20DefaultedConstructorC1Ev(&mut tmp);
+                            tmp.assume_init()
+                        }
+                    }
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void __rust_thunk___ZN20DefaultedConstructorC1Ev(
+                        struct DefaultedConstructor* __this) {
+                    crubit::construct_at(__this);
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_impl_clone_that_propagates_lifetime() -> Result<()> {
+        // This test covers the case where a single lifetime applies to 1)
+        // the `__this` parameter and 2) other constructor parameters. For
+        // example, maybe the newly constructed object needs to have the
+        // same lifetime as the constructor's parameter. (This might require
+        // annotating the whole C++ struct with a lifetime, so maybe the
+        // example below is not fully realistic/accurate...).
+        let ir = ir_from_cc(&with_lifetime_macros(
+            r#"#pragma clang lifetime_elision
+            struct Foo final {
+                Foo(const int& $a i) $a;
+            };"#,
+        ))?;
+        let ctor: &Func = ir
+            .items()
+            .filter_map(|item| match item {
+                Item::Func(func) => Some(&**func),
+                _ => None,
+            })
+            .find(|f| {
+                matches!(&f.name, UnqualifiedIdentifier::Constructor)
+                    && f.params
+                        .get(1)
+                        .map(|p| p.identifier.identifier.as_ref() == "i")
+                        .unwrap_or_default()
+            })
+            .unwrap();
+        {
+            // Double-check that the test scenario set up above uses the same lifetime
+            // for both of the constructor's parameters: `__this` and `i`.
+            assert_eq!(ctor.params.len(), 2);
+            let this_lifetime: LifetimeId =
+                *ctor.params[0].type_.rs_type.lifetime_args.first().unwrap();
+            let i_lifetime: LifetimeId =
+                *ctor.params[1].type_.rs_type.lifetime_args.first().unwrap();
+            assert_eq!(i_lifetime, this_lifetime);
+        }
+
+        // Before cl/423346348 the generated Rust code would incorrectly look
+        // like this (note the mismatched 'a and 'b lifetimes):
+        //     fn from<'b>(i: &'a i32) -> Self
+        // After this CL, this scenario will result in an explicit error.
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! {impl From});
+        assert_rs_matches!(rs_api, {
+            let txt = "Generated from: google3/ir_from_cc_virtual_header.h;l=34\n\
+                           Error while generating bindings for item 'Foo::Foo':\n\
+                           The lifetime of `__this` is \
+                               unexpectedly also used by another parameter: Lifetime(\"a\")";
+            quote! { __COMMENT__ #txt }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_impl_default_non_trivial_struct() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct NonTrivialStructWithConstructors final {
+                NonTrivialStructWithConstructors();
+                ~NonTrivialStructWithConstructors();  // Non-trivial
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! {impl Default});
+        Ok(())
+    }
+
+    #[test]
+    fn test_impl_from_for_1_arg_constructor() -> Result<()> {
+        for explicit_qualifier in ["", "explicit"] {
+            let ir = ir_from_cc(&format!(
+                r#"#pragma clang lifetime_elision
+                struct SomeStruct final {{
+                    {explicit_qualifier} SomeStruct(int i);  // implicit - no `explicit` keyword
+                }};"#,
+            ))?;
+            let rs_api = generate_bindings_tokens(ir)?.rs_api;
+            assert_rs_matches!(
+                rs_api,
+                quote! {
+                    impl From<::core::ffi::c_int> for SomeStruct {
+                        #[inline(always)]
+                        fn from(i: ::core::ffi::c_int) -> Self {
+                            let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+                            unsafe {
+                                crate::detail::__rust_thunk___ZN10SomeStructC1Ei(&mut tmp, i);
+                                tmp.assume_init()
+                            }
+                        }
+                    }
+                }
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_explicit_1_arg_constructor() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                explicit SomeStruct(int seconds);
+                explicit SomeStruct(float);
+                SomeStruct(char c);
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { constructor_functions: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl SomeStruct {
+                    ...
+                    #[inline(always)]
+                    pub fn from_seconds(seconds: ::core::ffi::c_int) -> Self {
+                        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+                        unsafe {
+                            crate::detail::__rust_thunk___ZN10SomeStructC1Ei(&mut tmp, seconds);
+                            tmp.assume_init()
+                        }
+                    }
+                    ...
+                }
+            }
+        );
+        assert_rs_matches!(rs_api, quote! { pub fn from_f32(__param_0: f32) -> Self });
+        assert_rs_not_matches!(rs_api, quote! { impl From<::core::ffi::c_int> });
+        assert_rs_not_matches!(rs_api, quote! { impl From<f32> });
+        // Converting constructors are still bound as `From`.
+        assert_rs_matches!(rs_api, quote! { impl From<::core::ffi::c_char> for SomeStruct });
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_arg_constructor() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                SomeStruct(int a, int b);
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { constructor_functions: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl SomeStruct {
+                    ...
+                    #[inline(always)]
+                    pub fn new(a: ::core::ffi::c_int, b: ::core::ffi::c_int) -> Self {
+                        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+                        unsafe {
+                            crate::detail::__rust_thunk___ZN10SomeStructC1Eii(&mut tmp, a, b);
+                            tmp.assume_init()
+                        }
+                    }
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_arg_constructor_without_constructor_functions() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                SomeStruct(int a, int b);
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { pub fn new });
+        Ok(())
+    }
+
+    #[test]
+    fn test_constructor_function_collides_with_method() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                explicit SomeStruct(float value);
+                SomeStruct(int a, int b);
+                SomeStruct(int a, double b);
+                static SomeStruct from_value(float value);
+                static SomeStruct new_c_int_c_int(int a);
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { constructor_functions: true, ..Default::default() },
+        )?
+        .rs_api;
+        // The methods keep their names, and the constructors don't get bindings.
+        assert_rs_matches!(rs_api, quote! { pub fn from_value(value: f32) -> crate::SomeStruct });
+        assert_rs_not_matches!(rs_api, quote! { pub fn from_value(value: f32) -> Self });
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn new_c_int_c_int(a: ::core::ffi::c_int) -> crate::SomeStruct }
+        );
+        assert_rs_not_matches!(
+            rs_api,
+            quote! { pub fn new_c_int_c_int(a: ::core::ffi::c_int, b: ::core::ffi::c_int) }
+        );
+        assert_rs_not_matches!(rs_api, quote! { pub fn new_c_int_f64 });
+        Ok(())
+    }
+
+    #[test]
+    fn test_overloaded_constructors() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                SomeStruct(int a, int b);
+                SomeStruct(int a, double b);
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { constructor_functions: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn new_c_int_c_int(a: ::core::ffi::c_int, b: ::core::ffi::c_int) -> Self }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn new_c_int_f64(a: ::core::ffi::c_int, b: f64) -> Self }
+        );
+        assert_rs_not_matches!(rs_api, quote! { Cannot generate bindings for overloaded function });
+
+        // Other overload naming policies apply to constructors, too.
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                SomeStruct(int a, int b);
+                SomeStruct(int a, double b);
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions {
+                overload_naming: OverloadNaming::Ordinal,
+                constructor_functions: true,
+                ..Default::default()
+            },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn new(a: ::core::ffi::c_int, b: ::core::ffi::c_int) -> Self }
+        );
+        assert_rs_matches!(rs_api, quote! { pub fn new1(a: ::core::ffi::c_int, b: f64) -> Self });
+        Ok(())
+    }
+
+    #[test]
+    fn test_impl_from_for_implicit_conversion_from_reference() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeOtherStruct final { int i; };
+            struct StructUnderTest final {
+                StructUnderTest(const SomeOtherStruct& other);  // implicit - no `explicit` keyword
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        // This is a regression test for b/223800038: We want to ensure that the
+        // code says `impl<'b>` (instead of incorrectly declaring that lifetime
+        // in `fn from<'b>`).
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl<'b> From<&'b crate::SomeOtherStruct> for StructUnderTest {
+                    #[inline(always)]
+                    fn from(other: &'b crate::SomeOtherStruct) -> Self {
+                        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+                        unsafe {
+                            crate::detail::__rust_thunk___ZN15StructUnderTestC1ERK15SomeOtherStruct(
+                                &mut tmp, other);
+                            tmp.assume_init()
+                        }
+                    }
+                }
+            },
+        );
+        Ok(())
+    }
+
+    /// Methods with missing lifetimes for `self` should give a useful error
+    /// message.
+    #[test]
+    fn test_eq_nolifetime() -> Result<()> {
+        // Missing lifetimes currently only causes hard errors for trait impls,
+        // not For inherent methods.
+        let ir = ir_from_cc("struct SomeStruct{SomeStruct& operator=(const SomeStruct&);};")?;
+
+        let rs_api = rs_tokens_to_formatted_string_for_tests(generate_bindings_tokens(ir)?.rs_api)?;
+        assert!(rs_api.contains(
+            "// Error while generating bindings for item 'SomeStruct::operator=':\n\
+             // `self` has no lifetime. Use lifetime annotations or \
+                `#pragma clang lifetime_elision` to create bindings for this function."
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_impl_eq_for_member_function() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                inline bool operator==(const SomeStruct& other) const {
+                    return i == other.i;
+                }
+                int i;
+            };"#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl PartialEq for SomeStruct {
+                    #[inline(always)]
+                    fn eq<'a, 'b>(&'a self, other: &'b Self) -> bool {
+                        unsafe { crate::detail::__rust_thunk___ZNK10SomeStructeqERKS_(self, other) }
+                    }
                 }
             }
         );
-        Ok(())
-    }
-
-    #[test]
-    fn test_unsigned_int_in_thunk_impls() -> Result<()> {
-        let ir = ir_from_cc("inline void foo(unsigned int i) {} ")?;
-        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
         assert_cc_matches!(
             rs_api_impl,
             quote! {
-                extern "C" void __rust_thunk___Z3fooj(unsigned int i) {
-                    foo(i);
+                extern "C" bool __rust_thunk___ZNK10SomeStructeqERKS_(
+                        const struct SomeStruct* __this, const struct SomeStruct* other) {
+                    return __this->operator==(*other);
                 }
             }
         );
@@ -2085,19 +6781,23 @@ mod tests {
     }
 
     #[test]
-    fn test_record_static_methods_qualify_call_in_thunk() -> Result<()> {
+    fn test_impl_eq_for_free_function() -> Result<()> {
         let ir = ir_from_cc(
-            r#"
-            struct SomeStruct {
-                static inline int some_func() { return 42; }
-            }; "#,
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final { int i; };
+            bool operator==(const SomeStruct& lhs, const SomeStruct& rhs) {
+                return lhs.i == rhs.i;
+            }"#,
         )?;
-
-        assert_cc_matches!(
-            generate_bindings_tokens(ir)?.rs_api_impl,
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
             quote! {
-                extern "C" int __rust_thunk___ZN10SomeStruct9some_funcEv() {
-                    return SomeStruct::some_func();
+                impl PartialEq for SomeStruct {
+                    #[inline(always)]
+                    fn eq<'a, 'b>(&'a self, rhs: &'b Self) -> bool {
+                        unsafe { crate::detail::__rust_thunk___ZeqRK10SomeStructS1_(self, rhs) }
+                    }
                 }
             }
         );
@@ -2105,20 +6805,24 @@ mod tests {
     }
 
     #[test]
-    fn test_record_instance_methods_deref_this_in_thunk() -> Result<()> {
+    fn test_impl_eq_for_free_function_different_types() -> Result<()> {
         let ir = ir_from_cc(
-            r#"
-            struct SomeStruct {
-                inline int some_func(int arg) const { return 42 + arg; }
-            }; "#,
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final { int i; };
+            struct SomeOtherStruct final { int i; };
+            bool operator==(const SomeStruct& lhs, const SomeOtherStruct& rhs) {
+                return lhs.i == rhs.i;
+            }"#,
         )?;
-
-        assert_cc_matches!(
-            generate_bindings_tokens(ir)?.rs_api_impl,
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
             quote! {
-                extern "C" int __rust_thunk___ZNK10SomeStruct9some_funcEi(
-                        const struct SomeStruct* __this, int arg) {
-                    return __this->some_func(arg);
+                impl PartialEq<crate::SomeOtherStruct> for SomeStruct {
+                    #[inline(always)]
+                    fn eq<'a, 'b>(&'a self, rhs: &'b crate::SomeOtherStruct) -> bool {
+                        unsafe { crate::detail::__rust_thunk___ZeqRK10SomeStructRK15SomeOtherStruct(self, rhs) }
+                    }
                 }
             }
         );
@@ -2126,99 +6830,117 @@ mod tests {
     }
 
     #[test]
-    fn test_ptr_func() -> Result<()> {
-        let ir = ir_from_cc(r#" inline int* Deref(int*const* p); "#)?;
-
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
-        assert_rs_matches!(
-            rs_api,
-            quote! {
-                #[inline(always)]
-                pub unsafe fn Deref(p: *const *mut ::core::ffi::c_int) -> *mut ::core::ffi::c_int {
-                    crate::detail::__rust_thunk___Z5DerefPKPi(p)
-                }
-            }
-        );
+    fn test_impl_eq_for_free_function_by_value() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final { int i; };
+            bool operator==(SomeStruct lhs, SomeStruct rhs) {
+                return lhs.i == rhs.i;
+            }"#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                mod detail {
-                    #[allow(unused_imports)]
-                    use super::*;
-                    extern "C" {
-                        pub(crate) fn __rust_thunk___Z5DerefPKPi(p: *const *mut ::core::ffi::c_int) -> *mut ::core::ffi::c_int;
+                impl PartialEq for SomeStruct {
+                    #[inline(always)]
+                    fn eq(& self, rhs: & Self) -> bool {
+                        unsafe { crate::detail::__rust_thunk___Zeq10SomeStructS_(
+                                &mut self.clone(), &mut rhs.clone()) }
                     }
                 }
             }
         );
-
-        assert_cc_matches!(
-            rs_api_impl,
-            quote! {
-                extern "C" int* __rust_thunk___Z5DerefPKPi(int* const * p) {
-                    return Deref(p);
-                }
-            }
-        );
         Ok(())
     }
 
     #[test]
-    fn test_const_char_ptr_func() -> Result<()> {
-        // This is a regression test: We used to include the "const" in the name
-        // of the CcType, which caused a panic in the code generator
-        // ('"const char" is not a valid Ident').
-        // It's therefore important that f() is inline so that we need to
-        // generate a thunk for it (where we then process the CcType).
-        let ir = ir_from_cc(r#" inline void f(const signed char *str); "#)?;
-
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
-        assert_rs_matches!(
-            rs_api,
-            quote! {
-                #[inline(always)]
-                pub unsafe fn f(str: *const ::core::ffi::c_schar) {
-                    crate::detail::__rust_thunk___Z1fPKa(str)
+    fn test_impl_lt_for_member_function() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                inline bool operator==(const SomeStruct& other) const {
+                    return i == other.i;
                 }
-            }
-        );
+                inline bool operator<(const SomeStruct& other) const {
+                    return i < other.i;
+                }
+                int i;
+            };"#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
-                extern "C" {
-                    pub(crate) fn __rust_thunk___Z1fPKa(str: *const ::core::ffi::c_schar);
+                impl PartialOrd for SomeStruct {
+                    #[inline(always)]
+                    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                        if self == other {
+                            return Some(core::cmp::Ordering::Equal);
+                        }
+                        if self < other {
+                            return Some(core::cmp::Ordering::Less);
+                        }
+                        if other < self {
+                            return Some(core::cmp::Ordering::Greater);
+                        }
+                        None
+                    }
+                    #[inline(always)]
+                    fn lt<'a, 'b>(&'a self, other: &'b Self) -> bool {
+                        unsafe { crate::detail::__rust_thunk___ZNK10SomeStructltERKS_(self, other) }
+                    }
                 }
             }
         );
-
         assert_cc_matches!(
             rs_api_impl,
             quote! {
-                extern "C" void __rust_thunk___Z1fPKa(signed char const * str){ f(str); }
+                extern "C" bool __rust_thunk___ZNK10SomeStructltERKS_(
+                        const struct SomeStruct* __this, const struct SomeStruct* other) {
+                    return __this->operator<(*other);
+                }
             }
         );
         Ok(())
     }
 
     #[test]
-    fn test_func_ptr_thunk() -> Result<()> {
-        // Using an `inline` keyword forces generation of a C++ thunk in
-        // `rs_api_impl` (i.e. exercises `format_cc_type` and similar code).
+    fn test_impl_lt_for_free_function() -> Result<()> {
         let ir = ir_from_cc(
-            r#"
-            int multiply(int x, int y);
-            inline int (*inline_get_pointer_to_function())(int, int) {
-                return multiply;
-            }
-        "#,
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                inline bool operator==(const SomeStruct& other) const {
+                    return i == other.i;
+                }
+                int i;
+            };
+            bool operator<(const SomeStruct& lhs, const SomeStruct& rhs) {
+                return lhs.i < rhs.i;
+            }"#,
         )?;
-        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
-        assert_cc_matches!(
-            rs_api_impl,
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
             quote! {
-                extern "C" crubit::type_identity_t<int(int , int)>*
-                __rust_thunk___Z30inline_get_pointer_to_functionv() {
-                    return inline_get_pointer_to_function();
+                impl PartialOrd for SomeStruct {
+                    #[inline(always)]
+                    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                        if self == other {
+                            return Some(core::cmp::Ordering::Equal);
+                        }
+                        if self < other {
+                            return Some(core::cmp::Ordering::Less);
+                        }
+                        if other < self {
+                            return Some(core::cmp::Ordering::Greater);
+                        }
+                        None
+                    }
+                    #[inline(always)]
+                    fn lt<'a, 'b>(&'a self, rhs: &'b Self) -> bool {
+                        unsafe { crate::detail::__rust_thunk___ZltRK10SomeStructS1_(self, rhs) }
+                    }
                 }
             }
         );
@@ -2226,76 +6948,121 @@ mod tests {
     }
 
     #[test]
-    fn test_doc_comment_func() -> Result<()> {
+    fn test_impl_lt_for_free_function_by_value() -> Result<()> {
         let ir = ir_from_cc(
-            "
-        // Doc Comment
-        // with two lines
-        int func();",
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final { int i; };
+            bool operator==(SomeStruct lhs, SomeStruct rhs) {
+                return lhs.i == rhs.i;
+            }
+            bool operator<(SomeStruct lhs, SomeStruct rhs) {
+                return lhs.i < rhs.i;
+            }"#,
         )?;
-
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
-            generate_bindings_tokens(ir)?.rs_api,
-            // leading space is intentional so there is a space between /// and the text of the
-            // comment
+            rs_api,
             quote! {
-                #[doc = " Doc Comment\n with two lines\n \n Generated from: google3/ir_from_cc_virtual_header.h;l=6"]
-                #[inline(always)]
-                pub fn func
+                impl PartialOrd for SomeStruct {
+                    #[inline(always)]
+                    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                        if self == other {
+                            return Some(core::cmp::Ordering::Equal);
+                        }
+                        if self < other {
+                            return Some(core::cmp::Ordering::Less);
+                        }
+                        if other < self {
+                            return Some(core::cmp::Ordering::Greater);
+                        }
+                        None
+                    }
+                    #[inline(always)]
+                    fn lt(& self, rhs: &Self) -> bool {
+                        unsafe { crate::detail::__rust_thunk___Zlt10SomeStructS_(
+                                &mut self.clone(), &mut rhs.clone()) }
+                    }
+                }
             }
         );
-
         Ok(())
     }
 
-    /// Trivial types (at least those that are mapped to Copy rust types) do not
-    /// get a Drop impl.
     #[test]
-    fn test_impl_drop_trivial() -> Result<()> {
+    fn test_assign() -> Result<()> {
         let ir = ir_from_cc(
-            r#"struct Trivial final {
-                ~Trivial() = default;
-                int x;
+            r#"
+            #pragma clang lifetime_elision
+            struct SomeStruct {
+                ~SomeStruct();
+                SomeStruct& operator=(const SomeStruct& other);
             };"#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
-        assert_rs_not_matches!(rs_api, quote! {impl Drop});
-        assert_rs_not_matches!(rs_api, quote! {impl ::ctor::PinnedDrop});
-        assert_rs_matches!(rs_api, quote! {pub x: ::core::ffi::c_int});
-        assert_cc_not_matches!(rs_api_impl, quote! { std::destroy_at });
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl<'b> ::ctor::Assign<&'b Self> for SomeStruct {
+                    #[inline(always)]
+                    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, other: &'b Self) {
+                        unsafe {
+                            crate::detail::__rust_thunk___ZN10SomeStructaSERKS_(self, other);
+                        }
+                    }
+                }
+            }
+        );
         Ok(())
     }
 
     #[test]
-    fn test_impl_default_explicitly_defaulted_constructor() -> Result<()> {
+    fn test_assign_nonreference_other() -> Result<()> {
         let ir = ir_from_cc(
-            r#"#pragma clang lifetime_elision
-            struct DefaultedConstructor final {
-                DefaultedConstructor() = default;
+            r#"
+            #pragma clang lifetime_elision
+            struct SomeStruct {
+                ~SomeStruct();
+                SomeStruct& operator=(int other);
             };"#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl Default for DefaultedConstructor {
+                impl<'b> ::ctor::Assign<&'b Self> for SomeStruct {
                     #[inline(always)]
-                    fn default() -> Self {
-                        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+                    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
                         unsafe {
-                            crate::detail::__rust_thunk___ZN20DefaultedConstructorC1Ev(&mut tmp);
-                            tmp.assume_init()
+                            crate::detail::__rust_thunk___ZN10SomeStructaSERKS_(self, __param_0);
                         }
                     }
                 }
             }
         );
-        assert_cc_matches!(
-            rs_api_impl,
+        Ok(())
+    }
+
+    #[test]
+    fn test_assign_nonreference_return() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            #pragma clang lifetime_elision
+            struct SomeStruct {
+                ~SomeStruct();
+                int operator=(const SomeStruct& other);
+            };"#,
+        )?;
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
             quote! {
-                extern "C" void __rust_thunk___ZN20DefaultedConstructorC1Ev(
-                        struct DefaultedConstructor* __this) {
-                    crubit::construct_at(__this);
+                impl<'b> ::ctor::Assign<&'b Self> for SomeStruct {
+                    #[inline(always)]
+                    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, other: &'b Self) {
+                        unsafe {
+                            crate::detail::__rust_thunk___ZN10SomeStructaSERKS_(self, other);
+                        }
+                    }
                 }
             }
         );
@@ -2303,181 +7070,167 @@ mod tests {
     }
 
     #[test]
-    fn test_impl_clone_that_propagates_lifetime() -> Result<()> {
-        // This test covers the case where a single lifetime applies to 1)
-        // the `__this` parameter and 2) other constructor parameters. For
-        // example, maybe the newly constructed object needs to have the
-        // same lifetime as the constructor's parameter. (This might require
-        // annotating the whole C++ struct with a lifetime, so maybe the
-        // example below is not fully realistic/accurate...).
-        let ir = ir_from_cc(&with_lifetime_macros(
+    fn test_impl_eq_non_const_member_function() -> Result<()> {
+        let ir = ir_from_cc(
             r#"#pragma clang lifetime_elision
-            struct Foo final {
-                Foo(const int& $a i) $a;
+            struct SomeStruct final {
+                bool operator==(const SomeStruct& other) /* no `const` here */;
             };"#,
-        ))?;
-        let ctor: &Func = ir
-            .items()
-            .filter_map(|item| match item {
-                Item::Func(func) => Some(&**func),
-                _ => None,
-            })
-            .find(|f| {
-                matches!(&f.name, UnqualifiedIdentifier::Constructor)
-                    && f.params
-                        .get(1)
-                        .map(|p| p.identifier.identifier.as_ref() == "i")
-                        .unwrap_or_default()
-            })
-            .unwrap();
-        {
-            // Double-check that the test scenario set up above uses the same lifetime
-            // for both of the constructor's parameters: `__this` and `i`.
-            assert_eq!(ctor.params.len(), 2);
-            let this_lifetime: LifetimeId =
-                *ctor.params[0].type_.rs_type.lifetime_args.first().unwrap();
-            let i_lifetime: LifetimeId =
-                *ctor.params[1].type_.rs_type.lifetime_args.first().unwrap();
-            assert_eq!(i_lifetime, this_lifetime);
-        }
-
-        // Before cl/423346348 the generated Rust code would incorrectly look
-        // like this (note the mismatched 'a and 'b lifetimes):
-        //     fn from<'b>(i: &'a i32) -> Self
-        // After this CL, this scenario will result in an explicit error.
+        )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
-        assert_rs_not_matches!(rs_api, quote! {impl From});
-        assert_rs_matches!(rs_api, {
-            let txt = "Generated from: google3/ir_from_cc_virtual_header.h;l=34\n\
-                           Error while generating bindings for item 'Foo::Foo':\n\
-                           The lifetime of `__this` is \
-                               unexpectedly also used by another parameter: Lifetime(\"a\")";
-            quote! { __COMMENT__ #txt }
-        });
+        assert_rs_not_matches!(rs_api, quote! {impl PartialEq});
         Ok(())
     }
 
     #[test]
-    fn test_impl_default_non_trivial_struct() -> Result<()> {
+    fn test_call_operator() -> Result<()> {
         let ir = ir_from_cc(
             r#"#pragma clang lifetime_elision
-            struct NonTrivialStructWithConstructors final {
-                NonTrivialStructWithConstructors();
-                ~NonTrivialStructWithConstructors();  // Non-trivial
+            struct SomeStruct final {
+                int operator()(int x, int y) const;
             };"#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
-        assert_rs_not_matches!(rs_api, quote! {impl Default});
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl SomeStruct {
+                    ...
+                    #[inline(always)]
+                    pub fn call<'a>(&'a self, x: ::core::ffi::c_int, y: ::core::ffi::c_int)
+                        -> ::core::ffi::c_int {
+                        unsafe { crate::detail::__rust_thunk___ZNK10SomeStructclEii(self, x, y) }
+                    }
+                    ...
+                    #[inline(always)]
+                    pub fn as_fn(&self)
+                        -> impl Fn(::core::ffi::c_int, ::core::ffi::c_int) -> ::core::ffi::c_int + '_ {
+                        move |x, y| self.call(x, y)
+                    }
+                }
+            }
+        );
         Ok(())
     }
 
     #[test]
-    fn test_impl_from_for_1_arg_constructor() -> Result<()> {
-        for explicit_qualifier in ["", "explicit"] {
-            let ir = ir_from_cc(&format!(
-                r#"#pragma clang lifetime_elision
-                struct SomeStruct final {{
-                    {explicit_qualifier} SomeStruct(int i);  // implicit - no `explicit` keyword
-                }};"#,
-            ))?;
-            let rs_api = generate_bindings_tokens(ir)?.rs_api;
-            assert_rs_matches!(
-                rs_api,
-                quote! {
-                    impl From<::core::ffi::c_int> for SomeStruct {
-                        #[inline(always)]
-                        fn from(i: ::core::ffi::c_int) -> Self {
-                            let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
-                            unsafe {
-                                crate::detail::__rust_thunk___ZN10SomeStructC1Ei(&mut tmp, i);
-                                tmp.assume_init()
-                            }
-                        }
+    fn test_call_operator_non_const() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                void operator()(int x);
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl SomeStruct {
+                    ...
+                    pub fn call<'a>(&'a mut self, x: ::core::ffi::c_int) {
+                        ...
                     }
                 }
-            );
-        }
+            }
+        );
+        // `as_fn` requires a const `operator()`.
+        assert_rs_not_matches!(rs_api, quote! { fn as_fn });
         Ok(())
     }
 
     #[test]
-    fn test_impl_from_for_implicit_conversion_from_reference() -> Result<()> {
+    fn test_call_operator_colliding_with_method() -> Result<()> {
         let ir = ir_from_cc(
             r#"#pragma clang lifetime_elision
-            struct SomeOtherStruct final { int i; };
-            struct StructUnderTest final {
-                StructUnderTest(const SomeOtherStruct& other);  // implicit - no `explicit` keyword
+            struct SomeStruct final {
+                int operator()(int x) const;
+                int call(int x) const;
             };"#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
-        // This is a regression test for b/223800038: We want to ensure that the
-        // code says `impl<'b>` (instead of incorrectly declaring that lifetime
-        // in `fn from<'b>`).
         assert_rs_matches!(
             rs_api,
-            quote! {
-                impl<'b> From<&'b crate::SomeOtherStruct> for StructUnderTest {
-                    #[inline(always)]
-                    fn from(other: &'b crate::SomeOtherStruct) -> Self {
-                        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
-                        unsafe {
-                            crate::detail::__rust_thunk___ZN15StructUnderTestC1ERK15SomeOtherStruct(
-                                &mut tmp, other);
-                            tmp.assume_init()
-                        }
-                    }
-                }
-            },
+            quote! { pub fn call<'a>(&'a self, x: ::core::ffi::c_int) -> ::core::ffi::c_int }
         );
+        assert_rs_not_matches!(rs_api, quote! { fn as_fn });
+        assert_cc_matches!(rs_api, {
+            let txt = "Generated from: google3/ir_from_cc_virtual_header.h;l=3\n\
+                       Error while generating bindings for item 'SomeStruct::operator()':\n\
+                       operator() can't be bound as `call`, because it collides with a method of \
+                       `SomeStruct`";
+            quote! { __COMMENT__ #txt }
+        });
         Ok(())
     }
 
-    /// Methods with missing lifetimes for `self` should give a useful error
-    /// message.
     #[test]
-    fn test_eq_nolifetime() -> Result<()> {
-        // Missing lifetimes currently only causes hard errors for trait impls,
-        // not For inherent methods.
-        let ir = ir_from_cc("struct SomeStruct{SomeStruct& operator=(const SomeStruct&);};")?;
+    fn test_call_operator_fn_adapter_colliding_with_method() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                int operator()(int x) const;
+                void as_fn() const;
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub fn call });
+        assert_rs_matches!(rs_api, quote! { pub fn as_fn<'a>(&'a self) });
+        assert_rs_not_matches!(rs_api, quote! { -> impl Fn });
+        assert_cc_matches!(rs_api, {
+            let txt = "The `as_fn` adapter of operator() is not generated, because it collides \
+                       with a method of `SomeStruct`";
+            quote! { __COMMENT__ #txt }
+        });
+        Ok(())
+    }
 
-        let rs_api = rs_tokens_to_formatted_string_for_tests(generate_bindings_tokens(ir)?.rs_api)?;
-        assert!(rs_api.contains(
-            "// Error while generating bindings for item 'SomeStruct::operator=':\n\
-             // `self` has no lifetime. Use lifetime annotations or \
-                `#pragma clang lifetime_elision` to create bindings for this function."
-        ));
+    #[test]
+    fn test_call_operator_without_experimental_features() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                int operator()(int x) const;
+            };"#,
+        )?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub fn call });
+        assert_rs_not_matches!(rs_api, quote! { fn as_fn });
         Ok(())
     }
 
     #[test]
-    fn test_impl_eq_for_member_function() -> Result<()> {
+    fn test_index_operator() -> Result<()> {
         let ir = ir_from_cc(
             r#"#pragma clang lifetime_elision
             struct SomeStruct final {
-                inline bool operator==(const SomeStruct& other) const {
-                    return i == other.i;
-                }
-                int i;
+                const int& operator[](int i) const;
+                int& operator[](int i);
             };"#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl PartialEq for SomeStruct {
+                impl ::core::ops::Index<::core::ffi::c_int> for SomeStruct {
+                    type Output = ::core::ffi::c_int;
                     #[inline(always)]
-                    fn eq<'a, 'b>(&'a self, other: &'b Self) -> bool {
-                        unsafe { crate::detail::__rust_thunk___ZNK10SomeStructeqERKS_(self, other) }
+                    fn index<'a>(&'a self, i: ::core::ffi::c_int) -> &'a ::core::ffi::c_int {
+                        unsafe { crate::detail::__rust_thunk___ZNK10SomeStructixEi(self, i) }
                     }
                 }
             }
         );
-        assert_cc_matches!(
-            rs_api_impl,
-            quote! {
-                extern "C" bool __rust_thunk___ZNK10SomeStructeqERKS_(
-                        const struct SomeStruct* __this, const struct SomeStruct* other) {
-                    return __this->operator==(*other);
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl ::core::ops::IndexMut<::core::ffi::c_int> for SomeStruct {
+                    #[inline(always)]
+                    fn index_mut<'a>(&'a mut self, i: ::core::ffi::c_int)
+                        -> &'a mut ::core::ffi::c_int {
+                        unsafe { crate::detail::__rust_thunk___ZN10SomeStructixEi(self, i) }
+                    }
                 }
             }
         );
@@ -2485,22 +7238,24 @@ mod tests {
     }
 
     #[test]
-    fn test_impl_eq_for_free_function() -> Result<()> {
+    fn test_index_operator_mut_without_const() -> Result<()> {
         let ir = ir_from_cc(
             r#"#pragma clang lifetime_elision
-            struct SomeStruct final { int i; };
-            bool operator==(const SomeStruct& lhs, const SomeStruct& rhs) {
-                return lhs.i == rhs.i;
-            }"#,
+            struct SomeStruct final {
+                int& operator[](int i);
+            };"#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        // `IndexMut` requires `Index`, so `operator[]` is bound as a method instead.
+        assert_rs_not_matches!(rs_api, quote! { IndexMut });
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl PartialEq for SomeStruct {
+                impl SomeStruct {
                     #[inline(always)]
-                    fn eq<'a, 'b>(&'a self, rhs: &'b Self) -> bool {
-                        unsafe { crate::detail::__rust_thunk___ZeqRK10SomeStructS1_(self, rhs) }
+                    pub fn index_mut<'a>(&'a mut self, i: ::core::ffi::c_int)
+                        -> &'a mut ::core::ffi::c_int {
+                        unsafe { crate::detail::__rust_thunk___ZN10SomeStructixEi(self, i) }
                     }
                 }
             }
@@ -2509,23 +7264,22 @@ mod tests {
     }
 
     #[test]
-    fn test_impl_eq_for_free_function_different_types() -> Result<()> {
+    fn test_index_operator_by_value() -> Result<()> {
         let ir = ir_from_cc(
             r#"#pragma clang lifetime_elision
-            struct SomeStruct final { int i; };
-            struct SomeOtherStruct final { int i; };
-            bool operator==(const SomeStruct& lhs, const SomeOtherStruct& rhs) {
-                return lhs.i == rhs.i;
-            }"#,
+            struct SomeStruct final {
+                int operator[](int i) const;
+            };"#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { ::core::ops::Index });
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl PartialEq<crate::SomeOtherStruct> for SomeStruct {
+                impl SomeStruct {
                     #[inline(always)]
-                    fn eq<'a, 'b>(&'a self, rhs: &'b crate::SomeOtherStruct) -> bool {
-                        unsafe { crate::detail::__rust_thunk___ZeqRK10SomeStructRK15SomeOtherStruct(self, rhs) }
+                    pub fn index<'a>(&'a self, i: ::core::ffi::c_int) -> ::core::ffi::c_int {
+                        unsafe { crate::detail::__rust_thunk___ZNK10SomeStructixEi(self, i) }
                     }
                 }
             }
@@ -2534,23 +7288,22 @@ mod tests {
     }
 
     #[test]
-    fn test_impl_eq_for_free_function_by_value() -> Result<()> {
+    fn test_operator_without_trait_is_bound_as_method() -> Result<()> {
         let ir = ir_from_cc(
             r#"#pragma clang lifetime_elision
-            struct SomeStruct final { int i; };
-            bool operator==(SomeStruct lhs, SomeStruct rhs) {
-                return lhs.i == rhs.i;
-            }"#,
+            struct SomeStruct final {
+                int operator+=(int rhs) const;
+            };"#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { AddAssign });
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl PartialEq for SomeStruct {
+                impl SomeStruct {
                     #[inline(always)]
-                    fn eq(& self, rhs: & Self) -> bool {
-                        unsafe { crate::detail::__rust_thunk___Zeq10SomeStructS_(
-                                &mut self.clone(), &mut rhs.clone()) }
+                    pub fn add_assign<'a>(&'a self, rhs: ::core::ffi::c_int) -> ::core::ffi::c_int {
+                        unsafe { crate::detail::__rust_thunk___ZNK10SomeStructpLEi(self, rhs) }
                     }
                 }
             }
@@ -2559,91 +7312,76 @@ mod tests {
     }
 
     #[test]
-    fn test_impl_lt_for_member_function() -> Result<()> {
+    fn test_operator_method_name_collision() -> Result<()> {
         let ir = ir_from_cc(
             r#"#pragma clang lifetime_elision
             struct SomeStruct final {
-                inline bool operator==(const SomeStruct& other) const {
-                    return i == other.i;
-                }
-                inline bool operator<(const SomeStruct& other) const {
-                    return i < other.i;
-                }
-                int i;
+                int operator+=(int rhs) const;
+                void add_assign();
+            };"#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { pub fn add_assign<'a>(&'a self, rhs: ...) });
+        assert_rs_matches!(rs_api, quote! { pub fn add_assign<'a>(&'a mut self) });
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrow_operator() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct Pointee final { int i; };
+            struct SmartPtr final {
+                Pointee* operator->() const;
+                Pointee& operator*() const;
             };"#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl PartialOrd for SomeStruct {
+                impl SmartPtr {
+                    ...
                     #[inline(always)]
-                    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-                        if self == other {
-                            return Some(core::cmp::Ordering::Equal);
-                        }
-                        if self < other {
-                            return Some(core::cmp::Ordering::Less);
-                        }
-                        if other < self {
-                            return Some(core::cmp::Ordering::Greater);
-                        }
-                        None
+                    pub fn get<'a>(&'a self) -> Option<&'a mut crate::Pointee> {
+                        unsafe { crate::detail::__rust_thunk___ZNK8SmartPtrptEv(self) }
                     }
+                    ...
                     #[inline(always)]
-                    fn lt<'a, 'b>(&'a self, other: &'b Self) -> bool {
-                        unsafe { crate::detail::__rust_thunk___ZNK10SomeStructltERKS_(self, other) }
+                    pub fn as_ref(&self) -> Option<&crate::Pointee> {
+                        self.get().map(|r| &*r)
                     }
                 }
             }
         );
-        assert_cc_matches!(
-            rs_api_impl,
-            quote! {
-                extern "C" bool __rust_thunk___ZNK10SomeStructltERKS_(
-                        const struct SomeStruct* __this, const struct SomeStruct* other) {
-                    return __this->operator<(*other);
-                }
-            }
-        );
+        // `operator*` is covered by `get` generated for `operator->`.
+        assert_rs_not_matches!(rs_api, quote! { __rust_thunk___ZNK8SmartPtrdeEv });
         Ok(())
     }
 
     #[test]
-    fn test_impl_lt_for_free_function() -> Result<()> {
+    fn test_deref_operator() -> Result<()> {
         let ir = ir_from_cc(
             r#"#pragma clang lifetime_elision
-            struct SomeStruct final {
-                inline bool operator==(const SomeStruct& other) const {
-                    return i == other.i;
-                }
-                int i;
-            };
-            bool operator<(const SomeStruct& lhs, const SomeStruct& rhs) {
-                return lhs.i < rhs.i;
-            }"#,
+            struct Pointee final { int i; };
+            struct SmartRef final {
+                const Pointee& operator*() const;
+            };"#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl PartialOrd for SomeStruct {
+                impl SmartRef {
+                    ...
                     #[inline(always)]
-                    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-                        if self == other {
-                            return Some(core::cmp::Ordering::Equal);
-                        }
-                        if self < other {
-                            return Some(core::cmp::Ordering::Less);
-                        }
-                        if other < self {
-                            return Some(core::cmp::Ordering::Greater);
-                        }
-                        None
+                    pub fn get<'a>(&'a self) -> &'a crate::Pointee {
+                        unsafe { crate::detail::__rust_thunk___ZNK8SmartRefdeEv(self) }
                     }
+                    ...
                     #[inline(always)]
-                    fn lt<'a, 'b>(&'a self, rhs: &'b Self) -> bool {
-                        unsafe { crate::detail::__rust_thunk___ZltRK10SomeStructS1_(self, rhs) }
+                    pub fn as_ref(&self) -> Option<&crate::Pointee> {
+                        Some(&*self.get())
                     }
                 }
             }
@@ -2652,120 +7390,199 @@ mod tests {
     }
 
     #[test]
-    fn test_impl_lt_for_free_function_by_value() -> Result<()> {
+    fn test_arrow_operator_without_lifetimes() -> Result<()> {
         let ir = ir_from_cc(
-            r#"#pragma clang lifetime_elision
-            struct SomeStruct final { int i; };
-            bool operator==(SomeStruct lhs, SomeStruct rhs) {
-                return lhs.i == rhs.i;
-            }
-            bool operator<(SomeStruct lhs, SomeStruct rhs) {
-                return lhs.i < rhs.i;
-            }"#,
+            r#"
+            struct Pointee final { int i; };
+            struct SmartPtr final {
+                Pointee* operator->() const;
+            };"#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl PartialOrd for SomeStruct {
-                    #[inline(always)]
-                    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-                        if self == other {
-                            return Some(core::cmp::Ordering::Equal);
-                        }
-                        if self < other {
-                            return Some(core::cmp::Ordering::Less);
-                        }
-                        if other < self {
-                            return Some(core::cmp::Ordering::Greater);
-                        }
-                        None
-                    }
-                    #[inline(always)]
-                    fn lt(& self, rhs: &Self) -> bool {
-                        unsafe { crate::detail::__rust_thunk___Zlt10SomeStructS_(
-                                &mut self.clone(), &mut rhs.clone()) }
+                impl SmartPtr {
+                    ...
+                    pub unsafe fn get(__this: *const Self) -> *mut crate::Pointee {
+                        ...
                     }
                 }
             }
         );
+        assert_rs_not_matches!(rs_api, quote! { fn as_ref });
         Ok(())
     }
 
     #[test]
-    fn test_assign() -> Result<()> {
+    fn test_arrow_operator_colliding_with_get() -> Result<()> {
         let ir = ir_from_cc(
-            r#"
-            #pragma clang lifetime_elision
-            struct SomeStruct {
-                ~SomeStruct();
-                SomeStruct& operator=(const SomeStruct& other);
+            r#"#pragma clang lifetime_elision
+            struct Pointee final { int i; };
+            struct SmartPtr final {
+                Pointee* get() const;
+                Pointee* operator->() const;
             };"#,
         )?;
-        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
-            quote! {
-                impl<'b> ::ctor::Assign<&'b Self> for SomeStruct {
-                    #[inline(always)]
-                    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, other: &'b Self) {
-                        unsafe {
-                            crate::detail::__rust_thunk___ZN10SomeStructaSERKS_(self, other);
-                        }
-                    }
-                }
-            }
+            quote! { pub fn get<'a>(&'a self) -> Option<&'a mut crate::Pointee> }
         );
+        assert_rs_not_matches!(rs_api, quote! { __rust_thunk___ZNK8SmartPtrptEv });
+        assert_rs_not_matches!(rs_api, quote! { fn as_ref });
+        assert_cc_matches!(rs_api, {
+            let txt = "Generated from: google3/ir_from_cc_virtual_header.h;l=5\n\
+                       Error while generating bindings for item 'SmartPtr::operator->':\n\
+                       operator-> can't be bound as `get`, because it collides with a method of \
+                       `SmartPtr`";
+            quote! { __COMMENT__ #txt }
+        });
         Ok(())
     }
 
     #[test]
-    fn test_assign_nonreference_other() -> Result<()> {
+    fn test_arrow_operator_as_ref_colliding_with_method() -> Result<()> {
         let ir = ir_from_cc(
-            r#"
-            #pragma clang lifetime_elision
-            struct SomeStruct {
-                ~SomeStruct();
-                SomeStruct& operator=(int other);
+            r#"#pragma clang lifetime_elision
+            struct Pointee final { int i; };
+            struct SmartPtr final {
+                Pointee* operator->() const;
+                void as_ref() const;
             };"#,
         )?;
-        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub fn get });
+        assert_rs_matches!(rs_api, quote! { pub fn as_ref<'a>(&'a self) });
+        assert_rs_not_matches!(rs_api, quote! { self.get().map });
+        assert_cc_matches!(rs_api, {
+            let txt = "The `as_ref` accessor of `get` is not generated, because it collides with \
+                       a method of `SmartPtr`";
+            quote! { __COMMENT__ #txt }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_deref_operator_when_arrow_operator_has_no_bindings() -> Result<()> {
+        let mut ir = ir_from_cc_dependency(
+            r#"#pragma clang lifetime_elision
+            struct Pointee final { int i; };
+            struct SmartPtr final {
+                NotPresent* operator->() const;
+                Pointee& operator*() const;
+            };"#,
+            "struct NotPresent final {};",
+        )?;
+        ir.target_crubit_features_mut(&ir::BazelLabel("//test:dependency".into())).clear();
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { __rust_thunk___ZNK8SmartPtrptEv });
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl<'b> ::ctor::Assign<&'b Self> for SomeStruct {
+                pub fn get<'a>(&'a self) -> &'a mut crate::Pointee {
+                    unsafe { crate::detail::__rust_thunk___ZNK8SmartPtrdeEv(self) }
+                }
+            }
+        );
+        Ok(())
+    }
+
+    const STD_STRING_CC: &str = r#"
+        namespace std {
+        template <typename CharT>
+        class basic_string final {
+          public:
+            const CharT* data() const;
+            decltype(sizeof(0)) size() const;
+          private:
+            CharT* data_;
+        };
+        using string = basic_string<char>;
+        }  // namespace std
+    "#;
+
+    #[test]
+    fn test_string_accessors() -> Result<()> {
+        let ir = ir_from_cc(&format!(
+            r#"#pragma clang lifetime_elision
+            {STD_STRING_CC}
+            struct S final {{
+                const std::string& name() const;
+            }};"#
+        ))?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl S {
+                    ...
+                    #[doc = " Returns the contents of the string returned by `name`."]
                     #[inline(always)]
-                    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
+                    pub fn name_bytes(&self) -> &[u8] {
+                        let mut __size = 0usize;
                         unsafe {
-                            crate::detail::__rust_thunk___ZN10SomeStructaSERKS_(self, __param_0);
+                            let __data = crate::detail::__rust_thunk___ZNK1S4nameEv__bytes(
+                                self.name(), &mut __size);
+                            ::core::slice::from_raw_parts(__data, __size)
+                        }
+                    }
+                    #[doc = " Returns a copy of the contents of the string returned by `name`."]
+                    #[inline(always)]
+                    pub fn name_to_owned(&self) -> ::std::vec::Vec<u8> {
+                        let mut __size = 0usize;
+                        unsafe {
+                            let __data = crate::detail::__rust_thunk___ZNK1S4nameEv__bytes(
+                                self.name(), &mut __size);
+                            ::core::slice::from_raw_parts(__data, __size).to_vec()
                         }
                     }
                 }
             }
         );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk___ZNK1S4nameEv__bytes(
+                    __s: *const crate::std::string, __size: *mut usize) -> *const u8;
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" const char* __rust_thunk___ZNK1S4nameEv__bytes(
+                    const std::basic_string<char>* __s, std::size_t* __size) {
+                    *__size = __s->size();
+                    return __s->data();
+                }
+            }
+        );
         Ok(())
     }
 
     #[test]
-    fn test_assign_nonreference_return() -> Result<()> {
-        let ir = ir_from_cc(
-            r#"
-            #pragma clang lifetime_elision
-            struct SomeStruct {
-                ~SomeStruct();
-                int operator=(const SomeStruct& other);
-            };"#,
-        )?;
-        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+    fn test_string_accessors_without_lifetimes() -> Result<()> {
+        let ir = ir_from_cc(&format!(
+            r#"{STD_STRING_CC}
+            struct S final {{
+                const std::string& name() const;
+            }};"#
+        ))?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl<'b> ::ctor::Assign<&'b Self> for SomeStruct {
-                    #[inline(always)]
-                    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, other: &'b Self) {
-                        unsafe {
-                            crate::detail::__rust_thunk___ZN10SomeStructaSERKS_(self, other);
-                        }
+                impl S {
+                    ...
+                    pub unsafe fn name_bytes(__this: *const Self) -> *const [u8] {
+                        let mut __size = 0usize;
+                        let __data = crate::detail::__rust_thunk___ZNK1S4nameEv__bytes(
+                            Self::name(__this), &mut __size);
+                        ::core::ptr::slice_from_raw_parts(__data, __size)
+                    }
+                    ...
+                    pub unsafe fn name_to_owned(__this: *const Self) -> ::std::vec::Vec<u8> {
+                        (*Self::name_bytes(__this)).to_vec()
                     }
                 }
             }
@@ -2774,15 +7591,47 @@ mod tests {
     }
 
     #[test]
-    fn test_impl_eq_non_const_member_function() -> Result<()> {
-        let ir = ir_from_cc(
+    fn test_string_accessors_only_for_const_getters() -> Result<()> {
+        let ir = ir_from_cc(&format!(
             r#"#pragma clang lifetime_elision
-            struct SomeStruct final {
-                bool operator==(const SomeStruct& other) /* no `const` here */;
-            };"#,
-        )?;
+            {STD_STRING_CC}
+            struct S final {{
+                const std::string& Get(int i) const;
+                std::string& Mutable();
+                const std::string& operator*() const;
+            }};
+            const std::string& Global();"#
+        ))?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
-        assert_rs_not_matches!(rs_api, quote! {impl PartialEq});
+        assert_rs_matches!(rs_api, quote! { pub fn Get });
+        assert_rs_not_matches!(rs_api, quote! { Get_bytes });
+        assert_rs_not_matches!(rs_api, quote! { Mutable_bytes });
+        assert_rs_not_matches!(rs_api, quote! { get_bytes });
+        assert_rs_not_matches!(rs_api, quote! { Global_bytes });
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_accessors_colliding_with_method() -> Result<()> {
+        let ir = ir_from_cc(&format!(
+            r#"#pragma clang lifetime_elision
+            {STD_STRING_CC}
+            struct S final {{
+                const std::string& name() const;
+                int name_bytes() const;
+            }};"#
+        ))?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn name_bytes<'a>(&'a self) -> ::core::ffi::c_int }
+        );
+        assert_rs_not_matches!(rs_api, quote! { name_to_owned });
+        assert_cc_matches!(rs_api, {
+            let txt = "The accessors of the string returned by `name` are not generated, because \
+                       `name_bytes` collides with a method of `S`";
+            quote! { __COMMENT__ #txt }
+        });
         Ok(())
     }
 
@@ -2860,25 +7709,313 @@ mod tests {
 
     #[test]
     fn test_thunk_ident_function() -> Result<()> {
-        let ir = ir_from_cc("inline int foo() {}")?;
+        let db = db_from_cc("inline int foo() {}")?;
+        let ir = db.ir();
         let func = retrieve_func(&ir, "foo");
-        assert_eq!(thunk_ident(func), make_rs_ident("__rust_thunk___Z3foov"));
+        assert_eq!(thunk_ident(&db, func), make_rs_ident("__rust_thunk___Z3foov"));
         Ok(())
     }
 
     #[test]
     fn test_thunk_ident_special_names() {
-        let ir = ir_from_cc("struct Class {};").unwrap();
+        let db = db_from_cc("struct Class {};").unwrap();
+        let ir = db.ir();
 
         let destructor =
             ir.get_functions_by_name(&UnqualifiedIdentifier::Destructor).next().unwrap();
-        assert_eq!(thunk_ident(destructor), make_rs_ident("__rust_thunk___ZN5ClassD1Ev"));
+        assert_eq!(thunk_ident(&db, destructor), make_rs_ident("__rust_thunk___ZN5ClassD1Ev"));
 
         let default_constructor = ir
             .get_functions_by_name(&UnqualifiedIdentifier::Constructor)
             .find(|f| f.params.len() == 1)
             .unwrap();
-        assert_eq!(thunk_ident(default_constructor), make_rs_ident("__rust_thunk___ZN5ClassC1Ev"));
+        assert_eq!(
+            thunk_ident(&db, default_constructor),
+            make_rs_ident("__rust_thunk___ZN5ClassC1Ev")
+        );
+    }
+
+    #[test]
+    fn test_thunk_ident_hashed() -> Result<()> {
+        let ir = ir_from_cc("inline int foo() {}")?;
+        let db = Database::new(
+            Rc::new(ir),
+            Rc::new(IgnoreErrors),
+            SourceLocationDocComment::Enabled,
+            Rc::new(GeneratorOptions { hash_thunk_names: true, ..Default::default() }),
+        );
+        let ir = db.ir();
+        let func = retrieve_func(&ir, "foo");
+        let target_hash = fnv1a_32(func.owning_target.0.as_bytes());
+        assert_eq!(
+            thunk_ident(&db, func),
+            make_rs_ident(&format!("__rust_thunk_{target_hash:08x}___Z3foov"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_thunk_ident_stable() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            [[clang::annotate("crubit_stable_thunk", "mylib_foo_v1")]]
+            inline int foo() {}"#,
+        )?;
+        let db = Database::new(
+            Rc::new(ir),
+            Rc::new(IgnoreErrors),
+            SourceLocationDocComment::Enabled,
+            Rc::new(GeneratorOptions { hash_thunk_names: true, ..Default::default() }),
+        );
+        let ir = db.ir();
+        // The pinned name is used as-is, even when thunk names are hashed.
+        assert_eq!(thunk_ident(&db, retrieve_func(&ir, "foo")), make_rs_ident("mylib_foo_v1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fnv1a_32() {
+        assert_eq!(fnv1a_32(b""), 0x811c9dc5);
+        assert_eq!(fnv1a_32(b"a"), 0xe40c292c);
+        assert_eq!(fnv1a_32(b"foobar"), 0xbf9cf968);
+    }
+
+    #[test]
+    fn test_virtual_method() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct Shape {
+              virtual ~Shape();
+              // Doc comment
+              virtual int Area() const;
+            };"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[doc = " Doc comment\n \n This method is virtual: the call is dispatched to the implementation for the\n dynamic type of the object, which may be a class derived from `Shape`." ...]
+                #[inline(always)]
+                pub fn Area<'a>(&'a self) -> ::core::ffi::c_int {
+                    unsafe { crate::detail::__rust_thunk___ZNK5Shape4AreaEv(self) }
+                }
+            }
+        );
+        // The thunk calls the method without qualifying it, so that the call is
+        // dispatched through the vtable.
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___ZNK5Shape4AreaEv(const struct Shape* __this) {
+                    return __this->Area();
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_final_virtual_method_is_called_directly() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct Shape {
+              virtual ~Shape();
+              virtual int Area() const;
+            };
+            struct Square final : Shape {
+              int Area() const override;
+            };"#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[link_name = "_ZNK6Square4AreaEv"]
+                pub(crate) fn __rust_thunk___ZNK6Square4AreaEv<'a>(__this: &'a crate::Square) -> ::core::ffi::c_int;
+            }
+        );
+        assert_cc_not_matches!(rs_api_impl, quote! { __rust_thunk___ZNK6Square4AreaEv });
+        Ok(())
+    }
+
+    #[test]
+    fn test_pure_virtual_method_of_final_class() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct Shape final {
+              virtual int Area() const = 0;
+            };"#,
+        )?;
+
+        // A pure virtual method may not be defined, so it is called through the thunk.
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___ZNK5Shape4AreaEv(const struct Shape* __this) {
+                    return __this->Area();
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_thunk_impl_line_directives() -> Result<()> {
+        let ir = ir_from_cc("inline int foo() { return 42; }")?;
+        let db = Database::new(
+            Rc::new(ir),
+            Rc::new(IgnoreErrors),
+            SourceLocationDocComment::Enabled,
+            Rc::new(GeneratorOptions {
+                rs_api_impl_path: "rs_api_impl.cc".into(),
+                ..Default::default()
+            }),
+        );
+        let ir = db.ir();
+        let thunk_impl = generate_func_thunk_impl(&db, retrieve_func(&ir, "foo"))?;
+        assert_cc_matches!(
+            thunk_impl,
+            quote! {
+                __HASH_TOKEN__ line 3 "ir_from_cc_virtual_header.h"
+                extern "C" int __rust_thunk___Z3foov() { return foo(); }
+                __HASH_TOKEN__ line __CRUBIT_GENERATED_LINE__
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_thunk_impl_line_directives_by_default() -> Result<()> {
+        let ir = ir_from_cc("inline int foo() { return 42; }")?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_matches!(rs_api_impl, quote! { extern "C" int __rust_thunk___Z3foov() });
+        assert_cc_not_matches!(rs_api_impl, quote! { __HASH_TOKEN__ line });
+        Ok(())
+    }
+
+    #[test]
+    fn test_thunk_exception_note() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            inline int MayThrow() { return 42; }
+            inline int NoThrow() noexcept { return 42; }"#,
+        )?;
+        let rs_api_impl = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions {
+                thunk_exception_note: "C++ exception thrown by `{function}`".into(),
+                ..Default::default()
+            },
+        )?
+        .rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                __HASH_TOKEN__ include "crubit/rs_bindings_support/internal/exceptions.h"
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___Z8MayThrowv() {
+                    try {
+                        return MayThrow();
+                    } catch (...) {
+                        crubit::TerminateWithNote("C++ exception thrown by `MayThrow`");
+                    }
+                }
+            }
+        );
+        // `noexcept` functions can't throw, so there is nothing to catch.
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk___Z7NoThrowv() {
+                    return NoThrow();
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_thunk_exception_note_requires_thunk() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            extern "C" int MayThrow();
+            extern "C" int NoThrow() noexcept;"#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions {
+                thunk_exception_note: "C++ exception thrown by `{function}`".into(),
+                ..Default::default()
+            },
+        )?;
+        // `MayThrow` could be called directly, but its exceptions have to be caught by a thunk.
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __rust_thunk__MayThrow() {
+                    try {
+                        return MayThrow();
+                    } catch (...) {
+                        crubit::TerminateWithNote("C++ exception thrown by `MayThrow`");
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(rs_api, quote! { crate::detail::__rust_thunk__MayThrow() });
+        assert_cc_not_matches!(rs_api_impl, quote! { __rust_thunk__NoThrow });
+        assert_rs_matches!(rs_api, quote! { #[link_name = "NoThrow"] });
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_thunk_exception_handling_by_default() -> Result<()> {
+        let ir = ir_from_cc("inline int MayThrow() { return 42; }")?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_not_matches!(rs_api_impl, quote! { try });
+        assert_cc_not_matches!(rs_api_impl, quote! { TerminateWithNote });
+        Ok(())
+    }
+
+    #[test]
+    fn test_cold_and_noinline_functions() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            [[gnu::cold]] void Cold();
+            [[gnu::noinline]] void NoInline();
+            [[gnu::cold, gnu::noinline]] void ColdNoInline();
+            inline void Inline() {}"#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { #[cold] pub fn Cold() });
+        assert_rs_not_matches!(rs_api, quote! { #[inline(always)] #[cold] });
+        assert_rs_matches!(rs_api, quote! { #[inline(never)] pub fn NoInline() });
+        assert_rs_matches!(rs_api, quote! { #[cold] #[inline(never)] pub fn ColdNoInline() });
+        // The API functions only call the thunks, so they are inlined by default.
+        assert_rs_matches!(rs_api, quote! { #[inline(always)] pub fn Inline() });
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_source_loc() {
+        assert_eq!(
+            parse_source_loc("Generated from: google3/foo/bar.h;l=12"),
+            Some(("foo/bar.h", 12))
+        );
+        assert_eq!(
+            parse_source_loc(
+                "Generated from: google3/foo/macros.h;l=3\nExpanded at: google3/foo/bar.h;l=12"
+            ),
+            Some(("foo/macros.h", 3))
+        );
+        assert_eq!(parse_source_loc("<unknown location>"), None);
+        assert_eq!(parse_source_loc("Generated from: google3/foo/bar.h;l=x"), None);
     }
 
     #[test]
@@ -2906,6 +8043,60 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_lifetimebound_param() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+          int& f(int& i1 [[clang::lifetimebound]], int& i2);
+          "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn f<'a, 'b>(i1: &'a mut ::core::ffi::c_int, i2: &'b mut ::core::ffi::c_int)
+                    -> &'a mut ::core::ffi::c_int { ... }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lifetimebound_this() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+          struct S final {
+            int& f(int& i) [[clang::lifetimebound]];
+          };"#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn f<'a, 'b>(&'a mut self, i: &'b mut ::core::ffi::c_int) -> &'a mut ::core::ffi::c_int { ... }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lifetimebound_multiple_params_uses_raw_pointers() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+          int& f(int& i1 [[clang::lifetimebound]], int& i2 [[clang::lifetimebound]]);
+          "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub unsafe fn f(i1: *mut ::core::ffi::c_int, i2: *mut ::core::ffi::c_int)
+                    -> *mut ::core::ffi::c_int { ... }
+            }
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_annotated_lifetimes() -> Result<()> {
         let ir = ir_from_cc(&with_lifetime_macros(
@@ -2964,9 +8155,9 @@ mod tests {
 
     #[test]
     fn test_overloaded_functions() -> Result<()> {
-        // TODO(b/213280424): We don't support creating bindings for overloaded
-        // functions yet, except in the case of overloaded constructors with a
-        // single parameter.
+        // By default, overloaded functions don't get bindings (see
+        // `OverloadNaming`), except in the case of overloaded constructors with
+        // a single parameter.
         let ir = ir_from_cc(
             r#" #pragma clang lifetime_elision
                 void f() {}
@@ -2987,7 +8178,7 @@ mod tests {
                 namespace bar { void not_overloaded(); }
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
 
         // Cannot overload free functions.
         assert_cc_matches!(rs_api, {
@@ -3024,6 +8215,138 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_overloaded_functions_named_after_param_types() -> Result<()> {
+        let ir = ir_from_cc(
+            r#" #pragma clang lifetime_elision
+                void f() {}
+                void f(int i) {}
+                void f(int i, const char* s) {}
+                struct S final {
+                  void g(int i) {}
+                  void g(double d) {}
+                };
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { overload_naming: OverloadNaming::ParamTypes, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(rs_api, quote! { pub fn f() });
+        assert_rs_matches!(rs_api, quote! { pub fn f_c_int(i: ::core::ffi::c_int) });
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub unsafe fn f_c_int_ptr_const_c_char(i: ::core::ffi::c_int, s: *const ::core::ffi::c_char)
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn g_c_int<'a>(&'a mut self, i: ::core::ffi::c_int) }
+        );
+        assert_rs_matches!(rs_api, quote! { pub fn g_f64<'a>(&'a mut self, d: f64) });
+        assert_rs_not_matches!(rs_api, quote! { Cannot generate bindings for overloaded function });
+        Ok(())
+    }
+
+    #[test]
+    fn test_overloaded_functions_named_by_ordinal() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+                void f() {}
+                void f(int i) {}
+                void f(double d) {}
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { overload_naming: OverloadNaming::Ordinal, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(rs_api, quote! { pub fn f() });
+        assert_rs_matches!(rs_api, quote! { pub fn f1(i: ::core::ffi::c_int) });
+        assert_rs_matches!(rs_api, quote! { pub fn f2(d: f64) });
+        Ok(())
+    }
+
+    #[test]
+    fn test_overloaded_functions_with_colliding_names() -> Result<()> {
+        // `f1` is already taken by another function, so the overloads of `f` can't
+        // be named by ordinal.
+        let ir = ir_from_cc(
+            r#"
+                void f() {}
+                void f(int i) {}
+                void f1() {}
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { overload_naming: OverloadNaming::Ordinal, ..Default::default() },
+        )?
+        .rs_api;
+        assert_cc_matches!(rs_api, {
+            let txt = "Generated from: google3/ir_from_cc_virtual_header.h;l=3\n\
+                           Error while generating bindings for item 'f':\n\
+                           Cannot generate bindings for overloaded function, because its \
+                           overloads can't be given distinct names";
+            quote! { __COMMENT__ #txt }
+        });
+        assert_rs_matches!(rs_api, quote! { pub fn f1() });
+        Ok(())
+    }
+
+    #[test]
+    fn test_overloaded_functions_with_names_colliding_across_overload_sets() -> Result<()> {
+        // The overloads of `f` and of `f_S` would both be named `f_S_T`.
+        let ir = ir_from_cc(
+            r#"
+                struct T final {};
+                struct S_T final {};
+                void f() {}
+                void f(S_T s) {}
+                void f_S() {}
+                void f_S(T t) {}
+                void g() {}
+                void g(T t) {}
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { overload_naming: OverloadNaming::ParamTypes, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_not_matches!(rs_api, quote! { pub fn f_S_T });
+        assert_rs_not_matches!(rs_api, quote! { pub fn f() });
+        assert_rs_not_matches!(rs_api, quote! { pub fn f_S() });
+        assert_rs_matches!(rs_api, quote! { pub fn g_T(t: crate::T) });
+        Ok(())
+    }
+
+    #[test]
+    fn test_type_name_words() {
+        let format = |tokens: TokenStream| {
+            let mut words = vec![];
+            type_name_words(tokens, &mut words);
+            words.join("_")
+        };
+        assert_eq!(format(quote! { ::core::ffi::c_int }), "c_int");
+        assert_eq!(format(quote! { *const ::core::ffi::c_char }), "ptr_const_c_char");
+        assert_eq!(format(quote! { &'a mut crate::ns::S }), "ref_mut_S");
+        assert_eq!(format(quote! { ::core::pin::Pin<&'a mut S> }), "Pin_ref_mut_S");
+        assert_eq!(format(quote! { [f64; 4] }), "f64_4");
+    }
+
+    #[test]
+    fn test_overload_naming_from_str() -> Result<()> {
+        assert_eq!("none".parse::<OverloadNaming>()?, OverloadNaming::None);
+        assert_eq!("param_types".parse::<OverloadNaming>()?, OverloadNaming::ParamTypes);
+        assert_eq!("ordinal".parse::<OverloadNaming>()?, OverloadNaming::Ordinal);
+        assert!("mangled".parse::<OverloadNaming>().is_err());
+        Ok(())
+    }
+
     /// !Unpin references should not be pinned.
     #[test]
     fn test_nonunpin_ref_param() -> Result<()> {
@@ -3289,7 +8612,7 @@ mod tests {
             Nontrivial ReturnsByValue(const int& x, const int& y);
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -3329,7 +8652,7 @@ mod tests {
             const Nontrivial ReturnsByValue(const int& x, const int& y);
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -3370,7 +8693,7 @@ mod tests {
             void foo(Trivial param);
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -3408,7 +8731,7 @@ mod tests {
             Trivial foo();
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -3518,7 +8841,7 @@ mod tests {
             };
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -3568,7 +8891,7 @@ mod tests {
             void TakesByValue(Nontrivial x);
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -3641,7 +8964,7 @@ mod tests {
             void TakesByValue(Nonmovable) {}
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         // Bindings for TakesByValue cannot be generated.
         assert_rs_not_matches!(rs_api, quote! {TakesByValue});
         assert_cc_not_matches!(rs_api_impl, quote! {TakesByValue});
@@ -3661,7 +8984,7 @@ mod tests {
             };
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -3,22 +3,30 @@
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 #![allow(clippy::collapsible_else_if)]
 
-use crate::{BindingsGenerator, Database, GeneratedItem};
+use crate::attribute_translation::{translate_attrs, untranslated_attrs};
+use crate::generate_func::has_method_named;
+use crate::{generate_thunk_decl, BindingsGenerator, Database, GeneratedItem, LayoutCheck};
 
-use crate::rs_snippet::{should_derive_clone, should_derive_copy, RsTypeKind};
+use crate::rs_snippet::{
+    should_derive_clone, should_derive_copy, Mutability, PrimitiveType, RsTypeKind,
+};
 use arc_anyhow::{Context, Result};
 use code_gen_utils::make_rs_ident;
-use error_report::{bail, ensure};
+use error_report::{anyhow, bail, ensure};
 use ir::*;
 use itertools::Itertools;
 use proc_macro2::{Ident, Literal, TokenStream};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use std::collections::BTreeSet;
 use std::iter;
 use std::rc::Rc;
 
 // TODO(jeanpierreda): Make this a method on RsTypeKind, or on Record?
 pub(crate) fn should_implement_drop(record: &Record) -> bool {
+    if record.is_opaque {
+        // Objects of opaque types are never owned by Rust.
+        return false;
+    }
     match record.destructor {
         // TODO(b/202258760): Only omit destructor if `Copy` is specified.
         SpecialMemberFunc::Trivial => false,
@@ -109,7 +117,7 @@ fn get_field_rs_type_kind_for_layout(
     if field.is_no_unique_address {
         bail!("`[[no_unique_address]]` attribute was present.");
     }
-    if let Some(unknown_attr) = &field.unknown_attr {
+    if let Some(unknown_attr) = untranslated_attrs(db, field.unknown_attr.as_deref()) {
         // Both the template definition and its instantiation should enable experimental
         // features.
         for target in record.defining_target.iter().chain([&record.owning_target]) {
@@ -125,6 +133,35 @@ fn get_field_rs_type_kind_for_layout(
         Ok(t) => db.rs_type_kind(t.rs_type.clone())?,
         Err(e) => bail!("{e}"),
     };
+    if let Some(bridge_type) = type_kind.as_bridge_type() {
+        bail!(
+            "{} is converted into {type_kind}, and can only be passed by value",
+            bridge_type.cc_name()
+        );
+    }
+    if type_kind.as_std_optional().is_some() {
+        bail!("std::optional is converted into {type_kind}, and can only be passed by value");
+    }
+    if type_kind.as_initializer_list().is_some() {
+        bail!(
+            "std::initializer_list is converted into {type_kind}, and can only be passed by value"
+        );
+    }
+    if let RsTypeKind::Record { record: field_record, .. } = type_kind.unalias() {
+        if field_record.is_opaque {
+            bail!("`{}` is opaque, and can only be used behind pointers", field_record.cc_name);
+        }
+        // The Rust struct of a `!Unpin` record (e.g. one whose tail padding a derived
+        // class may reuse) is neither `Unpin` nor `Copy`, so it can't be the type of a
+        // field of an `Unpin` record.
+        if record.is_unpin() && !field_record.is_unpin() {
+            bail!(
+                "`{}` is not `Unpin`, and can't be a field of the `Unpin` `{}`",
+                field_record.cc_name,
+                record.cc_name
+            );
+        }
+    }
     // In supported, we replace nontrivial fields with opaque blobs.
     // This is because we likely don't want the `ManuallyDrop<T>` solution to be the
     // one users get.
@@ -142,6 +179,20 @@ fn get_field_rs_type_kind_for_layout(
     Ok(type_kind)
 }
 
+/// Returns the doc comment of `field`, followed by the value of its default
+/// member initializer (if any).
+fn field_doc_comment(field: &Field) -> Option<String> {
+    let default_value = field.default_value.as_ref().map(|value| format!("Default: `{value}`"));
+    match (&field.doc_comment, default_value) {
+        (Some(doc_comment), Some(default_value)) => {
+            Some(format!("{doc_comment}\n\n{default_value}"))
+        }
+        (doc_comment, default_value) => {
+            doc_comment.as_deref().map(str::to_string).or(default_value)
+        }
+    }
+}
+
 /// Returns the type of a type-less, unaligned block of memory that can hold a
 /// specified number of bits, rounded up to the next multiple of 8.
 fn bit_padding(padding_size_in_bits: usize) -> TokenStream {
@@ -160,13 +211,16 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
         quote! { #crate_root_path:: #namespace_qualifier #ident }
     };
     let doc_comment = crate::generate_doc_comment(
-        record.doc_comment.as_deref(),
+        record_doc_comment(record).as_deref(),
         Some(&record.source_loc),
         db.generate_source_loc_doc_comment(),
     );
     let mut field_copy_trait_assertions: Vec<TokenStream> = vec![];
+    let mut array_field_size_assertions: Vec<TokenStream> = vec![];
 
-    let fields_with_bounds = (record.fields.iter())
+    // The fields of opaque records aren't exposed to Rust (see `head_padding` below).
+    let fields: &[Field] = if record.is_opaque { &[] } else { &record.fields };
+    let fields_with_bounds = (fields.iter())
         .filter(|field| field.size != 0)
         .map(|field| {
             (
@@ -279,9 +333,10 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
 
             let ident = make_rs_field_ident(field, field_index);
             let field_rs_type_kind = get_field_rs_type_kind_for_layout(db, record, field);
+            let field_doc_comment = field_doc_comment(field);
             let doc_comment = match &field_rs_type_kind {
                 Ok(_) => crate::generate_doc_comment(
-                    field.doc_comment.as_deref(),
+                    field_doc_comment.as_deref(),
                     None,
                     db.generate_source_loc_doc_comment(),
                 ),
@@ -291,9 +346,9 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
                         "Reason for representing this field as a blob of bytes:\n{:#}",
                         msg
                     );
-                    let new_text = match &field.doc_comment {
+                    let new_text = match &field_doc_comment {
                         None => supplemental_text,
-                        Some(old_text) => format!("{}\n\n{}", old_text.as_ref(), supplemental_text),
+                        Some(old_text) => format!("{}\n\n{}", old_text, supplemental_text),
                     };
                     crate::generate_doc_comment(
                         Some(new_text.as_str()),
@@ -312,6 +367,15 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
                 Err(_) => bit_padding(end - field.offset),
                 Ok(type_kind) => {
                     let mut formatted = quote! {#type_kind};
+                    if let RsTypeKind::Array { .. } = type_kind.unalias() {
+                        // The offset assertions don't cover the end of the last field (or of a
+                        // field followed by padding), so the size of arrays (which depends on
+                        // both the element type and the length) is asserted separately.
+                        let size = Literal::usize_unsuffixed(field.size / 8);
+                        array_field_size_assertions.push(quote! {
+                            assert!(::core::mem::size_of::<#formatted>() == #size);
+                        });
+                    }
                     if should_implement_drop(record) || record.is_union() {
                         if needs_manually_drop(&type_kind) {
                             // TODO(b/212690698): Avoid (somewhat unergonomic) ManuallyDrop
@@ -333,27 +397,18 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
         })
         .collect::<Result<Vec<_>>>()?;
 
-    let field_offset_assertions = fields_with_bounds
+    let field_offsets = fields_with_bounds
         .enumerate()
-        .map(|(field_index, (field, _, _, _))| {
-            if let Some(field) = field {
-                let field_ident = make_rs_field_ident(field, field_index);
+        .filter_map(|(field_index, (field, _, _, _))| {
+            let field = field?;
+            let field_ident = make_rs_field_ident(field, field_index);
 
-                // The assertion below reinforces that the division by 8 on the next line is
-                // justified (because the bitfields have been coallesced / filtered out
-                // earlier).
-                assert_eq!(field.offset % 8, 0);
-                let expected_offset = Literal::usize_unsuffixed(field.offset / 8);
-
-                let actual_offset_expr = quote! {
-                    ::core::mem::offset_of!(#qualified_ident, #field_ident)
-                };
-                quote! {
-                    assert!(#actual_offset_expr == #expected_offset);
-                }
-            } else {
-                quote! {}
-            }
+            // The assertion below reinforces that the division by 8 on the next line is
+            // justified (because the bitfields have been coallesced / filtered out
+            // earlier).
+            assert_eq!(field.offset % 8, 0);
+            let expected_offset = Literal::usize_unsuffixed(field.offset / 8);
+            Some((field_ident, expected_offset))
         })
         .collect_vec();
     let mut features = BTreeSet::new();
@@ -370,7 +425,7 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
         quote! { struct }
     };
 
-    let recursively_pinned_attribute = if record.is_unpin() {
+    let recursively_pinned_attribute = if record.is_unpin() || record.is_opaque {
         quote! {}
     } else {
         // negative_impls are necessary for universal initialization due to Rust's
@@ -385,7 +440,7 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
     };
 
     let mut repr_attributes = vec![quote! {C}];
-    if override_alignment && record.size_align.alignment > 1 {
+    if override_alignment && !record.is_opaque && record.size_align.alignment > 1 {
         let alignment = Literal::usize_unsuffixed(record.size_align.alignment);
         repr_attributes.push(quote! {align(#alignment)});
     }
@@ -412,7 +467,15 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
     //
     // TODO(b/232969667): Protect unions from direct initialization, too.
     let allow_direct_init = record.is_aggregate || record.is_union();
-    let head_padding = if head_padding > 0 || !allow_direct_init {
+    let head_padding = if record.is_opaque {
+        // Opaque records are only ever used behind pointers, so their size and alignment are
+        // unknown in Rust. The marker makes the type `!Send`, `!Sync` and `!Unpin`. See
+        // https://doc.rust-lang.org/nomicon/ffi.html#representing-opaque-structs
+        quote! {
+            _opaque: [u8; 0],
+            _marker: ::core::marker::PhantomData<(*mut u8, ::core::marker::PhantomPinned)>,
+        }
+    } else if head_padding > 0 || !allow_direct_init {
         let n = proc_macro2::Literal::usize_unsuffixed(head_padding);
         quote! {
             __non_field_data: [::core::mem::MaybeUninit<u8>; #n],
@@ -423,16 +486,19 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
 
     let fully_qualified_cc_name = crate::cc_tagless_type_name_for_record(record, &ir)?.to_string();
 
-    let mut record_generated_items = record
-        .child_item_ids
-        .iter()
-        .map(|id| {
-            let item = ir.find_decl(*id).with_context(|| {
-                format!("Failed to look up `record.child_item_ids` for {:?}", record)
-            })?;
-            crate::generate_item(db, item)
-        })
-        .collect::<Result<Vec<_>>>()?;
+    // The types nested inside the record go into a module named after it, unless they
+    // can't have bindings (see `has_bindings`).
+    let nested_types_module_name = record.nested_items_module_name();
+    let has_nested_types_module =
+        record.defining_target.is_none() && nested_types_module_name != record.rs_name;
+    let mut record_generated_items = vec![];
+    for id in &record.child_item_ids {
+        let item: &Item = ir.find_decl(*id).with_context(|| {
+            format!("Failed to look up `record.child_item_ids` for {:?}", record)
+        })?;
+        let is_nested_type = has_nested_types_module && item.is_type_definition();
+        record_generated_items.push((is_nested_type, crate::generate_item(db, item)?));
+    }
 
     // Both the template definition and its instantiation should enable experimental
     // features.
@@ -440,11 +506,52 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
     if let Some(defining_target) = &record.defining_target {
         crubit_features |= ir.target_crubit_features(defining_target);
     }
+    record_generated_items.push((false, cc_struct_default_impl(db, record, &ir)?));
     if crubit_features.contains(ir::CrubitFeature::Experimental) {
-        record_generated_items.push(cc_struct_upcast_impl(record, &ir)?);
+        record_generated_items.push((false, cc_struct_upcast_impl(db, record, &ir)?));
+    }
+    let no_unique_address_accessors =
+        if crubit_features.contains(ir::CrubitFeature::Experimental) && !record.is_opaque {
+            cc_struct_no_unique_address_impl(db, record)?
+        } else {
+            quote! {}
+        };
+    let awaitable_impl = match &record.awaitable {
+        Some(awaitable) if crubit_features.contains(ir::CrubitFeature::Experimental) => {
+            cc_struct_awaitable_impl(db, record, awaitable)?
+        }
+        _ => quote! {},
+    };
+    if !record.is_opaque {
+        record_generated_items.push((false, cc_struct_bitfield_accessors_impl(db, record)?));
+        let anonymous_member_accessors = cc_struct_anonymous_member_accessors_impl(db, record)?;
+        record_generated_items.push((false, anonymous_member_accessors));
+    }
+    if let Some(map_visitor) = &record.map_visitor {
+        if crubit_features.contains(ir::CrubitFeature::Experimental) {
+            let map_visitor_impl = cc_struct_map_visitor_impl(db, record, map_visitor)?;
+            record_generated_items.push((false, map_visitor_impl));
+        }
+    }
+    if let Some(element_type) = &record.std_vector_element_type {
+        record_generated_items.push((false, cc_struct_std_vector_impl(db, record, element_type)?));
     }
-    let no_unique_address_accessors = if crubit_features.contains(ir::CrubitFeature::Experimental) {
-        cc_struct_no_unique_address_impl(db, record)?
+    let extra_rust_impl = match &record.extra_rust_impl {
+        Some(code) => code.parse::<TokenStream>().map_err(|err| {
+            anyhow!(
+                "Failed to parse the `crubit_extra_rust_impl` file of `{}`: {err}",
+                record.cc_name
+            )
+        })?,
+        None => quote! {},
+    };
+    let bytemuck_impls = if db.options().generate_bytemuck_impls && is_record_pod(db, record) {
+        quote! {
+            // SAFETY: `is_record_pod` has checked that all bit patterns are valid, and
+            // that there is no padding.
+            unsafe impl ::bytemuck::Zeroable for #ident {}
+            unsafe impl ::bytemuck::Pod for #ident {}
+        }
     } else {
         quote! {}
     };
@@ -456,27 +563,76 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
         quote! {}
     };
 
+    let is_final = !record.is_inheritable;
+
     let mut items = vec![];
     let mut thunks_from_record_items = vec![];
-    let mut thunk_impls_from_record_items = vec![cc_struct_layout_assertion(db, record)?];
+    let mut mock_methods_from_record_items = vec![];
+    let mut thunk_impls_from_record_items =
+        if record.is_opaque { vec![] } else { vec![cc_struct_layout_assertion(db, record)?] };
     let mut assertions_from_record_items = vec![];
+    let mut stable_thunks = vec![];
+    let mut nested_types = vec![];
+    let mut layout_checks_from_record_items = vec![];
+    let mut ext_trait_decls = quote! {};
+    let mut ext_trait_impls = quote! {};
+    // The layout assertions need the definition of the record.
+    let mut required_headers = crate::required_public_headers(&ir, [record.id]);
+    let mut std_array_types = if record.is_opaque {
+        vec![]
+    } else {
+        record
+            .fields
+            .iter()
+            .filter(|f| f.access == AccessSpecifier::Public)
+            .filter_map(|field| field.type_.as_ref().ok())
+            .flat_map(|type_| crate::cc_std_array_types(&type_.cc_type))
+            .collect_vec()
+    };
 
-    for generated in record_generated_items {
-        items.push(generated.item);
+    for (is_nested_type, generated) in record_generated_items {
+        if is_nested_type {
+            nested_types.push(generated.item);
+        } else {
+            items.push(generated.item);
+        }
+        layout_checks_from_record_items.extend(generated.layout_checks);
         if !generated.thunks.is_empty() {
             thunks_from_record_items.push(generated.thunks);
         }
+        if !generated.mock_methods.is_empty() {
+            mock_methods_from_record_items.push(generated.mock_methods);
+        }
         if !generated.assertions.is_empty() {
             assertions_from_record_items.push(generated.assertions);
         }
         if !generated.thunk_impls.is_empty() {
             thunk_impls_from_record_items.push(generated.thunk_impls);
         }
+        stable_thunks.extend(generated.stable_thunks);
+        required_headers.extend(generated.required_headers);
+        std_array_types.extend(generated.std_array_types);
         features.extend(generated.features.clone());
+        ext_trait_decls.extend(generated.ext_trait_decls);
+        ext_trait_impls.extend(generated.ext_trait_impls);
     }
+    let ext_trait = generate_record_ext_trait(db, record, ext_trait_decls, ext_trait_impls)?;
+
+    let nested_types_module = if nested_types.is_empty() {
+        quote! {}
+    } else {
+        let module_name = make_rs_ident(&nested_types_module_name);
+        quote! {
+            pub mod #module_name {
+                #( #nested_types __NEWLINE__ __NEWLINE__ )*
+            }
+        }
+    };
 
+    let attrs = translate_attrs(db, record.unknown_attr.as_deref());
     let record_tokens = quote! {
         #doc_comment
+        #attrs
         #derives
         #recursively_pinned_attribute
         #[repr(#( #repr_attributes ),*)]
@@ -489,12 +645,26 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
         impl !Send for #ident {}
         impl !Sync for #ident {}
 
+        impl oops::CcClass for #ident {
+            const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: #is_final };
+        }
+
         #incomplete_definition
 
         #no_unique_address_accessors
 
+        #awaitable_impl
+
+        #bytemuck_impls
+
         __NEWLINE__ __NEWLINE__
         #( #items __NEWLINE__ __NEWLINE__)*
+
+        #nested_types_module
+
+        #extra_rust_impl
+
+        #ext_trait
     };
     features.insert(make_rs_ident("negative_impls"));
     // For #![register_tool(__crubit)] / #![__crubit::...]
@@ -526,11 +696,31 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
         add_conditional_assertion(should_implement_drop(record), quote! { Drop });
         assertions
     };
-    let size_align_assertions = rs_size_align_assertions(qualified_ident, &record.size_align);
+    let (size_align_assertions, field_offset_assertions) = if record.is_opaque {
+        (quote! {}, vec![])
+    } else if db.options().compact_layout_assertions {
+        let assertions =
+            rs_compact_layout_assertions(&qualified_ident, &record.size_align, &field_offsets);
+        (assertions, vec![])
+    } else {
+        let field_offset_assertions = field_offsets
+            .iter()
+            .map(|(field_ident, expected_offset)| {
+                let actual_offset_expr = quote! {
+                    ::core::mem::offset_of!(#qualified_ident, #field_ident)
+                };
+                quote! {
+                    assert!(#actual_offset_expr == #expected_offset);
+                }
+            })
+            .collect_vec();
+        (rs_size_align_assertions(&qualified_ident, &record.size_align), field_offset_assertions)
+    };
     let assertion_tokens = quote! {
         #size_align_assertions
         #( #record_trait_assertions )*
         #( #field_offset_assertions )*
+        #( #array_field_size_assertions )*
         #( #field_copy_trait_assertions )*
         #( #assertions_from_record_items )*
     };
@@ -539,12 +729,24 @@ pub fn generate_record(db: &Database, record: &Rc<Record>) -> Result<GeneratedIt
         #( #thunks_from_record_items )*
     };
 
+    let mut layout_checks = if db.options().generate_layout_verification && !record.is_opaque {
+        record_layout_checks(db, record)?
+    } else {
+        vec![]
+    };
+    layout_checks.extend(layout_checks_from_record_items);
+
     Ok(GeneratedItem {
         item: record_tokens,
         features,
         assertions: assertion_tokens,
         thunks: thunk_tokens,
+        mock_methods: quote! { #( #mock_methods_from_record_items )* },
         thunk_impls: quote! {#(#thunk_impls_from_record_items __NEWLINE__ __NEWLINE__)*},
+        layout_checks,
+        stable_thunks,
+        required_headers,
+        std_array_types,
         ..Default::default()
     })
 }
@@ -562,6 +764,35 @@ pub fn rs_size_align_assertions(
     }
 }
 
+/// Like `rs_size_align_assertions` followed by an assertion of the offset of each of
+/// `field_offsets`, but as a single loop over an array of `(actual, expected)` pairs,
+/// which is much shorter for records with many fields.
+fn rs_compact_layout_assertions(
+    type_name: &TokenStream,
+    size_align: &ir::SizeAlign,
+    field_offsets: &[(Ident, Literal)],
+) -> TokenStream {
+    let size = Literal::usize_unsuffixed(size_align.size);
+    let alignment = Literal::usize_unsuffixed(size_align.alignment);
+    let field_idents = field_offsets.iter().map(|(field_ident, _)| field_ident);
+    let expected_offsets = field_offsets.iter().map(|(_, expected_offset)| expected_offset);
+    quote! {
+        const _: () = {
+            type T = #type_name;
+            let layout = [
+                (::core::mem::size_of::<T>(), #size),
+                (::core::mem::align_of::<T>(), #alignment),
+                #( (::core::mem::offset_of!(T, #field_idents), #expected_offsets), )*
+            ];
+            let mut i = 0;
+            while i < layout.len() {
+                assert!(layout[i].0 == layout[i].1);
+                i += 1;
+            }
+        };
+    }
+}
+
 fn generate_derives(record: &Record) -> Vec<Ident> {
     let mut derives = vec![];
     if should_derive_clone(record) {
@@ -577,6 +808,13 @@ fn cc_struct_layout_assertion(db: &Database, record: &Record) -> Result<TokenStr
     let record_ident = crate::format_cc_ident(record.cc_name.as_ref());
     let namespace_qualifier = db.ir().namespace_qualifier(record)?.format_for_cc()?;
     let tag_kind = crate::cc_tag_kind(record);
+    let compact = db.options().compact_layout_assertions;
+    // In compact mode, the type is only spelled once, by an alias.
+    let cc_type = if compact {
+        quote! { T }
+    } else {
+        quote! { #tag_kind #namespace_qualifier #record_ident }
+    };
     let field_assertions = record
         .fields
         .iter()
@@ -595,9 +833,7 @@ fn cc_struct_layout_assertion(db: &Database, record: &Record) -> Result<TokenStr
 
             let field_ident =
                 crate::format_cc_ident(&field.identifier.as_ref().unwrap().identifier);
-            let actual_offset = quote! {
-                CRUBIT_OFFSET_OF(#field_ident, #tag_kind #namespace_qualifier #record_ident)
-            };
+            let actual_offset = quote! { CRUBIT_OFFSET_OF(#field_ident, #cc_type) };
 
             quote! { static_assert( #actual_offset == #expected_offset); }
         });
@@ -610,13 +846,78 @@ fn cc_struct_layout_assertion(db: &Database, record: &Record) -> Result<TokenStr
     } else {
         quote! {CRUBIT_SIZEOF}
     };
-    Ok(quote! {
-        static_assert(#sizeof(#tag_kind #namespace_qualifier #record_ident) == #size);
-        static_assert(alignof(#tag_kind #namespace_qualifier #record_ident) == #alignment);
+    let assertions = quote! {
+        static_assert(#sizeof(#cc_type) == #size);
+        static_assert(alignof(#cc_type) == #alignment);
         #( #field_assertions )*
+    };
+    if !compact {
+        return Ok(assertions);
+    }
+    // The function is never called, it only scopes the alias.
+    let function_ident =
+        format_ident!("__crubit_layout_assertions_{}", record.mangled_cc_name.as_ref());
+    Ok(quote! {
+        [[maybe_unused]] static void #function_ident() {
+            using T = #tag_kind #namespace_qualifier #record_ident;
+            #assertions
+        }
     })
 }
 
+/// Returns the layout properties of `record` that `crubit_verify_layouts()`
+/// re-checks at runtime: the size, the alignment, and the offsets of the fields
+/// that are covered by the compile-time assertions on both the Rust and the C++
+/// side, along with the values that Clang computed for them.
+fn record_layout_checks(db: &Database, record: &Record) -> Result<Vec<LayoutCheck>> {
+    let namespace_qualifier = db.ir().namespace_qualifier(record)?;
+    let cc_name = namespace_qualifier
+        .namespaces
+        .iter()
+        .chain(namespace_qualifier.nested_records.iter().map(|(cc_name, _)| cc_name))
+        .map(|ns| &**ns)
+        .chain(iter::once(record.cc_name.as_ref()))
+        .join("::");
+    let record_ident = crate::format_cc_ident(record.cc_name.as_ref());
+    let tag_kind = crate::cc_tag_kind(record);
+    let namespace_qualifier = namespace_qualifier.format_for_cc()?;
+    let cc_type = quote! { #tag_kind #namespace_qualifier #record_ident };
+    // Same as in `cc_struct_layout_assertion`.
+    let sizeof = if record.size_align.alignment == 1 {
+        quote! {sizeof}
+    } else {
+        quote! {CRUBIT_SIZEOF}
+    };
+
+    let mut checks = vec![
+        LayoutCheck {
+            description: format!("size of {cc_name}"),
+            expected_value: record.size_align.size,
+            cc_value: quote! { #sizeof(#cc_type) },
+        },
+        LayoutCheck {
+            description: format!("alignment of {cc_name}"),
+            expected_value: record.size_align.alignment,
+            cc_value: quote! { alignof(#cc_type) },
+        },
+    ];
+    for field in &record.fields {
+        let Some(Identifier { identifier }) = field.identifier.as_ref() else {
+            continue;
+        };
+        if field.access != AccessSpecifier::Public || field.is_bitfield || field.size == 0 {
+            continue;
+        }
+        let cc_field_ident = crate::format_cc_ident(identifier);
+        checks.push(LayoutCheck {
+            description: format!("offset of {cc_name}::{identifier}"),
+            expected_value: field.offset / 8,
+            cc_value: quote! { CRUBIT_OFFSET_OF(#cc_field_ident, #cc_type) },
+        });
+    }
+    Ok(checks)
+}
+
 /// Returns the accessor functions for no_unique_address member variables.
 fn cc_struct_no_unique_address_impl(db: &Database, record: &Record) -> Result<TokenStream> {
     let mut fields = vec![];
@@ -651,7 +952,7 @@ fn cc_struct_no_unique_address_impl(db: &Database, record: &Record) -> Result<To
             if field.size == 0 {
                 // These fields are not generated at all, so they need to be documented here.
                 doc_comments.push(crate::generate_doc_comment(
-                    field.doc_comment.as_deref(),
+                    field_doc_comment(field).as_deref(),
                     None,
                     db.generate_source_loc_doc_comment(),
                 ));
@@ -700,9 +1001,14 @@ fn cc_struct_no_unique_address_impl(db: &Database, record: &Record) -> Result<To
 
 /// Returns the implementation of base class conversions, for converting a type
 /// to its unambiguous public base classes.
-fn cc_struct_upcast_impl(record: &Rc<Record>, ir: &IR) -> Result<GeneratedItem> {
+fn cc_struct_upcast_impl(
+    db: &Database,
+    record: &Rc<Record>,
+    ir: &IR,
+) -> Result<GeneratedItem> {
     let mut impls = Vec::with_capacity(record.unambiguous_public_bases.len());
     let mut thunks = vec![];
+    let mut mock_methods = vec![];
     let mut cc_impls = vec![];
     for base in &record.unambiguous_public_bases {
         let base_record: &Rc<Record> = ir
@@ -728,12 +1034,20 @@ fn cc_struct_upcast_impl(record: &Rc<Record>, ir: &IR) -> Result<GeneratedItem>
                     return from;
                 }
             });
-            thunks.push(quote! {
-                pub fn #cast_fn_name (from: *const #derived_name) -> *const #base_name;
-            });
-            let crate_root_path = crate::crate_root_path_tokens(ir);
+            let thunk = generate_thunk_decl(
+                db,
+                quote! {},
+                quote! { pub },
+                &cast_fn_name,
+                quote! {},
+                &[(make_rs_ident("from"), quote! { *const #derived_name })],
+                quote! { -> *const #base_name },
+            );
+            thunks.push(thunk.thunks);
+            mock_methods.push(thunk.mock_methods);
+            let detail_module_path = crate::detail_module_path_tokens(db);
             body = quote! {
-                #crate_root_path::detail::#cast_fn_name(derived)
+                #detail_module_path::#cast_fn_name(derived)
             };
         }
         impls.push(quote! {
@@ -745,118 +1059,1051 @@ fn cc_struct_upcast_impl(record: &Rc<Record>, ir: &IR) -> Result<GeneratedItem>
         });
     }
 
+    if let Some(base_record) = single_public_base(record, ir)? {
+        let base_name = RsTypeKind::new_record(base_record.clone(), ir)?.into_token_stream();
+        let derived_name = RsTypeKind::new_record(record.clone(), ir)?.into_token_stream();
+        impls.push(quote! {
+            impl ::core::convert::AsRef<#base_name> for #derived_name {
+                fn as_ref(&self) -> &#base_name {
+                    oops::Upcast::upcast(self)
+                }
+            }
+        });
+        // A `&mut Base` can be used to overwrite the whole base class subobject,
+        // including its tail padding, so `Base` must be `Unpin`, i.e. its tail
+        // padding can't hold fields of the derived class.
+        if record.is_unpin() && base_record.is_unpin() {
+            impls.push(quote! {
+                impl ::core::convert::AsMut<#base_name> for #derived_name {
+                    fn as_mut(&mut self) -> &mut #base_name {
+                        oops::Upcast::upcast(self)
+                    }
+                }
+            });
+        }
+    }
+
     Ok(GeneratedItem {
         item: quote! {#(#impls)*},
         thunks: quote! {#(#thunks)*},
+        mock_methods: quote! {#(#mock_methods)*},
         thunk_impls: quote! {#(#cc_impls)*},
         ..Default::default()
     })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::tests::*;
-    use crate::BindingsTokens;
-    use ir_testing::with_lifetime_macros;
-    use token_stream_matchers::{assert_cc_matches, assert_rs_matches, assert_rs_not_matches};
-
-    #[test]
-    fn test_template_in_dependency_and_alias_in_current_target() -> Result<()> {
-        // See also the test with the same name in `ir_from_cc_test.rs`.
-        let ir = {
-            let dependency_src = r#" #pragma clang lifetime_elision
-                    template <typename T>
-                    struct MyTemplate {
-                        ~MyTemplate();
-                        T GetValue() { return field; }
-                        T field;
-                    }; "#;
-            let current_target_src = r#" #pragma clang lifetime_elision
-                    using MyAliasOfTemplate = MyTemplate<int>; "#;
-            ir_from_cc_dependency(current_target_src, dependency_src)?
-        };
+/// Returns the implementation of `Default` for an aggregate whose fields all
+/// have default member initializers, which default-constructs it with `T{}`.
+///
+/// Such a record already gets a `Default` implementation from its implicit
+/// default constructor, if the constructor has bindings (which requires
+/// lifetimes for `__this`). In that case, or if not all fields have default
+/// member initializers, nothing is generated.
+fn cc_struct_default_impl(db: &Database, record: &Rc<Record>, ir: &IR) -> Result<GeneratedItem> {
+    if !record.is_aggregate
+        || record.is_union()
+        || !record.is_unpin()
+        || record.fields.is_empty()
+        || record.fields.iter().any(|field| field.default_value.is_none())
+    {
+        return Ok(GeneratedItem::default());
+    }
+    let has_default_constructor_bindings =
+        ir.get_functions_by_name(&UnqualifiedIdentifier::Constructor).any(|ctor| {
+            ctor.params.len() == 1
+                && ctor.member_func_metadata.as_ref().map(|meta| meta.record_id) == Some(record.id)
+                && matches!(db.generate_func(ctor.clone()), Ok(Some(_)))
+        });
+    if has_default_constructor_bindings {
+        return Ok(GeneratedItem::default());
+    }
 
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
-        assert_rs_matches!(
-            rs_api,
-            quote! {
-                #[repr(C)]
-                #[__crubit::annotate(cc_type="MyTemplate < int >")]
-                pub struct __CcTemplateInst10MyTemplateIiE {
-                    pub field: ::core::ffi::c_int,
-                }
-            }
-        );
-        assert_rs_matches!(
-            rs_api,
-            quote! {
-                impl __CcTemplateInst10MyTemplateIiE {
-                    #[doc = " Generated from: google3/test/dependency_header.h;l=5"]
-                    #[inline(always)]
-                    pub fn GetValue<'a>(self: ... Pin<&'a mut Self>) -> ::core::ffi::c_int { unsafe {
-                        crate::detail::__rust_thunk___ZN10MyTemplateIiE8GetValueEv__2f_2ftest_3atesting_5ftarget(
-                            self)
-                    }}
+    let thunk_ident = make_rs_ident(&format!(
+        "__crubit_default__{record}_{odr_suffix}",
+        record = record.mangled_cc_name,
+        odr_suffix = record.owning_target.convert_to_cc_identifier(),
+    ));
+    let record_name = RsTypeKind::new_record(record.clone(), ir)?.into_token_stream();
+    let thunk_visibility = if db.options().generate_raw_thunks_module {
+        quote! { pub }
+    } else {
+        quote! { pub(crate) }
+    };
+    let thunk = generate_thunk_decl(
+        db,
+        quote! {},
+        thunk_visibility,
+        &thunk_ident,
+        quote! {},
+        &[(make_rs_ident("__this"), quote! { &mut ::core::mem::MaybeUninit<#record_name> })],
+        quote! {},
+    );
+    let detail_module_path = crate::detail_module_path_tokens(db);
+    let ident = make_rs_ident(record.rs_name.as_ref());
+    let cc_name = crate::cc_type_name_for_record(record, ir)?;
+    let tagless_cc_name = crate::cc_tagless_type_name_for_record(record, ir)?;
+    Ok(GeneratedItem {
+        item: quote! {
+            impl Default for #ident {
+                #[inline(always)]
+                fn default() -> Self {
+                    let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+                    unsafe {
+                        #detail_module_path::#thunk_ident(&mut tmp);
+                        tmp.assume_init()
+                    }
                 }
             }
-        );
-        assert_rs_matches!(
-            rs_api,
-            quote! {
-                pub type MyAliasOfTemplate = crate::__CcTemplateInst10MyTemplateIiE;
-            }
-        );
-        assert_rs_matches!(
-            rs_api,
-            quote! {
-                mod detail { ...  extern "C" {
-                    ...
-                    pub(crate) fn
-                    __rust_thunk___ZN10MyTemplateIiE8GetValueEv__2f_2ftest_3atesting_5ftarget<'a>(
-                        __this: ... Pin<&'a mut crate::__CcTemplateInst10MyTemplateIiE>
-                    ) -> ::core::ffi::c_int;
-                    ...
-                } }
-            }
-        );
-        assert_cc_matches!(
-            rs_api_impl,
-            quote! {
-                extern "C"
-                int __rust_thunk___ZN10MyTemplateIiE8GetValueEv__2f_2ftest_3atesting_5ftarget(
-                        struct MyTemplate<int>* __this) {
-                    return __this->GetValue();
-                }
+        },
+        thunks: thunk.thunks,
+        mock_methods: thunk.mock_methods,
+        thunk_impls: quote! {
+            extern "C" void #thunk_ident(#cc_name* __this) {
+                new (__this) #tagless_cc_name{};
             }
-        );
+        },
+        ..Default::default()
+    })
+}
 
-        Ok(())
+/// Returns true if `record` is plain old data, which can be safely reinterpreted
+/// to and from bytes: it is a trivially copyable aggregate, all of its fields are
+/// public and themselves plain old data, and it has no padding.
+fn is_record_pod(db: &Database, record: &Record) -> bool {
+    if record.is_union()
+        || !record.is_aggregate
+        || record.is_derived_class
+        || !record.lifetime_params.is_empty()
+        || !should_derive_copy(record)
+    {
+        return false;
     }
+    let mut size_of_fields = 0;
+    for field in &record.fields {
+        if field.access != AccessSpecifier::Public || field.is_bitfield {
+            return false;
+        }
+        match get_field_rs_type_kind_for_layout(db, record, field) {
+            Ok(type_kind) if is_type_pod(db, &type_kind) => size_of_fields += field.size,
+            _ => return false,
+        }
+    }
+    size_of_fields == record.size_align.size * 8
+}
 
-    #[test]
-    fn test_template_with_out_of_line_definition() -> Result<()> {
-        // See also an end-to-end test in the `test/templates/out_of_line_definition`
-        // directory.
-        let ir = ir_from_cc(
-            r#" #pragma clang lifetime_elision
-                template <typename T>
-                class MyTemplate final {
-                 public:
-                  static MyTemplate Create(T value);
-                  const T& value() const;
-
-                 private:
-                  T value_;
-                };
-
-                using MyTypeAlias = MyTemplate<int>; "#,
-        )?;
+fn is_type_pod(db: &Database, type_kind: &RsTypeKind) -> bool {
+    match type_kind.unalias() {
+        RsTypeKind::Primitive(primitive) => {
+            !matches!(primitive, PrimitiveType::Unit | PrimitiveType::bool)
+        }
+        RsTypeKind::Array { element_type, .. } => is_type_pod(db, element_type),
+        // The `Pod` impls are only generated for the records of the current target.
+        RsTypeKind::Record { record, .. } => {
+            db.ir().is_current_target(&record.owning_target) && is_record_pod(db, record)
+        }
+        _ => false,
+    }
+}
 
-        let BindingsTokens { rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+/// Returns the only direct base class of `record`, if it has exactly one
+/// public, non-virtual base class with bindings.
+///
+/// `unambiguous_public_bases` also lists the indirect base classes, so these are
+/// filtered out by removing the bases of the other bases.
+fn single_public_base(record: &Record, ir: &IR) -> Result<Option<Rc<Record>>> {
+    let mut bases = vec![];
+    for base in &record.unambiguous_public_bases {
+        let base_record: &Rc<Record> = ir
+            .find_decl(base.base_record_id)
+            .with_context(|| format!("Can't find a base record of {:?}", record))?;
+        bases.push((base, base_record));
+    }
+    let mut direct_bases = bases.iter().filter(|(base, _)| {
+        bases.iter().all(|(_, other)| {
+            other.unambiguous_public_bases.iter().all(|b| b.base_record_id != base.base_record_id)
+        })
+    });
+    match (direct_bases.next(), direct_bases.next()) {
+        (Some((base, base_record)), None) if base.offset.is_some() => {
+            Ok(Some((*base_record).clone()))
+        }
+        _ => Ok(None),
+    }
+}
 
-        // Even though the member functions above are *not* defined inline (e.g.
-        // IR::Func::is_inline is false), they still need to have thunks generated for
+/// Returns the doc comment of `record`, followed by a note on the caveats of
+/// using it as a base class if it isn't `final`.
+fn record_doc_comment(record: &Record) -> Option<String> {
+    if !record.is_inheritable {
+        return record.doc_comment.as_deref().map(str::to_string);
+    }
+    let mut note = String::from(
+        "This class is not `final`, so C++ code may derive from it. A reference to it may\n\
+         refer to the base class subobject of a derived class, and copying it by value\n\
+         slices off the derived part of the object.",
+    );
+    if record.is_trivial_abi && !record.is_opaque && record.may_reuse_tail_padding() {
+        note.push_str(
+            "\nSince a derived class may place its fields in the tail padding of this class, it\n\
+             is not `Unpin`, so that assigning to it can't overwrite them. Marking the class\n\
+             `final` lifts this restriction.",
+        );
+    }
+    match &record.doc_comment {
+        Some(doc_comment) => Some(format!("{doc_comment}\n\n{note}")),
+        None => Some(note),
+    }
+}
+
+/// Returns the member function of `record` named `name`, which is used to
+/// drive a `crubit_awaitable` type.
+fn awaitable_method(ir: &IR, record: &Record, name: &Rc<str>) -> Result<Rc<Func>> {
+    let identifier = UnqualifiedIdentifier::Identifier(Identifier { identifier: name.clone() });
+    let mut funcs = ir.get_functions_by_name(&identifier).filter(|func| {
+        func.is_instance_method()
+            && func.member_func_metadata.as_ref().map(|meta| meta.record_id) == Some(record.id)
+    });
+    let Some(func) = funcs.next() else {
+        bail!("Couldn't find the `crubit_awaitable` method `{name}` on `{}`", record.cc_name);
+    };
+    ensure!(
+        funcs.next().is_none(),
+        "The `crubit_awaitable` method `{name}` on `{}` must not be overloaded",
+        record.cc_name
+    );
+    Ok(func.clone())
+}
+
+/// Returns an expression which passes `self: Pin<&mut Self>` as the `this`
+/// parameter of `func`.
+fn awaitable_method_receiver(db: &Database, func: &Func) -> Result<TokenStream> {
+    let this = func.params.first().with_context(|| {
+        format!("Expected `{}` to have a `this` parameter", func.debug_name(&db.ir()))
+    })?;
+    match db.rs_type_kind(this.type_.rs_type.clone())? {
+        RsTypeKind::Reference { mutability: Mutability::Const, .. } => Ok(quote! { &*self }),
+        RsTypeKind::Reference { referent, .. } if referent.is_unpin() => {
+            Ok(quote! { self.as_mut().get_mut() })
+        }
+        RsTypeKind::Reference { .. } => Ok(quote! { self.as_mut() }),
+        other => bail!(
+            "`crubit_awaitable` methods must take `this` by lvalue reference, but `{}` takes `{other}`",
+            func.debug_name(&db.ir())
+        ),
+    }
+}
+
+/// Returns an implementation of `Future` for a record annotated with
+/// `crubit_awaitable`, which drives the awaitable through the bindings for its
+/// `poll` and `get` methods.
+///
+/// C++ awaitables don't know about the Rust `Waker`, so a pending awaitable
+/// immediately asks to be polled again, i.e. the executor spins until the
+/// awaitable completes. This is why the `Future` implementation requires the
+/// `experimental` feature.
+fn cc_struct_awaitable_impl(
+    db: &Database,
+    record: &Rc<Record>,
+    awaitable: &Awaitable,
+) -> Result<TokenStream> {
+    let ir = db.ir();
+    let poll = awaitable_method(&ir, record, &awaitable.poll)?;
+    let get = awaitable_method(&ir, record, &awaitable.get)?;
+    for func in [&poll, &get] {
+        ensure!(
+            db.generate_func(func.clone())?.is_some(),
+            "The `crubit_awaitable` method `{}` does not have bindings",
+            func.debug_name(&ir)
+        );
+    }
+
+    let poll_return_type = db.rs_type_kind(poll.return_type.rs_type.clone())?;
+    ensure!(
+        poll_return_type.is_bool(),
+        "The `crubit_awaitable` method `{}` must return `bool`, but returns `{poll_return_type}`",
+        poll.debug_name(&ir)
+    );
+    let output = db.rs_type_kind(get.return_type.rs_type.clone())?;
+    ensure!(
+        output.is_unpin() && output.lifetimes().next().is_none(),
+        "The `crubit_awaitable` method `{}` must return an `Unpin` type without lifetimes, but \
+        returns `{output}`",
+        get.debug_name(&ir)
+    );
+
+    let ident = make_rs_ident(record.rs_name.as_ref());
+    let poll_ident = make_rs_ident(&awaitable.poll);
+    let get_ident = make_rs_ident(&awaitable.get);
+    let poll_receiver = awaitable_method_receiver(db, &poll)?;
+    let get_receiver = awaitable_method_receiver(db, &get)?;
+    Ok(quote! {
+        impl ::core::future::Future for #ident {
+            type Output = #output;
+            fn poll(
+                mut self: ::core::pin::Pin<&mut Self>,
+                cx: &mut ::core::task::Context<'_>
+            ) -> ::core::task::Poll<Self::Output> {
+                if #ident::#poll_ident(#poll_receiver) {
+                    ::core::task::Poll::Ready(#ident::#get_ident(#get_receiver))
+                } else {
+                    cx.waker().wake_by_ref();
+                    ::core::task::Poll::Pending
+                }
+            }
+        }
+    })
+}
+
+/// Returns the getters and setters of the public, named bitfields of `record`.
+///
+/// Bitfields are laid out as opaque `__bitfieldsN` blobs, so the accessors call
+/// into C++ thunks, which take care of the masking and shifting.
+///
+/// Unions don't get accessors, since reading an inactive member is undefined
+/// behavior, which a safe getter can't rule out.
+fn cc_struct_bitfield_accessors_impl(db: &Database, record: &Rc<Record>) -> Result<GeneratedItem> {
+    if record.is_union() {
+        return Ok(GeneratedItem::default());
+    }
+    let ir = db.ir();
+    // The accessors are skipped if their name is already taken by a method.
+    let method_names: BTreeSet<&str> = record
+        .child_item_ids
+        .iter()
+        .filter_map(|id| match ir.find_decl::<Item>(*id) {
+            Ok(Item::Func(func)) => func.name.identifier_as_str(),
+            _ => None,
+        })
+        .collect();
+    let record_name = RsTypeKind::new_record(record.clone(), &ir)?.into_token_stream();
+    let cc_name = crate::cc_type_name_for_record(record, &ir)?;
+    let thunk_visibility = if db.options().generate_raw_thunks_module {
+        quote! { pub }
+    } else {
+        quote! { pub(crate) }
+    };
+    let detail_module_path = crate::detail_module_path_tokens(db);
+    let mut accessors = vec![];
+    let mut thunks = vec![];
+    let mut mock_methods = vec![];
+    let mut thunk_impls = vec![];
+    let mut decl_ids = vec![record.id];
+    for field in &record.fields {
+        let (Some(Identifier { identifier }), Ok(mapped_type)) =
+            (field.identifier.as_ref(), field.type_.as_ref())
+        else {
+            continue;
+        };
+        if !field.is_bitfield
+            || field.access != AccessSpecifier::Public
+            || untranslated_attrs(db, field.unknown_attr.as_deref()).is_some()
+        {
+            continue;
+        }
+        let getter_name = identifier.to_string();
+        let setter_name = format!("set_{identifier}");
+        if method_names.contains(getter_name.as_str())
+            || method_names.contains(setter_name.as_str())
+        {
+            continue;
+        }
+        // Only integral, `bool` and enum bitfields are supported.
+        let Ok(rs_type) = db.rs_type_kind(mapped_type.rs_type.clone()) else {
+            continue;
+        };
+        if !matches!(rs_type.unalias(), RsTypeKind::Primitive(_) | RsTypeKind::Enum { .. }) {
+            continue;
+        }
+        let cc_type = crate::format_cc_type(&mapped_type.cc_type, &ir)?;
+        decl_ids.extend(crate::cc_type_decl_ids(&mapped_type.cc_type));
+
+        let thunk_name = |kind: &str| {
+            make_rs_ident(&format!(
+                "__crubit_{kind}_{identifier}__{record}_{odr_suffix}",
+                record = record.mangled_cc_name,
+                odr_suffix = record.owning_target.convert_to_cc_identifier(),
+            ))
+        };
+        let getter_thunk = thunk_name("get");
+        let setter_thunk = thunk_name("set");
+        let getter_decl = generate_thunk_decl(
+            db,
+            quote! {},
+            thunk_visibility.clone(),
+            &getter_thunk,
+            quote! {},
+            &[(make_rs_ident("__this"), quote! { &#record_name })],
+            quote! { -> #rs_type },
+        );
+        let setter_decl = generate_thunk_decl(
+            db,
+            quote! {},
+            thunk_visibility.clone(),
+            &setter_thunk,
+            quote! {},
+            &[
+                (make_rs_ident("__this"), quote! { *mut #record_name }),
+                (make_rs_ident("value"), quote! { #rs_type }),
+            ],
+            quote! {},
+        );
+        for decl in [getter_decl, setter_decl] {
+            thunks.push(decl.thunks);
+            mock_methods.push(decl.mock_methods);
+        }
+
+        let getter_ident = make_rs_ident(&getter_name);
+        let setter_ident = make_rs_ident(&setter_name);
+        let getter_doc = format!(" Returns the value of the `{identifier}` bitfield.");
+        let setter_doc = format!(" Sets the value of the `{identifier}` bitfield.");
+        let (setter_self, setter_this) = if record.is_unpin() {
+            (quote! { &mut self }, quote! { self })
+        } else {
+            (quote! { self: ::core::pin::Pin<&mut Self> }, quote! { self.get_unchecked_mut() })
+        };
+        accessors.push(quote! {
+            #[doc = #getter_doc]
+            #[inline(always)]
+            pub fn #getter_ident(&self) -> #rs_type {
+                unsafe { #detail_module_path::#getter_thunk(self) }
+            }
+            #[doc = #setter_doc]
+            #[inline(always)]
+            pub fn #setter_ident(#setter_self, value: #rs_type) {
+                unsafe { #detail_module_path::#setter_thunk(#setter_this, value) }
+            }
+        });
+
+        let cc_field = crate::format_cc_ident(identifier);
+        thunk_impls.push(quote! {
+            extern "C" #cc_type #getter_thunk(const #cc_name* __this) {
+                return __this->#cc_field;
+            }
+            extern "C" void #setter_thunk(#cc_name* __this, #cc_type value) {
+                __this->#cc_field = value;
+            }
+        });
+    }
+    if accessors.is_empty() {
+        return Ok(GeneratedItem::default());
+    }
+    let ident = make_rs_ident(record.rs_name.as_ref());
+    Ok(GeneratedItem {
+        item: quote! {
+            impl #ident {
+                #( #accessors )*
+            }
+        },
+        thunks: quote! { #( #thunks )* },
+        mock_methods: quote! { #( #mock_methods )* },
+        thunk_impls: quote! { #( #thunk_impls __NEWLINE__ )* },
+        required_headers: crate::required_public_headers(&ir, decl_ids),
+        ..Default::default()
+    })
+}
+
+/// Generates accessors for the members of the anonymous structs and unions of
+/// `record` (e.g. `a()` and `a_mut()` for `struct S { union { int a; }; };`).
+///
+/// The anonymous struct or union itself is represented as an opaque blob of
+/// bytes, so the accessors compute the addresses of its members from their
+/// offsets. The accessors of the members of anonymous unions are `unsafe`,
+/// like reading the field of a Rust union.
+fn cc_struct_anonymous_member_accessors_impl(
+    db: &Database,
+    record: &Rc<Record>,
+) -> Result<GeneratedItem> {
+    /// Appends the named fields in `fields` (and, recursively, the members of
+    /// the anonymous structs and unions in `fields`) to `members`, along with
+    /// their offsets in bits relative to the record, and whether they are a
+    /// part of a union.
+    fn flatten_anonymous_members<'a>(
+        db: &dyn BindingsGenerator,
+        fields: impl IntoIterator<Item = &'a Field>,
+        base_offset: usize,
+        in_union: bool,
+        members: &mut Vec<(&'a Field, usize, bool)>,
+    ) {
+        for field in fields {
+            if field.access != AccessSpecifier::Public
+                || untranslated_attrs(db, field.unknown_attr.as_deref()).is_some()
+            {
+                continue;
+            }
+            let offset = base_offset + field.offset;
+            if field.identifier.is_none() {
+                let in_union = in_union || field.is_anonymous_union;
+                flatten_anonymous_members(db, &field.anonymous_members, offset, in_union, members);
+            } else {
+                members.push((field, offset, in_union));
+            }
+        }
+    }
+
+    let ir = db.ir();
+    let mut members = vec![];
+    let anonymous_fields = record.fields.iter().filter(|field| field.identifier.is_none());
+    flatten_anonymous_members(db, anonymous_fields, 0, record.is_union(), &mut members);
+    // The accessors are skipped if their name is already taken by a method.
+    let method_names: BTreeSet<&str> = record
+        .child_item_ids
+        .iter()
+        .filter_map(|id| match ir.find_decl::<Item>(*id) {
+            Ok(Item::Func(func)) => func.name.identifier_as_str(),
+            _ => None,
+        })
+        .collect();
+    let mut accessors = vec![];
+    for (member, offset, in_union) in members {
+        let Some(Identifier { identifier }) = &member.identifier else { continue };
+        if member.is_bitfield || offset % 8 != 0 {
+            continue;
+        }
+        let getter_name = identifier.to_string();
+        let mut_getter_name = format!("{identifier}_mut");
+        if method_names.contains(getter_name.as_str())
+            || method_names.contains(mut_getter_name.as_str())
+        {
+            continue;
+        }
+        let Ok(rs_type) = get_field_rs_type_kind_for_layout(db, record, member) else {
+            continue;
+        };
+        let offset = Literal::usize_unsuffixed(offset / 8);
+        let getter_ident = make_rs_ident(&getter_name);
+        let (unsafe_qualifier, kind, safety_doc) = if in_union {
+            (
+                quote! { unsafe },
+                "an anonymous union",
+                quote! {
+                    #[doc = ""]
+                    #[doc = " # Safety"]
+                    #[doc = ""]
+                    #[doc = " The member must be initialized, like when reading the field of a Rust union."]
+                },
+            )
+        } else {
+            (quote! {}, "an anonymous struct", quote! {})
+        };
+        let getter_doc = format!(" Returns a reference to the `{identifier}` member of {kind}.");
+        accessors.push(quote! {
+            #[doc = #getter_doc]
+            #safety_doc
+            #[inline(always)]
+            pub #unsafe_qualifier fn #getter_ident(&self) -> &#rs_type {
+                unsafe { &*(self as *const Self).cast::<u8>().add(#offset).cast::<#rs_type>() }
+            }
+        });
+        // Mutable references to types that aren't `Unpin` would allow moving the
+        // member out.
+        if rs_type.is_unpin() {
+            let mut_getter_ident = make_rs_ident(&mut_getter_name);
+            let mut_getter_doc =
+                format!(" Returns a mutable reference to the `{identifier}` member of {kind}.");
+            let (self_param, this) = if record.is_unpin() {
+                (quote! { &mut self }, quote! { self })
+            } else {
+                (quote! { self: ::core::pin::Pin<&mut Self> }, quote! { self.get_unchecked_mut() })
+            };
+            accessors.push(quote! {
+                #[doc = #mut_getter_doc]
+                #safety_doc
+                #[inline(always)]
+                pub #unsafe_qualifier fn #mut_getter_ident(#self_param) -> &mut #rs_type {
+                    unsafe {
+                        &mut *(#this as *mut Self).cast::<u8>().add(#offset).cast::<#rs_type>()
+                    }
+                }
+            });
+        }
+    }
+    if accessors.is_empty() {
+        return Ok(GeneratedItem::default());
+    }
+    let ident = make_rs_ident(record.rs_name.as_ref());
+    Ok(GeneratedItem {
+        item: quote! {
+            impl #ident {
+                #( #accessors )*
+            }
+        },
+        ..Default::default()
+    })
+}
+
+/// Returns a `for_each` method for a record annotated with `crubit_map_visitor`.
+///
+/// The method passes a closure to a C++ thunk, which iterates over the entries
+/// of the map and calls back into a Rust trampoline with the key and value of
+/// each entry.
+///
+/// If the record already has a method named `for_each`, the method is replaced
+/// by a comment, and an error is reported.
+fn cc_struct_map_visitor_impl(
+    db: &Database,
+    record: &Rc<Record>,
+    map_visitor: &MapVisitor,
+) -> Result<GeneratedItem> {
+    let ir = db.ir();
+    if has_method_named(&ir, record.id, "for_each") {
+        let msg = format!(
+            "The `for_each` method of the `crubit_map_visitor` `{}` is not generated, because \
+             it collides with a method of `{}`",
+            record.cc_name, record.rs_name
+        );
+        db.errors().insert(&anyhow!("{msg}"));
+        return Ok(GeneratedItem { item: quote! { __COMMENT__ #msg }, ..Default::default() });
+    }
+    let mut entry_types = vec![];
+    for mapped_type in [&map_visitor.key_type, &map_visitor.mapped_type] {
+        let rs_type = db.rs_type_kind(mapped_type.rs_type.clone())?;
+        ensure!(
+            rs_type.lifetimes().next().is_none()
+                && rs_type.as_bridge_type().is_none()
+                && rs_type.as_std_optional().is_none()
+                && rs_type.as_initializer_list().is_none(),
+            "The `crubit_map_visitor` entries of `{}` can't contain `{}`",
+            record.cc_name,
+            rs_type.to_token_stream()
+        );
+        let cc_pointer_type = crate::format_cc_type(
+            &CcType {
+                name: Some("*".into()),
+                is_const: false,
+                type_args: vec![CcType { is_const: true, ..mapped_type.cc_type.clone() }],
+                decl_id: None,
+            },
+            &ir,
+        )?;
+        entry_types.push((rs_type, cc_pointer_type));
+    }
+    let [(key_type, cc_key_pointer_type), (value_type, cc_value_pointer_type)] =
+        <[_; 2]>::try_from(entry_types).unwrap();
+
+    let thunk_ident = make_rs_ident(&format!(
+        "__crubit_for_each__{record}_{odr_suffix}",
+        record = record.mangled_cc_name,
+        odr_suffix = record.owning_target.convert_to_cc_identifier(),
+    ));
+    let record_name = RsTypeKind::new_record(record.clone(), &ir)?.into_token_stream();
+    let thunk_visibility = if db.options().generate_raw_thunks_module {
+        quote! { pub }
+    } else {
+        quote! { pub(crate) }
+    };
+    let thunk = generate_thunk_decl(
+        db,
+        quote! {},
+        thunk_visibility,
+        &thunk_ident,
+        quote! {},
+        &[
+            (make_rs_ident("__this"), quote! { &#record_name }),
+            (make_rs_ident("__f"), quote! { *mut ::core::ffi::c_void }),
+            (
+                make_rs_ident("__visit"),
+                quote! {
+                    unsafe extern "C" fn(*mut ::core::ffi::c_void, *const #key_type, *const #value_type)
+                },
+            ),
+        ],
+        quote! {},
+    );
+    let detail_module_path = crate::detail_module_path_tokens(db);
+    let ident = make_rs_ident(record.rs_name.as_ref());
+    let cc_name = crate::cc_type_name_for_record(record, &ir)?;
+    let begin = crate::format_cc_ident(&map_visitor.begin);
+    let end = crate::format_cc_ident(&map_visitor.end);
+    let export_macro = if db.options().thunk_export_macro.is_empty() {
+        quote! {}
+    } else {
+        syn::parse_str::<TokenStream>(&db.options().thunk_export_macro)?
+    };
+    let decl_ids = iter::once(record.id)
+        .chain(crate::cc_type_decl_ids(&map_visitor.key_type.cc_type))
+        .chain(crate::cc_type_decl_ids(&map_visitor.mapped_type.cc_type));
+    Ok(GeneratedItem {
+        item: quote! {
+            impl #ident {
+                /// Calls `f` with the key and the value of each entry of the map, in
+                /// iteration order.
+                #[inline(always)]
+                pub fn for_each<F: FnMut(&#key_type, &#value_type)>(&self, mut f: F) {
+                    unsafe extern "C" fn __visit<F: FnMut(&#key_type, &#value_type)>(
+                        __f: *mut ::core::ffi::c_void,
+                        __key: *const #key_type,
+                        __value: *const #value_type,
+                    ) {
+                        unsafe { (*(__f as *mut F))(&*__key, &*__value) }
+                    }
+                    unsafe {
+                        #detail_module_path::#thunk_ident(
+                            self,
+                            &mut f as *mut F as *mut ::core::ffi::c_void,
+                            __visit::<F>,
+                        )
+                    }
+                }
+            }
+        },
+        thunks: thunk.thunks,
+        mock_methods: thunk.mock_methods,
+        thunk_impls: quote! {
+            extern "C" #export_macro void #thunk_ident(
+                const #cc_name* __this,
+                void* __f,
+                void (*__visit)(void*, #cc_key_pointer_type, #cc_value_pointer_type)
+            ) {
+                for (auto __it = __this->#begin(); __it != __this->#end(); ++__it) {
+                    __visit(__f, &__it->first, &__it->second);
+                }
+            }
+        },
+        required_headers: crate::required_public_headers(&ir, decl_ids),
+        ..Default::default()
+    })
+}
+
+/// Returns the extension trait of `record` (e.g. `SomeStructExt` for
+/// `SomeStruct`), declaring its methods, and the implementation of the trait
+/// for the record, which delegates to the inherent methods.
+///
+/// `ext_trait_decls` and `ext_trait_impls` are collected from the bindings of
+/// the methods (see `generate_func::ext_trait_method_signature`). Downstream
+/// code can be written against the trait (e.g. with blanket impls, or mock
+/// implementations in tests) rather than against the generated type. Nothing
+/// is generated if the record has no methods that take `self`.
+///
+/// If the name of the trait is taken by another item in the module of the
+/// record, the trait is replaced by a comment, and an error is reported.
+fn generate_record_ext_trait(
+    db: &Database,
+    record: &Record,
+    ext_trait_decls: TokenStream,
+    ext_trait_impls: TokenStream,
+) -> Result<TokenStream> {
+    if ext_trait_decls.is_empty() {
+        return Ok(quote! {});
+    }
+    let trait_name = format!("{}Ext", record.rs_name);
+    let namespace_qualifier = db.ir().namespace_qualifier(record)?;
+    if db.module_item_names(namespace_qualifier).contains(trait_name.as_str()) {
+        let msg = format!(
+            "The extension trait `{trait_name}` of `{}` is not generated, because it collides \
+             with another item",
+            record.rs_name
+        );
+        db.errors().insert(&anyhow!("{msg}"));
+        return Ok(quote! { __COMMENT__ #msg });
+    }
+    let ident = make_rs_ident(record.rs_name.as_ref());
+    let trait_ident = make_rs_ident(&trait_name);
+    let doc = format!(" The methods of [`{}`], as a trait.", record.rs_name);
+    Ok(quote! {
+        #[doc = #doc]
+        pub trait #trait_ident {
+            #ext_trait_decls
+        }
+
+        impl #trait_ident for #ident {
+            #ext_trait_impls
+        }
+    })
+}
+
+/// Returns the names of the methods that the bindings of `record` provide if
+/// it is an instantiation of `std::vector` (see `cc_struct_std_vector_impl`).
+/// C++ member functions with these names don't get bindings, so that they
+/// don't collide.
+pub(crate) fn std_vector_methods(
+    db: &dyn BindingsGenerator,
+    record: &Record,
+) -> &'static [&'static str] {
+    let Some(element_type) = &record.std_vector_element_type else {
+        return &[];
+    };
+    match std_vector_slice_element_type(db, element_type) {
+        None => &[],
+        Some(rs_element_type) if rs_element_type.implements_copy() => {
+            &["as_slice", "len", "is_empty", "push_back"]
+        }
+        Some(_) => &["as_slice", "len", "is_empty"],
+    }
+}
+
+/// Returns the Rust type of the elements of a `std::vector`, if they can be
+/// exposed as a Rust slice (e.g. not if they are converted when they cross the
+/// FFI boundary).
+fn std_vector_slice_element_type(
+    db: &dyn BindingsGenerator,
+    element_type: &MappedType,
+) -> Option<RsTypeKind> {
+    let rs_element_type = db.rs_type_kind(element_type.rs_type.clone()).ok()?;
+    let is_slice_element = match rs_element_type.unalias() {
+        RsTypeKind::Primitive(_) | RsTypeKind::Pointer { .. } | RsTypeKind::Enum { .. } => true,
+        RsTypeKind::Record { record, .. } => !record.is_opaque,
+        _ => false,
+    };
+    if !is_slice_element || rs_element_type.lifetimes().next().is_some() {
+        return None;
+    }
+    Some(rs_element_type)
+}
+
+/// Returns `as_slice`, `len`, `is_empty` and `push_back` methods, and an
+/// `IntoIterator` implementation, for an instantiation of `std::vector`.
+///
+/// Nothing is generated if the elements can't be exposed as a Rust slice (e.g.
+/// because they are converted when they cross the FFI boundary). `push_back` is
+/// only generated for elements which implement `Copy`.
+fn cc_struct_std_vector_impl(
+    db: &Database,
+    record: &Rc<Record>,
+    element_type: &MappedType,
+) -> Result<GeneratedItem> {
+    let ir = db.ir();
+    let Some(rs_element_type) = std_vector_slice_element_type(db, element_type) else {
+        return Ok(GeneratedItem::default());
+    };
+    let cc_element_pointer_type = crate::format_cc_type(
+        &CcType {
+            name: Some("*".into()),
+            is_const: false,
+            type_args: vec![CcType { is_const: true, ..element_type.cc_type.clone() }],
+            decl_id: None,
+        },
+        &ir,
+    )?;
+
+    let thunk_ident = |method: &str| {
+        make_rs_ident(&format!(
+            "__crubit_std_vector_{method}__{record}_{odr_suffix}",
+            record = record.mangled_cc_name,
+            odr_suffix = record.owning_target.convert_to_cc_identifier(),
+        ))
+    };
+    let data_thunk_ident = thunk_ident("data");
+    let push_back_thunk_ident = thunk_ident("push_back");
+    let record_name = RsTypeKind::new_record(record.clone(), &ir)?.into_token_stream();
+    let thunk_visibility = if db.options().generate_raw_thunks_module {
+        quote! { pub }
+    } else {
+        quote! { pub(crate) }
+    };
+    let mut thunks = vec![generate_thunk_decl(
+        db,
+        quote! {},
+        thunk_visibility.clone(),
+        &data_thunk_ident,
+        quote! {},
+        &[
+            (make_rs_ident("__this"), quote! { &#record_name }),
+            (make_rs_ident("__size"), quote! { *mut usize }),
+        ],
+        quote! { -> *const #rs_element_type },
+    )];
+    let detail_module_path = crate::detail_module_path_tokens(db);
+    let ident = make_rs_ident(record.rs_name.as_ref());
+    let cc_name = crate::cc_type_name_for_record(record, &ir)?;
+    let mut thunk_impls = quote! {
+        extern "C" #cc_element_pointer_type #data_thunk_ident(
+            const #cc_name* __this, size_t* __size) {
+            *__size = __this->size();
+            return __this->data();
+        }
+    };
+
+    let push_back = if rs_element_type.implements_copy() {
+        thunks.push(generate_thunk_decl(
+            db,
+            quote! {},
+            thunk_visibility,
+            &push_back_thunk_ident,
+            quote! {},
+            &[
+                (make_rs_ident("__this"), quote! { *mut #record_name }),
+                (make_rs_ident("__value"), quote! { *const #rs_element_type }),
+            ],
+            quote! {},
+        ));
+        thunk_impls.extend(quote! {
+            extern "C" void #push_back_thunk_ident(
+                #cc_name* __this, #cc_element_pointer_type __value) {
+                __this->push_back(*__value);
+            }
+        });
+        let (self_param, this) = if record.is_unpin() {
+            (quote! { &mut self }, quote! { self })
+        } else {
+            (quote! { self: ::core::pin::Pin<&mut Self> }, quote! { self.get_unchecked_mut() })
+        };
+        quote! {
+            /// Appends `value` to the end of the vector.
+            #[inline(always)]
+            pub fn push_back(#self_param, value: #rs_element_type) {
+                unsafe { #detail_module_path::#push_back_thunk_ident(#this, &value) }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let decl_ids = iter::once(record.id).chain(crate::cc_type_decl_ids(&element_type.cc_type));
+    Ok(GeneratedItem {
+        item: quote! {
+            impl #ident {
+                /// Returns the elements of the vector.
+                #[inline(always)]
+                pub fn as_slice(&self) -> &[#rs_element_type] {
+                    let mut __size = 0usize;
+                    unsafe {
+                        let __data = #detail_module_path::#data_thunk_ident(self, &mut __size);
+                        if __size == 0 {
+                            &[]
+                        } else {
+                            ::core::slice::from_raw_parts(__data, __size)
+                        }
+                    }
+                }
+
+                /// Returns the number of elements of the vector.
+                #[inline(always)]
+                pub fn len(&self) -> usize {
+                    self.as_slice().len()
+                }
+
+                /// Returns whether the vector has no elements.
+                #[inline(always)]
+                pub fn is_empty(&self) -> bool {
+                    self.as_slice().is_empty()
+                }
+
+                #push_back
+            }
+
+            impl<'a> ::core::iter::IntoIterator for &'a #ident {
+                type Item = &'a #rs_element_type;
+                type IntoIter = ::core::slice::Iter<'a, #rs_element_type>;
+                #[inline(always)]
+                fn into_iter(self) -> Self::IntoIter {
+                    self.as_slice().iter()
+                }
+            }
+        },
+        thunks: thunks.iter().map(|thunk| &thunk.thunks).cloned().collect(),
+        mock_methods: thunks.iter().map(|thunk| &thunk.mock_methods).cloned().collect(),
+        thunk_impls,
+        required_headers: crate::required_public_headers(&ir, decl_ids),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::{BindingsTokens, GeneratorOptions};
+    use ir_testing::with_lifetime_macros;
+    use token_stream_matchers::{
+        assert_cc_matches, assert_cc_not_matches, assert_rs_matches, assert_rs_not_matches,
+    };
+
+    #[test]
+    fn test_template_in_dependency_and_alias_in_current_target() -> Result<()> {
+        // See also the test with the same name in `ir_from_cc_test.rs`.
+        let ir = {
+            let dependency_src = r#" #pragma clang lifetime_elision
+                    template <typename T>
+                    struct MyTemplate {
+                        ~MyTemplate();
+                        T GetValue() { return field; }
+                        T field;
+                    }; "#;
+            let current_target_src = r#" #pragma clang lifetime_elision
+                    using MyAliasOfTemplate = MyTemplate<int>; "#;
+            ir_from_cc_dependency(current_target_src, dependency_src)?
+        };
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[repr(C)]
+                #[__crubit::annotate(cc_type="MyTemplate < int >")]
+                pub struct __CcTemplateInst10MyTemplateIiE {
+                    pub field: ::core::ffi::c_int,
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl __CcTemplateInst10MyTemplateIiE {
+                    #[doc = " Generated from: google3/test/dependency_header.h;l=5"]
+                    #[inline(always)]
+                    pub fn GetValue<'a>(self: ... Pin<&'a mut Self>) -> ::core::ffi::c_int { unsafe {
+                        crate::detail::__rust_thunk___ZN10MyTemplateIiE8GetValueEv__2f_2ftest_3atesting_5ftarget(
+                            self)
+                    }}
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub type MyAliasOfTemplate = crate::__CcTemplateInst10MyTemplateIiE;
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                mod detail { ...  extern "C" {
+                    ...
+                    pub(crate) fn
+                    __rust_thunk___ZN10MyTemplateIiE8GetValueEv__2f_2ftest_3atesting_5ftarget<'a>(
+                        __this: ... Pin<&'a mut crate::__CcTemplateInst10MyTemplateIiE>
+                    ) -> ::core::ffi::c_int;
+                    ...
+                } }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C"
+                int __rust_thunk___ZN10MyTemplateIiE8GetValueEv__2f_2ftest_3atesting_5ftarget(
+                        struct MyTemplate<int>* __this) {
+                    return __this->GetValue();
+                }
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_with_out_of_line_definition() -> Result<()> {
+        // See also an end-to-end test in the `test/templates/out_of_line_definition`
+        // directory.
+        let ir = ir_from_cc(
+            r#" #pragma clang lifetime_elision
+                template <typename T>
+                class MyTemplate final {
+                 public:
+                  static MyTemplate Create(T value);
+                  const T& value() const;
+
+                 private:
+                  T value_;
+                };
+
+                using MyTypeAlias = MyTemplate<int>; "#,
+        )?;
+
+        let BindingsTokens { rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+
+        // Even though the member functions above are *not* defined inline (e.g.
+        // IR::Func::is_inline is false), they still need to have thunks generated for
         // them (to force/guarantee that the class template and its members get
         // instantiated).  This is also covered in the following end-to-end
         // tests:
@@ -870,20 +2117,769 @@ mod tests {
         assert_cc_matches!(
             rs_api_impl,
             quote! {
-                extern "C" void
-                __rust_thunk___ZN10MyTemplateIiE6CreateEi__2f_2ftest_3atesting_5ftarget(
-                    class MyTemplate<int>* __return, int value) {
-                  new (__return) auto(MyTemplate<int>::Create(value));
+                extern "C" void
+                __rust_thunk___ZN10MyTemplateIiE6CreateEi__2f_2ftest_3atesting_5ftarget(
+                    class MyTemplate<int>* __return, int value) {
+                  new (__return) auto(MyTemplate<int>::Create(value));
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int const*
+                __rust_thunk___ZNK10MyTemplateIiE5valueEv__2f_2ftest_3atesting_5ftarget(
+                        const class MyTemplate<int>*__this) {
+                    return &__this->value();
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_simple_struct() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            #pragma clang lifetime_elision
+            struct SomeStruct final {
+                ~SomeStruct() {}
+                int public_int;
+              protected:
+                int protected_int;
+              private:
+               int private_int;
+            };
+        "#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[::ctor::recursively_pinned(PinnedDrop)]
+                #[repr(C, align(4))]
+                #[__crubit::annotate(cc_type="SomeStruct")]
+                pub struct SomeStruct {
+                    __non_field_data: [::core::mem::MaybeUninit<u8>; 0],
+                    pub public_int: ::core::ffi::c_int,
+                    #[doc = " Reason for representing this field as a blob of bytes:\n Types of non-public C++ fields can be elided away"]
+                    pub(crate) protected_int: [::core::mem::MaybeUninit<u8>; 4],
+                    #[doc = " Reason for representing this field as a blob of bytes:\n Types of non-public C++ fields can be elided away"]
+                    pub(crate) private_int: [::core::mem::MaybeUninit<u8>; 4],
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                const _ : () = {
+                    ...
+                    assert!(::core::mem::size_of::<crate::SomeStruct>() == 12);
+                    assert!(::core::mem::align_of::<crate::SomeStruct>() == 4);
+                    static_assertions::assert_not_impl_any!(crate::SomeStruct: Copy);
+                    static_assertions::assert_impl_all!(crate::SomeStruct: Drop);
+                    assert!(::core::mem::offset_of!(crate::SomeStruct, public_int) == 0);
+                    assert!(::core::mem::offset_of!(crate::SomeStruct, protected_int) == 4);
+                    assert!(::core::mem::offset_of!(crate::SomeStruct, private_int) == 8);
+                    ...
+                };
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void __rust_thunk___ZN10SomeStructD1Ev(struct SomeStruct * __this) {
+                    std::destroy_at(__this);
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                static_assert(CRUBIT_SIZEOF(struct SomeStruct) == 12);
+                static_assert(alignof(struct SomeStruct) == 4);
+                static_assert(CRUBIT_OFFSET_OF(public_int, struct SomeStruct) == 0);
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_layout_assertions() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct SomeStruct final {
+                int first_int;
+                int second_int;
+            };
+        "#,
+        )?;
+
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { compact_layout_assertions: true, ..Default::default() },
+        )?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                const _: () = {
+                    type T = crate::SomeStruct;
+                    let layout = [
+                        (::core::mem::size_of::<T>(), 8),
+                        (::core::mem::align_of::<T>(), 4),
+                        (::core::mem::offset_of!(T, first_int), 0),
+                        (::core::mem::offset_of!(T, second_int), 4),
+                    ];
+                    let mut i = 0;
+                    while i < layout.len() {
+                        assert!(layout[i].0 == layout[i].1);
+                        i += 1;
+                    }
+                };
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { assert!(::core::mem::offset_of!(...) == ...) });
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                [[maybe_unused]] static void __crubit_layout_assertions_10SomeStruct() {
+                    using T = struct SomeStruct;
+                    static_assert(CRUBIT_SIZEOF(T) == 8);
+                    static_assert(alignof(T) == 4);
+                    static_assert(CRUBIT_OFFSET_OF(first_int, T) == 0);
+                    static_assert(CRUBIT_OFFSET_OF(second_int, T) == 4);
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_layout_assertions_are_smaller() -> Result<()> {
+        let fields = (0..20).map(|i| format!("int field_{i};")).join("\n");
+        let header = format!("namespace ns {{ struct SomeStruct final {{ {fields} }}; }}");
+        let generated_size = |compact: bool| -> Result<(usize, usize)> {
+            let ir = ir_from_cc(&header)?;
+            let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens_with_options(
+                ir,
+                GeneratorOptions { compact_layout_assertions: compact, ..Default::default() },
+            )?;
+            Ok((rs_api.to_string().len(), rs_api_impl.to_string().len()))
+        };
+        let (rs_size, cc_size) = generated_size(false)?;
+        let (compact_rs_size, compact_cc_size) = generated_size(true)?;
+        assert!(compact_rs_size < rs_size, "{compact_rs_size} >= {rs_size}");
+        assert!(compact_cc_size < cc_size, "{compact_cc_size} >= {cc_size}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_struct_vs_class() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            #pragma clang lifetime_elision
+            struct SomeStruct final {
+                SomeStruct() {}
+                int field;
+            };
+            class SomeClass final {
+              public:
+                SomeClass() {}
+                int field;
+            };
+        "#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+
+        // A Rust `struct` is generated for both `SomeStruct` and `SomeClass`.
+        assert_rs_matches!(rs_api, quote! { pub struct SomeStruct },);
+        assert_rs_matches!(rs_api, quote! { pub struct SomeClass },);
+
+        // But in C++ we still should refer to `struct SomeStruct` and `class
+        // SomeClass`. See also b/238212337.
+        assert_cc_matches!(rs_api_impl, quote! { struct SomeStruct * __this });
+        assert_cc_matches!(rs_api_impl, quote! { class SomeClass * __this });
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { static_assert(CRUBIT_SIZEOF(struct SomeStruct) == 4); }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { static_assert(CRUBIT_SIZEOF(class SomeClass) == 4); }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_struct_vs_typedefed_struct() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            #pragma clang lifetime_elision
+            struct SomeStruct final {
+              int x;
+            } __attribute__((aligned(16)));
+            typedef struct {
+              int x;
+            } SomeAnonStruct __attribute__((aligned(16)));
+        "#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+
+        // A `struct` is generated for both `SomeStruct` and `SomeAnonStruct`, both
+        // in Rust and in C++.
+        assert_rs_matches!(rs_api, quote! { pub struct SomeStruct },);
+        assert_rs_matches!(rs_api, quote! { pub struct SomeAnonStruct },);
+        assert_rs_matches!(rs_api_impl, quote! { struct SomeStruct * __this },);
+        assert_rs_matches!(rs_api_impl, quote! { SomeAnonStruct * __this },);
+
+        // In C++, both have align == 16, but size for `SomeAnonStruct` is not aligned.
+        // `SomeAnonStruct` won't have `struct` in the assert.
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { static_assert(alignof(struct SomeStruct) == 16); }
+        );
+        assert_cc_matches!(rs_api_impl, quote! { static_assert(alignof(SomeAnonStruct) == 16); });
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { static_assert(CRUBIT_SIZEOF(struct SomeStruct) == 16); }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { static_assert(CRUBIT_SIZEOF(SomeAnonStruct) == 16); }
+        );
+
+        // In Rust, both have align == 16 and size == 16.
+        assert_rs_matches!(
+            rs_api,
+            quote! { assert!(::core::mem::size_of::<crate::SomeStruct>() == 16); }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { assert!(::core::mem::align_of::<crate::SomeStruct>() == 16); }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { assert!(::core::mem::size_of::<crate::SomeAnonStruct>() == 16); }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { assert!(::core::mem::align_of::<crate::SomeAnonStruct>() == 16); }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_with_unsupported_field_type() -> Result<()> {
+        // Using a packed struct because it's currently not supported.
+        // But... any other unsupported type would also work for this test.
+        let ir = ir_from_cc(
+            r#"
+            struct __attribute__((packed, aligned(4))) PackedStruct {
+              int packed_field;
+            };
+            struct StructWithUnsupportedField {
+              // Doc comment for `my_field`.
+              PackedStruct my_field;
+            };
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[repr(C, align(4))]
+                #[__crubit::annotate(cc_type="StructWithUnsupportedField")]
+                pub struct StructWithUnsupportedField {
+                    #[doc = " Doc comment for `my_field`.\n \n Reason for representing this field as a blob of bytes:\n Unsupported type 'struct PackedStruct': No generated bindings found for 'PackedStruct'"]
+                    pub(crate) my_field: [::core::mem::MaybeUninit<u8>; 4],
+                }
+                ...
+                const _: () = {
+                    ...
+                    assert!(
+                    ::core::mem::offset_of!(crate::StructWithUnsupportedField, my_field) == 0);
+                    ...
+                };
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_with_array_fields() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct SomeStruct final {
+              int buf[16];
+              char tail[3];
+            };
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub struct SomeStruct {
+                    pub buf: [::core::ffi::c_int; 16],
+                    pub tail: [::core::ffi::c_char; 3],
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                const _: () = {
+                    assert!(::core::mem::size_of::<crate::SomeStruct>() == 68);
+                    ...
+                    assert!(::core::mem::offset_of!(crate::SomeStruct, buf) == 0);
+                    assert!(::core::mem::offset_of!(crate::SomeStruct, tail) == 64);
+                    assert!(::core::mem::size_of::<[::core::ffi::c_int; 16]>() == 64);
+                    assert!(::core::mem::size_of::<[::core::ffi::c_char; 3]>() == 3);
+                    ...
+                };
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_with_array_of_records_field() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct Element final { int x; };
+            struct SomeStruct final {
+              Element elements[2];
+            };
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub(crate) elements: [::core::mem::MaybeUninit<u8>; 8] }
+        );
+        assert_rs_not_matches!(rs_api, quote! { [crate::Element; 2] });
+        Ok(())
+    }
+
+    /// This is a regression test for b/283835873 where the alignment of the
+    /// generated struct was wrong/missing.
+    #[test]
+    fn test_struct_with_only_bitfields() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+                struct SomeStruct {
+                  char32_t code_point : 31;
+                  enum : char32_t {
+                    ok = 0,
+                    error = 1
+                  } status : 1;
+                };
+                static_assert(sizeof(SomeStruct) == 4);
+                static_assert(alignof(SomeStruct) == 4);
+            "#,
+        )?;
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+               #[repr(C, align(4))]
+                #[__crubit::annotate(cc_type="SomeStruct")]
+               pub struct SomeStruct { ...  }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                const _: () = {
+                    ...
+                    assert!(::core::mem::size_of::<crate::SomeStruct>() == 4);
+                    assert!(::core::mem::align_of::<crate::SomeStruct>() == 4);
+                    ...
+                };
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_struct_with_unnamed_bitfield_member() -> Result<()> {
+        // This test input causes `field_decl->getName()` to return an empty string.
+        // This example is based on `struct timex` from
+        // /usr/grte/v5/include/bits/timex.h
+        let ir = ir_from_cc(
+            r#"
+            struct SomeStruct {
+                int first_field;
+                int :32;
+                int last_field;
+            }; "#,
+        )?;
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[repr(C, align(4))]
+                #[__crubit::annotate(cc_type="SomeStruct")]
+                pub struct SomeStruct {
+                    pub first_field: ::core::ffi::c_int, ...
+                    __bitfields1: [::core::mem::MaybeUninit<u8>; 4],
+                    pub last_field: ::core::ffi::c_int,
+                }
+                ...
+                const _: () = {
+                    ...
+                    assert!(::core::mem::offset_of!(crate::SomeStruct, first_field) == 0);
+                    assert!(::core::mem::offset_of!(crate::SomeStruct, last_field) == 8);
+                    ...
+                };
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_struct_with_bitfield_accessors() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct SomeStruct final {
+                int mode : 3;
+                bool enabled : 1;
+                int : 4;
+                int last_field;
+            }; "#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub struct SomeStruct {
+                    ...
+                    __bitfields0: [::core::mem::MaybeUninit<u8>; 4],
+                    pub last_field: ::core::ffi::c_int,
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl SomeStruct {
+                    #[doc = " Returns the value of the `mode` bitfield."]
+                    #[inline(always)]
+                    pub fn mode(&self) -> ::core::ffi::c_int {
+                        unsafe {
+                            crate::detail::__crubit_get_mode__10SomeStruct___2f_2ftest_3atesting_5ftarget(self)
+                        }
+                    }
+                    #[doc = " Sets the value of the `mode` bitfield."]
+                    #[inline(always)]
+                    pub fn set_mode(&mut self, value: ::core::ffi::c_int) {
+                        unsafe {
+                            crate::detail::__crubit_set_mode__10SomeStruct___2f_2ftest_3atesting_5ftarget(self, value)
+                        }
+                    }
+                    #[doc = " Returns the value of the `enabled` bitfield."]
+                    #[inline(always)]
+                    pub fn enabled(&self) -> bool { ... }
+                    #[doc = " Sets the value of the `enabled` bitfield."]
+                    #[inline(always)]
+                    pub fn set_enabled(&mut self, value: bool) { ... }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __crubit_set_mode__10SomeStruct___2f_2ftest_3atesting_5ftarget(
+                    __this: *mut crate::SomeStruct,
+                    value: ::core::ffi::c_int
+                );
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int __crubit_get_mode__10SomeStruct___2f_2ftest_3atesting_5ftarget(
+                    const struct SomeStruct* __this
+                ) {
+                    return __this->mode;
+                }
+                extern "C" void __crubit_set_mode__10SomeStruct___2f_2ftest_3atesting_5ftarget(
+                    struct SomeStruct* __this, int value
+                ) {
+                    __this->mode = value;
+                }
+            }
+        );
+        // The layout assertions don't change.
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                const _: () = {
+                    ...
+                    assert!(::core::mem::size_of::<crate::SomeStruct>() == 8);
+                    assert!(::core::mem::align_of::<crate::SomeStruct>() == 4);
+                    ...
+                    assert!(::core::mem::offset_of!(crate::SomeStruct, last_field) == 4);
+                    ...
+                };
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_struct_with_bitfield_accessors_name_collision() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct SomeStruct final {
+                int mode : 3;
+                int flags : 3;
+                void set_mode(int);
+            }; "#,
+        )?;
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(rs_api, quote! { pub fn flags(&self) -> ::core::ffi::c_int });
+        assert_rs_not_matches!(rs_api, quote! { pub fn mode(&self) });
+        Ok(())
+    }
+
+    #[test]
+    fn test_union_with_bitfields_has_no_accessors() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            union SomeUnion final {
+                int mode : 3;
+                unsigned flags : 5;
+            }; "#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(rs_api, quote! { pub union SomeUnion });
+        assert_rs_not_matches!(rs_api, quote! { fn mode });
+        assert_rs_not_matches!(rs_api, quote! { fn set_flags });
+        assert_cc_not_matches!(rs_api_impl, quote! { __this->mode });
+        Ok(())
+    }
+
+    /// Classes with a non-public destructor shouldn't be constructible, not
+    /// even via Copy/Clone.
+    #[test]
+    fn test_trivial_nonpublic_destructor() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct Indestructible final {
+              Indestructible() = default;
+              Indestructible(int);
+              Indestructible(const Indestructible&) = default;
+              void Foo() const;
+             private:
+              ~Indestructible() = default;
+            };
+
+            Indestructible ReturnsValue();
+            void TakesValue(Indestructible);
+            void TakesReference(const Indestructible& x);
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        // It isn't available by value:
+        assert_rs_not_matches!(rs_api, quote! {Default});
+        assert_rs_not_matches!(rs_api, quote! {From});
+        assert_rs_not_matches!(rs_api, quote! {derive ( ... Copy ... )});
+        assert_rs_not_matches!(rs_api, quote! {derive ( ... Clone ... )});
+        assert_rs_not_matches!(rs_api, quote! {ReturnsValue});
+        assert_rs_not_matches!(rs_api, quote! {TakesValue});
+        // ... but it is otherwise available:
+        assert_rs_matches!(rs_api, quote! {struct Indestructible});
+        assert_rs_matches!(rs_api, quote! {fn Foo<'a>(&'a self)});
+        assert_rs_matches!(rs_api, quote! {fn TakesReference<'a>(x: &'a crate::Indestructible)});
+        Ok(())
+    }
+
+    #[test]
+    fn test_nontrivial_nonpublic_destructor() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct Indestructible final {
+              Indestructible() = default;
+              Indestructible(int);
+              Indestructible(const Indestructible&) = default;
+              void Foo() const;
+             private:
+              ~Indestructible() {}
+            };
+
+            Indestructible ReturnsValue();
+            void TakesValue(Indestructible);
+            void TakesReference(const Indestructible& x);
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        // It isn't available by value:
+        assert_rs_not_matches!(rs_api, quote! {CtorNew});
+        assert_rs_not_matches!(rs_api, quote! {ReturnsValue});
+        assert_rs_not_matches!(rs_api, quote! {TakesValue});
+        // ... but it is otherwise available:
+        assert_rs_matches!(rs_api, quote! {struct Indestructible});
+        assert_rs_matches!(rs_api, quote! {fn Foo<'a>(&'a self)});
+        assert_rs_matches!(rs_api, quote! {fn TakesReference<'a>(x: &'a crate::Indestructible)});
+        Ok(())
+    }
+
+    /// trivial abstract structs shouldn't be constructible, not even via
+    /// Copy/Clone.
+    ///
+    /// Right now, a struct can only be Copy/Clone if it's final, but that
+    /// restriction will likely be lifted later.
+    #[test]
+    fn test_trivial_abstract_by_value() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct Abstract final {
+              Abstract() = default;
+              Abstract(int);
+              Abstract(const Abstract&) = default;
+              virtual void Foo() const = 0;
+              void Nonvirtual() const;
+            };
+            void TakesAbstract(const Abstract& a);
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        // It isn't available by value:
+        assert_rs_not_matches!(rs_api, quote! {Default});
+        assert_rs_not_matches!(rs_api, quote! {From});
+        assert_rs_not_matches!(rs_api, quote! {derive ( ... Copy ... )});
+        assert_rs_not_matches!(rs_api, quote! {derive ( ... Clone ... )});
+        // ... but it is otherwise available:
+        assert_rs_matches!(rs_api, quote! {struct Abstract});
+        assert_rs_matches!(rs_api, quote! {fn Foo<'a>(&'a self)});
+        assert_rs_matches!(rs_api, quote! {fn Nonvirtual<'a>(&'a self)});
+        assert_rs_matches!(rs_api, quote! {fn TakesAbstract<'a>(a: &'a crate::Abstract)});
+        Ok(())
+    }
+
+    #[test]
+    fn test_nontrivial_abstract_by_value() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"#pragma clang lifetime_elision
+            struct Abstract final {
+              Abstract() {};
+              Abstract(int);
+              Abstract(const Abstract&) {}
+              virtual void Foo() const = 0;
+              void Nonvirtual() const;
+            };
+            void TakesAbstract(const Abstract& a);
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! {CtorNew});
+        // ... but it is otherwise available:
+        assert_rs_matches!(rs_api, quote! {struct Abstract});
+        assert_rs_matches!(rs_api, quote! {fn Foo<'a>(&'a self)});
+        assert_rs_matches!(rs_api, quote! {fn Nonvirtual<'a>(&'a self)});
+        assert_rs_matches!(rs_api, quote! {fn TakesAbstract<'a>(a: &'a crate::Abstract)});
+        Ok(())
+    }
+
+    #[test]
+    fn test_struct_with_unnamed_struct_and_union_members() -> Result<()> {
+        // This test input causes `field_decl->getName()` to return an empty string.
+        // See also:
+        // - https://en.cppreference.com/w/c/language/struct: "[...] an unnamed member
+        //   of a struct whose type is a struct without name is known as anonymous
+        //   struct."
+        // - https://rust-lang.github.io/rfcs/2102-unnamed-fields.html
+        let ir = ir_from_cc(
+            r#"
+            struct StructWithUnnamedMembers {
+              int first_field;
+
+              struct {
+                int anonymous_struct_field_1;
+                int anonymous_struct_field_2;
+              };
+              union {
+                int anonymous_union_field_1;
+                int anonymous_union_field_2;
+              };
+
+              int last_field;
+            }; "#,
+        )?;
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        // The anonymous struct and union are represented as blobs of bytes, and their
+        // members are exposed through accessors.
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[repr(C, align(4))]
+                #[__crubit::annotate(cc_type="StructWithUnnamedMembers")]
+                pub struct StructWithUnnamedMembers {
+                   pub first_field: ::core::ffi::c_int,
+                   #[doc =" Reason for representing this field as a blob of bytes:\n Unsupported type 'struct StructWithUnnamedMembers::(anonymous at ./ir_from_cc_virtual_header.h:7:15)': No generated bindings found for ''"]
+                   pub(crate) __unnamed_field1: [::core::mem::MaybeUninit<u8>; 8],
+                   #[doc =" Reason for representing this field as a blob of bytes:\n Unsupported type 'union StructWithUnnamedMembers::(anonymous at ./ir_from_cc_virtual_header.h:11:15)': No generated bindings found for ''"]
+                   pub(crate) __unnamed_field2: [::core::mem::MaybeUninit<u8>; 4],
+                   pub last_field: ::core::ffi::c_int,
                 }
+                ...
+                const _: () = {
+                    ...
+                    assert!(::core::mem::offset_of!(
+                        crate::StructWithUnnamedMembers, first_field) == 0);
+                    assert!(::core::mem::offset_of!(
+                       crate::StructWithUnnamedMembers, __unnamed_field1) == 4);
+                    assert!(::core::mem::offset_of!(
+                       crate::StructWithUnnamedMembers, __unnamed_field2) == 12);
+                    assert!(::core::mem::offset_of!(
+                       crate::StructWithUnnamedMembers, last_field) == 16);
+                    ...
+                };
             }
         );
-        assert_cc_matches!(
-            rs_api_impl,
+        assert_rs_matches!(
+            rs_api,
             quote! {
-                extern "C" int const*
-                __rust_thunk___ZNK10MyTemplateIiE5valueEv__2f_2ftest_3atesting_5ftarget(
-                        const class MyTemplate<int>*__this) {
-                    return &__this->value();
+                impl StructWithUnnamedMembers {
+                    #[doc = " Returns a reference to the `anonymous_struct_field_1` member of an anonymous struct."]
+                    #[inline(always)]
+                    pub fn anonymous_struct_field_1(&self) -> &::core::ffi::c_int {
+                        unsafe {
+                            &*(self as *const Self).cast::<u8>().add(4).cast::<::core::ffi::c_int>()
+                        }
+                    }
+                    #[doc = " Returns a mutable reference to the `anonymous_struct_field_1` member of an anonymous struct."]
+                    #[inline(always)]
+                    pub fn anonymous_struct_field_1_mut(&mut self) -> &mut ::core::ffi::c_int {
+                        unsafe {
+                            &mut *(self as *mut Self).cast::<u8>().add(4).cast::<::core::ffi::c_int>()
+                        }
+                    }
+                    ...
+                    pub fn anonymous_struct_field_2(&self) -> &::core::ffi::c_int {
+                        unsafe {
+                            &*(self as *const Self).cast::<u8>().add(8).cast::<::core::ffi::c_int>()
+                        }
+                    }
+                    ...
+                    #[doc = " Returns a reference to the `anonymous_union_field_1` member of an anonymous union."]
+                    #[doc = ""]
+                    #[doc = " # Safety"]
+                    ...
+                    pub unsafe fn anonymous_union_field_1(&self) -> &::core::ffi::c_int {
+                        unsafe {
+                            &*(self as *const Self).cast::<u8>().add(12).cast::<::core::ffi::c_int>()
+                        }
+                    }
+                    ...
+                    pub unsafe fn anonymous_union_field_2_mut(&mut self) -> &mut ::core::ffi::c_int {
+                        unsafe {
+                            &mut *(self as *mut Self).cast::<u8>().add(12).cast::<::core::ffi::c_int>()
+                        }
+                    }
                 }
             }
         );
@@ -891,182 +2887,226 @@ mod tests {
     }
 
     #[test]
-    fn test_simple_struct() -> Result<()> {
+    fn test_struct_with_anonymous_union_member_name_collision() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            #pragma clang lifetime_elision
-            struct SomeStruct final {
-                ~SomeStruct() {}
-                int public_int;
-              protected:
-                int protected_int;
-              private:
-               int private_int;
-            };
-        "#,
+            struct S {
+              union {
+                int value;
+                float ratio;
+              };
+              int value_mut() const;
+            }; "#,
         )?;
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_not_matches!(rs_api, quote! { fn value(&self) });
+        assert_rs_matches!(rs_api, quote! { pub unsafe fn ratio(&self) -> &f32 });
+        Ok(())
+    }
+
+    #[test]
+    fn test_copy_derives() {
+        let record = ir_record("S");
+        assert_eq!(generate_derives(&record), &["Clone", "Copy"]);
+    }
+
+    #[test]
+    fn test_copy_derives_not_is_trivial_abi() {
+        let mut record = ir_record("S");
+        record.is_trivial_abi = false;
+        assert_eq!(generate_derives(&record), &[""; 0]);
+    }
+
+    #[test]
+    fn test_copy_derives_ctor_deleted() {
+        let mut record = ir_record("S");
+        record.copy_constructor = ir::SpecialMemberFunc::Unavailable;
+        assert_eq!(generate_derives(&record), &[""; 0]);
+    }
+
+    #[test]
+    fn test_copy_derives_ctor_nontrivial_members() {
+        let mut record = ir_record("S");
+        record.copy_constructor = ir::SpecialMemberFunc::NontrivialMembers;
+        assert_eq!(generate_derives(&record), &[""; 0]);
+    }
 
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+    #[test]
+    fn test_copy_derives_ctor_nontrivial_self() {
+        let mut record = ir_record("S");
+        record.copy_constructor = ir::SpecialMemberFunc::NontrivialUserDefined;
+        assert_eq!(generate_derives(&record), &[""; 0]);
+    }
+
+    /// In Rust, a Drop type cannot be Copy.
+    #[test]
+    fn test_copy_derives_dtor_nontrivial_self() {
+        let mut record = ir_record("S");
+        for definition in
+            [ir::SpecialMemberFunc::NontrivialUserDefined, ir::SpecialMemberFunc::NontrivialMembers]
+        {
+            record.destructor = definition;
+            assert_eq!(generate_derives(&record), &["Clone"]);
+        }
+    }
+
+    #[test]
+    fn test_base_class_subobject_layout() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            // We use a class here to force `Derived::z` to live inside the tail padding of `Base`.
+            // On the Itanium ABI, this would not happen if `Base` were a POD type.
+            class Base {__INT64_TYPE__ x; char y;};
+            struct Derived final : Base {__INT16_TYPE__ z;};
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[::ctor::recursively_pinned(PinnedDrop)]
-                #[repr(C, align(4))]
-                #[__crubit::annotate(cc_type="SomeStruct")]
-                pub struct SomeStruct {
-                    __non_field_data: [::core::mem::MaybeUninit<u8>; 0],
-                    pub public_int: ::core::ffi::c_int,
-                    #[doc = " Reason for representing this field as a blob of bytes:\n Types of non-public C++ fields can be elided away"]
-                    pub(crate) protected_int: [::core::mem::MaybeUninit<u8>; 4],
-                    #[doc = " Reason for representing this field as a blob of bytes:\n Types of non-public C++ fields can be elided away"]
-                    pub(crate) private_int: [::core::mem::MaybeUninit<u8>; 4],
+                #[repr(C, align(8))]
+                #[__crubit::annotate(cc_type="Derived")]
+                pub struct Derived {
+                    __non_field_data: [::core::mem::MaybeUninit<u8>; 10],
+                    pub z: ::core::ffi::c_short,
                 }
             }
         );
+        Ok(())
+    }
+
+    /// The same as test_base_class_subobject_layout, but with multiple
+    /// inheritance.
+    #[test]
+    fn test_base_class_multiple_inheritance_subobject_layout() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            class Base1 {__INT64_TYPE__ x;};
+            class Base2 {char y;};
+            struct Derived final : Base1, Base2 {__INT16_TYPE__ z;};
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                const _ : () = {
-                    ...
-                    assert!(::core::mem::size_of::<crate::SomeStruct>() == 12);
-                    assert!(::core::mem::align_of::<crate::SomeStruct>() == 4);
-                    static_assertions::assert_not_impl_any!(crate::SomeStruct: Copy);
-                    static_assertions::assert_impl_all!(crate::SomeStruct: Drop);
-                    assert!(::core::mem::offset_of!(crate::SomeStruct, public_int) == 0);
-                    assert!(::core::mem::offset_of!(crate::SomeStruct, protected_int) == 4);
-                    assert!(::core::mem::offset_of!(crate::SomeStruct, private_int) == 8);
-                    ...
-                };
+                #[repr(C, align(8))]
+                #[__crubit::annotate(cc_type="Derived")]
+                pub struct Derived {
+                    __non_field_data: [::core::mem::MaybeUninit<u8>; 10],
+                    pub z: ::core::ffi::c_short,
+                }
             }
         );
-        assert_cc_matches!(
-            rs_api_impl,
+        Ok(())
+    }
+
+    /// The same as test_base_class_subobject_layout, but with a chain of
+    /// inheritance.
+    #[test]
+    fn test_base_class_deep_inheritance_subobject_layout() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            class Base1 {__INT64_TYPE__ x;};
+            class Base2 : Base1 {char y;};
+            struct Derived final : Base2 {__INT16_TYPE__ z;};
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
             quote! {
-                extern "C" void __rust_thunk___ZN10SomeStructD1Ev(struct SomeStruct * __this) {
-                    std::destroy_at(__this);
+                #[repr(C, align(8))]
+                #[__crubit::annotate(cc_type="Derived")]
+                pub struct Derived {
+                    __non_field_data: [::core::mem::MaybeUninit<u8>; 10],
+                    pub z: ::core::ffi::c_short,
                 }
             }
         );
-        assert_cc_matches!(
-            rs_api_impl,
+        Ok(())
+    }
+
+    /// For derived classes with no data members, we can't use the offset of the
+    /// first member to determine the size of the base class subobjects.
+    #[test]
+    fn test_base_class_subobject_fieldless_layout() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            class Base {__INT64_TYPE__ x; char y;};
+            struct Derived final : Base {};
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
             quote! {
-                static_assert(CRUBIT_SIZEOF(struct SomeStruct) == 12);
-                static_assert(alignof(struct SomeStruct) == 4);
-                static_assert(CRUBIT_OFFSET_OF(public_int, struct SomeStruct) == 0);
+                #[repr(C, align(8))]
+                #[__crubit::annotate(cc_type="Derived")]
+                pub struct Derived {
+                    __non_field_data: [::core::mem::MaybeUninit<u8>; 16],
+                }
             }
         );
         Ok(())
     }
 
     #[test]
-    fn test_struct_vs_class() -> Result<()> {
+    fn test_base_class_subobject_empty_fieldless() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            #pragma clang lifetime_elision
-            struct SomeStruct final {
-                SomeStruct() {}
-                int field;
-            };
-            class SomeClass final {
-              public:
-                SomeClass() {}
-                int field;
-            };
+            class Base {};
+            struct Derived final : Base {};
         "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
-
-        // A Rust `struct` is generated for both `SomeStruct` and `SomeClass`.
-        assert_rs_matches!(rs_api, quote! { pub struct SomeStruct },);
-        assert_rs_matches!(rs_api, quote! { pub struct SomeClass },);
-
-        // But in C++ we still should refer to `struct SomeStruct` and `class
-        // SomeClass`. See also b/238212337.
-        assert_cc_matches!(rs_api_impl, quote! { struct SomeStruct * __this });
-        assert_cc_matches!(rs_api_impl, quote! { class SomeClass * __this });
-        assert_cc_matches!(
-            rs_api_impl,
-            quote! { static_assert(CRUBIT_SIZEOF(struct SomeStruct) == 4); }
-        );
-        assert_cc_matches!(
-            rs_api_impl,
-            quote! { static_assert(CRUBIT_SIZEOF(class SomeClass) == 4); }
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[repr(C)]
+                #[__crubit::annotate(cc_type="Derived")]
+                pub struct Derived {
+                    ...
+                    __non_field_data: [::core::mem::MaybeUninit<u8>; 1],
+                }
+            }
         );
         Ok(())
     }
 
     #[test]
-    fn test_struct_vs_typedefed_struct() -> Result<()> {
+    fn test_base_class_subobject_empty() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            #pragma clang lifetime_elision
-            struct SomeStruct final {
-              int x;
-            } __attribute__((aligned(16)));
-            typedef struct {
-              int x;
-            } SomeAnonStruct __attribute__((aligned(16)));
+            class Base {};
+            struct Derived final : Base {
+                __INT16_TYPE__ x;
+            };
         "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
-
-        // A `struct` is generated for both `SomeStruct` and `SomeAnonStruct`, both
-        // in Rust and in C++.
-        assert_rs_matches!(rs_api, quote! { pub struct SomeStruct },);
-        assert_rs_matches!(rs_api, quote! { pub struct SomeAnonStruct },);
-        assert_rs_matches!(rs_api_impl, quote! { struct SomeStruct * __this },);
-        assert_rs_matches!(rs_api_impl, quote! { SomeAnonStruct * __this },);
-
-        // In C++, both have align == 16, but size for `SomeAnonStruct` is not aligned.
-        // `SomeAnonStruct` won't have `struct` in the assert.
-        assert_cc_matches!(
-            rs_api_impl,
-            quote! { static_assert(alignof(struct SomeStruct) == 16); }
-        );
-        assert_cc_matches!(rs_api_impl, quote! { static_assert(alignof(SomeAnonStruct) == 16); });
-        assert_cc_matches!(
-            rs_api_impl,
-            quote! { static_assert(CRUBIT_SIZEOF(struct SomeStruct) == 16); }
-        );
-        assert_cc_matches!(
-            rs_api_impl,
-            quote! { static_assert(CRUBIT_SIZEOF(SomeAnonStruct) == 16); }
-        );
-
-        // In Rust, both have align == 16 and size == 16.
-        assert_rs_matches!(
-            rs_api,
-            quote! { assert!(::core::mem::size_of::<crate::SomeStruct>() == 16); }
-        );
-        assert_rs_matches!(
-            rs_api,
-            quote! { assert!(::core::mem::align_of::<crate::SomeStruct>() == 16); }
-        );
-        assert_rs_matches!(
-            rs_api,
-            quote! { assert!(::core::mem::size_of::<crate::SomeAnonStruct>() == 16); }
-        );
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
-            quote! { assert!(::core::mem::align_of::<crate::SomeAnonStruct>() == 16); }
+            quote! {
+                #[__crubit::annotate(cc_type="Derived")]
+                pub struct Derived {
+                    pub x: ::core::ffi::c_short,
+                }
+            }
         );
-
         Ok(())
     }
 
+    /// Non-aggregate structs can't be directly initialized, because we add
+    /// a zero-sized private field to the bindings.
     #[test]
-    fn test_record_with_unsupported_field_type() -> Result<()> {
-        // Using a nested struct because it's currently not supported.
-        // But... any other unsupported type would also work for this test.
+    fn test_non_aggregate_struct_private_field() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            struct StructWithUnsupportedField {
-              struct NestedStruct {
-                int nested_field;
-              };
+            struct NonAggregate {
+                NonAggregate() {}
 
-              // Doc comment for `my_field`.
-              NestedStruct my_field;
+                __INT16_TYPE__ x = 0;
             };
         "#,
         )?;
@@ -1074,446 +3114,677 @@ mod tests {
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(C, align(4))]
-                #[__crubit::annotate(cc_type="StructWithUnsupportedField")]
-                pub struct StructWithUnsupportedField {
-                    #[doc = " Doc comment for `my_field`.\n \n Reason for representing this field as a blob of bytes:\n Unsupported type 'struct StructWithUnsupportedField::NestedStruct': No generated bindings found for 'NestedStruct'"]
-                    pub(crate) my_field: [::core::mem::MaybeUninit<u8>; 4],
+                pub struct NonAggregate {
+                    __non_field_data:  [::core::mem::MaybeUninit<u8>; 0],
+                    pub x: ::core::ffi::c_short,
                 }
-                ...
-                const _: () = {
-                    ...
-                    assert!(
-                    ::core::mem::offset_of!(crate::StructWithUnsupportedField, my_field) == 0);
-                    ...
-                };
             }
         );
         Ok(())
     }
 
-    /// This is a regression test for b/283835873 where the alignment of the
-    /// generated struct was wrong/missing.
+    /// When a field is [[no_unique_address]], it occupies the space up to the
+    /// next field.
     #[test]
-    fn test_struct_with_only_bitfields() -> Result<()> {
+    fn test_no_unique_address() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-                struct SomeStruct {
-                  char32_t code_point : 31;
-                  enum : char32_t {
-                    ok = 0,
-                    error = 1
-                  } status : 1;
-                };
-                static_assert(sizeof(SomeStruct) == 4);
-                static_assert(alignof(SomeStruct) == 4);
-            "#,
+            class Field1 {__INT64_TYPE__ x;};
+            class Field2 {char y;};
+            struct Struct final {
+                [[no_unique_address]] Field1 field1;
+                [[no_unique_address]] Field2 field2;
+                __INT16_TYPE__ z;
+            };
+        "#,
         )?;
-        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-               #[repr(C, align(4))]
-                #[__crubit::annotate(cc_type="SomeStruct")]
-               pub struct SomeStruct { ...  }
+                #[repr(C, align(8))]
+                #[__crubit::annotate(cc_type="Struct")]
+                pub struct Struct {
+                    ...
+                    pub(crate) field1: [::core::mem::MaybeUninit<u8>; 8],
+                    ...
+                    pub(crate) field2: [::core::mem::MaybeUninit<u8>; 2],
+                    pub z: ::core::ffi::c_short,
+                }
             }
         );
         assert_rs_matches!(
             rs_api,
             quote! {
-                const _: () = {
-                    ...
-                    assert!(::core::mem::size_of::<crate::SomeStruct>() == 4);
-                    assert!(::core::mem::align_of::<crate::SomeStruct>() == 4);
-                    ...
-                };
+                impl Struct {
+                    pub fn field1(&self) -> &crate::Field1 {
+                        unsafe {
+                            let ptr = (self as *const Self as *const u8).offset(0);
+                            &*(ptr as *const crate::Field1)
+                        }
+                    }
+                    pub fn field2(&self) -> &crate::Field2 {
+                        unsafe {
+                            let ptr = (self as *const Self as *const u8).offset(8);
+                            &*(ptr as *const crate::Field2)
+                        }
+                    }
+                }
             }
         );
         Ok(())
     }
 
+    /// When a [[no_unique_address]] field is the last one, it occupies the rest
+    /// of the object.
     #[test]
-    fn test_struct_with_unnamed_bitfield_member() -> Result<()> {
-        // This test input causes `field_decl->getName()` to return an empty string.
-        // This example is based on `struct timex` from
-        // /usr/grte/v5/include/bits/timex.h
+    fn test_no_unique_address_last_field() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            struct SomeStruct {
-                int first_field;
-                int :32;
-                int last_field;
-            }; "#,
+            class Field1 {__INT64_TYPE__ x;};
+            class Field2 {char y;};
+            struct Struct final {
+                [[no_unique_address]] Field1 field1;
+                [[no_unique_address]] Field2 field2;
+            };
+        "#,
         )?;
-        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(C, align(4))]
-                #[__crubit::annotate(cc_type="SomeStruct")]
-                pub struct SomeStruct {
-                    pub first_field: ::core::ffi::c_int, ...
-                    __bitfields1: [::core::mem::MaybeUninit<u8>; 4],
-                    pub last_field: ::core::ffi::c_int,
-                }
-                ...
-                const _: () = {
+                #[repr(C, align(8))]
+                #[__crubit::annotate(cc_type="Struct")]
+                pub struct Struct {
                     ...
-                    assert!(::core::mem::offset_of!(crate::SomeStruct, first_field) == 0);
-                    assert!(::core::mem::offset_of!(crate::SomeStruct, last_field) == 8);
+                    pub(crate) field1: [::core::mem::MaybeUninit<u8>; 8],
                     ...
-                };
+                    pub(crate) field2: [::core::mem::MaybeUninit<u8>; 8],
+                }
             }
         );
         Ok(())
     }
 
-    /// Classes with a non-public destructor shouldn't be constructible, not
-    /// even via Copy/Clone.
     #[test]
-    fn test_trivial_nonpublic_destructor() -> Result<()> {
+    fn test_no_unique_address_empty() -> Result<()> {
         let ir = ir_from_cc(
-            r#"#pragma clang lifetime_elision
-            struct Indestructible final {
-              Indestructible() = default;
-              Indestructible(int);
-              Indestructible(const Indestructible&) = default;
-              void Foo() const;
-             private:
-              ~Indestructible() = default;
+            r#"
+            class Field {};
+            struct Struct final {
+                // Doc comment for no_unique_address empty class type field.
+                [[no_unique_address]] Field field;
+                int x;
             };
-
-            Indestructible ReturnsValue();
-            void TakesValue(Indestructible);
-            void TakesReference(const Indestructible& x);
         "#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
-        // It isn't available by value:
-        assert_rs_not_matches!(rs_api, quote! {Default});
-        assert_rs_not_matches!(rs_api, quote! {From});
-        assert_rs_not_matches!(rs_api, quote! {derive ( ... Copy ... )});
-        assert_rs_not_matches!(rs_api, quote! {derive ( ... Clone ... )});
-        assert_rs_not_matches!(rs_api, quote! {ReturnsValue});
-        assert_rs_not_matches!(rs_api, quote! {TakesValue});
-        // ... but it is otherwise available:
-        assert_rs_matches!(rs_api, quote! {struct Indestructible});
-        assert_rs_matches!(rs_api, quote! {fn Foo<'a>(&'a self)});
-        assert_rs_matches!(rs_api, quote! {fn TakesReference<'a>(x: &'a crate::Indestructible)});
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[repr(C)]
+                #[__crubit::annotate(cc_type="Struct")]
+                pub struct Struct {
+                    pub x: ::core::ffi::c_int,
+                }
+                ...
+                impl Struct {
+                  # [doc = " Doc comment for no_unique_address empty class type field."]
+                  pub fn field(&self) -> &crate::Field {
+                        unsafe {
+                            let ptr = (self as *const Self as *const u8).offset(0);
+                            &*(ptr as *const crate::Field)
+                        }
+                      }
+                }
+                ...
+            }
+        );
         Ok(())
     }
 
     #[test]
-    fn test_nontrivial_nonpublic_destructor() -> Result<()> {
+    fn test_base_class_subobject_empty_last_field() -> Result<()> {
         let ir = ir_from_cc(
-            r#"#pragma clang lifetime_elision
-            struct Indestructible final {
-              Indestructible() = default;
-              Indestructible(int);
-              Indestructible(const Indestructible&) = default;
-              void Foo() const;
-             private:
-              ~Indestructible() {}
+            r#"
+            class Field {};
+            struct Struct final {
+                // Doc comment for no_unique_address empty class type field.
+                [[no_unique_address]] Field field;
             };
-
-            Indestructible ReturnsValue();
-            void TakesValue(Indestructible);
-            void TakesReference(const Indestructible& x);
         "#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
-        // It isn't available by value:
-        assert_rs_not_matches!(rs_api, quote! {CtorNew});
-        assert_rs_not_matches!(rs_api, quote! {ReturnsValue});
-        assert_rs_not_matches!(rs_api, quote! {TakesValue});
-        // ... but it is otherwise available:
-        assert_rs_matches!(rs_api, quote! {struct Indestructible});
-        assert_rs_matches!(rs_api, quote! {fn Foo<'a>(&'a self)});
-        assert_rs_matches!(rs_api, quote! {fn TakesReference<'a>(x: &'a crate::Indestructible)});
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[repr(C)]
+                #[__crubit::annotate(cc_type="Struct")]
+                pub struct Struct {}
+                ...
+                impl Struct {
+                  # [doc = " Doc comment for no_unique_address empty class type field."]
+                  pub fn field(&self) -> &crate::Field {
+                      unsafe {
+                          let ptr = (self as *const Self as *const u8).offset(0);
+                          &*(ptr as *const crate::Field)
+                      }
+                  }
+              }
+              ...
+            }
+        );
         Ok(())
     }
 
-    /// trivial abstract structs shouldn't be constructible, not even via
-    /// Copy/Clone.
-    ///
-    /// Right now, a struct can only be Copy/Clone if it's final, but that
-    /// restriction will likely be lifted later.
     #[test]
-    fn test_trivial_abstract_by_value() -> Result<()> {
+    fn test_awaitable() -> Result<()> {
         let ir = ir_from_cc(
-            r#"#pragma clang lifetime_elision
-            struct Abstract final {
-              Abstract() = default;
-              Abstract(int);
-              Abstract(const Abstract&) = default;
-              virtual void Foo() const = 0;
-              void Nonvirtual() const;
-            };
-            void TakesAbstract(const Abstract& a);
-        "#,
+            r#" #pragma clang lifetime_elision
+            struct [[clang::annotate("crubit_awaitable", "poll=Resume", "get=Get")]] Task final {
+                bool Resume();
+                int Get() const;
+            };"#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
-        // It isn't available by value:
-        assert_rs_not_matches!(rs_api, quote! {Default});
-        assert_rs_not_matches!(rs_api, quote! {From});
-        assert_rs_not_matches!(rs_api, quote! {derive ( ... Copy ... )});
-        assert_rs_not_matches!(rs_api, quote! {derive ( ... Clone ... )});
-        // ... but it is otherwise available:
-        assert_rs_matches!(rs_api, quote! {struct Abstract});
-        assert_rs_matches!(rs_api, quote! {fn Foo<'a>(&'a self)});
-        assert_rs_matches!(rs_api, quote! {fn Nonvirtual<'a>(&'a self)});
-        assert_rs_matches!(rs_api, quote! {fn TakesAbstract<'a>(a: &'a crate::Abstract)});
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl ::core::future::Future for Task {
+                    type Output = ::core::ffi::c_int;
+                    fn poll(
+                        mut self: ::core::pin::Pin<&mut Self>,
+                        cx: &mut ::core::task::Context<'_>
+                    ) -> ::core::task::Poll<Self::Output> {
+                        if Task::Resume(self.as_mut().get_mut()) {
+                            ::core::task::Poll::Ready(Task::Get(&*self))
+                        } else {
+                            cx.waker().wake_by_ref();
+                            ::core::task::Poll::Pending
+                        }
+                    }
+                }
+            }
+        );
         Ok(())
     }
 
     #[test]
-    fn test_nontrivial_abstract_by_value() -> Result<()> {
+    fn test_awaitable_poll_must_return_bool() -> Result<()> {
         let ir = ir_from_cc(
-            r#"#pragma clang lifetime_elision
-            struct Abstract final {
-              Abstract() {};
-              Abstract(int);
-              Abstract(const Abstract&) {}
-              virtual void Foo() const = 0;
-              void Nonvirtual() const;
-            };
-            void TakesAbstract(const Abstract& a);
-        "#,
+            r#" #pragma clang lifetime_elision
+            struct [[clang::annotate("crubit_awaitable", "poll=Resume", "get=Get")]] Task final {
+                int Resume();
+                int Get() const;
+            };"#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
-        assert_rs_not_matches!(rs_api, quote! {CtorNew});
-        // ... but it is otherwise available:
-        assert_rs_matches!(rs_api, quote! {struct Abstract});
-        assert_rs_matches!(rs_api, quote! {fn Foo<'a>(&'a self)});
-        assert_rs_matches!(rs_api, quote! {fn Nonvirtual<'a>(&'a self)});
-        assert_rs_matches!(rs_api, quote! {fn TakesAbstract<'a>(a: &'a crate::Abstract)});
+        assert_rs_not_matches!(rs_api, quote! { impl ::core::future::Future for Task });
         Ok(())
     }
 
     #[test]
-    fn test_struct_with_unnamed_struct_and_union_members() -> Result<()> {
-        // This test input causes `field_decl->getName()` to return an empty string.
-        // See also:
-        // - https://en.cppreference.com/w/c/language/struct: "[...] an unnamed member
-        //   of a struct whose type is a struct without name is known as anonymous
-        //   struct."
-        // - https://rust-lang.github.io/rfcs/2102-unnamed-fields.html
+    fn test_map_visitor() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            struct StructWithUnnamedMembers {
-              int first_field;
-
-              struct {
-                int anonymous_struct_field_1;
-                int anonymous_struct_field_2;
-              };
-              union {
-                int anonymous_union_field_1;
-                int anonymous_union_field_2;
-              };
-
-              int last_field;
-            }; "#,
+            struct Entry final {
+                int first;
+                float second;
+            };
+            struct [[clang::annotate("crubit_map_visitor", "begin=begin", "end=end")]] Registry final {
+                using key_type = int;
+                using mapped_type = float;
+                const Entry* begin() const;
+                const Entry* end() const;
+            };"#,
         )?;
-        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
-        // TODO(b/200067824): Once nested structs anhd unions are supported,
-        // `__unnamed_field1` and `__unnamed_field2` should have a real, usable
-        // type.
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(C, align(4))]
-                #[__crubit::annotate(cc_type="StructWithUnnamedMembers")]
-                pub struct StructWithUnnamedMembers {
-                   pub first_field: ::core::ffi::c_int,
-                   #[doc =" Reason for representing this field as a blob of bytes:\n Unsupported type 'struct StructWithUnnamedMembers::(anonymous at ./ir_from_cc_virtual_header.h:7:15)': No generated bindings found for ''"]
-                   pub(crate) __unnamed_field1: [::core::mem::MaybeUninit<u8>; 8],
-                   #[doc =" Reason for representing this field as a blob of bytes:\n Unsupported type 'union StructWithUnnamedMembers::(anonymous at ./ir_from_cc_virtual_header.h:11:15)': No generated bindings found for ''"]
-                   pub(crate) __unnamed_field2: [::core::mem::MaybeUninit<u8>; 4],
-                   pub last_field: ::core::ffi::c_int,
-                }
-                ...
-                const _: () = {
-                    ...
-                    assert!(::core::mem::offset_of!(
-                        crate::StructWithUnnamedMembers, first_field) == 0);
-                    assert!(::core::mem::offset_of!(
-                       crate::StructWithUnnamedMembers, __unnamed_field1) == 4);
-                    assert!(::core::mem::offset_of!(
-                       crate::StructWithUnnamedMembers, __unnamed_field2) == 12);
-                    assert!(::core::mem::offset_of!(
-                       crate::StructWithUnnamedMembers, last_field) == 16);
+                impl Registry {
                     ...
-                };
+                    #[inline(always)]
+                    pub fn for_each<F: FnMut(&::core::ffi::c_int, &f32)>(&self, mut f: F) {
+                        unsafe extern "C" fn __visit<F: FnMut(&::core::ffi::c_int, &f32)>(
+                            __f: *mut ::core::ffi::c_void,
+                            __key: *const ::core::ffi::c_int,
+                            __value: *const f32,
+                        ) {
+                            unsafe { (*(__f as *mut F))(&*__key, &*__value) }
+                        }
+                        unsafe {
+                            crate::detail::__crubit_for_each__8Registry___2f_2ftest_3atesting_5ftarget(
+                                self,
+                                &mut f as *mut F as *mut ::core::ffi::c_void,
+                                __visit::<F>,
+                            )
+                        }
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __crubit_for_each__8Registry___2f_2ftest_3atesting_5ftarget(
+                    __this: &crate::Registry,
+                    __f: *mut ::core::ffi::c_void,
+                    __visit: unsafe extern "C" fn(
+                        *mut ::core::ffi::c_void,
+                        *const ::core::ffi::c_int,
+                        *const f32
+                    )
+                );
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void __crubit_for_each__8Registry___2f_2ftest_3atesting_5ftarget(
+                    const struct Registry* __this,
+                    void* __f,
+                    void (*__visit)(void*, int const*, float const*)
+                ) {
+                    for (auto __it = __this->begin(); __it != __this->end(); ++__it) {
+                        __visit(__f, &__it->first, &__it->second);
+                    }
+                }
             }
         );
         Ok(())
     }
 
     #[test]
-    fn test_copy_derives() {
-        let record = ir_record("S");
-        assert_eq!(generate_derives(&record), &["Clone", "Copy"]);
+    fn test_map_visitor_requires_experimental_features() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"
+            struct Entry final {
+                int first;
+                float second;
+            };
+            struct [[clang::annotate("crubit_map_visitor", "begin=begin", "end=end")]] Registry final {
+                using key_type = int;
+                using mapped_type = float;
+                const Entry* begin() const;
+                const Entry* end() const;
+            };"#,
+        )?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn for_each });
+        Ok(())
     }
 
     #[test]
-    fn test_copy_derives_not_is_trivial_abi() {
-        let mut record = ir_record("S");
-        record.is_trivial_abi = false;
-        assert_eq!(generate_derives(&record), &[""; 0]);
+    fn test_map_visitor_colliding_with_method() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct Entry final {
+                int first;
+                float second;
+            };
+            struct [[clang::annotate("crubit_map_visitor", "begin=begin", "end=end")]] Registry final {
+                using key_type = int;
+                using mapped_type = float;
+                const Entry* begin() const;
+                const Entry* end() const;
+                void for_each() const;
+            };"#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_not_matches!(rs_api, quote! { fn for_each<F });
+        assert_cc_not_matches!(rs_api_impl, quote! { __crubit_for_each__ });
+        assert_rs_matches!(rs_api, {
+            let txt = "The `for_each` method of the `crubit_map_visitor` `Registry` is not \
+                       generated, because it collides with a method of `Registry`";
+            quote! { __COMMENT__ #txt }
+        });
+        Ok(())
     }
 
     #[test]
-    fn test_copy_derives_ctor_deleted() {
-        let mut record = ir_record("S");
-        record.copy_constructor = ir::SpecialMemberFunc::Unavailable;
-        assert_eq!(generate_derives(&record), &[""; 0]);
+    fn test_map_visitor_thunk_export_macro() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct Entry final {
+                int first;
+                float second;
+            };
+            struct [[clang::annotate("crubit_map_visitor", "begin=begin", "end=end")]] Registry final {
+                using key_type = int;
+                using mapped_type = float;
+                const Entry* begin() const;
+                const Entry* end() const;
+            };"#,
+        )?;
+        let rs_api_impl = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { thunk_export_macro: "MYLIB_EXPORT".into(), ..Default::default() },
+        )?
+        .rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" MYLIB_EXPORT void
+                __crubit_for_each__8Registry___2f_2ftest_3atesting_5ftarget(...) { ... }
+            }
+        );
+        Ok(())
     }
 
-    #[test]
-    fn test_copy_derives_ctor_nontrivial_members() {
-        let mut record = ir_record("S");
-        record.copy_constructor = ir::SpecialMemberFunc::NontrivialMembers;
-        assert_eq!(generate_derives(&record), &[""; 0]);
-    }
+    /// A minimal `std::vector`, for tests which don't use the standard library.
+    const STD_VECTOR_CC: &str = r#"
+        namespace std {
+        template <typename T>
+        class allocator {};
+        template <typename T, typename Alloc = allocator<T>>
+        class vector final {
+          public:
+            const T* data() const;
+            decltype(sizeof(0)) size() const;
+            void push_back(const T& value);
+          private:
+            T* begin_;
+            T* end_;
+            T* capacity_;
+        };
+        }  // namespace std
+    "#;
 
     #[test]
-    fn test_copy_derives_ctor_nontrivial_self() {
-        let mut record = ir_record("S");
-        record.copy_constructor = ir::SpecialMemberFunc::NontrivialUserDefined;
-        assert_eq!(generate_derives(&record), &[""; 0]);
+    fn test_std_vector() -> Result<()> {
+        let ir = ir_from_cc(&format!("{STD_VECTOR_CC} using IntVector = std::vector<int>;"))?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn as_slice(&self) -> &[::core::ffi::c_int] {
+                    let mut __size = 0usize;
+                    unsafe {
+                        let __data = crate::detail::...(self, &mut __size);
+                        if __size == 0 {
+                            &[]
+                        } else {
+                            ::core::slice::from_raw_parts(__data, __size)
+                        }
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn push_back(&mut self, value: ::core::ffi::c_int) {
+                    unsafe { crate::detail::...(self, &value) }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl<'a> ::core::iter::IntoIterator for &'a __CcTemplateInstSt6vectorIiSaIiEE {
+                    type Item = &'a ::core::ffi::c_int;
+                    type IntoIter = ::core::slice::Iter<'a, ::core::ffi::c_int>;
+                    #[inline(always)]
+                    fn into_iter(self) -> Self::IntoIter {
+                        self.as_slice().iter()
+                    }
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int const* ...(const ... * __this, size_t* __size) {
+                    *__size = __this->size();
+                    return __this->data();
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void ...(... * __this, int const* __value) {
+                    __this->push_back(*__value);
+                }
+            }
+        );
+        Ok(())
     }
 
-    /// In Rust, a Drop type cannot be Copy.
+    /// `push_back` is only provided for `Copy` elements, so the C++ `push_back`
+    /// keeps its bindings otherwise.
     #[test]
-    fn test_copy_derives_dtor_nontrivial_self() {
-        let mut record = ir_record("S");
-        for definition in
-            [ir::SpecialMemberFunc::NontrivialUserDefined, ir::SpecialMemberFunc::NontrivialMembers]
-        {
-            record.destructor = definition;
-            assert_eq!(generate_derives(&record), &["Clone"]);
-        }
+    fn test_std_vector_of_non_copy_elements() -> Result<()> {
+        let ir = ir_from_cc(&format!(
+            r#"#pragma clang lifetime_elision
+            {STD_VECTOR_CC}
+            struct [[clang::trivial_abi]] NonCopy final {{
+                ~NonCopy();
+                int x;
+            }};
+            using NonCopyVector = std::vector<NonCopy>;"#
+        ))?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub fn as_slice(&self) -> &[crate::NonCopy] });
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn push_back<'a, 'b>(&'a mut self, value: &'b crate::NonCopy) }
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_base_class_subobject_layout() -> Result<()> {
-        let ir = ir_from_cc(
-            r#"
-            // We use a class here to force `Derived::z` to live inside the tail padding of `Base`.
-            // On the Itanium ABI, this would not happen if `Base` were a POD type.
-            class Base {__INT64_TYPE__ x; char y;};
-            struct Derived final : Base {__INT16_TYPE__ z;};
-        "#,
-        )?;
+    fn test_std_vector_of_bool_is_not_a_slice() -> Result<()> {
+        let ir = ir_from_cc(&format!("{STD_VECTOR_CC} using BoolVector = std::vector<bool>;"))?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { fn as_slice });
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_ext_traits() -> Result<()> {
+        let cc = r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                int Get() const;
+                void Set(int value);
+                static SomeStruct Create();
+            };"#;
+        let rs_api = generate_bindings_tokens(ir_from_cc(cc)?)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { SomeStructExt });
+
+        let rs_api = generate_bindings_tokens_with_options(
+            ir_from_cc(cc)?,
+            GeneratorOptions { record_ext_traits: true, ..Default::default() },
+        )?
+        .rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(C, align(8))]
-                #[__crubit::annotate(cc_type="Derived")]
-                pub struct Derived {
-                    __non_field_data: [::core::mem::MaybeUninit<u8>; 10],
-                    pub z: ::core::ffi::c_short,
+                pub trait SomeStructExt {
+                    fn Get<'a>(&'a self) -> ::core::ffi::c_int;
+                    fn Set<'a>(&'a mut self, value: ::core::ffi::c_int);
+                }
+
+                impl SomeStructExt for SomeStruct {
+                    #[inline(always)]
+                    fn Get<'a>(&'a self) -> ::core::ffi::c_int {
+                        SomeStruct::Get(self)
+                    }
+                    #[inline(always)]
+                    fn Set<'a>(&'a mut self, value: ::core::ffi::c_int) {
+                        SomeStruct::Set(self, value)
+                    }
                 }
             }
         );
         Ok(())
     }
 
-    /// The same as test_base_class_subobject_layout, but with multiple
-    /// inheritance.
     #[test]
-    fn test_base_class_multiple_inheritance_subobject_layout() -> Result<()> {
+    fn test_record_ext_trait_collision() -> Result<()> {
         let ir = ir_from_cc(
-            r#"
-            class Base1 {__INT64_TYPE__ x;};
-            class Base2 {char y;};
-            struct Derived final : Base1, Base2 {__INT16_TYPE__ z;};
-        "#,
+            r#"#pragma clang lifetime_elision
+            struct SomeStruct final {
+                int Get() const;
+            };
+            struct SomeStructExt final {};"#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { record_ext_traits: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_not_matches!(rs_api, quote! { pub trait SomeStructExt });
+        assert_rs_matches!(rs_api, quote! { pub struct SomeStructExt });
+        assert_cc_matches!(rs_api, {
+            let txt = "The extension trait `SomeStructExt` of `SomeStruct` is not generated, \
+                       because it collides with another item";
+            quote! { __COMMENT__ #txt }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_extra_rust_impl() -> Result<()> {
+        let path =
+            std::path::Path::new(&std::env::var("TEST_TMPDIR")?).join("test_extra_rust_impl.rs");
+        std::fs::write(
+            &path,
+            "impl Point { pub fn manhattan_length(&self) -> i32 { self.x.abs() + self.y.abs() } }",
         )?;
+        let ir = ir_from_cc(&format!(
+            r#"
+            struct [[clang::annotate("crubit_extra_rust_impl", "{}")]] Point final {{
+                int x;
+                int y;
+            }};"#,
+            path.display()
+        ))?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(C, align(8))]
-                #[__crubit::annotate(cc_type="Derived")]
-                pub struct Derived {
-                    __non_field_data: [::core::mem::MaybeUninit<u8>; 10],
-                    pub z: ::core::ffi::c_short,
+                pub struct Point { ... }
+                ...
+                impl Point {
+                    pub fn manhattan_length(&self) -> i32 {
+                        self.x.abs() + self.y.abs()
+                    }
                 }
             }
         );
         Ok(())
     }
 
-    /// The same as test_base_class_subobject_layout, but with a chain of
-    /// inheritance.
     #[test]
-    fn test_base_class_deep_inheritance_subobject_layout() -> Result<()> {
-        let ir = ir_from_cc(
+    fn test_extra_rust_impl_with_invalid_tokens() -> Result<()> {
+        let path = std::path::Path::new(&std::env::var("TEST_TMPDIR")?)
+            .join("test_extra_rust_impl_with_invalid_tokens.rs");
+        std::fs::write(&path, "impl Point {")?;
+        let ir = ir_from_cc(&format!(
             r#"
-            class Base1 {__INT64_TYPE__ x;};
-            class Base2 : Base1 {char y;};
-            struct Derived final : Base2 {__INT16_TYPE__ z;};
-        "#,
-        )?;
+            struct [[clang::annotate("crubit_extra_rust_impl", "{}")]] Point final {{
+                int x;
+            }};"#,
+            path.display()
+        ))?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
-        assert_rs_matches!(
-            rs_api,
-            quote! {
-                #[repr(C, align(8))]
-                #[__crubit::annotate(cc_type="Derived")]
-                pub struct Derived {
-                    __non_field_data: [::core::mem::MaybeUninit<u8>; 10],
-                    pub z: ::core::ffi::c_short,
-                }
-            }
+        assert_rs_not_matches!(rs_api, quote! { pub struct Point });
+        assert!(
+            rs_api
+                .to_string()
+                .contains("Failed to parse the `crubit_extra_rust_impl` file of `Point`"),
+            "{rs_api}"
         );
         Ok(())
     }
 
-    /// For derived classes with no data members, we can't use the offset of the
-    /// first member to determine the size of the base class subobjects.
     #[test]
-    fn test_base_class_subobject_fieldless_layout() -> Result<()> {
+    fn test_opaque_record() -> Result<()> {
         let ir = ir_from_cc(
-            r#"
-            class Base {__INT64_TYPE__ x; char y;};
-            struct Derived final : Base {};
-        "#,
+            r#" #pragma clang lifetime_elision
+            class [[clang::annotate("crubit_opaque")]] Connection final {
+              public:
+                Connection();
+                ~Connection();
+                static Connection* Open();
+                bool IsOpen() const;
+              private:
+                int fd_;
+            };"#,
         )?;
-        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(C, align(8))]
-                #[__crubit::annotate(cc_type="Derived")]
-                pub struct Derived {
-                    __non_field_data: [::core::mem::MaybeUninit<u8>; 16],
+                #[repr(C)]
+                #[__crubit::annotate(cc_type = "Connection")]
+                pub struct Connection {
+                    _opaque: [u8; 0],
+                    _marker: ::core::marker::PhantomData<(*mut u8, ::core::marker::PhantomPinned)>,
                 }
             }
         );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Open() -> *mut crate::Connection { ... }
+            }
+        );
+        assert_rs_matches!(rs_api, quote! { pub fn IsOpen<'a>(&'a self) -> bool { ... } });
+        assert_rs_not_matches!(rs_api, quote! { fd_ });
+        assert_rs_not_matches!(rs_api, quote! { ctor_new });
+        assert_rs_not_matches!(rs_api, quote! { PinnedDrop });
+        assert_rs_not_matches!(rs_api, quote! { impl Default for Connection });
+        assert_rs_not_matches!(rs_api, quote! { ::core::mem::size_of::<crate::Connection>() });
+        assert_cc_not_matches!(
+            rs_api_impl,
+            quote! { static_assert(CRUBIT_SIZEOF(class Connection) == 4) }
+        );
         Ok(())
     }
 
     #[test]
-    fn test_base_class_subobject_empty_fieldless() -> Result<()> {
+    fn test_opaque_record_is_supported() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"
+            class [[clang::annotate("crubit_opaque")]] Connection final {
+              public:
+                ~Connection();
+                static Connection* Open();
+            };"#,
+        )?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub struct Connection { ... } });
+        assert_rs_matches!(rs_api, quote! { pub fn Open() -> *mut crate::Connection { ... } });
+        Ok(())
+    }
+
+    #[test]
+    fn test_opaque_record_field() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            class Base {};
-            struct Derived final : Base {};
-        "#,
+            struct [[clang::annotate("crubit_opaque")]] Opaque final { int x; };
+            struct Outer final {
+                Opaque opaque;
+                int y;
+            };"#,
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(C)]
-                #[__crubit::annotate(cc_type="Derived")]
-                pub struct Derived {
-                    ...
-                    __non_field_data: [::core::mem::MaybeUninit<u8>; 1],
+                #[repr(C, align(4))]
+                #[__crubit::annotate(cc_type="Outer")]
+                pub struct Outer {
+                    #[doc = " Reason for representing this field as a blob of bytes:\n `Opaque` is opaque, and can only be used behind pointers"]
+                    pub(crate) opaque: [::core::mem::MaybeUninit<u8>; 4],
+                    pub y: ::core::ffi::c_int,
                 }
             }
         );
@@ -1521,230 +3792,203 @@ mod tests {
     }
 
     #[test]
-    fn test_base_class_subobject_empty() -> Result<()> {
+    fn test_doc_comment_record() -> Result<()> {
         let ir = ir_from_cc(
-            r#"
-            class Base {};
-            struct Derived final : Base {
-                __INT16_TYPE__ x;
-            };
-        "#,
+            "// Doc Comment\n\
+            //\n\
+            //  * with bullet\n\
+            struct SomeStruct final {\n\
+                // Field doc\n\
+                int field;\
+            };",
         )?;
-        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+
         assert_rs_matches!(
-            rs_api,
+            generate_bindings_tokens(ir)?.rs_api,
             quote! {
-                #[__crubit::annotate(cc_type="Derived")]
-                pub struct Derived {
-                    pub x: ::core::ffi::c_short,
+                #[doc = " Doc Comment\n \n  * with bullet\n \n Generated from: google3/ir_from_cc_virtual_header.h;l=6"]
+                #[derive(Clone, Copy)]
+                #[repr(C)]
+                #[__crubit::annotate(cc_type="SomeStruct")]
+                pub struct SomeStruct {
+                    # [doc = " Field doc"]
+                    pub field: ::core::ffi::c_int,
                 }
             }
         );
         Ok(())
     }
 
-    /// Non-aggregate structs can't be directly initialized, because we add
-    /// a zero-sized private field to the bindings.
     #[test]
-    fn test_non_aggregate_struct_private_field() -> Result<()> {
+    fn test_doc_comment_field_default_value() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            struct NonAggregate {
-                NonAggregate() {}
-
-                __INT16_TYPE__ x = 0;
-            };
-        "#,
+            struct SomeStruct final {
+                // Field doc
+                int documented_field = 42;
+                int undocumented_field = 1 + 2;
+                int field_without_default;
+            };"#,
         )?;
-        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+
         assert_rs_matches!(
-            rs_api,
+            generate_bindings_tokens(ir)?.rs_api,
             quote! {
-                pub struct NonAggregate {
-                    __non_field_data:  [::core::mem::MaybeUninit<u8>; 0],
-                    pub x: ::core::ffi::c_short,
+                pub struct SomeStruct {
+                    # [doc = " Field doc\n \n Default: `42`"]
+                    pub documented_field: ::core::ffi::c_int,
+                    # [doc = " Default: `1 + 2`"]
+                    pub undocumented_field: ::core::ffi::c_int,
+                    pub field_without_default: ::core::ffi::c_int,
                 }
             }
         );
         Ok(())
     }
 
-    /// When a field is [[no_unique_address]], it occupies the space up to the
-    /// next field.
     #[test]
-    fn test_no_unique_address() -> Result<()> {
+    fn test_default_impl_for_aggregate_with_default_member_initializers() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            class Field1 {__INT64_TYPE__ x;};
-            class Field2 {char y;};
-            struct Struct final {
-                [[no_unique_address]] Field1 field1;
-                [[no_unique_address]] Field2 field2;
-                __INT16_TYPE__ z;
-            };
-        "#,
+            struct SomeStruct final {
+                int field1 = 1;
+                char field2{'a'};
+            };"#,
         )?;
-        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(C, align(8))]
-                #[__crubit::annotate(cc_type="Struct")]
-                pub struct Struct {
-                    ...
-                    pub(crate) field1: [::core::mem::MaybeUninit<u8>; 8],
-                    ...
-                    pub(crate) field2: [::core::mem::MaybeUninit<u8>; 2],
-                    pub z: ::core::ffi::c_short,
+                impl Default for SomeStruct {
+                    #[inline(always)]
+                    fn default() -> Self {
+                        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+                        unsafe {
+                            crate::detail::__crubit_default__10SomeStruct___2f_2ftest_3atesting_5ftarget(&mut tmp);
+                            tmp.assume_init()
+                        }
+                    }
                 }
             }
         );
         assert_rs_matches!(
             rs_api,
             quote! {
-                impl Struct {
-                    pub fn field1(&self) -> &crate::Field1 {
-                        unsafe {
-                            let ptr = (self as *const Self as *const u8).offset(0);
-                            &*(ptr as *const crate::Field1)
-                        }
-                    }
-                    pub fn field2(&self) -> &crate::Field2 {
-                        unsafe {
-                            let ptr = (self as *const Self as *const u8).offset(8);
-                            &*(ptr as *const crate::Field2)
-                        }
-                    }
+                pub(crate) fn __crubit_default__10SomeStruct___2f_2ftest_3atesting_5ftarget(
+                    __this: &mut ::core::mem::MaybeUninit<crate::SomeStruct>
+                );
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" void __crubit_default__10SomeStruct___2f_2ftest_3atesting_5ftarget(
+                    struct SomeStruct* __this) {
+                    new (__this) SomeStruct{};
                 }
             }
         );
         Ok(())
     }
 
-    /// When a [[no_unique_address]] field is the last one, it occupies the rest
-    /// of the object.
     #[test]
-    fn test_no_unique_address_last_field() -> Result<()> {
+    fn test_no_default_impl_for_aggregate_with_some_default_member_initializers() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            class Field1 {__INT64_TYPE__ x;};
-            class Field2 {char y;};
-            struct Struct final {
-                [[no_unique_address]] Field1 field1;
-                [[no_unique_address]] Field2 field2;
-            };
-        "#,
+            struct SomeStruct final {
+                int field1 = 1;
+                int field2;
+            };"#,
         )?;
-        let rs_api = generate_bindings_tokens(ir)?.rs_api;
-        assert_rs_matches!(
-            rs_api,
-            quote! {
-                #[repr(C, align(8))]
-                #[__crubit::annotate(cc_type="Struct")]
-                pub struct Struct {
-                    ...
-                    pub(crate) field1: [::core::mem::MaybeUninit<u8>; 8],
-                    ...
-                    pub(crate) field2: [::core::mem::MaybeUninit<u8>; 8],
-                }
-            }
-        );
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_not_matches!(rs_api, quote! { impl Default });
+        assert_cc_not_matches!(rs_api_impl, quote! { new (__this) SomeStruct{} });
         Ok(())
     }
 
     #[test]
-    fn test_no_unique_address_empty() -> Result<()> {
+    fn test_default_impl_for_aggregate_prefers_default_constructor() -> Result<()> {
         let ir = ir_from_cc(
             r#"
-            class Field {};
-            struct Struct final {
-                // Doc comment for no_unique_address empty class type field.
-                [[no_unique_address]] Field field;
-                int x;
-            };
-        "#,
+            #pragma clang lifetime_elision
+            struct SomeStruct final {
+                int field = 1;
+            };"#,
         )?;
-        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(C)]
-                #[__crubit::annotate(cc_type="Struct")]
-                pub struct Struct {
-                    pub x: ::core::ffi::c_int,
-                }
-                ...
-                impl Struct {
-                  # [doc = " Doc comment for no_unique_address empty class type field."]
-                  pub fn field(&self) -> &crate::Field {
-                        unsafe {
-                            let ptr = (self as *const Self as *const u8).offset(0);
-                            &*(ptr as *const crate::Field)
-                        }
-                      }
+                impl Default for SomeStruct {
+                    ...
+                    crate::detail::__rust_thunk___ZN10SomeStructC1Ev(&mut tmp);
+                    ...
                 }
-                ...
             }
         );
+        assert_rs_not_matches!(rs_api, quote! { __crubit_default__ });
+        assert_cc_not_matches!(rs_api_impl, quote! { __crubit_default__ });
         Ok(())
     }
 
     #[test]
-    fn test_base_class_subobject_empty_last_field() -> Result<()> {
+    fn test_doc_comment_non_final_record() -> Result<()> {
         let ir = ir_from_cc(
-            r#"
-            class Field {};
-            struct Struct final {
-                // Doc comment for no_unique_address empty class type field.
-                [[no_unique_address]] Field field;
-            };
-        "#,
+            "struct SomeStruct { int field; };\n\
+            // Doc Comment\n\
+            struct Derived : SomeStruct { char c; };",
         )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(C)]
-                #[__crubit::annotate(cc_type="Struct")]
-                pub struct Struct {}
+                #[doc = " This class is not `final`, so C++ code may derive from it. A reference to it may\n refer to the base class subobject of a derived class, and copying it by value\n slices off the derived part of the object.\n \n Generated from: google3/ir_from_cc_virtual_header.h;l=3"]
                 ...
-                impl Struct {
-                  # [doc = " Doc comment for no_unique_address empty class type field."]
-                  pub fn field(&self) -> &crate::Field {
-                      unsafe {
-                          let ptr = (self as *const Self as *const u8).offset(0);
-                          &*(ptr as *const crate::Field)
-                      }
-                  }
-              }
-              ...
+                pub struct SomeStruct { ... }
+            }
+        );
+        // `Derived` may have tail padding which a class derived from it reuses.
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[doc = " Doc Comment\n \n This class is not `final`, so C++ code may derive from it. A reference to it may\n refer to the base class subobject of a derived class, and copying it by value\n slices off the derived part of the object.\n Since a derived class may place its fields in the tail padding of this class, it\n is not `Unpin`, so that assigning to it can't overwrite them. Marking the class\n `final` lifts this restriction.\n \n Generated from: google3/ir_from_cc_virtual_header.h;l=5"]
+                ...
+                pub struct Derived { ... }
             }
         );
         Ok(())
     }
 
     #[test]
-    fn test_doc_comment_record() -> Result<()> {
+    fn test_record_cc_class_info() -> Result<()> {
         let ir = ir_from_cc(
-            "// Doc Comment\n\
-            //\n\
-            //  * with bullet\n\
-            struct SomeStruct final {\n\
-                // Field doc\n\
-                int field;\
-            };",
+            "struct Final final {};
+            struct NonFinal {};
+            union Union {};",
         )?;
-
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
-            generate_bindings_tokens(ir)?.rs_api,
+            rs_api,
             quote! {
-                #[doc = " Doc Comment\n \n  * with bullet\n \n Generated from: google3/ir_from_cc_virtual_header.h;l=6"]
-                #[derive(Clone, Copy)]
-                #[repr(C)]
-                #[__crubit::annotate(cc_type="SomeStruct")]
-                pub struct SomeStruct {
-                    # [doc = " Field doc"]
-                    pub field: ::core::ffi::c_int,
+                impl oops::CcClass for Final {
+                    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl oops::CcClass for NonFinal {
+                    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+                }
+            }
+        );
+        // A union can't be used as a base class.
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl oops::CcClass for Union {
+                    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
                 }
             }
         );
@@ -1762,7 +4006,7 @@ mod tests {
             };
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
 
         assert_rs_matches!(
             rs_api,
@@ -1815,8 +4059,8 @@ mod tests {
             quote! {
                 #[repr(C, align(4))]
                 #[__crubit::annotate(cc_type="MyUnion")]
-                pub union MyUnion { ...
-                    first_field: [::core::mem::MaybeUninit<u8>; 56],
+                pub union MyUnion {
+                    pub first_field: [::core::ffi::c_char; 56],
                     pub second_field: ::core::ffi::c_int,
                 }
             }
@@ -1836,6 +4080,59 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_union_with_unsupported_array_field() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            union MyUnion {
+                volatile char first_field[56];
+                int second_field;
+              };
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[repr(C, align(4))]
+                #[__crubit::annotate(cc_type="MyUnion")]
+                pub union MyUnion { ...
+                    pub(crate) first_field: [::core::mem::MaybeUninit<u8>; 56],
+                    pub second_field: ::core::ffi::c_int,
+                }
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { [::core::ffi::c_char; 56] });
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_with_unsupported_array_fields() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct SomeStruct final {
+              volatile int volatile_buf[4];
+              int matrix[2][3];
+            };
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub struct SomeStruct { ...
+                    pub(crate) volatile_buf: [::core::mem::MaybeUninit<u8>; 16],
+                    ...
+                    pub(crate) matrix: [::core::mem::MaybeUninit<u8>; 24],
+                }
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { [::core::ffi::c_int; 4] });
+        assert_rs_not_matches!(rs_api, quote! { [::core::ffi::c_int; 3] });
+        Ok(())
+    }
+
     #[test]
     fn test_currently_no_offset_assertions_for_unions() -> Result<()> {
         let ir = ir_from_cc(
@@ -2189,6 +4486,98 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_as_ref_single_inheritance() -> Result<()> {
+        let ir = ir_from_cc(
+            "
+            struct Base { int x; };
+            struct Derived final : Base { int y; };
+        ",
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl ::core::convert::AsRef<crate::Base> for crate::Derived {
+                    fn as_ref(&self) -> &crate::Base {
+                        oops::Upcast::upcast(self)
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl ::core::convert::AsMut<crate::Base> for crate::Derived {
+                    fn as_mut(&mut self) -> &mut crate::Base {
+                        oops::Upcast::upcast(self)
+                    }
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_as_ref_single_inheritance_only_direct_base() -> Result<()> {
+        let ir = ir_from_cc(
+            "
+            struct Base { int x; };
+            struct Intermediate : Base { int y; };
+            struct Derived : Intermediate { int z; };
+        ",
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! { impl ::core::convert::AsRef<crate::Intermediate> for crate::Derived }
+        );
+        assert_rs_not_matches!(
+            rs_api,
+            quote! { impl ::core::convert::AsRef<crate::Base> for crate::Derived }
+        );
+        // `Intermediate` is itself a derived class, so its tail padding is unknown.
+        assert_rs_not_matches!(
+            rs_api,
+            quote! { impl ::core::convert::AsMut<crate::Intermediate> for crate::Derived }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_as_mut_base_with_tail_padding() -> Result<()> {
+        let ir = ir_from_cc(
+            "
+            class Base { __INT64_TYPE__ x; char y; };
+            struct Derived : Base { char z; };
+        ",
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! { impl ::core::convert::AsRef<crate::Base> for crate::Derived }
+        );
+        assert_rs_not_matches!(rs_api, quote! { AsMut });
+        Ok(())
+    }
+
+    #[test]
+    fn test_as_ref_multiple_inheritance() -> Result<()> {
+        let ir = ir_from_cc(
+            "
+            struct Base1 { int x; };
+            struct Base2 { int y; };
+            struct VirtualBase { int z; };
+            struct MultipleInheritance : Base1, Base2 {};
+            struct VirtualInheritance : virtual VirtualBase {};
+        ",
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { AsRef });
+        assert_rs_not_matches!(rs_api, quote! { AsMut });
+        Ok(())
+    }
+
     #[test]
     fn test_virtual_thunk() -> Result<()> {
         let ir = ir_from_cc("struct Polymorphic { virtual void Foo(); };")?;
@@ -2378,6 +4767,67 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_supported_template_instantiation_in_function_signature() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"
+            template <typename T1, typename T2>
+            struct Pair final {
+                T1 first;
+                T2 second;
+            };
+
+            Pair<int, float> MakePair(int first, float second);
+            void TakePair(Pair<int, float> pair);
+            "#,
+        )?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn MakePair(first: ::core::ffi::c_int, second: f32)
+                    -> crate::__CcTemplateInst4PairIifE {...}
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn TakePair(mut pair: crate::__CcTemplateInst4PairIifE) {...} }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub struct __CcTemplateInst4PairIifE {
+                    pub first: ::core::ffi::c_int,
+                    pub second: f32,
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_supported_template_instantiation_not_rust_movable() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"
+            template <typename T>
+            struct Nontrivial final {
+                ~Nontrivial() {}
+                T value;
+            };
+
+            void TakeNontrivial(Nontrivial<int> nontrivial);
+            "#,
+        )?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { pub struct __CcTemplateInst10NontrivialIiE });
+        assert_rs_not_matches!(rs_api, quote! { pub fn TakeNontrivial });
+        Ok(())
+    }
+
     #[test]
     fn test_forward_declared_class_template_specialization_symbol() -> Result<()> {
         let rs_api = generate_bindings_tokens(ir_from_cc(
@@ -2445,7 +4895,7 @@ mod tests {
     fn test_supported_nontrivial_field() -> Result<()> {
         let mut ir = ir_from_cc(
             r#"
-            struct [[clang::trivial_abi]] Inner {~Inner();};
+            struct [[clang::trivial_abi]] Inner final {~Inner();};
             struct [[clang::trivial_abi]] Outer {Inner inner_field;};
             "#,
         )?;
@@ -2491,4 +4941,64 @@ mod tests {
         assert_rs_not_matches!(rs_api, quote! {pub fn field});
         Ok(())
     }
+
+    #[test]
+    fn test_bytemuck_impls() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct Point { int x; int y; };
+            struct Line { Point from; Point to; float width; };
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_bytemuck_impls: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                unsafe impl ::bytemuck::Zeroable for Point {}
+                unsafe impl ::bytemuck::Pod for Point {}
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                unsafe impl ::bytemuck::Zeroable for Line {}
+                unsafe impl ::bytemuck::Pod for Line {}
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_bytemuck_impls_disabled_by_default() -> Result<()> {
+        let ir = ir_from_cc("struct Point { int x; int y; };")?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { bytemuck });
+        Ok(())
+    }
+
+    #[test]
+    fn test_bytemuck_impls_not_pod() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct WithPadding { char c; int i; };
+            struct WithBool { bool b; };
+            struct WithPointer { int* p; };
+            struct WithPrivateField { int get() const; private: int i; };
+            struct WithDestructor { ~WithDestructor(); int i; };
+            struct WithNonPodField { WithPadding field; };
+            struct Empty {};
+        "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_bytemuck_impls: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_not_matches!(rs_api, quote! { bytemuck });
+        Ok(())
+    }
 }
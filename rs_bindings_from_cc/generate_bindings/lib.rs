@@ -3,27 +3,52 @@
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 #![allow(clippy::collapsible_else_if)]
 
+mod attribute_translation;
+mod bindings_plan;
+mod doxygen;
+mod feature_report;
+#[cfg(feature = "fuzzing")]
+mod fuzzing;
 mod generate_func;
 mod generate_record;
 mod rs_snippet;
+mod std_type_mapping;
 
 use generate_func::{
-    generate_func, get_binding, is_record_clonable, overloaded_funcs, FunctionId, ImplKind,
+    generate_default_arg_wrappers, generate_func, generate_renamed_from_alias,
+    generate_renamed_func, get_binding, is_record_clonable, overload_rs_names, overloaded_funcs,
+    parse_source_loc, FunctionId, ImplKind, OverloadNaming,
 };
 use generate_record::{generate_incomplete_record, generate_record};
 
-use crate::rs_snippet::{CratePath, Lifetime, Mutability, PrimitiveType, RsTypeKind};
+pub use bindings_plan::{
+    plan_bindings, query_bindings, BindingsPlan, BindingsStatus, NoBindingsCategory,
+    PlannedBindings, PlannedFeatureRequirement, PlannedItem, PlannedItemKind,
+};
+pub use feature_report::{
+    generate_crubit_features_report, CrubitFeaturesReport, FeatureSuggestion, UnlockedItem,
+};
+#[cfg(feature = "fuzzing")]
+pub use fuzzing::{fuzz_generate_bindings, mutate_ir_json, FuzzOutcome};
+
+use crate::attribute_translation::{translate_attrs, untranslated_attrs};
+use crate::rs_snippet::{BridgeType, CratePath, Lifetime, Mutability, PrimitiveType, RsTypeKind};
+use crate::std_type_mapping::{
+    AbslStatus, CcSpan, CcStringView, SharedPtr, StdFunction, StdString, UniquePtr,
+};
 use arc_anyhow::{Context, Error, Result};
-use code_gen_utils::{format_cc_includes, make_rs_ident, CcInclude};
+use code_gen_utils::{format_cc_includes, make_rs_ident, CcInclude, NamespaceQualifier};
 use error_report::{anyhow, bail, ensure, ErrorReport, ErrorReporting, IgnoreErrors};
 use ffi_types::*;
 use ir::*;
 use itertools::Itertools;
 use proc_macro2::{Ident, Literal, TokenStream};
-use quote::{quote, ToTokens};
-use std::collections::{BTreeSet, HashSet};
+use quote::{format_ident, quote, ToTokens};
+use serde::Deserialize;
+use std::collections::{hash_map, BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ffi::{OsStr, OsString};
 use std::fmt::{Display, Formatter};
+use std::ops::Range;
 use std::panic::catch_unwind;
 use std::path::Path;
 use std::process;
@@ -38,6 +63,9 @@ pub struct FfiBindings {
     rs_api: FfiU8SliceBox,
     rs_api_impl: FfiU8SliceBox,
     error_report: FfiU8SliceBox,
+    item_spans: FfiU8SliceBox,
+    stable_thunks: FfiU8SliceBox,
+    bindings_status: FfiU8SliceBox,
 }
 
 /// Deserializes IR from `json` and generates bindings source code.
@@ -55,13 +83,19 @@ pub struct FfiBindings {
 ///      FfiU8Slice for a valid array of bytes representing an UTF8-encoded
 ///      string (without the UTF-8 requirement, it seems that Rust doesn't offer
 ///      a way to convert to OsString on Windows)
-///    * `json`, `crubit_support_path_format`, `rustfmt_exe_path`, and
-///      `rustfmt_config_path` shouldn't change during the call.
+///    * `generator_options` should be a FfiU8Slice for a valid array of bytes
+///      representing a JSON-serialized `GeneratorOptions`
+///    * `stable_thunks_baseline` should be a FfiU8Slice for a valid array of
+///      bytes with the given size.
+///    * `json`, `crubit_support_path_format`, `rustfmt_exe_path`,
+///      `rustfmt_config_path`, `generator_options`, and
+///      `stable_thunks_baseline` shouldn't change during the call.
 ///
 /// Ownership:
 ///    * function doesn't take ownership of (in other words it borrows) the
 ///      input params: `json`, `crubit_support_path_format`, `rustfmt_exe_path`,
-///      and `rustfmt_config_path`
+///      `rustfmt_config_path`, `generator_options`, and
+///      `stable_thunks_baseline`
 ///    * function passes ownership of the returned value to the caller
 #[no_mangle]
 pub unsafe extern "C" fn GenerateBindingsImpl(
@@ -72,6 +106,8 @@ pub unsafe extern "C" fn GenerateBindingsImpl(
     rustfmt_config_path: FfiU8Slice,
     generate_error_report: bool,
     generate_source_loc_doc_comment: SourceLocationDocComment,
+    generator_options: FfiU8Slice,
+    stable_thunks_baseline: FfiU8Slice,
 ) -> FfiBindings {
     let json: &[u8] = json.as_slice();
     let crubit_support_path_format: &str =
@@ -82,20 +118,25 @@ pub unsafe extern "C" fn GenerateBindingsImpl(
         std::str::from_utf8(rustfmt_exe_path.as_slice()).unwrap().into();
     let rustfmt_config_path: OsString =
         std::str::from_utf8(rustfmt_config_path.as_slice()).unwrap().into();
+    let generator_options: &[u8] = generator_options.as_slice();
+    let stable_thunks_baseline: &[u8] = stable_thunks_baseline.as_slice();
     catch_unwind(|| {
         // It is ok to abort here.
         let errors: Rc<dyn ErrorReporting> =
             if generate_error_report { Rc::new(ErrorReport::new()) } else { Rc::new(IgnoreErrors) };
-        let Bindings { rs_api, rs_api_impl } = generate_bindings(
-            json,
-            crubit_support_path_format,
-            &clang_format_exe_path,
-            &rustfmt_exe_path,
-            &rustfmt_config_path,
-            errors.clone(),
-            generate_source_loc_doc_comment,
-        )
-        .unwrap();
+        let Bindings { rs_api, rs_api_impl, item_spans, stable_thunks, bindings_status } =
+            generate_bindings(
+                json,
+                crubit_support_path_format,
+                &clang_format_exe_path,
+                &rustfmt_exe_path,
+                &rustfmt_config_path,
+                errors.clone(),
+                generate_source_loc_doc_comment,
+                generator_options,
+                stable_thunks_baseline,
+            )
+            .unwrap();
         FfiBindings {
             rs_api: FfiU8SliceBox::from_boxed_slice(rs_api.into_bytes().into_boxed_slice()),
             rs_api_impl: FfiU8SliceBox::from_boxed_slice(
@@ -104,11 +145,140 @@ pub unsafe extern "C" fn GenerateBindingsImpl(
             error_report: FfiU8SliceBox::from_boxed_slice(
                 errors.serialize_to_vec().unwrap().into_boxed_slice(),
             ),
+            item_spans: FfiU8SliceBox::from_boxed_slice(
+                serialize_item_spans(&item_spans).unwrap().into_boxed_slice(),
+            ),
+            stable_thunks: FfiU8SliceBox::from_boxed_slice(
+                serde_json::to_vec(&stable_thunks).unwrap().into_boxed_slice(),
+            ),
+            bindings_status: FfiU8SliceBox::from_boxed_slice(
+                serialize_bindings_status(&bindings_status).unwrap().into_boxed_slice(),
+            ),
         }
     })
     .unwrap_or_else(|_| process::abort())
 }
 
+/// Options controlling which bindings are generated, and how.
+///
+/// The C++ side of the generator passes them as a JSON object (see
+/// `GeneratorOptions` in `src_code_gen.h`), in which omitted options keep their
+/// default values.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeneratorOptions {
+    /// Whether the thunks should be exposed to users of the generated
+    /// bindings via a public `raw` submodule.
+    pub generate_raw_thunks_module: bool,
+    /// The name of the generated module holding the `extern "C"` thunks, if it
+    /// should be something other than `detail`.
+    pub detail_module_name: Rc<str>,
+    /// Whether the thunks should be replaced with pure-Rust stubs that
+    /// dispatch to an implementation of the `mock::CcThunks` trait.
+    pub generate_mock_thunks: bool,
+    /// Name of a macro that the C++ thunks should be annotated with (e.g. an
+    /// export macro).  If empty, the C++ thunks get hidden visibility.
+    pub thunk_export_macro: Rc<str>,
+    /// Whether the names of the thunks should be prefixed with a hash of the
+    /// owning target (to avoid symbol collisions when multiple versions of
+    /// bindings for the same header are linked into a single binary).
+    pub hash_thunk_names: bool,
+    /// Whether to generate `crubit_verify_layouts()`, which re-checks the
+    /// layouts of the records at runtime (see `generate_verify_layouts_fn`).
+    pub generate_layout_verification: bool,
+    /// Whether to implement `bytemuck::Zeroable` and `bytemuck::Pod` for the
+    /// records which are plain old data (see `is_record_pod`).
+    pub generate_bytemuck_impls: bool,
+    /// Whether to generate a `prelude` module, which re-exports the records,
+    /// enums and free functions from all the namespaces of the target (see
+    /// `generate_prelude`).
+    pub generate_prelude_module: bool,
+    /// Whether the C++ thunks should `import` the public headers of the
+    /// target (as C++20 header units, or as the C++20 module named in the
+    /// IR), rather than `#include` them.
+    pub import_public_headers: bool,
+    /// The name of the C++20 module exported by the current target, which the
+    /// C++ thunks `import` (when importing the public headers). Empty if the
+    /// target doesn't export a module.
+    pub cc_module_name: Rc<str>,
+    /// Whether to compute the spans of the Rust bindings of each item (see
+    /// `extract_item_spans`).
+    pub generate_item_spans: bool,
+    /// Whether to compute whether each item of the current target gets
+    /// bindings (see `query_bindings`).
+    pub generate_bindings_status: bool,
+    /// The path of the generated C++ source file, as passed to the compiler.
+    /// If not empty, the C++ thunks are preceded by `#line` directives pointing
+    /// at the declarations of the wrapped functions, and followed by
+    /// directives pointing back at this file (see `resolve_line_directives`).
+    pub rs_api_impl_path: Rc<str>,
+    /// Whether to generate a `cxx_extern_types` module, which implements
+    /// `cxx::ExternType` for the records and enums of the target (see
+    /// `generate_mod_cxx_extern_types`).
+    pub generate_cxx_extern_types: bool,
+    /// A regex (like `bindgen --allowlist-file`) matching the headers whose
+    /// namespaced items should also be exported from the crate root under
+    /// the names that `bindgen` would give them (see
+    /// `generate_bindgen_aliases`). Empty if no aliases should be
+    /// generated.
+    pub bindgen_allowlist_file: Rc<str>,
+    /// Whether the C++ thunks should only include the public headers that
+    /// they need (see `required_public_headers`), rather than all the
+    /// public headers of the target.
+    pub minimize_public_header_includes: bool,
+    /// Whether the items of the IR should only be deserialized once bindings
+    /// generation visits them (see `deserialize_ir_lazily`).
+    pub lazy_ir: bool,
+    /// If not empty, the C++ thunks of the functions that aren't `noexcept`
+    /// catch all the exceptions, and abort with this note (where
+    /// `{function}` is replaced with the name of the function), rather than
+    /// let the exceptions unwind into Rust (see `generate_func_thunk_impl`).
+    pub thunk_exception_note: Rc<str>,
+    /// How the Rust bindings of overloaded functions are named (see
+    /// `overload_rs_names`).
+    pub overload_naming: OverloadNaming,
+    /// The crate-level lint attributes of the generated Rust code.
+    pub rs_api_lints: LintHeader,
+    /// Whether a function whose C++ pointer or reference types don't keep the
+    /// `const` qualifiers of their pointees in Rust should be an error, rather
+    /// than getting bindings with the wrong mutability.
+    pub strict_pointer_constness: bool,
+    /// Whether the layout assertions of each record should be generated as a
+    /// single array-driven check, rather than as one assertion per field.
+    pub compact_layout_assertions: bool,
+    /// Whether the generated C++ code should define a symbol encoding the
+    /// layout of each record, and reference the symbols of the records of
+    /// dependencies (see `generate_abi_hash_symbols`).
+    pub abi_hash_symbols: bool,
+    /// Whether string view parameters are bound as Rust `&str` (rather than
+    /// `&[u8]`).
+    pub string_view_params_as_str: bool,
+    /// Whether an extension trait, declaring the methods of the record, is
+    /// generated for each record.
+    pub record_ext_traits: bool,
+    /// Whether explicit single-argument constructors and constructors taking
+    /// more than one argument are bound as associated functions (`from_*` and
+    /// `new`).
+    pub constructor_functions: bool,
+    /// Translations of the attributes which Crubit doesn't otherwise
+    /// understand, from the C++ attribute name (as in `unknown_attr`) to the
+    /// Rust attribute, or to an empty string if the attribute should be
+    /// ignored (see `attribute_translation`).
+    pub attribute_translations: HashMap<Rc<str>, Rc<str>>,
+    /// Version of Crubit (e.g. a revision stamped by the build system) to
+    /// embed into the bindings, together with the generator options and a hash
+    /// of the IR (see `generate_crubit_metadata`). No metadata is generated if
+    /// empty.
+    pub version_stamp: Rc<str>,
+}
+
+impl GeneratorOptions {
+    /// Whether the C++ thunks should be surrounded with `#line` directives.
+    fn generate_line_directives(&self) -> bool {
+        !self.rs_api_impl_path.is_empty()
+    }
+}
+
 memoized::query_group! {
     trait BindingsGenerator {
         #[input]
@@ -117,6 +287,8 @@ memoized::query_group! {
         fn errors(&self) -> Rc<dyn ErrorReporting>;
         #[input]
         fn generate_source_loc_doc_comment(&self) -> SourceLocationDocComment;
+        #[input]
+        fn options(&self) -> Rc<GeneratorOptions>;
 
         fn rs_type_kind(&self, rs_type: RsType) -> Result<RsTypeKind>;
 
@@ -124,6 +296,10 @@ memoized::query_group! {
 
         fn overloaded_funcs(&self) -> Rc<HashSet<Rc<FunctionId>>>;
 
+        fn overload_rs_names(&self) -> Rc<HashMap<ItemId, Rc<str>>>;
+
+        fn module_item_names(&self, namespace_qualifier: NamespaceQualifier) -> Rc<HashSet<Rc<str>>>;
+
         fn is_record_clonable(&self, record: Rc<Record>) -> bool;
 
         fn get_binding(
@@ -141,16 +317,36 @@ struct Bindings {
     rs_api: String,
     // C++ source code.
     rs_api_impl: String,
+    // Spans of the bindings of the items in `rs_api` (only populated when
+    // generating item spans).
+    item_spans: Vec<ItemSpan>,
+    // The C++ signatures of the thunks whose symbol names are pinned by the
+    // `crubit_stable_thunk` attribute, by symbol name.
+    stable_thunks: BTreeMap<String, String>,
+    // Whether each item of the current target gets bindings (only populated
+    // when generating the bindings status).
+    bindings_status: HashMap<ItemId, BindingsStatus>,
 }
 
-/// Source code for generated bindings, as tokens.
-///
-/// This is public within the crate for testing purposes.
-pub(crate) struct BindingsTokens {
-    // Rust source code.
-    rs_api: TokenStream,
-    // C++ source code.
-    rs_api_impl: TokenStream,
+/// The span of the Rust bindings of an IR item in the formatted `rs_api`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ItemSpan {
+    item_id: ItemId,
+    // Byte range of the bindings. It covers whole lines: from the start of the
+    // first line of the bindings (including its indentation) to the end of the
+    // last non-blank line (including the newline).
+    range: Range<usize>,
+}
+
+/// Source code for generated bindings, as tokens (see `render_bindings`).
+pub struct BindingsTokens {
+    /// Rust source code.
+    pub rs_api: TokenStream,
+    /// C++ source code.
+    pub rs_api_impl: TokenStream,
+    // Thunks whose symbol names are pinned by the `crubit_stable_thunk`
+    // attribute.
+    stable_thunks: Vec<StableThunk>,
 }
 
 fn generate_bindings(
@@ -161,15 +357,29 @@ fn generate_bindings(
     rustfmt_config_path: &OsStr,
     errors: Rc<dyn ErrorReporting>,
     generate_source_loc_doc_comment: SourceLocationDocComment,
+    generator_options: &[u8],
+    stable_thunks_baseline: &[u8],
 ) -> Result<Bindings> {
-    let ir = Rc::new(deserialize_ir(json)?);
+    let options: Rc<GeneratorOptions> = Rc::new(serde_json::from_slice(generator_options)?);
+    let ir =
+        Rc::new(if options.lazy_ir { deserialize_ir_lazily(json)? } else { deserialize_ir(json)? });
+    attribute_translation::check_attribute_translations(&options.attribute_translations)?;
 
-    let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(
+    let BindingsTokens { mut rs_api, rs_api_impl, stable_thunks } = generate_bindings_tokens(
         ir.clone(),
         crubit_support_path_format,
         errors,
         generate_source_loc_doc_comment,
+        options.clone(),
     )?;
+    if !options.version_stamp.is_empty() {
+        let generator_options = std::str::from_utf8(generator_options)?;
+        rs_api.extend(generate_crubit_metadata(&options.version_stamp, generator_options, json));
+    }
+    let stable_thunks = stable_thunks_by_symbol(stable_thunks)?;
+    if !stable_thunks_baseline.is_empty() {
+        check_stable_thunks(stable_thunks_baseline, &stable_thunks)?;
+    }
     let rs_api = {
         let rustfmt_exe_path = Path::new(rustfmt_exe_path);
         let rustfmt_config_path = if rustfmt_config_path.is_empty() {
@@ -230,8 +440,220 @@ fn generate_bindings(
         "{top_level_comment}\n\
         {rs_api_impl}"
     );
+    let rs_api_impl = if options.generate_line_directives() {
+        resolve_line_directives(&rs_api_impl, &options.rs_api_impl_path)
+    } else {
+        rs_api_impl
+    };
+    let (rs_api, item_spans) =
+        if options.generate_item_spans { extract_item_spans(&rs_api)? } else { (rs_api, vec![]) };
+    let bindings_status = if options.generate_bindings_status {
+        query_bindings(ir, options.clone())?
+    } else {
+        HashMap::new()
+    };
+
+    Ok(Bindings { rs_api, rs_api_impl, item_spans, stable_thunks, bindings_status })
+}
+
+/// Generates the `__crubit_metadata` module (and the `crubit_metadata!` macro
+/// expanding to its `METADATA`), which identifies the Crubit `version_stamp`
+/// and the `generator_options` that generated the bindings, and the IR that
+/// they were generated from. This lets binaries report which Crubit produced
+/// their bindings, and lets builds detect crates whose bindings have been
+/// generated by mismatched versions of Crubit.
+///
+/// The metadata only depends on the inputs of the generator (rather than e.g.
+/// on the current time), so that the generated bindings stay reproducible.
+fn generate_crubit_metadata(
+    version_stamp: &str,
+    generator_options: &str,
+    ir_json: &[u8],
+) -> TokenStream {
+    let ir_hash = format!("{:08x}", generate_func::fnv1a_32(ir_json));
+    quote! {
+        __NEWLINE__
+        /// Identifies the Crubit that generated these bindings.
+        pub mod __crubit_metadata {
+            #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+            pub struct CrubitMetadata {
+                /// The version of Crubit.
+                pub version: &'static str,
+                /// The options of the generator, as JSON.
+                pub options: &'static str,
+                /// A hash of the IR of the C++ headers.
+                pub ir_hash: &'static str,
+            }
+            __NEWLINE__
+            pub const METADATA: CrubitMetadata = CrubitMetadata {
+                version: #version_stamp,
+                options: #generator_options,
+                ir_hash: #ir_hash,
+            };
+        }
+        __NEWLINE__
+        /// Expands to the `CrubitMetadata` of these bindings.
+        #[macro_export]
+        macro_rules! crubit_metadata {
+            () => {
+                $crate::__crubit_metadata::METADATA
+            };
+        }
+        __NEWLINE__
+    }
+}
+
+/// Returns the signatures of the stable thunks by symbol name, or `Err` if the
+/// same symbol name is pinned by more than one function.
+fn stable_thunks_by_symbol(stable_thunks: Vec<StableThunk>) -> Result<BTreeMap<String, String>> {
+    let mut by_symbol = BTreeMap::new();
+    for StableThunk { symbol, signature } in stable_thunks {
+        if by_symbol.contains_key(&symbol) {
+            bail!("The thunk symbol `{symbol}` is pinned by more than one function");
+        }
+        by_symbol.insert(symbol, signature);
+    }
+    Ok(by_symbol)
+}
+
+/// Checks that regenerating the bindings preserves the stable thunks of a
+/// previous release of the bindings, whose prebuilt `rs_api_impl` object may
+/// still be linked against them.
+///
+/// `baseline` is the JSON object of the previous signatures by symbol name (as
+/// written to `--stable_thunks_out`). Removing a stable thunk, or changing its
+/// signature, is an error. Adding a stable thunk is not.
+fn check_stable_thunks(baseline: &[u8], stable_thunks: &BTreeMap<String, String>) -> Result<()> {
+    let baseline: BTreeMap<String, String> = serde_json::from_slice(baseline)
+        .map_err(|err| anyhow!("Failed to parse the stable thunks baseline: {err}"))?;
+    let mut problems = vec![];
+    for (symbol, old_signature) in &baseline {
+        match stable_thunks.get(symbol) {
+            None => problems.push(format!("`{symbol}` was removed")),
+            Some(new_signature) if new_signature != old_signature => problems.push(format!(
+                "the signature of `{symbol}` changed from `{old_signature}` to `{new_signature}`"
+            )),
+            Some(_) => {}
+        }
+    }
+    if !problems.is_empty() {
+        bail!(
+            "The bindings are incompatible with the stable thunks baseline: {}",
+            problems.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Replaces the `#line __CRUBIT_GENERATED_LINE__` directives which follow the
+/// C++ thunks (see `generate_func_thunk_impl`) with directives which make the
+/// compiler attribute the following lines to `rs_api_impl_path` (the generated
+/// file itself) again.
+///
+/// This can only be done once `rs_api_impl` is formatted, because the
+/// directives have to contain the actual line numbers.
+fn resolve_line_directives(rs_api_impl: &str, rs_api_impl_path: &str) -> String {
+    let rs_api_impl_path = rs_api_impl_path.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut result = String::with_capacity(rs_api_impl.len());
+    for (index, line) in rs_api_impl.split_inclusive('\n').enumerate() {
+        if line.trim() == "#line __CRUBIT_GENERATED_LINE__" {
+            // `index` is 0-based, and the directive sets the number of the next line.
+            result += &format!("#line {} \"{rs_api_impl_path}\"\n", index + 2);
+        } else {
+            result += line;
+        }
+    }
+    result
+}
+
+/// Prefixes of the marker comments around the Rust bindings of each item (see
+/// `mark_item_span`).
+const ITEM_SPAN_BEGIN_MARKER: &str = "crubit-item-span-begin ";
+const ITEM_SPAN_END_MARKER: &str = "crubit-item-span-end ";
+
+/// Surrounds the Rust bindings of the item with marker comments, which
+/// `extract_item_spans` replaces with the spans of the item in the formatted
+/// code.
+///
+/// The markers are comments so that they survive `rustfmt`, and each of them
+/// is on its own line, so that `rustfmt` doesn't attach them to a neighbouring
+/// line of code.
+fn mark_item_span(item_id: ItemId, item: TokenStream) -> TokenStream {
+    let begin = format!("{ITEM_SPAN_BEGIN_MARKER}{item_id}");
+    let end = format!("{ITEM_SPAN_END_MARKER}{item_id}");
+    quote! {
+        __NEWLINE__ __COMMENT__ #begin
+        #item
+        __NEWLINE__ __COMMENT__ #end
+    }
+}
+
+/// Removes the lines with the marker comments added by `mark_item_span` from
+/// the formatted `rs_api`, and returns the remaining code along with the spans
+/// of the items in it (ordered by their start; the span of a namespace contains
+/// the spans of its children).
+fn extract_item_spans(rs_api: &str) -> Result<(String, Vec<ItemSpan>)> {
+    let parse_item_id = |item_id: &str| -> Result<ItemId> {
+        serde_json::from_str(item_id)
+            .with_context(|| format!("Invalid item ID in an item span marker: {item_id:?}"))
+    };
+    let mut code = String::with_capacity(rs_api.len());
+    let mut spans = vec![];
+    let mut open_spans: Vec<(ItemId, usize)> = vec![];
+    for line in rs_api.split_inclusive('\n') {
+        let comment = line.trim().strip_prefix("// ").unwrap_or_default();
+        if let Some(item_id) = comment.strip_prefix(ITEM_SPAN_BEGIN_MARKER) {
+            open_spans.push((parse_item_id(item_id)?, code.len()));
+        } else if let Some(item_id) = comment.strip_prefix(ITEM_SPAN_END_MARKER) {
+            let item_id = parse_item_id(item_id)?;
+            let (open_item_id, start) = open_spans
+                .pop()
+                .with_context(|| format!("Unmatched end of the span of item {item_id}"))?;
+            ensure!(
+                open_item_id == item_id,
+                "The span of item {open_item_id} ends with the end of the span of item {item_id}"
+            );
+            // Blank lines after the bindings are not a part of them.
+            let end = (code.trim_end().len() + 1).clamp(start, code.len());
+            spans.push(ItemSpan { item_id, range: start..end });
+        } else {
+            code.push_str(line);
+        }
+    }
+    if let Some((item_id, _)) = open_spans.pop() {
+        bail!("Unmatched start of the span of item {item_id}");
+    }
+    spans.sort_by_key(|span| (span.range.start, std::cmp::Reverse(span.range.end)));
+    Ok((code, spans))
+}
+
+/// Serializes the item spans to JSON, as an array of `{"id", "start", "end"}`
+/// objects (where `id` is the ID of the item in the IR, and `start..end` is
+/// the byte range of its bindings).
+fn serialize_item_spans(item_spans: &[ItemSpan]) -> Result<Vec<u8>> {
+    let item_spans: Vec<serde_json::Value> = item_spans
+        .iter()
+        .map(|span| {
+            serde_json::json!({
+                "id": span.item_id,
+                "start": span.range.start,
+                "end": span.range.end,
+            })
+        })
+        .collect();
+    Ok(serde_json::to_vec(&item_spans)?)
+}
 
-    Ok(Bindings { rs_api, rs_api_impl })
+/// Serializes the bindings status to JSON, as an array of `{"id", "status"}`
+/// objects sorted by `id` (where `id` is the ID of the item in the IR, and
+/// `status` is `"yes"`, `"maybe"` or `{"no": <category>}`).
+fn serialize_bindings_status(bindings_status: &HashMap<ItemId, BindingsStatus>) -> Result<Vec<u8>> {
+    let bindings_status: Vec<serde_json::Value> = bindings_status
+        .iter()
+        .sorted_by_key(|(id, _)| **id)
+        .map(|(id, status)| serde_json::json!({ "id": id, "status": status }))
+        .collect();
+    Ok(serde_json::to_vec(&bindings_status)?)
 }
 
 fn generate_doc_comment(
@@ -243,7 +665,8 @@ fn generate_doc_comment(
         SourceLocationDocComment::Enabled => source_loc,
         SourceLocationDocComment::Disabled => None,
     };
-    let (comment, sep, source_loc) = match (comment, source_loc) {
+    let comment = comment.map(doxygen::doxygen_to_rustdoc);
+    let (comment, sep, source_loc) = match (comment.as_deref(), source_loc) {
         (None, None) => return quote! {},
         (None, Some(source_loc)) => ("", "", source_loc),
         (Some(comment), Some(source_loc)) => (comment, "\n\n", source_loc),
@@ -268,8 +691,11 @@ fn generate_enum(db: &Database, enum_: &Enum) -> Result<GeneratedItem> {
             ),
         );
     };
+    if enum_.is_rust_enum {
+        return generate_rust_enum(db, enum_, &underlying_type, enumerators);
+    }
     let enumerators = enumerators.iter().map(|enumerator| {
-        if let Some(unknown_attr) = &enumerator.unknown_attr {
+        if let Some(unknown_attr) = untranslated_attrs(db, enumerator.unknown_attr.as_deref()) {
             let comment = format!(
                 "Omitting bindings for {ident}\nreason: unknown attribute(s): {unknown_attr}",
                 ident = &enumerator.identifier.identifier
@@ -295,7 +721,9 @@ fn generate_enum(db: &Database, enum_: &Enum) -> Result<GeneratedItem> {
         quote! {pub const #ident: #name = #name(#value);}
     });
 
+    let attrs = translate_attrs(db, enum_.unknown_attr.as_deref());
     let item = quote! {
+        #attrs
         #[repr(transparent)]
         #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, PartialOrd, Ord)]
         pub struct #name(#underlying_type);
@@ -316,6 +744,123 @@ fn generate_enum(db: &Database, enum_: &Enum) -> Result<GeneratedItem> {
     Ok(item.into())
 }
 
+/// Returns the primitive representation of a Rust `enum` with the given
+/// underlying type, or `None` if its width isn't the same on all platforms.
+fn rust_enum_repr(underlying_type: &RsTypeKind) -> Option<Ident> {
+    let RsTypeKind::Primitive(primitive) = underlying_type.unalias() else {
+        return None;
+    };
+    let repr = match primitive {
+        PrimitiveType::u8 | PrimitiveType::c_uchar => "u8",
+        PrimitiveType::i8 | PrimitiveType::c_schar => "i8",
+        PrimitiveType::u16 | PrimitiveType::c_ushort => "u16",
+        PrimitiveType::i16 | PrimitiveType::c_short => "i16",
+        PrimitiveType::u32 | PrimitiveType::c_uint => "u32",
+        PrimitiveType::i32 | PrimitiveType::c_int => "i32",
+        PrimitiveType::u64 | PrimitiveType::c_ulonglong => "u64",
+        PrimitiveType::i64 | PrimitiveType::c_longlong => "i64",
+        PrimitiveType::usize => "usize",
+        PrimitiveType::isize => "isize",
+        _ => return None,
+    };
+    Some(make_rs_ident(repr))
+}
+
+/// Generates a Rust `enum` for an enum annotated with `crubit_rust_enum`.
+///
+/// Unlike the newtype struct generated by default, a Rust `enum` can only hold
+/// the values of its enumerators, so C++ must not pass any other value to Rust.
+/// Values of the underlying type can be checked with `TryFrom`.
+fn generate_rust_enum(
+    db: &Database,
+    enum_: &Enum,
+    underlying_type: &RsTypeKind,
+    enumerators: &[Enumerator],
+) -> Result<GeneratedItem> {
+    let unsupported = |message: String| {
+        generate_unsupported(
+            db,
+            &UnsupportedItem::new_with_message(
+                &db.ir(),
+                enum_,
+                format!("Can't generate a Rust enum for `crubit_rust_enum`: {message}"),
+            ),
+        )
+    };
+    let Some(repr) = rust_enum_repr(underlying_type) else {
+        return unsupported(format!(
+            "the underlying type `{underlying_type}` is not an integer type of fixed width"
+        ));
+    };
+    if enumerators.is_empty() {
+        return unsupported("the enum has no enumerators".to_string());
+    }
+    if let Some(enumerator) =
+        enumerators.iter().find(|e| untranslated_attrs(db, e.unknown_attr.as_deref()).is_some())
+    {
+        return unsupported(format!(
+            "the enumerator `{}` has unknown attribute(s)",
+            enumerator.identifier.identifier
+        ));
+    }
+
+    let name = make_rs_ident(&enum_.identifier.identifier);
+    let mut variants = vec![];
+    let mut aliases = vec![];
+    let mut match_arms = vec![];
+    // Maps the values of the enumerators to the Rust variants which represent them.
+    let mut values = HashMap::new();
+    for enumerator in enumerators {
+        let ident = make_rs_ident(&enumerator.identifier.identifier);
+        let value = if enumerator.value.is_negative {
+            Literal::i64_unsuffixed(enumerator.value.wrapped_value as i64)
+        } else {
+            Literal::u64_unsuffixed(enumerator.value.wrapped_value)
+        };
+        match values.entry((enumerator.value.is_negative, enumerator.value.wrapped_value)) {
+            // Rust enums can't have several variants with the same value, so
+            // the later enumerators become associated constants.
+            hash_map::Entry::Occupied(variant) => {
+                let variant = variant.get();
+                aliases.push(quote! { pub const #ident: #name = #name::#variant; });
+            }
+            hash_map::Entry::Vacant(entry) => {
+                variants.push(quote! { #ident = #value });
+                match_arms.push(quote! { #value => Ok(#name::#ident), });
+                entry.insert(ident);
+            }
+        }
+    }
+
+    let attrs = translate_attrs(db, enum_.unknown_attr.as_deref());
+    let item = quote! {
+        #attrs
+        #[repr(#repr)]
+        #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, PartialOrd, Ord)]
+        pub enum #name {
+            #( #variants, )*
+        }
+        impl #name {
+            #( #aliases )*
+        }
+        impl ::core::convert::TryFrom<#underlying_type> for #name {
+            type Error = #underlying_type;
+            fn try_from(value: #underlying_type) -> ::core::result::Result<#name, #underlying_type> {
+                match value {
+                    #( #match_arms )*
+                    _ => Err(value),
+                }
+            }
+        }
+        impl From<#name> for #underlying_type {
+            fn from(value: #name) -> #underlying_type {
+                value as #underlying_type
+            }
+        }
+    };
+    Ok(item.into())
+}
+
 fn generate_type_alias(db: &Database, type_alias: &TypeAlias) -> Result<GeneratedItem> {
     let ident = make_rs_ident(&type_alias.identifier.identifier);
     let doc_comment = generate_doc_comment(
@@ -333,6 +878,253 @@ fn generate_type_alias(db: &Database, type_alias: &TypeAlias) -> Result<Generate
     .into())
 }
 
+/// Returns the names of the items of the current target which are declared
+/// directly in the Rust module `namespace_qualifier`.
+///
+/// Names generated by Crubit itself (e.g. aliases) must not reuse these. Macro
+/// constants are left out, since they give way to the other items (see
+/// `generate_global_var`).
+fn module_item_names(
+    db: &dyn BindingsGenerator,
+    namespace_qualifier: NamespaceQualifier,
+) -> Rc<HashSet<Rc<str>>> {
+    let ir = db.ir();
+    let mut names = HashSet::new();
+    for item in ir.current_target_items() {
+        let name = match item {
+            Item::Record(record) => &record.rs_name,
+            Item::IncompleteRecord(record) => &record.rs_name,
+            Item::Enum(enum_) => &enum_.identifier.identifier,
+            Item::TypeAlias(type_alias) => &type_alias.identifier.identifier,
+            Item::TypeMapOverride(type_override) => &type_override.rs_name,
+            Item::Func(func) if func.member_func_metadata.is_none() => match &func.name {
+                UnqualifiedIdentifier::Identifier(id) => &id.identifier,
+                _ => continue,
+            },
+            Item::GlobalVar(var) if !var.is_macro => &var.identifier.identifier,
+            Item::Namespace(namespace) => &namespace.name.identifier,
+            Item::UseMod(use_mod) => &use_mod.mod_name.identifier,
+            _ => continue,
+        };
+        // The members of records (other than nested types) are not in the module.
+        if is_nested_in_record(&ir, item) && !item.is_type_definition() {
+            continue;
+        }
+        if ir.namespace_qualifier(item).is_ok_and(|qualifier| qualifier == namespace_qualifier) {
+            names.insert(name.clone());
+        }
+    }
+    Rc::new(names)
+}
+
+/// Generates a re-export named `old_name` of the type named `new_name`, which
+/// was renamed from `old_name` in C++ (see the `crubit_renamed_from`
+/// attribute).
+///
+/// If `old_name` is taken by another item in the module of `item`, the
+/// re-export is replaced by a comment, and an error is reported.
+fn generate_renamed_type_alias(
+    db: &Database,
+    item: &impl GenericItem,
+    old_name: &str,
+    new_name: &str,
+) -> Result<TokenStream> {
+    let namespace_qualifier = db.ir().namespace_qualifier(item)?;
+    if db.module_item_names(namespace_qualifier).contains(old_name) {
+        let msg = format!(
+            "The alias `{old_name}` of `{new_name}` is not generated, because it collides with \
+             another item"
+        );
+        db.errors().insert(&anyhow!("{msg}"));
+        return Ok(quote! { __COMMENT__ #msg });
+    }
+    let doc = format!(" `{old_name}` was renamed to `{new_name}`.");
+    let old_ident = make_rs_ident(old_name);
+    let new_ident = make_rs_ident(new_name);
+    Ok(quote! {
+        #[doc = #doc]
+        pub use self::#new_ident as #old_ident;
+    })
+}
+
+/// Returns a comment replacing the top-level module `mod_name` generated by
+/// Crubit (e.g. `raw`), and reports an error, if `mod_name` is taken by another
+/// item of the top-level module of the current target.
+fn generate_crubit_module_collision(db: &Database, mod_name: &str) -> Option<TokenStream> {
+    if !db.module_item_names(NamespaceQualifier::new::<&str>([])).contains(mod_name) {
+        return None;
+    }
+    let msg =
+        format!("The `{mod_name}` module is not generated, because it collides with another item");
+    db.errors().insert(&anyhow!("{msg}"));
+    Some(quote! { __COMMENT__ #msg })
+}
+
+/// Generates Rust source code for a given `GlobalVar`.
+///
+/// If the value of the variable is known at compile time, it is bound to a
+/// `const`. Otherwise, the bindings contain a function named after the variable,
+/// which returns its address. If the variable is `const`, and its value can't
+/// change behind a shared reference (i.e. it is arithmetic, an enum, or a record
+/// without `mutable` fields), a `&'static` reference is returned instead. Static
+/// data members become associated items of their record.
+///
+/// Macro constants aren't scoped, so their names may be taken by other items
+/// (e.g. by a declaration preceding the `#define`). Such macros don't have
+/// bindings.
+fn generate_global_var(db: &Database, var: &GlobalVar) -> Result<GeneratedItem> {
+    let ir = db.ir();
+    if var.is_macro {
+        let namespace_qualifier = ir.namespace_qualifier(var)?;
+        ensure!(
+            !db.module_item_names(namespace_qualifier).contains(&*var.identifier.identifier),
+            "The macro `{}` is not bound, because its name collides with another item",
+            var.identifier.identifier
+        );
+    }
+    let ident = make_rs_ident(&var.identifier.identifier);
+    let doc_comment = generate_doc_comment(
+        var.doc_comment.as_deref(),
+        Some(&var.source_loc),
+        db.generate_source_loc_doc_comment(),
+    );
+    let rs_type = db
+        .rs_type_kind(var.type_.rs_type.clone())
+        .with_context(|| format!("Failed to format the type of {}", var))?;
+    let record = match var.enclosing_item_id.map(|id| ir.find_untyped_decl(id)) {
+        Some(Item::Record(record)) => Some(record.clone()),
+        _ => None,
+    };
+
+    let mut generated_item = if let Some(value) = &var.value {
+        let value = if rs_type.is_bool() {
+            if value.wrapped_value == 0 {
+                quote! {false}
+            } else {
+                quote! {true}
+            }
+        } else if value.is_negative {
+            Literal::i64_unsuffixed(value.wrapped_value as i64).into_token_stream()
+        } else {
+            Literal::u64_unsuffixed(value.wrapped_value).into_token_stream()
+        };
+        GeneratedItem::from(quote! {
+            #doc_comment
+            pub const #ident: #rs_type = #value;
+        })
+    } else {
+        if rs_type.as_bridge_type().is_some()
+            || rs_type.as_std_optional().is_some()
+            || rs_type.as_initializer_list().is_some()
+        {
+            bail!("Variables of type `{}` are not supported yet", rs_type.to_token_stream());
+        }
+        let thunk_ident = if db.options().hash_thunk_names {
+            let target_hash = generate_func::fnv1a_32(var.owning_target.0.as_bytes());
+            format_ident!("__rust_thunk_{target_hash:08x}__{}", var.mangled_name.as_ref())
+        } else {
+            format_ident!("__rust_thunk__{}", var.mangled_name.as_ref())
+        };
+        let detail_module_path = detail_module_path_tokens(db);
+        let is_const = var.type_.cc_type.is_const;
+        let is_immutable = is_const
+            && match rs_type.unalias() {
+                RsTypeKind::Primitive(_) | RsTypeKind::Enum { .. } => true,
+                RsTypeKind::Record { record, .. } => !record.has_mutable_fields,
+                _ => false,
+            };
+        let (thunk_return_type, api_return_type, api_body) = if is_immutable {
+            (
+                quote! { *const #rs_type },
+                quote! { &'static #rs_type },
+                quote! { unsafe { &*#detail_module_path::#thunk_ident() } },
+            )
+        } else if is_const {
+            (
+                quote! { *const #rs_type },
+                quote! { *const #rs_type },
+                quote! { unsafe { #detail_module_path::#thunk_ident() } },
+            )
+        } else {
+            (
+                quote! { *mut #rs_type },
+                quote! { *mut #rs_type },
+                quote! { unsafe { #detail_module_path::#thunk_ident() } },
+            )
+        };
+        let thunk_visibility = if db.options().generate_raw_thunks_module {
+            quote! { pub }
+        } else {
+            quote! { pub(crate) }
+        };
+        let thunk = generate_thunk_decl(
+            db,
+            quote! {},
+            thunk_visibility,
+            &thunk_ident,
+            quote! {},
+            &[],
+            quote! { -> #thunk_return_type },
+        );
+
+        let cc_var_ident = format_cc_ident(&var.identifier.identifier);
+        let cc_var_name = match &record {
+            Some(record) => {
+                let cc_record_name = cc_tagless_type_name_for_record(record, &ir)?;
+                quote! { #cc_record_name :: #cc_var_ident }
+            }
+            None => {
+                let namespace_qualifier = ir.namespace_qualifier(var)?.format_for_cc()?;
+                quote! { #namespace_qualifier #cc_var_ident }
+            }
+        };
+        let cc_pointer_type = format_cc_type(
+            &CcType {
+                name: Some("*".into()),
+                is_const: false,
+                type_args: vec![var.type_.cc_type.clone()],
+                decl_id: None,
+            },
+            &ir,
+        )?;
+        let export_macro = if db.options().thunk_export_macro.is_empty() {
+            quote! {}
+        } else {
+            syn::parse_str::<TokenStream>(&db.options().thunk_export_macro)?
+        };
+        let thunk_impls = quote! {
+            extern "C" #export_macro #cc_pointer_type #thunk_ident() {
+                return &#cc_var_name;
+            }
+        };
+        let decl_ids = std::iter::once(var.id)
+            .chain(record.as_ref().map(|record| record.id))
+            .chain(cc_type_decl_ids(&var.type_.cc_type));
+        GeneratedItem {
+            item: quote! {
+                #doc_comment
+                #[inline(always)]
+                pub fn #ident() -> #api_return_type {
+                    #api_body
+                }
+            },
+            thunk_impls,
+            required_headers: required_public_headers(&ir, decl_ids),
+            ..thunk
+        }
+    };
+    if let Some(record) = &record {
+        let record_ident = make_rs_ident(record.rs_name.as_ref());
+        let item = &generated_item.item;
+        generated_item.item = quote! {
+            impl #record_ident {
+                #item
+            }
+        };
+    }
+    Ok(generated_item)
+}
+
 /// Generates Rust source code for a given `UnsupportedItem`.
 fn generate_unsupported(db: &Database, item: &UnsupportedItem) -> Result<GeneratedItem> {
     for error in &item.errors {
@@ -368,9 +1160,14 @@ fn generate_namespace(db: &Database, namespace: &Namespace) -> Result<GeneratedI
     let ir = db.ir();
     let mut items = vec![];
     let mut thunks = vec![];
+    let mut mock_methods = vec![];
     let mut thunk_impls = vec![];
     let mut assertions = vec![];
     let mut features = BTreeSet::new();
+    let mut layout_checks = vec![];
+    let mut stable_thunks = vec![];
+    let mut required_headers = BTreeSet::new();
+    let mut std_array_types = vec![];
 
     for item_id in namespace.child_item_ids.iter() {
         let item = ir.find_decl(*item_id).with_context(|| {
@@ -381,6 +1178,9 @@ fn generate_namespace(db: &Database, namespace: &Namespace) -> Result<GeneratedI
         if !generated.thunks.is_empty() {
             thunks.push(generated.thunks);
         }
+        if !generated.mock_methods.is_empty() {
+            mock_methods.push(generated.mock_methods);
+        }
         if !generated.thunk_impls.is_empty() {
             thunk_impls.push(generated.thunk_impls);
         }
@@ -388,6 +1188,10 @@ fn generate_namespace(db: &Database, namespace: &Namespace) -> Result<GeneratedI
             assertions.push(generated.assertions);
         }
         features.extend(generated.features);
+        layout_checks.extend(generated.layout_checks);
+        stable_thunks.extend(generated.stable_thunks);
+        required_headers.extend(generated.required_headers);
+        std_array_types.extend(generated.std_array_types);
     }
 
     let reopened_namespace_idx = ir.get_reopened_namespace_idx(namespace.id)?;
@@ -444,8 +1248,13 @@ fn generate_namespace(db: &Database, namespace: &Namespace) -> Result<GeneratedI
         item: namespace_tokens,
         features,
         thunks: quote! { #( #thunks )* },
+        mock_methods: quote! { #( #mock_methods )* },
         thunk_impls: quote! { #( #thunk_impls )* },
         assertions: quote! { #( #assertions )* },
+        layout_checks,
+        stable_thunks,
+        required_headers,
+        std_array_types,
         ..Default::default()
     })
 }
@@ -454,10 +1263,68 @@ fn generate_namespace(db: &Database, namespace: &Namespace) -> Result<GeneratedI
 struct GeneratedItem {
     item: TokenStream,
     thunks: TokenStream,
+    // Methods of the `mock::CcThunks` trait (only populated when generating
+    // mock thunks).
+    mock_methods: TokenStream,
     // C++ source code for helper functions.
     thunk_impls: TokenStream,
     assertions: TokenStream,
     features: BTreeSet<Ident>,
+    // Layout properties to re-check at runtime (only populated when generating
+    // layout verification).
+    layout_checks: Vec<LayoutCheck>,
+    // Thunks whose symbol names are pinned by the `crubit_stable_thunk`
+    // attribute.
+    stable_thunks: Vec<StableThunk>,
+    // Public headers of the current target that `thunk_impls` needs (see
+    // `required_public_headers`).
+    required_headers: BTreeSet<Rc<str>>,
+    // The `std::array` types that `thunk_impls` uses, whose layouts are asserted
+    // once for the whole `rs_api_impl` (see `cc_std_array_layout_assertions`).
+    std_array_types: Vec<CcType>,
+    // Declarations of the methods of the extension trait of the enclosing
+    // record, and their implementations for the record (only populated with
+    // `--record_ext_traits`).
+    ext_trait_decls: TokenStream,
+    ext_trait_impls: TokenStream,
+}
+
+/// A thunk whose symbol name is pinned by the `crubit_stable_thunk` attribute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct StableThunk {
+    symbol: String,
+    /// The C++ signature of the thunk (see `generate_func::stable_thunk`).
+    signature: String,
+}
+
+/// A layout property of a record (e.g. its size) that `crubit_verify_layouts()`
+/// re-checks at runtime.
+#[derive(Clone, Debug)]
+struct LayoutCheck {
+    /// Human-readable description, e.g. "size of SomeStruct".
+    description: String,
+    /// The value of the property that Clang computed when importing the record.
+    expected_value: usize,
+    /// C++ constant expression computing the property (of type `size_t`).
+    cc_value: TokenStream,
+}
+
+impl GeneratedItem {
+    /// Appends the bindings of `other` to these bindings.
+    fn extend(&mut self, other: GeneratedItem) {
+        self.item.extend(other.item);
+        self.thunks.extend(other.thunks);
+        self.mock_methods.extend(other.mock_methods);
+        self.thunk_impls.extend(other.thunk_impls);
+        self.assertions.extend(other.assertions);
+        self.features.extend(other.features);
+        self.layout_checks.extend(other.layout_checks);
+        self.stable_thunks.extend(other.stable_thunks);
+        self.required_headers.extend(other.required_headers);
+        self.std_array_types.extend(other.std_array_types);
+        self.ext_trait_decls.extend(other.ext_trait_decls);
+        self.ext_trait_impls.extend(other.ext_trait_impls);
+    }
 }
 
 impl From<TokenStream> for GeneratedItem {
@@ -469,18 +1336,23 @@ impl From<TokenStream> for GeneratedItem {
 /// Returns generated bindings for an item, or `Err` if bindings generation
 /// failed in such a way as to make the generated bindings as a whole invalid.
 fn generate_item(db: &Database, item: &Item) -> Result<GeneratedItem> {
-    match generate_item_impl(db, item) {
-        Ok(generated) => Ok(generated),
+    let mut generated = match generate_item_impl(db, item) {
+        Ok(generated) => generated,
         Err(err) => {
             let ir = db.ir();
             if has_bindings(db, item) != HasBindings::Yes {
                 // We didn't guarantee that bindings would exist, so it is not invalid to
                 // write down the error but continue.
-                return generate_unsupported(db, &UnsupportedItem::new_with_cause(&ir, item, err));
+                generate_unsupported(db, &UnsupportedItem::new_with_cause(&ir, item, err))?
+            } else {
+                return Err(err);
             }
-            Err(err)
         }
+    };
+    if db.options().generate_item_spans && !generated.item.is_empty() {
+        generated.item = mark_item_span(item.id(), generated.item);
     }
+    Ok(generated)
 }
 
 /// The implementation of generate_item, without the error recovery logic.
@@ -495,22 +1367,87 @@ fn generate_item_impl(db: &Database, item: &Item) -> Result<GeneratedItem> {
     }
     let overloaded_funcs = db.overloaded_funcs();
     let generated_item = match item {
-        Item::Func(func) => match db.generate_func(func.clone())? {
-            None => GeneratedItem::default(),
-            Some((item, function_id)) => {
-                if overloaded_funcs.contains(&function_id) {
-                    bail!("Cannot generate bindings for overloaded function")
-                } else {
-                    (*item).clone()
+        Item::Func(func) => {
+            let generated_func = match db.generate_func(func.clone())? {
+                None => None,
+                Some((item, function_id)) => {
+                    if !overloaded_funcs.contains(&function_id) {
+                        Some((item, function_id))
+                    } else if let Some(rs_name) = db.overload_rs_names().get(&func.id) {
+                        generate_renamed_func(db, func.clone(), rs_name)?
+                    } else if db.options().overload_naming == OverloadNaming::None {
+                        bail!("Cannot generate bindings for overloaded function")
+                    } else {
+                        bail!(
+                            "Cannot generate bindings for overloaded function, because its \
+                             overloads can't be given distinct names"
+                        )
+                    }
+                }
+            };
+            match generated_func {
+                None => GeneratedItem::default(),
+                Some((item, function_id)) => {
+                    let mut generated_item = (*item).clone();
+                    for wrapper in generate_default_arg_wrappers(db, func, &function_id)? {
+                        generated_item.extend((*wrapper).clone());
+                    }
+                    if !db.overload_rs_names().contains_key(&func.id) {
+                        // The function itself is still usable if its alias can't be generated.
+                        match generate_renamed_from_alias(db, func, &function_id) {
+                            Ok(Some(alias)) => generated_item.extend(alias),
+                            Ok(None) => {}
+                            Err(err) => {
+                                db.errors().insert(&err);
+                                let msg = err.to_string();
+                                generated_item.item.extend(quote! { __COMMENT__ #msg });
+                            }
+                        }
+                    }
+                    generated_item
                 }
             }
-        },
+        }
         Item::IncompleteRecord(incomplete_record) => {
             generate_incomplete_record(db, incomplete_record)?
         }
-        Item::Record(record) => generate_record(db, record)?,
-        Item::Enum(enum_) => generate_enum(db, enum_)?,
-        Item::TypeAlias(type_alias) => generate_type_alias(db, type_alias)?,
+        Item::Record(record) => {
+            let mut generated_item = generate_record(db, record)?;
+            if let Some(old_name) = &record.renamed_from {
+                generated_item.item.extend(generate_renamed_type_alias(
+                    db,
+                    &**record,
+                    old_name,
+                    &record.rs_name,
+                )?);
+            }
+            generated_item
+        }
+        Item::Enum(enum_) => {
+            let mut generated_item = generate_enum(db, enum_)?;
+            if let (Some(old_name), Some(_)) = (&enum_.renamed_from, &enum_.enumerators) {
+                generated_item.item.extend(generate_renamed_type_alias(
+                    db,
+                    &**enum_,
+                    old_name,
+                    &enum_.identifier.identifier,
+                )?);
+            }
+            generated_item
+        }
+        Item::TypeAlias(type_alias) => {
+            let mut generated_item = generate_type_alias(db, type_alias)?;
+            if let Some(old_name) = &type_alias.renamed_from {
+                generated_item.item.extend(generate_renamed_type_alias(
+                    db,
+                    &**type_alias,
+                    old_name,
+                    &type_alias.identifier.identifier,
+                )?);
+            }
+            generated_item
+        }
+        Item::GlobalVar(var) => generate_global_var(db, var)?,
         Item::UnsupportedItem(unsupported) => generate_unsupported(db, unsupported)?,
         Item::Comment(comment) => generate_comment(comment)?,
         Item::Namespace(namespace) => generate_namespace(db, namespace)?,
@@ -535,10 +1472,27 @@ fn generate_item_impl(db: &Database, item: &Item) -> Result<GeneratedItem> {
                     an existing Rust type ({rs_type})",
                 cc_type = type_override.debug_name(&ir),
             );
-            let assertions = if let Some(size_align) = &type_override.size_align {
-                generate_record::rs_size_align_assertions(rs_type, size_align)
+            // The size and alignment recorded in the IR are checked against both the
+            // Rust type and the C++ type, so that a change on either side is caught
+            // when the bindings are compiled.
+            let (assertions, thunk_impls) = if let Some(size_align) = &type_override.size_align {
+                let cc_type = cc_type_name_for_item(item, &ir)?;
+                let size = Literal::usize_unsuffixed(size_align.size);
+                let alignment = Literal::usize_unsuffixed(size_align.alignment);
+                (
+                    generate_record::rs_size_align_assertions(rs_type, size_align),
+                    quote! {
+                        static_assert(sizeof(#cc_type) == #size);
+                        static_assert(alignof(#cc_type) == #alignment);
+                    },
+                )
             } else {
-                quote! {}
+                (quote! {}, quote! {})
+            };
+            let required_headers = if thunk_impls.is_empty() {
+                BTreeSet::new()
+            } else {
+                required_public_headers(&ir, [type_override.id])
             };
 
             GeneratedItem {
@@ -546,6 +1500,8 @@ fn generate_item_impl(db: &Database, item: &Item) -> Result<GeneratedItem> {
                     __COMMENT__ #disable_comment
                 },
                 assertions,
+                thunk_impls,
+                required_headers,
                 ..Default::default()
             }
         }
@@ -667,15 +1623,45 @@ fn has_bindings(db: &dyn BindingsGenerator, item: &Item) -> HasBindings {
             HasBindings::Yes => {}
         }
 
-        // TODO(b/200067824): Allow nested type items inside records.
         if item.is_type_definition() {
-            if let ir::Item::Record(_) = parent {
-                return HasBindings::No(NoBindingsReason::Unsupported {
-                    context: item.debug_name(&ir),
-                    error: anyhow!(
-                        "b/200067824: type definitions nested inside records are not yet supported"
-                    ),
-                });
+            if let ir::Item::Record(record) = parent {
+                if record.defining_target.is_some() {
+                    return HasBindings::No(NoBindingsReason::Unsupported {
+                        context: item.debug_name(&ir),
+                        error: anyhow!(
+                            "b/200067824: type definitions nested inside class template \
+                             instantiations are not yet supported"
+                        ),
+                    });
+                }
+                // The nested types are placed in a module named after the record, which
+                // can't have the same name as the record itself.
+                if record.nested_items_module_name() == record.rs_name {
+                    return HasBindings::No(NoBindingsReason::Unsupported {
+                        context: item.debug_name(&ir),
+                        error: anyhow!(
+                            "Can't generate bindings for types nested inside `{}`, because its \
+                             name is already `snake_case`",
+                            record.rs_name
+                        ),
+                    });
+                }
+                // Nor can it have the name of another item in the module of the record.
+                let module_name = record.nested_items_module_name();
+                if ir.is_current_target(&record.owning_target)
+                    && ir.namespace_qualifier(parent).is_ok_and(|namespace_qualifier| {
+                        db.module_item_names(namespace_qualifier).contains(&module_name)
+                    })
+                {
+                    return HasBindings::No(NoBindingsReason::Unsupported {
+                        context: item.debug_name(&ir),
+                        error: anyhow!(
+                            "Can't generate bindings for types nested inside `{}`, because the \
+                             name of their module `{module_name}` collides with another item",
+                            record.rs_name
+                        ),
+                    });
+                }
             }
         }
     }
@@ -695,6 +1681,16 @@ fn has_bindings(db: &dyn BindingsGenerator, item: &Item) -> HasBindings {
     }
 }
 
+impl NoBindingsReason {
+    fn category(&self) -> NoBindingsCategory {
+        match self {
+            NoBindingsReason::MissingRequiredFeatures { .. } => NoBindingsCategory::MissingFeatures,
+            NoBindingsReason::DependencyFailed { .. } => NoBindingsCategory::DependencyFailed,
+            NoBindingsReason::Unsupported { .. } => NoBindingsCategory::Unsupported,
+        }
+    }
+}
+
 impl From<NoBindingsReason> for Error {
     fn from(reason: NoBindingsReason) -> Error {
         match reason {
@@ -780,7 +1776,7 @@ fn required_crubit_features(
         }
     };
 
-    if let Some(unknown_attr) = item.unknown_attr() {
+    if let Some(unknown_attr) = untranslated_attrs(db, item.unknown_attr().as_deref()) {
         require_any_feature(&mut missing_features, ir::CrubitFeature::Experimental.into(), &|| {
             format!("unknown attribute(s): {unknown_attr}").into()
         });
@@ -848,7 +1844,9 @@ fn required_crubit_features(
                     );
                 }
                 for param in &func.params {
-                    if let Some(unknown_attr) = &param.unknown_attr {
+                    if let Some(unknown_attr) =
+                        untranslated_attrs(db, param.unknown_attr.as_deref())
+                    {
                         require_any_feature(
                             &mut missing_features,
                             ir::CrubitFeature::Experimental.into(),
@@ -885,6 +1883,24 @@ fn required_crubit_features(
                 &|| "".into(),
             );
         }
+        Item::GlobalVar(var) => {
+            let var_type = db.rs_type_kind(var.type_.rs_type.clone())?;
+            require_rs_type_kind(&mut missing_features, &var_type, &|| "type".into());
+            if var.value.is_none() {
+                require_any_feature(
+                    &mut missing_features,
+                    ir::CrubitFeature::Experimental.into(),
+                    &|| "non-constant variable".into(),
+                );
+            }
+            if var.is_macro {
+                require_any_feature(
+                    &mut missing_features,
+                    ir::CrubitFeature::Experimental.into(),
+                    &|| "macro constant".into(),
+                );
+            }
+        }
         Item::Namespace(_) => {
             require_any_feature(
                 &mut missing_features,
@@ -911,41 +1927,155 @@ fn required_crubit_features(
     Ok(missing_features)
 }
 
-// Returns the Rust code implementing bindings, plus any auxiliary C++ code
-// needed to support it.
-fn generate_bindings_tokens(
-    ir: Rc<IR>,
-    crubit_support_path_format: &str,
-    errors: Rc<dyn ErrorReporting>,
-    generate_source_loc_doc_comment: SourceLocationDocComment,
-) -> Result<BindingsTokens> {
-    let db = Database::new(ir.clone(), errors, generate_source_loc_doc_comment);
+/// The lint attributes at the top of the generated Rust crate, in addition to
+/// the ones that the bindings always need (e.g. `allow(nonstandard_style)`).
+///
+/// Parsed from a comma-separated list of `deny(...)`, `warn(...)` and
+/// `allow(...)` attributes, as passed to `--rs_api_lints`. The attributes are
+/// emitted in this order, so that a lint that is both denied and allowed ends up
+/// allowed.
+#[derive(Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct LintHeader {
+    deny: Vec<syn::Path>,
+    warn: Vec<syn::Path>,
+    allow: Vec<syn::Path>,
+}
+
+impl Default for LintHeader {
+    /// `#![deny(warnings)]`.
+    fn default() -> Self {
+        LintHeader { deny: vec![syn::parse_quote!(warnings)], warn: vec![], allow: vec![] }
+    }
+}
+
+impl std::str::FromStr for LintHeader {
+    type Err = arc_anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        use syn::parse::Parser;
+        let attrs = syn::punctuated::Punctuated::<syn::MetaList, syn::Token![,]>::parse_terminated
+            .parse_str(s)
+            .map_err(|err| anyhow!("Invalid lint attributes {s:?}: {err}"))?;
+        let mut lint_header = LintHeader { deny: vec![], warn: vec![], allow: vec![] };
+        for attr in attrs {
+            let lints = match attr.path.get_ident().map(Ident::to_string).as_deref() {
+                Some("deny") => &mut lint_header.deny,
+                Some("warn") => &mut lint_header.warn,
+                Some("allow") => &mut lint_header.allow,
+                _ => bail!("Unknown lint level in {s:?}: {}", attr.path.to_token_stream()),
+            };
+            let paths = attr
+                .parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+                )
+                .map_err(|err| anyhow!("Invalid lint attributes {s:?}: {err}"))?;
+            lints.extend(paths);
+        }
+        Ok(lint_header)
+    }
+}
+
+impl TryFrom<String> for LintHeader {
+    type Error = arc_anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl ToTokens for LintHeader {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for (level, lints) in [("deny", &self.deny), ("warn", &self.warn), ("allow", &self.allow)] {
+            if !lints.is_empty() {
+                let level = make_rs_ident(level);
+                tokens.extend(quote! { #![#level( #(#lints),* )] __NEWLINE__ });
+            }
+        }
+    }
+}
+
+// Returns the Rust code implementing bindings, plus any auxiliary C++ code
+// needed to support it.
+fn generate_bindings_tokens(
+    ir: Rc<IR>,
+    crubit_support_path_format: &str,
+    errors: Rc<dyn ErrorReporting>,
+    generate_source_loc_doc_comment: SourceLocationDocComment,
+    options: Rc<GeneratorOptions>,
+) -> Result<BindingsTokens> {
+    let db = Database::new(ir, errors, generate_source_loc_doc_comment, options);
+    let plan = bindings_plan::plan_bindings_impl(&db)?;
+    render_bindings_tokens(&db, &plan, crubit_support_path_format)
+}
+
+/// Runs the rendering phase of bindings generation: generates the bindings of
+/// the items of `plan`, which should have been returned by `plan_bindings` for
+/// the same `ir` and `options` (and may have been post-processed since, e.g. to
+/// drop items).
+pub fn render_bindings(
+    ir: Rc<IR>,
+    plan: &BindingsPlan,
+    crubit_support_path_format: &str,
+    errors: Rc<dyn ErrorReporting>,
+    generate_source_loc_doc_comment: SourceLocationDocComment,
+    options: Rc<GeneratorOptions>,
+) -> Result<BindingsTokens> {
+    let db = Database::new(ir, errors, generate_source_loc_doc_comment, options);
+    render_bindings_tokens(&db, plan, crubit_support_path_format)
+}
+
+/// Generates the bindings of the items of `plan` (see `bindings_plan`).
+fn render_bindings_tokens(
+    db: &Database,
+    plan: &BindingsPlan,
+    crubit_support_path_format: &str,
+) -> Result<BindingsTokens> {
+    let ir = db.ir();
+    ensure!(
+        plan.target == *ir.current_target(),
+        "The bindings plan is for {}, but the IR is for {}",
+        plan.target,
+        ir.current_target()
+    );
     let mut items = vec![];
     let mut thunks = vec![];
-    let mut thunk_impls = vec![
-        generate_rs_api_impl_includes(&db, crubit_support_path_format)?,
-        quote! {
+    let mut mock_methods = vec![];
+    let mut thunk_impls = vec![quote! {
             __HASH_TOKEN__ pragma clang diagnostic push __NEWLINE__
             // Disable Clang thread-safety-analysis warnings that would otherwise
             // complain about thunks that call mutex locking functions in an unpaired way.
             __HASH_TOKEN__ pragma clang diagnostic ignored "-Wthread-safety-analysis" __NEWLINE__
-        },
-    ];
+    }];
+    // Unless the thunks are explicitly annotated with an export macro, they are an
+    // implementation detail of the bindings and shouldn't be exported from a shared library.
+    let hide_thunks = db.options().thunk_export_macro.is_empty();
+    if hide_thunks {
+        thunk_impls.push(quote! {
+            __HASH_TOKEN__ pragma GCC visibility push(hidden) __NEWLINE__
+        });
+    }
     let mut assertions = vec![];
+    let mut layout_checks = vec![];
+    let mut stable_thunks = vec![];
+    let mut required_headers = BTreeSet::new();
+    let mut std_array_types = vec![];
 
     let mut features = BTreeSet::new();
 
     // For #![rustfmt::skip].
     features.insert(make_rs_ident("custom_inner_attributes"));
 
-    for top_level_item_id in ir.top_level_item_ids() {
-        let item =
-            ir.find_decl(*top_level_item_id).context("Failed to look up ir.top_level_item_ids")?;
-        let generated = generate_item(&db, item)?;
+    for planned_item in &plan.items {
+        let item = ir.find_decl(planned_item.id).context("Failed to look up the planned item")?;
+        let generated = generate_item(db, item)?;
         items.push(generated.item);
         if !generated.thunks.is_empty() {
             thunks.push(generated.thunks);
         }
+        if !generated.mock_methods.is_empty() {
+            mock_methods.push(generated.mock_methods);
+        }
         if !generated.assertions.is_empty() {
             assertions.push(generated.assertions);
         }
@@ -953,8 +2083,37 @@ fn generate_bindings_tokens(
             thunk_impls.push(generated.thunk_impls);
         }
         features.extend(generated.features);
+        layout_checks.extend(generated.layout_checks);
+        stable_thunks.extend(generated.stable_thunks);
+        required_headers.extend(generated.required_headers);
+        std_array_types.extend(generated.std_array_types);
+    }
+    let rs_api_impl_includes =
+        generate_rs_api_impl_includes(db, crubit_support_path_format, &required_headers)?;
+    let std_array_layout_assertions = cc_std_array_layout_assertions(&std_array_types, &ir)?;
+
+    // The layouts can't be verified against the C++ code when it is mocked out.
+    let verify_layouts_fn = if db.options().generate_layout_verification
+        && !db.options().generate_mock_thunks
+        && !layout_checks.is_empty()
+    {
+        let (rs_api, rs_api_impl) = generate_verify_layouts_fn(db, &layout_checks)?;
+        thunk_impls.push(rs_api_impl);
+        rs_api
+    } else {
+        quote! {}
+    };
+
+    if db.options().abi_hash_symbols {
+        thunk_impls.push(generate_abi_hash_symbols(db)?);
     }
 
+    if hide_thunks {
+        thunk_impls.push(quote! {
+            __NEWLINE__
+            __HASH_TOKEN__ pragma GCC visibility pop __NEWLINE__
+        });
+    }
     thunk_impls.push(quote! {
         __NEWLINE__
         __HASH_TOKEN__ pragma clang diagnostic pop __NEWLINE__
@@ -962,11 +2121,22 @@ fn generate_bindings_tokens(
         __NEWLINE__
     });
 
+    let options = db.options();
+    let lint_header = &options.rs_api_lints;
+    let detail_module = detail_module_ident(db);
     let mod_detail = if thunks.is_empty() {
         quote! {}
+    } else if db.options().generate_mock_thunks {
+        quote! {
+            mod #detail_module {
+                #[allow(unused_imports)]
+                use super::*;
+                #( #thunks )*
+            }
+        }
     } else {
         quote! {
-            mod detail {
+            mod #detail_module {
                 #[allow(unused_imports)]
                 use super::*;
                 extern "C" {
@@ -976,6 +2146,57 @@ fn generate_bindings_tokens(
         }
     };
 
+    let mod_prelude = if db.options().generate_prelude_module {
+        generate_prelude(db)?
+    } else {
+        quote! {}
+    };
+
+    let bindgen_aliases = if db.options().bindgen_allowlist_file.is_empty() {
+        quote! {}
+    } else {
+        generate_bindgen_aliases(db)?
+    };
+
+    let mod_cxx_extern_types = if db.options().generate_cxx_extern_types {
+        generate_mod_cxx_extern_types(db)?
+    } else {
+        quote! {}
+    };
+
+    let mod_mock = if thunks.is_empty() || !db.options().generate_mock_thunks {
+        quote! {}
+    } else {
+        generate_mod_mock(&mock_methods)
+    };
+
+    let mod_raw = if thunks.is_empty() || !db.options().generate_raw_thunks_module {
+        quote! {}
+    } else if let Some(comment) = generate_crubit_module_collision(db, "raw") {
+        comment
+    } else {
+        let doc_comment = generate_doc_comment(
+            Some(
+                "Raw `extern \"C\"` thunks used by the bindings in this crate.\n\n\
+                 # Safety\n\n\
+                 These functions perform no checks of their own. Callers must uphold all the\n\
+                 invariants that the corresponding safe wrappers would have upheld: pointers and\n\
+                 references must be valid and aligned, out-parameters must be valid for writes,\n\
+                 and the preconditions of the underlying C++ function must be satisfied.\n\n\
+                 The signatures of these functions are not stable and may change whenever the\n\
+                 bindings are regenerated.",
+            ),
+            None,
+            SourceLocationDocComment::Disabled,
+        );
+        quote! {
+            #doc_comment
+            pub mod raw {
+                pub use super::#detail_module::*;
+            }
+        }
+    };
+
     // Allows the use of #[__crubit::foo] attributes to control the behavior of
     // cc_bindings_from_rs on the generated code.
     //
@@ -1027,25 +2248,542 @@ fn generate_bindings_tokens(
             // C++ names don't follow Rust guidelines:
             #![allow(nonstandard_style)] __NEWLINE__
 
-            #![deny(warnings)] __NEWLINE__ __NEWLINE__
+            #lint_header __NEWLINE__
 
             #( #items __NEWLINE__ __NEWLINE__ )*
 
+            #bindgen_aliases __NEWLINE__ __NEWLINE__
+
             #mod_detail __NEWLINE__ __NEWLINE__
 
+            #mod_raw __NEWLINE__ __NEWLINE__
+
+            #mod_prelude __NEWLINE__ __NEWLINE__
+
+            #mod_cxx_extern_types __NEWLINE__ __NEWLINE__
+
+            #mod_mock __NEWLINE__ __NEWLINE__
+
+            #verify_layouts_fn
+
             #assertions
         },
-        rs_api_impl: quote! {#(#thunk_impls  __NEWLINE__ __NEWLINE__ )*},
+        rs_api_impl: quote! {
+            #rs_api_impl_includes __NEWLINE__ __NEWLINE__
+            #std_array_layout_assertions __NEWLINE__ __NEWLINE__
+            #(#thunk_impls  __NEWLINE__ __NEWLINE__ )*
+        },
+        stable_thunks,
+    })
+}
+
+/// Formats the Rust side of a thunk.
+///
+/// Normally this is a declaration of an `extern "C"` function (meant to be
+/// placed in the `extern "C"` block of the `detail` module).  When generating
+/// mock thunks, this is instead a pure-Rust function that forwards to the
+/// `mock::CcThunks` trait, and the returned `GeneratedItem` also contains the
+/// corresponding trait method.
+fn generate_thunk_decl(
+    db: &dyn BindingsGenerator,
+    thunk_attr: TokenStream,
+    thunk_visibility: TokenStream,
+    thunk_ident: &Ident,
+    generic_params: TokenStream,
+    params: &[(Ident, TokenStream)],
+    return_type_fragment: TokenStream,
+) -> GeneratedItem {
+    let (param_idents, param_types): (Vec<_>, Vec<_>) = params.iter().cloned().unzip();
+    if !db.options().generate_mock_thunks {
+        return GeneratedItem {
+            thunks: quote! {
+                #thunk_attr
+                #thunk_visibility fn #thunk_ident #generic_params(
+                    #( #param_idents: #param_types ),*
+                ) #return_type_fragment ;
+            },
+            ..Default::default()
+        };
+    }
+    let unmocked_message = format!("`{thunk_ident}` is not mocked");
+    GeneratedItem {
+        thunks: quote! {
+            #thunk_visibility unsafe fn #thunk_ident #generic_params(
+                #( #param_idents: #param_types ),*
+            ) #return_type_fragment {
+                super::mock::with_mock(|__mock| unsafe {
+                    __mock.#thunk_ident( #( #param_idents ),* )
+                })
+            }
+        },
+        mock_methods: quote! {
+            #[allow(unused_variables)]
+            unsafe fn #thunk_ident #generic_params(
+                &self, #( #param_idents: #param_types ),*
+            ) #return_type_fragment {
+                ::core::unimplemented!(#unmocked_message)
+            }
+        },
+        ..Default::default()
+    }
+}
+
+/// Generates `crubit_verify_layouts()` (returned as the first element) and the
+/// C++ function that it calls (returned as the second element).
+///
+/// `crubit_verify_layouts()` compares the layouts that Clang computed when the
+/// bindings were generated against the layouts that the C++ function, which is
+/// compiled as part of `rs_api_impl.cc`, reports at runtime. Only C++ code that
+/// is compiled along with `rs_api_impl.cc` is covered: the layouts that other
+/// C++ code (e.g. a separately built shared library) was compiled with can't be
+/// observed from the bindings.
+fn generate_verify_layouts_fn(
+    db: &Database,
+    layout_checks: &[LayoutCheck],
+) -> Result<(TokenStream, TokenStream)> {
+    let ir = db.ir();
+    let fn_name = format!("__crubit_layouts{}", ir.current_target().convert_to_cc_identifier());
+    let rs_fn_ident = make_rs_ident(&fn_name);
+    let cc_fn_ident = format_cc_ident(&fn_name);
+    let descriptions = layout_checks.iter().map(|check| &check.description);
+    let expected_values =
+        layout_checks.iter().map(|check| Literal::usize_unsuffixed(check.expected_value));
+    let cc_values = layout_checks.iter().map(|check| &check.cc_value);
+    let export_macro = if db.options().thunk_export_macro.is_empty() {
+        quote! {}
+    } else {
+        syn::parse_str::<TokenStream>(&db.options().thunk_export_macro)?
+    };
+
+    let rs_api = quote! {
+        /// A layout property of a type that differs between the bindings and the
+        /// C++ code that they were compiled with.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct CrubitLayoutMismatch {
+            /// What was checked, e.g. "size of SomeStruct".
+            pub description: &'static str,
+            /// The value that Clang computed when the bindings were generated.
+            pub expected_value: usize,
+            /// The value reported by the C++ side of the bindings.
+            pub cc_value: usize,
+        }
+        __NEWLINE__
+
+        /// Verifies that the sizes, alignments and field offsets of the C++
+        /// types in this crate, as computed by the C++ compiler that compiled
+        /// the C++ side of the bindings, match the ones that Clang computed when
+        /// the bindings were generated, and returns the mismatches (if any).
+        ///
+        /// This doesn't cover C++ code that was compiled separately from the
+        /// bindings (e.g. a shared library built at a different time).
+        pub fn crubit_verify_layouts() -> impl Iterator<Item = CrubitLayoutMismatch> {
+            extern "C" {
+                fn #rs_fn_ident(len: *mut usize) -> *const usize;
+            }
+            const EXPECTED_LAYOUTS: &[(&str, usize)] =
+                &[ #( (#descriptions, #expected_values) ),* ];
+            let mut cc_len: usize = 0;
+            // SAFETY: the C++ function returns a pointer to a static array of
+            // `cc_len` elements.
+            let cc_layouts = unsafe {
+                let cc_layouts = #rs_fn_ident(&mut cc_len);
+                ::core::slice::from_raw_parts(cc_layouts, cc_len)
+            };
+            let len_mismatch = (cc_len != EXPECTED_LAYOUTS.len()).then_some(CrubitLayoutMismatch {
+                description: "number of layout checks",
+                expected_value: EXPECTED_LAYOUTS.len(),
+                cc_value: cc_len,
+            });
+            len_mismatch.into_iter().chain(
+                EXPECTED_LAYOUTS
+                    .iter()
+                    .zip(cc_layouts)
+                    .filter(|((_, expected_value), cc_value)| expected_value != *cc_value)
+                    .map(|(&(description, expected_value), &cc_value)| CrubitLayoutMismatch {
+                        description,
+                        expected_value,
+                        cc_value,
+                    }),
+            )
+        }
+        __NEWLINE__ __NEWLINE__
+    };
+    let rs_api_impl = quote! {
+        extern "C" #export_macro const std::size_t* #cc_fn_ident(std::size_t* len) {
+            static constexpr std::size_t kLayouts[] = { #( #cc_values ),* };
+            *len = sizeof(kLayouts) / sizeof(kLayouts[0]);
+            return kLayouts;
+        }
+    };
+    Ok((rs_api, rs_api_impl))
+}
+
+/// Returns the name of the symbol whose name encodes a hash of the layout of
+/// `record` (see `generate_abi_hash_symbols`).
+///
+/// The hash covers the size and alignment of the record, and the names,
+/// offsets and sizes of its fields, as seen by the generator.
+fn abi_hash_symbol_name(record: &Record) -> String {
+    use std::fmt::Write;
+    let mut layout =
+        format!("{}:{}:{}", record.cc_name, record.size_align.size, record.size_align.alignment);
+    for field in &record.fields {
+        let name = field.identifier.as_ref().map(|id| id.identifier.as_ref()).unwrap_or_default();
+        write!(layout, ";{name}@{}:{}", field.offset, field.size).unwrap();
+    }
+    format!(
+        "__crubit_abi_hash__{record}_{odr_suffix}_{hash:08x}",
+        record = record.mangled_cc_name,
+        odr_suffix = record.owning_target.convert_to_cc_identifier(),
+        hash = generate_func::fnv1a_32(layout.as_bytes()),
+    )
+}
+
+/// Generates the C++ code which defines a symbol for each record of the current
+/// target, named after a hash of the layout of the record, and references the
+/// corresponding symbols of the records of the dependencies.
+///
+/// If the bindings of a dependency were generated from a different version of
+/// its headers than the bindings of the current target, the symbol names don't
+/// match, and linking fails, rather than the two crates silently disagreeing
+/// about the layout of a type.
+fn generate_abi_hash_symbols(db: &Database) -> Result<TokenStream> {
+    let ir = db.ir();
+    let export_macro = if db.options().thunk_export_macro.is_empty() {
+        quote! {}
+    } else {
+        syn::parse_str::<TokenStream>(&db.options().thunk_export_macro)?
+    };
+    let mut definitions = vec![];
+    let mut references = vec![];
+    for record in ir.records() {
+        // Template instantiations get bindings in each target that uses them.
+        if record.defining_target.is_some()
+            || has_bindings(db, ir.find_untyped_decl(record.id)) != HasBindings::Yes
+        {
+            continue;
+        }
+        let symbol = format_cc_ident(&abi_hash_symbol_name(record));
+        if record.owning_target == *ir.current_target() {
+            definitions.push(quote! {
+                extern "C" #export_macro const char #symbol = 0; __NEWLINE__
+            });
+        } else {
+            references.push(symbol);
+        }
+    }
+    let references = if references.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            #( extern "C" const char #references; __NEWLINE__ )*
+            [[gnu::used]] static const void* const __crubit_abi_hash_references[] = {
+                #( &#references ),*
+            };
+        }
+    };
+    Ok(quote! {
+        #( #definitions )*
+        #references
+    })
+}
+
+/// Generates the `mock` module, which lets Rust tests substitute the C++
+/// implementation of the bindings (see `generate_thunk_decl`).
+fn generate_mod_mock(mock_methods: &[TokenStream]) -> TokenStream {
+    let trait_doc_comment = generate_doc_comment(
+        Some(
+            "The C++ functions used by the bindings in this crate.\n\n\
+             The bindings were generated in mock mode, so they don't call into C++. Instead,\n\
+             every call is dispatched to the implementation of this trait installed via\n\
+             `set_mock`. Methods that are not overridden panic with `unimplemented!()`.",
+        ),
+        None,
+        SourceLocationDocComment::Disabled,
+    );
+    quote! {
+        pub mod mock {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #trait_doc_comment
+            pub trait CcThunks {
+                #( #mock_methods )*
+            }
+
+            struct Unmocked;
+            impl CcThunks for Unmocked {}
+
+            ::std::thread_local! {
+                static MOCK: ::core::cell::RefCell<
+                    ::core::option::Option<::std::boxed::Box<dyn CcThunks>>
+                > = ::core::cell::RefCell::new(::core::option::Option::None);
+            }
+
+            /// Installs `mock` for the current thread, and returns the previously installed mock (if any).
+            pub fn set_mock(
+                mock: ::std::boxed::Box<dyn CcThunks>,
+            ) -> ::core::option::Option<::std::boxed::Box<dyn CcThunks>> {
+                MOCK.with(|cell| cell.borrow_mut().replace(mock))
+            }
+
+            /// Uninstalls the mock of the current thread, and returns it (if any).
+            pub fn clear_mock() -> ::core::option::Option<::std::boxed::Box<dyn CcThunks>> {
+                MOCK.with(|cell| cell.borrow_mut().take())
+            }
+
+            pub(crate) fn with_mock<R>(f: impl ::core::ops::FnOnce(&dyn CcThunks) -> R) -> R {
+                MOCK.with(|cell| match &*cell.borrow() {
+                    ::core::option::Option::Some(mock) => f(&**mock),
+                    ::core::option::Option::None => f(&Unmocked),
+                })
+            }
+        }
+    }
+}
+
+/// Generates the `prelude` module, which re-exports the records, enums and free
+/// functions of the current target from all of its namespaces, so that they
+/// can be imported with a single `use ...::prelude::*`.
+///
+/// Items whose names collide (e.g. `ns1::Foo` and `ns2::Foo`) are not
+/// re-exported, and the `prelude` module lists them in a comment instead.
+/// Types nested inside records are not re-exported either. If the target has
+/// another top-level item named `prelude`, the module is replaced by a comment.
+fn generate_prelude(db: &Database) -> Result<TokenStream> {
+    if let Some(comment) = generate_crubit_module_collision(db, "prelude") {
+        return Ok(comment);
+    }
+    let ir = db.ir();
+    let overloaded_funcs = db.overloaded_funcs();
+    let overload_rs_names = db.overload_rs_names();
+    let mut name_to_qualifiers: BTreeMap<Rc<str>, Vec<NamespaceQualifier>> = BTreeMap::new();
+    for item in ir.current_target_items() {
+        let name: Rc<str> = match item {
+            // Template instantiations get mangled names, which are not meant to be spelled
+            // out by users.
+            Item::Record(record) if record.defining_target.is_none() => {
+                if has_bindings(db, item) != HasBindings::Yes {
+                    continue;
+                }
+                record.rs_name.clone()
+            }
+            Item::Enum(enum_) => {
+                if has_bindings(db, item) != HasBindings::Yes {
+                    continue;
+                }
+                enum_.identifier.identifier.clone()
+            }
+            Item::Func(func) if func.member_func_metadata.is_none() => {
+                let UnqualifiedIdentifier::Identifier(id) = &func.name else {
+                    continue;
+                };
+                if matches!(has_bindings(db, item), HasBindings::No(_)) {
+                    continue;
+                }
+                match db.generate_func(func.clone()) {
+                    Ok(Some((_, function_id))) if !overloaded_funcs.contains(&function_id) => {
+                        id.identifier.clone()
+                    }
+                    Ok(Some(_)) => match overload_rs_names.get(&func.id) {
+                        Some(rs_name) => rs_name.clone(),
+                        None => continue,
+                    },
+                    _ => continue,
+                }
+            }
+            _ => continue,
+        };
+        if is_nested_in_record(&ir, item) {
+            continue;
+        }
+        name_to_qualifiers.entry(name).or_default().push(ir.namespace_qualifier(item)?);
+    }
+
+    let crate_root_path = crate_root_path_tokens(&ir);
+    let reexports = name_to_qualifiers.into_iter().map(|(name, qualifiers)| {
+        if let [qualifier] = &qualifiers[..] {
+            let namespace_qualifier = qualifier.format_for_rs();
+            let ident = make_rs_ident(&name);
+            quote! { pub use #crate_root_path :: #namespace_qualifier #ident; __NEWLINE__ }
+        } else {
+            let paths = qualifiers
+                .iter()
+                .map(|qualifier| {
+                    qualifier.namespaces.iter().map(|ns| &**ns).chain([&*name]).join("::")
+                })
+                .join(", ");
+            let msg = format!("`{name}` is not re-exported, because it is ambiguous: {paths}");
+            quote! { __COMMENT__ #msg }
+        }
+    });
+    Ok(quote! {
+        pub mod prelude {
+            #( #reexports )*
+        }
+    })
+}
+
+/// Generates a `cxx_extern_types` module, which implements `cxx::ExternType`
+/// for the records and enums of the target, so that `#[cxx::bridge]` modules
+/// can refer to them (e.g. while migrating from `cxx` to Crubit).
+///
+/// The type IDs are the fully qualified C++ names. Records are `Trivial` (i.e.
+/// can be passed by value through `cxx`) if they have a trivial move
+/// constructor and a trivial destructor, and `Opaque` otherwise. Template
+/// instantiations and nested types are skipped, because `cxx::type_id!`
+/// can't spell them. If the target has another top-level item named
+/// `cxx_extern_types`, the module is replaced by a comment.
+fn generate_mod_cxx_extern_types(db: &Database) -> Result<TokenStream> {
+    if let Some(comment) = generate_crubit_module_collision(db, "cxx_extern_types") {
+        return Ok(comment);
+    }
+    let ir = db.ir();
+    let crate_root_path = crate_root_path_tokens(&ir);
+    let mut impls = vec![];
+    for item in ir.current_target_items() {
+        let (rs_name, cc_name, is_trivial) = match item {
+            Item::Record(record) if record.defining_target.is_none() => (
+                &record.rs_name,
+                &record.cc_name,
+                record.move_constructor == SpecialMemberFunc::Trivial
+                    && record.destructor == SpecialMemberFunc::Trivial,
+            ),
+            Item::Enum(enum_) => (&enum_.identifier.identifier, &enum_.identifier.identifier, true),
+            _ => continue,
+        };
+        if has_bindings(db, item) != HasBindings::Yes || is_nested_in_record(&ir, item) {
+            continue;
+        }
+        let namespace_qualifier = ir.namespace_qualifier(item)?;
+        let type_id =
+            namespace_qualifier.namespaces.iter().map(|ns| &**ns).chain([&**cc_name]).join("::");
+        let rs_namespace_qualifier = namespace_qualifier.format_for_rs();
+        let ident = make_rs_ident(rs_name);
+        let kind = make_rs_ident(if is_trivial { "Trivial" } else { "Opaque" });
+        impls.push(quote! {
+            unsafe impl ::cxx::ExternType for #crate_root_path :: #rs_namespace_qualifier #ident {
+                type Id = ::cxx::type_id!(#type_id);
+                type Kind = ::cxx::kind::#kind;
+            }
+        });
+    }
+    Ok(quote! {
+        pub mod cxx_extern_types {
+            #( #impls __NEWLINE__ )*
+        }
     })
 }
 
+/// Generates `pub use` aliases which export the namespaced records, enums, type
+/// aliases and free functions from the crate root, under the names that
+/// `bindgen` gives them: the C++ namespaces and the name of the item joined
+/// with `_` (e.g. `foo_bar_Baz` for `foo::bar::Baz`). Only the items declared in
+/// the headers matching the `bindgen_allowlist_file` regex are aliased.
+///
+/// This smooths the migration of code which used `bindgen`-generated bindings.
+/// Aliases which would collide with another item in the crate root are not
+/// generated.
+fn generate_bindgen_aliases(db: &Database) -> Result<TokenStream> {
+    let ir = db.ir();
+    // Like `bindgen`, the regex has to match the whole path of the header.
+    let allowlist = regex::Regex::new(&format!("^(?:{})$", db.options().bindgen_allowlist_file))
+        .map_err(|err| anyhow!("Invalid bindgen allowlist regex: {err}"))?;
+    let overloaded_funcs = db.overloaded_funcs();
+    let overload_rs_names = db.overload_rs_names();
+    let mut root_names = HashSet::new();
+    let mut alias_to_paths: BTreeMap<String, Vec<(NamespaceQualifier, Rc<str>)>> = BTreeMap::new();
+    for item in ir.current_target_items() {
+        let name: Rc<str> = match item {
+            Item::Record(record) if record.defining_target.is_none() => {
+                if has_bindings(db, item) != HasBindings::Yes {
+                    continue;
+                }
+                record.rs_name.clone()
+            }
+            Item::Enum(enum_) => {
+                if has_bindings(db, item) != HasBindings::Yes {
+                    continue;
+                }
+                enum_.identifier.identifier.clone()
+            }
+            Item::TypeAlias(type_alias) => {
+                if has_bindings(db, item) != HasBindings::Yes {
+                    continue;
+                }
+                type_alias.identifier.identifier.clone()
+            }
+            Item::Func(func) if func.member_func_metadata.is_none() => {
+                let UnqualifiedIdentifier::Identifier(id) = &func.name else {
+                    continue;
+                };
+                if matches!(has_bindings(db, item), HasBindings::No(_)) {
+                    continue;
+                }
+                match db.generate_func(func.clone()) {
+                    Ok(Some((_, function_id))) if !overloaded_funcs.contains(&function_id) => {
+                        id.identifier.clone()
+                    }
+                    Ok(Some(_)) => match overload_rs_names.get(&func.id) {
+                        Some(rs_name) => rs_name.clone(),
+                        None => continue,
+                    },
+                    _ => continue,
+                }
+            }
+            Item::Namespace(namespace) if namespace.enclosing_item_id.is_none() => {
+                root_names.insert(namespace.name.identifier.to_string());
+                continue;
+            }
+            _ => continue,
+        };
+        if is_nested_in_record(&ir, item) {
+            continue;
+        }
+        let namespace_qualifier = ir.namespace_qualifier(item)?;
+        if namespace_qualifier.namespaces.is_empty() {
+            root_names.insert(name.to_string());
+            continue;
+        }
+        let source_loc = item.source_loc().unwrap_or_default();
+        let Some((header, _)) = parse_source_loc(&source_loc) else {
+            continue;
+        };
+        if !allowlist.is_match(header) {
+            continue;
+        }
+        let alias = namespace_qualifier.namespaces.iter().map(|ns| &**ns).chain([&*name]).join("_");
+        alias_to_paths.entry(alias).or_default().push((namespace_qualifier, name));
+    }
+
+    let crate_root_path = crate_root_path_tokens(&ir);
+    let aliases = alias_to_paths.into_iter().map(|(alias, paths)| match &paths[..] {
+        [(namespace_qualifier, name)] if !root_names.contains(&alias) => {
+            let namespace_qualifier = namespace_qualifier.format_for_rs();
+            let ident = make_rs_ident(name);
+            let alias = make_rs_ident(&alias);
+            quote! { pub use #crate_root_path :: #namespace_qualifier #ident as #alias; __NEWLINE__ }
+        }
+        _ => {
+            let msg = format!("The bindgen alias `{alias}` is not generated, because it collides with another item");
+            quote! { __COMMENT__ #msg }
+        }
+    });
+    Ok(quote! { #( #aliases )* })
+}
+
+/// Returns whether `item` is declared inside of a record.
+fn is_nested_in_record(ir: &IR, item: &Item) -> bool {
+    item.enclosing_item_id().is_some_and(|id| matches!(ir.find_untyped_decl(id), Item::Record(_)))
+}
+
 /// Formats a C++ identifier.  Panics if `ident` is a C++ reserved keyword.
 fn format_cc_ident(ident: &str) -> TokenStream {
     code_gen_utils::format_cc_ident(ident).expect("IR should only contain valid C++ identifiers")
 }
 
 fn rs_type_kind(db: &dyn BindingsGenerator, ty: ir::RsType) -> Result<RsTypeKind> {
-    if let Some(unknown_attr) = &ty.unknown_attr {
+    if let Some(unknown_attr) = untranslated_attrs(db, ty.unknown_attr.as_deref()) {
         // In most places, we only bail for unknown attributes in supported. However,
         // it's difficult and expensive to generate an RsTypeKind differently
         // depending on the translation unit for the item that contains it.
@@ -1060,7 +2798,30 @@ fn rs_type_kind(db: &dyn BindingsGenerator, ty: ir::RsType) -> Result<RsTypeKind
     let ir = db.ir();
     // The lambdas deduplicate code needed by multiple `match` branches.
     let get_type_args = || -> Result<Vec<RsTypeKind>> {
-        ty.type_args.iter().map(|type_arg| db.rs_type_kind(type_arg.clone())).collect()
+        ty.type_args
+            .iter()
+            .map(|type_arg| {
+                let type_arg = db.rs_type_kind(type_arg.clone())?;
+                if let Some(bridge_type) = type_arg.as_bridge_type() {
+                    bail!(
+                        "{} is converted into {type_arg}, and can only be passed by value",
+                        bridge_type.cc_name()
+                    );
+                }
+                if type_arg.as_std_optional().is_some() {
+                    bail!(
+                        "std::optional is converted into {type_arg}, and can only be passed by value"
+                    );
+                }
+                if type_arg.as_initializer_list().is_some() {
+                    bail!(
+                        "std::initializer_list is converted into {type_arg}, and can only be \
+                        passed by value"
+                    );
+                }
+                Ok(type_arg)
+            })
+            .collect()
     };
     let get_pointee = || -> Result<Rc<RsTypeKind>> {
         if ty.type_args.len() != 1 {
@@ -1168,16 +2929,166 @@ fn rs_type_kind(db: &dyn BindingsGenerator, ty: ir::RsType) -> Result<RsTypeKind
                 );
                 RsTypeKind::Option(Rc::new(type_args.remove(0)))
             }
-            name => {
+            "#stdOptional" => {
                 let mut type_args = get_type_args()?;
-
-                if let Some(primitive) = PrimitiveType::from_str(name) {
-                    if !type_args.is_empty() {
-                        bail!("{name} type must not have type arguments: {:?}", ty);
-                    }
-                    RsTypeKind::Primitive(primitive)
-                } else if let Some(abi) = name.strip_prefix("#funcPtr ") {
-                    // Assert that function pointers in the IR either have static lifetime or
+                ensure!(
+                    type_args.len() == 1,
+                    "std::optional should have exactly 1 type argument (got {})",
+                    type_args.len()
+                );
+                let value_type = type_args.remove(0);
+                // The thunks move the value in and out of the `std::optional` through a
+                // pointer to `MaybeUninit<T>`.
+                ensure!(
+                    value_type.is_unpin(),
+                    "std::optional value type `{value_type}` must be Unpin"
+                );
+                RsTypeKind::StdOptional(Rc::new(value_type))
+            }
+            std_type_mapping::STRING_VIEW_RS_TYPE_NAME => {
+                ensure!(ty.type_args.is_empty(), "string view must not have type arguments");
+                let lifetime =
+                    if ty.lifetime_args.is_empty() { None } else { Some(get_lifetime()?) };
+                RsTypeKind::StringView(std_type_mapping::StringView { lifetime })
+            }
+            std_type_mapping::STD_STRING_TYPE_NAME => {
+                ensure!(ty.type_args.is_empty(), "std::string must not have type arguments");
+                RsTypeKind::StdString(StdString)
+            }
+            std_type_mapping::ABSL_STATUS_TYPE_NAME => {
+                ensure!(ty.type_args.is_empty(), "absl::Status must not have type arguments");
+                RsTypeKind::AbslStatus(AbslStatus { value_type: None })
+            }
+            std_type_mapping::ABSL_STATUS_OR_TYPE_NAME => {
+                let mut type_args = get_type_args()?;
+                ensure!(
+                    type_args.len() == 1,
+                    "absl::StatusOr should have exactly 1 type argument (got {})",
+                    type_args.len()
+                );
+                let value_type = type_args.remove(0);
+                // The value is moved into a `MaybeUninit` return slot owned by Rust.
+                let is_movable_into_rust = match value_type.unalias() {
+                    RsTypeKind::Primitive(_)
+                    | RsTypeKind::Pointer { .. }
+                    | RsTypeKind::Enum { .. } => true,
+                    RsTypeKind::Record { record, .. } => !record.is_opaque && record.is_unpin(),
+                    _ => false,
+                };
+                ensure!(
+                    is_movable_into_rust,
+                    "absl::StatusOr value type `{value_type}` must be a primitive, pointer, enum \
+                     or Unpin record"
+                );
+                RsTypeKind::AbslStatus(AbslStatus { value_type: Some(Rc::new(value_type)) })
+            }
+            std_type_mapping::UNIQUE_PTR_TYPE_NAME => {
+                let mut type_args = get_type_args()?;
+                ensure!(
+                    type_args.len() == 1,
+                    "std::unique_ptr should have exactly 1 type argument (got {})",
+                    type_args.len()
+                );
+                let pointee_type = type_args.remove(0);
+                // The deleter thunk calls `delete`, which needs the complete type.
+                ensure!(
+                    !matches!(pointee_type.unalias(), RsTypeKind::IncompleteRecord { .. }),
+                    "std::unique_ptr pointee type `{pointee_type}` must be complete"
+                );
+                RsTypeKind::UniquePtr(UniquePtr { pointee_type: Rc::new(pointee_type) })
+            }
+            std_type_mapping::SHARED_PTR_TYPE_NAME => {
+                let mut type_args = get_type_args()?;
+                ensure!(
+                    type_args.len() == 1,
+                    "std::shared_ptr should have exactly 1 type argument (got {})",
+                    type_args.len()
+                );
+                RsTypeKind::SharedPtr(SharedPtr { pointee_type: Rc::new(type_args.remove(0)) })
+            }
+            std_type_mapping::STD_FUNCTION_TYPE_NAME => {
+                let mut type_args = get_type_args()?;
+                ensure!(
+                    !type_args.is_empty(),
+                    "std::function should have at least the return type as a type argument"
+                );
+                // The closure is called through an `extern "C"` function, which receives the
+                // arguments and returns the result by value.
+                for type_arg in &type_args {
+                    ensure!(
+                        type_arg.is_c_abi_compatible_by_value()
+                            && !matches!(
+                                type_arg.unalias(),
+                                RsTypeKind::Reference { .. } | RsTypeKind::RvalueReference { .. }
+                            ),
+                        "std::function parameter and return types must be primitives, enums, or \
+                         pointers (got `{type_arg}`)"
+                    );
+                }
+                let return_type = type_args.remove(type_args.len() - 1);
+                RsTypeKind::StdFunction(StdFunction {
+                    return_type: Rc::new(return_type),
+                    param_types: Rc::from(type_args),
+                })
+            }
+            "#initializerList" => {
+                let mut type_args = get_type_args()?;
+                ensure!(
+                    type_args.len() == 1,
+                    "std::initializer_list should have exactly 1 type argument (got {})",
+                    type_args.len()
+                );
+                let element_type = type_args.remove(0);
+                // The elements are passed to the thunks through a pointer to the slice.
+                ensure!(
+                    element_type.is_unpin(),
+                    "std::initializer_list element type `{element_type}` must be Unpin"
+                );
+                RsTypeKind::InitializerList(Rc::new(element_type))
+            }
+            name @ (std_type_mapping::SPAN_CONST_RS_TYPE_NAME
+            | std_type_mapping::SPAN_MUT_RS_TYPE_NAME) => {
+                let mut type_args = get_type_args()?;
+                ensure!(
+                    type_args.len() == 1,
+                    "span should have exactly 1 type argument (got {})",
+                    type_args.len()
+                );
+                let element_type = type_args.remove(0);
+                // The Rust slice points directly at the elements of the span.
+                let is_same_layout = match element_type.unalias() {
+                    RsTypeKind::Primitive(_)
+                    | RsTypeKind::Pointer { .. }
+                    | RsTypeKind::Enum { .. } => true,
+                    RsTypeKind::Record { record, .. } => !record.is_opaque && record.is_unpin(),
+                    _ => false,
+                };
+                ensure!(
+                    is_same_layout,
+                    "span element type `{element_type}` must have the same layout in Rust and C++"
+                );
+                let mutability = if name == std_type_mapping::SPAN_CONST_RS_TYPE_NAME {
+                    Mutability::Const
+                } else {
+                    Mutability::Mut
+                };
+                RsTypeKind::Span { element_type: Rc::new(element_type), mutability }
+            }
+            name => {
+                let mut type_args = get_type_args()?;
+
+                if let Some(primitive) = PrimitiveType::from_str(name) {
+                    if !type_args.is_empty() {
+                        bail!("{name} type must not have type arguments: {:?}", ty);
+                    }
+                    RsTypeKind::Primitive(primitive)
+                } else if let Some(bridge_type) = BridgeType::from_rs_type_name(name) {
+                    if !type_args.is_empty() {
+                        bail!("{name} type must not have type arguments: {:?}", ty);
+                    }
+                    RsTypeKind::BridgeType(bridge_type)
+                } else if let Some(abi) = name.strip_prefix("#funcPtr ") {
+                    // Assert that function pointers in the IR either have static lifetime or
                     // no lifetime.
                     match get_lifetime() {
                         Err(_) => (), // No lifetime
@@ -1198,6 +3109,21 @@ fn rs_type_kind(db: &dyn BindingsGenerator, ty: ir::RsType) -> Result<RsTypeKind
                         return_type: Rc::new(type_args.remove(type_args.len() - 1)),
                         param_types: Rc::from(type_args),
                     }
+                } else if let Some(size) = name.strip_prefix("#array ") {
+                    let size = size
+                        .parse::<usize>()
+                        .with_context(|| format!("Invalid array size: {size}"))?;
+                    ensure!(
+                        type_args.len() == 1,
+                        "Array should have exactly 1 type argument (got {})",
+                        type_args.len()
+                    );
+                    let element_type = type_args.remove(0);
+                    ensure!(
+                        element_type.is_c_abi_compatible_by_value(),
+                        "Array element type `{element_type}` must be C-ABI compatible"
+                    );
+                    RsTypeKind::Array { element_type: Rc::new(element_type), size }
                 } else {
                     RsTypeKind::Other {
                         name: name.into(),
@@ -1271,10 +3197,8 @@ fn cc_qualified_path_prefix(item: &ir::Item, ir: &ir::IR) -> Result<TokenStream>
     };
     let parent: &ir::Item = ir.find_decl(parent)?;
     match parent {
-        ir::Item::Namespace(_) => Ok(ir.namespace_qualifier(item)?.format_for_cc()?),
-        ir::Item::Record(r) => {
-            let name = cc_tagless_type_name_for_record(r, ir)?;
-            Ok(quote! {#name ::})
+        ir::Item::Namespace(_) | ir::Item::Record(_) => {
+            Ok(ir.namespace_qualifier(item)?.format_for_cc()?)
         }
         _ => bail!("Unexpected enclosing item: {item:?}"),
     }
@@ -1318,6 +3242,58 @@ fn format_cc_type_inner(ty: &ir::CcType, ir: &IR, references_ok: bool) -> Result
         quote! {}
     };
     if let Some(ref name) = ty.name {
+        if let Some(string_view) = CcStringView::from_cc_type_name(name) {
+            let string_view_type = string_view.format_cc_type();
+            return Ok(quote! { #string_view_type #const_fragment });
+        }
+        if name.as_ref() == std_type_mapping::STD_STRING_TYPE_NAME {
+            return Ok(quote! { std::string #const_fragment });
+        }
+        if name.as_ref() == std_type_mapping::ABSL_STATUS_TYPE_NAME {
+            return Ok(quote! { absl::Status #const_fragment });
+        }
+        if name.as_ref() == std_type_mapping::ABSL_STATUS_OR_TYPE_NAME {
+            let [value_type] = ty.type_args.as_slice() else {
+                bail!("Invalid absl::StatusOr type (need exactly 1 type argument): {:?}", ty);
+            };
+            let value_type = format_cc_type_inner(value_type, ir, references_ok)?;
+            return Ok(quote! { absl::StatusOr<#value_type> #const_fragment });
+        }
+        if name.as_ref() == std_type_mapping::UNIQUE_PTR_TYPE_NAME {
+            let [pointee_type] = ty.type_args.as_slice() else {
+                bail!("Invalid std::unique_ptr type (need exactly 1 type argument): {:?}", ty);
+            };
+            let pointee_type = format_cc_type_inner(pointee_type, ir, references_ok)?;
+            return Ok(quote! { std::unique_ptr<#pointee_type> #const_fragment });
+        }
+        if name.as_ref() == std_type_mapping::SHARED_PTR_TYPE_NAME {
+            let [pointee_type] = ty.type_args.as_slice() else {
+                bail!("Invalid std::shared_ptr type (need exactly 1 type argument): {:?}", ty);
+            };
+            let pointee_type = format_cc_type_inner(pointee_type, ir, references_ok)?;
+            return Ok(quote! { std::shared_ptr<#pointee_type> #const_fragment });
+        }
+        if name.as_ref() == std_type_mapping::STD_FUNCTION_TYPE_NAME {
+            let Some((return_type, param_types)) = ty.type_args.split_last() else {
+                bail!("Invalid std::function type (need at least the return type): {:?}", ty);
+            };
+            let return_type = format_cc_type_inner(return_type, ir, references_ok)?;
+            let param_types = param_types
+                .iter()
+                .map(|t| format_cc_type_inner(t, ir, references_ok))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(quote! {
+                std::function<#return_type( #( #param_types ),* )> #const_fragment
+            });
+        }
+        if let Some(span) = CcSpan::from_cc_type_name(name) {
+            let [element_type] = ty.type_args.as_slice() else {
+                bail!("Invalid span type (need exactly 1 type argument): {:?}", ty);
+            };
+            let span_type =
+                span.format_cc_type(format_cc_type_inner(element_type, ir, references_ok)?);
+            return Ok(quote! { #span_type #const_fragment });
+        }
         match name.as_ref() {
             mut name @ ("*" | "&" | "&&") => {
                 if ty.type_args.len() != 1 {
@@ -1335,6 +3311,35 @@ fn format_cc_type_inner(ty: &ir::CcType, ir: &IR, references_ok: bool) -> Result
                 };
                 Ok(quote! {#nested_type #ptr #const_fragment})
             }
+            cc_type_name if cc_type_name.starts_with("#array ") => {
+                let (element_type, size) = cc_array_parts(ty)?;
+                let element_type = format_cc_type_inner(element_type, ir, references_ok)?;
+                // The constness of an array is the constness of its elements, which is already
+                // part of `element_type`.
+                Ok(quote! { crubit::type_identity_t<#element_type[#size]> })
+            }
+            cc_type_name if cc_type_name.starts_with("#stdArray ") => {
+                let (element_type, size) = cc_array_parts(ty)?;
+                let element_type = format_cc_type_inner(element_type, ir, references_ok)?;
+                Ok(quote! { std::array<#element_type, #size> #const_fragment })
+            }
+            "#stdOptional" => {
+                let [value_type] = ty.type_args.as_slice() else {
+                    bail!("Invalid std::optional type (need exactly 1 type argument): {:?}", ty);
+                };
+                let value_type = format_cc_type_inner(value_type, ir, references_ok)?;
+                Ok(quote! { std::optional<#value_type> #const_fragment })
+            }
+            "#initializerList" => {
+                let [element_type] = ty.type_args.as_slice() else {
+                    bail!(
+                        "Invalid std::initializer_list type (need exactly 1 type argument): {:?}",
+                        ty
+                    );
+                };
+                let element_type = format_cc_type_inner(element_type, ir, references_ok)?;
+                Ok(quote! { std::initializer_list<#element_type> #const_fragment })
+            }
             cc_type_name => match cc_type_name.strip_prefix("#funcValue ") {
                 None => {
                     if !ty.type_args.is_empty() {
@@ -1379,6 +3384,114 @@ fn format_cc_type_inner(ty: &ir::CcType, ir: &IR, references_ok: bool) -> Result
     }
 }
 
+/// Returns the element type and size of a C array or `std::array` type.
+fn cc_array_parts(ty: &ir::CcType) -> Result<(&ir::CcType, Literal)> {
+    let size = ty
+        .name
+        .as_deref()
+        .and_then(|name| name.strip_prefix("#array ").or_else(|| name.strip_prefix("#stdArray ")))
+        .with_context(|| format!("Not an array type: {ty:?}"))?;
+    let size = size.parse::<usize>().with_context(|| format!("Invalid array size: {size}"))?;
+    match ty.type_args.as_slice() {
+        [element_type] => Ok((element_type, Literal::usize_unsuffixed(size))),
+        _ => bail!("Invalid array type (need exactly 1 type argument): {:?}", ty),
+    }
+}
+
+/// Returns the `std::array` types in `ty`, including `ty` itself, with the
+/// nested ones first.
+fn cc_std_array_types(ty: &ir::CcType) -> Vec<ir::CcType> {
+    let mut array_types = ty.type_args.iter().flat_map(cc_std_array_types).collect_vec();
+    if ty.name.as_deref().is_some_and(|name| name.starts_with("#stdArray ")) {
+        array_types.push(ty.clone());
+    }
+    array_types
+}
+
+/// Returns C++ assertions that each distinct `std::array` type in `array_types`
+/// is laid out like the Rust array it is mapped to.
+fn cc_std_array_layout_assertions(array_types: &[ir::CcType], ir: &IR) -> Result<TokenStream> {
+    let mut assertions = vec![];
+    for ty in array_types.iter().unique() {
+        let (element_type, size) = cc_array_parts(ty)?;
+        let array_type = format_cc_type(ty, ir)?;
+        let element_type = format_cc_type(element_type, ir)?;
+        assertions.push(quote! {
+            static_assert(sizeof(#array_type) == #size * sizeof(#element_type));
+            static_assert(alignof(#array_type) == alignof(#element_type));
+        });
+    }
+    Ok(quote! { #( #assertions )* })
+}
+
+/// Returns the support library headers needed by the `BridgeType`s that the
+/// functions of the current target pass to or from C++.
+fn bridge_type_support_headers(db: &Database) -> BTreeSet<&'static str> {
+    let ir = db.ir();
+    ir.functions()
+        .filter(|func| ir.is_current_target(&func.owning_target))
+        .flat_map(|func| {
+            func.params.iter().map(|param| &param.type_).chain(std::iter::once(&func.return_type))
+        })
+        .filter_map(|type_| db.rs_type_kind(type_.rs_type.clone()).ok()?.as_bridge_type())
+        .filter_map(BridgeType::cc_support_header)
+        .collect()
+}
+
+/// Returns whether any function of the current target takes a
+/// `std::initializer_list`, whose thunk needs `crubit::CallWithInitializerList`.
+fn uses_initializer_list(db: &Database) -> bool {
+    let ir = db.ir();
+    let uses_initializer_list = ir
+        .functions()
+        .filter(|func| ir.is_current_target(&func.owning_target))
+        .flat_map(|func| func.params.iter())
+        .filter_map(|param| db.rs_type_kind(param.type_.rs_type.clone()).ok())
+        .any(|type_| type_.as_initializer_list().is_some());
+    uses_initializer_list
+}
+
+/// Returns whether any function of the current target returns an
+/// `absl::Status` or `absl::StatusOr<T>`, whose thunk needs
+/// `crubit::ReportStatus`.
+fn returns_absl_status(db: &Database) -> bool {
+    let ir = db.ir();
+    let returns_absl_status = ir
+        .functions()
+        .filter(|func| ir.is_current_target(&func.owning_target))
+        .filter_map(|func| db.rs_type_kind(func.return_type.rs_type.clone()).ok())
+        .any(|type_| type_.as_absl_status().is_some());
+    returns_absl_status
+}
+
+/// Returns whether any function of the current target takes or returns a
+/// `std::shared_ptr<T>`, whose thunks need the handle functions from
+/// `crubit/support/internal/shared_ptr.h`.
+fn uses_shared_ptr(db: &Database) -> bool {
+    let ir = db.ir();
+    let uses_shared_ptr = ir
+        .functions()
+        .filter(|func| ir.is_current_target(&func.owning_target))
+        .flat_map(|func| func.params.iter().map(|param| &param.type_).chain([&func.return_type]))
+        .filter_map(|type_| db.rs_type_kind(type_.rs_type.clone()).ok())
+        .any(|type_| type_.as_shared_ptr().is_some());
+    uses_shared_ptr
+}
+
+/// Returns whether any function of the current target takes a
+/// `std::function`, whose thunk needs the wrapper of Rust closures from
+/// `crubit/support/internal/function.h`.
+fn uses_std_function(db: &Database) -> bool {
+    let ir = db.ir();
+    let uses_std_function = ir
+        .functions()
+        .filter(|func| ir.is_current_target(&func.owning_target))
+        .flat_map(|func| func.params.iter())
+        .filter_map(|param| db.rs_type_kind(param.type_.rs_type.clone()).ok())
+        .any(|type_| type_.as_std_function().is_some());
+    uses_std_function
+}
+
 pub(crate) fn crate_root_path_tokens(ir: &IR) -> TokenStream {
     match ir.crate_root_path().as_deref().map(make_rs_ident) {
         None => quote! { crate },
@@ -1386,9 +3499,58 @@ pub(crate) fn crate_root_path_tokens(ir: &IR) -> TokenStream {
     }
 }
 
+/// Returns the name of the module holding the `extern "C"` thunks.
+pub(crate) fn detail_module_ident(db: &dyn BindingsGenerator) -> Ident {
+    let detail_module_name = &db.options().detail_module_name;
+    make_rs_ident(if detail_module_name.is_empty() { "detail" } else { detail_module_name })
+}
+
+/// Returns the path of the module holding the `extern "C"` thunks.
+pub(crate) fn detail_module_path_tokens(db: &dyn BindingsGenerator) -> TokenStream {
+    let crate_root_path = crate_root_path_tokens(&db.ir());
+    let detail_module = detail_module_ident(db);
+    quote! { #crate_root_path :: #detail_module }
+}
+
+/// Returns the public headers of the current target which the C++ code generated
+/// for an item needs, given the ids of the declarations that the code refers to.
+///
+/// The declarations of other targets don't need any extra headers, because they
+/// are reachable through the public headers of the current target. If a
+/// declaration of the current target is not in one of its public headers (e.g.
+/// it is in a textual header, or comes from a macro), all the public headers are
+/// returned.
+pub(crate) fn required_public_headers(
+    ir: &IR,
+    decl_ids: impl IntoIterator<Item = ItemId>,
+) -> BTreeSet<Rc<str>> {
+    let public_headers: HashSet<&str> = ir.public_headers().map(|hdr| &*hdr.name).collect();
+    let mut required_headers = BTreeSet::new();
+    for decl_id in decl_ids {
+        let item = ir.find_untyped_decl(decl_id);
+        if !item.owning_target().is_some_and(|target| ir.is_current_target(target)) {
+            continue;
+        }
+        let source_loc = item.source_loc().unwrap_or_default();
+        match parse_source_loc(&source_loc) {
+            Some((header, _)) if public_headers.contains(header) => {
+                required_headers.insert(header.into());
+            }
+            _ => return ir.public_headers().map(|hdr| hdr.name.clone()).collect(),
+        }
+    }
+    required_headers
+}
+
+/// Returns the ids of the declarations that `ty` refers to.
+pub(crate) fn cc_type_decl_ids(ty: &CcType) -> Vec<ItemId> {
+    ty.decl_id.into_iter().chain(ty.type_args.iter().flat_map(cc_type_decl_ids)).collect()
+}
+
 fn generate_rs_api_impl_includes(
     db: &Database,
     crubit_support_path_format: &str,
+    required_headers: &BTreeSet<Rc<str>>,
 ) -> Result<TokenStream> {
     let ir = db.ir();
 
@@ -1407,105 +3569,1282 @@ fn generate_rs_api_impl_includes(
             crubit_header.into(),
         ));
     }
-    let internal_includes = format_cc_includes(&internal_includes);
+    if !db.options().thunk_exception_note.is_empty() {
+        internal_includes.insert(CcInclude::SupportLibHeader(
+            crubit_support_path_format.into(),
+            "internal/exceptions.h".into(),
+        ));
+    }
+    if uses_initializer_list(db) {
+        internal_includes.insert(CcInclude::SupportLibHeader(
+            crubit_support_path_format.into(),
+            "internal/initializer_list.h".into(),
+        ));
+    }
+    if returns_absl_status(db) {
+        internal_includes.insert(CcInclude::SupportLibHeader(
+            crubit_support_path_format.into(),
+            "internal/absl_status.h".into(),
+        ));
+    }
+    if uses_shared_ptr(db) {
+        internal_includes.insert(CcInclude::SupportLibHeader(
+            crubit_support_path_format.into(),
+            "internal/shared_ptr.h".into(),
+        ));
+    }
+    if uses_std_function(db) {
+        internal_includes.insert(CcInclude::SupportLibHeader(
+            crubit_support_path_format.into(),
+            "internal/function.h".into(),
+        ));
+    }
+    for crubit_header in bridge_type_support_headers(db) {
+        internal_includes.insert(CcInclude::SupportLibHeader(
+            crubit_support_path_format.into(),
+            crubit_header.into(),
+        ));
+    }
+    let internal_includes = format_cc_includes(&internal_includes);
+
+    // In order to generate C++ thunk in all the cases Clang needs to be able to
+    // access declarations from public headers of the C++ library.  We don't
+    // process these includes via `format_cc_includes` to preserve their
+    // original order (some libraries require certain headers to be included
+    // first - e.g. `config.h`).
+    let public_headers = ir
+        .public_headers()
+        .filter(|hdr| {
+            !db.options().minimize_public_header_includes || required_headers.contains(&hdr.name)
+        })
+        .collect_vec();
+    let ir_includes = if !db.options().import_public_headers {
+        public_headers
+            .iter()
+            .map(|hdr| CcInclude::user_header(hdr.name.clone()).into_token_stream())
+            .collect_vec()
+    } else if !db.options().cc_module_name.is_empty() {
+        // The module exported by the target covers all of its public headers.
+        let cc_module_name = &db.options().cc_module_name;
+        let cc_module_name = syn::parse_str::<TokenStream>(cc_module_name)
+            .with_context(|| format!("Invalid C++ module name: {cc_module_name}"))?;
+        vec![quote! { import #cc_module_name; __NEWLINE__ }]
+    } else {
+        // Otherwise, each public header is imported as a C++20 header unit.
+        public_headers
+            .iter()
+            .map(|hdr| {
+                let path = hdr.name.as_ref();
+                quote! { import #path; __NEWLINE__ }
+            })
+            .collect_vec()
+    };
+
+    Ok(quote! {
+        #internal_includes
+        __NEWLINE__
+        __COMMENT__ "Public headers of the C++ library being wrapped."
+        #( #ir_includes )* __NEWLINE__
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ir_testing::{make_ir_from_items, retrieve_func, retrieve_record, with_lifetime_macros};
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use token_stream_matchers::{
+        assert_cc_matches, assert_cc_not_matches, assert_rs_matches, assert_rs_not_matches,
+    };
+    use token_stream_printer::rs_tokens_to_formatted_string_for_tests;
+
+    pub fn ir_from_cc(header: &str) -> Result<IR> {
+        ir_testing::ir_from_cc(multiplatform_testing::test_platform(), header)
+    }
+    pub fn ir_from_cc_dependency(header: &str, dep_header: &str) -> Result<IR> {
+        ir_testing::ir_from_cc_dependency(
+            multiplatform_testing::test_platform(),
+            header,
+            dep_header,
+        )
+    }
+    pub fn ir_record(name: &str) -> Record {
+        ir_testing::ir_record(multiplatform_testing::test_platform(), name)
+    }
+
+    pub fn generate_bindings_tokens(ir: IR) -> Result<BindingsTokens> {
+        generate_bindings_tokens_with_options(ir, GeneratorOptions::default())
+    }
+
+    pub fn generate_bindings_tokens_with_options(
+        ir: IR,
+        options: GeneratorOptions,
+    ) -> Result<BindingsTokens> {
+        super::generate_bindings_tokens(
+            Rc::new(ir),
+            "crubit/rs_bindings_support",
+            Rc::new(IgnoreErrors),
+            SourceLocationDocComment::Enabled,
+            Rc::new(options),
+        )
+    }
+
+    pub fn render_bindings_tokens_for_plan(
+        ir: Rc<IR>,
+        plan: &BindingsPlan,
+        options: Rc<GeneratorOptions>,
+    ) -> Result<BindingsTokens> {
+        super::render_bindings(
+            ir,
+            plan,
+            "crubit/rs_bindings_support",
+            Rc::new(IgnoreErrors),
+            SourceLocationDocComment::Enabled,
+            options,
+        )
+    }
+
+    pub fn db_from_cc(cc_src: &str) -> Result<Database> {
+        Ok(Database::new(
+            Rc::new(ir_from_cc(cc_src)?),
+            Rc::new(ErrorReport::new()),
+            SourceLocationDocComment::Enabled,
+            Rc::default(),
+        ))
+    }
+
+    #[test]
+    fn test_raw_thunks_module() -> Result<()> {
+        let ir = ir_from_cc("int Add(int a, int b);")?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_raw_thunks_module: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                mod detail {
+                    ...
+                    extern "C" {
+                        #[link_name = "_Z3Addii"]
+                        pub fn __rust_thunk___Z3Addii(...) -> ::core::ffi::c_int;
+                    }
+                }
+                ...
+                #[doc = ...]
+                pub mod raw {
+                    pub use super::detail::*;
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_header() -> Result<()> {
+        let rs_api = generate_bindings_tokens(ir_from_cc("int Add(int a, int b);")?)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #![allow(nonstandard_style)]
+                #![deny(warnings)]
+            }
+        );
+
+        let rs_api = generate_bindings_tokens_with_options(
+            ir_from_cc("int Add(int a, int b);")?,
+            GeneratorOptions {
+                rs_api_lints: "allow(dead_code, clippy::all), warn(warnings)".parse()?,
+                ..Default::default()
+            },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #![allow(nonstandard_style)]
+                #![warn(warnings)]
+                #![allow(dead_code, clippy::all)]
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { deny });
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_header_from_str() -> Result<()> {
+        let lint_header: LintHeader = "".parse()?;
+        assert!(lint_header.to_token_stream().is_empty());
+        let lint_header: LintHeader = "deny(warnings), deny(unused)".parse()?;
+        assert_eq!(
+            lint_header.to_token_stream().to_string(),
+            quote! { #![deny(warnings, unused)] __NEWLINE__ }.to_string()
+        );
+        assert!("forbid(warnings)".parse::<LintHeader>().is_err());
+        assert!("deny".parse::<LintHeader>().is_err());
+        assert!("deny(\"warnings\")".parse::<LintHeader>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_generator_options_from_json() -> Result<()> {
+        let options: GeneratorOptions = serde_json::from_str(
+            r#"{
+                "hash_thunk_names": true,
+                "overload_naming": "param_types",
+                "rs_api_lints": "allow(dead_code)",
+                "attribute_translations": { "gnu::hot": "" }
+            }"#,
+        )?;
+        assert!(options.hash_thunk_names);
+        assert!(!options.generate_mock_thunks);
+        assert_eq!(options.overload_naming, OverloadNaming::ParamTypes);
+        assert_eq!(
+            options.rs_api_lints.to_token_stream().to_string(),
+            quote! { #![allow(dead_code)] __NEWLINE__ }.to_string()
+        );
+        assert_eq!(options.attribute_translations.get("gnu::hot").map(|s| &**s), Some(""));
+
+        let options: GeneratorOptions = serde_json::from_str("{}")?;
+        assert_eq!(options.overload_naming, OverloadNaming::None);
+        assert_eq!(
+            options.rs_api_lints.to_token_stream().to_string(),
+            LintHeader::default().to_token_stream().to_string()
+        );
+
+        assert!(serde_json::from_str::<GeneratorOptions>(r#"{"no_such_option": true}"#).is_err());
+        assert!(serde_json::from_str::<GeneratorOptions>(r#"{"rs_api_lints": "deny"}"#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_thunks() -> Result<()> {
+        let ir = ir_from_cc("int Add(int a, int b);")?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_mock_thunks: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Add(a: ::core::ffi::c_int, b: ::core::ffi::c_int) -> ::core::ffi::c_int {
+                    unsafe { crate::detail::__rust_thunk___Z3Addii(a, b) }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                mod detail {
+                    #[allow(unused_imports)]
+                    use super::*;
+                    pub(crate) unsafe fn __rust_thunk___Z3Addii(
+                        a: ::core::ffi::c_int, b: ::core::ffi::c_int
+                    ) -> ::core::ffi::c_int {
+                        super::mock::with_mock(|__mock| unsafe {
+                            __mock.__rust_thunk___Z3Addii(a, b)
+                        })
+                    }
+                }
+                ...
+                pub mod mock {
+                    ...
+                    pub trait CcThunks {
+                        #[allow(unused_variables)]
+                        unsafe fn __rust_thunk___Z3Addii(
+                            &self, a: ::core::ffi::c_int, b: ::core::ffi::c_int
+                        ) -> ::core::ffi::c_int {
+                            ::core::unimplemented!("`__rust_thunk___Z3Addii` is not mocked")
+                        }
+                    }
+                    ...
+                    pub fn set_mock(...) -> ... { ... }
+                    ...
+                }
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { extern "C" });
+        assert_rs_not_matches!(rs_api, quote! { link_name });
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_thunks_module_with_colliding_name() -> Result<()> {
+        let ir = ir_from_cc("int Add(int a, int b); struct raw final {};")?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_raw_thunks_module: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                __COMMENT__ "The `raw` module is not generated, because it collides with another item"
+            }
+        );
+        assert_rs_matches!(rs_api, quote! { pub struct raw });
+        assert_rs_not_matches!(rs_api, quote! { pub mod raw });
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_thunks_module_disabled_by_default() -> Result<()> {
+        let ir = ir_from_cc("int Add(int a, int b);")?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub(crate) fn __rust_thunk___Z3Addii });
+        assert_rs_not_matches!(rs_api, quote! { pub mod raw });
+        Ok(())
+    }
+
+    #[test]
+    fn test_detail_module_name() -> Result<()> {
+        let ir = ir_from_cc("int Add(int a, int b);")?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions {
+                generate_raw_thunks_module: true,
+                detail_module_name: "__crubit_detail".into(),
+                ..Default::default()
+            },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn Add(a: ::core::ffi::c_int, b: ::core::ffi::c_int) -> ::core::ffi::c_int {
+                    unsafe { crate::__crubit_detail::__rust_thunk___Z3Addii(a, b) }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                mod __crubit_detail {
+                    ...
+                }
+                ...
+                pub mod raw {
+                    pub use super::__crubit_detail::*;
+                }
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { mod detail });
+        Ok(())
+    }
+
+    #[test]
+    fn test_crubit_support_path_format() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; };")?;
+        let rs_api_impl = super::generate_bindings_tokens(
+            Rc::new(ir),
+            "\"vendored/crubit/{header}\"",
+            Rc::new(IgnoreErrors),
+            SourceLocationDocComment::Enabled,
+            Rc::default(),
+        )?
+        .rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { __HASH_TOKEN__ include "vendored/crubit/internal/sizeof.h" }
+        );
+        assert_cc_not_matches!(rs_api_impl, quote! { crubit/rs_bindings_support });
+        Ok(())
+    }
+
+    #[test]
+    fn test_public_headers_are_included_by_default() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; };")?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { __HASH_TOKEN__ include "ir_from_cc_virtual_header.h" }
+        );
+        assert_cc_not_matches!(rs_api_impl, quote! { import });
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_public_headers_as_header_units() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; };")?;
+        let rs_api_impl = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { import_public_headers: true, ..Default::default() },
+        )?
+        .rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                __COMMENT__ "Public headers of the C++ library being wrapped."
+                import "ir_from_cc_virtual_header.h";
+            }
+        );
+        assert_cc_not_matches!(
+            rs_api_impl,
+            quote! { __HASH_TOKEN__ include "ir_from_cc_virtual_header.h" }
+        );
+        // Other headers are still `#include`d.
+        assert_cc_matches!(rs_api_impl, quote! { __HASH_TOKEN__ include <memory> });
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_public_headers_as_module() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; };")?;
+        let rs_api_impl = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions {
+                import_public_headers: true,
+                cc_module_name: "foo.bar".into(),
+                ..Default::default()
+            },
+        )?
+        .rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                __COMMENT__ "Public headers of the C++ library being wrapped."
+                import foo.bar;
+            }
+        );
+        assert_cc_not_matches!(rs_api_impl, quote! { "ir_from_cc_virtual_header.h" });
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimal_includes_keep_headers_needed_by_thunks() -> Result<()> {
+        let ir = ir_from_cc("inline int Add(int a, int b) { return a + b; }")?;
+        let rs_api_impl = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { minimize_public_header_includes: true, ..Default::default() },
+        )?
+        .rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { __HASH_TOKEN__ include "ir_from_cc_virtual_header.h" }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimal_includes_keep_headers_needed_by_layout_assertions() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; };")?;
+        let rs_api_impl = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { minimize_public_header_includes: true, ..Default::default() },
+        )?
+        .rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { __HASH_TOKEN__ include "ir_from_cc_virtual_header.h" }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimal_includes_skip_headers_without_thunks() -> Result<()> {
+        let ir = ir_from_cc("enum class SomeEnum : int { kA };")?;
+        let rs_api_impl = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { minimize_public_header_includes: true, ..Default::default() },
+        )?
+        .rs_api_impl;
+        assert_cc_not_matches!(rs_api_impl, quote! { "ir_from_cc_virtual_header.h" });
+
+        // The header is included by default.
+        let ir = ir_from_cc("enum class SomeEnum : int { kA };")?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { __HASH_TOKEN__ include "ir_from_cc_virtual_header.h" }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_spans_are_not_marked_by_default() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; };")?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api.to_string();
+        assert!(!rs_api.contains(ITEM_SPAN_BEGIN_MARKER), "{rs_api}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_spans_are_marked() -> Result<()> {
+        let ir = ir_from_cc("namespace ns { struct S { int x; }; }")?;
+        let record_id = retrieve_record(&ir, "S").id;
+        let begin = format!("{ITEM_SPAN_BEGIN_MARKER}{record_id}");
+        let end = format!("{ITEM_SPAN_END_MARKER}{record_id}");
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_item_spans: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub mod ns {
+                    ...
+                    __COMMENT__ #begin
+                    ...
+                    pub struct S { ... }
+                    ...
+                    __COMMENT__ #end
+                    ...
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_item_spans() -> Result<()> {
+        let rs_api = "\
+            // Header\n\
+            // crubit-item-span-begin 1\n\
+            pub mod ns {\n\
+            \x20   // crubit-item-span-begin 2\n\
+            \x20   pub fn f() {}\n\
+            \x20   // crubit-item-span-end 2\n\
+            }\n\
+            // crubit-item-span-end 1\n\
+            // crubit-item-span-begin 3\n\
+            pub fn g() {}\n\
+            \n\
+            // crubit-item-span-end 3\n";
+        let (code, item_spans) = extract_item_spans(rs_api)?;
+        assert_eq!(code, "// Header\npub mod ns {\n    pub fn f() {}\n}\npub fn g() {}\n\n");
+        assert_eq!(
+            item_spans,
+            vec![
+                ItemSpan { item_id: ItemId::new_for_testing(1), range: 10..43 },
+                ItemSpan { item_id: ItemId::new_for_testing(2), range: 23..41 },
+                ItemSpan { item_id: ItemId::new_for_testing(3), range: 43..57 },
+            ]
+        );
+        assert_eq!(&code[23..41], "    pub fn f() {}\n");
+        assert_eq!(&code[43..57], "pub fn g() {}\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_item_spans_unmatched_markers() {
+        let err = extract_item_spans("// crubit-item-span-begin 1\nfn f() {}\n").unwrap_err();
+        assert_eq!(err.to_string(), "Unmatched start of the span of item 1");
+        let err = extract_item_spans("fn f() {}\n// crubit-item-span-end 1\n").unwrap_err();
+        assert_eq!(err.to_string(), "Unmatched end of the span of item 1");
+        let err = extract_item_spans(
+            "// crubit-item-span-begin 1\n// crubit-item-span-begin 2\n// crubit-item-span-end 1\n",
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "The span of item 2 ends with the end of the span of item 1");
+    }
+
+    #[test]
+    fn test_resolve_line_directives() {
+        let rs_api_impl = "\
+            // Header\n\
+            #line 12 \"foo/bar.h\"\n\
+            extern \"C\" void thunk() { f(); }\n\
+            #line __CRUBIT_GENERATED_LINE__\n\
+            \n\
+            static_assert(true);\n";
+        assert_eq!(
+            resolve_line_directives(rs_api_impl, "out/foo_rust_api_impl.cc"),
+            "// Header\n\
+             #line 12 \"foo/bar.h\"\n\
+             extern \"C\" void thunk() { f(); }\n\
+             #line 5 \"out/foo_rust_api_impl.cc\"\n\
+             \n\
+             static_assert(true);\n"
+        );
+    }
+
+    #[test]
+    fn test_serialize_item_spans() -> Result<()> {
+        let item_spans = [
+            ItemSpan { item_id: ItemId::new_for_testing(1), range: 10..43 },
+            ItemSpan { item_id: ItemId::new_for_testing(3), range: 43..57 },
+        ];
+        assert_eq!(
+            String::from_utf8(serialize_item_spans(&item_spans)?).unwrap(),
+            r#"[{"end":43,"id":1,"start":10},{"end":57,"id":3,"start":43}]"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_bindings_status() -> Result<()> {
+        let bindings_status = HashMap::from([
+            (ItemId::new_for_testing(3), BindingsStatus::No(NoBindingsCategory::Unsupported)),
+            (ItemId::new_for_testing(1), BindingsStatus::Yes),
+            (ItemId::new_for_testing(2), BindingsStatus::Maybe),
+        ]);
+        assert_eq!(
+            String::from_utf8(serialize_bindings_status(&bindings_status)?).unwrap(),
+            r#"[{"id":1,"status":"yes"},{"id":2,"status":"maybe"},{"id":3,"status":{"no":"unsupported"}}]"#
+        );
+        Ok(())
+    }
+
+    fn stable_thunk(symbol: &str, signature: &str) -> StableThunk {
+        StableThunk { symbol: symbol.to_string(), signature: signature.to_string() }
+    }
+
+    #[test]
+    fn test_stable_thunks_by_symbol() -> Result<()> {
+        let stable_thunks = stable_thunks_by_symbol(vec![
+            stable_thunk("mylib_b_v1", "void ()"),
+            stable_thunk("mylib_a_v1", "int (int)"),
+        ])?;
+        assert_eq!(
+            serde_json::to_string(&stable_thunks).unwrap(),
+            r#"{"mylib_a_v1":"int (int)","mylib_b_v1":"void ()"}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_stable_thunks_by_symbol_duplicate_symbol() {
+        let err = stable_thunks_by_symbol(vec![
+            stable_thunk("mylib_a_v1", "int (int)"),
+            stable_thunk("mylib_a_v1", "int (long)"),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The thunk symbol `mylib_a_v1` is pinned by more than one function"
+        );
+    }
+
+    #[test]
+    fn test_check_stable_thunks() -> Result<()> {
+        let stable_thunks = stable_thunks_by_symbol(vec![
+            stable_thunk("mylib_a_v1", "int (int)"),
+            stable_thunk("mylib_b_v1", "void ()"),
+        ])?;
+        // Adding a stable thunk is compatible.
+        check_stable_thunks(br#"{"mylib_a_v1": "int (int)"}"#, &stable_thunks)?;
+        check_stable_thunks(b"{}", &stable_thunks)?;
+
+        let err = check_stable_thunks(
+            br#"{"mylib_a_v1": "int (long)", "mylib_c_v1": "void ()"}"#,
+            &stable_thunks,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The bindings are incompatible with the stable thunks baseline: \
+             the signature of `mylib_a_v1` changed from `int (long)` to `int (int)`, \
+             `mylib_c_v1` was removed"
+        );
+
+        let err = check_stable_thunks(b"[]", &stable_thunks).unwrap_err();
+        assert!(
+            err.to_string().starts_with("Failed to parse the stable thunks baseline: "),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_disable_thread_safety_warnings() -> Result<()> {
+        let ir = ir_from_cc("inline void foo() {}")?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                ...
+                __HASH_TOKEN__ pragma clang diagnostic push
+                __HASH_TOKEN__ pragma clang diagnostic ignored "-Wthread-safety-analysis"
+                ...
+
+                __HASH_TOKEN__ pragma clang diagnostic pop
+                ...
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_thunks_hidden_by_default() -> Result<()> {
+        let ir = ir_from_cc("inline void foo() {}")?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                ...
+                __HASH_TOKEN__ pragma GCC visibility push(hidden)
+                ...
+                extern "C" void __rust_thunk___Z3foov() { foo(); }
+                ...
+                __HASH_TOKEN__ pragma GCC visibility pop
+                ...
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_thunk_export_macro() -> Result<()> {
+        let ir = ir_from_cc("inline void foo() {}")?;
+        let rs_api_impl = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { thunk_export_macro: "MYLIB_EXPORT".into(), ..Default::default() },
+        )?
+        .rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" MYLIB_EXPORT void __rust_thunk___Z3foov() { foo(); }
+            }
+        );
+        assert_cc_not_matches!(rs_api_impl, quote! { __HASH_TOKEN__ pragma GCC visibility });
+        Ok(())
+    }
+
+    #[test]
+    fn test_layout_verification() -> Result<()> {
+        let ir = ir_from_cc("namespace ns { struct S { int x; }; }")?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_layout_verification: true, ..Default::default() },
+        )?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub fn crubit_verify_layouts() -> impl Iterator<Item = CrubitLayoutMismatch> {
+                    extern "C" {
+                        fn ... (len: *mut usize) -> *const usize;
+                    }
+                    const EXPECTED_LAYOUTS: &[(&str, usize)] = &[
+                        ("size of ns::S", 4),
+                        ("alignment of ns::S", 4),
+                        ("offset of ns::S::x", 0)
+                    ];
+                    ...
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" const std::size_t* ... (std::size_t* len) {
+                    static constexpr std::size_t kLayouts[] = {
+                        CRUBIT_SIZEOF(struct ns::S),
+                        alignof(struct ns::S),
+                        CRUBIT_OFFSET_OF(x, struct ns::S)
+                    };
+                    *len = sizeof(kLayouts) / sizeof(kLayouts[0]);
+                    return kLayouts;
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_crubit_metadata() {
+        let metadata = generate_crubit_metadata("1.2.3", "{\"lazy_ir\":true}", b"{}");
+        let ir_hash = format!("{:08x}", generate_func::fnv1a_32(b"{}"));
+        assert_rs_matches!(
+            metadata,
+            quote! {
+                pub mod __crubit_metadata {
+                    ...
+                    pub const METADATA: CrubitMetadata = CrubitMetadata {
+                        version: "1.2.3",
+                        options: "{\"lazy_ir\":true}",
+                        ir_hash: #ir_hash,
+                    };
+                }
+            }
+        );
+        assert_rs_matches!(
+            metadata,
+            quote! {
+                #[macro_export]
+                macro_rules! crubit_metadata {
+                    () => {
+                        $crate::__crubit_metadata::METADATA
+                    };
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_crubit_metadata_depends_on_ir() {
+        let metadata = generate_crubit_metadata("1.2.3", "{}", b"{}").to_string();
+        assert_eq!(metadata, generate_crubit_metadata("1.2.3", "{}", b"{}").to_string());
+        assert_ne!(metadata, generate_crubit_metadata("1.2.3", "{}", b"{ }").to_string());
+    }
+
+    #[test]
+    fn test_layout_verification_disabled_by_default() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; };")?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_not_matches!(rs_api, quote! { crubit_verify_layouts });
+        assert_cc_not_matches!(rs_api_impl, quote! { kLayouts });
+        Ok(())
+    }
+
+    #[test]
+    fn test_abi_hash_symbols() -> Result<()> {
+        let ir = ir_from_cc_dependency("struct S { Dep dep; };", "struct Dep { int x; };")?;
+        let local_symbol = format_cc_ident(&abi_hash_symbol_name(retrieve_record(&ir, "S")));
+        let dep_symbol = format_cc_ident(&abi_hash_symbol_name(retrieve_record(&ir, "Dep")));
+        let rs_api_impl = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { abi_hash_symbols: true, ..Default::default() },
+        )?
+        .rs_api_impl;
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" const char #local_symbol = 0;
+                extern "C" const char #dep_symbol;
+                [[gnu::used]] static const void* const __crubit_abi_hash_references[] = {
+                    &#dep_symbol
+                };
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_abi_hash_symbol_name_depends_on_layout() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; }; struct T { int x; int y; };")?;
+        let s_symbol = abi_hash_symbol_name(retrieve_record(&ir, "S"));
+        assert!(s_symbol.starts_with("__crubit_abi_hash__1S___2f_2ftest"), "{s_symbol}");
+
+        let mut s = retrieve_record(&ir, "S").clone();
+        assert_eq!(abi_hash_symbol_name(&s), s_symbol);
+        s.size_align.size += 4;
+        assert_ne!(abi_hash_symbol_name(&s), s_symbol);
+
+        let mut s = retrieve_record(&ir, "S").clone();
+        s.fields[0].offset += 32;
+        assert_ne!(abi_hash_symbol_name(&s), s_symbol);
+        Ok(())
+    }
+
+    #[test]
+    fn test_abi_hash_symbols_disabled_by_default() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; };")?;
+        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
+        assert_cc_not_matches!(rs_api_impl, quote! { __crubit_abi_hash_references });
+        assert!(!rs_api_impl.to_string().contains("__crubit_abi_hash"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prelude_module() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace ns1 {
+              struct SomeStruct { int x; };
+              enum class SomeEnum : int { kA };
+              void SomeFunc();
+            }
+            namespace ns2 {
+              namespace nested {
+                void OtherFunc();
+              }
+            }
+            struct TopLevelStruct final {
+              void Method();
+            };
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_prelude_module: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub mod prelude {
+                    pub use crate::ns2::nested::OtherFunc;
+                    pub use crate::ns1::SomeEnum;
+                    pub use crate::ns1::SomeFunc;
+                    pub use crate::ns1::SomeStruct;
+                    pub use crate::TopLevelStruct;
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_prelude_module_name_collisions() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace ns1 { struct SomeStruct {}; }
+            namespace ns2 { struct SomeStruct {}; }
+            void Overloaded(int);
+            void Overloaded(double);
+            struct OtherStruct {};
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_prelude_module: true, ..Default::default() },
+        )?
+        .rs_api;
+        let expected_comment =
+            "`SomeStruct` is not re-exported, because it is ambiguous: ns1::SomeStruct, ns2::SomeStruct";
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub mod prelude {
+                    pub use crate::OtherStruct;
+                    __COMMENT__ #expected_comment
+                }
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { pub use crate::Overloaded; });
+        Ok(())
+    }
 
-    // In order to generate C++ thunk in all the cases Clang needs to be able to
-    // access declarations from public headers of the C++ library.  We don't
-    // process these includes via `format_cc_includes` to preserve their
-    // original order (some libraries require certain headers to be included
-    // first - e.g. `config.h`).
-    let ir_includes =
-        ir.public_headers().map(|hdr| CcInclude::user_header(hdr.name.clone())).collect_vec();
+    #[test]
+    fn test_prelude_module_with_colliding_name() -> Result<()> {
+        let ir = ir_from_cc("struct S final {}; void prelude();")?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_prelude_module: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                __COMMENT__ "The `prelude` module is not generated, because it collides with another item"
+            }
+        );
+        assert_rs_matches!(rs_api, quote! { pub fn prelude() });
+        assert_rs_not_matches!(rs_api, quote! { pub mod prelude });
+        Ok(())
+    }
 
-    Ok(quote! {
-        #internal_includes
-        __NEWLINE__
-        __COMMENT__ "Public headers of the C++ library being wrapped."
-        #( #ir_includes )* __NEWLINE__
-    })
-}
+    #[test]
+    fn test_prelude_module_disabled_by_default() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; };")?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { pub mod prelude });
+        Ok(())
+    }
 
-#[cfg(test)]
-pub(crate) mod tests {
-    use super::*;
-    use ir_testing::{make_ir_from_items, retrieve_func, with_lifetime_macros};
-    use static_assertions::{assert_impl_all, assert_not_impl_any};
-    use token_stream_matchers::{
-        assert_cc_matches, assert_cc_not_matches, assert_rs_matches, assert_rs_not_matches,
-    };
-    use token_stream_printer::rs_tokens_to_formatted_string_for_tests;
+    #[test]
+    fn test_cxx_extern_types() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace ns {
+              struct Trivial { int x; };
+              enum class SomeEnum : int { kA };
+            }
+            struct NonTrivial {
+              ~NonTrivial();
+            };
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_cxx_extern_types: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub mod cxx_extern_types {
+                    ...
+                    unsafe impl ::cxx::ExternType for crate::ns::Trivial {
+                        type Id = ::cxx::type_id!("ns::Trivial");
+                        type Kind = ::cxx::kind::Trivial;
+                    }
+                    ...
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                unsafe impl ::cxx::ExternType for crate::ns::SomeEnum {
+                    type Id = ::cxx::type_id!("ns::SomeEnum");
+                    type Kind = ::cxx::kind::Trivial;
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                unsafe impl ::cxx::ExternType for crate::NonTrivial {
+                    type Id = ::cxx::type_id!("NonTrivial");
+                    type Kind = ::cxx::kind::Opaque;
+                }
+            }
+        );
+        Ok(())
+    }
 
-    pub fn ir_from_cc(header: &str) -> Result<IR> {
-        ir_testing::ir_from_cc(multiplatform_testing::test_platform(), header)
+    #[test]
+    fn test_cxx_extern_types_skips_template_instantiations() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            template <typename T> struct Wrapper { T value; };
+            using IntWrapper = Wrapper<int>;
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_cxx_extern_types: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(rs_api, quote! { pub mod cxx_extern_types {} });
+        Ok(())
     }
-    pub fn ir_from_cc_dependency(header: &str, dep_header: &str) -> Result<IR> {
-        ir_testing::ir_from_cc_dependency(
-            multiplatform_testing::test_platform(),
-            header,
-            dep_header,
-        )
+
+    #[test]
+    fn test_cxx_extern_types_with_colliding_name() -> Result<()> {
+        let ir = ir_from_cc("struct S final {}; namespace cxx_extern_types { void f(); }")?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { generate_cxx_extern_types: true, ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                __COMMENT__ "The `cxx_extern_types` module is not generated, because it collides with another item"
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { ExternType });
+        Ok(())
     }
-    pub fn ir_record(name: &str) -> Record {
-        ir_testing::ir_record(multiplatform_testing::test_platform(), name)
+
+    #[test]
+    fn test_cxx_extern_types_disabled_by_default() -> Result<()> {
+        let ir = ir_from_cc("struct S { int x; };")?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { ExternType });
+        Ok(())
     }
 
-    pub fn generate_bindings_tokens(ir: IR) -> Result<BindingsTokens> {
-        super::generate_bindings_tokens(
-            Rc::new(ir),
-            "crubit/rs_bindings_support",
-            Rc::new(IgnoreErrors),
-            SourceLocationDocComment::Enabled,
-        )
+    #[test]
+    fn test_bindgen_aliases() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace foo {
+              namespace bar {
+                struct SomeStruct { int x; };
+                enum class SomeEnum : int { kA };
+                using SomeAlias = int;
+                void SomeFunc();
+              }
+            }
+            struct TopLevel { int x; };
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions {
+                bindgen_allowlist_file: ".*_virtual_header\\.h".into(),
+                ..Default::default()
+            },
+        )?
+        .rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub use crate::foo::bar::SomeAlias as foo_bar_SomeAlias;
+                pub use crate::foo::bar::SomeEnum as foo_bar_SomeEnum;
+                pub use crate::foo::bar::SomeFunc as foo_bar_SomeFunc;
+                pub use crate::foo::bar::SomeStruct as foo_bar_SomeStruct;
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { TopLevel as });
+        Ok(())
     }
 
-    pub fn db_from_cc(cc_src: &str) -> Result<Database> {
-        Ok(Database::new(
-            Rc::new(ir_from_cc(cc_src)?),
-            Rc::new(ErrorReport::new()),
-            SourceLocationDocComment::Enabled,
-        ))
+    #[test]
+    fn test_bindgen_aliases_header_not_in_allowlist() -> Result<()> {
+        let ir = ir_from_cc("namespace foo { struct SomeStruct { int x; }; }")?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { bindgen_allowlist_file: "other\\.h".into(), ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_not_matches!(rs_api, quote! { foo_SomeStruct });
+
+        // The regex has to match the whole path.
+        let ir = ir_from_cc("namespace foo { struct SomeStruct { int x; }; }")?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { bindgen_allowlist_file: "ir_from_cc".into(), ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_not_matches!(rs_api, quote! { foo_SomeStruct });
+        Ok(())
     }
 
     #[test]
-    fn test_disable_thread_safety_warnings() -> Result<()> {
-        let ir = ir_from_cc("inline void foo() {}")?;
-        let rs_api_impl = generate_bindings_tokens(ir)?.rs_api_impl;
-        assert_cc_matches!(
-            rs_api_impl,
+    fn test_bindgen_aliases_collisions() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace foo { struct Bar { int x; }; }
+            struct foo_Bar { int x; };
+            namespace a { struct b_C { int x; }; }
+            namespace a { namespace b { struct C { int x; }; } }
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { bindgen_allowlist_file: ".*".into(), ..Default::default() },
+        )?
+        .rs_api;
+        assert_rs_not_matches!(rs_api, quote! { as foo_Bar });
+        assert_rs_not_matches!(rs_api, quote! { as a_b_C });
+        assert_rs_matches!(
+            rs_api,
             quote! {
-                ...
-                __HASH_TOKEN__ pragma clang diagnostic push
-                __HASH_TOKEN__ pragma clang diagnostic ignored "-Wthread-safety-analysis"
-                ...
-
-                __HASH_TOKEN__ pragma clang diagnostic pop
-                ...
+                __COMMENT__ "The bindgen alias `a_b_C` is not generated, because it collides with another item"
+                __COMMENT__ "The bindgen alias `foo_Bar` is not generated, because it collides with another item"
             }
         );
         Ok(())
     }
 
-    // TODO(b/200067824): These should generate nested types.
+    #[test]
+    fn test_bindgen_aliases_disabled_by_default() -> Result<()> {
+        let ir = ir_from_cc("namespace foo { struct SomeStruct { int x; }; }")?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { foo_SomeStruct });
+        Ok(())
+    }
+
+    #[test]
+    fn test_bindgen_aliases_invalid_regex() -> Result<()> {
+        let ir = ir_from_cc("namespace foo { struct SomeStruct { int x; }; }")?;
+        let Err(err) = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions { bindgen_allowlist_file: "(".into(), ..Default::default() },
+        ) else {
+            panic!("Expected an error for an invalid regex");
+        };
+        assert!(err.to_string().contains("Invalid bindgen allowlist regex"), "{err}");
+        Ok(())
+    }
+
     #[test]
     fn test_nested_type_definitions() -> Result<()> {
-        for nested_type in ["enum NotPresent {};", "struct NotPresent {};", "struct NotPresent;"] {
+        for nested_type in ["enum Nested {};", "struct Nested {};", "struct Nested;"] {
             let ir = ir_from_cc(&format!(
                 r#"
                     struct SomeStruct final {{
                         {nested_type}
                     }};
-                    SomeStruct::NotPresent* AlsoNotPresent();
+                    SomeStruct::Nested* GetNested();
                 "#
             ))?;
             let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
-            assert_rs_not_matches!(rs_api, quote! { NotPresent });
-            assert_rs_not_matches!(rs_api, quote! { AlsoNotPresent });
+            assert_rs_matches!(
+                rs_api,
+                quote! {
+                    pub mod some_struct {
+                        ... Nested ...
+                    }
+                }
+            );
+            assert_rs_matches!(
+                rs_api,
+                quote! { pub fn GetNested() -> *mut crate::some_struct::Nested { ... } }
+            );
         }
         Ok(())
     }
 
-    /// Unlike other nested type definitions, typedefs can use the aliased type
-    /// instead.
+    #[test]
+    fn test_nested_record() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            namespace ns {
+            struct Outer final {
+              struct Inner final {
+                int field;
+              };
+              Inner inner;
+            };
+            }  // namespace ns
+        "#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub mod ns {
+                    ...
+                    pub struct Outer {
+                        pub inner: crate::ns::outer::Inner,
+                    }
+                    ...
+                    pub mod outer {
+                        ...
+                        #[__crubit::annotate(cc_type = "ns :: Outer :: Inner")]
+                        pub struct Inner {
+                            pub field: ::core::ffi::c_int,
+                        }
+                        ...
+                    }
+                    ...
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { assert!(::core::mem::size_of::<crate::ns::outer::Inner>() == 4); }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! { static_assert(CRUBIT_SIZEOF(struct ns::Outer::Inner) == 4); }
+        );
+        Ok(())
+    }
+
+    /// The nested types go into a module named after the record, which can't
+    /// have the same name as the record itself.
+    #[test]
+    fn test_nested_type_definitions_in_snake_case_record() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct some_struct final {
+              struct Nested final {};
+            };
+        "#,
+        )?;
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_not_matches!(rs_api, quote! { pub mod some_struct });
+        assert_rs_not_matches!(rs_api, quote! { pub struct Nested });
+        Ok(())
+    }
+
+    #[test]
+    fn test_nested_type_definitions_with_colliding_module_name() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+            struct SomeStruct final {
+              struct Nested final {};
+            };
+            void some_struct();
+        "#,
+        )?;
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(rs_api, quote! { pub fn some_struct() });
+        assert_rs_not_matches!(rs_api, quote! { pub mod some_struct });
+        assert_rs_not_matches!(rs_api, quote! { pub struct Nested });
+        Ok(())
+    }
+
     #[test]
     fn test_typedef_member() -> Result<()> {
         let ir = ir_from_cc(
@@ -1516,9 +4855,19 @@ pub(crate) mod tests {
             inline SomeStruct::Type Function() {return 0;}
         "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
-        // TODO(b/200067824): This should use the alias's real name in Rust, as well.
-        assert_rs_matches!(rs_api, quote! { pub fn Function() -> ::core::ffi::c_int { ... } },);
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub mod some_struct {
+                    pub type Type = ::core::ffi::c_int;
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! { pub fn Function() -> crate::some_struct::Type { ... } },
+        );
 
         assert_cc_matches!(
             rs_api_impl,
@@ -1532,7 +4881,7 @@ pub(crate) mod tests {
     #[test]
     fn test_struct_from_other_target() -> Result<()> {
         let ir = ir_from_cc_dependency("// intentionally empty", "struct SomeStruct {};")?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_not_matches!(rs_api, quote! { SomeStruct });
         assert_cc_not_matches!(rs_api_impl, quote! { SomeStruct });
         Ok(())
@@ -1541,7 +4890,7 @@ pub(crate) mod tests {
     #[test]
     fn test_func_ptr_where_params_are_primitive_types() -> Result<()> {
         let ir = ir_from_cc(r#" int (*get_ptr_to_func())(float, double); "#)?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -1613,7 +4962,7 @@ pub(crate) mod tests {
     #[test]
     fn test_func_ptr_where_params_are_raw_ptrs() -> Result<()> {
         let ir = ir_from_cc(r#" const int* (*get_ptr_to_func())(const int*); "#)?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -1695,7 +5044,7 @@ pub(crate) mod tests {
                 }
             );
 
-            let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+            let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
             // Check that the custom "vectorcall" ABI gets propagated into the
             // return type (i.e. into `extern "vectorcall" fn`).
             assert_rs_matches!(
@@ -1794,7 +5143,7 @@ pub(crate) mod tests {
                 double f_c_calling_convention(double p1, double p2);
             "#,
             )?;
-            let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+            let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
             assert_rs_matches!(
                 rs_api,
                 quote! {
@@ -1917,31 +5266,104 @@ pub(crate) mod tests {
     }
 
     #[test]
-    fn test_generate_scoped_enum_basic() -> Result<()> {
-        let ir = ir_from_cc("enum class Color { kRed = -5, kBlue };")?;
+    fn test_generate_scoped_enum_basic() -> Result<()> {
+        let ir = ir_from_cc("enum class Color { kRed = -5, kBlue };")?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[repr(transparent)]
+                #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, PartialOrd, Ord)]
+                pub struct Color(::core::ffi::c_int);
+                impl Color {
+                    pub const kRed: Color = Color(-5);
+                    pub const kBlue: Color = Color(-4);
+                }
+                impl From<::core::ffi::c_int> for Color {
+                    fn from(value: ::core::ffi::c_int) -> Color {
+                        Color(value)
+                    }
+                }
+                impl From<Color> for ::core::ffi::c_int {
+                    fn from(value: Color) -> ::core::ffi::c_int {
+                        value.0
+                    }
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_rust_enum() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"enum class [[clang::annotate("crubit_rust_enum")]] Color : signed char {
+                kRed = -1,
+                kBlue,
+                kDefault = kRed,
+            };"#,
+        )?;
         let rs_api = generate_bindings_tokens(ir)?.rs_api;
         assert_rs_matches!(
             rs_api,
             quote! {
-                #[repr(transparent)]
+                #[repr(i8)]
                 #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, PartialOrd, Ord)]
-                pub struct Color(::core::ffi::c_int);
+                pub enum Color {
+                    kRed = -1,
+                    kBlue = 0,
+                }
                 impl Color {
-                    pub const kRed: Color = Color(-5);
-                    pub const kBlue: Color = Color(-4);
+                    pub const kDefault: Color = Color::kRed;
                 }
-                impl From<::core::ffi::c_int> for Color {
-                    fn from(value: ::core::ffi::c_int) -> Color {
-                        Color(value)
+                impl ::core::convert::TryFrom<::core::ffi::c_schar> for Color {
+                    type Error = ::core::ffi::c_schar;
+                    fn try_from(
+                        value: ::core::ffi::c_schar
+                    ) -> ::core::result::Result<Color, ::core::ffi::c_schar> {
+                        match value {
+                            -1 => Ok(Color::kRed),
+                            0 => Ok(Color::kBlue),
+                            _ => Err(value),
+                        }
                     }
                 }
-                impl From<Color> for ::core::ffi::c_int {
-                    fn from(value: Color) -> ::core::ffi::c_int {
-                        value.0
+                impl From<Color> for ::core::ffi::c_schar {
+                    fn from(value: Color) -> ::core::ffi::c_schar {
+                        value as ::core::ffi::c_schar
                     }
                 }
             }
         );
+        assert_rs_not_matches!(rs_api, quote! { pub struct Color });
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_rust_enum_without_enumerators() -> Result<()> {
+        let ir = ir_from_cc(r#"enum class [[clang::annotate("crubit_rust_enum")]] Color {};"#)?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { pub enum Color });
+        assert!(
+            rs_api.to_string().contains(
+                "Can't generate a Rust enum for `crubit_rust_enum`: the enum has no enumerators"
+            ),
+            "{rs_api}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_rust_enum_with_bool_underlying_type() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"enum class [[clang::annotate("crubit_rust_enum")]] Flag : bool { kOff, kOn };"#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { pub enum Flag });
+        assert!(
+            rs_api.to_string().contains("the underlying type `bool` is not an integer type"),
+            "{rs_api}"
+        );
         Ok(())
     }
 
@@ -2242,7 +5664,7 @@ pub(crate) mod tests {
                 inline void f(MyTypedefDecl t) {}
             "#,
         )?;
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_matches!(
             rs_api,
             quote! {
@@ -2267,6 +5689,270 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_global_var_constant() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+                constexpr int kMax = 5;
+                const long long kMin = -3;
+                const bool kEnabled = true;
+                struct S final {
+                    static constexpr unsigned kSize = 4;
+                };
+            "#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(rs_api, quote! { pub const kMax: ::core::ffi::c_int = 5; });
+        assert_rs_matches!(rs_api, quote! { pub const kMin: ::core::ffi::c_longlong = -3; });
+        assert_rs_matches!(rs_api, quote! { pub const kEnabled: bool = true; });
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl S {
+                    ...
+                    pub const kSize: ::core::ffi::c_uint = 4;
+                }
+            }
+        );
+        assert_cc_not_matches!(rs_api_impl, quote! { kMax });
+        assert_cc_not_matches!(rs_api_impl, quote! { kSize });
+        Ok(())
+    }
+
+    #[test]
+    fn test_macro_constant() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+                #define kAnswer 42
+                #define kNone (-1LL)
+            "#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(rs_api, quote! { pub const kAnswer: ::core::ffi::c_int = 42; });
+        assert_rs_matches!(rs_api, quote! { pub const kNone: ::core::ffi::c_longlong = -1; });
+        assert_cc_not_matches!(rs_api_impl, quote! { kAnswer });
+        Ok(())
+    }
+
+    #[test]
+    fn test_macro_constant_without_experimental_features() -> Result<()> {
+        let mut ir = ir_from_cc("#define kAnswer 42")?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_not_matches!(rs_api, quote! { pub const kAnswer });
+        Ok(())
+    }
+
+    #[test]
+    fn test_macro_constant_with_colliding_name() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+                int kLimit();
+                #define kLimit 5
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(rs_api, quote! { pub fn kLimit() -> ::core::ffi::c_int });
+        assert_rs_not_matches!(rs_api, quote! { pub const kLimit });
+        assert_cc_matches!(rs_api, {
+            let txt = "Generated from: google3/ir_from_cc_virtual_header.h;l=3\n\
+                           Error while generating bindings for item 'kLimit':\n\
+                           The macro `kLimit` is not bound, because its name collides with \
+                           another item";
+            quote! { __COMMENT__ #txt }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_var_accessor() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+                extern int kGlobal;
+                extern const int kConstGlobal;
+                namespace ns {
+                    struct S final {
+                        static int counter;
+                    };
+                }
+            "#,
+        )?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn kGlobal() -> *mut ::core::ffi::c_int {
+                    unsafe { crate::detail::__rust_thunk__kGlobal() }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[inline(always)]
+                pub fn kConstGlobal() -> &'static ::core::ffi::c_int {
+                    unsafe { &*crate::detail::__rust_thunk__kConstGlobal() }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                impl S {
+                    ...
+                    pub fn counter() -> *mut ::core::ffi::c_int {
+                        unsafe { crate::detail::__rust_thunk___ZN2ns1S7counterE() }
+                    }
+                }
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub(crate) fn __rust_thunk__kConstGlobal() -> *const ::core::ffi::c_int;
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int* __rust_thunk__kGlobal() {
+                    return &kGlobal;
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int const* __rust_thunk__kConstGlobal() {
+                    return &kConstGlobal;
+                }
+            }
+        );
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                extern "C" int* __rust_thunk___ZN2ns1S7counterE() {
+                    return &ns::S::counter;
+                }
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_const_global_var_with_mutable_fields() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+                struct Cache final {
+                    mutable int hits;
+                };
+                struct Holder final {
+                    Cache cache;
+                };
+                struct Plain final {
+                    int value;
+                };
+                extern const Cache kCache;
+                extern const Holder kHolder;
+                extern const Plain kPlain;
+                extern int* const kPointer;
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        // `mutable` fields can change behind a shared reference.
+        assert_rs_matches!(rs_api, quote! { pub fn kCache() -> *const crate::Cache });
+        assert_rs_matches!(rs_api, quote! { pub fn kHolder() -> *const crate::Holder });
+        assert_rs_matches!(rs_api, quote! { pub fn kPlain() -> &'static crate::Plain });
+        assert_rs_matches!(rs_api, quote! { pub fn kPointer() -> *const *mut ::core::ffi::c_int });
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_var_without_experimental_features() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"
+                constexpr int kMax = 5;
+                extern int kGlobal;
+            "#,
+        )?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
+        assert_rs_matches!(rs_api, quote! { pub const kMax: ::core::ffi::c_int = 5; });
+        assert_rs_not_matches!(rs_api, quote! { pub fn kGlobal });
+        assert_cc_not_matches!(rs_api_impl, quote! { kGlobal });
+        Ok(())
+    }
+
+    #[test]
+    fn test_renamed_from() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+                struct [[clang::annotate("crubit_renamed_from", "Dimensions")]] Size final {};
+                enum [[clang::annotate("crubit_renamed_from", "OldColor")]] Color { kRed };
+                using Area [[clang::annotate("crubit_renamed_from", "Surface")]] = int;
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[doc = " `Dimensions` was renamed to `Size`."]
+                pub use self::Size as Dimensions;
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[doc = " `OldColor` was renamed to `Color`."]
+                pub use self::Color as OldColor;
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                #[doc = " `Surface` was renamed to `Area`."]
+                pub use self::Area as Surface;
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_renamed_from_collision() -> Result<()> {
+        let ir = ir_from_cc(
+            r#"
+                struct [[clang::annotate("crubit_renamed_from", "Dimensions")]] Size final {};
+                struct Dimensions final {};
+                namespace ns {
+                    // Only the names in the same module collide.
+                    struct [[clang::annotate("crubit_renamed_from", "Dimensions")]] Size final {};
+                }
+            "#,
+        )?;
+        let rs_api = generate_bindings_tokens(ir)?.rs_api;
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                __COMMENT__ "The alias `Dimensions` of `Size` is not generated, because it collides with another item"
+            }
+        );
+        assert_rs_matches!(
+            rs_api,
+            quote! {
+                pub mod ns {
+                    ...
+                    pub use self::Size as Dimensions;
+                    ...
+                }
+            }
+        );
+        assert_rs_not_matches!(rs_api, quote! { pub use self::Size as Dimensions; ... pub mod ns });
+        Ok(())
+    }
+
     #[test]
     fn test_rs_type_kind_implements_copy() -> Result<()> {
         let template = r#" LIFETIMES
@@ -2846,6 +6532,19 @@ pub(crate) mod tests {
         assert_rs_matches!(actual, quote! {#[doc = " Some doc comment"]});
     }
 
+    #[test]
+    fn test_generate_doc_comment_with_doxygen_comment() {
+        let actual = generate_doc_comment(
+            Some("\\brief Does things.\n@param x the thing"),
+            None,
+            SourceLocationDocComment::Enabled,
+        );
+        assert_rs_matches!(
+            actual,
+            quote! {#[doc = " Does things.\n \n # Arguments\n \n * `x` - the thing"]}
+        );
+    }
+
     #[test]
     fn test_no_generate_doc_comment_with_no_comment_with_no_source_loc_with_source_loc_disabled() {
         let actual = generate_doc_comment(None, None, SourceLocationDocComment::Disabled);
@@ -2906,6 +6605,7 @@ pub(crate) mod tests {
             Rc::new(make_ir_from_items([])),
             Rc::new(ErrorReport::new()),
             SourceLocationDocComment::Enabled,
+            Rc::default(),
         );
         let actual = generate_unsupported(
             &db,
@@ -2929,6 +6629,7 @@ pub(crate) mod tests {
             Rc::new(make_ir_from_items([])),
             Rc::new(ErrorReport::new()),
             SourceLocationDocComment::Enabled,
+            Rc::default(),
         );
         let actual = generate_unsupported(
             &db,
@@ -2949,6 +6650,7 @@ pub(crate) mod tests {
             Rc::new(make_ir_from_items([])),
             Rc::new(ErrorReport::new()),
             SourceLocationDocComment::Disabled,
+            Rc::default(),
         );
         let actual = generate_unsupported(
             &db,
@@ -2983,6 +6685,63 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    /// Attributes with a translation (see `attribute_translation.rs`) don't
+    /// require experimental features.
+    #[test]
+    fn test_supported_translated_attr() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"
+            enum Enum {
+                kShown [[maybe_unused]],
+            };
+            [[gnu::hot]] extern "C" void Hot();
+            struct [[nodiscard]] Error final {};
+            "#,
+        )?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions {
+                attribute_translations: HashMap::from([("gnu::hot".into(), "".into())]),
+                ..Default::default()
+            },
+        )?;
+        assert_rs_matches!(rs_api, quote! {pub const kShown: Enum});
+        assert_rs_matches!(rs_api, quote! {pub fn Hot()});
+        assert_rs_matches!(rs_api, quote! {#[must_use] #[derive(...)] ... pub struct Error});
+        Ok(())
+    }
+
+    #[test]
+    fn test_configured_attr_translation() -> Result<()> {
+        let mut ir = ir_from_cc(
+            r#"
+            struct [[deprecated]] Old final {};
+            [[gnu::hot]] extern "C" void Hot();
+            "#,
+        )?;
+        *ir.target_crubit_features_mut(&ir.current_target().clone()) =
+            ir::CrubitFeature::Supported.into();
+        let BindingsTokens { rs_api, .. } = generate_bindings_tokens_with_options(
+            ir,
+            GeneratorOptions {
+                attribute_translations: HashMap::from([(
+                    "deprecated".into(),
+                    r#"doc = "Deprecated in C++.""#.into(),
+                )]),
+                ..Default::default()
+            },
+        )?;
+        assert_rs_matches!(
+            rs_api,
+            quote! {#[doc = "Deprecated in C++."] #[derive(...)] ... pub struct Old}
+        );
+        // `gnu::hot` has no translation.
+        assert_rs_not_matches!(rs_api, quote! {pub fn Hot});
+        Ok(())
+    }
+
     /// Namespaces with an unknown attribute are not present in supported.
     ///
     /// This is hard to test any other way than token comparison, because it's
@@ -3075,12 +6834,12 @@ pub(crate) mod tests {
     fn test_default_crubit_features_disabled_supported() -> Result<()> {
         for item in [
             "extern \"C\" void NotPresent() {}",
-            "struct NotPresent {};",
+            "struct NotPresent final {};",
             "extern \"C\" int NotPresent() {}",
         ] {
             let mut ir = ir_from_cc(item)?;
             ir.target_crubit_features_mut(&ir.current_target().clone()).clear();
-            let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+            let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
             assert_rs_not_matches!(rs_api, quote! {NotPresent});
             assert_cc_not_matches!(rs_api_impl, quote! {NotPresent});
             let contents = rs_tokens_to_formatted_string_for_tests(rs_api)?;
@@ -3101,7 +6860,7 @@ pub(crate) mod tests {
     fn test_default_crubit_features_disabled_experimental() -> Result<()> {
         let mut ir = ir_from_cc("struct NotPresent {~NotPresent();};")?;
         ir.target_crubit_features_mut(&ir.current_target().clone()).clear();
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_not_matches!(rs_api, quote! {NotPresent});
         assert_cc_not_matches!(rs_api_impl, quote! {NotPresent});
         let expected = "\
@@ -3116,10 +6875,10 @@ pub(crate) mod tests {
     #[test]
     fn test_default_crubit_features_disabled_dependency_supported_function_parameter() -> Result<()>
     {
-        for dependency in ["struct NotPresent {};"] {
+        for dependency in ["struct NotPresent final {};"] {
             let mut ir = ir_from_cc_dependency("void Func(NotPresent);", dependency)?;
             ir.target_crubit_features_mut(&ir::BazelLabel("//test:dependency".into())).clear();
-            let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+            let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
             assert_rs_not_matches!(rs_api, quote! {Func});
             assert_cc_not_matches!(rs_api_impl, quote! {Func});
             let expected = "\
@@ -3138,7 +6897,7 @@ pub(crate) mod tests {
         let mut ir =
             ir_from_cc_dependency("void Func(NotPresent);", "struct NotPresent {~NotPresent();};")?;
         ir.target_crubit_features_mut(&ir::BazelLabel("//test:dependency".into())).clear();
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_not_matches!(rs_api, quote! {Func});
         assert_cc_not_matches!(rs_api_impl, quote! {Func});
         let expected = "\
@@ -3153,9 +6912,9 @@ pub(crate) mod tests {
     #[test]
     fn test_default_crubit_features_disabled_dependency_supported_function_return_type()
     -> Result<()> {
-        let mut ir = ir_from_cc_dependency("NotPresent Func();", "struct NotPresent {};")?;
+        let mut ir = ir_from_cc_dependency("NotPresent Func();", "struct NotPresent final {};")?;
         ir.target_crubit_features_mut(&ir::BazelLabel("//test:dependency".into())).clear();
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_not_matches!(rs_api, quote! {Func});
         assert_cc_not_matches!(rs_api_impl, quote! {Func});
         let expected = "\
@@ -3173,7 +6932,7 @@ pub(crate) mod tests {
         let mut ir =
             ir_from_cc_dependency("NotPresent Func();", "struct NotPresent {~NotPresent();};")?;
         ir.target_crubit_features_mut(&ir::BazelLabel("//test:dependency".into())).clear();
-        let BindingsTokens { rs_api, rs_api_impl } = generate_bindings_tokens(ir)?;
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir)?;
         assert_rs_not_matches!(rs_api, quote! {Func});
         assert_cc_not_matches!(rs_api_impl, quote! {Func});
         let expected = "\
@@ -3191,7 +6950,7 @@ pub(crate) mod tests {
         {
             let mut ir = ir_from_cc_dependency("struct Present {NotPresent field;};", dependency)?;
             ir.target_crubit_features_mut(&ir::BazelLabel("//test:dependency".into())).clear();
-            let BindingsTokens { rs_api, rs_api_impl: _ } = generate_bindings_tokens(ir)?;
+            let BindingsTokens { rs_api, .. } = generate_bindings_tokens(ir)?;
             assert_rs_matches!(
                 rs_api,
                 quote! {
@@ -3207,13 +6966,12 @@ pub(crate) mod tests {
 
     #[test]
     fn test_type_map_override_assert() -> Result<()> {
-        let rs_api = generate_bindings_tokens(ir_from_cc(
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir_from_cc(
             r#" #pragma clang lifetime_elision
                 // Broken class: uses i32 but has size 1.
                 // (These asserts would fail if this were compiled.)
                 class [[clang::annotate("crubit_internal_rust_type", "i32")]] Class final {};"#,
-        )?)?
-        .rs_api;
+        )?)?;
 
         assert_rs_matches!(
             rs_api,
@@ -3228,6 +6986,14 @@ pub(crate) mod tests {
                 assert!(::core::mem::align_of::<i32>() == 1);
             }
         );
+
+        assert_cc_matches!(
+            rs_api_impl,
+            quote! {
+                static_assert(sizeof(class Class) == 1);
+                static_assert(alignof(class Class) == 1);
+            }
+        );
         Ok(())
     }
 
@@ -3289,14 +7055,13 @@ pub(crate) mod tests {
     /// We cannot generate size/align assertions for incomplete types.
     #[test]
     fn test_type_map_override_assert_incomplete() -> Result<()> {
-        let rs_api = generate_bindings_tokens(ir_from_cc(
+        let BindingsTokens { rs_api, rs_api_impl, .. } = generate_bindings_tokens(ir_from_cc(
             r#" #pragma clang lifetime_elision
                 // Broken class: uses i32 but has size 1.
                 // (These asserts would fail if this were compiled.)
                 class [[clang::annotate("crubit_internal_rust_type", "i32")]] Incomplete;
             "#,
-        )?)?
-        .rs_api;
+        )?)?;
 
         assert_rs_not_matches!(
             rs_api,
@@ -3309,6 +7074,8 @@ pub(crate) mod tests {
             quote! {
             const _: () = { ... ::core::mem::align_of::<i32>() ... }}
         );
+
+        assert_cc_not_matches!(rs_api_impl, quote! { sizeof(class Incomplete) });
         Ok(())
     }
 }
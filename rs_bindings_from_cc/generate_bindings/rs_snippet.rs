@@ -4,13 +4,16 @@
 #![allow(clippy::collapsible_else_if)]
 //! Vocabulary types and code generation functions for generating Rust code.
 
+use crate::std_type_mapping::{
+    AbslStatus, SharedPtr, StdFunction, StdString, StringView, UniquePtr,
+};
 use arc_anyhow::Result;
 use code_gen_utils::make_rs_ident;
 use code_gen_utils::NamespaceQualifier;
 use error_report::bail;
 use ir::*;
 use itertools::Itertools;
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::{quote, ToTokens};
 use std::collections::HashSet;
 use std::rc::Rc;
@@ -179,6 +182,13 @@ pub fn should_derive_copy(record: &Record) -> bool {
 }
 
 pub fn check_by_value(record: &Record) -> Result<()> {
+    if record.is_opaque {
+        bail!(
+            "Can't directly construct values of type `{}`: it is opaque, and can only be used \
+            behind pointers and references",
+            record.cc_name.as_ref()
+        );
+    }
     if record.destructor == SpecialMemberFunc::Unavailable {
         bail!(
             "Can't directly construct values of type `{}` as it has a non-public or deleted destructor",
@@ -290,6 +300,202 @@ impl ToTokens for PrimitiveType {
     }
 }
 
+/// A C++ vocabulary type that doesn't get bindings of its own, and is instead
+/// converted into an existing Rust type (and back) whenever it crosses the FFI
+/// boundary.
+///
+/// Values of these types are passed through the thunks in an "ABI
+/// representation" (e.g. a number of nanoseconds for `absl::Duration`), which
+/// is then converted on both sides of the thunk. This means that they can only
+/// be passed by value: pointers, references, fields, etc. of these types are
+/// not supported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BridgeType {
+    /// `absl::Duration`, represented as `::core::time::Duration`.
+    AbslDuration,
+    /// `absl::Time`, represented as `::absl_time::Time`.
+    AbslTime,
+    /// A `std::chrono::duration`, represented as `::core::time::Duration`.
+    ChronoDuration(ChronoUnit),
+    /// A `std::chrono::system_clock` time point, represented as
+    /// `::cc_chrono::SystemTime` (a re-export of `std::time::SystemTime`).
+    ChronoSystemTime(ChronoUnit),
+}
+
+/// The unit (i.e. the duration of a single tick) of a `std::chrono::duration`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChronoUnit {
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+}
+
+impl ChronoUnit {
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "nanoseconds" => Some(Self::Nanoseconds),
+            "microseconds" => Some(Self::Microseconds),
+            "milliseconds" => Some(Self::Milliseconds),
+            "seconds" => Some(Self::Seconds),
+            _ => None,
+        }
+    }
+
+    /// The name of the `std::chrono` alias for durations with this unit.
+    fn cc_name(self) -> &'static str {
+        match self {
+            Self::Nanoseconds => "nanoseconds",
+            Self::Microseconds => "microseconds",
+            Self::Milliseconds => "milliseconds",
+            Self::Seconds => "seconds",
+        }
+    }
+
+    fn nanos_per_tick(self) -> Literal {
+        Literal::u64_unsuffixed(match self {
+            Self::Nanoseconds => 1,
+            Self::Microseconds => 1_000,
+            Self::Milliseconds => 1_000_000,
+            Self::Seconds => 1_000_000_000,
+        })
+    }
+
+    fn format_cc_duration_type(self) -> TokenStream {
+        let ident = crate::format_cc_ident(self.cc_name());
+        quote! { std::chrono::#ident }
+    }
+}
+
+impl BridgeType {
+    /// Returns the bridge type that is spelled as `name` in `RsType::name`.
+    pub fn from_rs_type_name(name: &str) -> Option<Self> {
+        match name {
+            "#abslDuration" => Some(Self::AbslDuration),
+            "#abslTime" => Some(Self::AbslTime),
+            _ => {
+                let (kind, unit) = name.split_once(' ')?;
+                let unit = ChronoUnit::from_str(unit)?;
+                match kind {
+                    "#chronoDuration" => Some(Self::ChronoDuration(unit)),
+                    "#chronoSystemTime" => Some(Self::ChronoSystemTime(unit)),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// The C++ spelling of the type.
+    pub fn cc_name(self) -> String {
+        match self {
+            Self::AbslDuration => "absl::Duration".to_string(),
+            Self::AbslTime => "absl::Time".to_string(),
+            Self::ChronoDuration(unit) => format!("std::chrono::{}", unit.cc_name()),
+            Self::ChronoSystemTime(unit) => format!(
+                "std::chrono::time_point<std::chrono::system_clock, std::chrono::{}>",
+                unit.cc_name()
+            ),
+        }
+    }
+
+    /// The support library header (if any) that declares the C++ side of the
+    /// conversions.
+    pub fn cc_support_header(self) -> Option<&'static str> {
+        match self {
+            Self::AbslDuration | Self::AbslTime => Some("internal/absl_time.h"),
+            Self::ChronoDuration(_) | Self::ChronoSystemTime(_) => None,
+        }
+    }
+
+    /// The type used to pass values through the Rust side of a thunk.
+    pub fn format_rs_abi_type(self) -> TokenStream {
+        quote! { i64 }
+    }
+
+    /// The type used to pass values through the C++ side of a thunk.
+    pub fn format_cc_abi_type(self) -> TokenStream {
+        quote! { std::int64_t }
+    }
+
+    /// Converts the Rust expression `value` into the ABI representation.
+    pub fn format_rs_to_abi(self, value: TokenStream) -> TokenStream {
+        match self {
+            Self::AbslDuration => quote! { ::absl_time::internal::duration_to_nanos(#value) },
+            Self::AbslTime => quote! { ::absl_time::internal::time_to_unix_nanos(#value) },
+            Self::ChronoDuration(unit) => {
+                let nanos_per_tick = unit.nanos_per_tick();
+                quote! { ::cc_chrono::internal::duration_to_ticks::<#nanos_per_tick>(#value) }
+            }
+            Self::ChronoSystemTime(unit) => {
+                let nanos_per_tick = unit.nanos_per_tick();
+                quote! { ::cc_chrono::internal::system_time_to_ticks::<#nanos_per_tick>(#value) }
+            }
+        }
+    }
+
+    /// Converts the Rust expression `value` from the ABI representation.
+    pub fn format_rs_from_abi(self, value: TokenStream) -> TokenStream {
+        match self {
+            Self::AbslDuration => quote! { ::absl_time::internal::duration_from_nanos(#value) },
+            Self::AbslTime => quote! { ::absl_time::internal::time_from_unix_nanos(#value) },
+            Self::ChronoDuration(unit) => {
+                let nanos_per_tick = unit.nanos_per_tick();
+                quote! { ::cc_chrono::internal::duration_from_ticks::<#nanos_per_tick>(#value) }
+            }
+            Self::ChronoSystemTime(unit) => {
+                let nanos_per_tick = unit.nanos_per_tick();
+                quote! {
+                    ::cc_chrono::internal::system_time_from_ticks::<#nanos_per_tick>(#value)
+                }
+            }
+        }
+    }
+
+    /// Converts the C++ expression `value` into the ABI representation.
+    pub fn format_cc_to_abi(self, value: TokenStream) -> TokenStream {
+        match self {
+            Self::AbslDuration => quote! { crubit::AbslDurationToNanos(#value) },
+            Self::AbslTime => quote! { crubit::AbslTimeToUnixNanos(#value) },
+            Self::ChronoDuration(_) => quote! { #value.count() },
+            Self::ChronoSystemTime(_) => quote! { #value.time_since_epoch().count() },
+        }
+    }
+
+    /// Converts the C++ expression `value` from the ABI representation.
+    ///
+    /// For `std::chrono` types, the result uses the standard `std::chrono`
+    /// alias, which implicitly converts to any other representation with the
+    /// same period.
+    pub fn format_cc_from_abi(self, value: TokenStream) -> TokenStream {
+        match self {
+            Self::AbslDuration => quote! { crubit::AbslDurationFromNanos(#value) },
+            Self::AbslTime => quote! { crubit::AbslTimeFromUnixNanos(#value) },
+            Self::ChronoDuration(unit) => {
+                let duration_type = unit.format_cc_duration_type();
+                quote! { #duration_type(#value) }
+            }
+            Self::ChronoSystemTime(unit) => {
+                let duration_type = unit.format_cc_duration_type();
+                quote! {
+                    std::chrono::time_point<std::chrono::system_clock, #duration_type>(
+                        #duration_type(#value))
+                }
+            }
+        }
+    }
+}
+
+impl ToTokens for BridgeType {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::AbslDuration | Self::ChronoDuration(_) => quote! {::core::time::Duration},
+            Self::AbslTime => quote! {::absl_time::Time},
+            Self::ChronoSystemTime(_) => quote! {::cc_chrono::SystemTime},
+        }
+        .to_tokens(tokens)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum RsTypeKind {
     Pointer {
@@ -333,6 +539,56 @@ pub enum RsTypeKind {
     Primitive(PrimitiveType),
     /// Nullable T, using the rust Option type.
     Option(Rc<RsTypeKind>),
+    /// A fixed-size array, `[T; N]`, corresponding to a C++ `std::array<T, N>`.
+    Array {
+        element_type: Rc<RsTypeKind>,
+        size: usize,
+    },
+    /// `Option<T>`, corresponding to a C++ `std::optional<T>` of a trivially
+    /// copyable `T`.
+    ///
+    /// Like a `BridgeType`, it is converted when it crosses the FFI boundary,
+    /// and so it can only be passed by value.
+    StdOptional(Rc<RsTypeKind>),
+    /// `&[T]`, corresponding to a C++ `std::initializer_list<T>` of a trivially
+    /// copyable `T`.
+    ///
+    /// The thunks receive the pointer and length of the slice, and build the
+    /// `std::initializer_list` on the C++ side, so it can only be passed by
+    /// value.
+    InitializerList(Rc<RsTypeKind>),
+    /// `&[T]` or `&mut [T]`, corresponding to a C++ `absl::Span<T>` or
+    /// `std::span<T>` parameter (of `const T` elements, or not).
+    ///
+    /// The thunks receive the pointer and length of the slice, and build the
+    /// span on the C++ side, so that it points directly at the elements of the
+    /// slice.
+    Span {
+        element_type: Rc<RsTypeKind>,
+        mutability: Mutability,
+    },
+    /// `&[u8]`, corresponding to a C++ `std::string_view` or
+    /// `absl::string_view` parameter or return value.
+    StringView(StringView),
+    /// `&[u8]` or `Vec<u8>`, corresponding to a C++ `std::string` parameter or
+    /// return value, respectively.
+    StdString(StdString),
+    /// `Result<(), cc_status::Status>` or `Result<T, cc_status::Status>`,
+    /// corresponding to a C++ `absl::Status` or `absl::StatusOr<T>` return
+    /// value.
+    AbslStatus(AbslStatus),
+    /// `Option<cc_unique_ptr::UniquePtr<T>>`, corresponding to a C++
+    /// `std::unique_ptr<T>` parameter or return value.
+    UniquePtr(UniquePtr),
+    /// `Option<cc_shared_ptr::SharedPtr<T>>`, corresponding to a C++
+    /// `std::shared_ptr<T>` parameter or return value.
+    SharedPtr(SharedPtr),
+    /// `impl Fn(Args...) -> R + 'static`, corresponding to a C++
+    /// `std::function<R(Args...)>` parameter.
+    StdFunction(StdFunction),
+    /// A C++ type that is converted into a Rust type when it crosses the FFI
+    /// boundary.
+    BridgeType(BridgeType),
     Other {
         name: Rc<str>,
         type_args: Rc<[RsTypeKind]>,
@@ -377,6 +633,103 @@ impl RsTypeKind {
         }
     }
 
+    /// Returns the bridge type that this type is (or is an alias of), if any.
+    pub fn as_bridge_type(&self) -> Option<BridgeType> {
+        match self {
+            RsTypeKind::BridgeType(bridge_type) => Some(*bridge_type),
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.as_bridge_type(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value type of the `std::optional` that this type is (or is
+    /// an alias of), if any.
+    pub fn as_std_optional(&self) -> Option<&RsTypeKind> {
+        match self {
+            RsTypeKind::StdOptional(value_type) => Some(value_type),
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.as_std_optional(),
+            _ => None,
+        }
+    }
+
+    /// Returns the element type of the `std::initializer_list` that this type
+    /// is (or is an alias of), if any.
+    pub fn as_initializer_list(&self) -> Option<&RsTypeKind> {
+        match self {
+            RsTypeKind::InitializerList(element_type) => Some(element_type),
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.as_initializer_list(),
+            _ => None,
+        }
+    }
+
+    /// Returns the element type and mutability of the span that this type is
+    /// (or is an alias of), if any.
+    pub fn as_span(&self) -> Option<(&RsTypeKind, Mutability)> {
+        match self {
+            RsTypeKind::Span { element_type, mutability } => Some((element_type, *mutability)),
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.as_span(),
+            _ => None,
+        }
+    }
+
+    /// Returns the string view that this type is (or is an alias of), if any.
+    pub fn as_string_view(&self) -> Option<&StringView> {
+        match self {
+            RsTypeKind::StringView(string_view) => Some(string_view),
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.as_string_view(),
+            _ => None,
+        }
+    }
+
+    /// Returns the `std::string` that this type is (or is an alias of), if any.
+    pub fn as_std_string(&self) -> Option<StdString> {
+        match self {
+            RsTypeKind::StdString(std_string) => Some(*std_string),
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.as_std_string(),
+            _ => None,
+        }
+    }
+
+    /// Returns the `absl::Status` or `absl::StatusOr<T>` that this type is (or
+    /// is an alias of), if any.
+    pub fn as_absl_status(&self) -> Option<&AbslStatus> {
+        match self {
+            RsTypeKind::AbslStatus(absl_status) => Some(absl_status),
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.as_absl_status(),
+            _ => None,
+        }
+    }
+
+    /// Returns the `std::unique_ptr<T>` that this type is (or is an alias of),
+    /// if any.
+    pub fn as_unique_ptr(&self) -> Option<&UniquePtr> {
+        match self {
+            RsTypeKind::UniquePtr(unique_ptr) => Some(unique_ptr),
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.as_unique_ptr(),
+            _ => None,
+        }
+    }
+
+    /// Returns the `std::shared_ptr<T>` that this type is (or is an alias of),
+    /// if any.
+    pub fn as_shared_ptr(&self) -> Option<&SharedPtr> {
+        match self {
+            RsTypeKind::SharedPtr(shared_ptr) => Some(shared_ptr),
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.as_shared_ptr(),
+            _ => None,
+        }
+    }
+
+    /// Returns the `std::function<R(Args...)>` that this type is (or is an
+    /// alias of), if any.
+    pub fn as_std_function(&self) -> Option<&StdFunction> {
+        match self {
+            RsTypeKind::StdFunction(std_function) => Some(std_function),
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.as_std_function(),
+            _ => None,
+        }
+    }
+
     /// Returns true if this type is unsafe to pass across function boundaries.
     ///
     /// In particular, anything representing a pointer with unknown lifetime is
@@ -447,9 +800,14 @@ impl RsTypeKind {
                 //
                 // Instead, what matters is the abstract properties of the struct itself!
                 RsTypeKind::Record { record, .. } => {
-                    // Types which aren't rust-movable, or which are template instantiations, are
-                    // only supported experimentally.
-                    if rs_type_kind.is_unpin() && record.defining_target.is_none() {
+                    // Types which aren't rust-movable are only supported experimentally.
+                    //
+                    // Template instantiations are treated like any other record: the bindings
+                    // for the instantiation itself check that the target which defines the
+                    // template enables the same features.
+                    //
+                    // Opaque types are never moved, since they can only be used behind pointers.
+                    if rs_type_kind.is_unpin() || record.is_opaque {
                         require_feature(CrubitFeature::Supported, None)
                     } else {
                         require_feature(
@@ -466,6 +824,45 @@ impl RsTypeKind {
                 RsTypeKind::TypeAlias { .. } => require_feature(CrubitFeature::Supported, None),
                 RsTypeKind::Primitive { .. } => require_feature(CrubitFeature::Supported, None),
                 RsTypeKind::Option { .. } => require_feature(CrubitFeature::Supported, None),
+                RsTypeKind::Array { .. } => require_feature(CrubitFeature::Supported, None),
+                RsTypeKind::StdOptional { .. } => require_feature(CrubitFeature::Supported, None),
+                RsTypeKind::InitializerList { .. } => {
+                    require_feature(CrubitFeature::Supported, None)
+                }
+                RsTypeKind::Span { .. } => require_feature(
+                    CrubitFeature::Experimental,
+                    Some(&|| {
+                        format!("absl::Span and std::span are converted into {rs_type_kind}").into()
+                    }),
+                ),
+                RsTypeKind::StringView(_) => require_feature(CrubitFeature::Supported, None),
+                RsTypeKind::StdString(_) => require_feature(CrubitFeature::Supported, None),
+                RsTypeKind::AbslStatus(_) => require_feature(
+                    CrubitFeature::Experimental,
+                    Some(&|| {
+                        format!("absl::Status and absl::StatusOr are converted into {rs_type_kind}")
+                            .into()
+                    }),
+                ),
+                RsTypeKind::UniquePtr(_) => require_feature(
+                    CrubitFeature::Experimental,
+                    Some(&|| format!("std::unique_ptr is converted into {rs_type_kind}").into()),
+                ),
+                RsTypeKind::SharedPtr(_) => require_feature(
+                    CrubitFeature::Experimental,
+                    Some(&|| format!("std::shared_ptr is converted into {rs_type_kind}").into()),
+                ),
+                RsTypeKind::StdFunction(_) => require_feature(
+                    CrubitFeature::Experimental,
+                    Some(&|| format!("std::function is converted into {rs_type_kind}").into()),
+                ),
+                RsTypeKind::BridgeType(bridge_type) => require_feature(
+                    CrubitFeature::Experimental,
+                    Some(&|| {
+                        format!("{} is converted into {rs_type_kind}", bridge_type.cc_name())
+                            .into()
+                    }),
+                ),
                 // Fallback case, we can't really give a good error message here.
                 RsTypeKind::Other { .. } => require_feature(CrubitFeature::Experimental, None),
             }
@@ -496,6 +893,8 @@ impl RsTypeKind {
             // TODO(b/274177296): Return `true` for structs where bindings replicate the type of
             // all the fields.
             RsTypeKind::Record { .. } => false,
+            // Rust arrays are not FFI-safe by value, so they are passed by pointer instead.
+            RsTypeKind::Array { .. } => false,
             RsTypeKind::Other { is_same_abi, .. } => *is_same_abi,
             _ => true,
         }
@@ -617,6 +1016,17 @@ impl RsTypeKind {
             RsTypeKind::Enum { .. } => true,
             RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.implements_copy(),
             RsTypeKind::Option(t) => t.implements_copy(),
+            RsTypeKind::Array { element_type, .. } => element_type.implements_copy(),
+            RsTypeKind::StdOptional(value_type) => value_type.implements_copy(),
+            RsTypeKind::InitializerList(_) => true,
+            RsTypeKind::Span { mutability, .. } => *mutability == Mutability::Const,
+            RsTypeKind::StringView(_) => true,
+            RsTypeKind::StdString(_) => false,
+            RsTypeKind::AbslStatus(_) => false,
+            RsTypeKind::UniquePtr(_) => false,
+            RsTypeKind::SharedPtr(_) => false,
+            RsTypeKind::StdFunction(_) => false,
+            RsTypeKind::BridgeType(_) => true,
             RsTypeKind::Other { type_args, .. } => {
                 // All types that may appear here without `type_args` (e.g.
                 // primitive types like `i32`) implement `Copy`. Generic types
@@ -661,6 +1071,14 @@ impl RsTypeKind {
         }
     }
 
+    /// Returns the type that `self` refers to, looking through type aliases.
+    pub fn unalias(&self) -> &RsTypeKind {
+        match self {
+            RsTypeKind::TypeAlias { underlying_type, .. } => underlying_type.unalias(),
+            _ => self,
+        }
+    }
+
     /// Iterates over `self` and all the nested types (e.g. pointees, generic
     /// type args, etc.) in DFS order.
     pub fn dfs_iter(&self) -> impl Iterator<Item = &RsTypeKind> + '_ {
@@ -684,12 +1102,14 @@ impl RsTypeKind {
         }
     }
 
-    /// Returns the reference lifetime, or None if this is not a reference.
+    /// Returns the reference lifetime (or the lifetime of the characters of a
+    /// string view), or None if this is not a reference.
     pub fn lifetime(&self) -> Option<Lifetime> {
         match self {
             Self::Reference { lifetime, .. } | Self::RvalueReference { lifetime, .. } => {
                 Some(lifetime.clone())
             }
+            Self::StringView(string_view) => string_view.lifetime.clone(),
             _ => None,
         }
     }
@@ -752,6 +1172,37 @@ impl RsTypeKind {
                 // TODO(jeanpierreda): This should likely be `::core::option::Option`.
                 quote! {Option<#type_arg>}
             }
+            RsTypeKind::Array { element_type, size } => {
+                let element_type = element_type.to_token_stream_replacing_by_self(self_record);
+                let size = Literal::usize_unsuffixed(*size);
+                quote! {[#element_type; #size]}
+            }
+            RsTypeKind::StdOptional(value_type) => {
+                let value_type = value_type.to_token_stream_replacing_by_self(self_record);
+                quote! {Option<#value_type>}
+            }
+            RsTypeKind::InitializerList(element_type) => {
+                let element_type = element_type.to_token_stream_replacing_by_self(self_record);
+                quote! {&[#element_type]}
+            }
+            RsTypeKind::Span { element_type, mutability } => {
+                let element_type = element_type.to_token_stream_replacing_by_self(self_record);
+                let mut_ = mutability.format_for_reference();
+                quote! {& #mut_ [#element_type]}
+            }
+            RsTypeKind::AbslStatus(AbslStatus { value_type: Some(value_type) }) => {
+                let value_type = value_type.to_token_stream_replacing_by_self(self_record);
+                AbslStatus::format_result_type(value_type)
+            }
+            RsTypeKind::UniquePtr(UniquePtr { pointee_type }) => {
+                let pointee_type = pointee_type.to_token_stream_replacing_by_self(self_record);
+                UniquePtr::format_option_type(pointee_type)
+            }
+            RsTypeKind::SharedPtr(SharedPtr { pointee_type }) => {
+                let pointee_type = pointee_type.to_token_stream_replacing_by_self(self_record);
+                SharedPtr::format_option_type(pointee_type)
+            }
+            RsTypeKind::StdFunction(std_function) => quote! {#std_function},
             RsTypeKind::Other { name, type_args, .. } => {
                 let name: TokenStream = name.parse().expect("Invalid RsType::name in the IR");
                 let generic_params =
@@ -841,6 +1292,23 @@ impl ToTokens for RsTypeKind {
                 // TODO(jeanpierreda): This should likely be `::core::option::Option`.
                 quote! {Option<#t>}
             }
+            RsTypeKind::Array { element_type, size } => {
+                let size = Literal::usize_unsuffixed(*size);
+                quote! {[#element_type; #size]}
+            }
+            RsTypeKind::StdOptional(value_type) => quote! {Option<#value_type>},
+            RsTypeKind::InitializerList(element_type) => quote! {&[#element_type]},
+            RsTypeKind::Span { element_type, mutability } => {
+                let mut_ = mutability.format_for_reference();
+                quote! {& #mut_ [#element_type]}
+            }
+            RsTypeKind::StringView(string_view) => quote! {#string_view},
+            RsTypeKind::StdString(std_string) => quote! {#std_string},
+            RsTypeKind::AbslStatus(absl_status) => quote! {#absl_status},
+            RsTypeKind::UniquePtr(unique_ptr) => quote! {#unique_ptr},
+            RsTypeKind::SharedPtr(shared_ptr) => quote! {#shared_ptr},
+            RsTypeKind::StdFunction(std_function) => quote! {#std_function},
+            RsTypeKind::BridgeType(bridge_type) => quote! {#bridge_type},
             RsTypeKind::Other { name, type_args, .. } => {
                 let name: TokenStream = name.parse().expect("Invalid RsType::name in the IR");
                 let generic_params =
@@ -870,6 +1338,9 @@ impl<'ty> Iterator for RsTypeKindIter<'ty> {
             Some(curr) => {
                 match curr {
                     RsTypeKind::Primitive { .. }
+                    | RsTypeKind::StringView(_)
+                    | RsTypeKind::StdString(_)
+                    | RsTypeKind::BridgeType(_)
                     | RsTypeKind::IncompleteRecord { .. }
                     | RsTypeKind::Record { .. }
                     | RsTypeKind::Enum { .. } => {}
@@ -882,6 +1353,19 @@ impl<'ty> Iterator for RsTypeKindIter<'ty> {
                         self.todo.extend(param_types.iter().rev());
                     }
                     RsTypeKind::Option(t) => self.todo.push(t),
+                    RsTypeKind::Array { element_type, .. } => self.todo.push(element_type),
+                    RsTypeKind::StdOptional(value_type) => self.todo.push(value_type),
+                    RsTypeKind::InitializerList(element_type) => self.todo.push(element_type),
+                    RsTypeKind::Span { element_type, .. } => self.todo.push(element_type),
+                    RsTypeKind::AbslStatus(absl_status) => {
+                        self.todo.extend(absl_status.value_type.as_deref())
+                    }
+                    RsTypeKind::UniquePtr(unique_ptr) => self.todo.push(&unique_ptr.pointee_type),
+                    RsTypeKind::SharedPtr(shared_ptr) => self.todo.push(&shared_ptr.pointee_type),
+                    RsTypeKind::StdFunction(std_function) => {
+                        self.todo.push(&std_function.return_type);
+                        self.todo.extend(std_function.param_types.iter().rev());
+                    }
                     RsTypeKind::Other { type_args, .. } => self.todo.extend(type_args.iter().rev()),
                 };
                 Some(curr)
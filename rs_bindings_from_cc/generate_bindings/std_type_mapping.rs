@@ -0,0 +1,580 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! C++ standard library (and Abseil) types which are converted into Rust types
+//! when they are passed to or returned from a function.
+//!
+//! The importer spells these types with `#`-prefixed names in `RsType::name`
+//! and `CcType::name`. This module maps the names to the Rust types (for
+//! `rs_type_kind`) and to the C++ types (for `format_cc_type`), and formats the
+//! conversions done by the thunks.
+
+use crate::rs_snippet::{Lifetime, RsTypeKind};
+use code_gen_utils::make_rs_ident;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use std::rc::Rc;
+
+/// The `RsType::name` of `std::string_view` and `absl::string_view`.
+pub const STRING_VIEW_RS_TYPE_NAME: &str = "#stringView";
+
+/// The spelling of a string view type in C++.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CcStringView {
+    /// `std::string_view`.
+    Std,
+    /// `absl::string_view`, when it isn't an alias of `std::string_view`.
+    Absl,
+}
+
+impl CcStringView {
+    /// Returns the string view type that is spelled as `name` in
+    /// `CcType::name`.
+    pub fn from_cc_type_name(name: &str) -> Option<Self> {
+        match name {
+            "#stdStringView" => Some(Self::Std),
+            "#abslStringView" => Some(Self::Absl),
+            _ => None,
+        }
+    }
+
+    /// The C++ spelling of the type.
+    pub fn format_cc_type(self) -> TokenStream {
+        match self {
+            Self::Std => quote! { std::string_view },
+            Self::Absl => quote! { absl::string_view },
+        }
+    }
+}
+
+/// A `std::string_view` or `absl::string_view`, which is represented by a Rust
+/// `&[u8]`.
+///
+/// The thunks pass the pointer to the characters and their number, and the
+/// string view is created (or taken apart) on the C++ side.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StringView {
+    /// The lifetime of the viewed characters, if known (e.g. from lifetime
+    /// annotations).
+    pub lifetime: Option<Lifetime>,
+}
+
+impl StringView {
+    /// The type of a string view parameter: `&[u8]`, or `&str` if
+    /// `--string_view_params_as_str` is enabled.
+    ///
+    /// Unlike return values, parameters can be borrowed for the duration of
+    /// the call even if their lifetime is unknown.
+    pub fn format_param_type(&self, as_str: bool) -> TokenStream {
+        let lifetime = self.lifetime.as_ref().map(Lifetime::format_for_reference);
+        if as_str {
+            quote! { & #lifetime str }
+        } else {
+            quote! { & #lifetime [u8] }
+        }
+    }
+
+    /// Converts the pointer to the characters and their number, as returned
+    /// by a thunk, into a Rust slice (or a slice pointer, if the lifetime of
+    /// the characters is unknown).
+    ///
+    /// An empty string view may have a null `data()`, which isn't allowed in
+    /// a Rust slice, so it is replaced with a dangling pointer.
+    pub fn format_rs_from_abi(&self, data: TokenStream, size: TokenStream) -> TokenStream {
+        match self.lifetime {
+            Some(_) => quote! {
+                if #data.is_null() { &[] } else { ::core::slice::from_raw_parts(#data, #size) }
+            },
+            None => quote! {
+                ::core::ptr::slice_from_raw_parts(
+                    if #data.is_null() { ::core::ptr::NonNull::dangling().as_ptr() } else { #data },
+                    #size,
+                )
+            },
+        }
+    }
+}
+
+impl ToTokens for StringView {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match &self.lifetime {
+            Some(lifetime) => {
+                let lifetime = lifetime.format_for_reference();
+                quote! { & #lifetime [u8] }
+            }
+            None => quote! { *const [u8] },
+        }
+        .to_tokens(tokens)
+    }
+}
+
+/// The `RsType::name` and `CcType::name` of `std::string`.
+pub const STD_STRING_TYPE_NAME: &str = "#stdString";
+
+/// A `std::string`, which is passed to a function as a Rust `&[u8]`, and
+/// returned as a `Vec<u8>`.
+///
+/// The characters of a parameter are copied into a `std::string` by the thunk.
+/// The thunk of a function returning a string copies its characters into a
+/// `Vec<u8>` by calling back into Rust, so that the `std::string` never needs
+/// to be moved into Rust.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StdString;
+
+impl StdString {
+    /// The type of a `std::string` parameter.
+    pub fn format_param_type(self) -> TokenStream {
+        quote! { &[u8] }
+    }
+
+    /// The Rust thunk parameters through which the returned string is passed
+    /// back: the `Vec<u8>` that the characters are appended to, and the
+    /// callback which appends them.
+    pub fn format_return_thunk_params(self) -> [(Ident, TokenStream); 2] {
+        [
+            (make_rs_ident("__return"), quote! { *mut ::core::ffi::c_void }),
+            (
+                make_rs_ident("__return_append"),
+                quote! { unsafe extern "C" fn(*mut ::core::ffi::c_void, *const u8, usize) },
+            ),
+        ]
+    }
+
+    /// Calls the thunk (with `thunk_args` following the parameters from
+    /// `format_return_thunk_params`), and returns the `Vec<u8>` that the
+    /// characters of the returned string were copied into.
+    pub fn format_rs_return(self, thunk: TokenStream, thunk_args: TokenStream) -> TokenStream {
+        quote! {
+            unsafe extern "C" fn __return_append(
+                __return: *mut ::core::ffi::c_void, __data: *const u8, __size: usize,
+            ) {
+                unsafe {
+                    (*(__return as *mut ::std::vec::Vec<u8>))
+                        .extend_from_slice(::core::slice::from_raw_parts(__data, __size));
+                }
+            }
+            let mut __return = ::std::vec::Vec::<u8>::new();
+            #thunk(
+                &mut __return as *mut ::std::vec::Vec<u8> as *mut ::core::ffi::c_void,
+                __return_append
+                #thunk_args
+            );
+            __return
+        }
+    }
+
+    /// The C++ thunk parameters corresponding to `format_return_thunk_params`.
+    pub fn format_cc_return_thunk_params(self) -> [(TokenStream, TokenStream); 2] {
+        [
+            (quote! { __return }, quote! { void* }),
+            (
+                quote! { __return_append },
+                quote! { crubit::type_identity_t<void(void*, char const*, size_t)>* },
+            ),
+        ]
+    }
+}
+
+impl ToTokens for StdString {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        quote! { ::std::vec::Vec<u8> }.to_tokens(tokens)
+    }
+}
+
+/// The `RsType::name` of an `absl::Span` or `std::span` of const elements.
+pub const SPAN_CONST_RS_TYPE_NAME: &str = "#span const";
+
+/// The `RsType::name` of an `absl::Span` or `std::span` of mutable elements.
+pub const SPAN_MUT_RS_TYPE_NAME: &str = "#span mut";
+
+/// The spelling of a span type in C++.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CcSpan {
+    /// `std::span`, with a dynamic extent.
+    Std,
+    /// `absl::Span`.
+    Absl,
+}
+
+impl CcSpan {
+    /// Returns the span type that is spelled as `name` in `CcType::name`.
+    pub fn from_cc_type_name(name: &str) -> Option<Self> {
+        match name {
+            "#stdSpan" => Some(Self::Std),
+            "#abslSpan" => Some(Self::Absl),
+            _ => None,
+        }
+    }
+
+    /// The C++ spelling of the span of `element_type`.
+    pub fn format_cc_type(self, element_type: TokenStream) -> TokenStream {
+        match self {
+            Self::Std => quote! { std::span<#element_type> },
+            Self::Absl => quote! { absl::Span<#element_type> },
+        }
+    }
+}
+
+/// The `RsType::name` and `CcType::name` of `absl::Status`.
+pub const ABSL_STATUS_TYPE_NAME: &str = "#abslStatus";
+
+/// The `RsType::name` and `CcType::name` of `absl::StatusOr<T>`.
+pub const ABSL_STATUS_OR_TYPE_NAME: &str = "#abslStatusOr";
+
+/// An `absl::Status` or `absl::StatusOr<T>` return value, which is returned as
+/// a Rust `Result<(), cc_status::Status>` or `Result<T, cc_status::Status>`.
+///
+/// The thunk reports a non-OK status by calling back into Rust with its code
+/// and message (see `cc_status::internal::set_status_error`), and moves the
+/// value of an OK `absl::StatusOr<T>` into a return slot provided by Rust.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AbslStatus {
+    /// The `T` of `absl::StatusOr<T>`, or `None` for `absl::Status`.
+    pub value_type: Option<Rc<RsTypeKind>>,
+}
+
+impl AbslStatus {
+    /// The `Result` type returning `value_type` (the unit type for
+    /// `absl::Status`).
+    pub fn format_result_type(value_type: TokenStream) -> TokenStream {
+        quote! { ::core::result::Result<#value_type, ::cc_status::Status> }
+    }
+
+    /// The Rust thunk parameters through which the status is passed back: the
+    /// slot that the value of an `absl::StatusOr<T>` is moved into, the
+    /// `Option<cc_status::Status>` that an error is stored into, and the
+    /// callback which stores it.
+    pub fn format_return_thunk_params(&self) -> Vec<(Ident, TokenStream)> {
+        let mut params = vec![];
+        if let Some(value_type) = &self.value_type {
+            params.push((make_rs_ident("__return"), quote! { *mut #value_type }));
+        }
+        params.push((make_rs_ident("__return_error"), quote! { *mut ::core::ffi::c_void }));
+        params.push((
+            make_rs_ident("__set_error"),
+            quote! {
+                unsafe extern "C" fn(
+                    *mut ::core::ffi::c_void, ::core::ffi::c_int, *const u8, usize)
+            },
+        ));
+        params
+    }
+
+    /// Calls the thunk (with `thunk_args` following the parameters from
+    /// `format_return_thunk_params`), and returns the value or the error that
+    /// it passed back.
+    pub fn format_rs_return(&self, thunk: TokenStream, thunk_args: TokenStream) -> TokenStream {
+        let (return_slot, return_arg, value) = match &self.value_type {
+            Some(value_type) => (
+                quote! { let mut __return = ::core::mem::MaybeUninit::<#value_type>::uninit(); },
+                quote! { __return.as_mut_ptr(), },
+                quote! { __return.assume_init() },
+            ),
+            None => (quote! {}, quote! {}, quote! { () }),
+        };
+        quote! {
+            let mut __error: ::core::option::Option<::cc_status::Status> = None;
+            #return_slot
+            #thunk(
+                #return_arg
+                &mut __error as *mut ::core::option::Option<::cc_status::Status>
+                    as *mut ::core::ffi::c_void,
+                ::cc_status::internal::set_status_error
+                #thunk_args
+            );
+            match __error {
+                None => Ok(#value),
+                Some(__error) => Err(__error),
+            }
+        }
+    }
+
+    /// The C++ thunk parameters corresponding to `format_return_thunk_params`.
+    /// `cc_value_type` is the C++ type of the value of an `absl::StatusOr<T>`.
+    pub fn format_cc_return_thunk_params(
+        &self,
+        cc_value_type: Option<TokenStream>,
+    ) -> Vec<(TokenStream, TokenStream)> {
+        let mut params = vec![];
+        if let Some(cc_value_type) = cc_value_type {
+            params.push((quote! { __return }, quote! { #cc_value_type* }));
+        }
+        params.push((quote! { __return_error }, quote! { void* }));
+        params.push((quote! { __set_error }, quote! { crubit::SetStatusError* }));
+        params
+    }
+
+    /// The C++ thunk statements which report the status returned by
+    /// `return_expr`, and move the value of an OK `absl::StatusOr<T>` into
+    /// `__return`.
+    pub fn format_cc_return(&self, return_expr: TokenStream) -> TokenStream {
+        if self.value_type.is_none() {
+            return quote! { crubit::ReportStatus(#return_expr, __return_error, __set_error) };
+        }
+        quote! {
+            auto __status_or = #return_expr;
+            if (crubit::ReportStatus(__status_or.status(), __return_error, __set_error))
+                new(__return) auto(*std::move(__status_or))
+        }
+    }
+}
+
+impl ToTokens for AbslStatus {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value_type = match &self.value_type {
+            Some(value_type) => value_type.to_token_stream(),
+            None => quote! { () },
+        };
+        Self::format_result_type(value_type).to_tokens(tokens)
+    }
+}
+
+/// The `RsType::name` and `CcType::name` of `std::unique_ptr<T>`.
+pub const UNIQUE_PTR_TYPE_NAME: &str = "#uniquePtr";
+
+/// A `std::unique_ptr<T>` parameter or return value, which is passed as a Rust
+/// `Option<cc_unique_ptr::UniquePtr<T>>`.
+///
+/// The thunks pass the raw pointer across the FFI boundary, transferring the
+/// ownership of the object: a parameter is released by Rust and wrapped into a
+/// `std::unique_ptr<T>` by the thunk, and a return value is released by the
+/// thunk and deleted by `UniquePtr` through a deleter thunk (which calls
+/// `delete`, since only `std::default_delete<T>` is supported).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UniquePtr {
+    /// The `T` of `std::unique_ptr<T>`.
+    pub pointee_type: Rc<RsTypeKind>,
+}
+
+impl UniquePtr {
+    /// The `Option<UniquePtr<T>>` type owning a `pointee_type`.
+    pub fn format_option_type(pointee_type: TokenStream) -> TokenStream {
+        quote! { ::core::option::Option<::cc_unique_ptr::UniquePtr<#pointee_type>> }
+    }
+
+    /// The type of the raw pointer passed to and returned from the Rust thunk.
+    pub fn format_thunk_type(&self) -> TokenStream {
+        let pointee_type = &self.pointee_type;
+        quote! { *mut #pointee_type }
+    }
+
+    /// The Rust thunk argument releasing the parameter `ident`.
+    pub fn format_thunk_arg(ident: &Ident) -> TokenStream {
+        quote! { ::cc_unique_ptr::internal::into_raw_or_null(#ident) }
+    }
+
+    /// Takes the ownership of the pointer returned by `thunk_call`, which is
+    /// deleted by calling the thunk `deleter`.
+    ///
+    /// The result must be placed in an `unsafe` context (which also covers the
+    /// call in the deleter closure).
+    pub fn format_rs_return(thunk_call: TokenStream, deleter: TokenStream) -> TokenStream {
+        quote! {
+            ::cc_unique_ptr::UniquePtr::from_raw(#thunk_call, |__ptr| #deleter(__ptr))
+        }
+    }
+
+    /// The C++ thunk argument wrapping the raw pointer `ident` into the
+    /// `std::unique_ptr` type `cc_type`, which takes its ownership.
+    pub fn format_cc_thunk_arg(cc_type: TokenStream, ident: TokenStream) -> TokenStream {
+        quote! { #cc_type(#ident) }
+    }
+
+    /// The C++ thunk statement releasing the pointer returned by `return_expr`.
+    pub fn format_cc_return(return_expr: TokenStream) -> TokenStream {
+        quote! { return #return_expr.release() }
+    }
+}
+
+impl ToTokens for UniquePtr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        Self::format_option_type(self.pointee_type.to_token_stream()).to_tokens(tokens)
+    }
+}
+
+/// The `RsType::name` and `CcType::name` of `std::shared_ptr<T>`.
+pub const SHARED_PTR_TYPE_NAME: &str = "#sharedPtr";
+
+/// A `std::shared_ptr<T>` parameter or return value, which is passed as a Rust
+/// `Option<cc_shared_ptr::SharedPtr<T>>`.
+///
+/// The thunks pass a handle to a heap-allocated `std::shared_ptr<T>` across the
+/// FFI boundary (see `crubit/support/internal/shared_ptr.h`).  A returned handle
+/// is accessed through additional thunks: `SharedPtr::clone` copies the
+/// `std::shared_ptr<T>` (incrementing the reference count), and
+/// `SharedPtr::drop` deletes it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SharedPtr {
+    /// The `T` of `std::shared_ptr<T>`.
+    pub pointee_type: Rc<RsTypeKind>,
+}
+
+impl SharedPtr {
+    /// The `Option<SharedPtr<T>>` type sharing a `pointee_type`.
+    pub fn format_option_type(pointee_type: TokenStream) -> TokenStream {
+        quote! { ::core::option::Option<::cc_shared_ptr::SharedPtr<#pointee_type>> }
+    }
+
+    /// The type of the handle passed to and returned from the Rust thunk.
+    pub fn format_thunk_type() -> TokenStream {
+        quote! { *mut ::core::ffi::c_void }
+    }
+
+    /// The Rust thunk argument releasing the handle of the parameter `ident`.
+    pub fn format_thunk_arg(ident: &Ident) -> TokenStream {
+        quote! { ::cc_shared_ptr::internal::into_raw_or_null(#ident) }
+    }
+
+    /// Takes the ownership of the handle returned by `thunk_call`, which is
+    /// accessed through the thunks `get`, `clone`, and `delete`.
+    ///
+    /// The result must be placed in an `unsafe` context (which also covers the
+    /// calls in the closures).
+    pub fn format_rs_return(
+        thunk_call: TokenStream,
+        get: TokenStream,
+        clone: TokenStream,
+        delete: TokenStream,
+    ) -> TokenStream {
+        quote! {
+            ::cc_shared_ptr::SharedPtr::from_raw(
+                #thunk_call,
+                ::cc_shared_ptr::SharedPtrOps {
+                    get: |__handle| #get(__handle),
+                    clone: |__handle| #clone(__handle),
+                    delete: |__handle| #delete(__handle),
+                },
+            )
+        }
+    }
+
+    /// The C++ thunk argument taking the `std::shared_ptr` of type `cc_type`
+    /// out of the handle `ident`.
+    pub fn format_cc_thunk_arg(cc_type: TokenStream, ident: TokenStream) -> TokenStream {
+        quote! { crubit::TakeSharedPtrHandle<#cc_type>(#ident) }
+    }
+
+    /// The C++ thunk statement returning a handle to the `std::shared_ptr`
+    /// returned by `return_expr`.
+    pub fn format_cc_return(return_expr: TokenStream) -> TokenStream {
+        quote! { return crubit::NewSharedPtrHandle(#return_expr) }
+    }
+}
+
+impl ToTokens for SharedPtr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        Self::format_option_type(self.pointee_type.to_token_stream()).to_tokens(tokens)
+    }
+}
+
+/// The `RsType::name` and `CcType::name` of `std::function<R(Args...)>`.
+pub const STD_FUNCTION_TYPE_NAME: &str = "#stdFunction";
+
+/// A `std::function<R(Args...)>` parameter, which is passed as a Rust
+/// `impl Fn(Args...) -> R + Send + Sync + 'static`.
+///
+/// C++ may copy the `std::function`, and call the copies from any thread (even
+/// concurrently), so the closure must be `Send` and `Sync`.
+///
+/// The closure is boxed, and the ownership of the box is passed to the thunk
+/// as a handle, together with a function calling the closure (defined next to
+/// the call of the thunk), and a function dropping it.  The thunk wraps them
+/// into a `std::function` (see `crubit/support/internal/function.h`).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StdFunction {
+    /// The `R` of `std::function<R(Args...)>`.
+    pub return_type: Rc<RsTypeKind>,
+    /// The `Args...` of `std::function<R(Args...)>`.
+    pub param_types: Rc<[RsTypeKind]>,
+}
+
+impl StdFunction {
+    /// The `Fn(Args...) -> R` trait implemented by the closure.
+    fn format_fn_trait(&self) -> TokenStream {
+        let param_types = self.param_types.iter();
+        let return_frag = self.return_type.format_as_return_type_fragment(None);
+        quote! { Fn( #( #param_types ),* ) #return_frag }
+    }
+
+    /// The type of the boxed closure, whose handle is passed to the thunk.
+    fn format_dyn_type(&self) -> TokenStream {
+        let fn_trait = self.format_fn_trait();
+        quote! { dyn #fn_trait + Send + Sync + 'static }
+    }
+
+    /// The Rust thunk parameters through which the parameter `ident` is
+    /// passed: the handle to the closure, the function calling it, and the
+    /// function dropping it.
+    pub fn format_thunk_params(&self, ident: &Ident) -> [(Ident, TokenStream); 3] {
+        let param_types = self.param_types.iter();
+        let return_frag = self.return_type.format_as_return_type_fragment(None);
+        [
+            (ident.clone(), quote! { *mut ::core::ffi::c_void }),
+            (
+                format_ident!("__{}_call", ident),
+                quote! {
+                    unsafe extern "C" fn(*mut ::core::ffi::c_void, #( #param_types ),*) #return_frag
+                },
+            ),
+            (
+                format_ident!("__{}_drop", ident),
+                quote! { unsafe extern "C" fn(*mut ::core::ffi::c_void) },
+            ),
+        ]
+    }
+
+    /// The Rust thunk arguments corresponding to `format_thunk_params`, which
+    /// release the closure `ident`.
+    pub fn format_thunk_args(&self, ident: &Ident) -> TokenStream {
+        let dyn_type = self.format_dyn_type();
+        let param_types = self.param_types.iter();
+        let arg_idents =
+            (0..self.param_types.len()).map(|i| format_ident!("__arg{i}")).collect::<Vec<_>>();
+        let return_frag = self.return_type.format_as_return_type_fragment(None);
+        quote! {
+            ::cc_function::internal::into_raw::<#dyn_type>(::std::boxed::Box::new(#ident)),
+            {
+                unsafe extern "C" fn __call(
+                    __closure: *mut ::core::ffi::c_void, #( #arg_idents: #param_types ),*
+                ) #return_frag {
+                    unsafe {
+                        ::cc_function::internal::get::<#dyn_type>(__closure)( #( #arg_idents ),* )
+                    }
+                }
+                __call
+            },
+            ::cc_function::internal::drop_raw::<#dyn_type>
+        }
+    }
+
+    /// The types of the C++ thunk parameters corresponding to
+    /// `format_thunk_params`, for a parameter of type `cc_type` (i.e.
+    /// `std::function<R(Args...)>`).
+    pub fn format_cc_thunk_param_types(cc_type: &TokenStream) -> [TokenStream; 3] {
+        [
+            quote! { void* },
+            quote! { crubit::RustFunctionCall<#cc_type> },
+            quote! { crubit::RustFunctionDrop },
+        ]
+    }
+
+    /// The C++ thunk argument wrapping the closure passed through the
+    /// parameters `ident`, `call_ident`, and `drop_ident` (see
+    /// `format_cc_thunk_param_types`) into a `cc_type`.
+    pub fn format_cc_thunk_arg(
+        cc_type: TokenStream,
+        ident: TokenStream,
+        call_ident: TokenStream,
+        drop_ident: TokenStream,
+    ) -> TokenStream {
+        quote! { crubit::MakeRustFunction<#cc_type>(#ident, #call_ident, #drop_ident) }
+    }
+}
+
+impl ToTokens for StdFunction {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let fn_trait = self.format_fn_trait();
+        quote! { impl #fn_trait + Send + Sync + 'static }.to_tokens(tokens)
+    }
+}
@@ -13,6 +13,7 @@
 #include "absl/container/flat_hash_set.h"
 #include "absl/log/check.h"
 #include "absl/strings/string_view.h"
+#include "common/file_io.h"
 #include "common/status_macros.h"
 #include "rs_bindings_from_cc/cmdline.h"
 #include "rs_bindings_from_cc/collect_instantiations.h"
@@ -79,19 +80,68 @@ absl::StatusOr<BindingsAndMetadata> GenerateBindingsAndMetadata(
                  .extra_rs_srcs = args.extra_rs_srcs,
                  .clang_args = clang_args_view,
                  .extra_instantiations = requested_instantiations,
-                 .crubit_features = args.target_to_features}));
+                 .crubit_features = args.target_to_features,
+                 .convert_string_views = args.convert_string_views}));
 
   if (!args.instantiations_out.empty()) {
     ir.crate_root_path = "__cc_template_instantiations_rs_api";
   }
+  // The current target may use its own copy of the support library.
+  absl::string_view crubit_support_path_format =
+      args.crubit_support_path_format;
+  if (auto it = args.target_to_crubit_support_path_format.find(
+          args.current_target);
+      it != args.target_to_crubit_support_path_format.end()) {
+    crubit_support_path_format = it->second;
+  }
+
+  GeneratorOptions options{
+      .generate_raw_thunks_module = args.generate_raw_thunks_module,
+      .detail_module_name = args.detail_module_name,
+      .generate_mock_thunks = args.generate_mock_thunks,
+      .thunk_export_macro = args.thunk_export_macro,
+      .hash_thunk_names = args.hash_thunk_names,
+      .generate_layout_verification = args.generate_layout_verification,
+      .generate_bytemuck_impls = args.generate_bytemuck_impls,
+      .generate_prelude_module = args.generate_prelude_module,
+      .import_public_headers = args.import_public_headers,
+      .generate_item_spans = !args.item_spans_out.empty(),
+      .generate_bindings_status = !args.bindings_status_out.empty(),
+      .rs_api_impl_path = args.generate_line_directives ? args.cc_out : "",
+      .generate_cxx_extern_types = args.generate_cxx_extern_types,
+      .bindgen_allowlist_file = args.bindgen_allowlist_file,
+      .minimize_public_header_includes = args.minimize_public_header_includes,
+      .lazy_ir = args.lazy_ir,
+      .thunk_exception_note = args.thunk_exception_note,
+      .overload_naming = args.overload_naming,
+      .rs_api_lints = args.rs_api_lints,
+      .strict_pointer_constness = args.strict_pointer_constness,
+      .compact_layout_assertions = args.compact_layout_assertions,
+      .abi_hash_symbols = args.abi_hash_symbols,
+      .string_view_params_as_str = args.string_view_params_as_str,
+      .record_ext_traits = args.record_ext_traits,
+      .constructor_functions = args.constructor_functions,
+      .attribute_translations = args.attribute_translations,
+      .version_stamp = args.version_stamp,
+  };
+  if (auto it = args.target_to_cc_module_name.find(args.current_target);
+      it != args.target_to_cc_module_name.end()) {
+    options.cc_module_name = it->second;
+  }
 
   bool generate_error_report = !args.error_report_out.empty();
+  std::string stable_thunks_baseline;
+  if (!args.stable_thunks_baseline.empty()) {
+    CRUBIT_ASSIGN_OR_RETURN(stable_thunks_baseline,
+                            GetFileContents(args.stable_thunks_baseline));
+  }
   CRUBIT_ASSIGN_OR_RETURN(
       Bindings bindings,
-      GenerateBindings(ir, args.crubit_support_path_format,
+      GenerateBindings(ir, crubit_support_path_format,
                        args.clang_format_exe_path, args.rustfmt_exe_path,
                        args.rustfmt_config_path, generate_error_report,
-                       args.generate_source_location_in_doc_comment));
+                       args.generate_source_location_in_doc_comment, options,
+                       stable_thunks_baseline));
 
   absl::flat_hash_map<std::string, std::string> instantiations;
   std::optional<const Namespace*> ns =
@@ -113,6 +163,9 @@ absl::StatusOr<BindingsAndMetadata> GenerateBindingsAndMetadata(
       .namespaces = std::move(top_level_namespaces),
       .instantiations = std::move(instantiations),
       .error_report = bindings.error_report,
+      .item_spans = bindings.item_spans,
+      .bindings_status = bindings.bindings_status,
+      .stable_thunks = bindings.stable_thunks,
   };
 }
 
@@ -31,6 +31,12 @@ struct BindingsAndMetadata {
   absl::flat_hash_map<std::string, std::string> instantiations;
   // A JSON error report, if requested.
   std::string error_report;
+  // The JSON spans of the bindings of the items in `rs_api`, if requested.
+  std::string item_spans;
+  // The JSON list of whether each item gets bindings, if requested.
+  std::string bindings_status;
+  // The JSON signatures of the pinned thunks, by symbol name.
+  std::string stable_thunks;
 };
 
 // Returns `BindingsAndMetadata` as requested by the user on the command line.
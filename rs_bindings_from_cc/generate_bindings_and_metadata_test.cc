@@ -25,7 +25,9 @@ namespace crubit {
 namespace {
 
 using ::testing::ElementsAre;
+using ::testing::HasSubstr;
 using ::testing::IsEmpty;
+using ::testing::Not;
 using ::testing::Pair;
 using ::testing::StrEq;
 
@@ -75,6 +77,27 @@ TEST(GenerateBindingsAndMetadataTest, GeneratingIR) {
   ASSERT_EQ(item->owning_target.value(), "//:target");
 }
 
+TEST(GenerateBindingsAndMetadataTest, MinimizePublicHeaderIncludes) {
+  CmdlineArgs args = MakeCmdline("a.h").args();
+  args.public_headers.push_back(HeaderName("b.h"));
+  args.headers_to_targets[HeaderName("b.h")] = args.current_target;
+  args.minimize_public_header_includes = true;
+  absl::StatusOr<Cmdline> cmdline = Cmdline::Create(args);
+  CHECK_OK(cmdline);
+
+  ASSERT_OK_AND_ASSIGN(
+      BindingsAndMetadata result,
+      GenerateBindingsAndMetadata(
+          *cmdline, DefaultClangArgs(),
+          /*virtual_headers_contents_for_testing=*/
+          {{HeaderName("a.h"), "struct S { int x; };"},
+           {HeaderName("b.h"), "enum class E : int { kA };"}}));
+
+  // Only the record needs C++ code (its layout assertions).
+  EXPECT_THAT(result.rs_api_impl, HasSubstr("#include \"a.h\""));
+  EXPECT_THAT(result.rs_api_impl, Not(HasSubstr("#include \"b.h\"")));
+}
+
 TEST(GenerateBindingsAndMetadataTest, InstantiationsAreEmptyInNormalMode) {
   Cmdline cmdline = MakeCmdline("a.h");
 
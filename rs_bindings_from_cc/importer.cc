@@ -43,8 +43,10 @@
 #include "clang/AST/DeclBase.h"
 #include "clang/AST/DeclCXX.h"
 #include "clang/AST/DeclFriend.h"
+#include "clang/AST/DeclTemplate.h"
 #include "clang/AST/Mangle.h"
 #include "clang/AST/RawCommentList.h"
+#include "clang/AST/TemplateBase.h"
 #include "clang/AST/Type.h"
 #include "clang/Basic/AttrKinds.h"
 #include "clang/Basic/Diagnostic.h"
@@ -54,8 +56,19 @@
 #include "clang/Basic/SourceLocation.h"
 #include "clang/Basic/SourceManager.h"
 #include "clang/Basic/Specifiers.h"
+#include "clang/Basic/TokenKinds.h"
+#include "clang/Lex/LiteralSupport.h"
+#include "clang/Lex/MacroInfo.h"
+#include "clang/Lex/Preprocessor.h"
+#include "clang/Lex/Token.h"
 #include "clang/Sema/Sema.h"
+#include "llvm/ADT/APInt.h"
+#include "llvm/ADT/APSInt.h"
+#include "llvm/ADT/ArrayRef.h"
 #include "llvm/ADT/STLExtras.h"
+#include "llvm/ADT/SmallString.h"
+#include "llvm/ADT/SmallVector.h"
+#include "llvm/ADT/StringRef.h"
 #include "llvm/Support/Casting.h"
 #include "llvm/Support/FormatVariadic.h"
 #include "llvm/Support/Regex.h"
@@ -103,6 +116,71 @@ bool IsFilteredComment(const clang::SourceManager& sm,
   }
   return false;
 }
+
+// Returns the value and the type of an object-like macro whose replacement
+// list is an integer literal, optionally negated and parenthesized (e.g.
+// `#define kMin (-1)`), or a `bool` literal. Returns `std::nullopt` for any
+// other macro.
+//
+// Like in C++, the type of an integer literal is the first type of `int`,
+// `long` and `long long` (or their unsigned counterparts, for literals with a
+// `u` suffix) which is allowed by the suffix and can represent the value.
+// Unsuffixed hexadecimal, octal and binary literals may also be unsigned.
+std::optional<std::pair<llvm::APSInt, clang::QualType>> EvaluateLiteralMacro(
+    clang::Preprocessor& pp, clang::ASTContext& ctx,
+    const clang::MacroInfo& macro_info) {
+  llvm::ArrayRef<clang::Token> tokens = macro_info.tokens();
+  while (tokens.size() >= 2 && tokens.front().is(clang::tok::l_paren) &&
+         tokens.back().is(clang::tok::r_paren)) {
+    tokens = tokens.drop_front().drop_back();
+  }
+  if (tokens.size() == 1 &&
+      tokens[0].isOneOf(clang::tok::kw_true, clang::tok::kw_false)) {
+    llvm::APInt value(1, tokens[0].is(clang::tok::kw_true));
+    return std::make_pair(llvm::APSInt(value, /*isUnsigned=*/true),
+                          ctx.BoolTy);
+  }
+  bool is_negated = !tokens.empty() && tokens.front().is(clang::tok::minus);
+  if (is_negated) tokens = tokens.drop_front();
+  if (tokens.size() != 1 || !tokens[0].is(clang::tok::numeric_constant)) {
+    return std::nullopt;
+  }
+
+  llvm::SmallString<32> buffer;
+  bool invalid = false;
+  llvm::StringRef spelling = pp.getSpelling(tokens[0], buffer, &invalid);
+  if (invalid) return std::nullopt;
+  clang::NumericLiteralParser literal(
+      spelling, tokens[0].getLocation(), pp.getSourceManager(),
+      pp.getLangOpts(), pp.getTargetInfo(), pp.getDiagnostics());
+  if (literal.hadError || !literal.isIntegerLiteral() ||
+      literal.hasUDSuffix() || literal.isSizeT || literal.isBitInt ||
+      literal.isImaginary) {
+    return std::nullopt;
+  }
+  llvm::APInt value(64, 0);
+  if (literal.GetIntegerValue(value)) return std::nullopt;  // Overflow.
+
+  llvm::SmallVector<clang::QualType, 6> types;
+  if (!literal.isLong && !literal.isLongLong) {
+    types.append({ctx.IntTy, ctx.UnsignedIntTy});
+  }
+  if (!literal.isLongLong) types.append({ctx.LongTy, ctx.UnsignedLongTy});
+  types.append({ctx.LongLongTy, ctx.UnsignedLongLongTy});
+  for (clang::QualType type : types) {
+    bool is_unsigned = type->isUnsignedIntegerType();
+    if (is_unsigned ? !literal.isUnsigned && literal.getRadix() == 10
+                    : literal.isUnsigned) {
+      continue;
+    }
+    unsigned width = ctx.getIntWidth(type);
+    if (value.getActiveBits() > (is_unsigned ? width : width - 1)) continue;
+    llvm::APSInt result(value.zextOrTrunc(width), is_unsigned);
+    if (is_negated) result = -result;
+    return std::make_pair(std::move(result), type);
+  }
+  return std::nullopt;
+}
 }  // namespace
 
 namespace {
@@ -270,6 +348,12 @@ Importer::SourceOrderKey Importer::GetSourceOrderKey(
   return SourceOrderKey(comment->getSourceRange());
 }
 
+Importer::SourceOrderKey Importer::GetSourceOrderKey(
+    const clang::MacroInfo* macro_info) const {
+  return SourceOrderKey(clang::SourceRange(macro_info->getDefinitionLoc(),
+                                           macro_info->getDefinitionEndLoc()));
+}
+
 class Importer::SourceLocationComparator {
  public:
   bool operator()(const clang::SourceLocation& a,
@@ -472,6 +556,12 @@ std::vector<ItemId> Importer::GetItemIdsInSourceOrder(
   for (auto& [_, comment] : ordered_comments) {
     items.push_back({GetSourceOrderKey(comment), GenerateItemId(comment)});
   }
+  // Macros aren't scoped, so their constants are always top-level items.
+  if (decl_context->isTranslationUnit()) {
+    for (const auto& [macro_info, constant] : macro_constants_) {
+      items.push_back({GetSourceOrderKey(macro_info), constant.id});
+    }
+  }
   llvm::sort(items, compare_locations);
 
   std::vector<ItemId> ordered_item_ids;
@@ -517,6 +607,45 @@ void Importer::ImportFreeComments() {
   llvm::sort(comments_, SourceLocationComparator(sm));
 }
 
+void Importer::ImportMacroConstants() {
+  clang::Preprocessor& pp = sema_.getPreprocessor();
+  for (const auto& [name, _] : pp.macros()) {
+    const clang::MacroInfo* macro_info = pp.getMacroInfo(name);
+    if (macro_info == nullptr || !macro_info->isObjectLike() ||
+        macro_info->isBuiltinMacro() ||
+        GetOwningTarget(macro_info->getDefinitionLoc()) !=
+            invocation_.target_) {
+      continue;
+    }
+    // The literal parser reports invalid literals (e.g. `0x`) as errors, which
+    // shouldn't fail the import when the macro is never expanded.
+    std::optional<std::pair<llvm::APSInt, clang::QualType>> value;
+    RecordingDiagnosticConsumer diagnostic_recorder =
+        RecordDiagnostics(sema_.getDiagnostics(), [&] {
+          value = EvaluateLiteralMacro(pp, ctx_, *macro_info);
+        });
+    if (!value.has_value() || diagnostic_recorder.getNumErrors() != 0) {
+      continue;
+    }
+    const clang::tidy::lifetimes::ValueLifetimes* no_lifetimes = nullptr;
+    absl::StatusOr<MappedType> type =
+        ConvertQualType(value->second, no_lifetimes, std::nullopt);
+    if (!type.ok()) continue;
+    macro_constants_.push_back(
+        {macro_info,
+         GlobalVar{
+             .identifier = Identifier(name->getName().str()),
+             .id = ItemId(reinterpret_cast<uintptr_t>(macro_info)),
+             .owning_target = invocation_.target_,
+             .type = *std::move(type),
+             .value = IntegerConstant(value->first),
+             .source_loc =
+                 ConvertSourceLocation(macro_info->getDefinitionLoc()),
+             .is_macro = true,
+         }});
+  }
+}
+
 void Importer::Import(clang::TranslationUnitDecl* translation_unit_decl) {
   ImportFreeComments();
   clang::SourceManager& sm = ctx_.getSourceManager();
@@ -530,6 +659,11 @@ void Importer::Import(clang::TranslationUnitDecl* translation_unit_decl) {
                  .id = GenerateItemId(comment)}});
   }
 
+  ImportMacroConstants();
+  for (const auto& [macro_info, constant] : macro_constants_) {
+    ordered_items.push_back({GetSourceOrderKey(macro_info), constant});
+  }
+
   ImportDeclsFromDeclContext(translation_unit_decl);
   for (const auto& [decl, item] : import_cache_) {
     if (item.has_value()) {
@@ -699,9 +833,12 @@ BazelLabel Importer::GetOwningTarget(const clang::Decl* decl) const {
   if (IsFullClassTemplateSpecializationOrChild(decl)) {
     return invocation_.target_;
   }
+  return GetOwningTarget(decl->getLocation());
+}
 
+BazelLabel Importer::GetOwningTarget(clang::SourceLocation loc) const {
   clang::SourceManager& source_manager = ctx_.getSourceManager();
-  auto source_location = decl->getLocation();
+  auto source_location = loc;
 
   // If the header this decl comes from is not associated with a target we
   // consider it a textual header. In that case we go up the include stack
@@ -910,6 +1047,427 @@ absl::StatusOr<MappedType> Importer::ConvertTemplateSpecializationType(
   return ConvertTypeDecl(specialization_decl);
 }
 
+std::optional<MappedType> Importer::ConvertStdArrayType(
+    const clang::Type& type) {
+  // Type aliases of `std::array` are preserved as aliases.
+  if (type.getAs<clang::TypedefType>() != nullptr ||
+      type.getAs<clang::UsingType>() != nullptr) {
+    return std::nullopt;
+  }
+  const auto* specialization_decl =
+      clang::dyn_cast_or_null<clang::ClassTemplateSpecializationDecl>(
+          type.getAsCXXRecordDecl());
+  if (specialization_decl == nullptr ||
+      !specialization_decl->isInStdNamespace() ||
+      specialization_decl->getName() != "array") {
+    return std::nullopt;
+  }
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() != 2 || args[0].getKind() != clang::TemplateArgument::Type ||
+      args[1].getKind() != clang::TemplateArgument::Integral) {
+    return std::nullopt;
+  }
+
+  // Only scalar element types (arithmetic types, enums and pointers) are
+  // guaranteed to be laid out identically to, and passable by value like, the
+  // corresponding Rust type.
+  clang::QualType element_type = args[0].getAsType();
+  if (!element_type->isScalarType()) return std::nullopt;
+  absl::StatusOr<MappedType> mapped_element_type =
+      ConvertQualType(element_type, /*lifetimes=*/nullptr,
+                      /*ref_qualifier_kind=*/std::nullopt);
+  if (!mapped_element_type.ok()) return std::nullopt;
+
+  return MappedType::StdArrayOf(*std::move(mapped_element_type),
+                                args[1].getAsIntegral().getZExtValue());
+}
+
+std::optional<MappedType> Importer::ConvertStdOptionalType(
+    const clang::Type& type) {
+  // Type aliases of `std::optional` are preserved as aliases.
+  if (type.getAs<clang::TypedefType>() != nullptr ||
+      type.getAs<clang::UsingType>() != nullptr) {
+    return std::nullopt;
+  }
+  const auto* specialization_decl =
+      clang::dyn_cast_or_null<clang::ClassTemplateSpecializationDecl>(
+          type.getAsCXXRecordDecl());
+  if (specialization_decl == nullptr ||
+      !specialization_decl->isInStdNamespace() ||
+      specialization_decl->getName() != "optional") {
+    return std::nullopt;
+  }
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() != 1 || args[0].getKind() != clang::TemplateArgument::Type) {
+    return std::nullopt;
+  }
+
+  // The thunks copy the value in and out of the `std::optional`, which is only
+  // equivalent to a Rust move if the value type is trivially copyable.
+  clang::QualType value_type = args[0].getAsType();
+  if (value_type.hasQualifiers() ||
+      !value_type.isTriviallyCopyableType(ctx_)) {
+    return std::nullopt;
+  }
+  absl::StatusOr<MappedType> mapped_value_type =
+      ConvertQualType(value_type, /*lifetimes=*/nullptr,
+                      /*ref_qualifier_kind=*/std::nullopt);
+  if (!mapped_value_type.ok()) return std::nullopt;
+
+  return MappedType::StdOptionalOf(*std::move(mapped_value_type));
+}
+
+std::optional<MappedType> Importer::ConvertInitializerListType(
+    const clang::Type& type) {
+  if (type.getAs<clang::TypedefType>() != nullptr ||
+      type.getAs<clang::UsingType>() != nullptr) {
+    return std::nullopt;
+  }
+  const auto* specialization_decl =
+      clang::dyn_cast_or_null<clang::ClassTemplateSpecializationDecl>(
+          type.getAsCXXRecordDecl());
+  if (specialization_decl == nullptr ||
+      !specialization_decl->isInStdNamespace() ||
+      specialization_decl->getName() != "initializer_list") {
+    return std::nullopt;
+  }
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() != 1 || args[0].getKind() != clang::TemplateArgument::Type) {
+    return std::nullopt;
+  }
+
+  // The thunks copy the elements of the Rust slice into the
+  // `std::initializer_list`, which is only equivalent to a Rust copy if the
+  // element type is trivially copyable.
+  clang::QualType element_type = args[0].getAsType();
+  if (element_type.hasQualifiers() ||
+      !element_type.isTriviallyCopyableType(ctx_)) {
+    return std::nullopt;
+  }
+  absl::StatusOr<MappedType> mapped_element_type =
+      ConvertQualType(element_type, /*lifetimes=*/nullptr,
+                      /*ref_qualifier_kind=*/std::nullopt);
+  if (!mapped_element_type.ok()) return std::nullopt;
+
+  return MappedType::InitializerListOf(*std::move(mapped_element_type));
+}
+
+std::optional<MappedType> Importer::ConvertStringViewType(
+    clang::QualType qual_type,
+    const clang::tidy::lifetimes::ValueLifetimes* lifetimes) {
+  if (!invocation_.convert_string_views_) return std::nullopt;
+  const clang::Type& type = *qual_type.getTypePtr();
+  // Type aliases of the string view types are preserved as aliases (but the
+  // standard spellings are aliases themselves, e.g. `std::string_view` is an
+  // alias of `std::basic_string_view<char>`).
+  if (const auto* typedef_type = type.getAs<clang::TypedefType>()) {
+    std::string alias_name =
+        typedef_type->getDecl()->getQualifiedNameAsString();
+    if (alias_name != "std::string_view" &&
+        alias_name != "absl::string_view") {
+      return std::nullopt;
+    }
+  }
+  const clang::CXXRecordDecl* record_decl = type.getAsCXXRecordDecl();
+  if (record_decl == nullptr) return std::nullopt;
+  absl::string_view cc_name;
+  if (const auto* specialization_decl =
+          clang::dyn_cast<clang::ClassTemplateSpecializationDecl>(
+              record_decl);
+      specialization_decl != nullptr &&
+      specialization_decl->isInStdNamespace() &&
+      specialization_decl->getName() == "basic_string_view") {
+    const clang::TemplateArgumentList& args =
+        specialization_decl->getTemplateArgs();
+    if (args.size() == 0 ||
+        args[0].getKind() != clang::TemplateArgument::Type ||
+        !ctx_.hasSameType(args[0].getAsType(), ctx_.CharTy)) {
+      return std::nullopt;
+    }
+    cc_name = internal::kCcStdStringView;
+  } else if (record_decl->getQualifiedNameAsString() == "absl::string_view") {
+    cc_name = internal::kCcAbslStringView;
+  } else {
+    return std::nullopt;
+  }
+
+  std::optional<LifetimeId> lifetime;
+  if (lifetimes != nullptr) {
+    llvm::SmallVector<std::string> lifetime_params =
+        clang::tidy::lifetimes::GetLifetimeParameters(
+            clang::QualType(&type, 0));
+    if (lifetime_params.size() == 1) {
+      lifetime = LifetimeId(
+          lifetimes->GetLifetimeParameter(lifetime_params[0]).Id());
+    }
+  }
+  return MappedType::StringView(cc_name, lifetime);
+}
+
+std::optional<MappedType> Importer::ConvertStdStringType(
+    clang::QualType qual_type, bool is_param) {
+  if (is_param) {
+    if (const auto* reference_type =
+            qual_type->getAs<clang::LValueReferenceType>()) {
+      if (!reference_type->getPointeeType().isConstQualified()) {
+        return std::nullopt;
+      }
+      qual_type = reference_type->getPointeeType();
+    }
+  }
+  const clang::Type& type = *qual_type.getTypePtr();
+  // As for string views, other aliases of `std::string` are preserved.
+  if (const auto* typedef_type = type.getAs<clang::TypedefType>()) {
+    if (typedef_type->getDecl()->getQualifiedNameAsString() != "std::string") {
+      return std::nullopt;
+    }
+  }
+  const auto* specialization_decl =
+      clang::dyn_cast_or_null<clang::ClassTemplateSpecializationDecl>(
+          type.getAsCXXRecordDecl());
+  if (specialization_decl == nullptr ||
+      !specialization_decl->isInStdNamespace() ||
+      specialization_decl->getName() != "basic_string") {
+    return std::nullopt;
+  }
+  // Only `std::basic_string<char>` with the default traits and allocator is
+  // converted.
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() == 0 || args[0].getKind() != clang::TemplateArgument::Type ||
+      !ctx_.hasSameType(args[0].getAsType(), ctx_.CharTy)) {
+    return std::nullopt;
+  }
+  for (unsigned i = 1; i < args.size(); ++i) {
+    absl::string_view expected_name = i == 1 ? "char_traits" : "allocator";
+    const clang::CXXRecordDecl* arg_decl =
+        args[i].getKind() == clang::TemplateArgument::Type
+            ? args[i].getAsType()->getAsCXXRecordDecl()
+            : nullptr;
+    if (arg_decl == nullptr || !arg_decl->isInStdNamespace() ||
+        arg_decl->getName() != expected_name) {
+      return std::nullopt;
+    }
+  }
+  return MappedType::StdString();
+}
+
+std::optional<MappedType> Importer::ConvertSpanType(clang::QualType qual_type) {
+  const clang::Type& type = *qual_type.getTypePtr();
+  // Type aliases of the span types are preserved as aliases.
+  if (type.getAs<clang::TypedefType>() != nullptr ||
+      type.getAs<clang::UsingType>() != nullptr) {
+    return std::nullopt;
+  }
+  const auto* specialization_decl =
+      clang::dyn_cast_or_null<clang::ClassTemplateSpecializationDecl>(
+          type.getAsCXXRecordDecl());
+  if (specialization_decl == nullptr) return std::nullopt;
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() == 0 || args[0].getKind() != clang::TemplateArgument::Type) {
+    return std::nullopt;
+  }
+  absl::string_view cc_name;
+  if (specialization_decl->isInStdNamespace() &&
+      specialization_decl->getName() == "span") {
+    // Only `std::span<T, std::dynamic_extent>` has a pointer and a size.
+    if (args.size() != 2 ||
+        args[1].getKind() != clang::TemplateArgument::Integral ||
+        !args[1].getAsIntegral().isMaxValue()) {
+      return std::nullopt;
+    }
+    cc_name = internal::kCcStdSpan;
+  } else if (specialization_decl->getQualifiedNameAsString() == "absl::Span" &&
+             args.size() == 1) {
+    cc_name = internal::kCcAbslSpan;
+  } else {
+    return std::nullopt;
+  }
+
+  // The Rust slice points directly at the elements of the span, so they must
+  // have the same layout in both languages (which is checked by the bindings
+  // generator for records).
+  clang::QualType element_type = args[0].getAsType();
+  if (element_type.isVolatileQualified() ||
+      !(element_type->isScalarType() || element_type->isRecordType())) {
+    return std::nullopt;
+  }
+  absl::StatusOr<MappedType> mapped_element_type =
+      ConvertQualType(element_type, /*lifetimes=*/nullptr,
+                      /*ref_qualifier_kind=*/std::nullopt);
+  if (!mapped_element_type.ok()) return std::nullopt;
+
+  return MappedType::SpanOf(cc_name, *std::move(mapped_element_type));
+}
+
+std::optional<MappedType> Importer::ConvertStatusType(
+    clang::QualType qual_type) {
+  if (qual_type.hasQualifiers()) return std::nullopt;
+  const clang::Type& type = *qual_type.getTypePtr();
+  // Type aliases of the status types are preserved as aliases.
+  if (type.getAs<clang::TypedefType>() != nullptr ||
+      type.getAs<clang::UsingType>() != nullptr) {
+    return std::nullopt;
+  }
+  const clang::CXXRecordDecl* record_decl = type.getAsCXXRecordDecl();
+  if (record_decl == nullptr) return std::nullopt;
+  std::string name = record_decl->getQualifiedNameAsString();
+  if (name == "absl::Status") return MappedType::AbslStatus();
+  if (name != "absl::StatusOr") return std::nullopt;
+
+  const auto* specialization_decl =
+      clang::dyn_cast<clang::ClassTemplateSpecializationDecl>(record_decl);
+  if (specialization_decl == nullptr) return std::nullopt;
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() != 1 || args[0].getKind() != clang::TemplateArgument::Type) {
+    return std::nullopt;
+  }
+  // The value is moved out of the `absl::StatusOr<T>` by the thunk, into a
+  // return slot owned by Rust (which is checked by the bindings generator for
+  // records).
+  clang::QualType value_type = args[0].getAsType();
+  if (value_type.hasQualifiers() ||
+      !(value_type->isScalarType() || value_type->isRecordType())) {
+    return std::nullopt;
+  }
+  absl::StatusOr<MappedType> mapped_value_type =
+      ConvertQualType(value_type, /*lifetimes=*/nullptr,
+                      /*ref_qualifier_kind=*/std::nullopt);
+  if (!mapped_value_type.ok()) return std::nullopt;
+
+  return MappedType::AbslStatusOf(*std::move(mapped_value_type));
+}
+
+std::optional<MappedType> Importer::ConvertUniquePtrType(
+    clang::QualType qual_type) {
+  if (qual_type.hasQualifiers()) return std::nullopt;
+  const clang::Type& type = *qual_type.getTypePtr();
+  // Type aliases of `std::unique_ptr<T>` are preserved as aliases.
+  if (type.getAs<clang::TypedefType>() != nullptr ||
+      type.getAs<clang::UsingType>() != nullptr) {
+    return std::nullopt;
+  }
+  const auto* specialization_decl =
+      clang::dyn_cast_or_null<clang::ClassTemplateSpecializationDecl>(
+          type.getAsCXXRecordDecl());
+  if (specialization_decl == nullptr ||
+      !specialization_decl->isInStdNamespace() ||
+      specialization_decl->getName() != "unique_ptr") {
+    return std::nullopt;
+  }
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() != 2 || args[0].getKind() != clang::TemplateArgument::Type ||
+      args[1].getKind() != clang::TemplateArgument::Type) {
+    return std::nullopt;
+  }
+  // The pointer is deleted by a thunk calling `delete`, so only the default
+  // deleter is supported (which also rules out custom deleters with state).
+  const clang::CXXRecordDecl* deleter_decl =
+      args[1].getAsType()->getAsCXXRecordDecl();
+  if (deleter_decl == nullptr || !deleter_decl->isInStdNamespace() ||
+      deleter_decl->getName() != "default_delete") {
+    return std::nullopt;
+  }
+  // `std::unique_ptr<T[]>` would have to be deleted with `delete[]`.
+  clang::QualType pointee_type = args[0].getAsType();
+  if (pointee_type.hasQualifiers() ||
+      !(pointee_type->isScalarType() || pointee_type->isRecordType())) {
+    return std::nullopt;
+  }
+  absl::StatusOr<MappedType> mapped_pointee_type =
+      ConvertQualType(pointee_type, /*lifetimes=*/nullptr,
+                      /*ref_qualifier_kind=*/std::nullopt);
+  if (!mapped_pointee_type.ok()) return std::nullopt;
+
+  return MappedType::UniquePtrOf(*std::move(mapped_pointee_type));
+}
+
+std::optional<MappedType> Importer::ConvertSharedPtrType(
+    clang::QualType qual_type) {
+  if (qual_type.hasQualifiers()) return std::nullopt;
+  const clang::Type& type = *qual_type.getTypePtr();
+  // Type aliases of `std::shared_ptr<T>` are preserved as aliases.
+  if (type.getAs<clang::TypedefType>() != nullptr ||
+      type.getAs<clang::UsingType>() != nullptr) {
+    return std::nullopt;
+  }
+  const auto* specialization_decl =
+      clang::dyn_cast_or_null<clang::ClassTemplateSpecializationDecl>(
+          type.getAsCXXRecordDecl());
+  if (specialization_decl == nullptr ||
+      !specialization_decl->isInStdNamespace() ||
+      specialization_decl->getName() != "shared_ptr") {
+    return std::nullopt;
+  }
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() != 1 || args[0].getKind() != clang::TemplateArgument::Type) {
+    return std::nullopt;
+  }
+  // `std::shared_ptr<T[]>` is not supported.
+  clang::QualType pointee_type = args[0].getAsType();
+  if (pointee_type.hasQualifiers() ||
+      !(pointee_type->isScalarType() || pointee_type->isRecordType())) {
+    return std::nullopt;
+  }
+  absl::StatusOr<MappedType> mapped_pointee_type =
+      ConvertQualType(pointee_type, /*lifetimes=*/nullptr,
+                      /*ref_qualifier_kind=*/std::nullopt);
+  if (!mapped_pointee_type.ok()) return std::nullopt;
+
+  return MappedType::SharedPtrOf(*std::move(mapped_pointee_type));
+}
+
+std::optional<MappedType> Importer::ConvertStdFunctionType(
+    clang::QualType qual_type) {
+  if (qual_type.hasQualifiers()) return std::nullopt;
+  const clang::Type& type = *qual_type.getTypePtr();
+  // Type aliases of `std::function<R(Args...)>` are preserved as aliases.
+  if (type.getAs<clang::TypedefType>() != nullptr ||
+      type.getAs<clang::UsingType>() != nullptr) {
+    return std::nullopt;
+  }
+  const auto* specialization_decl =
+      clang::dyn_cast_or_null<clang::ClassTemplateSpecializationDecl>(
+          type.getAsCXXRecordDecl());
+  if (specialization_decl == nullptr ||
+      !specialization_decl->isInStdNamespace() ||
+      specialization_decl->getName() != "function") {
+    return std::nullopt;
+  }
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() != 1 || args[0].getKind() != clang::TemplateArgument::Type) {
+    return std::nullopt;
+  }
+  const auto* func_type =
+      args[0].getAsType()->getAs<clang::FunctionProtoType>();
+  // C-style variadic functions can't be called from Rust closures.
+  if (func_type == nullptr || func_type->isVariadic()) return std::nullopt;
+
+  absl::StatusOr<MappedType> mapped_return_type =
+      ConvertQualType(func_type->getReturnType(), /*lifetimes=*/nullptr,
+                      /*ref_qualifier_kind=*/std::nullopt);
+  if (!mapped_return_type.ok()) return std::nullopt;
+  std::vector<MappedType> mapped_param_types;
+  for (clang::QualType param_type : func_type->getParamTypes()) {
+    absl::StatusOr<MappedType> mapped_param_type =
+        ConvertQualType(param_type, /*lifetimes=*/nullptr,
+                        /*ref_qualifier_kind=*/std::nullopt);
+    if (!mapped_param_type.ok()) return std::nullopt;
+    mapped_param_types.push_back(*std::move(mapped_param_type));
+  }
+
+  return MappedType::StdFunctionOf(*std::move(mapped_return_type),
+                                   std::move(mapped_param_types));
+}
+
 absl::StatusOr<MappedType> Importer::ConvertTypeDecl(clang::NamedDecl* decl) {
   if (!EnsureSuccessfullyImported(decl)) {
     return absl::NotFoundError(absl::Substitute(
@@ -1100,6 +1658,33 @@ absl::StatusOr<MappedType> Importer::ConvertUnattributedType(
       default:
         return absl::UnimplementedError("Unsupported builtin type");
     }
+  } else if (const auto* array_type =
+                 llvm::dyn_cast<clang::ConstantArrayType>(type)) {
+    // Like for `std::array`, only scalar element types (arithmetic types, enums
+    // and pointers) are guaranteed to be laid out identically to the
+    // corresponding Rust type.
+    clang::QualType element_type = array_type->getElementType();
+    if (!element_type->isScalarType()) {
+      return absl::UnimplementedError(
+          "Only arrays of arithmetic types, enums and pointers are supported");
+    }
+    CRUBIT_ASSIGN_OR_RETURN(
+        MappedType mapped_element_type,
+        ConvertQualType(element_type, /*lifetimes=*/nullptr,
+                        /*ref_qualifier_kind=*/std::nullopt));
+    return MappedType::ArrayOf(std::move(mapped_element_type),
+                               array_type->getSize().getZExtValue());
+  } else if (std::optional<MappedType> array_type = ConvertStdArrayType(*type);
+             array_type.has_value()) {
+    return *std::move(array_type);
+  } else if (std::optional<MappedType> optional_type =
+                 ConvertStdOptionalType(*type);
+             optional_type.has_value()) {
+    return *std::move(optional_type);
+  } else if (std::optional<MappedType> initializer_list_type =
+                 ConvertInitializerListType(*type);
+             initializer_list_type.has_value()) {
+    return *std::move(initializer_list_type);
   } else if (const auto* tag_type = type->getAsAdjusted<clang::TagType>()) {
     return ConvertTypeDecl(tag_type->getDecl());
   } else if (const auto* typedef_type =
@@ -9,6 +9,7 @@
 #include <optional>
 #include <set>
 #include <string>
+#include <utility>
 #include <variant>
 #include <vector>
 
@@ -27,6 +28,7 @@
 #include "rs_bindings_from_cc/importers/friend.h"
 #include "rs_bindings_from_cc/importers/function.h"
 #include "rs_bindings_from_cc/importers/function_template.h"
+#include "rs_bindings_from_cc/importers/global_var.h"
 #include "rs_bindings_from_cc/importers/namespace.h"
 #include "rs_bindings_from_cc/importers/type_alias.h"
 #include "rs_bindings_from_cc/importers/type_map_override.h"
@@ -37,6 +39,7 @@
 #include "clang/AST/RawCommentList.h"
 #include "clang/AST/Type.h"
 #include "clang/Basic/SourceLocation.h"
+#include "clang/Lex/MacroInfo.h"
 #include "clang/Sema/Sema.h"
 
 namespace crubit {
@@ -59,6 +62,7 @@ class Importer final : public ImportContext {
     decl_importers_.push_back(std::make_unique<FunctionDeclImporter>(*this));
     decl_importers_.push_back(
         std::make_unique<FunctionTemplateDeclImporter>(*this));
+    decl_importers_.push_back(std::make_unique<GlobalVarImporter>(*this));
     decl_importers_.push_back(std::make_unique<NamespaceDeclImporter>(*this));
     decl_importers_.push_back(std::make_unique<TypeAliasImporter>(*this));
   }
@@ -108,6 +112,20 @@ class Importer final : public ImportContext {
       const clang::tidy::lifetimes::ValueLifetimes* lifetimes,
       std::optional<clang::RefQualifierKind> ref_qualifier_kind,
       bool nullable = true) override;
+  std::optional<MappedType> ConvertStringViewType(
+      clang::QualType qual_type,
+      const clang::tidy::lifetimes::ValueLifetimes* lifetimes) override;
+  std::optional<MappedType> ConvertStdStringType(clang::QualType qual_type,
+                                                 bool is_param) override;
+  std::optional<MappedType> ConvertSpanType(clang::QualType qual_type) override;
+  std::optional<MappedType> ConvertStatusType(
+      clang::QualType qual_type) override;
+  std::optional<MappedType> ConvertUniquePtrType(
+      clang::QualType qual_type) override;
+  std::optional<MappedType> ConvertSharedPtrType(
+      clang::QualType qual_type) override;
+  std::optional<MappedType> ConvertStdFunctionType(
+      clang::QualType qual_type) override;
 
   void MarkAsSuccessfullyImported(const clang::NamedDecl* decl) override;
   bool HasBeenAlreadySuccessfullyImported(
@@ -129,6 +147,9 @@ class Importer final : public ImportContext {
   // Returns a SourceOrderKey for the given `comment` that should be used for
   // ordering Items.
   SourceOrderKey GetSourceOrderKey(const clang::RawComment* comment) const;
+  // Returns a SourceOrderKey for the given `macro_info` that should be used for
+  // ordering Items.
+  SourceOrderKey GetSourceOrderKey(const clang::MacroInfo* macro_info) const;
 
   // Returns a name for `decl` that should be used for ordering declarations.
   std::string GetNameForSourceOrder(const clang::Decl* decl) const;
@@ -141,6 +162,12 @@ class Importer final : public ImportContext {
   std::optional<IR::Item> GetDeclItem(clang::Decl* decl) override;
   // Stores the comments of this target in source order.
   void ImportFreeComments();
+  // Stores the constants for the object-like macros of this target whose value
+  // is an integer literal (e.g. `#define kMaxSize 42`).
+  void ImportMacroConstants();
+
+  // Returns the target that owns the header containing `loc`.
+  BazelLabel GetOwningTarget(clang::SourceLocation loc) const;
 
   clang::Decl* CanonicalizeDecl(clang::Decl* decl) const;
   const clang::Decl* CanonicalizeDecl(const clang::Decl* decl) const;
@@ -169,6 +196,24 @@ class Importer final : public ImportContext {
   absl::StatusOr<MappedType> ConvertTemplateSpecializationType(
       const clang::TemplateSpecializationType* type);
 
+  // Converts `type` into a Rust array, if it is a `std::array<T, N>` whose
+  // element type `T` can be passed by value across the C ABI. Returns
+  // `std::nullopt` otherwise, in which case the type should be converted like
+  // any other class template specialization.
+  std::optional<MappedType> ConvertStdArrayType(const clang::Type& type);
+
+  // Converts `type` into a Rust `Option`, if it is a `std::optional<T>` whose
+  // value type `T` is trivially copyable. Returns `std::nullopt` otherwise, in
+  // which case the type should be converted like any other class template
+  // specialization.
+  std::optional<MappedType> ConvertStdOptionalType(const clang::Type& type);
+
+  // Converts `type` into a Rust slice, if it is a `std::initializer_list<T>`
+  // whose element type `T` is trivially copyable. Returns `std::nullopt`
+  // otherwise.
+  std::optional<MappedType> ConvertInitializerListType(
+      const clang::Type& type);
+
   // The different decl importers. Note that order matters: the first importer
   // to successfully match a decl "wins", and no other importers are tried.
   std::vector<std::unique_ptr<DeclImporter>> decl_importers_;
@@ -178,6 +223,7 @@ class Importer final : public ImportContext {
   absl::flat_hash_set<const clang::ClassTemplateSpecializationDecl*>
       class_template_instantiations_;
   std::vector<const clang::RawComment*> comments_;
+  std::vector<std::pair<const clang::MacroInfo*, GlobalVar>> macro_constants_;
 
   // Set of decls that have been successfully imported (i.e. that will be
   // present in the IR output / that will not produce dangling ItemIds in the IR
@@ -11,6 +11,7 @@
 #include "gtest/gtest.h"
 #include "absl/status/status.h"
 #include "absl/strings/match.h"
+#include "absl/strings/str_cat.h"
 #include "absl/strings/string_view.h"
 #include "common/status_test_matchers.h"
 #include "rs_bindings_from_cc/bazel_types.h"
@@ -408,6 +409,62 @@ TEST(ImporterTest, TestImportReferenceFunc) {
                   ReturnType(IsIntRef()), ParamsAre(ParamType(IsIntRef()))))));
 }
 
+TEST(ImporterTest, LifetimeboundIsNotAnUnknownAttr) {
+  ASSERT_OK_AND_ASSIGN(
+      IR ir, IrFromCc({"int& Foo(int& a [[clang::lifetimebound]], int* b "
+                       "[[clang::noescape]]);"}));
+
+  EXPECT_THAT(ItemsWithoutBuiltins(ir),
+              UnorderedElementsAre(VariantWith<Func>(ParamsAre(
+                  testing::Field("unknown_attr", &FuncParam::unknown_attr,
+                                 testing::Eq(std::nullopt)),
+                  testing::Field("unknown_attr", &FuncParam::unknown_attr,
+                                 testing::Optional(HasSubstr("noescape")))))));
+}
+
+// A minimal `std::string_view`, so that the tests don't depend on the standard
+// library.
+constexpr absl::string_view kStringViewForTest = R"cc(
+  namespace std {
+  template <typename CharT>
+  class basic_string_view {
+   public:
+    basic_string_view(const CharT* data, decltype(sizeof(0)) size);
+
+   private:
+    const CharT* data_;
+    decltype(sizeof(0)) size_;
+  };
+  using string_view = basic_string_view<char>;
+  }  // namespace std
+)cc";
+
+TEST(ImporterTest, StringViewParamsAreNotConvertedByDefault) {
+  std::string file = absl::StrCat(kStringViewForTest,
+                                  "std::string_view Foo(std::string_view s);");
+  ASSERT_OK_AND_ASSIGN(IR ir, IrFromCc({file}));
+
+  EXPECT_THAT(ir.items, Contains(VariantWith<Func>(AllOf(
+                            IdentifierIs("Foo"),
+                            ReturnType(CcTypeIs(Not(NameIs("#stdStringView")))),
+                            ParamsAre(ParamType(
+                                CcTypeIs(Not(NameIs("#stdStringView")))))))));
+}
+
+TEST(ImporterTest, ConvertStringViews) {
+  std::string file = absl::StrCat(kStringViewForTest,
+                                  "std::string_view Foo(std::string_view s);");
+  ASSERT_OK_AND_ASSIGN(
+      IR ir, IrFromCc({.extra_source_code_for_testing = file,
+                       .convert_string_views = true}));
+
+  auto is_string_view = AllOf(CcTypeIs(NameIs("#stdStringView")),
+                              RsTypeIs(NameIs("#stringView")));
+  EXPECT_THAT(ir.items, Contains(VariantWith<Func>(AllOf(
+                            IdentifierIs("Foo"), ReturnType(is_string_view),
+                            ParamsAre(ParamType(is_string_view))))));
+}
+
 TEST(ImporterTest, TrivialCopyConstructor) {
   absl::string_view file = R"cc(
     struct Implicit {};
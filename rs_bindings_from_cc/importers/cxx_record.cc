@@ -6,6 +6,7 @@
 
 #include <algorithm>
 #include <cstdint>
+#include <memory>
 #include <optional>
 #include <string>
 #include <utility>
@@ -18,8 +19,11 @@
 #include "absl/log/die_if_null.h"
 #include "absl/log/log.h"
 #include "absl/status/status.h"
+#include "absl/status/statusor.h"
 #include "absl/strings/str_cat.h"
 #include "absl/strings/string_view.h"
+#include "absl/strings/strip.h"
+#include "common/status_macros.h"
 #include "lifetime_annotations/type_lifetimes.h"
 #include "rs_bindings_from_cc/ast_convert.h"
 #include "rs_bindings_from_cc/ast_util.h"
@@ -34,10 +38,16 @@
 #include "clang/AST/PrettyPrinter.h"
 #include "clang/AST/RecordLayout.h"
 #include "clang/AST/Type.h"
+#include "clang/Basic/FileManager.h"
 #include "clang/Basic/LLVM.h"
+#include "clang/Basic/SourceManager.h"
 #include "clang/Basic/Specifiers.h"
+#include "clang/Lex/Lexer.h"
 #include "clang/Sema/Sema.h"
+#include "llvm/ADT/SmallString.h"
 #include "llvm/Support/ErrorHandling.h"
+#include "llvm/Support/MemoryBuffer.h"
+#include "llvm/Support/Path.h"
 namespace crubit {
 
 namespace {
@@ -121,6 +131,122 @@ absl::StatusOr<RecordType> TranslateRecordType(
   llvm::report_fatal_error("Unrecognized clang::TagKind");
 }
 
+// Gets the crubit_awaitable attribute for `decl`.
+//
+// The attribute takes two string literal arguments, `"poll=<method>"` and
+// `"get=<method>"`, naming the member functions used to drive the awaitable.
+absl::StatusOr<std::optional<Awaitable>> GetAwaitableAttribute(
+    const clang::Decl* decl) {
+  CRUBIT_ASSIGN_OR_RETURN(const clang::AnnotateAttr* attr,
+                          GetAnnotateAttr(decl, "crubit_awaitable"));
+  if (attr == nullptr) return std::nullopt;
+  if (attr->args_size() != 2)
+    return absl::InvalidArgumentError(
+        "The `crubit_awaitable` attribute requires two string literal "
+        "arguments, `poll=<method>` and `get=<method>`.");
+  Awaitable awaitable;
+  for (const clang::Expr* arg : attr->args()) {
+    CRUBIT_ASSIGN_OR_RETURN(
+        absl::string_view value,
+        EvaluateAsStringLiteral(*arg, decl->getASTContext()));
+    if (absl::ConsumePrefix(&value, "poll=")) {
+      awaitable.poll = std::string(value);
+    } else if (absl::ConsumePrefix(&value, "get=")) {
+      awaitable.get = std::string(value);
+    } else {
+      return absl::InvalidArgumentError(absl::StrCat(
+          "Unexpected `crubit_awaitable` argument: \"", value, "\""));
+    }
+  }
+  if (awaitable.poll.empty() || awaitable.get.empty())
+    return absl::InvalidArgumentError(
+        "The `crubit_awaitable` attribute requires both a `poll=<method>` and "
+        "a `get=<method>` argument.");
+  return awaitable;
+}
+
+// Gets the crubit_extra_rust_impl attribute for `decl`, and returns the
+// contents of the Rust file that it points at.
+//
+// The attribute takes a single string literal argument, the path of the Rust
+// file. Relative paths are resolved against the directory of the header which
+// declares `decl`.
+absl::StatusOr<std::optional<std::string>> GetExtraRustImplAttribute(
+    const clang::Decl* decl) {
+  CRUBIT_ASSIGN_OR_RETURN(const clang::AnnotateAttr* attr,
+                          GetAnnotateAttr(decl, "crubit_extra_rust_impl"));
+  if (attr == nullptr) return std::nullopt;
+  if (attr->args_size() != 1)
+    return absl::InvalidArgumentError(
+        "The `crubit_extra_rust_impl` attribute requires a single string "
+        "literal argument, the path of the Rust file.");
+  CRUBIT_ASSIGN_OR_RETURN(
+      absl::string_view path,
+      EvaluateAsStringLiteral(**attr->args_begin(), decl->getASTContext()));
+  const clang::SourceManager& sm = decl->getASTContext().getSourceManager();
+  llvm::SmallString<256> full_path;
+  if (llvm::sys::path::is_relative(path)) {
+    full_path = llvm::sys::path::parent_path(
+        sm.getFilename(sm.getExpansionLoc(decl->getLocation())));
+  }
+  llvm::sys::path::append(full_path, path);
+  llvm::ErrorOr<std::unique_ptr<llvm::MemoryBuffer>> buffer =
+      sm.getFileManager().getBufferForFile(full_path);
+  if (!buffer)
+    return absl::InvalidArgumentError(
+        absl::StrCat("Couldn't read \"", std::string(full_path.str()),
+                     "\": ", buffer.getError().message()));
+  return std::string((*buffer)->getBuffer());
+}
+
+// Gets the crubit_opaque attribute for `decl`.
+// If the attribute is specified, returns true. If it's unspecified, returns
+// false. If the attribute is malformed, returns a bad status.
+absl::StatusOr<bool> GetIsOpaqueAttribute(const clang::Decl* decl) {
+  CRUBIT_ASSIGN_OR_RETURN(const clang::AnnotateAttr* attr,
+                          GetAnnotateAttr(decl, "crubit_opaque"));
+  if (attr != nullptr && attr->args_size() != 0)
+    return absl::InvalidArgumentError(
+        "The `crubit_opaque` attribute takes no arguments.");
+  return attr != nullptr;
+}
+
+// Declares the constructors that `record_decl` inherits from its bases via
+// `using Base::Base;`.
+//
+// Clang only declares inherited constructors lazily, when they are used, so
+// without this they wouldn't be children of `record_decl` and wouldn't get
+// imported as constructors of the derived record.
+void DeclareInheritedConstructors(clang::Sema& sema,
+                                  clang::CXXRecordDecl* record_decl) {
+  // `findInheritingConstructor` adds declarations to `record_decl`, so the
+  // shadow declarations have to be collected first.
+  std::vector<clang::ConstructorUsingShadowDecl*> shadow_decls;
+  for (clang::Decl* decl : record_decl->decls()) {
+    if (auto* shadow_decl =
+            clang::dyn_cast<clang::ConstructorUsingShadowDecl>(decl)) {
+      shadow_decls.push_back(shadow_decl);
+    }
+  }
+  for (clang::ConstructorUsingShadowDecl* shadow_decl : shadow_decls) {
+    // TODO: Support inheriting constructor templates.
+    auto* base_ctor = clang::dyn_cast<clang::CXXConstructorDecl>(
+        shadow_decl->getTargetDecl());
+    if (base_ctor == nullptr || base_ctor->isDeleted() ||
+        base_ctor->getAccess() != clang::AS_public) {
+      continue;
+    }
+    // Default, copy and move constructors are not inherited - the derived
+    // class has its own.
+    if (base_ctor->getNumParams() == 0 ||
+        base_ctor->isCopyOrMoveConstructor()) {
+      continue;
+    }
+    sema.findInheritingConstructor(shadow_decl->getLocation(), base_ctor,
+                                   shadow_decl);
+  }
+}
+
 }  // namespace
 
 std::optional<Identifier> CXXRecordDeclImporter::GetTranslatedFieldName(
@@ -144,6 +270,95 @@ std::optional<Identifier> CXXRecordDeclImporter::GetTranslatedFieldName(
   return *name;
 }
 
+absl::StatusOr<std::optional<MapVisitor>> CXXRecordDeclImporter::GetMapVisitor(
+    const clang::CXXRecordDecl* record_decl) {
+  CRUBIT_ASSIGN_OR_RETURN(const clang::AnnotateAttr* attr,
+                          GetAnnotateAttr(record_decl, "crubit_map_visitor"));
+  if (attr == nullptr) return std::nullopt;
+  if (attr->args_size() != 2)
+    return absl::InvalidArgumentError(
+        "The `crubit_map_visitor` attribute requires two string literal "
+        "arguments, `begin=<method>` and `end=<method>`.");
+  MapVisitor map_visitor;
+  for (const clang::Expr* arg : attr->args()) {
+    CRUBIT_ASSIGN_OR_RETURN(absl::string_view value,
+                            EvaluateAsStringLiteral(*arg, ictx_.ctx_));
+    if (absl::ConsumePrefix(&value, "begin=")) {
+      map_visitor.begin = std::string(value);
+    } else if (absl::ConsumePrefix(&value, "end=")) {
+      map_visitor.end = std::string(value);
+    } else {
+      return absl::InvalidArgumentError(absl::StrCat(
+          "Unexpected `crubit_map_visitor` argument: \"", value, "\""));
+    }
+  }
+  if (map_visitor.begin.empty() || map_visitor.end.empty())
+    return absl::InvalidArgumentError(
+        "The `crubit_map_visitor` attribute requires both a `begin=<method>` "
+        "and an `end=<method>` argument.");
+
+  for (const std::string& method : {map_visitor.begin, map_visitor.end}) {
+    auto lookup_result = record_decl->lookup(&ictx_.ctx_.Idents.get(method));
+    if (lookup_result.find_first<clang::CXXMethodDecl>() == nullptr)
+      return absl::InvalidArgumentError(
+          absl::StrCat("`", method, "` is not a member function of `",
+                       record_decl->getNameAsString(), "`."));
+  }
+  for (auto [name, mapped_type] :
+       {std::pair("key_type", &map_visitor.key_type),
+        std::pair("mapped_type", &map_visitor.mapped_type)}) {
+    auto lookup_result = record_decl->lookup(&ictx_.ctx_.Idents.get(name));
+    const auto* typedef_decl =
+        lookup_result.find_first<clang::TypedefNameDecl>();
+    if (typedef_decl == nullptr)
+      return absl::InvalidArgumentError(
+          absl::StrCat("`", record_decl->getNameAsString(),
+                       "` doesn't declare the member type `", name, "`."));
+    const clang::tidy::lifetimes::ValueLifetimes* no_lifetimes = nullptr;
+    absl::StatusOr<MappedType> type = ictx_.ConvertQualType(
+        typedef_decl->getUnderlyingType(), no_lifetimes, std::nullopt);
+    if (!type.ok())
+      return absl::InvalidArgumentError(absl::StrCat(
+          "Unsupported `", name, "`: ", type.status().message()));
+    *mapped_type = *std::move(type);
+  }
+  return map_visitor;
+}
+
+std::optional<MappedType> CXXRecordDeclImporter::GetStdVectorElementType(
+    const clang::CXXRecordDecl* record_decl) {
+  const auto* specialization_decl =
+      clang::dyn_cast<clang::ClassTemplateSpecializationDecl>(record_decl);
+  if (specialization_decl == nullptr ||
+      !specialization_decl->isInStdNamespace() ||
+      specialization_decl->getName() != "vector") {
+    return std::nullopt;
+  }
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() == 0 || args[0].getKind() != clang::TemplateArgument::Type) {
+    return std::nullopt;
+  }
+  clang::QualType element_type = args[0].getAsType();
+  // `std::vector<bool>` doesn't store its elements contiguously.
+  if (element_type->isBooleanType()) return std::nullopt;
+  if (args.size() > 1) {
+    const clang::CXXRecordDecl* allocator_decl =
+        args[1].getKind() == clang::TemplateArgument::Type
+            ? args[1].getAsType()->getAsCXXRecordDecl()
+            : nullptr;
+    if (allocator_decl == nullptr || !allocator_decl->isInStdNamespace() ||
+        allocator_decl->getName() != "allocator") {
+      return std::nullopt;
+    }
+  }
+  const clang::tidy::lifetimes::ValueLifetimes* no_lifetimes = nullptr;
+  absl::StatusOr<MappedType> type =
+      ictx_.ConvertQualType(element_type, no_lifetimes, std::nullopt);
+  if (!type.ok()) return std::nullopt;
+  return *std::move(type);
+}
+
 std::optional<IR::Item> CXXRecordDeclImporter::Import(
     clang::CXXRecordDecl* record_decl) {
   const clang::DeclContext* decl_context = record_decl->getDeclContext();
@@ -165,10 +380,6 @@ std::optional<IR::Item> CXXRecordDeclImporter::Import(
   if (record_decl->isImplicit()) {
     return std::nullopt;
   }
-  if (decl_context->isRecord()) {
-    return ictx_.ImportUnsupportedItem(record_decl,
-                                       "Nested classes are not supported yet");
-  }
   if (clang::isa<clang::ClassTemplatePartialSpecializationDecl>(record_decl)) {
     return ictx_.ImportUnsupportedItem(
         record_decl, "Partially-specialized class templates are not supported");
@@ -210,6 +421,14 @@ std::optional<IR::Item> CXXRecordDeclImporter::Import(
           return true;
         } else if (clang::isa<clang::TrivialABIAttr>(attr)) {
           return true;
+        } else if (auto* annotate = clang::dyn_cast<clang::AnnotateAttr>(&attr);
+                   annotate &&
+                   (annotate->getAnnotation() == "crubit_awaitable" ||
+                    annotate->getAnnotation() == "crubit_extra_rust_impl" ||
+                    annotate->getAnnotation() == "crubit_map_visitor" ||
+                    annotate->getAnnotation() == "crubit_opaque" ||
+                    annotate->getAnnotation() == "crubit_renamed_from")) {
+          return true;
         } else if (auto* visibility =
                        clang::dyn_cast<clang::VisibilityAttr>(&attr);
                    visibility && record_decl->isInStdNamespace()) {
@@ -228,6 +447,45 @@ std::optional<IR::Item> CXXRecordDeclImporter::Import(
     return attr_error_item;
   }
 
+  absl::StatusOr<std::optional<Awaitable>> awaitable =
+      GetAwaitableAttribute(record_decl);
+  if (!awaitable.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        record_decl, absl::StrCat("Invalid crubit_awaitable attribute: ",
+                                  awaitable.status().message()));
+  }
+
+  absl::StatusOr<std::optional<MapVisitor>> map_visitor =
+      GetMapVisitor(record_decl);
+  if (!map_visitor.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        record_decl, absl::StrCat("Invalid crubit_map_visitor attribute: ",
+                                  map_visitor.status().message()));
+  }
+
+  absl::StatusOr<std::optional<std::string>> extra_rust_impl =
+      GetExtraRustImplAttribute(record_decl);
+  if (!extra_rust_impl.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        record_decl, absl::StrCat("Invalid crubit_extra_rust_impl attribute: ",
+                                  extra_rust_impl.status().message()));
+  }
+
+  absl::StatusOr<bool> is_opaque = GetIsOpaqueAttribute(record_decl);
+  if (!is_opaque.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        record_decl, absl::StrCat("Invalid crubit_opaque attribute: ",
+                                  is_opaque.status().message()));
+  }
+
+  absl::StatusOr<std::optional<std::string>> renamed_from =
+      GetRenamedFromAttribute(record_decl);
+  if (!renamed_from.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        record_decl, absl::StrCat("Invalid crubit_renamed_from attribute: ",
+                                  renamed_from.status().message()));
+  }
+
   std::string rs_name, cc_name, preferred_cc_name;
   clang::SourceLocation source_loc;
   std::optional<std::string> doc_comment;
@@ -310,6 +568,13 @@ std::optional<IR::Item> CXXRecordDeclImporter::Import(
   }
 
   ictx_.sema_.ForceDeclarationOfImplicitMembers(record_decl);
+  // Inherited constructors are only supported for trivially relocatable
+  // types, which can be constructed by value on the Rust side.
+  //
+  // TODO: Support inherited constructors of non-Unpin types.
+  if (record_decl->canPassInRegisters()) {
+    DeclareInheritedConstructors(ictx_.sema_, record_decl);
+  }
 
   const clang::ASTRecordLayout& layout =
       ictx_.ctx_.getASTRecordLayout(record_decl);
@@ -351,7 +616,14 @@ std::optional<IR::Item> CXXRecordDeclImporter::Import(
       .is_abstract = record_decl->isAbstract(),
       .record_type = *record_type,
       .is_aggregate = record_decl->isAggregate(),
+      .has_mutable_fields = record_decl->hasMutableFields(),
       .is_anon_record_with_typedef = anon_typedef != nullptr,
+      .awaitable = *std::move(awaitable),
+      .map_visitor = *std::move(map_visitor),
+      .std_vector_element_type = GetStdVectorElementType(record_decl),
+      .extra_rust_impl = *std::move(extra_rust_impl),
+      .is_opaque = *is_opaque,
+      .renamed_from = *std::move(renamed_from),
       .is_explicit_class_template_instantiation_definition =
           is_explicit_class_template_instantiation_definition,
       .child_item_ids = std::move(item_ids),
@@ -441,6 +713,18 @@ std::vector<Field> CXXRecordDeclImporter::ImportFields(
       }
     }
 
+    // The initializer is only used for documentation and to decide whether
+    // the record can be default-constructed, so it is kept as source text.
+    std::optional<std::string> default_value;
+    if (const clang::Expr* init = field_decl->getInClassInitializer()) {
+      llvm::StringRef text = clang::Lexer::getSourceText(
+          clang::CharSourceRange::getTokenRange(init->getSourceRange()),
+          ictx_.ctx_.getSourceManager(), ictx_.ctx_.getLangOpts());
+      if (!text.empty()) {
+        default_value = text.str();
+      }
+    }
+
     uint64_t size;
     if (field_decl->isZeroSize(ictx_.ctx_)) {
       size = 0;
@@ -450,6 +734,15 @@ std::vector<Field> CXXRecordDeclImporter::ImportFields(
       size = ictx_.ctx_.getTypeSize(field_decl->getType());
     }
 
+    // The members of an anonymous struct or union are imported along with
+    // it, since the anonymous record itself doesn't get bindings.
+    std::vector<Field> anonymous_members;
+    bool is_anonymous_union = false;
+    if (field_decl->isAnonymousStructOrUnion() && field_record != nullptr) {
+      anonymous_members = ImportFields(field_record);
+      is_anonymous_union = field_record->isUnion();
+    }
+
     fields.push_back(
         {.identifier = GetTranslatedFieldName(field_decl),
          .doc_comment = ictx_.GetComment(field_decl),
@@ -461,7 +754,10 @@ std::vector<Field> CXXRecordDeclImporter::ImportFields(
          .is_no_unique_address =
              field_decl->hasAttr<clang::NoUniqueAddressAttr>(),
          .is_bitfield = field_decl->isBitField(),
-         .is_inheritable = is_inheritable});
+         .is_inheritable = is_inheritable,
+         .default_value = std::move(default_value),
+         .anonymous_members = std::move(anonymous_members),
+         .is_anonymous_union = is_anonymous_union});
   }
   return fields;
 }
@@ -8,6 +8,7 @@
 #include <optional>
 #include <vector>
 
+#include "absl/status/statusor.h"
 #include "rs_bindings_from_cc/decl_importer.h"
 #include "rs_bindings_from_cc/ir.h"
 #include "clang/AST/Decl.h"
@@ -28,6 +29,14 @@ class CXXRecordDeclImporter : public DeclImporterBase<clang::CXXRecordDecl> {
       const clang::CXXRecordDecl& record_decl) const;
   std::optional<Identifier> GetTranslatedFieldName(
       const clang::FieldDecl* field);
+  // Gets the crubit_map_visitor attribute for `record_decl`, and imports the
+  // `key_type` and `mapped_type` member types that it requires.
+  absl::StatusOr<std::optional<MapVisitor>> GetMapVisitor(
+      const clang::CXXRecordDecl* record_decl);
+  // Returns the element type of `record_decl`, if it is an instantiation of
+  // `std::vector` whose elements can be exposed as a Rust slice.
+  std::optional<MappedType> GetStdVectorElementType(
+      const clang::CXXRecordDecl* record_decl);
 };
 
 }  // namespace crubit
@@ -9,16 +9,34 @@
 #include <vector>
 
 #include "absl/algorithm/container.h"
+#include "absl/status/status.h"
 #include "absl/status/statusor.h"
 #include "absl/strings/str_cat.h"
+#include "common/status_macros.h"
 #include "lifetime_annotations/type_lifetimes.h"
 #include "rs_bindings_from_cc/ast_util.h"
 #include "rs_bindings_from_cc/ir.h"
+#include "clang/AST/Attr.h"
 #include "clang/AST/Decl.h"
 #include "clang/AST/Type.h"
 #include "clang/Basic/LLVM.h"
 
 namespace crubit {
+namespace {
+
+// Gets the crubit_rust_enum attribute for `decl`.
+// If the attribute is specified, returns true. If it's unspecified, returns
+// false. If the attribute is malformed, returns a bad status.
+absl::StatusOr<bool> GetIsRustEnumAttribute(const clang::Decl* decl) {
+  CRUBIT_ASSIGN_OR_RETURN(const clang::AnnotateAttr* attr,
+                          GetAnnotateAttr(decl, "crubit_rust_enum"));
+  if (attr != nullptr && attr->args_size() != 0)
+    return absl::InvalidArgumentError(
+        "The `crubit_rust_enum` attribute takes no arguments.");
+  return attr != nullptr;
+}
+
+}  // namespace
 
 std::optional<IR::Item> EnumDeclImporter::Import(clang::EnumDecl* enum_decl) {
   if (enum_decl->getName().empty()) {
@@ -48,6 +66,20 @@ std::optional<IR::Item> EnumDeclImporter::Import(clang::EnumDecl* enum_decl) {
         enum_decl,
         "Forward declared enums without type specifiers are not supported");
   }
+  absl::StatusOr<bool> is_rust_enum = GetIsRustEnumAttribute(enum_decl);
+  if (!is_rust_enum.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        enum_decl, absl::StrCat("Invalid crubit_rust_enum attribute: ",
+                                is_rust_enum.status().message()));
+  }
+  absl::StatusOr<std::optional<std::string>> renamed_from =
+      GetRenamedFromAttribute(enum_decl);
+  if (!renamed_from.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        enum_decl, absl::StrCat("Invalid crubit_renamed_from attribute: ",
+                                renamed_from.status().message()));
+  }
+
   const clang::tidy::lifetimes::ValueLifetimes* no_lifetimes = nullptr;
   absl::StatusOr<MappedType> type =
       ictx_.ConvertQualType(cc_type, no_lifetimes, std::nullopt);
@@ -90,7 +122,16 @@ std::optional<IR::Item> EnumDeclImporter::Import(clang::EnumDecl* enum_decl) {
       .enumerators = enum_decl->isCompleteDefinition()
                          ? std::make_optional(std::move(enumerators))
                          : std::nullopt,
-      .unknown_attr = CollectUnknownAttrs(*enum_decl),
+      .unknown_attr = CollectUnknownAttrs(
+          *enum_decl,
+          [](const clang::Attr& attr) {
+            auto* annotate = clang::dyn_cast<clang::AnnotateAttr>(&attr);
+            return annotate &&
+                   (annotate->getAnnotation() == "crubit_rust_enum" ||
+                    annotate->getAnnotation() == "crubit_renamed_from");
+          }),
+      .is_rust_enum = *is_rust_enum,
+      .renamed_from = *std::move(renamed_from),
       .enclosing_item_id = *std::move(enclosing_item_id),
   };
 }
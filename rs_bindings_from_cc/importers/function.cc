@@ -12,10 +12,16 @@
 #include <utility>
 #include <vector>
 
+#include "absl/algorithm/container.h"
 #include "absl/log/check.h"
+#include "absl/status/status.h"
 #include "absl/status/statusor.h"
+#include "absl/strings/ascii.h"
 #include "absl/strings/str_cat.h"
+#include "absl/strings/string_view.h"
+#include "absl/strings/strip.h"
 #include "absl/strings/substitute.h"
+#include "common/status_macros.h"
 #include "lifetime_annotations/lifetime.h"
 #include "lifetime_annotations/lifetime_annotations.h"
 #include "lifetime_annotations/lifetime_error.h"
@@ -25,6 +31,7 @@
 #include "rs_bindings_from_cc/bazel_types.h"
 #include "rs_bindings_from_cc/ir.h"
 #include "rs_bindings_from_cc/recording_diagnostic_consumer.h"
+#include "clang/AST/ASTContext.h"
 #include "clang/AST/Attr.h"
 #include "clang/AST/Attrs.inc"
 #include "clang/AST/DeclarationName.h"
@@ -32,6 +39,7 @@
 #include "clang/Basic/Diagnostic.h"
 #include "clang/Basic/LLVM.h"
 #include "clang/Basic/Specifiers.h"
+#include "clang/Lex/Lexer.h"
 #include "clang/Sema/Sema.h"
 #include "llvm/ADT/STLExtras.h"
 #include "llvm/ADT/StringRef.h"
@@ -50,6 +58,163 @@ static bool IsInStdNamespace(const clang::FunctionDecl* decl) {
   return false;
 }
 
+// Returns the return type of `decl`, with the top-level `auto` or
+// `decltype(auto)` placeholder replaced by the deduced type (e.g. the return
+// type of `const auto f() { return 42; }` is imported as `const int`). The
+// return type must already have been deduced (e.g. via
+// `Sema::DeduceReturnType`).
+static clang::QualType GetDeducedReturnType(const clang::FunctionDecl& decl,
+                                            clang::ASTContext& ctx) {
+  clang::QualType return_type = decl.getReturnType();
+  const auto* deduced_type = return_type->getAs<clang::DeducedType>();
+  if (deduced_type == nullptr) return return_type;
+  CHECK(deduced_type->isDeduced());
+  return ctx.getQualifiedType(deduced_type->getDeducedType(),
+                              return_type.getQualifiers());
+}
+
+// Gets the `crubit_buffer` attributes of `decl`.
+//
+// Each attribute takes two string literal arguments, `"ptr=<param>"` and
+// `"len=<param>"`, naming a pointer parameter and the length parameter of the
+// buffer that it points to. An optional `"returns_len"` argument marks the
+// return value as the number of bytes written to the buffer.
+static absl::StatusOr<std::vector<BufferParam>> GetBufferParamsAttributes(
+    const clang::FunctionDecl& decl) {
+  auto has_param = [&decl](absl::string_view name) {
+    return absl::c_any_of(decl.parameters(),
+                          [name](const clang::ParmVarDecl* param) {
+                            return param->getName() == llvm::StringRef(name);
+                          });
+  };
+  std::vector<BufferParam> buffer_params;
+  for (const clang::AnnotateAttr* attr :
+       decl.specific_attrs<clang::AnnotateAttr>()) {
+    if (attr->getAnnotation() != "crubit_buffer") continue;
+    if (attr->args_size() != 2 && attr->args_size() != 3)
+      return absl::InvalidArgumentError(
+          "The `crubit_buffer` attribute requires two string literal "
+          "arguments, `ptr=<param>` and `len=<param>`, optionally followed "
+          "by `returns_len`.");
+    BufferParam buffer_param;
+    for (const clang::Expr* arg : attr->args()) {
+      CRUBIT_ASSIGN_OR_RETURN(
+          absl::string_view value,
+          EvaluateAsStringLiteral(*arg, decl.getASTContext()));
+      if (absl::ConsumePrefix(&value, "ptr=")) {
+        buffer_param.ptr = std::string(value);
+      } else if (absl::ConsumePrefix(&value, "len=")) {
+        buffer_param.len = std::string(value);
+      } else if (value == "returns_len") {
+        buffer_param.returns_len = true;
+      } else {
+        return absl::InvalidArgumentError(absl::StrCat(
+            "Unexpected `crubit_buffer` argument: \"", value, "\""));
+      }
+    }
+    for (absl::string_view name : {buffer_param.ptr, buffer_param.len}) {
+      if (!has_param(name))
+        return absl::InvalidArgumentError(
+            absl::StrCat("`crubit_buffer` refers to \"", name,
+                         "\", which is not a parameter of the function."));
+    }
+    for (const BufferParam& other : buffer_params) {
+      for (absl::string_view name : {buffer_param.ptr, buffer_param.len}) {
+        if (name == other.ptr || name == other.len)
+          return absl::InvalidArgumentError(
+              absl::StrCat("The parameter \"", name,
+                           "\" is used by more than one `crubit_buffer`."));
+      }
+    }
+    if (buffer_param.returns_len &&
+        absl::c_any_of(buffer_params,
+                       [](const BufferParam& b) { return b.returns_len; }))
+      return absl::InvalidArgumentError(
+          "Only one `crubit_buffer` of a function can return its length.");
+    if (buffer_param.ptr == buffer_param.len)
+      return absl::InvalidArgumentError(
+          "The `crubit_buffer` pointer and length must be different "
+          "parameters.");
+    buffer_params.push_back(std::move(buffer_param));
+  }
+  return buffer_params;
+}
+
+// Gets the `crubit_out_param` attribute of `decl`.
+//
+// The attribute takes a single string literal argument, naming the pointer
+// parameter through which the function returns its result.
+static absl::StatusOr<std::optional<std::string>> GetOutParamAttribute(
+    const clang::FunctionDecl& decl) {
+  CRUBIT_ASSIGN_OR_RETURN(const clang::AnnotateAttr* attr,
+                          GetAnnotateAttr(&decl, "crubit_out_param"));
+  if (attr == nullptr) return std::nullopt;
+  if (attr->args_size() != 1)
+    return absl::InvalidArgumentError(
+        "The `crubit_out_param` attribute requires a single string literal "
+        "argument, the name of the out parameter.");
+  CRUBIT_ASSIGN_OR_RETURN(
+      absl::string_view name,
+      EvaluateAsStringLiteral(**attr->args_begin(), decl.getASTContext()));
+  if (absl::c_none_of(decl.parameters(),
+                      [name](const clang::ParmVarDecl* param) {
+                        return param->getName() == llvm::StringRef(name);
+                      }))
+    return absl::InvalidArgumentError(
+        absl::StrCat("`crubit_out_param` refers to \"", name,
+                     "\", which is not a parameter of the function."));
+  return std::string(name);
+}
+
+// Gets the `crubit_stable_thunk` attribute of `decl`.
+//
+// The attribute takes a single string literal argument, the symbol name of the
+// thunk, which must be a valid C identifier.
+static absl::StatusOr<std::optional<std::string>> GetStableThunkAttribute(
+    const clang::FunctionDecl& decl) {
+  CRUBIT_ASSIGN_OR_RETURN(const clang::AnnotateAttr* attr,
+                          GetAnnotateAttr(&decl, "crubit_stable_thunk"));
+  if (attr == nullptr) return std::nullopt;
+  if (attr->args_size() != 1)
+    return absl::InvalidArgumentError(
+        "The `crubit_stable_thunk` attribute requires a single string literal "
+        "argument, the symbol name of the thunk.");
+  CRUBIT_ASSIGN_OR_RETURN(
+      absl::string_view name,
+      EvaluateAsStringLiteral(**attr->args_begin(), decl.getASTContext()));
+  if (name.empty() || absl::ascii_isdigit(name.front()) ||
+      !absl::c_all_of(name, [](char c) {
+        return absl::ascii_isalnum(c) || c == '_';
+      }))
+    return absl::InvalidArgumentError(absl::StrCat(
+        "\"", name, "\" is not a valid symbol name for a thunk."));
+  return std::string(name);
+}
+
+// Gets the `crubit_returns_static` attribute of `decl`.
+//
+// The attribute takes no arguments, and can only be applied to functions
+// returning a `const` lvalue reference. (A non-`const` referent could be
+// mutated from C++ while Rust holds a shared `&'static` reference to it.)
+static absl::StatusOr<bool> GetReturnsStaticAttribute(
+    const clang::FunctionDecl& decl) {
+  CRUBIT_ASSIGN_OR_RETURN(const clang::AnnotateAttr* attr,
+                          GetAnnotateAttr(&decl, "crubit_returns_static"));
+  if (attr == nullptr) return false;
+  if (attr->args_size() != 0)
+    return absl::InvalidArgumentError(
+        "The `crubit_returns_static` attribute takes no arguments.");
+  if (!decl.getReturnType()->isLValueReferenceType())
+    return absl::InvalidArgumentError(
+        "The `crubit_returns_static` attribute can only be applied to "
+        "functions returning an lvalue reference.");
+  if (!decl.getReturnType()->getPointeeType().isConstQualified())
+    return absl::InvalidArgumentError(
+        "The `crubit_returns_static` attribute can only be applied to "
+        "functions returning a `const` reference.");
+  return true;
+}
+
 Identifier FunctionDeclImporter::GetTranslatedParamName(
     const clang::ParmVarDecl* param_decl) {
   int param_pos = param_decl->getFunctionScopeIndex();
@@ -238,11 +403,11 @@ std::optional<IR::Item> FunctionDeclImporter::Import(
         add_error(absl::StrCat("`this` parameter is not supported: ",
                                param_type.status().message()));
       } else {
-        params.push_back(
-            {.type = *std::move(param_type),
-             .identifier = Identifier("__this"),
-             // TODO(b/319524852): catch `[[clang::lifetimbound]]` on `this`.
-             .unknown_attr = {}});
+        // `[[clang::lifetimebound]]` on `this` is reflected in the lifetimes
+        // of the return type (see `GetLifetimeAnnotations`).
+        params.push_back({.type = *std::move(param_type),
+                          .identifier = Identifier("__this"),
+                          .unknown_attr = {}});
       }
     }
   }
@@ -257,8 +422,30 @@ std::optional<IR::Item> FunctionDeclImporter::Import(
     if (lifetimes) {
       param_lifetimes = &lifetimes->GetParamLifetimes(i);
     }
-    auto param_type =
-        ictx_.ConvertQualType(param->getType(), param_lifetimes, std::nullopt);
+    absl::StatusOr<MappedType> param_type;
+    if (std::optional<MappedType> string_view_type =
+            ictx_.ConvertStringViewType(param->getType(), param_lifetimes)) {
+      param_type = *std::move(string_view_type);
+    } else if (std::optional<MappedType> string_type =
+                   ictx_.ConvertStdStringType(param->getType(),
+                                              /*is_param=*/true)) {
+      param_type = *std::move(string_type);
+    } else if (std::optional<MappedType> span_type =
+                   ictx_.ConvertSpanType(param->getType())) {
+      param_type = *std::move(span_type);
+    } else if (std::optional<MappedType> unique_ptr_type =
+                   ictx_.ConvertUniquePtrType(param->getType())) {
+      param_type = *std::move(unique_ptr_type);
+    } else if (std::optional<MappedType> shared_ptr_type =
+                   ictx_.ConvertSharedPtrType(param->getType())) {
+      param_type = *std::move(shared_ptr_type);
+    } else if (std::optional<MappedType> std_function_type =
+                   ictx_.ConvertStdFunctionType(param->getType())) {
+      param_type = *std::move(std_function_type);
+    } else {
+      param_type = ictx_.ConvertQualType(param->getType(), param_lifetimes,
+                                         std::nullopt);
+    }
     if (!param_type.ok()) {
       add_error(absl::Substitute("Parameter #$0 is not supported: $1", i,
                                  param_type.status().message()));
@@ -267,9 +454,26 @@ std::optional<IR::Item> FunctionDeclImporter::Import(
 
     std::optional<Identifier> param_name = GetTranslatedParamName(param);
     CHECK(param_name.has_value());  // No known failure cases.
+    // `[[clang::lifetimebound]]` is reflected in the lifetimes of the return
+    // type (see `GetLifetimeAnnotations`).
+    auto is_known = [](const clang::Attr& attr) {
+      return clang::isa<clang::LifetimeBoundAttr>(attr);
+    };
+    // The default argument is evaluated by the C++ thunks, so it is only kept
+    // as source text.
+    std::optional<std::string> default_argument;
+    if (param->hasDefaultArg()) {
+      llvm::StringRef text = clang::Lexer::getSourceText(
+          clang::CharSourceRange::getTokenRange(param->getDefaultArgRange()),
+          ictx_.ctx_.getSourceManager(), ictx_.ctx_.getLangOpts());
+      if (!text.empty()) {
+        default_argument = text.str();
+      }
+    }
     params.push_back({.type = *param_type,
                       .identifier = *std::move(param_name),
-                      .unknown_attr = CollectUnknownAttrs(*param)});
+                      .unknown_attr = CollectUnknownAttrs(*param, is_known),
+                      .default_argument = std::move(default_argument)});
   }
 
   bool undeduced_return_type =
@@ -295,8 +499,29 @@ std::optional<IR::Item> FunctionDeclImporter::Import(
     if (lifetimes) {
       return_lifetimes = &lifetimes->GetReturnLifetimes();
     }
-    return_type = ictx_.ConvertQualType(function_decl->getReturnType(),
-                                        return_lifetimes, std::nullopt);
+    clang::QualType deduced_return_type =
+        GetDeducedReturnType(*function_decl, ictx_.ctx_);
+    if (std::optional<MappedType> string_view_type =
+            ictx_.ConvertStringViewType(deduced_return_type,
+                                        return_lifetimes)) {
+      return_type = *std::move(string_view_type);
+    } else if (std::optional<MappedType> string_type =
+                   ictx_.ConvertStdStringType(deduced_return_type,
+                                              /*is_param=*/false)) {
+      return_type = *std::move(string_type);
+    } else if (std::optional<MappedType> status_type =
+                   ictx_.ConvertStatusType(deduced_return_type)) {
+      return_type = *std::move(status_type);
+    } else if (std::optional<MappedType> unique_ptr_type =
+                   ictx_.ConvertUniquePtrType(deduced_return_type)) {
+      return_type = *std::move(unique_ptr_type);
+    } else if (std::optional<MappedType> shared_ptr_type =
+                   ictx_.ConvertSharedPtrType(deduced_return_type)) {
+      return_type = *std::move(shared_ptr_type);
+    } else {
+      return_type = ictx_.ConvertQualType(deduced_return_type,
+                                          return_lifetimes, std::nullopt);
+    }
     if (!return_type.ok()) {
       add_error(absl::StrCat("Return type is not supported: ",
                              return_type.status().message()));
@@ -342,6 +567,10 @@ std::optional<IR::Item> FunctionDeclImporter::Import(
           .reference = reference,
           .is_const = method_decl->isConst(),
           .is_virtual = method_decl->isVirtual(),
+          .is_pure_virtual = method_decl->isPureVirtual(),
+          .is_final = method_decl->isVirtual() &&
+                      (method_decl->hasAttr<clang::FinalAttr>() ||
+                       method_decl->getParent()->isEffectivelyFinal()),
       };
     }
 
@@ -360,6 +589,24 @@ std::optional<IR::Item> FunctionDeclImporter::Import(
   bool is_member_or_descendant_of_class_template =
       IsFullClassTemplateSpecializationOrChild(function_decl);
 
+  const auto* ctor_decl =
+      clang::dyn_cast<clang::CXXConstructorDecl>(function_decl);
+  bool is_explicit_ctor = ctor_decl != nullptr && ctor_decl->isExplicit();
+
+  bool is_noexcept = false;
+  if (const auto* proto_type =
+          function_decl->getType()->getAs<clang::FunctionProtoType>()) {
+    // The exception specifications of implicitly declared functions (e.g.
+    // destructors) and of members of class templates are computed lazily.
+    if (clang::isUnresolvedExceptionSpec(
+            proto_type->getExceptionSpecType())) {
+      proto_type = ictx_.sema_.ResolveExceptionSpec(
+          function_decl->getLocation(), proto_type);
+    }
+    is_noexcept =
+        proto_type != nullptr && proto_type->canThrow() == clang::CT_Cannot;
+  }
+
   std::optional<std::string> doc_comment = ictx_.GetComment(function_decl);
   if (!doc_comment.has_value() && is_member_or_descendant_of_class_template) {
     // Despite `is_member_or_descendant_of_class_template` check above, we are
@@ -372,6 +619,49 @@ std::optional<IR::Item> FunctionDeclImporter::Import(
     }
   }
 
+  absl::StatusOr<std::vector<BufferParam>> buffer_params =
+      GetBufferParamsAttributes(*function_decl);
+  if (!buffer_params.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        function_decl, absl::StrCat("Invalid crubit_buffer attribute: ",
+                                    buffer_params.status().message()));
+  }
+  absl::StatusOr<std::optional<std::string>> out_param =
+      GetOutParamAttribute(*function_decl);
+  if (!out_param.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        function_decl, absl::StrCat("Invalid crubit_out_param attribute: ",
+                                    out_param.status().message()));
+  }
+  absl::StatusOr<std::optional<std::string>> stable_thunk_name =
+      GetStableThunkAttribute(*function_decl);
+  if (!stable_thunk_name.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        function_decl,
+        absl::StrCat("Invalid crubit_stable_thunk attribute: ",
+                     stable_thunk_name.status().message()));
+  }
+  absl::StatusOr<std::optional<std::string>> renamed_from =
+      GetRenamedFromAttribute(function_decl);
+  if (renamed_from.ok() && renamed_from->has_value() &&
+      clang::isa<clang::CXXMethodDecl>(function_decl)) {
+    renamed_from = absl::InvalidArgumentError(
+        "Only functions at namespace scope can be annotated.");
+  }
+  if (!renamed_from.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        function_decl, absl::StrCat("Invalid crubit_renamed_from attribute: ",
+                                    renamed_from.status().message()));
+  }
+  absl::StatusOr<bool> returns_static =
+      GetReturnsStaticAttribute(*function_decl);
+  if (!returns_static.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        function_decl,
+        absl::StrCat("Invalid crubit_returns_static attribute: ",
+                     returns_static.status().message()));
+  }
+
   std::optional<std::string> nodiscard;
   std::optional<std::string> deprecated;
   std::optional<std::string> unknown_attr =
@@ -389,6 +679,16 @@ std::optional<IR::Item> FunctionDeclImporter::Import(
         } else if (clang::isa<clang::NoThrowAttr>(attr)) {
           // nothrow attributes don't affect Rust.
           return true;
+        } else if (clang::isa<clang::ColdAttr, clang::NoInlineAttr>(attr)) {
+          return true;  // we call hasAttr below, instead
+        } else if (auto* annotate = clang::dyn_cast<clang::AnnotateAttr>(&attr);
+                   annotate &&
+                   (annotate->getAnnotation() == "crubit_buffer" ||
+                    annotate->getAnnotation() == "crubit_out_param" ||
+                    annotate->getAnnotation() == "crubit_stable_thunk" ||
+                    annotate->getAnnotation() == "crubit_renamed_from" ||
+                    annotate->getAnnotation() == "crubit_returns_static")) {
+          return true;
         }
         return false;
       });
@@ -410,11 +710,20 @@ std::optional<IR::Item> FunctionDeclImporter::Import(
       .mangled_name = ictx_.GetMangledName(function_decl),
       .return_type = *return_type,
       .params = std::move(params),
+      .buffer_params = *std::move(buffer_params),
+      .out_param = *std::move(out_param),
+      .stable_thunk_name = *std::move(stable_thunk_name),
+      .renamed_from = *std::move(renamed_from),
+      .returns_static = *returns_static,
       .lifetime_params = std::move(lifetime_params),
       .is_inline = function_decl->isInlined(),
       .member_func_metadata = std::move(member_func_metadata),
       .is_extern_c = function_decl->isExternC(),
       .is_noreturn = function_decl->isNoReturn(),
+      .is_noexcept = is_noexcept,
+      .is_cold = function_decl->hasAttr<clang::ColdAttr>(),
+      .is_noinline = function_decl->hasAttr<clang::NoInlineAttr>(),
+      .is_explicit_ctor = is_explicit_ctor,
       .nodiscard = std::move(nodiscard),
       .deprecated = std::move(deprecated),
       .unknown_attr = std::move(unknown_attr),
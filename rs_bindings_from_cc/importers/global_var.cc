@@ -0,0 +1,121 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#include "rs_bindings_from_cc/importers/global_var.h"
+
+#include <optional>
+#include <string>
+#include <utility>
+
+#include "absl/status/statusor.h"
+#include "absl/strings/str_cat.h"
+#include "lifetime_annotations/type_lifetimes.h"
+#include "rs_bindings_from_cc/ir.h"
+#include "clang/AST/APValue.h"
+#include "clang/AST/Decl.h"
+#include "clang/AST/DeclCXX.h"
+#include "clang/AST/DeclTemplate.h"
+#include "clang/AST/Expr.h"
+#include "clang/AST/Type.h"
+#include "clang/Basic/LLVM.h"
+
+namespace crubit {
+namespace {
+
+// Returns the value of `var_decl` if it is a constant of integral type whose
+// initializer can be evaluated at compile time.
+std::optional<IntegerConstant> GetConstantValue(
+    clang::ASTContext& ctx, const clang::VarDecl& var_decl) {
+  clang::QualType type = var_decl.getType();
+  if (!type.isConstQualified() || !type->isIntegerType() ||
+      type->isEnumeralType() || ctx.getTypeSize(type) > 64) {
+    return std::nullopt;
+  }
+  const clang::VarDecl* init_decl = nullptr;
+  if (var_decl.getAnyInitializer(init_decl) == nullptr ||
+      !init_decl->isUsableInConstantExpressions(ctx)) {
+    return std::nullopt;
+  }
+  const clang::APValue* value = init_decl->evaluateValue();
+  if (value == nullptr || !value->isInt()) return std::nullopt;
+  return IntegerConstant(value->getInt());
+}
+
+}  // namespace
+
+std::optional<IR::Item> GlobalVarImporter::Import(clang::VarDecl* var_decl) {
+  // Parameters and local variables are not global, and neither structured
+  // bindings nor the specializations of variable templates are supported.
+  if (var_decl->isLocalVarDeclOrParm() ||
+      clang::isa<clang::DecompositionDecl,
+                 clang::VarTemplateSpecializationDecl>(var_decl)) {
+    return std::nullopt;
+  }
+
+  if (clang::isa<clang::ClassTemplateSpecializationDecl>(
+          var_decl->getDeclContext())) {
+    return ictx_.ImportUnsupportedItem(
+        var_decl,
+        "Static data members of class templates are not supported yet");
+  }
+  if (var_decl->getTLSKind() != clang::VarDecl::TLS_None) {
+    return ictx_.ImportUnsupportedItem(
+        var_decl, "thread_local variables are not supported");
+  }
+
+  absl::StatusOr<Identifier> identifier =
+      ictx_.GetTranslatedIdentifier(var_decl);
+  if (!identifier.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        var_decl, absl::StrCat("Variable name is not supported: ",
+                               identifier.status().message()));
+  }
+
+  clang::QualType type = var_decl->getType();
+  if (type->isReferenceType()) {
+    return ictx_.ImportUnsupportedItem(
+        var_decl, "Variables of reference type are not supported yet");
+  }
+
+  std::optional<IntegerConstant> value =
+      GetConstantValue(ictx_.ctx_, *var_decl);
+  // Without a value, the bindings access the variable through a thunk, which
+  // would see a different object than the other translation units if the
+  // variable had internal linkage.
+  if (!value.has_value() && !var_decl->isExternallyVisible()) {
+    return ictx_.ImportUnsupportedItem(
+        var_decl,
+        "Variables with internal linkage are only supported if they are "
+        "compile-time constants of integral type");
+  }
+
+  clang::tidy::lifetimes::ValueLifetimes* no_lifetimes = nullptr;
+  absl::StatusOr<MappedType> mapped_type =
+      ictx_.ConvertQualType(type, no_lifetimes, std::nullopt);
+  if (!mapped_type.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        var_decl, std::string(mapped_type.status().message()));
+  }
+
+  auto enclosing_item_id = ictx_.GetEnclosingItemId(var_decl);
+  if (!enclosing_item_id.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        var_decl, std::string(enclosing_item_id.status().message()));
+  }
+
+  ictx_.MarkAsSuccessfullyImported(var_decl);
+  return GlobalVar{
+      .identifier = *std::move(identifier),
+      .id = ictx_.GenerateItemId(var_decl),
+      .owning_target = ictx_.GetOwningTarget(var_decl),
+      .doc_comment = ictx_.GetComment(var_decl),
+      .mangled_name = ictx_.GetMangledName(var_decl),
+      .type = *std::move(mapped_type),
+      .value = std::move(value),
+      .source_loc = ictx_.ConvertSourceLocation(var_decl->getBeginLoc()),
+      .enclosing_item_id = *std::move(enclosing_item_id),
+  };
+}
+
+}  // namespace crubit
@@ -9,11 +9,13 @@
 #include <utility>
 
 #include "absl/log/check.h"
+#include "absl/status/statusor.h"
 #include "absl/strings/str_cat.h"
 #include "lifetime_annotations/type_lifetimes.h"
 #include "rs_bindings_from_cc/ast_util.h"
 #include "rs_bindings_from_cc/ir.h"
 #include "clang/AST/ASTContext.h"
+#include "clang/AST/Attr.h"
 #include "clang/AST/Decl.h"
 #include "clang/AST/DeclBase.h"
 #include "clang/AST/Type.h"
@@ -63,6 +65,14 @@ std::optional<IR::Item> crubit::TypeAliasImporter::Import(
                            identifier.status().message()));
   }
 
+  absl::StatusOr<std::optional<std::string>> renamed_from =
+      GetRenamedFromAttribute(decl);
+  if (!renamed_from.ok()) {
+    return ictx_.ImportUnsupportedItem(
+        decl, absl::StrCat("Invalid crubit_renamed_from attribute: ",
+                           renamed_from.status().message()));
+  }
+
   clang::tidy::lifetimes::ValueLifetimes* no_lifetimes = nullptr;
   // TODO(mboehme): Once lifetime_annotations supports retrieving lifetimes in
   // type aliases, pass these to ConvertQualType().
@@ -85,8 +95,15 @@ std::optional<IR::Item> crubit::TypeAliasImporter::Import(
       .id = ictx_.GenerateItemId(decl),
       .owning_target = ictx_.GetOwningTarget(decl),
       .doc_comment = ictx_.GetComment(decl),
-      .unknown_attr = CollectUnknownAttrs(*decl),
+      .unknown_attr = CollectUnknownAttrs(
+          *decl,
+          [](const clang::Attr& attr) {
+            auto* annotate = clang::dyn_cast<clang::AnnotateAttr>(&attr);
+            return annotate &&
+                   annotate->getAnnotation() == "crubit_renamed_from";
+          }),
       .underlying_type = *underlying_type,
+      .renamed_from = *std::move(renamed_from),
       .source_loc = ictx_.ConvertSourceLocation(decl->getBeginLoc()),
       .enclosing_item_id = *std::move(enclosing_item_id),
   };
@@ -203,6 +203,116 @@ MappedType MappedType::FuncRef(absl::string_view cc_call_conv,
   };
 }
 
+MappedType MappedType::ArrayOf(MappedType element_type, uint64_t size) {
+  return MappedType{
+      .rs_type = RsType{.name = absl::StrCat(internal::kRustArray, " ", size),
+                        .type_args = {std::move(element_type.rs_type)}},
+      .cc_type = CcType{.name = absl::StrCat(internal::kCcArray, " ", size),
+                        .type_args = {std::move(element_type.cc_type)}},
+  };
+}
+
+MappedType MappedType::StdArrayOf(MappedType element_type, uint64_t size) {
+  return MappedType{
+      .rs_type = RsType{.name = absl::StrCat(internal::kRustArray, " ", size),
+                        .type_args = {std::move(element_type.rs_type)}},
+      .cc_type = CcType{.name = absl::StrCat(internal::kCcStdArray, " ", size),
+                        .type_args = {std::move(element_type.cc_type)}},
+  };
+}
+
+MappedType MappedType::StdOptionalOf(MappedType value_type) {
+  return MappedType{
+      .rs_type = RsType{.name = std::string(internal::kRustStdOptional),
+                        .type_args = {std::move(value_type.rs_type)}},
+      .cc_type = CcType{.name = std::string(internal::kCcStdOptional),
+                        .type_args = {std::move(value_type.cc_type)}},
+  };
+}
+
+MappedType MappedType::InitializerListOf(MappedType element_type) {
+  return MappedType{
+      .rs_type = RsType{.name = std::string(internal::kRustInitializerList),
+                        .type_args = {std::move(element_type.rs_type)}},
+      .cc_type = CcType{.name = std::string(internal::kCcInitializerList),
+                        .type_args = {std::move(element_type.cc_type)}},
+  };
+}
+
+MappedType MappedType::StringView(absl::string_view cc_name,
+                                  std::optional<LifetimeId> lifetime) {
+  MappedType result = Simple(std::string(internal::kRustStringView),
+                             std::string(cc_name));
+  if (lifetime.has_value()) {
+    result.rs_type.lifetime_args.push_back(*lifetime);
+  }
+  return result;
+}
+
+MappedType MappedType::StdString() {
+  return Simple(std::string(internal::kRustStdString),
+                std::string(internal::kCcStdString));
+}
+
+MappedType MappedType::SpanOf(absl::string_view cc_name,
+                              MappedType element_type) {
+  absl::string_view rs_name = element_type.cc_type.is_const
+                                  ? internal::kRustSpanConst
+                                  : internal::kRustSpanMut;
+  return MappedType{
+      .rs_type = RsType{.name = std::string(rs_name),
+                        .type_args = {std::move(element_type.rs_type)}},
+      .cc_type = CcType{.name = std::string(cc_name),
+                        .type_args = {std::move(element_type.cc_type)}},
+  };
+}
+
+MappedType MappedType::AbslStatus() {
+  return Simple(std::string(internal::kRustAbslStatus),
+                std::string(internal::kCcAbslStatus));
+}
+
+MappedType MappedType::AbslStatusOf(MappedType value_type) {
+  return MappedType{
+      .rs_type = RsType{.name = std::string(internal::kRustAbslStatusOr),
+                        .type_args = {std::move(value_type.rs_type)}},
+      .cc_type = CcType{.name = std::string(internal::kCcAbslStatusOr),
+                        .type_args = {std::move(value_type.cc_type)}},
+  };
+}
+
+MappedType MappedType::UniquePtrOf(MappedType pointee_type) {
+  return MappedType{
+      .rs_type = RsType{.name = std::string(internal::kRustUniquePtr),
+                        .type_args = {std::move(pointee_type.rs_type)}},
+      .cc_type = CcType{.name = std::string(internal::kCcUniquePtr),
+                        .type_args = {std::move(pointee_type.cc_type)}},
+  };
+}
+
+MappedType MappedType::SharedPtrOf(MappedType pointee_type) {
+  return MappedType{
+      .rs_type = RsType{.name = std::string(internal::kRustSharedPtr),
+                        .type_args = {std::move(pointee_type.rs_type)}},
+      .cc_type = CcType{.name = std::string(internal::kCcSharedPtr),
+                        .type_args = {std::move(pointee_type.cc_type)}},
+  };
+}
+
+MappedType MappedType::StdFunctionOf(MappedType return_type,
+                                     std::vector<MappedType> param_types) {
+  MappedType result = MappedType{
+      .rs_type = RsType{.name = std::string(internal::kRustStdFunction)},
+      .cc_type = CcType{.name = std::string(internal::kCcStdFunction)},
+  };
+  param_types.push_back(std::move(return_type));
+  for (MappedType& type_arg : param_types) {
+    result.rs_type.type_args.push_back(std::move(type_arg.rs_type));
+    result.cc_type.type_args.push_back(std::move(type_arg.cc_type));
+  }
+  return result;
+}
+
 llvm::json::Value MappedType::ToJson() const {
   return llvm::json::Object{
       {"rs_type", rs_type},
@@ -260,9 +370,21 @@ llvm::json::Value FuncParam::ToJson() const {
       {"type", type},
       {"identifier", identifier},
       {"unknown_attr", unknown_attr},
+      {"default_argument", default_argument},
   };
 }
 
+llvm::json::Value BufferParam::ToJson() const {
+  llvm::json::Object result{
+      {"ptr", ptr},
+      {"len", len},
+  };
+  if (returns_len) {
+    result["returns_len"] = true;
+  }
+  return std::move(result);
+}
+
 std::ostream& operator<<(std::ostream& o, const SpecialName& special_name) {
   return o << SpecialNameToString(special_name);
 }
@@ -285,6 +407,8 @@ llvm::json::Value MemberFuncMetadata::InstanceMethodMetadata::ToJson() const {
       {"reference", reference_str},
       {"is_const", is_const},
       {"is_virtual", is_virtual},
+      {"is_pure_virtual", is_pure_virtual},
+      {"is_final", is_final},
   };
 }
 
@@ -332,11 +456,20 @@ llvm::json::Value Func::ToJson() const {
       {"mangled_name", mangled_name},
       {"return_type", return_type},
       {"params", params},
+      {"buffer_params", buffer_params},
+      {"out_param", out_param},
+      {"stable_thunk_name", stable_thunk_name},
+      {"renamed_from", renamed_from},
+      {"returns_static", returns_static},
       {"lifetime_params", lifetime_params},
       {"is_inline", is_inline},
       {"member_func_metadata", member_func_metadata},
       {"is_extern_c", is_extern_c},
       {"is_noreturn", is_noreturn},
+      {"is_noexcept", is_noexcept},
+      {"is_cold", is_cold},
+      {"is_noinline", is_noinline},
+      {"is_explicit_ctor", is_explicit_ctor},
       {"nodiscard", nodiscard},
       {"deprecated", deprecated},
       {"has_c_calling_convention", has_c_calling_convention},
@@ -380,6 +513,9 @@ llvm::json::Value Field::ToJson() const {
       {"is_no_unique_address", is_no_unique_address},
       {"is_bitfield", is_bitfield},
       {"is_inheritable", is_inheritable},
+      {"default_value", default_value},
+      {"anonymous_members", anonymous_members},
+      {"is_anonymous_union", is_anonymous_union},
   };
 }
 
@@ -441,6 +577,22 @@ llvm::json::Value SizeAlign::ToJson() const {
   };
 }
 
+llvm::json::Value Awaitable::ToJson() const {
+  return llvm::json::Object{
+      {"poll", poll},
+      {"get", get},
+  };
+}
+
+llvm::json::Value MapVisitor::ToJson() const {
+  return llvm::json::Object{
+      {"begin", begin},
+      {"end", end},
+      {"key_type", key_type},
+      {"mapped_type", mapped_type},
+  };
+}
+
 llvm::json::Value Record::ToJson() const {
   std::vector<llvm::json::Value> json_item_ids;
   json_item_ids.reserve(child_item_ids.size());
@@ -472,7 +624,14 @@ llvm::json::Value Record::ToJson() const {
       {"is_abstract", is_abstract},
       {"record_type", RecordTypeToString(record_type)},
       {"is_aggregate", is_aggregate},
+      {"has_mutable_fields", has_mutable_fields},
       {"is_anon_record_with_typedef", is_anon_record_with_typedef},
+      {"awaitable", awaitable},
+      {"map_visitor", map_visitor},
+      {"std_vector_element_type", std_vector_element_type},
+      {"extra_rust_impl", extra_rust_impl},
+      {"is_opaque", is_opaque},
+      {"renamed_from", renamed_from},
       {"child_item_ids", std::move(json_item_ids)},
       {"enclosing_item_id", enclosing_item_id},
   };
@@ -499,6 +658,8 @@ llvm::json::Value Enum::ToJson() const {
       {"underlying_type", underlying_type},
       {"enumerators", enumerators},
       {"unknown_attr", unknown_attr},
+      {"is_rust_enum", is_rust_enum},
+      {"renamed_from", renamed_from},
       {"enclosing_item_id", enclosing_item_id},
   };
 
@@ -515,6 +676,7 @@ llvm::json::Value TypeAlias::ToJson() const {
       {"unknown_attr", unknown_attr},
       {"doc_comment", doc_comment},
       {"underlying_type", underlying_type},
+      {"renamed_from", renamed_from},
       {"source_loc", source_loc},
       {"enclosing_item_id", enclosing_item_id},
   };
@@ -524,6 +686,25 @@ llvm::json::Value TypeAlias::ToJson() const {
   };
 }
 
+llvm::json::Value GlobalVar::ToJson() const {
+  llvm::json::Object global_var{
+      {"identifier", identifier},
+      {"id", id},
+      {"owning_target", owning_target},
+      {"doc_comment", doc_comment},
+      {"mangled_name", mangled_name},
+      {"type", type},
+      {"value", value},
+      {"source_loc", source_loc},
+      {"enclosing_item_id", enclosing_item_id},
+      {"is_macro", is_macro},
+  };
+
+  return llvm::json::Object{
+      {"GlobalVar", std::move(global_var)},
+  };
+}
+
 llvm::json::Value FormattedError::ToJson() const {
   return llvm::json::Object{
       {"fmt", fmt},
@@ -57,11 +57,81 @@ inline constexpr absl::string_view kRustRvalueRefConst =
 // Function pointers.
 inline constexpr absl::string_view kRustFuncPtr = "#funcPtr";
 
+// Fixed-size arrays.
+inline constexpr absl::string_view kRustArray = "#array";
+
+// `std::optional<T>`, which is converted into a Rust `Option<T>` (and back)
+// when it crosses the FFI boundary.
+inline constexpr absl::string_view kRustStdOptional = "#stdOptional";
+
+// `std::initializer_list<T>`, which is represented by a Rust `&[T]`.
+inline constexpr absl::string_view kRustInitializerList = "#initializerList";
+
+// `std::string_view` and `absl::string_view`, which are converted into a Rust
+// `&[u8]` (and back) when they cross the FFI boundary.
+inline constexpr absl::string_view kRustStringView = "#stringView";
+
+// `std::string`, which is converted from a Rust `&[u8]` when it is passed to a
+// function, and into a Rust `Vec<u8>` when it is returned.
+inline constexpr absl::string_view kRustStdString = "#stdString";
+
+// `absl::Span<T>` and `std::span<T>` parameters, which are represented by a
+// Rust `&[T]` (or `&mut [T]`, if `T` isn't const). The element type is stored
+// in `type_args[0]`.
+inline constexpr absl::string_view kRustSpanConst = "#span const";
+inline constexpr absl::string_view kRustSpanMut = "#span mut";
+
+// `absl::Status` and `absl::StatusOr<T>` return values, which are converted
+// into a Rust `Result<(), cc_status::Status>` (or `Result<T, ...>`). The value
+// type of `absl::StatusOr<T>` is stored in `type_args[0]`.
+inline constexpr absl::string_view kRustAbslStatus = "#abslStatus";
+inline constexpr absl::string_view kRustAbslStatusOr = "#abslStatusOr";
+
+// `std::unique_ptr<T>` parameters and return values, which are represented by a
+// Rust `Option<cc_unique_ptr::UniquePtr<T>>`. The pointee type is stored in
+// `type_args[0]`.
+inline constexpr absl::string_view kRustUniquePtr = "#uniquePtr";
+
+// `std::shared_ptr<T>` parameters and return values, which are represented by a
+// Rust `Option<cc_shared_ptr::SharedPtr<T>>`. The pointee type is stored in
+// `type_args[0]`.
+inline constexpr absl::string_view kRustSharedPtr = "#sharedPtr";
+
+// `std::function<R(Args...)>` parameters, which are represented by a Rust
+// `impl Fn(Args...) -> R + 'static`. The return type is the last element of
+// `type_args`, and the parameter types are the other elements.
+inline constexpr absl::string_view kRustStdFunction = "#stdFunction";
+
+// Abseil time types, which are converted into Rust types (and back) when they
+// cross the FFI boundary.
+inline constexpr absl::string_view kRustAbslDuration = "#abslDuration";
+inline constexpr absl::string_view kRustAbslTime = "#abslTime";
+
+// `std::chrono` types, which are converted into Rust types (and back) when they
+// cross the FFI boundary.  The name is followed by the unit of the duration
+// (e.g. "#chronoDuration milliseconds").
+inline constexpr absl::string_view kRustChronoDuration = "#chronoDuration";
+inline constexpr absl::string_view kRustChronoSystemTime = "#chronoSystemTime";
+
 // C++ types therein.
 inline constexpr absl::string_view kCcPtr = "*";
 inline constexpr absl::string_view kCcLValueRef = "&";
 inline constexpr absl::string_view kCcRValueRef = "&&";
 inline constexpr absl::string_view kCcFuncValue = "#funcValue";
+inline constexpr absl::string_view kCcArray = "#array";
+inline constexpr absl::string_view kCcStdArray = "#stdArray";
+inline constexpr absl::string_view kCcStdOptional = "#stdOptional";
+inline constexpr absl::string_view kCcInitializerList = "#initializerList";
+inline constexpr absl::string_view kCcStdStringView = "#stdStringView";
+inline constexpr absl::string_view kCcAbslStringView = "#abslStringView";
+inline constexpr absl::string_view kCcStdString = "#stdString";
+inline constexpr absl::string_view kCcStdSpan = "#stdSpan";
+inline constexpr absl::string_view kCcAbslSpan = "#abslSpan";
+inline constexpr absl::string_view kCcAbslStatus = "#abslStatus";
+inline constexpr absl::string_view kCcAbslStatusOr = "#abslStatusOr";
+inline constexpr absl::string_view kCcUniquePtr = "#uniquePtr";
+inline constexpr absl::string_view kCcSharedPtr = "#sharedPtr";
+inline constexpr absl::string_view kCcStdFunction = "#stdFunction";
 
 inline constexpr int kJsonIndent = 2;
 }  // namespace internal
@@ -146,6 +216,26 @@ struct CcType {
   // - "#funcValue <callConv>" (compare with "#funcPtr <abi>" in RsType::name
   //   and note that Rust only supports function pointers; note that <callConv>
   //   in CcType doesn't map 1:1 to <abi> in RsType).
+  // - "#array <size>" (`T[size]`; element type stored in `type_args[0]`).
+  // - "#stdArray <size>" (`std::array<T, size>`; element type stored in
+  //   `type_args[0]`).
+  // - "#stdOptional" (`std::optional<T>`; value type stored in
+  //   `type_args[0]`).
+  // - "#initializerList" (`std::initializer_list<T>`; element type stored in
+  //   `type_args[0]`).
+  // - "#stdStringView" and "#abslStringView" (`std::string_view` and
+  //   `absl::string_view`).
+  // - "#stdString" (`std::string`).
+  // - "#stdSpan" and "#abslSpan" (`std::span<T>` and `absl::Span<T>`; element
+  //   type stored in `type_args[0]`).
+  // - "#abslStatus" and "#abslStatusOr" (`absl::Status` and
+  //   `absl::StatusOr<T>`; value type stored in `type_args[0]`).
+  // - "#uniquePtr" (`std::unique_ptr<T>`; pointee type stored in
+  //   `type_args[0]`).
+  // - "#sharedPtr" (`std::shared_ptr<T>`; pointee type stored in
+  //   `type_args[0]`).
+  // - "#stdFunction" (`std::function<R(Args...)>`; return type is the last
+  //   elem in `type_args`; param types are stored in other `type_args`).
   // - An empty string when `decl_id` is non-empty.
   std::string name;
 
@@ -184,6 +274,23 @@ struct RsType {
   //   `type_args`; param types are stored in other `type_args`; <abi> would be
   //   replaced with "cdecl", "stdcall" or other Abi - see
   //   https://doc.rust-lang.org/reference/types/function-pointer.html);
+  // - "#array <size>" (`[T; size]`; element type stored in `type_args[0]`).
+  // - "#stdOptional" (`std::optional<T>`, which is represented by `Option<T>`
+  //   in Rust; value type stored in `type_args[0]`).
+  // - "#initializerList" (`std::initializer_list<T>`, which is represented by
+  //   `&[T]` in Rust; element type stored in `type_args[0]`).
+  // - "#stringView" (`std::string_view` or `absl::string_view`, which are
+  //   represented by `&[u8]` in Rust; the lifetime of the viewed characters,
+  //   if known, is stored in `lifetime_args[0]`).
+  // - "#stdString" (`std::string`, which is represented by `&[u8]` in Rust
+  //   when it is passed to a function, and by `Vec<u8>` when it is returned).
+  // - "#abslDuration" and "#abslTime" (`absl::Duration` and `absl::Time`,
+  //   which are represented by `::core::time::Duration` and
+  //   `::absl_time::Time` in Rust).
+  // - "#chronoDuration <unit>" and "#chronoSystemTime <unit>"
+  //   (`std::chrono::<unit>`, and `std::chrono::system_clock` time points with
+  //   that duration, which are represented by `::core::time::Duration` and
+  //   `::cc_chrono::SystemTime` in Rust).
   // - An empty string when `decl_id` is non-empty.
   std::string name;
 
@@ -263,6 +370,57 @@ struct MappedType {
                             MappedType return_type,
                             std::vector<MappedType> param_types);
 
+  // Creates a mapped type for the C array `T[size]`, which is spelled
+  // `[T; size]` in Rust.
+  static MappedType ArrayOf(MappedType element_type, uint64_t size);
+
+  // Creates a mapped type for `std::array<T, size>`, which is spelled
+  // `[T; size]` in Rust.
+  static MappedType StdArrayOf(MappedType element_type, uint64_t size);
+
+  // Creates a mapped type for `std::optional<T>`, which is spelled
+  // `Option<T>` in Rust.
+  static MappedType StdOptionalOf(MappedType value_type);
+
+  // Creates a mapped type for `std::initializer_list<T>`, which is spelled
+  // `&[T]` in Rust.
+  static MappedType InitializerListOf(MappedType element_type);
+
+  // Creates a mapped type for a string view type, which is spelled `&[u8]` in
+  // Rust. `cc_name` is either `kCcStdStringView` or `kCcAbslStringView`.
+  static MappedType StringView(absl::string_view cc_name,
+                               std::optional<LifetimeId> lifetime);
+
+  // Creates a mapped type for `std::string`, which is spelled `&[u8]` (for
+  // parameters) or `Vec<u8>` (for return values) in Rust.
+  static MappedType StdString();
+
+  // Creates a mapped type for a span type, which is spelled `&[T]` (or
+  // `&mut [T]`, if the element type isn't const) in Rust. `cc_name` is either
+  // `kCcStdSpan` or `kCcAbslSpan`.
+  static MappedType SpanOf(absl::string_view cc_name, MappedType element_type);
+
+  // Creates a mapped type for `absl::Status`, which is returned as a
+  // `Result<(), cc_status::Status>` in Rust.
+  static MappedType AbslStatus();
+
+  // Creates a mapped type for `absl::StatusOr<T>`, which is returned as a
+  // `Result<T, cc_status::Status>` in Rust.
+  static MappedType AbslStatusOf(MappedType value_type);
+
+  // Creates a mapped type for `std::unique_ptr<T>`, which is spelled
+  // `Option<cc_unique_ptr::UniquePtr<T>>` in Rust.
+  static MappedType UniquePtrOf(MappedType pointee_type);
+
+  // Creates a mapped type for `std::shared_ptr<T>`, which is spelled
+  // `Option<cc_shared_ptr::SharedPtr<T>>` in Rust.
+  static MappedType SharedPtrOf(MappedType pointee_type);
+
+  // Creates a mapped type for `std::function<R(Args...)>`, which is spelled
+  // `impl Fn(Args...) -> R + 'static` in Rust.
+  static MappedType StdFunctionOf(MappedType return_type,
+                                  std::vector<MappedType> param_types);
+
   bool IsVoid() const { return rs_type.name == "()"; }
 
   llvm::json::Value ToJson() const;
@@ -368,12 +526,29 @@ struct FuncParam {
   MappedType type;
   Identifier identifier;
   std::optional<std::string> unknown_attr;
+  // Source text of the default argument (e.g. `3` for `int b = 3`), if the
+  // parameter has one.
+  std::optional<std::string> default_argument;
 };
 
 inline std::ostream& operator<<(std::ostream& o, const FuncParam& param) {
   return o << std::string(llvm::formatv("{0:2}", param.ToJson()));
 }
 
+// A pointer parameter and a length parameter that together describe a buffer,
+// as specified by the `crubit_buffer` attribute (e.g. `const char* data` and
+// `size_t size`). The pair is exposed as a single slice parameter in Rust.
+struct BufferParam {
+  llvm::json::Value ToJson() const;
+
+  // The name of the pointer parameter.
+  std::string ptr;
+  // The name of the length parameter.
+  std::string len;
+  // Whether the function returns the number of bytes it wrote to the buffer.
+  bool returns_len = false;
+};
+
 enum SpecialName {
   kDestructor,
   kConstructor,
@@ -406,6 +581,12 @@ struct MemberFuncMetadata {
     ReferenceQualification reference = kUnqualified;
     bool is_const = false;
     bool is_virtual = false;
+    // Set if the method is pure virtual (`= 0`), in which case it may not have
+    // a definition.
+    bool is_pure_virtual = false;
+    // Set if the method is virtual, but can't be overridden, because it is
+    // `final` or a member of a `final` class.
+    bool is_final = false;
   };
 
   llvm::json::Value ToJson() const;
@@ -429,12 +610,33 @@ struct Func {
   std::string mangled_name;
   MappedType return_type;
   std::vector<FuncParam> params;
+  // The pointer/length parameter pairs that are exposed as slices.
+  std::vector<BufferParam> buffer_params;
+  // The name of the pointer parameter whose value is returned in Rust, as
+  // specified by the `crubit_out_param` attribute.
+  std::optional<std::string> out_param;
+  // The symbol name of the thunk, as pinned by the `crubit_stable_thunk`
+  // attribute.
+  std::optional<std::string> stable_thunk_name;
+  // The previous name of the function, as specified by the
+  // `crubit_renamed_from` attribute.
+  std::optional<std::string> renamed_from;
+  // Whether the function returns a reference to an object with static storage
+  // duration, as specified by the `crubit_returns_static` attribute.
+  bool returns_static = false;
   std::vector<LifetimeName> lifetime_params;
   bool is_inline;
   // If null, this is not a member function.
   std::optional<MemberFuncMetadata> member_func_metadata;
   bool is_extern_c = false;
   bool is_noreturn = false;
+  // Whether the function can't throw exceptions (e.g. because it is
+  // `noexcept`, or because it is a destructor).
+  bool is_noexcept = false;
+  bool is_cold = false;
+  bool is_noinline = false;
+  // Whether the function is a constructor marked `explicit`.
+  bool is_explicit_ctor = false;
   std::optional<std::string> nodiscard;
   std::optional<std::string> deprecated;
   std::optional<std::string> unknown_attr;
@@ -483,6 +685,14 @@ struct Field {
   bool is_no_unique_address;  // True if the field is [[no_unique_address]].
   bool is_bitfield;           // True if the field is a bitfield.
   bool is_inheritable;        // True if the field is inheritable.
+  // Source text of the default member initializer (e.g. `42` for
+  // `int x = 42;`), if the field has one.
+  std::optional<std::string> default_value;
+  // If the field is an anonymous struct or union (e.g. `union { int a; };`),
+  // the members of that struct or union, which can be accessed as if they were
+  // members of the enclosing record.  Their offsets are relative to the field.
+  std::vector<Field> anonymous_members;
+  bool is_anonymous_union;  // True if the field is an anonymous union.
 };
 
 inline std::ostream& operator<<(std::ostream& o, const Field& f) {
@@ -551,6 +761,35 @@ struct SizeAlign {
   int64_t alignment;
 };
 
+// The member functions used to drive an awaitable type (e.g. a coroutine
+// `Task<T>`) from Rust, as specified by the `crubit_awaitable` attribute.
+//
+// `poll` names a member function with the signature `bool()`, which resumes
+// the awaitable and returns whether it has completed. `get` names a member
+// function which returns the result of a completed awaitable.
+struct Awaitable {
+  llvm::json::Value ToJson() const;
+
+  std::string poll;
+  std::string get;
+};
+
+// The member functions and types used to visit the entries of a map-like type
+// (e.g. `absl::flat_hash_map<K, V>`) from Rust, as specified by the
+// `crubit_map_visitor` attribute.
+//
+// `begin` and `end` name the member functions returning the iterators over the
+// entries, which have `first` and `second` members. `key_type` and
+// `mapped_type` are the types of `first` and `second`.
+struct MapVisitor {
+  llvm::json::Value ToJson() const;
+
+  std::string begin;
+  std::string end;
+  MappedType key_type;
+  MappedType mapped_type;
+};
+
 // A record (struct, class, union).
 struct Record {
   llvm::json::Value ToJson() const;
@@ -622,9 +861,36 @@ struct Record {
   // * https://en.cppreference.com/w/cpp/language/aggregate_initialization
   bool is_aggregate = false;
 
+  // Whether this type has `mutable` fields, including the fields of its bases
+  // and of its fields of class type.
+  bool has_mutable_fields = false;
+
   // It is an anoymous record with a typedef name.
   bool is_anon_record_with_typedef = false;
 
+  // Set if this record is annotated with `crubit_awaitable`.
+  std::optional<Awaitable> awaitable;
+
+  // Set if this record is annotated with `crubit_map_visitor`.
+  std::optional<MapVisitor> map_visitor;
+
+  // Set if this record is an instantiation of `std::vector<T>` (with the
+  // default allocator): the type of its elements, `T`.
+  std::optional<MappedType> std_vector_element_type;
+
+  // The contents of the Rust file named by the `crubit_extra_rust_impl`
+  // attribute, which are spliced into the bindings next to the record.
+  std::optional<std::string> extra_rust_impl;
+
+  // Set if this record is annotated with `crubit_opaque`: the bindings don't
+  // expose its fields, constructors or layout, and it can only be used behind
+  // pointers and references.
+  bool is_opaque = false;
+
+  // The previous name of the record, as specified by the `crubit_renamed_from`
+  // attribute.
+  std::optional<std::string> renamed_from;
+
   // True when this record is created from an explicit class template
   // instantiation definition (which is also what cc_template!{} macro results
   // in).
@@ -664,6 +930,12 @@ struct Enum {
   MappedType underlying_type;
   std::optional<std::vector<Enumerator>> enumerators;
   std::optional<std::string> unknown_attr;
+  // Set if this enum is annotated with `crubit_rust_enum`, and should be
+  // bound to a Rust `enum` rather than to a newtype struct.
+  bool is_rust_enum = false;
+  // The previous name of the enum, as specified by the `crubit_renamed_from`
+  // attribute.
+  std::optional<std::string> renamed_from;
   std::optional<ItemId> enclosing_item_id;
 };
 
@@ -681,6 +953,9 @@ struct TypeAlias {
   std::optional<std::string> doc_comment;
   std::optional<std::string> unknown_attr;
   MappedType underlying_type;
+  // The previous name of the type alias, as specified by the
+  // `crubit_renamed_from` attribute.
+  std::optional<std::string> renamed_from;
   std::string source_loc;
   std::optional<ItemId> enclosing_item_id;
 };
@@ -689,6 +964,30 @@ inline std::ostream& operator<<(std::ostream& o, const TypeAlias& t) {
   return o << std::string(llvm::formatv("{0:2}", t.ToJson()));
 }
 
+// A global variable, a static data member, or an object-like macro whose value
+// is an integer or `bool` literal. Macros don't have a `mangled_name`.
+struct GlobalVar {
+  llvm::json::Value ToJson() const;
+
+  Identifier identifier;
+  ItemId id;
+  BazelLabel owning_target;
+  std::optional<std::string> doc_comment;
+  std::string mangled_name;
+  MappedType type;
+  // The value of the variable, if it is a constant of integral type whose
+  // initializer can be evaluated at compile time.
+  std::optional<IntegerConstant> value;
+  std::string source_loc;
+  std::optional<ItemId> enclosing_item_id;
+  // Whether this is an object-like macro, rather than a variable.
+  bool is_macro = false;
+};
+
+inline std::ostream& operator<<(std::ostream& o, const GlobalVar& v) {
+  return o << std::string(llvm::formatv("{0:2}", v.ToJson()));
+}
+
 // An error that stores its format string as well as the formatted message.
 struct FormattedError {
   llvm::json::Value ToJson() const;
@@ -810,8 +1109,8 @@ struct IR {
   BazelLabel current_target;
 
   using Item = std::variant<Func, Record, IncompleteRecord, Enum, TypeAlias,
-                            UnsupportedItem, Comment, Namespace, UseMod,
-                            TypeMapOverride>;
+                            GlobalVar, UnsupportedItem, Comment, Namespace,
+                            UseMod, TypeMapOverride>;
   std::vector<Item> items;
   std::vector<ItemId> top_level_item_ids;
   // Empty string signals that the bindings should be generated in the crate
@@ -11,7 +11,9 @@ use code_gen_utils::{make_rs_ident, NamespaceQualifier};
 use once_cell::unsync::OnceCell;
 use proc_macro2::{Ident, TokenStream};
 use quote::{quote, ToTokens};
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::value::RawValue;
+use std::cell::Cell;
 use std::collections::hash_map::{Entry, HashMap};
 use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
@@ -54,7 +56,24 @@ where
 
 /// Deserialize `IR` from JSON given as a reader.
 pub fn deserialize_ir<R: Read>(reader: R) -> Result<IR> {
-    let flat_ir = serde_json::from_reader(reader)?;
+    let flat_ir: FlatIR<Item> = serde_json::from_reader(reader)?;
+    Ok(make_ir(flat_ir.map_items(LazyItem::from)))
+}
+
+/// Deserialize `IR` from JSON given as a reader, without deserializing its
+/// items upfront.
+///
+/// Only the ids (and the few other fields that `IR` indexes) of the items are
+/// deserialized here. The rest of an item is kept as JSON until the item is
+/// first accessed, so that the items which are never visited by the bindings
+/// generator (e.g. most of the items of the dependencies) don't allocate their
+/// `Rc<...>` graphs. This reduces the peak memory usage for very large IR
+/// inputs.
+///
+/// Unlike `deserialize_ir`, this panics (when the item is accessed) if an
+/// item can't be deserialized.
+pub fn deserialize_ir_lazily<R: Read>(reader: R) -> Result<IR> {
+    let flat_ir: FlatIR = serde_json::from_reader(reader)?;
     Ok(make_ir(flat_ir))
 }
 
@@ -74,7 +93,7 @@ where
     make_ir(FlatIR {
         public_headers,
         current_target,
-        items,
+        items: items.into_iter().map(LazyItem::from).collect(),
         top_level_item_ids,
         crate_root_path,
         crubit_features: crubit_features
@@ -85,27 +104,16 @@ where
 }
 
 fn make_ir(flat_ir: FlatIR) -> IR {
-    let mut used_decl_ids = HashMap::new();
-    for item in &flat_ir.items {
-        if let Some(existing_decl) = used_decl_ids.insert(item.id(), item) {
-            panic!("Duplicate decl_id found in {:?} and {:?}", existing_decl, item);
+    let mut item_id_to_item_idx = HashMap::new();
+    for (idx, item) in flat_ir.items.iter().enumerate() {
+        if let Some(existing_idx) = item_id_to_item_idx.insert(item.summary.id, idx) {
+            panic!("Duplicate decl_id found in {:?} and {:?}", flat_ir.items[existing_idx], item);
         }
     }
-    let item_id_to_item_idx = flat_ir
-        .items
-        .iter()
-        .enumerate()
-        .map(|(idx, item)| (item.id(), idx))
-        .collect::<HashMap<_, _>>();
 
     let mut lifetimes: HashMap<LifetimeId, LifetimeName> = HashMap::new();
     for item in &flat_ir.items {
-        let lifetime_params = match item {
-            Item::Record(record) => &record.lifetime_params,
-            Item::Func(func) => &func.lifetime_params,
-            _ => continue,
-        };
-        for lifetime in lifetime_params {
+        for lifetime in &item.summary.lifetime_params {
             match lifetimes.entry(lifetime.id) {
                 Entry::Occupied(occupied) => {
                     panic!(
@@ -127,12 +135,8 @@ fn make_ir(flat_ir: FlatIR) -> IR {
     flat_ir
         .items
         .iter()
-        .filter_map(|item| match item {
-            Item::Namespace(ns) if ns.owning_target == flat_ir.current_target => {
-                Some((ns.canonical_namespace_id, ns.id))
-            }
-            _ => None,
-        })
+        .filter(|item| item.summary.owning_target.as_ref() == Some(&flat_ir.current_target))
+        .filter_map(|item| Some((item.summary.canonical_namespace_id?, item.summary.id)))
         .for_each(|(canonical_id, id)| {
             let current_count =
                 *namespace_id_to_number_of_reopened_namespaces.entry(canonical_id).or_insert(0);
@@ -140,11 +144,15 @@ fn make_ir(flat_ir: FlatIR) -> IR {
             namespace_id_to_number_of_reopened_namespaces.insert(canonical_id, current_count + 1);
         });
 
+    // Functions are only imported from the current target, so materializing
+    // them here doesn't cost much (and they are all visited by the bindings
+    // generator anyway).
     let mut function_name_to_functions = HashMap::<UnqualifiedIdentifier, Vec<Rc<Func>>>::new();
     flat_ir
         .items
         .iter()
-        .filter_map(|item| match item {
+        .filter(|item| item.summary.kind == ItemKind::Func)
+        .filter_map(|item| match item.get() {
             Item::Func(func) => Some(func),
             _ => None,
         })
@@ -275,7 +283,7 @@ impl Debug for Operator {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct ItemId(usize);
 
@@ -291,8 +299,15 @@ impl ToTokens for ItemId {
     }
 }
 
+/// Formats the ID as the number used for it in the IR JSON.
+impl Display for ItemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// A Bazel label, e.g. `//foo:bar`.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct BazelLabel(pub Rc<str>);
 
@@ -418,6 +433,12 @@ pub struct InstanceMethodMetadata {
     pub reference: ReferenceQualification,
     pub is_const: bool,
     pub is_virtual: bool,
+    /// Whether the method is pure virtual (`= 0`), in which case it may not
+    /// have a definition.
+    pub is_pure_virtual: bool,
+    /// Whether the method is virtual, but can't be overridden, because it is
+    /// `final` or a member of a `final` class.
+    pub is_final: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize)]
@@ -439,9 +460,27 @@ pub struct FuncParam {
     /// parameters in ways that may affect interop, we default-closed and
     /// do not expose functions with unknown attributes.
     ///
-    /// One notable example is `lifetimebound`, which we might expect to map
-    /// to Rust lifetimes.
+    /// (`lifetimebound` is understood: it is reflected in the lifetimes of the
+    /// return type.)
     pub unknown_attr: Option<Rc<str>>,
+    /// The source text of the default argument of the parameter (e.g. `3` for
+    /// `int b = 3`), if any.
+    pub default_argument: Option<Rc<str>>,
+}
+
+/// A pointer parameter and a length parameter that together describe a
+/// buffer, as specified by the `crubit_buffer` attribute.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BufferParam {
+    /// The name of the pointer parameter.
+    pub ptr: Rc<str>,
+    /// The name of the length parameter.
+    pub len: Rc<str>,
+    /// Whether the function returns the number of bytes it wrote to the
+    /// buffer.
+    #[serde(default)]
+    pub returns_len: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize)]
@@ -453,6 +492,25 @@ pub struct Func {
     pub doc_comment: Option<Rc<str>>,
     pub return_type: MappedType,
     pub params: Vec<FuncParam>,
+    /// The pointer/length parameter pairs that are exposed as slices, as
+    /// specified by the `crubit_buffer` attribute.
+    pub buffer_params: Vec<BufferParam>,
+    /// The name of the pointer parameter whose value is returned in Rust
+    /// (wrapped in an `Option` or a `Result`), as specified by the
+    /// `crubit_out_param` attribute.
+    pub out_param: Option<Rc<str>>,
+    /// The symbol name of the thunk, as pinned by the `crubit_stable_thunk`
+    /// attribute. Pinned thunks are always generated, and their names don't
+    /// depend on the mangled name of the function.
+    pub stable_thunk_name: Option<Rc<str>>,
+    /// The previous name of the function, as specified by the
+    /// `crubit_renamed_from` attribute. A deprecated alias is generated under
+    /// that name.
+    pub renamed_from: Option<Rc<str>>,
+    /// Whether the function returns a reference to an object with static
+    /// storage duration, as specified by the `crubit_returns_static` attribute.
+    /// The reference is bound to a `&'static` Rust reference.
+    pub returns_static: bool,
     /// For tests and internal use only.
     ///
     /// Prefer to reconstruct the lifetime params from the parameter types, as
@@ -463,6 +521,15 @@ pub struct Func {
     pub member_func_metadata: Option<MemberFuncMetadata>,
     pub is_extern_c: bool,
     pub is_noreturn: bool,
+    /// Whether the function can't throw exceptions (e.g. because it is
+    /// `noexcept`, or because it is a destructor).
+    pub is_noexcept: bool,
+    /// Whether the function is `[[gnu::cold]]`.
+    pub is_cold: bool,
+    /// Whether the function is `[[gnu::noinline]]`.
+    pub is_noinline: bool,
+    /// Whether the function is a constructor marked `explicit`.
+    pub is_explicit_ctor: bool,
     /// The `[[nodiscard("...")]]` string. If `[[nodiscard]]`, then the empty
     /// string is used.
     pub nodiscard: Option<Rc<str>>,
@@ -481,6 +548,15 @@ pub struct Func {
     pub id: ItemId,
     pub enclosing_item_id: Option<ItemId>,
     pub adl_enclosing_record: Option<ItemId>,
+    /// The number of trailing parameters whose default arguments are used
+    /// instead of being passed from Rust.
+    ///
+    /// This is never set by the importer: `params` of such a `Func` have
+    /// already been truncated by the bindings generator, which uses it to
+    /// generate a wrapper for each number of arguments that the C++ function
+    /// can be called with.
+    #[serde(default)]
+    pub num_omitted_default_args: usize,
 }
 
 impl GenericItem for Func {
@@ -552,6 +628,17 @@ pub struct Field {
     // TODO(kinuko): Consider removing this, it is a duplicate of the same information
     // in `Record`.
     pub is_inheritable: bool,
+
+    /// The source text of the default member initializer of the field (e.g.
+    /// `42` for `int x = 42;`), if any.
+    pub default_value: Option<Rc<str>>,
+
+    /// If the field is an anonymous struct or union (e.g. `union { int a; };`),
+    /// the members of that struct or union, which C++ code can access as if they
+    /// were members of the enclosing record. Their offsets are relative to the
+    /// field.
+    pub anonymous_members: Vec<Field>,
+    pub is_anonymous_union: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize)]
@@ -626,6 +713,33 @@ pub struct SizeAlign {
     pub alignment: usize,
 }
 
+/// The member functions used to drive an awaitable type from Rust, as
+/// specified by the `crubit_awaitable` attribute.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Awaitable {
+    /// A member function `bool()` which resumes the awaitable, and returns
+    /// whether it has completed.
+    pub poll: Rc<str>,
+    /// A member function which returns the result of a completed awaitable.
+    pub get: Rc<str>,
+}
+
+/// The member functions and types used to visit the entries of a map-like
+/// type from Rust, as specified by the `crubit_map_visitor` attribute.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MapVisitor {
+    /// A member function returning an iterator to the first entry.
+    pub begin: Rc<str>,
+    /// A member function returning an iterator past the last entry.
+    pub end: Rc<str>,
+    /// The type of the `first` member of the entries.
+    pub key_type: MappedType,
+    /// The type of the `second` member of the entries.
+    pub mapped_type: MappedType,
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Record {
@@ -659,7 +773,24 @@ pub struct Record {
     pub is_abstract: bool,
     pub record_type: RecordType,
     pub is_aggregate: bool,
+    /// Whether the record has `mutable` fields, including the fields of its
+    /// bases and of its fields of class type.
+    pub has_mutable_fields: bool,
     pub is_anon_record_with_typedef: bool,
+    pub awaitable: Option<Awaitable>,
+    pub map_visitor: Option<MapVisitor>,
+    /// The type of the elements, if this is an instantiation of `std::vector`
+    /// (with the default allocator).
+    pub std_vector_element_type: Option<MappedType>,
+    /// The contents of the Rust file named by the `crubit_extra_rust_impl`
+    /// attribute.
+    pub extra_rust_impl: Option<Rc<str>>,
+    /// Whether the record is annotated with `crubit_opaque`, and can only be
+    /// used behind pointers and references.
+    pub is_opaque: bool,
+    /// The previous name of the record, as specified by the
+    /// `crubit_renamed_from` attribute.
+    pub renamed_from: Option<Rc<str>>,
     pub child_item_ids: Vec<ItemId>,
     pub enclosing_item_id: Option<ItemId>,
 }
@@ -692,7 +823,30 @@ impl Record {
     ///
     /// Described in more detail at: docs/unpin
     pub fn is_unpin(&self) -> bool {
-        self.is_trivial_abi
+        // The size of opaque records is unknown in Rust, so they can't be moved.
+        // Assigning through a `&mut` to a record whose tail padding may hold the
+        // fields of a derived class would overwrite them.
+        self.is_trivial_abi && !self.is_opaque && !self.may_reuse_tail_padding()
+    }
+
+    /// Returns true if the record may have tail padding, i.e. if its fields
+    /// (or, since their layout isn't known, its base classes) don't cover all
+    /// of its size.
+    pub fn has_tail_padding(&self) -> bool {
+        if self.is_derived_class {
+            // The layout of the base class subobjects isn't known.
+            return true;
+        }
+        let end_of_fields =
+            self.fields.iter().map(|field| field.offset + field.size).max().unwrap_or(0);
+        end_of_fields < self.size_align.size * 8
+    }
+
+    /// Returns true if C++ may place the fields of a derived class in the tail
+    /// padding of the record. This is never the case for `final` classes (or
+    /// unions).
+    pub fn may_reuse_tail_padding(&self) -> bool {
+        self.is_inheritable && self.has_tail_padding()
     }
 
     pub fn is_union(&self) -> bool {
@@ -701,6 +855,40 @@ impl Record {
             RecordType::Struct | RecordType::Class => false,
         }
     }
+
+    /// The name of the Rust module containing the bindings for the types
+    /// nested inside this record: the `snake_case` version of its Rust name.
+    ///
+    /// For example, the bindings for `SomeStruct::Inner` are
+    /// `some_struct::Inner`.
+    pub fn nested_items_module_name(&self) -> Rc<str> {
+        camel_case_to_snake_case(&self.rs_name).into()
+    }
+}
+
+/// Converts a `CamelCase` name into `snake_case` (e.g. `HTTPServer` into
+/// `http_server`).
+fn camel_case_to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut result = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_uppercase() {
+            result.push(c);
+            continue;
+        }
+        let previous = if i > 0 { Some(chars[i - 1]) } else { None };
+        let next = chars.get(i + 1);
+        let starts_word = match previous {
+            Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit() => true,
+            Some(p) if p.is_ascii_uppercase() => next.is_some_and(|n| n.is_ascii_lowercase()),
+            _ => false,
+        };
+        if starts_word {
+            result.push('_');
+        }
+        result.push(c.to_ascii_lowercase());
+    }
+    result
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize)]
@@ -719,6 +907,12 @@ pub struct Enum {
     pub enumerators: Option<Vec<Enumerator>>,
     /// A human-readable list of attributes that Crubit doesn't understand.
     pub unknown_attr: Option<Rc<str>>,
+    /// Whether the enum is annotated with `crubit_rust_enum`, and should be
+    /// bound to a Rust `enum` rather than to a newtype struct.
+    pub is_rust_enum: bool,
+    /// The previous name of the enum, as specified by the
+    /// `crubit_renamed_from` attribute.
+    pub renamed_from: Option<Rc<str>>,
     pub enclosing_item_id: Option<ItemId>,
 }
 
@@ -756,6 +950,9 @@ pub struct TypeAlias {
     /// A human-readable list of attributes that Crubit doesn't understand.
     pub unknown_attr: Option<Rc<str>>,
     pub underlying_type: MappedType,
+    /// The previous name of the type alias, as specified by the
+    /// `crubit_renamed_from` attribute.
+    pub renamed_from: Option<Rc<str>>,
     pub source_loc: Rc<str>,
     pub enclosing_item_id: Option<ItemId>,
 }
@@ -781,6 +978,49 @@ impl Display for TypeAlias {
     }
 }
 
+/// A global variable, a static data member, or an object-like macro whose
+/// value is an integer or `bool` literal. Macros don't have a `mangled_name`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GlobalVar {
+    pub identifier: Identifier,
+    pub id: ItemId,
+    pub owning_target: BazelLabel,
+    pub doc_comment: Option<Rc<str>>,
+    pub mangled_name: Rc<str>,
+    #[serde(rename = "type")]
+    pub type_: MappedType,
+    /// The value of the variable, if it is a constant of integral type whose
+    /// initializer can be evaluated at compile time.
+    pub value: Option<IntegerConstant>,
+    pub source_loc: Rc<str>,
+    pub enclosing_item_id: Option<ItemId>,
+    /// Whether this is an object-like macro, rather than a variable.
+    #[serde(default)]
+    pub is_macro: bool,
+}
+
+impl GenericItem for GlobalVar {
+    fn id(&self) -> ItemId {
+        self.id
+    }
+    fn debug_name(&self, _: &IR) -> Rc<str> {
+        self.identifier.identifier.clone()
+    }
+    fn source_loc(&self) -> Option<Rc<str>> {
+        Some(self.source_loc.clone())
+    }
+    fn unknown_attr(&self) -> Option<Rc<str>> {
+        None
+    }
+}
+
+impl Display for GlobalVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}, {})", self.identifier, self.owning_target, self.source_loc)
+    }
+}
+
 /// A wrapper type that does not contribute to equality or hashing. All
 /// instances are equal.
 #[derive(Clone, Copy, Default)]
@@ -974,6 +1214,7 @@ pub enum Item {
     Record(Rc<Record>),
     Enum(Rc<Enum>),
     TypeAlias(Rc<TypeAlias>),
+    GlobalVar(Rc<GlobalVar>),
     UnsupportedItem(Rc<UnsupportedItem>),
     Comment(Rc<Comment>),
     Namespace(Rc<Namespace>),
@@ -989,6 +1230,7 @@ macro_rules! forward_item {
             Item::Record($item_name) => $expr,
             Item::Enum($item_name) => $expr,
             Item::TypeAlias($item_name) => $expr,
+            Item::GlobalVar($item_name) => $expr,
             Item::UnsupportedItem($item_name) => $expr,
             Item::Comment($item_name) => $expr,
             Item::Namespace($item_name) => $expr,
@@ -1038,6 +1280,7 @@ impl Item {
             Item::Func(func) => func.enclosing_item_id,
             Item::Namespace(namespace) => namespace.enclosing_item_id,
             Item::TypeAlias(type_alias) => type_alias.enclosing_item_id,
+            Item::GlobalVar(var) => var.enclosing_item_id,
             Item::Comment(..) => None,
             Item::UnsupportedItem(..) => None,
             Item::UseMod(..) => None,
@@ -1062,6 +1305,7 @@ impl Item {
             Item::Record(record) => Some(&record.owning_target),
             Item::Enum(e) => Some(&e.owning_target),
             Item::TypeAlias(type_alias) => Some(&type_alias.owning_target),
+            Item::GlobalVar(var) => Some(&var.owning_target),
             Item::UnsupportedItem(..) => None,
             Item::Comment(..) => None,
             Item::Namespace(ns) => Some(&ns.owning_target),
@@ -1079,6 +1323,7 @@ impl Item {
             Item::Record(_) => true,
             Item::Enum(_) => true,
             Item::TypeAlias(_) => true,
+            Item::GlobalVar(_) => false,
             Item::UnsupportedItem(_) => false,
             Item::Comment(_) => false,
             Item::Namespace(_) => false,
@@ -1214,14 +1459,174 @@ impl<'de> serde::Deserialize<'de> for CrubitFeaturesIR {
     }
 }
 
+/// The kind (i.e. the variant) of an `Item`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize)]
+enum ItemKind {
+    Func,
+    IncompleteRecord,
+    Record,
+    Enum,
+    TypeAlias,
+    GlobalVar,
+    UnsupportedItem,
+    Comment,
+    Namespace,
+    UseMod,
+    TypeMapOverride,
+}
+
+/// The fields of an item that `IR` indexes upfront, without materializing the
+/// item (see `deserialize_ir_lazily`).
+#[derive(Debug, Clone)]
+struct ItemSummary {
+    kind: ItemKind,
+    id: ItemId,
+    owning_target: Option<BazelLabel>,
+    lifetime_params: Vec<LifetimeName>,
+    canonical_namespace_id: Option<ItemId>,
+}
+
+impl From<&Item> for ItemSummary {
+    fn from(item: &Item) -> Self {
+        let kind = match item {
+            Item::Func(_) => ItemKind::Func,
+            Item::IncompleteRecord(_) => ItemKind::IncompleteRecord,
+            Item::Record(_) => ItemKind::Record,
+            Item::Enum(_) => ItemKind::Enum,
+            Item::TypeAlias(_) => ItemKind::TypeAlias,
+            Item::GlobalVar(_) => ItemKind::GlobalVar,
+            Item::UnsupportedItem(_) => ItemKind::UnsupportedItem,
+            Item::Comment(_) => ItemKind::Comment,
+            Item::Namespace(_) => ItemKind::Namespace,
+            Item::UseMod(_) => ItemKind::UseMod,
+            Item::TypeMapOverride(_) => ItemKind::TypeMapOverride,
+        };
+        let lifetime_params = match item {
+            Item::Record(record) => record.lifetime_params.clone(),
+            Item::Func(func) => func.lifetime_params.clone(),
+            _ => vec![],
+        };
+        let canonical_namespace_id = match item {
+            Item::Namespace(ns) => Some(ns.canonical_namespace_id),
+            _ => None,
+        };
+        ItemSummary {
+            kind,
+            id: item.id(),
+            owning_target: item.owning_target().cloned(),
+            lifetime_params,
+            canonical_namespace_id,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemSummary {
+    fn deserialize<D>(deserializer: D) -> Result<ItemSummary, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // The fields of the item that are not listed here are skipped.
+        #[derive(Deserialize)]
+        struct Fields {
+            id: ItemId,
+            #[serde(default)]
+            owning_target: Option<BazelLabel>,
+            #[serde(default)]
+            lifetime_params: Vec<LifetimeName>,
+            #[serde(default)]
+            canonical_namespace_id: Option<ItemId>,
+        }
+        // Items are serialized as `{"<kind>": {<fields>}}`.
+        let mut entries = HashMap::<ItemKind, Fields>::deserialize(deserializer)?.into_iter();
+        let (Some((kind, fields)), None) = (entries.next(), entries.next()) else {
+            return Err(<D::Error as serde::de::Error>::custom("Expected exactly one item kind"));
+        };
+        Ok(ItemSummary {
+            kind,
+            id: fields.id,
+            owning_target: fields.owning_target,
+            lifetime_params: fields.lifetime_params,
+            canonical_namespace_id: fields.canonical_namespace_id,
+        })
+    }
+}
+
+/// An item of `FlatIR`. Items of an `IR` created by `deserialize_ir_lazily`
+/// are only deserialized from their JSON when they are first accessed.
+struct LazyItem {
+    summary: ItemSummary,
+    /// The JSON of the item, until the item is materialized.
+    json: Cell<Option<Box<RawValue>>>,
+    item: OnceCell<Item>,
+}
+
+impl LazyItem {
+    fn get(&self) -> &Item {
+        self.item.get_or_init(|| {
+            let json = self.json.take().expect("The JSON of an item is only taken once");
+            serde_json::from_str(json.get()).unwrap_or_else(|err| {
+                panic!("Couldn't deserialize item {:?}: {err}", self.summary.id)
+            })
+        })
+    }
+
+    fn get_mut(&mut self) -> &mut Item {
+        self.get();
+        self.item.get_mut().expect("`get` materializes the item")
+    }
+}
+
+impl From<Item> for LazyItem {
+    fn from(item: Item) -> Self {
+        LazyItem {
+            summary: ItemSummary::from(&item),
+            json: Cell::new(None),
+            item: OnceCell::with_value(item),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LazyItem {
+    fn deserialize<D>(deserializer: D) -> Result<LazyItem, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let json = Box::<RawValue>::deserialize(deserializer)?;
+        let summary = serde_json::from_str(json.get()).map_err(serde::de::Error::custom)?;
+        Ok(LazyItem { summary, json: Cell::new(Some(json)), item: OnceCell::new() })
+    }
+}
+
+impl Clone for LazyItem {
+    fn clone(&self) -> Self {
+        LazyItem::from(self.get().clone())
+    }
+}
+
+impl PartialEq for LazyItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl Eq for LazyItem {}
+
+impl Debug for LazyItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.get(), f)
+    }
+}
+
+/// The deserialized `IR`. `deserialize_ir` deserializes the items directly
+/// into `Item`s, and `deserialize_ir_lazily` into `LazyItem`s.
 #[derive(PartialEq, Eq, Clone, Deserialize)]
 #[serde(deny_unknown_fields, rename(deserialize = "IR"))]
-struct FlatIR {
+struct FlatIR<ItemT = LazyItem> {
     #[serde(default)]
     public_headers: Vec<HeaderName>,
     current_target: BazelLabel,
-    #[serde(default)]
-    items: Vec<Item>,
+    #[serde(default = "Vec::new")]
+    items: Vec<ItemT>,
     #[serde(default)]
     top_level_item_ids: Vec<ItemId>,
     #[serde(default)]
@@ -1230,6 +1635,19 @@ struct FlatIR {
     crubit_features: HashMap<BazelLabel, CrubitFeaturesIR>,
 }
 
+impl<ItemT> FlatIR<ItemT> {
+    fn map_items<NewItemT>(self, f: impl FnMut(ItemT) -> NewItemT) -> FlatIR<NewItemT> {
+        FlatIR {
+            public_headers: self.public_headers,
+            current_target: self.current_target,
+            items: self.items.into_iter().map(f).collect(),
+            top_level_item_ids: self.top_level_item_ids,
+            crate_root_path: self.crate_root_path,
+            crubit_features: self.crubit_features,
+        }
+    }
+}
+
 /// A custom debug impl that wraps the HashMap in rustfmt-friendly notation.
 ///
 /// See b/272530008.
@@ -1282,7 +1700,24 @@ pub struct IR {
 
 impl IR {
     pub fn items(&self) -> impl Iterator<Item = &Item> {
-        self.flat_ir.items.iter()
+        self.flat_ir.items.iter().map(LazyItem::get)
+    }
+
+    /// Returns the items owned by the current target.
+    ///
+    /// Unlike filtering `items()`, this doesn't materialize the items of the
+    /// other targets (see `deserialize_ir_lazily`).
+    pub fn current_target_items(&self) -> impl Iterator<Item = &Item> {
+        self.flat_ir
+            .items
+            .iter()
+            .filter(|item| item.summary.owning_target.as_ref() == Some(self.current_target()))
+            .map(LazyItem::get)
+    }
+
+    /// Returns the items of the given `kind`, without materializing the others.
+    fn items_of_kind(&self, kind: ItemKind) -> impl Iterator<Item = &Item> {
+        self.flat_ir.items.iter().filter(move |item| item.summary.kind == kind).map(LazyItem::get)
     }
 
     pub fn top_level_item_ids(&self) -> impl Iterator<Item = &ItemId> {
@@ -1290,7 +1725,7 @@ impl IR {
     }
 
     pub fn items_mut(&mut self) -> impl Iterator<Item = &mut Item> {
-        self.flat_ir.items.iter_mut()
+        self.flat_ir.items.iter_mut().map(LazyItem::get_mut)
     }
 
     pub fn public_headers(&self) -> impl Iterator<Item = &HeaderName> {
@@ -1298,35 +1733,35 @@ impl IR {
     }
 
     pub fn functions(&self) -> impl Iterator<Item = &Rc<Func>> {
-        self.items().filter_map(|item| match item {
+        self.items_of_kind(ItemKind::Func).filter_map(|item| match item {
             Item::Func(func) => Some(func),
             _ => None,
         })
     }
 
     pub fn records(&self) -> impl Iterator<Item = &Rc<Record>> {
-        self.items().filter_map(|item| match item {
+        self.items_of_kind(ItemKind::Record).filter_map(|item| match item {
             Item::Record(func) => Some(func),
             _ => None,
         })
     }
 
     pub fn unsupported_items(&self) -> impl Iterator<Item = &Rc<UnsupportedItem>> {
-        self.items().filter_map(|item| match item {
+        self.items_of_kind(ItemKind::UnsupportedItem).filter_map(|item| match item {
             Item::UnsupportedItem(unsupported_item) => Some(unsupported_item),
             _ => None,
         })
     }
 
     pub fn comments(&self) -> impl Iterator<Item = &Rc<Comment>> {
-        self.items().filter_map(|item| match item {
+        self.items_of_kind(ItemKind::Comment).filter_map(|item| match item {
             Item::Comment(comment) => Some(comment),
             _ => None,
         })
     }
 
     pub fn namespaces(&self) -> impl Iterator<Item = &Rc<Namespace>> {
-        self.items().filter_map(|item| match item {
+        self.items_of_kind(ItemKind::Namespace).filter_map(|item| match item {
             Item::Namespace(ns) => Some(ns),
             _ => None,
         })
@@ -1361,6 +1796,7 @@ impl IR {
             .items
             .get(idx)
             .unwrap_or_else(|| panic!("Couldn't find an item at idx {}", idx))
+            .get()
     }
 
     /// Returns whether `target` is the current target.
@@ -1456,8 +1892,13 @@ impl IR {
         self.function_name_to_functions.get(function_name).map_or([].iter(), |v| v.iter())
     }
 
+    /// Returns the namespaces enclosing `item`, and for type definitions nested
+    /// inside records, the enclosing records as well.
+    ///
+    /// The records enclosing other members (e.g. methods) are not included.
     pub fn namespace_qualifier(&self, item: &impl GenericItem) -> Result<NamespaceQualifier> {
         let mut namespaces = vec![];
+        let mut nested_records = vec![];
         let item: &Item = self.find_decl(item.id())?;
         let mut enclosing_item_id = item.enclosing_item_id();
         while let Some(parent_id) = enclosing_item_id {
@@ -1466,9 +1907,12 @@ impl IR {
                     namespaces.push(ns.name.identifier.clone());
                     enclosing_item_id = ns.enclosing_item_id;
                 }
-                // TODO(b/200067824): This can lead to bugs, if this is used without checking for a
-                // parent struct. This function will likely need to be expanded to navigate into
-                // records, as part of b/200067824.
+                Item::Record(record) if item.is_type_definition() => {
+                    ensure!(namespaces.is_empty(), "Found namespaces inside of a record");
+                    nested_records
+                        .push((record.cc_name.clone(), record.nested_items_module_name()));
+                    enclosing_item_id = record.enclosing_item_id;
+                }
                 Item::Record { .. } => {
                     ensure!(namespaces.is_empty(), "Found namespaces inside of a record");
                     break;
@@ -1478,7 +1922,9 @@ impl IR {
                 }
             }
         }
-        Ok(NamespaceQualifier::new(namespaces.into_iter().rev()))
+        namespaces.reverse();
+        nested_records.reverse();
+        Ok(NamespaceQualifier { namespaces, nested_records })
     }
 }
 
@@ -1504,6 +1950,16 @@ mod tests {
         assert_eq!(format!("{:?}", Identifier { identifier: "hello".into() }), "\"hello\"");
     }
 
+    #[test]
+    fn test_camel_case_to_snake_case() {
+        assert_eq!("outer", camel_case_to_snake_case("Outer"));
+        assert_eq!("some_struct", camel_case_to_snake_case("SomeStruct"));
+        assert_eq!("http_server", camel_case_to_snake_case("HTTPServer"));
+        assert_eq!("vec3_f", camel_case_to_snake_case("Vec3F"));
+        assert_eq!("some_struct", camel_case_to_snake_case("some_struct"));
+        assert_eq!("some_struct", camel_case_to_snake_case("Some_Struct"));
+    }
+
     #[test]
     fn test_unqualified_identifier_debug_print() {
         assert_eq!(
@@ -1556,6 +2012,77 @@ mod tests {
         assert_eq!(ir.crate_root_path().as_deref(), Some("__cc_template_instantiations_rs_api"));
     }
 
+    /// An IR with an item of the current target, an item without an owning
+    /// target and an item of another target. The item of the other target
+    /// can't be deserialized (because of the unknown field).
+    const LAZY_IR_INPUT: &str = r#"
+    {
+        "current_target": "//foo:bar",
+        "items": [
+            { "Namespace": {
+                "name": { "identifier": "ns" },
+                "id": 1,
+                "canonical_namespace_id": 1,
+                "unknown_attr": null,
+                "owning_target": "//foo:bar",
+                "enclosing_item_id": null,
+                "is_inline": false
+            }},
+            { "Comment": { "text": "comment", "id": 2 } },
+            { "IncompleteRecord": {
+                "cc_name": "Dep",
+                "rs_name": "Dep",
+                "id": 3,
+                "owning_target": "//foo:dep",
+                "unknown_attr": null,
+                "unknown_field": 42,
+                "record_type": "Struct",
+                "enclosing_item_id": null
+            }}
+        ],
+        "top_level_item_ids": [1, 2]
+    }
+    "#;
+
+    #[test]
+    fn test_lazy_ir_materializes_items_on_demand() {
+        let ir = deserialize_ir_lazily(LAZY_IR_INPUT.as_bytes()).unwrap();
+        assert!(ir.flat_ir.items.iter().all(|item| item.item.get().is_none()));
+
+        let current_target_items =
+            ir.current_target_items().map(|item| item.id()).collect::<Vec<_>>();
+        assert_eq!(current_target_items, vec![ItemId::new_for_testing(1)]);
+        let comments = ir.comments().map(|comment| comment.text.clone()).collect::<Vec<_>>();
+        assert_eq!(comments, vec![Rc::<str>::from("comment")]);
+        assert_eq!(ir.get_reopened_namespace_idx(ItemId::new_for_testing(1)).unwrap(), 0);
+
+        // The item of the other target was never accessed, so it was never
+        // deserialized (which would fail).
+        assert!(ir.flat_ir.items[2].item.get().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Couldn't deserialize item ItemId(3)")]
+    fn test_lazy_ir_invalid_item_panics_when_accessed() {
+        let ir = deserialize_ir_lazily(LAZY_IR_INPUT.as_bytes()).unwrap();
+        ir.find_untyped_decl(ItemId::new_for_testing(3));
+    }
+
+    #[test]
+    fn test_eager_ir_invalid_item() {
+        let err = deserialize_ir(LAZY_IR_INPUT.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("unknown field `unknown_field`"), "{err}");
+    }
+
+    #[test]
+    fn test_lazy_ir_same_as_eager_ir() {
+        let input = LAZY_IR_INPUT.replace(r#""unknown_field": 42,"#, "");
+        let eager_ir = deserialize_ir(input.as_bytes()).unwrap();
+        let lazy_ir = deserialize_ir_lazily(input.as_bytes()).unwrap();
+        assert_eq!(lazy_ir.flat_ir, eager_ir.flat_ir);
+        assert_eq!(lazy_ir.flat_ir_debug_print(), eager_ir.flat_ir_debug_print());
+    }
+
     #[test]
     fn test_bazel_label_target() {
         let label: BazelLabel = "//foo:bar".into();
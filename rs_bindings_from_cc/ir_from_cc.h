@@ -40,6 +40,7 @@ struct IrFromCcOptions final {
   absl::Span<const std::string> extra_instantiations = {};
   absl::flat_hash_map<BazelLabel, absl::flat_hash_set<std::string>>
       crubit_features = {};
+  bool convert_string_views = false;
 
   // Not an argument, just here to prevent the options struct from being
   // copied/moved with nontrivial lifetime implications.
@@ -72,6 +73,9 @@ static_assert(std::is_aggregate_v<IrFromCcOptions>);
 // * `extra_instantiations`: names of full C++ class template specializations
 //   to instantiate and generate bindings from.
 // * `crubit_features`: The set of Crubit features to enable for each target.
+// * `convert_string_views`: Whether `std::string_view` and `absl::string_view`
+//   parameters and return values are converted into Rust byte slices (rather
+//   than use the bindings of the string view classes).
 //
 absl::StatusOr<IR> IrFromCc(IrFromCcOptions options);
 
@@ -66,6 +66,7 @@ fn test_function() {
                         },
                         identifier: "a",
                         unknown_attr: None,
+                        default_argument: None,
                     },
                     FuncParam {
                         type_: MappedType {
@@ -85,13 +86,23 @@ fn test_function() {
                         },
                         identifier: "b",
                         unknown_attr: None,
+                        default_argument: None,
                     },
                 ],
+                buffer_params: [],
+                out_param: None,
+                stable_thunk_name: None,
+                renamed_from: None,
+                returns_static: false,
                 lifetime_params: [],
                 is_inline: false,
                 member_func_metadata: None,
                 is_extern_c: false,
                 is_noreturn: false,
+                is_noexcept: false,
+                is_cold: false,
+                is_noinline: false,
+                is_explicit_ctor: false,
                 nodiscard: None,
                 deprecated: None,
                 unknown_attr: None,
@@ -301,6 +312,206 @@ fn test_function_with_custom_calling_convention() {
     );
 }
 
+#[test]
+fn test_function_noexcept() {
+    let ir = ir_from_cc(
+        r#"
+        void MayThrow();
+        void NoThrow() noexcept;
+        struct S { ~S(); };"#,
+    )
+    .unwrap();
+    assert!(!retrieve_func(&ir, "MayThrow").is_noexcept);
+    assert!(retrieve_func(&ir, "NoThrow").is_noexcept);
+    let destructor = ir.functions().find(|f| f.name == UnqualifiedIdentifier::Destructor).unwrap();
+    assert!(destructor.is_noexcept);
+}
+
+#[test]
+fn test_function_cold_and_noinline() {
+    let ir = ir_from_cc(
+        r#"
+        [[gnu::cold]] void Cold();
+        [[gnu::noinline]] void NoInline();
+        void Plain();"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "Cold", ...
+                is_cold: true,
+                is_noinline: false, ...
+                unknown_attr: None, ...
+            }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "NoInline", ...
+                is_cold: false,
+                is_noinline: true, ...
+                unknown_attr: None, ...
+            }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "Plain", ...
+                is_cold: false,
+                is_noinline: false, ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_explicit_constructor() {
+    let ir = ir_from_cc(
+        r#"
+        struct S final {
+          explicit S(int);
+          S(float);
+        };"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: Constructor, ...
+                mangled_name: "_ZN1SC1Ei", ...
+                is_explicit_ctor: true, ...
+            }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: Constructor, ...
+                mangled_name: "_ZN1SC1Ef", ...
+                is_explicit_ctor: false, ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_function_with_auto_return_type() {
+    let ir = ir_from_cc(
+        r#"
+        inline auto f() { return 42; }
+        inline const auto g() { return 42; }
+        "#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "f", ...
+                return_type: MappedType {
+                    rs_type: RsType {
+                        name: Some("::core::ffi::c_int"), ...
+                    },
+                    cc_type: CcType {
+                        name: Some("int"),
+                        is_const: false, ...
+                    },
+                }, ...
+            }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "g", ...
+                return_type: MappedType {
+                    rs_type: RsType {
+                        name: Some("::core::ffi::c_int"), ...
+                    },
+                    cc_type: CcType {
+                        name: Some("int"),
+                        is_const: true, ...
+                    },
+                }, ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_function_with_decltype_auto_return_type() {
+    let ir = ir_from_cc(
+        r#" #pragma clang lifetime_elision
+        inline decltype(auto) f(int& x) { return (x); }
+        "#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "f", ...
+                return_type: MappedType {
+                    rs_type: RsType {
+                        name: Some("&mut"),
+                        lifetime_args: [LifetimeId(...)],
+                        type_args: [RsType {
+                            name: Some("::core::ffi::c_int"), ...
+                        }], ...
+                    },
+                    cc_type: CcType {
+                        name: Some("&"),
+                        is_const: false,
+                        type_args: [CcType {
+                            name: Some("int"),
+                            is_const: false, ...
+                        }], ...
+                    },
+                }, ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_function_with_auto_return_type_deduced_to_record() {
+    let ir = ir_from_cc(
+        r#"
+        struct SomeStruct {};
+        inline auto MakeSomeStruct() { return SomeStruct{}; }
+        "#,
+    )
+    .unwrap();
+    let record_id = retrieve_record(&ir, "SomeStruct").id;
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "MakeSomeStruct", ...
+                return_type: MappedType {
+                    rs_type: RsType {
+                        name: None, ...
+                        decl_id: Some(ItemId(#record_id)),
+                    },
+                    cc_type: CcType {
+                        name: None, ...
+                        decl_id: Some(ItemId(#record_id)),
+                    },
+                }, ...
+            }
+        }
+    );
+}
+
 #[test]
 fn test_functions_from_dependency_are_not_emitted() -> Result<()> {
     let ir = ir_from_cc_dependency("int Add(int a, int b);", "int Multiply(int a, int b);")?;
@@ -478,6 +689,121 @@ fn test_bitfields() {
     );
 }
 
+#[test]
+fn test_anonymous_struct_and_union_members() {
+    let ir = ir_from_cc(
+        r#"
+        struct S {
+            int first;
+            union {
+                int a;
+                struct {
+                    short b;
+                    short c;
+                };
+            };
+        };"#,
+    )
+    .unwrap();
+
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Record {
+                rs_name: "S", ...
+                fields: [
+                    Field { identifier: Some("first"), ... anonymous_members: [], ... },
+                    Field {
+                        identifier: None, ...
+                        offset: 32, ...
+                        anonymous_members: [
+                            Field {
+                                identifier: Some("a"), ...
+                                offset: 0, ...
+                                anonymous_members: [],
+                                is_anonymous_union: false,
+                            },
+                            Field {
+                                identifier: None, ...
+                                offset: 0, ...
+                                anonymous_members: [
+                                    Field { identifier: Some("b"), ... offset: 0, ... },
+                                    Field { identifier: Some("c"), ... offset: 16, ... },
+                                ],
+                                is_anonymous_union: false,
+                            },
+                        ],
+                        is_anonymous_union: true,
+                    },
+                ] ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_func_param_default_arguments() {
+    let ir = ir_from_cc("int f(int a, int b = 3, int c = 1 + 2);").unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "f", ...
+                params: [
+                    FuncParam {
+                        ... identifier: "a", ...
+                        default_argument: None,
+                    },
+                    FuncParam {
+                        ... identifier: "b", ...
+                        default_argument: Some("3"),
+                    },
+                    FuncParam {
+                        ... identifier: "c", ...
+                        default_argument: Some("1 + 2"),
+                    },
+                ], ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_field_default_values() {
+    let ir = ir_from_cc(
+        r#"
+        struct SomeStruct {
+            int with_default = 42;
+            int with_brace_default{1 + 2};
+            int without_default;
+        };"#,
+    )
+    .unwrap();
+
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Record {
+                rs_name: "SomeStruct", ...
+                fields: [
+                    Field {
+                        identifier: Some("with_default"), ...
+                        default_value: Some("42"), ...
+                    },
+                    Field {
+                        identifier: Some("with_brace_default"), ...
+                        default_value: Some("{1 + 2}"), ...
+                    },
+                    Field {
+                        identifier: Some("without_default"), ...
+                        default_value: None, ...
+                    },
+                ] ...
+            }
+        }
+    );
+}
+
 /// This is a regression test for b/270748945.
 #[test]
 fn test_struct_with_packed_attribute() {
@@ -576,7 +902,7 @@ fn test_struct_with_unnamed_struct_and_union_members() {
     .unwrap();
 
     // TODO(b/200067824): `type_` should not be `Err(...)` in the expectations below
-    // / we should support nested structs eventually.
+    // / we should support anonymous structs eventually.
     assert_ir_matches!(
         ir,
         quote! {
@@ -1046,6 +1372,7 @@ fn test_typedef() -> Result<()> {
             doc_comment: Some("Doc comment for MyTypedefDecl."),
             unknown_attr: None,
             underlying_type: #int,
+            renamed_from: None,
             source_loc: ...
             enclosing_item_id: None,
           }
@@ -1061,6 +1388,7 @@ fn test_typedef() -> Result<()> {
             doc_comment: Some("Doc comment for MyTypeAliasDecl."),
             unknown_attr: None,
             underlying_type: #int,
+            renamed_from: None,
             source_loc: ...,
             enclosing_item_id: None,
           }
@@ -1120,44 +1448,224 @@ fn test_typedef_duplicate() -> Result<()> {
 }
 
 #[test]
-fn test_typedef_of_full_template_specialization() -> Result<()> {
+fn test_global_var() -> Result<()> {
     let ir = ir_from_cc(
-        r#" #pragma clang lifetime_elision
-            namespace test_namespace_bindings {
-                // Doc comment of MyStruct template.
-                template <typename T>
-                struct MyStruct {
-                  // Doc comment of GetValue method.
-                  const T& GetValue() const { return value; }
-
-                  // Doc comment of `value` field.
-                  T value;
-                };
+        r#"
+            // Doc comment for kGlobal.
+            extern int kGlobal;
 
-                // Doc comment of MyTypeAlias.
-                using MyTypeAlias = MyStruct<int>;
-            }"#,
+            struct S {
+                static constexpr int kMax = -5;
+            };
+        "#,
     )?;
-    // Instantiation of MyStruct<int> specialization:
     assert_ir_matches!(
         ir,
         quote! {
-          Record {
-            rs_name: "__CcTemplateInstN23test_namespace_bindings8MyStructIiEE", ...
-            cc_name: "test_namespace_bindings::MyStruct<int>", ...
-            owning_target: BazelLabel("//test:testing_target"), ...
-            doc_comment: Some("Doc comment of MyStruct template."), ...
-            fields: [Field {
-                identifier: Some("value"), ...
-                doc_comment: Some("Doc comment of `value` field."), ...
-                type_: Ok(MappedType {
-                    rs_type: RsType { name: Some("::core::ffi::c_int"), ... },
-                    cc_type: CcType { name: Some("int"), ... },
-                }),
-                access: Public,
-                offset: 0, ...
-            }], ...
-            enclosing_item_id: None, ...
+          GlobalVar {
+            identifier: "kGlobal",
+            id: ItemId(...),
+            owning_target: BazelLabel("//test:testing_target"),
+            doc_comment: Some("Doc comment for kGlobal."),
+            mangled_name: "kGlobal",
+            type_: MappedType {
+              rs_type: RsType { name: Some("::core::ffi::c_int"), ... },
+              cc_type: CcType { name: Some("int"), is_const: false, ... },
+            },
+            value: None,
+            source_loc: ...,
+            enclosing_item_id: None,
+            is_macro: false,
+          }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+          GlobalVar {
+            identifier: "kMax",
+            ...
+            mangled_name: "_ZN1S4kMaxE",
+            type_: MappedType {
+              ...
+              cc_type: CcType { name: Some("int"), is_const: true, ... },
+            },
+            value: Some(IntegerConstant {
+              is_negative: true,
+              wrapped_value: 18446744073709551611,
+            }),
+            source_loc: ...,
+            enclosing_item_id: Some(ItemId(...)),
+            is_macro: false,
+          }
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_global_var_unsupported() -> Result<()> {
+    let ir = ir_from_cc(
+        r#"
+            thread_local int kThreadLocal;
+            static int kInternal;
+            extern int& kReference;
+        "#,
+    )?;
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "kThreadLocal", ...
+            errors: [FormattedError {
+                ... message: "thread_local variables are not supported", ...
+            }], ...
+        }}
+    );
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "kInternal", ...
+            errors: [FormattedError {
+                ... message: "Variables with internal linkage are only supported if they are compile-time constants of integral type", ...
+            }], ...
+        }}
+    );
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "kReference", ...
+            errors: [FormattedError {
+                ... message: "Variables of reference type are not supported yet", ...
+            }], ...
+        }}
+    );
+    Ok(())
+}
+
+#[test]
+fn test_macro_constants() -> Result<()> {
+    let ir = ir_from_cc(
+        r#"
+            #define kAnswer 42
+            #define kMin (-1)
+            #define kHighBit 0x80000000
+            #define kLarge 5000000000
+            #define kEnabled true
+            #define kNotALiteral kAnswer + 1
+            #define kString "hello"
+            #define kFunctionLike(x) 42
+        "#,
+    )?;
+    assert_ir_matches!(
+        ir,
+        quote! {
+          GlobalVar {
+            identifier: "kAnswer",
+            id: ItemId(...),
+            owning_target: BazelLabel("//test:testing_target"),
+            doc_comment: None,
+            mangled_name: "",
+            type_: MappedType {
+              rs_type: RsType { name: Some("::core::ffi::c_int"), ... },
+              cc_type: CcType { name: Some("int"), ... },
+            },
+            value: Some(IntegerConstant { is_negative: false, wrapped_value: 42 }),
+            source_loc: ...,
+            enclosing_item_id: None,
+            is_macro: true,
+          }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+          GlobalVar {
+            identifier: "kMin", ...
+            type_: MappedType { rs_type: RsType { name: Some("::core::ffi::c_int"), ... }, ... },
+            value: Some(IntegerConstant {
+              is_negative: true,
+              wrapped_value: 18446744073709551615,
+            }), ...
+          }
+        }
+    );
+    // Like in C++, unsuffixed hexadecimal literals which don't fit into `int`
+    // are unsigned.
+    assert_ir_matches!(
+        ir,
+        quote! {
+          GlobalVar {
+            identifier: "kHighBit", ...
+            type_: MappedType { rs_type: RsType { name: Some("::core::ffi::c_uint"), ... }, ... },
+            value: Some(IntegerConstant { is_negative: false, wrapped_value: 2147483648 }), ...
+          }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+          GlobalVar {
+            identifier: "kLarge", ...
+            type_: MappedType { rs_type: RsType { name: Some("::core::ffi::c_long"), ... }, ... },
+            value: Some(IntegerConstant { is_negative: false, wrapped_value: 5000000000 }), ...
+          }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+          GlobalVar {
+            identifier: "kEnabled", ...
+            type_: MappedType { rs_type: RsType { name: Some("bool"), ... }, ... },
+            value: Some(IntegerConstant { is_negative: false, wrapped_value: 1 }), ...
+          }
+        }
+    );
+    assert_ir_not_matches!(ir, quote! { GlobalVar { identifier: "kNotALiteral", ... } });
+    assert_ir_not_matches!(ir, quote! { GlobalVar { identifier: "kString", ... } });
+    assert_ir_not_matches!(ir, quote! { GlobalVar { identifier: "kFunctionLike", ... } });
+    Ok(())
+}
+
+#[test]
+fn test_typedef_of_full_template_specialization() -> Result<()> {
+    let ir = ir_from_cc(
+        r#" #pragma clang lifetime_elision
+            namespace test_namespace_bindings {
+                // Doc comment of MyStruct template.
+                template <typename T>
+                struct MyStruct {
+                  // Doc comment of GetValue method.
+                  const T& GetValue() const { return value; }
+
+                  // Doc comment of `value` field.
+                  T value;
+                };
+
+                // Doc comment of MyTypeAlias.
+                using MyTypeAlias = MyStruct<int>;
+            }"#,
+    )?;
+    // Instantiation of MyStruct<int> specialization:
+    assert_ir_matches!(
+        ir,
+        quote! {
+          Record {
+            rs_name: "__CcTemplateInstN23test_namespace_bindings8MyStructIiEE", ...
+            cc_name: "test_namespace_bindings::MyStruct<int>", ...
+            owning_target: BazelLabel("//test:testing_target"), ...
+            doc_comment: Some("Doc comment of MyStruct template."), ...
+            fields: [Field {
+                identifier: Some("value"), ...
+                doc_comment: Some("Doc comment of `value` field."), ...
+                type_: Ok(MappedType {
+                    rs_type: RsType { name: Some("::core::ffi::c_int"), ... },
+                    cc_type: CcType { name: Some("int"), ... },
+                }),
+                access: Public,
+                offset: 0, ...
+            }], ...
+            enclosing_item_id: None, ...
           }
         }
     );
@@ -1543,6 +2051,7 @@ fn test_subst_template_type_parm_pack_type() -> Result<()> {
                         },
                         identifier: "__my_args_0",
                         unknown_attr: None,
+                        default_argument: None,
                     },
                     FuncParam {
                         type_: MappedType {
@@ -1551,6 +2060,7 @@ fn test_subst_template_type_parm_pack_type() -> Result<()> {
                         },
                         identifier: "__my_args_1",
                         unknown_attr: None,
+                        default_argument: None,
                     },
                 ], ...
             }
@@ -1672,6 +2182,7 @@ fn test_fully_instantiated_template_in_function_param_type() -> Result<()> {
                 },
                 identifier: "my_param",
                 unknown_attr: None,
+                default_argument: None,
             }], ...
             is_inline: false, ...
             member_func_metadata: None, ...
@@ -1739,6 +2250,9 @@ fn test_fully_instantiated_template_in_public_field() -> Result<()> {
                        is_no_unique_address: false,
                        is_bitfield: false,
                        is_inheritable: true,
+                       default_value: None,
+                       anonymous_members: [],
+                       is_anonymous_union: false,
                    }], ...
                }
         }
@@ -1780,6 +2294,9 @@ fn test_fully_instantiated_template_in_private_field() -> Result<()> {
                        is_no_unique_address: false,
                        is_bitfield: false,
                        is_inheritable: false,
+                       default_value: None,
+                       anonymous_members: [],
+                       is_anonymous_union: false,
                    }], ...
                }
         }
@@ -2197,6 +2714,7 @@ fn test_well_known_types_check_namespaces() -> Result<()> {
               },
               identifier: "i",
               unknown_attr: None,
+              default_argument: None,
              }], ...
           }
         }
@@ -2331,30 +2849,30 @@ fn test_ignore_union_typedef_from_decl_context_redecl_from_multiple_targets() {
 }
 
 #[test]
-fn test_records_nested_in_records_not_supported_yet() {
+fn test_records_nested_in_records() {
     let ir = ir_from_cc("struct SomeStruct { struct NestedStruct {}; };").unwrap();
+    let outer_id = retrieve_record(&ir, "SomeStruct").id;
+    let nested = retrieve_record(&ir, "NestedStruct");
+    assert_eq!(nested.enclosing_item_id, Some(outer_id));
     assert_ir_matches!(
         ir,
-        quote! { UnsupportedItem {
-            name: "SomeStruct::NestedStruct",
-            errors: [FormattedError {
-                ..., message: "Nested classes are not supported yet", ...
-            }], ...
+        quote! { Record {
+            rs_name: "NestedStruct",
+            cc_name: "NestedStruct", ...
         }}
     );
 }
 
 #[test]
 fn test_record_with_unsupported_field_type() -> Result<()> {
-    // Using a nested struct because it's currently not supported.
+    // Using a packed struct because it's currently not supported.
     // But... any other unsupported type would also work for this test.
     let ir = ir_from_cc(
         r#"
+        struct __attribute__((packed)) PackedStruct {};
         struct StructWithUnsupportedField {
-          struct NestedStruct {};
-
           // Doc comment for `my_field`.
-          NestedStruct my_field;
+          PackedStruct my_field;
         };
     "#,
     )?;
@@ -2369,7 +2887,7 @@ fn test_record_with_unsupported_field_type() -> Result<()> {
                    identifier: Some("my_field"),
                    doc_comment: Some("Doc comment for `my_field`."),
                    type_: Err(
-                       "Unsupported type 'struct StructWithUnsupportedField::NestedStruct': No generated bindings found for 'NestedStruct'",
+                       "Unsupported type 'struct PackedStruct': No generated bindings found for 'PackedStruct'",
                    ),
                    access: Public,
                    offset: 0,
@@ -2378,6 +2896,9 @@ fn test_record_with_unsupported_field_type() -> Result<()> {
                    is_no_unique_address: false,
                    is_bitfield: false,
                    is_inheritable: false,
+                   default_value: None,
+                   anonymous_members: [],
+                   is_anonymous_union: false,
                }],
                ...
                 size_align: SizeAlign {
@@ -2392,9 +2913,9 @@ fn test_record_with_unsupported_field_type() -> Result<()> {
         ir,
         quote! {
             UnsupportedItem {
-                name: "StructWithUnsupportedField::NestedStruct",
+                name: "PackedStruct",
                 errors: [FormattedError {
-                    ..., message: "Nested classes are not supported yet", ...
+                    ..., message: "Records with packed layout are not supported", ...
                 }], ...
             }
         }
@@ -2405,24 +2926,22 @@ fn test_record_with_unsupported_field_type() -> Result<()> {
 #[test]
 fn test_record_with_unsupported_base() -> Result<()> {
     let ir = ir_from_cc(
-        r#" struct OuterStruct {
-              struct NestedStruct {
-                // Having a field here avoids empty base class optimization
-                // and forces `derived_field` to be at a non-zero offset.
-                // See also: https://en.cppreference.com/w/cpp/language/ebo
-                char nested_field;
-              };
+        r#" struct __attribute__((packed)) PackedStruct {
+              // Having a field here avoids empty base class optimization
+              // and forces `derived_field` to be at a non-zero offset.
+              // See also: https://en.cppreference.com/w/cpp/language/ebo
+              char packed_field;
             };
 
-            // Using a nested struct as a base class because nested structs are
+            // Using a packed struct as a base class because packed structs are
             // currently unsupported.  But... any other unsupported base class
             // would also work for this test.
-            struct DerivedClass : public OuterStruct::NestedStruct {
+            struct DerivedClass : public PackedStruct {
               int derived_field;
             }; "#,
     )?;
     // Verify that `unambiguous_public_bases` are empty (instead of containing a
-    // dangling `ItemId` of the `NestedStruct` (which got imported as
+    // dangling `ItemId` of the `PackedStruct` (which got imported as
     // `UnsupportedItem` rather than as a `Record`).
     assert_ir_matches!(
         ir,
@@ -2436,7 +2955,7 @@ fn test_record_with_unsupported_base() -> Result<()> {
               defining_target: None,
               unknown_attr: None,
               doc_comment: Some(...),
-              source_loc: "Generated from: google3/ir_from_cc_virtual_header.h;l=15",
+              source_loc: "Generated from: google3/ir_from_cc_virtual_header.h;l=13",
               unambiguous_public_bases: [],
               fields: [Field {
                   identifier: Some("derived_field"), ...
@@ -2452,16 +2971,16 @@ fn test_record_with_unsupported_base() -> Result<()> {
            }
         }
     );
-    // Verify that the NestedStruct is unsupported (this is mostly verification
+    // Verify that the PackedStruct is unsupported (this is mostly verification
     // that the test input correctly sets up the test scenario;  the real
     // verification is above).
     assert_ir_matches!(
         ir,
         quote! {
            UnsupportedItem {
-                name: "OuterStruct::NestedStruct",
+                name: "PackedStruct",
                 errors: [FormattedError {
-                    ..., message: "Nested classes are not supported yet", ...
+                    ..., message: "Records with packed layout are not supported", ...
                 }], ...
            }
         }
@@ -2471,40 +2990,36 @@ fn test_record_with_unsupported_base() -> Result<()> {
 
 #[test]
 fn test_do_not_import_static_member_functions_when_record_not_supported_yet() {
-    // only using nested struct as an example of a record we cannot import yet.
+    // only using packed struct as an example of a record we cannot import yet.
     let ir = ir_from_cc(
         "
-        struct SomeStruct {
-          struct NestedStruct {
-            static void StaticMemberFunction();
-          };
+        struct __attribute__((packed)) PackedStruct {
+          static void StaticMemberFunction();
         };",
     )
     .unwrap();
     assert_ir_matches!(
         ir,
         quote! { UnsupportedItem {
-          name: "SomeStruct::NestedStruct::StaticMemberFunction" ...
+          name: "PackedStruct::StaticMemberFunction" ...
         }}
     );
 }
 
 #[test]
 fn test_do_not_import_nonstatic_member_functions_when_record_not_supported_yet() {
-    // only using nested struct as an example of a record we cannot import yet.
+    // only using packed struct as an example of a record we cannot import yet.
     let ir = ir_from_cc(
         "
-        struct SomeStruct {
-          struct NestedStruct {
-            void NonStaticMemberFunction();
-          };
+        struct __attribute__((packed)) PackedStruct {
+          void NonStaticMemberFunction();
         };",
     )
     .unwrap();
     assert_ir_matches!(
         ir,
         quote! { UnsupportedItem {
-          name: "SomeStruct::NestedStruct::NonStaticMemberFunction" ...
+          name: "PackedStruct::NonStaticMemberFunction" ...
         }}
     );
 }
@@ -2561,6 +3076,7 @@ fn test_integer_typedef_usage() -> Result<()> {
             },
             identifier: "my_typedef",
             unknown_attr: None,
+            default_argument: None,
            }], ...
         } }
     );
@@ -2628,126 +3144,674 @@ fn test_class() {
 }
 
 #[test]
-fn test_struct_forward_declaration() {
-    let ir = ir_from_cc("struct Struct;").unwrap();
-    assert!(!ir.records().any(|r| r.rs_name.as_ref() == "Struct"));
-}
-
-#[test]
-fn test_struct_forward_declaration_in_namespace() -> Result<()> {
+fn test_std_array_type() {
     let ir = ir_from_cc(
         r#"
-        namespace MyNamespace {
-        struct FwdDeclared;
+        // We mock `std::array` because we can't include C++ standard library headers.
+        namespace std {
+        template <typename T, decltype(sizeof(0)) N>
+        struct array { T elems[N]; };
         }
-        "#,
-    )?;
-
-    assert_eq!(1, ir.namespaces().count());
-    let ns = ir.namespaces().next().unwrap();
-    assert_eq!("MyNamespace", ns.name.identifier.as_ref());
-    assert_eq!(1, ns.child_item_ids.len());
-
-    let ns_id = ns.id;
-    let child_id = ns.child_item_ids[0];
+        struct S {
+            std::array<int, 4> field;
+        };"#,
+    )
+    .unwrap();
     assert_ir_matches!(
         ir,
         quote! {
-            Namespace(Namespace {
-                name: "MyNamespace" ...
-                id: ItemId(#ns_id) ...
-                child_item_ids: [ItemId(#child_id)] ...
-                enclosing_item_id: None ...
-            }),
-            IncompleteRecord(IncompleteRecord {
-                cc_name: "FwdDeclared" ...
-                rs_name: "FwdDeclared" ...
-                id: ItemId(#child_id) ...
-                ...
-                enclosing_item_id: Some(ItemId(#ns_id)) ...
-            }),
+            Field {
+                identifier: Some("field"), ...
+                type_: Ok(MappedType {
+                    rs_type: RsType {
+                        name: Some("#array 4"), ...
+                        type_args: [RsType { name: Some("::core::ffi::c_int"), ... }], ...
+                    },
+                    cc_type: CcType {
+                        name: Some("#stdArray 4"), ...
+                        type_args: [CcType { name: Some("int"), ... }], ...
+                    },
+                }), ...
+            }
         }
     );
-
-    Ok(())
 }
 
 #[test]
-fn test_union() {
-    let ir = ir_from_cc("union SomeUnion { int first_field; int second_field; };").unwrap();
+fn test_std_optional_type() {
+    let ir = ir_from_cc(
+        r#"
+        // We mock `std::optional` because we can't include C++ standard library headers.
+        namespace std {
+        template <typename T>
+        class optional { bool engaged_; T value_; };
+        }
+        void Consume(std::optional<int> x);"#,
+    )
+    .unwrap();
     assert_ir_matches!(
         ir,
         quote! {
-            Record {
-                rs_name: "SomeUnion" ...
-                cc_name: "SomeUnion" ...
-                fields: [
-                    Field {
-                        identifier: Some("first_field"), ...
-                        type_: Ok(MappedType {
-                            rs_type : RsType { name : Some("::core::ffi::c_int"), ...},
-                            cc_type : CcType { name : Some ("int"), ...},
-                         }), ...
-                        offset: 0, ...
-                        size: 32, ...
-                        is_bitfield: false, ...
-                    },
-                    Field {
-                        identifier: Some("second_field"), ...
-                        type_: Ok(MappedType {
-                            rs_type : RsType { name : Some("::core::ffi::c_int"), ...},
-                            cc_type : CcType { name : Some ("int"), ...},
-                         }), ...
-                        offset: 0, ...
-                        size: 32, ...
-                        is_bitfield: false, ...
-                    },
-                ], ...
-                size_align: SizeAlign {
-                    size: 4,
-                    alignment: 4,
-                }, ...
-                record_type: Union, ...
+            Func {
+                name: "Consume", ...
+                params: [FuncParam {
+                    type_: MappedType {
+                        rs_type: RsType {
+                            name: Some("#stdOptional"), ...
+                            type_args: [RsType { name: Some("::core::ffi::c_int"), ... }], ...
+                        },
+                        cc_type: CcType {
+                            name: Some("#stdOptional"), ...
+                            type_args: [CcType { name: Some("int"), ... }], ...
+                        },
+                    }, ...
+                }], ...
             }
         }
     );
 }
 
 #[test]
-fn test_union_with_data_members_with_different_sizes() {
+fn test_initializer_list_type() {
     let ir = ir_from_cc(
         r#"
-    union MyUnion {
-      char first_field[56];
-      int second_field;
-    };
-  "#,
+        // We mock `std::initializer_list` because we can't include C++ standard library headers.
+        namespace std {
+        template <typename T>
+        class initializer_list { const T* begin_; decltype(sizeof(0)) size_; };
+        }
+        void Consume(std::initializer_list<int> x);"#,
     )
     .unwrap();
     assert_ir_matches!(
         ir,
         quote! {
-              Record { ...
-                rs_name: "MyUnion"...
-                fields: [
-                  Field {
-                    identifier: Some("first_field") ...
-                    offset: 0 ...
-                    size: 448 ...
-                  },
-                  Field {
-                    identifier: Some("second_field") ...
-                    offset: 0 ...
-                    size: 32 ...
-                  } ...
-                ] ...
-              }
+            Func {
+                name: "Consume", ...
+                params: [FuncParam {
+                    type_: MappedType {
+                        rs_type: RsType {
+                            name: Some("#initializerList"), ...
+                            type_args: [RsType { name: Some("::core::ffi::c_int"), ... }], ...
+                        },
+                        cc_type: CcType {
+                            name: Some("#initializerList"), ...
+                            type_args: [CcType { name: Some("int"), ... }], ...
+                        },
+                    }, ...
+                }], ...
+            }
         }
     );
 }
 
 #[test]
-fn test_member_function_params() {
+fn test_absl_duration_type() {
+    let ir = ir_from_cc(
+        r#"
+        // We mock `absl::Duration` because we can't include Abseil headers.
+        namespace absl {
+        class Duration { long long rep_hi_; unsigned rep_lo_; };
+        }
+        using MyDuration = absl::Duration;
+        void Sleep(MyDuration d);"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "Sleep", ...
+                params: [FuncParam {
+                    type_: MappedType {
+                        rs_type: RsType { name: Some("#abslDuration"), ... },
+                        cc_type: CcType { name: Some("absl::Duration"), ... },
+                    }, ...
+                }], ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_chrono_duration_type() {
+    let ir = ir_from_cc(
+        r#"
+        // We mock `std::chrono` because we can't include standard headers.
+        namespace std {
+        template <long long N, long long D = 1> struct ratio {};
+        namespace chrono {
+        template <class Rep, class Period = ratio<1>> class duration { Rep rep_; };
+        using milliseconds = duration<long long, ratio<1, 1000>>;
+        }
+        }
+        void Sleep(std::chrono::milliseconds d);"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "Sleep", ...
+                params: [FuncParam {
+                    type_: MappedType {
+                        rs_type: RsType { name: Some("#chronoDuration milliseconds"), ... },
+                        cc_type: CcType { name: Some("std::chrono::milliseconds"), ... },
+                    }, ...
+                }], ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_struct_awaitable() {
+    let ir = ir_from_cc(
+        r#"
+        struct [[clang::annotate("crubit_awaitable", "poll=Resume", "get=Get")]] Task {
+            bool Resume();
+            int Get();
+        };"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Record {
+                rs_name: "Task" ...
+                unknown_attr: None ...
+                awaitable: Some(Awaitable { poll: "Resume", get: "Get" }) ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_struct_awaitable_missing_get() {
+    let ir = ir_from_cc(
+        r#"
+        struct [[clang::annotate("crubit_awaitable", "poll=Resume", "Get")]] Task {
+            bool Resume();
+            int Get();
+        };"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "Task", ...
+            errors: [FormattedError {
+                ..., message: "Invalid crubit_awaitable attribute: Unexpected `crubit_awaitable` argument: \"Get\"", ...
+            }], ...
+        }}
+    );
+}
+
+#[test]
+fn test_struct_map_visitor() {
+    let ir = ir_from_cc(
+        r#"
+        struct Entry { int first; float second; };
+        struct [[clang::annotate("crubit_map_visitor", "begin=begin", "end=end")]] Registry {
+            using key_type = int;
+            using mapped_type = float;
+            const Entry* begin() const;
+            const Entry* end() const;
+        };"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Record {
+                rs_name: "Registry" ...
+                unknown_attr: None ...
+                map_visitor: Some(MapVisitor {
+                    begin: "begin",
+                    end: "end",
+                    key_type: MappedType {
+                        rs_type: RsType { name: Some("::core::ffi::c_int") ... },
+                        cc_type: CcType { name: Some("int") ... },
+                    },
+                    mapped_type: MappedType {
+                        rs_type: RsType { name: Some("f32") ... },
+                        cc_type: CcType { name: Some("float") ... },
+                    },
+                }) ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_struct_map_visitor_without_key_type() {
+    let ir = ir_from_cc(
+        r#"
+        struct Entry { int first; float second; };
+        struct [[clang::annotate("crubit_map_visitor", "begin=begin", "end=end")]] Registry {
+            using mapped_type = float;
+            const Entry* begin() const;
+            const Entry* end() const;
+        };"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "Registry", ...
+            errors: [FormattedError {
+                ..., message: "Invalid crubit_map_visitor attribute: `Registry` doesn't declare the member type `key_type`.", ...
+            }], ...
+        }}
+    );
+}
+
+#[test]
+fn test_struct_map_visitor_unknown_method() {
+    let ir = ir_from_cc(
+        r#"
+        struct Entry { int first; float second; };
+        struct [[clang::annotate("crubit_map_visitor", "begin=cbegin", "end=end")]] Registry {
+            using key_type = int;
+            using mapped_type = float;
+            const Entry* begin() const;
+            const Entry* end() const;
+        };"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "Registry", ...
+            errors: [FormattedError {
+                ..., message: "Invalid crubit_map_visitor attribute: `cbegin` is not a member function of `Registry`.", ...
+            }], ...
+        }}
+    );
+}
+
+#[test]
+fn test_std_vector_element_type() {
+    let ir = ir_from_cc(
+        r#"
+        namespace std {
+        template <typename T> class allocator {};
+        template <typename T, typename Alloc = allocator<T>> class vector {
+          T* begin_;
+        };
+        }  // namespace std
+        using IntVector = std::vector<int>;
+        template <typename T> class vector { T* begin_; };
+        using NotStdVector = vector<int>;"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Record {
+                rs_name: "__CcTemplateInstSt6vectorIiSaIiEE" ...
+                std_vector_element_type: Some(MappedType {
+                    rs_type: RsType { name: Some("::core::ffi::c_int") ... },
+                    cc_type: CcType { name: Some("int") ... },
+                }) ...
+            }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Record {
+                rs_name: "__CcTemplateInst6vectorIiE" ...
+                std_vector_element_type: None ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_struct_extra_rust_impl() {
+    let path = std::path::Path::new(&std::env::var("TEST_TMPDIR").unwrap())
+        .join("test_struct_extra_rust_impl.rs");
+    std::fs::write(&path, "impl Point {}").unwrap();
+    let ir = ir_from_cc(&format!(
+        r#"
+        struct [[clang::annotate("crubit_extra_rust_impl", "{}")]] Point {{
+            int x;
+        }};"#,
+        path.display()
+    ))
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Record {
+                rs_name: "Point" ...
+                unknown_attr: None ...
+                extra_rust_impl: Some("impl Point {}") ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_struct_extra_rust_impl_missing_file() {
+    let ir = ir_from_cc(
+        r#"
+        struct [[clang::annotate("crubit_extra_rust_impl", "/does/not/exist.rs")]] Point {
+            int x;
+        };"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "Point", ...
+            errors: [FormattedError {
+                ..., message: "Invalid crubit_extra_rust_impl attribute: Couldn't read \"/does/not/exist.rs\": No such file or directory", ...
+            }], ...
+        }}
+    );
+}
+
+#[test]
+fn test_struct_opaque() {
+    let ir = ir_from_cc(
+        r#"
+        struct [[clang::annotate("crubit_opaque")]] Handle {
+            int x;
+        };"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Record {
+                rs_name: "Handle" ...
+                unknown_attr: None ...
+                is_opaque: true ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_struct_opaque_with_arguments() {
+    let ir = ir_from_cc(
+        r#"
+        struct [[clang::annotate("crubit_opaque", "yes")]] Handle {
+            int x;
+        };"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "Handle", ...
+            errors: [FormattedError {
+                ..., message: "Invalid crubit_opaque attribute: The `crubit_opaque` attribute takes no arguments.", ...
+            }], ...
+        }}
+    );
+}
+
+#[test]
+fn test_function_buffer_params() {
+    let ir = ir_from_cc(
+        r#"
+        [[clang::annotate("crubit_buffer", "ptr=data", "len=size")]]
+        void Write(const char* data, unsigned long size);"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "Write", ...
+                buffer_params: [BufferParam { ptr: "data", len: "size", returns_len: false }], ...
+                unknown_attr: None, ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_function_buffer_params_returning_len() {
+    let ir = ir_from_cc(
+        r#"
+        [[clang::annotate("crubit_buffer", "ptr=buf", "len=len", "returns_len")]]
+        unsigned long ReadInto(char* buf, unsigned long len);"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "ReadInto", ...
+                buffer_params: [BufferParam { ptr: "buf", len: "len", returns_len: true }], ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_function_buffer_params_returning_len_twice() {
+    let ir = ir_from_cc(
+        r#"
+        [[clang::annotate("crubit_buffer", "ptr=a", "len=a_len", "returns_len")]]
+        [[clang::annotate("crubit_buffer", "ptr=b", "len=b_len", "returns_len")]]
+        unsigned long ReadInto(char* a, unsigned long a_len, char* b, unsigned long b_len);"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "ReadInto", ...
+            errors: [FormattedError {
+                ..., message: "Invalid crubit_buffer attribute: Only one `crubit_buffer` of a function can return its length.", ...
+            }], ...
+        }}
+    );
+}
+
+#[test]
+fn test_function_buffer_params_unknown_param() {
+    let ir = ir_from_cc(
+        r#"
+        [[clang::annotate("crubit_buffer", "ptr=data", "len=length")]]
+        void Write(const char* data, unsigned long size);"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "Write", ...
+            errors: [FormattedError {
+                ..., message: "Invalid crubit_buffer attribute: `crubit_buffer` refers to \"length\", which is not a parameter of the function.", ...
+            }], ...
+        }}
+    );
+}
+
+#[test]
+fn test_function_out_param() {
+    let ir = ir_from_cc(
+        r#"
+        [[clang::annotate("crubit_out_param", "out")]]
+        bool Lookup(int key, int* out);"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "Lookup", ...
+                out_param: Some("out"), ...
+                unknown_attr: None, ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_function_stable_thunk() {
+    let ir = ir_from_cc(
+        r#"
+        [[clang::annotate("crubit_stable_thunk", "mylib_add_v1")]]
+        int Add(int x, int y);"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "Add", ...
+                stable_thunk_name: Some("mylib_add_v1"), ...
+                unknown_attr: None, ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_function_stable_thunk_invalid_symbol() {
+    let ir = ir_from_cc(
+        r#"
+        [[clang::annotate("crubit_stable_thunk", "mylib::add")]]
+        int Add(int x, int y);"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! { UnsupportedItem {
+            name: "Add", ...
+            errors: [FormattedError {
+                ..., message: "Invalid crubit_stable_thunk attribute: \"mylib::add\" is not a valid symbol name for a thunk.", ...
+            }], ...
+        }}
+    );
+}
+
+#[test]
+fn test_struct_forward_declaration() {
+    let ir = ir_from_cc("struct Struct;").unwrap();
+    assert!(!ir.records().any(|r| r.rs_name.as_ref() == "Struct"));
+}
+
+#[test]
+fn test_struct_forward_declaration_in_namespace() -> Result<()> {
+    let ir = ir_from_cc(
+        r#"
+        namespace MyNamespace {
+        struct FwdDeclared;
+        }
+        "#,
+    )?;
+
+    assert_eq!(1, ir.namespaces().count());
+    let ns = ir.namespaces().next().unwrap();
+    assert_eq!("MyNamespace", ns.name.identifier.as_ref());
+    assert_eq!(1, ns.child_item_ids.len());
+
+    let ns_id = ns.id;
+    let child_id = ns.child_item_ids[0];
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Namespace(Namespace {
+                name: "MyNamespace" ...
+                id: ItemId(#ns_id) ...
+                child_item_ids: [ItemId(#child_id)] ...
+                enclosing_item_id: None ...
+            }),
+            IncompleteRecord(IncompleteRecord {
+                cc_name: "FwdDeclared" ...
+                rs_name: "FwdDeclared" ...
+                id: ItemId(#child_id) ...
+                ...
+                enclosing_item_id: Some(ItemId(#ns_id)) ...
+            }),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_union() {
+    let ir = ir_from_cc("union SomeUnion { int first_field; int second_field; };").unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Record {
+                rs_name: "SomeUnion" ...
+                cc_name: "SomeUnion" ...
+                fields: [
+                    Field {
+                        identifier: Some("first_field"), ...
+                        type_: Ok(MappedType {
+                            rs_type : RsType { name : Some("::core::ffi::c_int"), ...},
+                            cc_type : CcType { name : Some ("int"), ...},
+                         }), ...
+                        offset: 0, ...
+                        size: 32, ...
+                        is_bitfield: false, ...
+                    },
+                    Field {
+                        identifier: Some("second_field"), ...
+                        type_: Ok(MappedType {
+                            rs_type : RsType { name : Some("::core::ffi::c_int"), ...},
+                            cc_type : CcType { name : Some ("int"), ...},
+                         }), ...
+                        offset: 0, ...
+                        size: 32, ...
+                        is_bitfield: false, ...
+                    },
+                ], ...
+                size_align: SizeAlign {
+                    size: 4,
+                    alignment: 4,
+                }, ...
+                record_type: Union, ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_union_with_data_members_with_different_sizes() {
+    let ir = ir_from_cc(
+        r#"
+    union MyUnion {
+      char first_field[56];
+      int second_field;
+    };
+  "#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+              Record { ...
+                rs_name: "MyUnion"...
+                fields: [
+                  Field {
+                    identifier: Some("first_field") ...
+                    offset: 0 ...
+                    size: 448 ...
+                  },
+                  Field {
+                    identifier: Some("second_field") ...
+                    offset: 0 ...
+                    size: 32 ...
+                  } ...
+                ] ...
+              }
+        }
+    );
+}
+
+#[test]
+fn test_member_function_params() {
     let ir = ir_from_cc(
         r#"
             struct Struct {
@@ -2818,6 +3882,8 @@ fn test_member_function() {
             reference: ir::ReferenceQualification::Unqualified,
             is_const: false,
             is_virtual: false,
+            is_pure_virtual: false,
+            is_final: false,
         }),
     );
 }
@@ -2831,6 +3897,8 @@ fn test_member_function_const() {
             reference: ir::ReferenceQualification::Unqualified,
             is_const: true,
             is_virtual: false,
+            is_pure_virtual: false,
+            is_final: false,
         }),
     );
 }
@@ -2844,6 +3912,55 @@ fn test_member_function_virtual() {
             reference: ir::ReferenceQualification::Unqualified,
             is_const: false,
             is_virtual: true,
+            is_pure_virtual: false,
+            is_final: false,
+        }),
+    );
+}
+
+#[test]
+fn test_member_function_pure_virtual() {
+    assert_member_function_has_instance_method_metadata(
+        "Function",
+        "virtual void Function() = 0;",
+        &Some(ir::InstanceMethodMetadata {
+            reference: ir::ReferenceQualification::Unqualified,
+            is_const: false,
+            is_virtual: true,
+            is_pure_virtual: true,
+            is_final: false,
+        }),
+    );
+}
+
+#[test]
+fn test_member_function_virtual_final() {
+    assert_member_function_has_instance_method_metadata(
+        "Function",
+        "virtual void Function() final;",
+        &Some(ir::InstanceMethodMetadata {
+            reference: ir::ReferenceQualification::Unqualified,
+            is_const: false,
+            is_virtual: true,
+            is_pure_virtual: false,
+            is_final: true,
+        }),
+    );
+}
+
+#[test]
+fn test_member_function_virtual_of_final_class() {
+    let ir = ir_from_cc("struct Struct final { virtual void Function(); };").unwrap();
+    assert_member_function_with_predicate_has_instance_method_metadata(
+        &ir,
+        "Struct",
+        |f| f.name == UnqualifiedIdentifier::Identifier(ir_id("Function")),
+        &Some(ir::InstanceMethodMetadata {
+            reference: ir::ReferenceQualification::Unqualified,
+            is_const: false,
+            is_virtual: true,
+            is_pure_virtual: false,
+            is_final: true,
         }),
     );
 }
@@ -2857,6 +3974,8 @@ fn test_member_function_lvalue() {
             reference: ir::ReferenceQualification::LValue,
             is_const: false,
             is_virtual: false,
+            is_pure_virtual: false,
+            is_final: false,
         }),
     );
 }
@@ -2870,6 +3989,8 @@ fn test_member_function_rvalue() {
             reference: ir::ReferenceQualification::RValue,
             is_const: false,
             is_virtual: false,
+            is_pure_virtual: false,
+            is_final: false,
         }),
     );
 }
@@ -2925,6 +4046,8 @@ fn test_member_function_explicit_constructor() {
             reference: ir::ReferenceQualification::Unqualified,
             is_const: false,
             is_virtual: false,
+            is_pure_virtual: false,
+            is_final: false,
         }),
     );
 }
@@ -2947,11 +4070,68 @@ fn test_member_function_constructor() {
                 reference: ir::ReferenceQualification::Unqualified,
                 is_const: false,
                 is_virtual: false,
+                is_pure_virtual: false,
+                is_final: false,
             }),
         );
     }
 }
 
+#[test]
+fn test_inherited_constructor() {
+    let ir = ir_from_cc(
+        r#"
+        struct Base {
+          explicit Base(int i);
+          Base(int i, float f);
+        };
+        struct Derived final : Base {
+          using Base::Base;
+        }; "#,
+    )
+    .unwrap();
+    let derived_id = retrieve_record(&ir, "Derived").id;
+    let mut inherited_ctor_params = ir
+        .functions()
+        .filter(|f| {
+            f.name == UnqualifiedIdentifier::Constructor
+                && f.member_func_metadata.as_ref().unwrap().record_id == derived_id
+        })
+        .map(|f| {
+            f.params
+                .iter()
+                .skip(1)
+                .map(|p| p.type_.cc_type.name.as_deref().unwrap_or_default().to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|params| !params.iter().any(|p| p.contains('&')))
+        .collect::<Vec<_>>();
+    inherited_ctor_params.sort();
+    assert_eq!(inherited_ctor_params, vec![vec!["int"], vec!["int", "float"]]);
+}
+
+#[test]
+fn test_inherited_constructor_non_trivially_relocatable() {
+    let ir = ir_from_cc(
+        r#"
+        struct Base {
+          explicit Base(int i);
+          ~Base();
+        };
+        struct Derived final : Base {
+          using Base::Base;
+        }; "#,
+    )
+    .unwrap();
+    let derived_id = retrieve_record(&ir, "Derived").id;
+    assert!(!ir.functions().any(|f| {
+        f.name == UnqualifiedIdentifier::Constructor
+            && f.member_func_metadata.as_ref().unwrap().record_id == derived_id
+            && f.params.len() == 2
+            && f.params[1].type_.cc_type.name.as_deref() == Some("int")
+    }));
+}
+
 fn get_func_names(definition: &str) -> Vec<ir::UnqualifiedIdentifier> {
     let ir = ir_from_cc(definition).unwrap();
     ir.functions().map(|f| f.name.clone()).collect()
@@ -3221,6 +4401,140 @@ fn test_unnamed_enum_unsupported() {
     );
 }
 
+#[test]
+fn test_rust_enum() {
+    let ir = ir_from_cc(r#"enum class [[clang::annotate("crubit_rust_enum")]] Color { kRed };"#)
+        .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Enum {
+                identifier: "Color", ...
+                unknown_attr: None,
+                is_rust_enum: true, ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_rust_enum_with_arguments() {
+    let ir =
+        ir_from_cc(r#"enum class [[clang::annotate("crubit_rust_enum", "yes")]] Color { kRed };"#)
+            .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            UnsupportedItem {
+                name: "Color",
+                errors: [FormattedError {
+                    ..., message: "Invalid crubit_rust_enum attribute: The `crubit_rust_enum` attribute takes no arguments.", ...
+                }], ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_renamed_from() {
+    let ir = ir_from_cc(
+        r#"
+        struct [[clang::annotate("crubit_renamed_from", "Dimensions")]] Size {};
+        [[clang::annotate("crubit_renamed_from", "ComputeArea")]] int Area(Size size);"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Record {
+                rs_name: "Size", ...
+                renamed_from: Some("Dimensions"), ...
+            }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "Area", ...
+                renamed_from: Some("ComputeArea"), ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_renamed_from_invalid_identifier() {
+    let ir = ir_from_cc(
+        r#"
+        [[clang::annotate("crubit_renamed_from", "Compute Area")]] int Area();"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            UnsupportedItem {
+                name: "Area",
+                errors: [FormattedError {
+                    ..., message: "Invalid crubit_renamed_from attribute: \"Compute Area\" is not a valid identifier.", ...
+                }], ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_returns_static() {
+    let ir = ir_from_cc(
+        r#"
+        [[clang::annotate("crubit_returns_static")]] const int& Get();
+        [[clang::annotate("crubit_returns_static")]] const int* GetPtr();"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            Func {
+                name: "Get", ...
+                returns_static: true, ...
+            }
+        }
+    );
+    assert_ir_matches!(
+        ir,
+        quote! {
+            UnsupportedItem {
+                name: "GetPtr",
+                errors: [FormattedError {
+                    ..., message: "Invalid crubit_returns_static attribute: The `crubit_returns_static` attribute can only be applied to functions returning an lvalue reference.", ...
+                }], ...
+            }
+        }
+    );
+}
+
+#[test]
+fn test_renamed_from_on_member_function() {
+    let ir = ir_from_cc(
+        r#"
+        struct S {
+          [[clang::annotate("crubit_renamed_from", "OldMethod")]] void Method();
+        };"#,
+    )
+    .unwrap();
+    assert_ir_matches!(
+        ir,
+        quote! {
+            UnsupportedItem {
+                name: "S::Method",
+                errors: [FormattedError {
+                    ..., message: "Invalid crubit_renamed_from attribute: Only functions at namespace scope can be annotated.", ...
+                }], ...
+            }
+        }
+    );
+}
+
 #[test]
 fn test_literal_operator_unsupported() {
     let ir = ir_from_cc(
@@ -40,7 +40,10 @@ extern "C" FfiU8SliceBox json_from_cc_dependency(
            "-std=gnu++20",
            "-target",
            StringViewFromFfiU8Slice(target_triple),
-       }});
+       },
+       // The Rust tests cover the conversion of string views (and use their
+       // own minimal `std::string_view`).
+       .convert_string_views = true});
 
   // TODO(forster): For now it is good enough to just exit: We are just
   // using this from tests, which are ok to just fail. Clang has already
@@ -90,6 +90,21 @@ absl::Status Main(absl::Span<char* const> positional_args) {
                                            bindings_and_metadata.error_report));
   }
 
+  if (!args.item_spans_out.empty()) {
+    CRUBIT_RETURN_IF_ERROR(SetFileContents(args.item_spans_out,
+                                           bindings_and_metadata.item_spans));
+  }
+
+  if (!args.bindings_status_out.empty()) {
+    CRUBIT_RETURN_IF_ERROR(SetFileContents(
+        args.bindings_status_out, bindings_and_metadata.bindings_status));
+  }
+
+  if (!args.stable_thunks_out.empty()) {
+    CRUBIT_RETURN_IF_ERROR(SetFileContents(
+        args.stable_thunks_out, bindings_and_metadata.stable_thunks));
+  }
+
   return absl::OkStatus();
 }
 
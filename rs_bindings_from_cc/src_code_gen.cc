@@ -12,6 +12,7 @@
 #include "common/status_macros.h"
 #include "rs_bindings_from_cc/ir.h"
 #include "llvm/Support/FormatVariadic.h"
+#include "llvm/Support/JSON.h"
 
 namespace crubit {
 
@@ -20,6 +21,9 @@ struct FfiBindings {
   FfiU8SliceBox rs_api;
   FfiU8SliceBox rs_api_impl;
   FfiU8SliceBox error_report;
+  FfiU8SliceBox item_spans;
+  FfiU8SliceBox stable_thunks;
+  FfiU8SliceBox bindings_status;
 };
 
 // This function is implemented in Rust.
@@ -27,7 +31,8 @@ extern "C" FfiBindings GenerateBindingsImpl(
     FfiU8Slice json, FfiU8Slice crubit_support_path_format,
     FfiU8Slice clang_format_exe_path, FfiU8Slice rustfmt_exe_path,
     FfiU8Slice rustfmt_config_path, bool generate_error_report,
-    SourceLocationDocComment generate_source_location_in_doc_comment);
+    SourceLocationDocComment generate_source_location_in_doc_comment,
+    FfiU8Slice generator_options, FfiU8Slice stable_thunks_baseline);
 
 // Creates `Bindings` instance from copied data from `ffi_bindings`.
 static absl::StatusOr<Bindings> MakeBindingsFromFfiBindings(
@@ -37,10 +42,17 @@ static absl::StatusOr<Bindings> MakeBindingsFromFfiBindings(
   const FfiU8SliceBox& rs_api = ffi_bindings.rs_api;
   const FfiU8SliceBox& rs_api_impl = ffi_bindings.rs_api_impl;
   const FfiU8SliceBox& error_report = ffi_bindings.error_report;
+  const FfiU8SliceBox& item_spans = ffi_bindings.item_spans;
+  const FfiU8SliceBox& stable_thunks = ffi_bindings.stable_thunks;
+  const FfiU8SliceBox& bindings_status = ffi_bindings.bindings_status;
 
   bindings.rs_api = std::string(rs_api.ptr, rs_api.size);
   bindings.rs_api_impl = std::string(rs_api_impl.ptr, rs_api_impl.size);
   bindings.error_report = std::string(error_report.ptr, error_report.size);
+  bindings.item_spans = std::string(item_spans.ptr, item_spans.size);
+  bindings.stable_thunks = std::string(stable_thunks.ptr, stable_thunks.size);
+  bindings.bindings_status =
+      std::string(bindings_status.ptr, bindings_status.size);
   return bindings;
 }
 
@@ -49,19 +61,88 @@ static void FreeFfiBindings(FfiBindings ffi_bindings) {
   FreeFfiU8SliceBox(ffi_bindings.rs_api);
   FreeFfiU8SliceBox(ffi_bindings.rs_api_impl);
   FreeFfiU8SliceBox(ffi_bindings.error_report);
+  FreeFfiU8SliceBox(ffi_bindings.item_spans);
+  FreeFfiU8SliceBox(ffi_bindings.stable_thunks);
+  FreeFfiU8SliceBox(ffi_bindings.bindings_status);
+}
+
+bool AbslParseFlag(absl::string_view text, OverloadNaming* overload_naming,
+                   std::string* error) {
+  if (text == "none") {
+    *overload_naming = OverloadNaming::kNone;
+  } else if (text == "param_types") {
+    *overload_naming = OverloadNaming::kParamTypes;
+  } else if (text == "ordinal") {
+    *overload_naming = OverloadNaming::kOrdinal;
+  } else {
+    *error = "must be one of `none`, `param_types` or `ordinal`";
+    return false;
+  }
+  return true;
+}
+
+std::string AbslUnparseFlag(OverloadNaming overload_naming) {
+  switch (overload_naming) {
+    case OverloadNaming::kNone:
+      return "none";
+    case OverloadNaming::kParamTypes:
+      return "param_types";
+    case OverloadNaming::kOrdinal:
+      return "ordinal";
+  }
+}
+
+llvm::json::Value GeneratorOptions::ToJson() const {
+  llvm::json::Object attribute_translations_json;
+  for (const auto& [cc_attribute, rs_attribute] : attribute_translations) {
+    attribute_translations_json[cc_attribute] = rs_attribute;
+  }
+  return llvm::json::Object{
+      {"generate_raw_thunks_module", generate_raw_thunks_module},
+      {"detail_module_name", detail_module_name},
+      {"generate_mock_thunks", generate_mock_thunks},
+      {"thunk_export_macro", thunk_export_macro},
+      {"hash_thunk_names", hash_thunk_names},
+      {"generate_layout_verification", generate_layout_verification},
+      {"generate_bytemuck_impls", generate_bytemuck_impls},
+      {"generate_prelude_module", generate_prelude_module},
+      {"import_public_headers", import_public_headers},
+      {"cc_module_name", cc_module_name},
+      {"generate_item_spans", generate_item_spans},
+      {"generate_bindings_status", generate_bindings_status},
+      {"rs_api_impl_path", rs_api_impl_path},
+      {"generate_cxx_extern_types", generate_cxx_extern_types},
+      {"bindgen_allowlist_file", bindgen_allowlist_file},
+      {"minimize_public_header_includes", minimize_public_header_includes},
+      {"lazy_ir", lazy_ir},
+      {"thunk_exception_note", thunk_exception_note},
+      {"overload_naming", AbslUnparseFlag(overload_naming)},
+      {"rs_api_lints", rs_api_lints},
+      {"strict_pointer_constness", strict_pointer_constness},
+      {"compact_layout_assertions", compact_layout_assertions},
+      {"abi_hash_symbols", abi_hash_symbols},
+      {"string_view_params_as_str", string_view_params_as_str},
+      {"record_ext_traits", record_ext_traits},
+      {"constructor_functions", constructor_functions},
+      {"attribute_translations", std::move(attribute_translations_json)},
+      {"version_stamp", version_stamp},
+  };
 }
 
 absl::StatusOr<Bindings> GenerateBindings(
     const IR& ir, absl::string_view crubit_support_path_format,
     absl::string_view clang_format_exe_path, absl::string_view rustfmt_exe_path,
     absl::string_view rustfmt_config_path, bool generate_error_report,
-    SourceLocationDocComment generate_source_location_in_doc_comment) {
+    SourceLocationDocComment generate_source_location_in_doc_comment,
+    const GeneratorOptions& options, absl::string_view stable_thunks_baseline) {
   std::string json = llvm::formatv("{0}", ir.ToJson());
+  std::string options_json = llvm::formatv("{0}", options.ToJson());
   FfiBindings ffi_bindings = GenerateBindingsImpl(
       MakeFfiU8Slice(json), MakeFfiU8Slice(crubit_support_path_format),
       MakeFfiU8Slice(clang_format_exe_path), MakeFfiU8Slice(rustfmt_exe_path),
       MakeFfiU8Slice(rustfmt_config_path), generate_error_report,
-      generate_source_location_in_doc_comment);
+      generate_source_location_in_doc_comment, MakeFfiU8Slice(options_json),
+      MakeFfiU8Slice(stable_thunks_baseline));
   CRUBIT_ASSIGN_OR_RETURN(Bindings bindings,
                           MakeBindingsFromFfiBindings(ffi_bindings));
   FreeFfiBindings(ffi_bindings);
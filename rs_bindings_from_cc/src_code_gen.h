@@ -7,10 +7,12 @@
 
 #include <string>
 
+#include "absl/container/flat_hash_map.h"
 #include "absl/status/statusor.h"
 #include "absl/strings/string_view.h"
 #include "common/ffi_types.h"
 #include "rs_bindings_from_cc/ir.h"
+#include "llvm/Support/JSON.h"
 
 namespace crubit {
 
@@ -22,14 +24,97 @@ struct Bindings {
   std::string rs_api_impl;
   // Optional JSON error report.
   std::string error_report;
+  // Optional JSON array of the spans of the bindings of the items in `rs_api`,
+  // as `{"id": ..., "start": ..., "end": ...}` objects (where `id` is the ID of
+  // the item in the IR, and `start`..`end` is a byte range).
+  std::string item_spans;
+  // Optional JSON array listing whether each item of the target gets bindings,
+  // as `{"id": ..., "status": ...}` objects (see `query_bindings`).
+  std::string bindings_status;
+  // JSON object mapping the symbol names of the thunks pinned by the
+  // `crubit_stable_thunk` attribute to their C++ signatures.
+  std::string stable_thunks;
+};
+
+// How the Rust bindings of overloaded functions are named. Mirrors
+// `OverloadNaming` in `generate_bindings/generate_func.rs`.
+enum class OverloadNaming {
+  // Overloaded functions don't get bindings.
+  kNone,
+  // The names of the overloads are suffixed with the names of their parameter
+  // types (e.g. `f_c_int`).
+  kParamTypes,
+  // The first overload keeps its name, and the names of the other overloads
+  // are suffixed with their ordinal (e.g. `f1`), like `bindgen`.
+  kOrdinal,
+};
+
+// Parses the value of `--overload_naming` (`none`, `param_types` or
+// `ordinal`).
+bool AbslParseFlag(absl::string_view text, OverloadNaming* overload_naming,
+                   std::string* error);
+// Returns the name of `overload_naming`, as accepted by `AbslParseFlag`.
+std::string AbslUnparseFlag(OverloadNaming overload_naming);
+
+// Options controlling which bindings are generated, and how. Mirrors
+// `GeneratorOptions` in `generate_bindings/lib.rs`, which documents each
+// option.
+struct GeneratorOptions {
+  bool generate_raw_thunks_module = false;
+  // Empty string means `detail`.
+  std::string detail_module_name;
+  bool generate_mock_thunks = false;
+  std::string thunk_export_macro;
+  bool hash_thunk_names = false;
+  bool generate_layout_verification = false;
+  bool generate_bytemuck_impls = false;
+  bool generate_prelude_module = false;
+  bool import_public_headers = false;
+  // Empty string means that the target doesn't export a C++20 module.
+  std::string cc_module_name;
+  bool generate_item_spans = false;
+  bool generate_bindings_status = false;
+  // The path of the generated C++ source file, as passed to the compiler. If
+  // not empty, the C++ thunks are surrounded with `#line` directives, which
+  // point at the wrapped declarations and back at `rs_api_impl_path`.
+  std::string rs_api_impl_path;
+  bool generate_cxx_extern_types = false;
+  std::string bindgen_allowlist_file;
+  bool minimize_public_header_includes = false;
+  // If true, the items of the IR are only deserialized on the Rust side once
+  // bindings generation visits them.
+  bool lazy_ir = false;
+  // If not empty, the C++ thunks of functions that aren't `noexcept` abort
+  // with this note if the function throws.
+  std::string thunk_exception_note;
+  OverloadNaming overload_naming = OverloadNaming::kNone;
+  std::string rs_api_lints = "deny(warnings)";
+  bool strict_pointer_constness = false;
+  bool compact_layout_assertions = false;
+  bool abi_hash_symbols = false;
+  bool string_view_params_as_str = false;
+  bool record_ext_traits = false;
+  bool constructor_functions = false;
+  // Maps C++ attribute names to Rust attributes (or to an empty string, if the
+  // attribute should be ignored).
+  absl::flat_hash_map<std::string, std::string> attribute_translations;
+  // Empty string means that no `__crubit_metadata` is generated.
+  std::string version_stamp;
+
+  llvm::json::Value ToJson() const;
 };
 
 // Generates bindings from the given `IR`.
+//
+// If `stable_thunks_baseline` (the `stable_thunks` of previously generated
+// bindings) is not empty, bindings generation fails if one of the pinned thunks
+// in it was removed or its signature changed.
 absl::StatusOr<Bindings> GenerateBindings(
     const IR& ir, absl::string_view crubit_support_path_format,
     absl::string_view clang_format_exe_path, absl::string_view rustfmt_exe_path,
     absl::string_view rustfmt_config_path, bool generate_error_report,
-    SourceLocationDocComment generate_source_location_in_doc_comment);
+    SourceLocationDocComment generate_source_location_in_doc_comment,
+    const GeneratorOptions& options, absl::string_view stable_thunks_baseline);
 
 }  // namespace crubit
 
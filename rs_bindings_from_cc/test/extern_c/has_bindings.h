@@ -61,7 +61,7 @@ inline void crubit_invoke_nullable_callback(void (*f)(int* x), int* x) { f(x); }
 
 extern "C" void crubit_extern_c_directly_function();
 
-struct MyDerivedStruct : Struct {
+struct MyDerivedStruct final : Struct {
   int derived_x;
 };
 }  // namespace crubit::has_bindings
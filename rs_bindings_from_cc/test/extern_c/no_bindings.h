@@ -63,6 +63,8 @@ inline Nontrivial crubit_returns_nontrivial_value() { return {}; }
 [[deprecated]] inline void crubit_enable_if()
     __attribute__((enable_if(2 + 2 == 4, ""))) {}
 
+// Receives Rust references (which require the `experimental` feature), since
+// `[[clang::lifetimebound]]` gives the pointers a lifetime.
 inline void* crubit_parameter_lifetimebound(void* x [[clang::lifetimebound]]) {
   return x;
 }
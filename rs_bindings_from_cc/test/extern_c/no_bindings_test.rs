@@ -54,8 +54,11 @@ fn test_vectorcall() {
     assert!(!value_exists!(no_bindings::crubit_vectorcall));
 }
 
+/// `[[clang::lifetimebound]]` is understood, and gives the parameter and the
+/// return value a lifetime, so that the function receives Rust references.
+/// References require the `experimental` feature.
 #[test]
-fn test_parameter_lifetimebound() {
+fn test_parameter_lifetimebound_requires_experimental() {
     assert!(!value_exists!(no_bindings::crubit_parameter_lifetimebound));
 }
 
@@ -7,7 +7,7 @@
 // Features: experimental, supported
 
 #![rustfmt::skip]
-#![feature(custom_inner_attributes, negative_impls, register_tool)]
+#![feature(custom_inner_attributes, impl_trait_in_assoc_type, negative_impls, register_tool)]
 #![allow(stable_features)]
 #![no_std]
 #![register_tool(__crubit)]
@@ -15,7 +15,13 @@
 #![allow(nonstandard_style)]
 #![deny(warnings)]
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C, align(4))]
 #[__crubit::annotate(cc_type = "WithBitfields")]
 pub struct WithBitfields {
@@ -37,6 +43,9 @@ pub struct WithBitfields {
 }
 impl !Send for WithBitfields {}
 impl !Sync for WithBitfields {}
+impl oops::CcClass for WithBitfields {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("WithBitfields"), crate::WithBitfields);
 impl WithBitfields {
     pub fn f7(&self) -> &::core::ffi::c_char {
@@ -47,40 +56,90 @@ impl WithBitfields {
     }
 }
 
-impl Default for WithBitfields {
+impl ::ctor::CtorNew<()> for WithBitfields {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN13WithBitfieldsC1Ev(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN13WithBitfieldsC1Ev(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>> for WithBitfields {
+impl<'b> ::ctor::CtorNew<&'b Self> for WithBitfields {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN13WithBitfieldsC1EOS_(&mut tmp, __param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN13WithBitfieldsC1ERKS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for WithBitfields {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for WithBitfields {
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for WithBitfields {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN13WithBitfieldsC1EOS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for WithBitfields {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self> for WithBitfields {
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN13WithBitfieldsaSERKS_(self, __param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for WithBitfields {
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for WithBitfields {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN13WithBitfieldsaSEOS_(self, __param_0);
         }
@@ -89,6 +148,10 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for WithBitfield
 
 /// This is a regression test for b/283835873 where the alignment of the
 /// generated struct was wrong/missing.
+///
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[derive(Clone, Copy)]
 #[repr(C, align(4))]
 #[__crubit::annotate(cc_type = "AlignmentRegressionTest")]
@@ -99,6 +162,9 @@ pub struct AlignmentRegressionTest {
 }
 impl !Send for AlignmentRegressionTest {}
 impl !Sync for AlignmentRegressionTest {}
+impl oops::CcClass for AlignmentRegressionTest {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AlignmentRegressionTest"),
     crate::AlignmentRegressionTest
@@ -154,18 +220,22 @@ mod detail {
         pub(crate) fn __rust_thunk___ZN13WithBitfieldsC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::WithBitfields>,
         );
+        pub(crate) fn __rust_thunk___ZN13WithBitfieldsC1ERKS_<'a, 'b>(
+            __this: &'a mut ::core::mem::MaybeUninit<crate::WithBitfields>,
+            __param_0: &'b crate::WithBitfields,
+        );
         pub(crate) fn __rust_thunk___ZN13WithBitfieldsC1EOS_<'a, 'b>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::WithBitfields>,
             __param_0: ::ctor::RvalueReference<'b, crate::WithBitfields>,
         );
         pub(crate) fn __rust_thunk___ZN13WithBitfieldsaSERKS_<'a, 'b>(
-            __this: &'a mut crate::WithBitfields,
+            __this: ::core::pin::Pin<&'a mut crate::WithBitfields>,
             __param_0: &'b crate::WithBitfields,
-        ) -> &'a mut crate::WithBitfields;
+        ) -> ::core::pin::Pin<&'a mut crate::WithBitfields>;
         pub(crate) fn __rust_thunk___ZN13WithBitfieldsaSEOS_<'a, 'b>(
-            __this: &'a mut crate::WithBitfields,
+            __this: ::core::pin::Pin<&'a mut crate::WithBitfields>,
             __param_0: ::ctor::RvalueReference<'b, crate::WithBitfields>,
-        ) -> &'a mut crate::WithBitfields;
+        ) -> ::core::pin::Pin<&'a mut crate::WithBitfields>;
         pub(crate) fn __rust_thunk___ZN23AlignmentRegressionTestC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::AlignmentRegressionTest>,
         );
@@ -187,8 +257,7 @@ mod detail {
 const _: () = {
     assert!(::core::mem::size_of::<crate::WithBitfields>() == 32);
     assert!(::core::mem::align_of::<crate::WithBitfields>() == 4);
-    static_assertions::assert_impl_all!(crate::WithBitfields: Clone);
-    static_assertions::assert_impl_all!(crate::WithBitfields: Copy);
+    static_assertions::assert_not_impl_any!(crate::WithBitfields: Copy);
     static_assertions::assert_not_impl_any!(crate::WithBitfields: Drop);
     assert!(::core::mem::offset_of!(crate::WithBitfields, f2) == 4);
     assert!(::core::mem::offset_of!(crate::WithBitfields, f5) == 20);
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct WithBitfields) == 32);
 static_assert(alignof(struct WithBitfields) == 4);
@@ -30,6 +31,11 @@ extern "C" void __rust_thunk___ZN13WithBitfieldsC1Ev(
   crubit::construct_at(__this);
 }
 
+extern "C" void __rust_thunk___ZN13WithBitfieldsC1ERKS_(
+    struct WithBitfields* __this, const struct WithBitfields* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void __rust_thunk___ZN13WithBitfieldsC1EOS_(
     struct WithBitfields* __this, struct WithBitfields* __param_0) {
   crubit::construct_at(__this, std::move(*__param_0));
@@ -73,4 +79,6 @@ __rust_thunk___ZN23AlignmentRegressionTestaSEOS_(
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -7,7 +7,7 @@
 // Features: experimental, supported
 
 #![rustfmt::skip]
-#![feature(custom_inner_attributes, negative_impls, register_tool)]
+#![feature(custom_inner_attributes, impl_trait_in_assoc_type, negative_impls, register_tool)]
 #![allow(stable_features)]
 #![no_std]
 #![register_tool(__crubit)]
@@ -15,7 +15,13 @@
 #![allow(nonstandard_style)]
 #![deny(warnings)]
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C, align(64))]
 #[__crubit::annotate(cc_type = "HasCustomAlignment")]
 pub struct HasCustomAlignment {
@@ -23,59 +29,120 @@ pub struct HasCustomAlignment {
 }
 impl !Send for HasCustomAlignment {}
 impl !Sync for HasCustomAlignment {}
+impl oops::CcClass for HasCustomAlignment {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("HasCustomAlignment"),
     crate::HasCustomAlignment
 );
 
-impl Default for HasCustomAlignment {
+impl ::ctor::CtorNew<()> for HasCustomAlignment {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN18HasCustomAlignmentC1Ev(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN18HasCustomAlignmentC1Ev(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>> for HasCustomAlignment {
+impl<'b> ::ctor::CtorNew<&'b Self> for HasCustomAlignment {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN18HasCustomAlignmentC1EOS_(&mut tmp, __param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN18HasCustomAlignmentC1ERKS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for HasCustomAlignment {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for HasCustomAlignment {
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for HasCustomAlignment {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN18HasCustomAlignmentC1EOS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for HasCustomAlignment {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self> for HasCustomAlignment {
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN18HasCustomAlignmentaSERKS_(self, __param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for HasCustomAlignment {
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for HasCustomAlignment {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN18HasCustomAlignmentaSEOS_(self, __param_0);
         }
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[derive(Clone, Copy)]
-#[repr(C)]
+#[repr(C, align(64))]
 #[__crubit::annotate(cc_type = "HasFieldWithCustomAlignment")]
 pub struct HasFieldWithCustomAlignment {
-    pub field: crate::HasCustomAlignment,
+    /// Reason for representing this field as a blob of bytes:
+    /// `HasCustomAlignment` is not `Unpin`, and can't be a field of the `Unpin` `HasFieldWithCustomAlignment`
+    pub(crate) field: [::core::mem::MaybeUninit<u8>; 64],
 }
 impl !Send for HasFieldWithCustomAlignment {}
 impl !Sync for HasFieldWithCustomAlignment {}
+impl oops::CcClass for HasFieldWithCustomAlignment {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("HasFieldWithCustomAlignment"),
     crate::HasFieldWithCustomAlignment
@@ -123,7 +190,13 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for HasFieldWith
     }
 }
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C, align(64))]
 #[__crubit::annotate(cc_type = "InheritsFromBaseWithCustomAlignment")]
 pub struct InheritsFromBaseWithCustomAlignment {
@@ -131,38 +204,90 @@ pub struct InheritsFromBaseWithCustomAlignment {
 }
 impl !Send for InheritsFromBaseWithCustomAlignment {}
 impl !Sync for InheritsFromBaseWithCustomAlignment {}
+impl oops::CcClass for InheritsFromBaseWithCustomAlignment {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("InheritsFromBaseWithCustomAlignment"),
     crate::InheritsFromBaseWithCustomAlignment
 );
 
-impl Default for InheritsFromBaseWithCustomAlignment {
+impl ::ctor::CtorNew<()> for InheritsFromBaseWithCustomAlignment {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1Ev(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1Ev(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>> for InheritsFromBaseWithCustomAlignment {
+impl<'b> ::ctor::CtorNew<&'b Self> for InheritsFromBaseWithCustomAlignment {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1EOS_(
-                &mut tmp, __param_0,
-            );
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1ERKS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for InheritsFromBaseWithCustomAlignment {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for InheritsFromBaseWithCustomAlignment {
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>
+    for InheritsFromBaseWithCustomAlignment
+{
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1EOS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)>
+    for InheritsFromBaseWithCustomAlignment
+{
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self> for InheritsFromBaseWithCustomAlignment {
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentaSERKS_(
                 self, __param_0,
@@ -171,11 +296,12 @@ impl<'b> ::ctor::UnpinAssign<&'b Self> for InheritsFromBaseWithCustomAlignment {
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>>
-    for InheritsFromBaseWithCustomAlignment
-{
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for InheritsFromBaseWithCustomAlignment {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentaSEOS_(
                 self, __param_0,
@@ -192,7 +318,13 @@ unsafe impl oops::Inherits<crate::HasCustomAlignment>
     }
 }
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C, align(64))]
 #[__crubit::annotate(cc_type = "HasCustomAlignmentWithGnuAttr")]
 pub struct HasCustomAlignmentWithGnuAttr {
@@ -200,47 +332,98 @@ pub struct HasCustomAlignmentWithGnuAttr {
 }
 impl !Send for HasCustomAlignmentWithGnuAttr {}
 impl !Sync for HasCustomAlignmentWithGnuAttr {}
+impl oops::CcClass for HasCustomAlignmentWithGnuAttr {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("HasCustomAlignmentWithGnuAttr"),
     crate::HasCustomAlignmentWithGnuAttr
 );
 
-impl Default for HasCustomAlignmentWithGnuAttr {
+impl ::ctor::CtorNew<()> for HasCustomAlignmentWithGnuAttr {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1Ev(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1Ev(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>> for HasCustomAlignmentWithGnuAttr {
+impl<'b> ::ctor::CtorNew<&'b Self> for HasCustomAlignmentWithGnuAttr {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1EOS_(
-                &mut tmp, __param_0,
-            );
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1ERKS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for HasCustomAlignmentWithGnuAttr {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for HasCustomAlignmentWithGnuAttr {
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for HasCustomAlignmentWithGnuAttr {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1EOS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for HasCustomAlignmentWithGnuAttr {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self> for HasCustomAlignmentWithGnuAttr {
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN29HasCustomAlignmentWithGnuAttraSERKS_(self, __param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for HasCustomAlignmentWithGnuAttr {
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for HasCustomAlignmentWithGnuAttr {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN29HasCustomAlignmentWithGnuAttraSEOS_(self, __param_0);
         }
@@ -265,7 +448,14 @@ pub mod template_with_preferred_name {
 
 /// Based on `llvm/include/c++/v1/__fwd/string_view.h` - mimics
 /// forward declaration of `basic_string_view` class template.
-#[derive(Clone, Copy)]
+///
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "template_with_preferred_name :: SomeTemplate < int >")]
 pub struct __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE {
@@ -273,51 +463,110 @@ pub struct __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE {
 }
 impl !Send for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE {}
 impl !Sync for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE {}
+impl oops::CcClass for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("template_with_preferred_name :: SomeTemplate < int >"),
     crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE
 );
 
-impl Default for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE {
+impl ::ctor::CtorNew<()> for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1Ev__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1Ev__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>>
+impl<'b> ::ctor::CtorNew<&'b Self>
     for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE
 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1EOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc(&mut tmp,__param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1ERKS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)>
+    for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE
+{
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self>
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>
     for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE
 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1EOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)>
+    for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE
+{
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self>
+    for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE
+{
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEaSERKS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc(self,__param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>>
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>>
     for __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE
 {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEaSEOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc(self,__param_0);
         }
@@ -326,7 +575,7 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>>
 
 impl __CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE {
     #[inline(always)]
-    pub fn foo<'a>(&'a mut self) -> ::core::ffi::c_int {
+    pub fn foo<'a>(self: ::core::pin::Pin<&'a mut Self>) -> ::core::ffi::c_int {
         unsafe {
             crate::detail::__rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiE3fooEv__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc(self)
         }
@@ -340,18 +589,22 @@ mod detail {
         pub(crate) fn __rust_thunk___ZN18HasCustomAlignmentC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::HasCustomAlignment>,
         );
+        pub(crate) fn __rust_thunk___ZN18HasCustomAlignmentC1ERKS_<'a, 'b>(
+            __this: &'a mut ::core::mem::MaybeUninit<crate::HasCustomAlignment>,
+            __param_0: &'b crate::HasCustomAlignment,
+        );
         pub(crate) fn __rust_thunk___ZN18HasCustomAlignmentC1EOS_<'a, 'b>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::HasCustomAlignment>,
             __param_0: ::ctor::RvalueReference<'b, crate::HasCustomAlignment>,
         );
         pub(crate) fn __rust_thunk___ZN18HasCustomAlignmentaSERKS_<'a, 'b>(
-            __this: &'a mut crate::HasCustomAlignment,
+            __this: ::core::pin::Pin<&'a mut crate::HasCustomAlignment>,
             __param_0: &'b crate::HasCustomAlignment,
-        ) -> &'a mut crate::HasCustomAlignment;
+        ) -> ::core::pin::Pin<&'a mut crate::HasCustomAlignment>;
         pub(crate) fn __rust_thunk___ZN18HasCustomAlignmentaSEOS_<'a, 'b>(
-            __this: &'a mut crate::HasCustomAlignment,
+            __this: ::core::pin::Pin<&'a mut crate::HasCustomAlignment>,
             __param_0: ::ctor::RvalueReference<'b, crate::HasCustomAlignment>,
-        ) -> &'a mut crate::HasCustomAlignment;
+        ) -> ::core::pin::Pin<&'a mut crate::HasCustomAlignment>;
         pub(crate) fn __rust_thunk___ZN27HasFieldWithCustomAlignmentC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::HasFieldWithCustomAlignment>,
         );
@@ -370,33 +623,41 @@ mod detail {
         pub(crate) fn __rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::InheritsFromBaseWithCustomAlignment>,
         );
+        pub(crate) fn __rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1ERKS_<'a, 'b>(
+            __this: &'a mut ::core::mem::MaybeUninit<crate::InheritsFromBaseWithCustomAlignment>,
+            __param_0: &'b crate::InheritsFromBaseWithCustomAlignment,
+        );
         pub(crate) fn __rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1EOS_<'a, 'b>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::InheritsFromBaseWithCustomAlignment>,
             __param_0: ::ctor::RvalueReference<'b, crate::InheritsFromBaseWithCustomAlignment>,
         );
         pub(crate) fn __rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentaSERKS_<'a, 'b>(
-            __this: &'a mut crate::InheritsFromBaseWithCustomAlignment,
+            __this: ::core::pin::Pin<&'a mut crate::InheritsFromBaseWithCustomAlignment>,
             __param_0: &'b crate::InheritsFromBaseWithCustomAlignment,
-        ) -> &'a mut crate::InheritsFromBaseWithCustomAlignment;
+        ) -> ::core::pin::Pin<&'a mut crate::InheritsFromBaseWithCustomAlignment>;
         pub(crate) fn __rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentaSEOS_<'a, 'b>(
-            __this: &'a mut crate::InheritsFromBaseWithCustomAlignment,
+            __this: ::core::pin::Pin<&'a mut crate::InheritsFromBaseWithCustomAlignment>,
             __param_0: ::ctor::RvalueReference<'b, crate::InheritsFromBaseWithCustomAlignment>,
-        ) -> &'a mut crate::InheritsFromBaseWithCustomAlignment;
+        ) -> ::core::pin::Pin<&'a mut crate::InheritsFromBaseWithCustomAlignment>;
         pub(crate) fn __rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::HasCustomAlignmentWithGnuAttr>,
         );
+        pub(crate) fn __rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1ERKS_<'a, 'b>(
+            __this: &'a mut ::core::mem::MaybeUninit<crate::HasCustomAlignmentWithGnuAttr>,
+            __param_0: &'b crate::HasCustomAlignmentWithGnuAttr,
+        );
         pub(crate) fn __rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1EOS_<'a, 'b>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::HasCustomAlignmentWithGnuAttr>,
             __param_0: ::ctor::RvalueReference<'b, crate::HasCustomAlignmentWithGnuAttr>,
         );
         pub(crate) fn __rust_thunk___ZN29HasCustomAlignmentWithGnuAttraSERKS_<'a, 'b>(
-            __this: &'a mut crate::HasCustomAlignmentWithGnuAttr,
+            __this: ::core::pin::Pin<&'a mut crate::HasCustomAlignmentWithGnuAttr>,
             __param_0: &'b crate::HasCustomAlignmentWithGnuAttr,
-        ) -> &'a mut crate::HasCustomAlignmentWithGnuAttr;
+        ) -> ::core::pin::Pin<&'a mut crate::HasCustomAlignmentWithGnuAttr>;
         pub(crate) fn __rust_thunk___ZN29HasCustomAlignmentWithGnuAttraSEOS_<'a, 'b>(
-            __this: &'a mut crate::HasCustomAlignmentWithGnuAttr,
+            __this: ::core::pin::Pin<&'a mut crate::HasCustomAlignmentWithGnuAttr>,
             __param_0: ::ctor::RvalueReference<'b, crate::HasCustomAlignmentWithGnuAttr>,
-        ) -> &'a mut crate::HasCustomAlignmentWithGnuAttr;
+        ) -> ::core::pin::Pin<&'a mut crate::HasCustomAlignmentWithGnuAttr>;
         pub(crate) fn __rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1Ev__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc<
             'a,
         >(
@@ -404,6 +665,15 @@ mod detail {
                 crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
             >,
         );
+        pub(crate) fn __rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1ERKS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc<
+            'a,
+            'b,
+        >(
+            __this: &'a mut ::core::mem::MaybeUninit<
+                crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
+            >,
+            __param_0: &'b crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
+        );
         pub(crate) fn __rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1EOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc<
             'a,
             'b,
@@ -420,23 +690,33 @@ mod detail {
             'a,
             'b,
         >(
-            __this: &'a mut crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
+            __this: ::core::pin::Pin<
+                &'a mut crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
+            >,
             __param_0: &'b crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
-        ) -> &'a mut crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE;
+        ) -> ::core::pin::Pin<
+            &'a mut crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
+        >;
         pub(crate) fn __rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEaSEOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc<
             'a,
             'b,
         >(
-            __this: &'a mut crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
+            __this: ::core::pin::Pin<
+                &'a mut crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
+            >,
             __param_0: ::ctor::RvalueReference<
                 'b,
                 crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
             >,
-        ) -> &'a mut crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE;
+        ) -> ::core::pin::Pin<
+            &'a mut crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
+        >;
         pub(crate) fn __rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiE3fooEv__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc<
             'a,
         >(
-            __this: &'a mut crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
+            __this: ::core::pin::Pin<
+                &'a mut crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
+            >,
         ) -> ::core::ffi::c_int;
     }
 }
@@ -444,8 +724,7 @@ mod detail {
 const _: () = {
     assert!(::core::mem::size_of::<crate::HasCustomAlignment>() == 64);
     assert!(::core::mem::align_of::<crate::HasCustomAlignment>() == 64);
-    static_assertions::assert_impl_all!(crate::HasCustomAlignment: Clone);
-    static_assertions::assert_impl_all!(crate::HasCustomAlignment: Copy);
+    static_assertions::assert_not_impl_any!(crate::HasCustomAlignment: Copy);
     static_assertions::assert_not_impl_any!(crate::HasCustomAlignment: Drop);
 
     assert!(::core::mem::size_of::<crate::HasFieldWithCustomAlignment>() == 64);
@@ -457,14 +736,12 @@ const _: () = {
 
     assert!(::core::mem::size_of::<crate::InheritsFromBaseWithCustomAlignment>() == 64);
     assert!(::core::mem::align_of::<crate::InheritsFromBaseWithCustomAlignment>() == 64);
-    static_assertions::assert_impl_all!(crate::InheritsFromBaseWithCustomAlignment: Clone);
-    static_assertions::assert_impl_all!(crate::InheritsFromBaseWithCustomAlignment: Copy);
+    static_assertions::assert_not_impl_any!(crate::InheritsFromBaseWithCustomAlignment: Copy);
     static_assertions::assert_not_impl_any!(crate::InheritsFromBaseWithCustomAlignment: Drop);
 
     assert!(::core::mem::size_of::<crate::HasCustomAlignmentWithGnuAttr>() == 64);
     assert!(::core::mem::align_of::<crate::HasCustomAlignmentWithGnuAttr>() == 64);
-    static_assertions::assert_impl_all!(crate::HasCustomAlignmentWithGnuAttr: Clone);
-    static_assertions::assert_impl_all!(crate::HasCustomAlignmentWithGnuAttr: Copy);
+    static_assertions::assert_not_impl_any!(crate::HasCustomAlignmentWithGnuAttr: Copy);
     static_assertions::assert_not_impl_any!(crate::HasCustomAlignmentWithGnuAttr: Drop);
 
     assert!(
@@ -477,7 +754,6 @@ const _: () = {
             crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE,
         >() == 1
     );
-    static_assertions::assert_impl_all!(crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE: Clone);
-    static_assertions::assert_impl_all!(crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE: Copy);
+    static_assertions::assert_not_impl_any!(crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE: Copy);
     static_assertions::assert_not_impl_any!(crate::__CcTemplateInstN28template_with_preferred_name12SomeTemplateIiEE: Drop);
 };
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct HasCustomAlignment) == 64);
 static_assert(alignof(struct HasCustomAlignment) == 64);
@@ -27,6 +28,12 @@ extern "C" void __rust_thunk___ZN18HasCustomAlignmentC1Ev(
   crubit::construct_at(__this);
 }
 
+extern "C" void __rust_thunk___ZN18HasCustomAlignmentC1ERKS_(
+    struct HasCustomAlignment* __this,
+    const struct HasCustomAlignment* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void __rust_thunk___ZN18HasCustomAlignmentC1EOS_(
     struct HasCustomAlignment* __this, struct HasCustomAlignment* __param_0) {
   crubit::construct_at(__this, std::move(*__param_0));
@@ -82,6 +89,12 @@ extern "C" void __rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1Ev(
   crubit::construct_at(__this);
 }
 
+extern "C" void __rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1ERKS_(
+    struct InheritsFromBaseWithCustomAlignment* __this,
+    const struct InheritsFromBaseWithCustomAlignment* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void __rust_thunk___ZN35InheritsFromBaseWithCustomAlignmentC1EOS_(
     struct InheritsFromBaseWithCustomAlignment* __this,
     struct InheritsFromBaseWithCustomAlignment* __param_0) {
@@ -110,6 +123,12 @@ extern "C" void __rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1Ev(
   crubit::construct_at(__this);
 }
 
+extern "C" void __rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1ERKS_(
+    struct HasCustomAlignmentWithGnuAttr* __this,
+    const struct HasCustomAlignmentWithGnuAttr* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void __rust_thunk___ZN29HasCustomAlignmentWithGnuAttrC1EOS_(
     struct HasCustomAlignmentWithGnuAttr* __this,
     struct HasCustomAlignmentWithGnuAttr* __param_0) {
@@ -141,6 +160,13 @@ __rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1Ev__2f_2fthird
   crubit::construct_at(__this);
 }
 
+extern "C" void
+__rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1ERKS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc(
+    struct template_with_preferred_name::SomeTemplate<int>* __this,
+    const struct template_with_preferred_name::SomeTemplate<int>* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void
 __rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiEC1EOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3aclang_5fattrs_5fcc(
     struct template_with_preferred_name::SomeTemplate<int>* __this,
@@ -168,4 +194,6 @@ __rust_thunk___ZN28template_with_preferred_name12SomeTemplateIiE3fooEv__2f_2fthi
   return __this->foo();
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -33,6 +33,9 @@ pub struct Foo {
 }
 impl !Send for Foo {}
 impl !Sync for Foo {}
+impl oops::CcClass for Foo {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Foo"), crate::Foo);
 
 impl Default for Foo {
@@ -105,6 +108,9 @@ pub struct Bar {
 }
 impl !Send for Bar {}
 impl !Sync for Bar {}
+impl oops::CcClass for Bar {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Bar"), crate::Bar);
 
 impl Default for Bar {
@@ -156,6 +162,9 @@ pub struct HasNoComments {
 }
 impl !Send for HasNoComments {}
 impl !Sync for HasNoComments {}
+impl oops::CcClass for HasNoComments {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("HasNoComments"), crate::HasNoComments);
 
 impl Default for HasNoComments {
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct Foo) == 8);
 static_assert(alignof(struct Foo) == 4);
@@ -92,4 +93,6 @@ extern "C" struct HasNoComments* __rust_thunk___ZN13HasNoCommentsaSEOS_(
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
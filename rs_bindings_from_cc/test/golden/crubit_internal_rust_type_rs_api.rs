@@ -52,6 +52,9 @@ pub struct TypeMapOverrideFieldTypes {
 }
 impl !Send for TypeMapOverrideFieldTypes {}
 impl !Sync for TypeMapOverrideFieldTypes {}
+impl oops::CcClass for TypeMapOverrideFieldTypes {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("TypeMapOverrideFieldTypes"),
     crate::TypeMapOverrideFieldTypes
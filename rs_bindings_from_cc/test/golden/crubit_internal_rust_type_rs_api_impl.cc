@@ -18,6 +18,19 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
+
+static_assert(sizeof(struct MyI8Struct) == 1);
+static_assert(alignof(struct MyI8Struct) == 1);
+
+static_assert(sizeof(struct MyI8Class) == 1);
+static_assert(alignof(struct MyI8Class) == 1);
+
+static_assert(sizeof(enum MyI8Enum) == 1);
+static_assert(alignof(enum MyI8Enum) == 1);
+
+static_assert(sizeof(MyI8Alias) == 1);
+static_assert(alignof(MyI8Alias) == 1);
 
 static_assert(sizeof(struct TypeMapOverrideFieldTypes) == 5);
 static_assert(alignof(struct TypeMapOverrideFieldTypes) == 1);
@@ -56,4 +69,6 @@ __rust_thunk___ZN25TypeMapOverrideFieldTypesaSEOS_(
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -15,7 +15,13 @@
 #![allow(nonstandard_style)]
 #![deny(warnings)]
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "ForwardDeclaredStruct")]
 pub struct ForwardDeclaredStruct {
@@ -23,6 +29,9 @@ pub struct ForwardDeclaredStruct {
 }
 impl !Send for ForwardDeclaredStruct {}
 impl !Sync for ForwardDeclaredStruct {}
+impl oops::CcClass for ForwardDeclaredStruct {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("ForwardDeclaredStruct"),
     crate::ForwardDeclaredStruct
@@ -46,7 +55,6 @@ forward_declare::unsafe_define!(
 const _: () = {
     assert!(::core::mem::size_of::<crate::ForwardDeclaredStruct>() == 1);
     assert!(::core::mem::align_of::<crate::ForwardDeclaredStruct>() == 1);
-    static_assertions::assert_impl_all!(crate::ForwardDeclaredStruct: Clone);
-    static_assertions::assert_impl_all!(crate::ForwardDeclaredStruct: Copy);
+    static_assertions::assert_not_impl_any!(crate::ForwardDeclaredStruct: Copy);
     static_assertions::assert_not_impl_any!(crate::ForwardDeclaredStruct: Drop);
 };
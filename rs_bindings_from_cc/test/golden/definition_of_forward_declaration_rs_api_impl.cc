@@ -18,8 +18,11 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(sizeof(struct ForwardDeclaredStruct) == 1);
 static_assert(alignof(struct ForwardDeclaredStruct) == 1);
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -28,6 +28,9 @@ pub struct DocCommentSlashes {
 }
 impl !Send for DocCommentSlashes {}
 impl !Sync for DocCommentSlashes {}
+impl oops::CcClass for DocCommentSlashes {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("DocCommentSlashes"),
     crate::DocCommentSlashes
@@ -126,6 +129,9 @@ pub struct DocCommentBang {
 }
 impl !Send for DocCommentBang {}
 impl !Sync for DocCommentBang {}
+impl oops::CcClass for DocCommentBang {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("DocCommentBang"), crate::DocCommentBang);
 
 impl Default for DocCommentBang {
@@ -180,6 +186,9 @@ pub struct MultilineCommentTwoStars {
 }
 impl !Send for MultilineCommentTwoStars {}
 impl !Sync for MultilineCommentTwoStars {}
+impl oops::CcClass for MultilineCommentTwoStars {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MultilineCommentTwoStars"),
     crate::MultilineCommentTwoStars
@@ -237,6 +246,9 @@ pub struct LineComment {
 }
 impl !Send for LineComment {}
 impl !Sync for LineComment {}
+impl oops::CcClass for LineComment {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("LineComment"), crate::LineComment);
 
 impl Default for LineComment {
@@ -291,6 +303,9 @@ pub struct MultilineOneStar {
 }
 impl !Send for MultilineOneStar {}
 impl !Sync for MultilineOneStar {}
+impl oops::CcClass for MultilineOneStar {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MultilineOneStar"),
     crate::MultilineOneStar
@@ -376,6 +391,9 @@ pub struct __CcTemplateInst10MyTemplateIiE {
 }
 impl !Send for __CcTemplateInst10MyTemplateIiE {}
 impl !Sync for __CcTemplateInst10MyTemplateIiE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateIiE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < int >"),
     crate::__CcTemplateInst10MyTemplateIiE
@@ -445,6 +463,9 @@ pub struct __CcTemplateInst10MyTemplateIfE {
 }
 impl !Send for __CcTemplateInst10MyTemplateIfE {}
 impl !Sync for __CcTemplateInst10MyTemplateIfE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateIfE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < float >"),
     crate::__CcTemplateInst10MyTemplateIfE
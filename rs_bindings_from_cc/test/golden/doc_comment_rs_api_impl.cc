@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct DocCommentSlashes) == 4);
 static_assert(alignof(struct DocCommentSlashes) == 4);
@@ -211,4 +212,6 @@ __rust_thunk___ZNK10MyTemplateIfE15get_field_valueEv__2f_2fthird_5fparty_2fcrubi
   return &__this->get_field_value();
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -23,6 +23,9 @@ pub struct r#type {
 }
 impl !Send for r#type {}
 impl !Sync for r#type {}
+impl oops::CcClass for r#type {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("type"), crate::r#type);
 
 impl Default for r#type {
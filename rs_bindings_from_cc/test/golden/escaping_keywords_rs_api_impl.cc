@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct type) == 4);
 static_assert(alignof(struct type) == 4);
@@ -42,4 +43,6 @@ extern "C" struct type* __rust_thunk___ZN4typeaSEOS_(struct type* __this,
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
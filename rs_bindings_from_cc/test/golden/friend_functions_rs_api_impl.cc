@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(sizeof(class SomeClass) == 1);
 static_assert(alignof(class SomeClass) == 1);
@@ -51,4 +52,6 @@ extern "C" int __rust_thunk___Z21multiple_declarationsRK9SomeClass(
   return multiple_declarations(*__param_0);
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
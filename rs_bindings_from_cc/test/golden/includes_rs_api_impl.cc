@@ -18,5 +18,8 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
+
+#pragma GCC visibility pop
 
 #pragma clang diagnostic pop
@@ -18,7 +18,14 @@
 /// Using classes to force these to be non-POD.
 /// In the Itanium ABI, the tail padding of POD types cannot be reused by other
 /// objects, even if the POD type is potentially-overlapping.
-#[derive(Clone, Copy)]
+///
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "Base0")]
 pub struct Base0 {
@@ -26,49 +33,108 @@ pub struct Base0 {
 }
 impl !Send for Base0 {}
 impl !Sync for Base0 {}
+impl oops::CcClass for Base0 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Base0"), crate::Base0);
 
-impl Default for Base0 {
+impl ::ctor::CtorNew<()> for Base0 {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN5Base0C1Ev(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN5Base0C1Ev(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>> for Base0 {
+impl<'b> ::ctor::CtorNew<&'b Self> for Base0 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN5Base0C1EOS_(&mut tmp, __param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN5Base0C1ERKS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for Base0 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for Base0 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN5Base0C1EOS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for Base0 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for Base0 {
+impl<'b> ::ctor::Assign<&'b Self> for Base0 {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN5Base0aSERKS_(self, __param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for Base0 {
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for Base0 {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN5Base0aSEOS_(self, __param_0);
         }
     }
 }
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C, align(8))]
 #[__crubit::annotate(cc_type = "Base1")]
 pub struct Base1 {
@@ -82,48 +148,104 @@ pub struct Base1 {
 }
 impl !Send for Base1 {}
 impl !Sync for Base1 {}
+impl oops::CcClass for Base1 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Base1"), crate::Base1);
 
-impl Default for Base1 {
+impl ::ctor::CtorNew<()> for Base1 {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN5Base1C1Ev(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN5Base1C1Ev(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>> for Base1 {
+impl<'b> ::ctor::CtorNew<&'b Self> for Base1 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN5Base1C1EOS_(&mut tmp, __param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN5Base1C1ERKS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for Base1 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for Base1 {
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for Base1 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN5Base1C1EOS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for Base1 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self> for Base1 {
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN5Base1aSERKS_(self, __param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for Base1 {
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for Base1 {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN5Base1aSEOS_(self, __param_0);
         }
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[derive(Clone, Copy)]
 #[repr(C, align(2))]
 #[__crubit::annotate(cc_type = "Base2")]
@@ -135,6 +257,9 @@ pub struct Base2 {
 }
 impl !Send for Base2 {}
 impl !Sync for Base2 {}
+impl oops::CcClass for Base2 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Base2"), crate::Base2);
 
 impl Default for Base2 {
@@ -186,6 +311,9 @@ pub struct Derived {
 }
 impl !Send for Derived {}
 impl !Sync for Derived {}
+impl oops::CcClass for Derived {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Derived"), crate::Derived);
 
 impl Default for Derived {
@@ -244,6 +372,9 @@ unsafe impl oops::Inherits<crate::Base2> for crate::Derived {
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned]
 #[repr(C, align(8))]
 #[__crubit::annotate(cc_type = "VirtualBase1")]
@@ -252,6 +383,9 @@ pub struct VirtualBase1 {
 }
 impl !Send for VirtualBase1 {}
 impl !Sync for VirtualBase1 {}
+impl oops::CcClass for VirtualBase1 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("VirtualBase1"), crate::VirtualBase1);
 
 impl ::ctor::CtorNew<()> for VirtualBase1 {
@@ -350,6 +484,9 @@ unsafe impl oops::Inherits<crate::Base1> for crate::VirtualBase1 {
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned]
 #[repr(C, align(8))]
 #[__crubit::annotate(cc_type = "VirtualBase2")]
@@ -358,6 +495,9 @@ pub struct VirtualBase2 {
 }
 impl !Send for VirtualBase2 {}
 impl !Sync for VirtualBase2 {}
+impl oops::CcClass for VirtualBase2 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("VirtualBase2"), crate::VirtualBase2);
 
 impl ::ctor::CtorNew<()> for VirtualBase2 {
@@ -456,6 +596,9 @@ unsafe impl oops::Inherits<crate::Base1> for crate::VirtualBase2 {
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned]
 #[repr(C, align(8))]
 #[__crubit::annotate(cc_type = "VirtualDerived")]
@@ -464,6 +607,9 @@ pub struct VirtualDerived {
 }
 impl !Send for VirtualDerived {}
 impl !Sync for VirtualDerived {}
+impl oops::CcClass for VirtualDerived {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("VirtualDerived"), crate::VirtualDerived);
 
 impl ::ctor::CtorNew<()> for VirtualDerived {
@@ -572,6 +718,9 @@ unsafe impl oops::Inherits<crate::VirtualBase2> for crate::VirtualDerived {
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned]
 #[repr(C, align(8))]
 #[__crubit::annotate(cc_type = "MyAbstractClass")]
@@ -580,6 +729,9 @@ pub struct MyAbstractClass {
 }
 impl !Send for MyAbstractClass {}
 impl !Sync for MyAbstractClass {}
+impl oops::CcClass for MyAbstractClass {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyAbstractClass"),
     crate::MyAbstractClass
@@ -601,7 +753,14 @@ impl<'b> ::ctor::Assign<&'b Self> for MyAbstractClass {
 }
 
 /// Method inheritance
-#[derive(Clone, Copy)]
+///
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "MethodBase1")]
 pub struct MethodBase1 {
@@ -609,42 +768,95 @@ pub struct MethodBase1 {
 }
 impl !Send for MethodBase1 {}
 impl !Sync for MethodBase1 {}
+impl oops::CcClass for MethodBase1 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("MethodBase1"), crate::MethodBase1);
 
-impl Default for MethodBase1 {
+impl ::ctor::CtorNew<()> for MethodBase1 {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN11MethodBase1C1Ev(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN11MethodBase1C1Ev(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>> for MethodBase1 {
+impl<'b> ::ctor::CtorNew<&'b Self> for MethodBase1 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN11MethodBase1C1EOS_(&mut tmp, __param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN11MethodBase1C1ERKS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for MethodBase1 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for MethodBase1 {
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for MethodBase1 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN11MethodBase1C1EOS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for MethodBase1 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self> for MethodBase1 {
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN11MethodBase1aSERKS_(self, __param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for MethodBase1 {
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for MethodBase1 {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN11MethodBase1aSEOS_(self, __param_0);
         }
@@ -653,26 +865,32 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for MethodBase1
 
 impl MethodBase1 {
     #[inline(always)]
-    pub fn Public<'a>(&'a mut self) {
+    pub fn Public<'a>(self: ::core::pin::Pin<&'a mut Self>) {
         unsafe { crate::detail::__rust_thunk___ZN11MethodBase16PublicEv(self) }
     }
 }
 
 impl MethodBase1 {
     #[inline(always)]
-    pub fn Colliding1<'a>(&'a mut self) {
+    pub fn Colliding1<'a>(self: ::core::pin::Pin<&'a mut Self>) {
         unsafe { crate::detail::__rust_thunk___ZN11MethodBase110Colliding1Ev(self) }
     }
 }
 
 impl MethodBase1 {
     #[inline(always)]
-    pub fn Colliding2<'a>(&'a mut self) {
+    pub fn Colliding2<'a>(self: ::core::pin::Pin<&'a mut Self>) {
         unsafe { crate::detail::__rust_thunk___ZN11MethodBase110Colliding2Ev(self) }
     }
 }
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "MethodBase2")]
 pub struct MethodBase2 {
@@ -680,42 +898,95 @@ pub struct MethodBase2 {
 }
 impl !Send for MethodBase2 {}
 impl !Sync for MethodBase2 {}
+impl oops::CcClass for MethodBase2 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("MethodBase2"), crate::MethodBase2);
 
-impl Default for MethodBase2 {
+impl ::ctor::CtorNew<()> for MethodBase2 {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN11MethodBase2C1Ev(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN11MethodBase2C1Ev(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>> for MethodBase2 {
+impl<'b> ::ctor::CtorNew<&'b Self> for MethodBase2 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN11MethodBase2C1EOS_(&mut tmp, __param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN11MethodBase2C1ERKS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for MethodBase2 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for MethodBase2 {
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for MethodBase2 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN11MethodBase2C1EOS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for MethodBase2 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self> for MethodBase2 {
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN11MethodBase2aSERKS_(self, __param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for MethodBase2 {
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for MethodBase2 {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN11MethodBase2aSEOS_(self, __param_0);
         }
@@ -724,14 +995,14 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for MethodBase2
 
 impl MethodBase2 {
     #[inline(always)]
-    pub fn Colliding1<'a>(&'a mut self) {
+    pub fn Colliding1<'a>(self: ::core::pin::Pin<&'a mut Self>) {
         unsafe { crate::detail::__rust_thunk___ZN11MethodBase210Colliding1Ev(self) }
     }
 }
 
 impl MethodBase2 {
     #[inline(always)]
-    pub fn Colliding2<'a>(&'a mut self) {
+    pub fn Colliding2<'a>(self: ::core::pin::Pin<&'a mut Self>) {
         unsafe { crate::detail::__rust_thunk___ZN11MethodBase210Colliding2Ev(self) }
     }
 }
@@ -744,6 +1015,9 @@ pub struct MethodDerived {
 }
 impl !Send for MethodDerived {}
 impl !Sync for MethodDerived {}
+impl oops::CcClass for MethodDerived {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("MethodDerived"), crate::MethodDerived);
 
 impl Default for MethodDerived {
@@ -804,33 +1078,41 @@ mod detail {
         pub(crate) fn __rust_thunk___ZN5Base0C1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::Base0>,
         );
+        pub(crate) fn __rust_thunk___ZN5Base0C1ERKS_<'a, 'b>(
+            __this: &'a mut ::core::mem::MaybeUninit<crate::Base0>,
+            __param_0: &'b crate::Base0,
+        );
         pub(crate) fn __rust_thunk___ZN5Base0C1EOS_<'a, 'b>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::Base0>,
             __param_0: ::ctor::RvalueReference<'b, crate::Base0>,
         );
         pub(crate) fn __rust_thunk___ZN5Base0aSERKS_<'a, 'b>(
-            __this: &'a mut crate::Base0,
+            __this: ::core::pin::Pin<&'a mut crate::Base0>,
             __param_0: &'b crate::Base0,
-        ) -> &'a mut crate::Base0;
+        ) -> ::core::pin::Pin<&'a mut crate::Base0>;
         pub(crate) fn __rust_thunk___ZN5Base0aSEOS_<'a, 'b>(
-            __this: &'a mut crate::Base0,
+            __this: ::core::pin::Pin<&'a mut crate::Base0>,
             __param_0: ::ctor::RvalueReference<'b, crate::Base0>,
-        ) -> &'a mut crate::Base0;
+        ) -> ::core::pin::Pin<&'a mut crate::Base0>;
         pub(crate) fn __rust_thunk___ZN5Base1C1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::Base1>,
         );
+        pub(crate) fn __rust_thunk___ZN5Base1C1ERKS_<'a, 'b>(
+            __this: &'a mut ::core::mem::MaybeUninit<crate::Base1>,
+            __param_0: &'b crate::Base1,
+        );
         pub(crate) fn __rust_thunk___ZN5Base1C1EOS_<'a, 'b>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::Base1>,
             __param_0: ::ctor::RvalueReference<'b, crate::Base1>,
         );
         pub(crate) fn __rust_thunk___ZN5Base1aSERKS_<'a, 'b>(
-            __this: &'a mut crate::Base1,
+            __this: ::core::pin::Pin<&'a mut crate::Base1>,
             __param_0: &'b crate::Base1,
-        ) -> &'a mut crate::Base1;
+        ) -> ::core::pin::Pin<&'a mut crate::Base1>;
         pub(crate) fn __rust_thunk___ZN5Base1aSEOS_<'a, 'b>(
-            __this: &'a mut crate::Base1,
+            __this: ::core::pin::Pin<&'a mut crate::Base1>,
             __param_0: ::ctor::RvalueReference<'b, crate::Base1>,
-        ) -> &'a mut crate::Base1;
+        ) -> ::core::pin::Pin<&'a mut crate::Base1>;
         pub(crate) fn __rust_thunk___ZN5Base2C1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::Base2>,
         );
@@ -940,52 +1222,60 @@ mod detail {
         pub(crate) fn __rust_thunk___ZN11MethodBase1C1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::MethodBase1>,
         );
+        pub(crate) fn __rust_thunk___ZN11MethodBase1C1ERKS_<'a, 'b>(
+            __this: &'a mut ::core::mem::MaybeUninit<crate::MethodBase1>,
+            __param_0: &'b crate::MethodBase1,
+        );
         pub(crate) fn __rust_thunk___ZN11MethodBase1C1EOS_<'a, 'b>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::MethodBase1>,
             __param_0: ::ctor::RvalueReference<'b, crate::MethodBase1>,
         );
         pub(crate) fn __rust_thunk___ZN11MethodBase1aSERKS_<'a, 'b>(
-            __this: &'a mut crate::MethodBase1,
+            __this: ::core::pin::Pin<&'a mut crate::MethodBase1>,
             __param_0: &'b crate::MethodBase1,
-        ) -> &'a mut crate::MethodBase1;
+        ) -> ::core::pin::Pin<&'a mut crate::MethodBase1>;
         pub(crate) fn __rust_thunk___ZN11MethodBase1aSEOS_<'a, 'b>(
-            __this: &'a mut crate::MethodBase1,
+            __this: ::core::pin::Pin<&'a mut crate::MethodBase1>,
             __param_0: ::ctor::RvalueReference<'b, crate::MethodBase1>,
-        ) -> &'a mut crate::MethodBase1;
+        ) -> ::core::pin::Pin<&'a mut crate::MethodBase1>;
         #[link_name = "_ZN11MethodBase16PublicEv"]
         pub(crate) fn __rust_thunk___ZN11MethodBase16PublicEv<'a>(
-            __this: &'a mut crate::MethodBase1,
+            __this: ::core::pin::Pin<&'a mut crate::MethodBase1>,
         );
         #[link_name = "_ZN11MethodBase110Colliding1Ev"]
         pub(crate) fn __rust_thunk___ZN11MethodBase110Colliding1Ev<'a>(
-            __this: &'a mut crate::MethodBase1,
+            __this: ::core::pin::Pin<&'a mut crate::MethodBase1>,
         );
         #[link_name = "_ZN11MethodBase110Colliding2Ev"]
         pub(crate) fn __rust_thunk___ZN11MethodBase110Colliding2Ev<'a>(
-            __this: &'a mut crate::MethodBase1,
+            __this: ::core::pin::Pin<&'a mut crate::MethodBase1>,
         );
         pub(crate) fn __rust_thunk___ZN11MethodBase2C1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::MethodBase2>,
         );
+        pub(crate) fn __rust_thunk___ZN11MethodBase2C1ERKS_<'a, 'b>(
+            __this: &'a mut ::core::mem::MaybeUninit<crate::MethodBase2>,
+            __param_0: &'b crate::MethodBase2,
+        );
         pub(crate) fn __rust_thunk___ZN11MethodBase2C1EOS_<'a, 'b>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::MethodBase2>,
             __param_0: ::ctor::RvalueReference<'b, crate::MethodBase2>,
         );
         pub(crate) fn __rust_thunk___ZN11MethodBase2aSERKS_<'a, 'b>(
-            __this: &'a mut crate::MethodBase2,
+            __this: ::core::pin::Pin<&'a mut crate::MethodBase2>,
             __param_0: &'b crate::MethodBase2,
-        ) -> &'a mut crate::MethodBase2;
+        ) -> ::core::pin::Pin<&'a mut crate::MethodBase2>;
         pub(crate) fn __rust_thunk___ZN11MethodBase2aSEOS_<'a, 'b>(
-            __this: &'a mut crate::MethodBase2,
+            __this: ::core::pin::Pin<&'a mut crate::MethodBase2>,
             __param_0: ::ctor::RvalueReference<'b, crate::MethodBase2>,
-        ) -> &'a mut crate::MethodBase2;
+        ) -> ::core::pin::Pin<&'a mut crate::MethodBase2>;
         #[link_name = "_ZN11MethodBase210Colliding1Ev"]
         pub(crate) fn __rust_thunk___ZN11MethodBase210Colliding1Ev<'a>(
-            __this: &'a mut crate::MethodBase2,
+            __this: ::core::pin::Pin<&'a mut crate::MethodBase2>,
         );
         #[link_name = "_ZN11MethodBase210Colliding2Ev"]
         pub(crate) fn __rust_thunk___ZN11MethodBase210Colliding2Ev<'a>(
-            __this: &'a mut crate::MethodBase2,
+            __this: ::core::pin::Pin<&'a mut crate::MethodBase2>,
         );
         pub(crate) fn __rust_thunk___ZN13MethodDerivedC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::MethodDerived>,
@@ -1008,14 +1298,12 @@ mod detail {
 const _: () = {
     assert!(::core::mem::size_of::<crate::Base0>() == 1);
     assert!(::core::mem::align_of::<crate::Base0>() == 1);
-    static_assertions::assert_impl_all!(crate::Base0: Clone);
-    static_assertions::assert_impl_all!(crate::Base0: Copy);
+    static_assertions::assert_not_impl_any!(crate::Base0: Copy);
     static_assertions::assert_not_impl_any!(crate::Base0: Drop);
 
     assert!(::core::mem::size_of::<crate::Base1>() == 16);
     assert!(::core::mem::align_of::<crate::Base1>() == 8);
-    static_assertions::assert_impl_all!(crate::Base1: Clone);
-    static_assertions::assert_impl_all!(crate::Base1: Copy);
+    static_assertions::assert_not_impl_any!(crate::Base1: Copy);
     static_assertions::assert_not_impl_any!(crate::Base1: Drop);
     assert!(::core::mem::offset_of!(crate::Base1, b1_1_) == 0);
     assert!(::core::mem::offset_of!(crate::Base1, b1_2_) == 8);
@@ -1056,14 +1344,12 @@ const _: () = {
 
     assert!(::core::mem::size_of::<crate::MethodBase1>() == 1);
     assert!(::core::mem::align_of::<crate::MethodBase1>() == 1);
-    static_assertions::assert_impl_all!(crate::MethodBase1: Clone);
-    static_assertions::assert_impl_all!(crate::MethodBase1: Copy);
+    static_assertions::assert_not_impl_any!(crate::MethodBase1: Copy);
     static_assertions::assert_not_impl_any!(crate::MethodBase1: Drop);
 
     assert!(::core::mem::size_of::<crate::MethodBase2>() == 1);
     assert!(::core::mem::align_of::<crate::MethodBase2>() == 1);
-    static_assertions::assert_impl_all!(crate::MethodBase2: Clone);
-    static_assertions::assert_impl_all!(crate::MethodBase2: Copy);
+    static_assertions::assert_not_impl_any!(crate::MethodBase2: Copy);
     static_assertions::assert_not_impl_any!(crate::MethodBase2: Drop);
 
     assert!(::core::mem::size_of::<crate::MethodDerived>() == 1);
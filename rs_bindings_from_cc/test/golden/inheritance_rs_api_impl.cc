@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(sizeof(class Base0) == 1);
 static_assert(alignof(class Base0) == 1);
@@ -26,6 +27,11 @@ extern "C" void __rust_thunk___ZN5Base0C1Ev(class Base0* __this) {
   crubit::construct_at(__this);
 }
 
+extern "C" void __rust_thunk___ZN5Base0C1ERKS_(class Base0* __this,
+                                               const class Base0* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void __rust_thunk___ZN5Base0C1EOS_(class Base0* __this,
                                               class Base0* __param_0) {
   crubit::construct_at(__this, std::move(*__param_0));
@@ -48,6 +54,11 @@ extern "C" void __rust_thunk___ZN5Base1C1Ev(class Base1* __this) {
   crubit::construct_at(__this);
 }
 
+extern "C" void __rust_thunk___ZN5Base1C1ERKS_(class Base1* __this,
+                                               const class Base1* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void __rust_thunk___ZN5Base1C1EOS_(class Base1* __this,
                                               class Base1* __param_0) {
   crubit::construct_at(__this, std::move(*__param_0));
@@ -235,6 +246,11 @@ extern "C" void __rust_thunk___ZN11MethodBase1C1Ev(class MethodBase1* __this) {
   crubit::construct_at(__this);
 }
 
+extern "C" void __rust_thunk___ZN11MethodBase1C1ERKS_(
+    class MethodBase1* __this, const class MethodBase1* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void __rust_thunk___ZN11MethodBase1C1EOS_(
     class MethodBase1* __this, class MethodBase1* __param_0) {
   crubit::construct_at(__this, std::move(*__param_0));
@@ -257,6 +273,11 @@ extern "C" void __rust_thunk___ZN11MethodBase2C1Ev(class MethodBase2* __this) {
   crubit::construct_at(__this);
 }
 
+extern "C" void __rust_thunk___ZN11MethodBase2C1ERKS_(
+    class MethodBase2* __this, const class MethodBase2* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void __rust_thunk___ZN11MethodBase2C1EOS_(
     class MethodBase2* __this, class MethodBase2* __param_0) {
   crubit::construct_at(__this, std::move(*__param_0));
@@ -295,4 +316,6 @@ extern "C" class MethodDerived* __rust_thunk___ZN13MethodDerivedaSEOS_(
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
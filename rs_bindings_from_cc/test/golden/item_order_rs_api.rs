@@ -23,6 +23,9 @@ pub struct FirstStruct {
 }
 impl !Send for FirstStruct {}
 impl !Sync for FirstStruct {}
+impl oops::CcClass for FirstStruct {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("FirstStruct"), crate::FirstStruct);
 
 impl Default for FirstStruct {
@@ -78,6 +81,9 @@ pub struct SecondStruct {
 }
 impl !Send for SecondStruct {}
 impl !Sync for SecondStruct {}
+impl oops::CcClass for SecondStruct {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("SecondStruct"), crate::SecondStruct);
 
 impl Default for SecondStruct {
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct FirstStruct) == 4);
 static_assert(alignof(struct FirstStruct) == 4);
@@ -70,4 +71,6 @@ extern "C" struct SecondStruct* __rust_thunk___ZN12SecondStructaSEOS_(
 
 extern "C" int __rust_thunk___Z11second_funcv() { return second_func(); }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -43,8 +43,7 @@ pub unsafe fn ConsumeArray(pair: *mut ::core::ffi::c_int) {
     crate::detail::__rust_thunk___Z12ConsumeArrayPi(pair)
 }
 
-// Error while generating bindings for item 'Arr':
-// Unsupported type 'int[2]': Unsupported clang::Type class 'ConstantArray'
+pub type Arr = [::core::ffi::c_int; 2];
 
 #[inline(always)]
 pub unsafe fn ConsumeArrayWithTypedef(__param_0: *mut ::core::ffi::c_int) {
@@ -15,7 +15,13 @@
 #![allow(nonstandard_style)]
 #![deny(warnings)]
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "Noninline")]
 pub struct Noninline {
@@ -23,6 +29,9 @@ pub struct Noninline {
 }
 impl !Send for Noninline {}
 impl !Sync for Noninline {}
+impl oops::CcClass for Noninline {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Noninline"), crate::Noninline);
 
 // Error while generating bindings for item 'Noninline::Noninline':
@@ -75,7 +84,13 @@ impl Noninline {
     }
 }
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "Inline")]
 pub struct Inline {
@@ -83,6 +98,9 @@ pub struct Inline {
 }
 impl !Send for Inline {}
 impl !Sync for Inline {}
+impl oops::CcClass for Inline {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Inline"), crate::Inline);
 
 // Error while generating bindings for item 'Inline::Inline':
@@ -166,13 +184,11 @@ mod detail {
 const _: () = {
     assert!(::core::mem::size_of::<crate::Noninline>() == 1);
     assert!(::core::mem::align_of::<crate::Noninline>() == 1);
-    static_assertions::assert_impl_all!(crate::Noninline: Clone);
-    static_assertions::assert_impl_all!(crate::Noninline: Copy);
+    static_assertions::assert_not_impl_any!(crate::Noninline: Copy);
     static_assertions::assert_not_impl_any!(crate::Noninline: Drop);
 
     assert!(::core::mem::size_of::<crate::Inline>() == 1);
     assert!(::core::mem::align_of::<crate::Inline>() == 1);
-    static_assertions::assert_impl_all!(crate::Inline: Clone);
-    static_assertions::assert_impl_all!(crate::Inline: Copy);
+    static_assertions::assert_not_impl_any!(crate::Inline: Copy);
     static_assertions::assert_not_impl_any!(crate::Inline: Drop);
 };
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(sizeof(struct Noninline) == 1);
 static_assert(alignof(struct Noninline) == 1);
@@ -50,4 +51,6 @@ extern "C" void __rust_thunk___ZNKO6Inline17RvalueMethodConstEv(
   std::move(*__this).RvalueMethodConst();
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -24,6 +24,9 @@ pub mod test_namespace_bindings {
     }
     impl !Send for S {}
     impl !Sync for S {}
+    impl oops::CcClass for S {
+        const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+    }
     forward_declare::unsafe_define!(
         forward_declare::symbol!("test_namespace_bindings :: S"),
         crate::test_namespace_bindings::S
@@ -123,6 +126,9 @@ pub mod test_namespace_bindings_reopened_0 {
         }
         impl !Send for S {}
         impl !Sync for S {}
+        impl oops::CcClass for S {
+            const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+        }
         forward_declare::unsafe_define!(
             forward_declare::symbol!("test_namespace_bindings_reopened :: inner :: S"),
             crate::test_namespace_bindings_reopened::inner::S
@@ -216,6 +222,9 @@ pub mod test_namespace_bindings_inline {
         }
         impl !Send for StructInInlineNamespace {}
         impl !Sync for StructInInlineNamespace {}
+        impl oops::CcClass for StructInInlineNamespace {
+            const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+        }
         forward_declare::unsafe_define!(
             forward_declare::symbol!(
                 "test_namespace_bindings_inline :: inner :: StructInInlineNamespace"
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct test_namespace_bindings::S) == 4);
 static_assert(alignof(struct test_namespace_bindings::S) == 4);
@@ -151,4 +152,6 @@ __rust_thunk___Z45useStructInInlineNamespaceSkipInlineQualifierN30test_namespace
 
 extern "C" void __rust_thunk___ZN4impl3fooEv() { impl::foo(); }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -28,6 +28,9 @@ pub struct S {
 }
 impl !Send for S {}
 impl !Sync for S {}
+impl oops::CcClass for S {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("S"), crate::S);
 
 // Error while generating bindings for item 'S::S':
@@ -67,6 +70,9 @@ impl S {
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned(PinnedDrop)]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "TriviallyCopyableButNontriviallyDestructible")]
@@ -75,6 +81,9 @@ pub struct TriviallyCopyableButNontriviallyDestructible {
 }
 impl !Send for TriviallyCopyableButNontriviallyDestructible {}
 impl !Sync for TriviallyCopyableButNontriviallyDestructible {}
+impl oops::CcClass for TriviallyCopyableButNontriviallyDestructible {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("TriviallyCopyableButNontriviallyDestructible"),
     crate::TriviallyCopyableButNontriviallyDestructible
@@ -98,6 +107,9 @@ pub unsafe fn take_pointer(p: *mut ::core::ffi::c_int) {
     crate::detail::__rust_thunk___Z12take_pointerPi(p)
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[derive(Clone, Copy)]
 #[repr(C, align(4))]
 #[__crubit::annotate(cc_type = "WrappedValue")]
@@ -109,6 +121,9 @@ pub struct WrappedValue {
 }
 impl !Send for WrappedValue {}
 impl !Sync for WrappedValue {}
+impl oops::CcClass for WrappedValue {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("WrappedValue"), crate::WrappedValue);
 
 // Error while generating bindings for item 'WrappedValue::WrappedValue':
@@ -126,8 +141,14 @@ forward_declare::unsafe_define!(forward_declare::symbol!("WrappedValue"), crate:
 // Error while generating bindings for item 'WrappedValue::WrappedValue':
 // Unsafe constructors (e.g. with no elided or explicit lifetimes) are intentionally not supported
 
-// Error while generating bindings for item 'WrappedValue::operator+':
-// Expected first parameter to be a record or reference
+impl WrappedValue {
+    #[inline(always)]
+    pub unsafe fn add(__this: *const Self, rhs: *const Self) -> crate::WrappedValue {
+        let mut __return = ::core::mem::MaybeUninit::<Self>::uninit();
+        crate::detail::__rust_thunk___ZNK12WrappedValueplERKS_(&mut __return, __this, rhs);
+        __return.assume_init()
+    }
+}
 
 mod detail {
     #[allow(unused_imports)]
@@ -155,6 +176,11 @@ mod detail {
         );
         #[link_name = "_Z12take_pointerPi"]
         pub(crate) fn __rust_thunk___Z12take_pointerPi(p: *mut ::core::ffi::c_int);
+        pub(crate) fn __rust_thunk___ZNK12WrappedValueplERKS_(
+            __return: &mut ::core::mem::MaybeUninit<crate::WrappedValue>,
+            __this: *const crate::WrappedValue,
+            rhs: *const crate::WrappedValue,
+        );
     }
 }
 
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(sizeof(struct S) == 1);
 static_assert(alignof(struct S) == 1);
@@ -29,4 +30,12 @@ static_assert(alignof(struct TriviallyCopyableButNontriviallyDestructible) ==
 static_assert(CRUBIT_SIZEOF(class WrappedValue) == 4);
 static_assert(alignof(class WrappedValue) == 4);
 
+extern "C" void __rust_thunk___ZNK12WrappedValueplERKS_(
+    class WrappedValue* __return, const class WrappedValue* __this,
+    const class WrappedValue* rhs) {
+  new (__return) auto(__this->operator+(*rhs));
+}
+
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
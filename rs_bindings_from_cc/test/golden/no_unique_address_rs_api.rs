@@ -36,6 +36,9 @@ pub struct Struct {
 }
 impl !Send for Struct {}
 impl !Sync for Struct {}
+impl oops::CcClass for Struct {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Struct"), crate::Struct);
 impl Struct {
     pub fn field1(&self) -> &::core::ffi::c_int {
@@ -113,16 +116,23 @@ impl Struct {
 #[__crubit::annotate(cc_type = "PaddingBetweenFields")]
 pub struct PaddingBetweenFields {
     /// size: 1, alignment: 1 => offset: 0
+    ///
+    /// Default: `1`
     pub field1: ::core::ffi::c_char,
     __padding1: [::core::mem::MaybeUninit<u8>; 3],
     /// size: 4, alignment: 4 => offset: 4
     ///
+    /// Default: `2`
+    ///
     /// Reason for representing this field as a blob of bytes:
     /// `[[no_unique_address]]` attribute was present.
     pub(crate) field2: [::core::mem::MaybeUninit<u8>; 4],
 }
 impl !Send for PaddingBetweenFields {}
 impl !Sync for PaddingBetweenFields {}
+impl oops::CcClass for PaddingBetweenFields {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("PaddingBetweenFields"),
     crate::PaddingBetweenFields
@@ -192,6 +202,10 @@ impl PaddingBetweenFields {
 /// - dsize (size without padding): 5
 ///   (4 bytes for `inner_int_field`, 1 byte for `inner_char_field`)
 /// - size: 8 (dsize adjusted up to account for alignment)
+///
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned(PinnedDrop)]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "FieldInTailPadding_InnerStruct")]
@@ -203,6 +217,9 @@ pub struct FieldInTailPadding_InnerStruct {
 }
 impl !Send for FieldInTailPadding_InnerStruct {}
 impl !Sync for FieldInTailPadding_InnerStruct {}
+impl oops::CcClass for FieldInTailPadding_InnerStruct {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("FieldInTailPadding_InnerStruct"),
     crate::FieldInTailPadding_InnerStruct
@@ -279,6 +296,10 @@ impl ::ctor::PinnedDrop for FieldInTailPadding_InnerStruct {
 /// done through compile-time assertions of field offsets in the generated Rust
 /// code.  The initial alignment-based fix idea for b/232418721 would incorrectly
 /// put `char_in_tail_padding_of_prev_field` at offset 8.
+///
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned(PinnedDrop)]
 #[repr(C, align(4))]
 #[__crubit::annotate(cc_type = "FieldInTailPadding")]
@@ -292,6 +313,9 @@ pub struct FieldInTailPadding {
 }
 impl !Send for FieldInTailPadding {}
 impl !Sync for FieldInTailPadding {}
+impl oops::CcClass for FieldInTailPadding {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("FieldInTailPadding"),
     crate::FieldInTailPadding
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct Struct) == 8);
 static_assert(alignof(struct Struct) == 4);
@@ -154,4 +155,6 @@ extern "C" void __rust_thunk___ZN18FieldInTailPaddingC1Eicc(
   crubit::construct_at(__this, inner_int, inner_char, outer_char);
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -39,7 +39,8 @@ struct Nontrivial final {
   bool operator<(const Nontrivial& rhs) const;
   Nontrivial operator+(const Nontrivial& rhs) const;
 
-  // Bindings for `operator+=` are *not* supported for non-trivial types.
+  // `operator+=` of a non-Unpin type can't implement `AddAssign`, and is bound
+  // as an `add_assign` method instead.
   Nontrivial& operator+=(const Nontrivial& rhs);
 };
 
@@ -109,7 +110,7 @@ const NontrivialUnpin&& TakesUnpinByConstRvalueReference(
     const NontrivialUnpin&& nontrivial);
 
 // Finally, testing for strange by-value APIs.
-struct NontrivialByValue {
+struct NontrivialByValue final {
   NontrivialByValue(const NontrivialByValue& other) = default;
   NontrivialByValue(NontrivialByValue&& other) = default;
   NontrivialByValue& operator=(const NontrivialByValue& other) = default;
@@ -35,6 +35,9 @@ pub struct Nontrivial {
 }
 impl !Send for Nontrivial {}
 impl !Sync for Nontrivial {}
+impl oops::CcClass for Nontrivial {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Nontrivial"), crate::Nontrivial);
 
 impl ::ctor::CtorNew<()> for Nontrivial {
@@ -296,8 +299,17 @@ impl<'a, 'b> ::core::ops::Add<&'b crate::Nontrivial> for &'a crate::Nontrivial {
     }
 }
 
-// Error while generating bindings for item 'Nontrivial::operator+=':
-// Compound assignment operators are not supported for non-Unpin types);
+impl Nontrivial {
+    /// `operator+=` of a non-Unpin type can't implement `AddAssign`, and is bound
+    /// as an `add_assign` method instead.
+    #[inline(always)]
+    pub fn add_assign<'a, 'b>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        rhs: &'b Self,
+    ) -> ::core::pin::Pin<&'a mut crate::Nontrivial> {
+        unsafe { crate::detail::__rust_thunk___ZN10NontrivialpLERKS_(self, rhs) }
+    }
+}
 
 /// Nontrivial due to (inline) user-specified constructor and destructor.
 ///
@@ -312,6 +324,9 @@ pub struct NontrivialInline {
 }
 impl !Send for NontrivialInline {}
 impl !Sync for NontrivialInline {}
+impl oops::CcClass for NontrivialInline {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("NontrivialInline"),
     crate::NontrivialInline
@@ -488,6 +503,9 @@ pub struct NontrivialMembers {
 }
 impl !Send for NontrivialMembers {}
 impl !Sync for NontrivialMembers {}
+impl oops::CcClass for NontrivialMembers {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("NontrivialMembers"),
     crate::NontrivialMembers
@@ -599,6 +617,9 @@ pub struct NontrivialUnpin {
 }
 impl !Send for NontrivialUnpin {}
 impl !Sync for NontrivialUnpin {}
+impl oops::CcClass for NontrivialUnpin {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("NontrivialUnpin"),
     crate::NontrivialUnpin
@@ -822,6 +843,9 @@ pub struct NontrivialByValue {
 }
 impl !Send for NontrivialByValue {}
 impl !Sync for NontrivialByValue {}
+impl oops::CcClass for NontrivialByValue {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("NontrivialByValue"),
     crate::NontrivialByValue
@@ -873,8 +897,20 @@ impl<'other> ::ctor::UnpinAssign<::ctor::RvalueReference<'other, crate::Nontrivi
     }
 }
 
-// Error while generating bindings for item 'NontrivialByValue::operator==':
-// operator== where lhs operand is not record nor const reference to record
+impl NontrivialByValue {
+    #[inline(always)]
+    pub fn eq<'a>(&'a mut self, mut other: Self) -> crate::NontrivialByValue {
+        unsafe {
+            let mut __return = ::core::mem::MaybeUninit::<Self>::uninit();
+            crate::detail::__rust_thunk___ZN17NontrivialByValueeqES_(
+                &mut __return,
+                self,
+                &mut other,
+            );
+            __return.assume_init()
+        }
+    }
+}
 
 #[::ctor::recursively_pinned(PinnedDrop)]
 #[repr(C)]
@@ -884,6 +920,9 @@ pub struct Nonmovable {
 }
 impl !Send for Nonmovable {}
 impl !Sync for Nonmovable {}
+impl oops::CcClass for Nonmovable {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Nonmovable"), crate::Nonmovable);
 
 impl ::ctor::CtorNew<()> for Nonmovable {
@@ -1025,6 +1064,11 @@ mod detail {
             __this: &'a crate::Nontrivial,
             rhs: &'b crate::Nontrivial,
         );
+        #[link_name = "_ZN10NontrivialpLERKS_"]
+        pub(crate) fn __rust_thunk___ZN10NontrivialpLERKS_<'a, 'b>(
+            __this: ::core::pin::Pin<&'a mut crate::Nontrivial>,
+            rhs: &'b crate::Nontrivial,
+        ) -> ::core::pin::Pin<&'a mut crate::Nontrivial>;
         pub(crate) fn __rust_thunk___ZN16NontrivialInlineC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::NontrivialInline>,
         );
@@ -1193,6 +1237,11 @@ mod detail {
             __this: &'a mut crate::NontrivialByValue,
             other: ::ctor::RvalueReference<'other, crate::Nontrivial>,
         );
+        pub(crate) fn __rust_thunk___ZN17NontrivialByValueeqES_<'a>(
+            __return: &mut ::core::mem::MaybeUninit<crate::NontrivialByValue>,
+            __this: &'a mut crate::NontrivialByValue,
+            other: &mut crate::NontrivialByValue,
+        );
         #[link_name = "_ZN10NonmovableC1Ev"]
         pub(crate) fn __rust_thunk___ZN10NonmovableC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::Nonmovable>,
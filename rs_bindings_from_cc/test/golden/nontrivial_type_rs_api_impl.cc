@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct Nontrivial) == 4);
 static_assert(alignof(struct Nontrivial) == 4);
@@ -171,6 +172,12 @@ extern "C" void __rust_thunk___ZN17NontrivialByValueaSE10Nontrivial(
   new (__return) auto(__this->operator=(std::move(*other)));
 }
 
+extern "C" void __rust_thunk___ZN17NontrivialByValueeqES_(
+    struct NontrivialByValue* __return, struct NontrivialByValue* __this,
+    struct NontrivialByValue* other) {
+  new (__return) auto(__this->operator==(std::move(*other)));
+}
+
 static_assert(sizeof(struct Nonmovable) == 1);
 static_assert(alignof(struct Nonmovable) == 1);
 
@@ -179,4 +186,6 @@ extern "C" void __rust_thunk___Z24ReturnsNonmovableByValuev(
   new (__return) auto(ReturnsNonmovableByValue());
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -26,6 +26,9 @@ pub struct AddableConstMember {
 }
 impl !Send for AddableConstMember {}
 impl !Sync for AddableConstMember {}
+impl oops::CcClass for AddableConstMember {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddableConstMember"),
     crate::AddableConstMember
@@ -94,6 +97,9 @@ pub struct AddableNonConstMember {
 }
 impl !Send for AddableNonConstMember {}
 impl !Sync for AddableNonConstMember {}
+impl oops::CcClass for AddableNonConstMember {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddableNonConstMember"),
     crate::AddableNonConstMember
@@ -168,6 +174,9 @@ pub struct AddableFriend {
 }
 impl !Send for AddableFriend {}
 impl !Sync for AddableFriend {}
+impl oops::CcClass for AddableFriend {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("AddableFriend"), crate::AddableFriend);
 
 impl Default for AddableFriend {
@@ -230,6 +239,9 @@ pub struct AddableFreeByConstRef {
 }
 impl !Send for AddableFreeByConstRef {}
 impl !Sync for AddableFreeByConstRef {}
+impl oops::CcClass for AddableFreeByConstRef {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddableFreeByConstRef"),
     crate::AddableFreeByConstRef
@@ -283,6 +295,9 @@ pub struct AddableFreeByMutRef {
 }
 impl !Send for AddableFreeByMutRef {}
 impl !Sync for AddableFreeByMutRef {}
+impl oops::CcClass for AddableFreeByMutRef {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddableFreeByMutRef"),
     crate::AddableFreeByMutRef
@@ -336,6 +351,9 @@ pub struct AddableFreeByValue {
 }
 impl !Send for AddableFreeByValue {}
 impl !Sync for AddableFreeByValue {}
+impl oops::CcClass for AddableFreeByValue {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddableFreeByValue"),
     crate::AddableFreeByValue
@@ -389,6 +407,9 @@ pub struct AddableFreeByRValueRef {
 }
 impl !Send for AddableFreeByRValueRef {}
 impl !Sync for AddableFreeByRValueRef {}
+impl oops::CcClass for AddableFreeByRValueRef {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddableFreeByRValueRef"),
     crate::AddableFreeByRValueRef
@@ -489,6 +510,9 @@ pub struct Overloaded {
 }
 impl !Send for Overloaded {}
 impl !Sync for Overloaded {}
+impl oops::CcClass for Overloaded {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Overloaded"), crate::Overloaded);
 
 impl Default for Overloaded {
@@ -555,6 +579,9 @@ pub struct IncompatibleLHS {
 }
 impl !Send for IncompatibleLHS {}
 impl !Sync for IncompatibleLHS {}
+impl oops::CcClass for IncompatibleLHS {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("IncompatibleLHS"),
     crate::IncompatibleLHS
@@ -617,6 +644,9 @@ pub struct AddableReturnsVoid {
 }
 impl !Send for AddableReturnsVoid {}
 impl !Sync for AddableReturnsVoid {}
+impl oops::CcClass for AddableReturnsVoid {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddableReturnsVoid"),
     crate::AddableReturnsVoid
@@ -681,6 +711,9 @@ pub struct AddableConstMemberNonunpin {
 }
 impl !Send for AddableConstMemberNonunpin {}
 impl !Sync for AddableConstMemberNonunpin {}
+impl oops::CcClass for AddableConstMemberNonunpin {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddableConstMemberNonunpin"),
     crate::AddableConstMemberNonunpin
@@ -777,6 +810,9 @@ pub struct AddAssignMemberInt {
 }
 impl !Send for AddAssignMemberInt {}
 impl !Sync for AddAssignMemberInt {}
+impl oops::CcClass for AddAssignMemberInt {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddAssignMemberInt"),
     crate::AddAssignMemberInt
@@ -839,6 +875,9 @@ pub struct AddAssignMemberByConstRef {
 }
 impl !Send for AddAssignMemberByConstRef {}
 impl !Sync for AddAssignMemberByConstRef {}
+impl oops::CcClass for AddAssignMemberByConstRef {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddAssignMemberByConstRef"),
     crate::AddAssignMemberByConstRef
@@ -901,6 +940,9 @@ pub struct AddAssignFreeByConstRef {
 }
 impl !Send for AddAssignFreeByConstRef {}
 impl !Sync for AddAssignFreeByConstRef {}
+impl oops::CcClass for AddAssignFreeByConstRef {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddAssignFreeByConstRef"),
     crate::AddAssignFreeByConstRef
@@ -957,6 +999,9 @@ pub struct AddAssignFreeByValue {
 }
 impl !Send for AddAssignFreeByValue {}
 impl !Sync for AddAssignFreeByValue {}
+impl oops::CcClass for AddAssignFreeByValue {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddAssignFreeByValue"),
     crate::AddAssignFreeByValue
@@ -1019,6 +1064,9 @@ pub struct AddAssignFriendByConstRef {
 }
 impl !Send for AddAssignFriendByConstRef {}
 impl !Sync for AddAssignFriendByConstRef {}
+impl oops::CcClass for AddAssignFriendByConstRef {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddAssignFriendByConstRef"),
     crate::AddAssignFriendByConstRef
@@ -1072,6 +1120,9 @@ pub struct AddAssignFriendByValue {
 }
 impl !Send for AddAssignFriendByValue {}
 impl !Sync for AddAssignFriendByValue {}
+impl oops::CcClass for AddAssignFriendByValue {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddAssignFriendByValue"),
     crate::AddAssignFriendByValue
@@ -1134,6 +1185,9 @@ pub struct AddAssignProhibitedConstMember {
 }
 impl !Send for AddAssignProhibitedConstMember {}
 impl !Sync for AddAssignProhibitedConstMember {}
+impl oops::CcClass for AddAssignProhibitedConstMember {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddAssignProhibitedConstMember"),
     crate::AddAssignProhibitedConstMember
@@ -1183,8 +1237,12 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for AddAssignPro
     }
 }
 
-// Error while generating bindings for item 'AddAssignProhibitedConstMember::operator+=':
-// Compound assignment with const left-hand side is not supported
+impl AddAssignProhibitedConstMember {
+    #[inline(always)]
+    pub fn add_assign<'a>(&'a self, rhs: ::core::ffi::c_int) -> ::core::ffi::c_int {
+        unsafe { crate::detail::__rust_thunk___ZNK30AddAssignProhibitedConstMemberpLEi(self, rhs) }
+    }
+}
 
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -1194,6 +1252,9 @@ pub struct AddAssignProhibitedFriendConstLhs {
 }
 impl !Send for AddAssignProhibitedFriendConstLhs {}
 impl !Sync for AddAssignProhibitedFriendConstLhs {}
+impl oops::CcClass for AddAssignProhibitedFriendConstLhs {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("AddAssignProhibitedFriendConstLhs"),
     crate::AddAssignProhibitedFriendConstLhs
@@ -1258,6 +1319,9 @@ pub struct ManyOperators {
 }
 impl !Send for ManyOperators {}
 impl !Sync for ManyOperators {}
+impl oops::CcClass for ManyOperators {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("ManyOperators"), crate::ManyOperators);
 
 impl Default for ManyOperators {
@@ -1881,6 +1945,11 @@ mod detail {
             __this: &'a mut crate::AddAssignProhibitedConstMember,
             __param_0: ::ctor::RvalueReference<'b, crate::AddAssignProhibitedConstMember>,
         ) -> &'a mut crate::AddAssignProhibitedConstMember;
+        #[link_name = "_ZNK30AddAssignProhibitedConstMemberpLEi"]
+        pub(crate) fn __rust_thunk___ZNK30AddAssignProhibitedConstMemberpLEi<'a>(
+            __this: &'a crate::AddAssignProhibitedConstMember,
+            rhs: ::core::ffi::c_int,
+        ) -> ::core::ffi::c_int;
         pub(crate) fn __rust_thunk___ZN33AddAssignProhibitedFriendConstLhsC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::AddAssignProhibitedFriendConstLhs>,
         );
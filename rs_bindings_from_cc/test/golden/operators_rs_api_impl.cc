@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(class AddableConstMember) == 4);
 static_assert(alignof(class AddableConstMember) == 4);
@@ -672,4 +673,6 @@ extern "C" void __rust_thunk___ZNK13ManyOperatorsrsERKS_(
   new (__return) auto(__this->operator>>(*rhs));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -16,9 +16,12 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 extern "C" void __rust_thunk___Z20AlsoTemplateOverloadv() {
   AlsoTemplateOverload();
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -15,6 +15,9 @@
 #![allow(nonstandard_style)]
 #![deny(warnings)]
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned(PinnedDrop)]
 #[repr(C, align(8))]
 #[__crubit::annotate(cc_type = "PolymorphicBase")]
@@ -23,6 +26,9 @@ pub struct PolymorphicBase {
 }
 impl !Send for PolymorphicBase {}
 impl !Sync for PolymorphicBase {}
+impl oops::CcClass for PolymorphicBase {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("PolymorphicBase"),
     crate::PolymorphicBase
@@ -87,6 +93,9 @@ impl ::ctor::PinnedDrop for PolymorphicBase {
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned(PinnedDrop)]
 #[repr(C, align(8))]
 #[__crubit::annotate(cc_type = "PolymorphicBase2")]
@@ -95,6 +104,9 @@ pub struct PolymorphicBase2 {
 }
 impl !Send for PolymorphicBase2 {}
 impl !Sync for PolymorphicBase2 {}
+impl oops::CcClass for PolymorphicBase2 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("PolymorphicBase2"),
     crate::PolymorphicBase2
@@ -153,6 +165,8 @@ impl<'b> ::ctor::Assign<&'b Self> for PolymorphicBase2 {
 }
 
 impl PolymorphicBase2 {
+    /// This method is virtual: the call is dispatched to the implementation for the
+    /// dynamic type of the object, which may be a class derived from `PolymorphicBase2`.
     #[inline(always)]
     pub fn Foo<'a>(self: ::core::pin::Pin<&'a mut Self>) {
         unsafe { crate::detail::__rust_thunk___ZN16PolymorphicBase23FooEv(self) }
@@ -166,6 +180,9 @@ impl ::ctor::PinnedDrop for PolymorphicBase2 {
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned(PinnedDrop)]
 #[repr(C, align(8))]
 #[__crubit::annotate(cc_type = "PolymorphicDerived")]
@@ -174,6 +191,9 @@ pub struct PolymorphicDerived {
 }
 impl !Send for PolymorphicDerived {}
 impl !Sync for PolymorphicDerived {}
+impl oops::CcClass for PolymorphicDerived {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("PolymorphicDerived"),
     crate::PolymorphicDerived
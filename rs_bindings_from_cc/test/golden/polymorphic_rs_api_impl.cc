@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(class PolymorphicBase) == 8);
 static_assert(alignof(class PolymorphicBase) == 8);
@@ -107,4 +108,6 @@ __rust_thunk___ZN18PolymorphicDerivedaSEOS_(
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
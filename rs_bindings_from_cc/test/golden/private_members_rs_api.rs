@@ -28,6 +28,9 @@ pub mod test_namespace_bindings {
     }
     impl !Send for SomeClass {}
     impl !Sync for SomeClass {}
+    impl oops::CcClass for SomeClass {
+        const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+    }
     forward_declare::unsafe_define!(
         forward_declare::symbol!("test_namespace_bindings :: SomeClass"),
         crate::test_namespace_bindings::SomeClass
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(class test_namespace_bindings::SomeClass) == 8);
 static_assert(alignof(class test_namespace_bindings::SomeClass) == 4);
@@ -49,4 +50,6 @@ __rust_thunk___ZN23test_namespace_bindings9SomeClassaSEOS0_(
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
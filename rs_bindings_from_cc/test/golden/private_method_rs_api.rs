@@ -18,7 +18,13 @@
 // Error while generating bindings for item 'Ptr':
 // Class templates are not supported yet
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "Outer")]
 pub struct Outer {
@@ -26,6 +32,9 @@ pub struct Outer {
 }
 impl !Send for Outer {}
 impl !Sync for Outer {}
+impl oops::CcClass for Outer {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Outer"), crate::Outer);
 
 // Error while generating bindings for item 'Outer::Outer':
@@ -46,7 +55,6 @@ forward_declare::unsafe_define!(forward_declare::symbol!("Outer"), crate::Outer)
 const _: () = {
     assert!(::core::mem::size_of::<crate::Outer>() == 1);
     assert!(::core::mem::align_of::<crate::Outer>() == 1);
-    static_assertions::assert_impl_all!(crate::Outer: Clone);
-    static_assertions::assert_impl_all!(crate::Outer: Copy);
+    static_assertions::assert_not_impl_any!(crate::Outer: Copy);
     static_assertions::assert_not_impl_any!(crate::Outer: Drop);
 };
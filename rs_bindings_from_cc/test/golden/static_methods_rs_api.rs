@@ -26,6 +26,9 @@ pub struct SomeClass {
 }
 impl !Send for SomeClass {}
 impl !Sync for SomeClass {}
+impl oops::CcClass for SomeClass {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("SomeClass"), crate::SomeClass);
 
 impl Default for SomeClass {
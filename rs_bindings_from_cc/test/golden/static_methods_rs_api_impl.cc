@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(class SomeClass) == 4);
 static_assert(alignof(class SomeClass) == 4);
@@ -46,4 +47,6 @@ extern "C" void __rust_thunk___ZN9SomeClass21static_factory_methodEi(
   new (__return) auto(SomeClass::static_factory_method(initial_value_of_field));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -7,7 +7,7 @@
 // Features: experimental, supported
 
 #![rustfmt::skip]
-#![feature(custom_inner_attributes, negative_impls, register_tool)]
+#![feature(custom_inner_attributes, impl_trait_in_assoc_type, negative_impls, register_tool)]
 #![allow(stable_features)]
 #![no_std]
 #![register_tool(__crubit)]
@@ -23,6 +23,9 @@ pub struct DifferentScope {
 }
 impl !Send for DifferentScope {}
 impl !Sync for DifferentScope {}
+impl oops::CcClass for DifferentScope {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("DifferentScope"), crate::DifferentScope);
 
 impl Default for DifferentScope {
@@ -82,6 +85,9 @@ pub mod test_namespace_bindings {
     }
     impl !Send for TemplateParam {}
     impl !Sync for TemplateParam {}
+    impl oops::CcClass for TemplateParam {
+        const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+    }
     forward_declare::unsafe_define!(
         forward_declare::symbol!("test_namespace_bindings :: TemplateParam"),
         crate::test_namespace_bindings::TemplateParam
@@ -213,6 +219,9 @@ pub mod forward_declared_template {
 // namespace forward_declared_template
 
 pub mod private_classes {
+    /// This class is not `final`, so C++ code may derive from it. A reference to it may
+    /// refer to the base class subobject of a derived class, and copying it by value
+    /// slices off the derived part of the object.
     #[derive(Clone, Copy)]
     #[repr(C)]
     #[__crubit::annotate(cc_type = "private_classes :: HasPrivateType")]
@@ -221,6 +230,9 @@ pub mod private_classes {
     }
     impl !Send for HasPrivateType {}
     impl !Sync for HasPrivateType {}
+    impl oops::CcClass for HasPrivateType {
+        const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+    }
     forward_declare::unsafe_define!(
         forward_declare::symbol!("private_classes :: HasPrivateType"),
         crate::private_classes::HasPrivateType
@@ -275,6 +287,9 @@ pub struct __CcTemplateInstN23test_namespace_bindings10MyTemplateI14DifferentSco
 }
 impl !Send for __CcTemplateInstN23test_namespace_bindings10MyTemplateI14DifferentScopeEE {}
 impl !Sync for __CcTemplateInstN23test_namespace_bindings10MyTemplateI14DifferentScopeEE {}
+impl oops::CcClass for __CcTemplateInstN23test_namespace_bindings10MyTemplateI14DifferentScopeEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("test_namespace_bindings :: MyTemplate < DifferentScope >"),
     crate::__CcTemplateInstN23test_namespace_bindings10MyTemplateI14DifferentScopeEE
@@ -361,6 +376,9 @@ pub struct __CcTemplateInstN23test_namespace_bindings10MyTemplateINS_13TemplateP
 }
 impl !Send for __CcTemplateInstN23test_namespace_bindings10MyTemplateINS_13TemplateParamEEE {}
 impl !Sync for __CcTemplateInstN23test_namespace_bindings10MyTemplateINS_13TemplateParamEEE {}
+impl oops::CcClass for __CcTemplateInstN23test_namespace_bindings10MyTemplateINS_13TemplateParamEEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!(
         "test_namespace_bindings :: MyTemplate < test_namespace_bindings :: TemplateParam >"
@@ -447,6 +465,9 @@ pub struct __CcTemplateInstN23test_namespace_bindings10MyTemplateIiEE {
 }
 impl !Send for __CcTemplateInstN23test_namespace_bindings10MyTemplateIiEE {}
 impl !Sync for __CcTemplateInstN23test_namespace_bindings10MyTemplateIiEE {}
+impl oops::CcClass for __CcTemplateInstN23test_namespace_bindings10MyTemplateIiEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("test_namespace_bindings :: MyTemplate < int >"),
     crate::__CcTemplateInstN23test_namespace_bindings10MyTemplateIiEE
@@ -531,6 +552,9 @@ pub struct __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsINS0
 }
 impl !Send for __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsINS0_IiiEEiEE {}
 impl !Sync for __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsINS0_IiiEEiEE {}
+impl oops::CcClass for __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsINS0_IiiEEiEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!(
         "test_namespace_bindings :: TemplateWithTwoParams < test_namespace_bindings :: TemplateWithTwoParams < int , int >, int >"
@@ -593,6 +617,9 @@ pub struct __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsIifE
 }
 impl !Send for __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsIifEE {}
 impl !Sync for __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsIifEE {}
+impl oops::CcClass for __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsIifEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("test_namespace_bindings :: TemplateWithTwoParams < int , float >"),
     crate::__CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsIifEE
@@ -653,6 +680,9 @@ pub struct __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsIiiE
 }
 impl !Send for __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsIiiEE {}
 impl !Sync for __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsIiiEE {}
+impl oops::CcClass for __CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsIiiEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("test_namespace_bindings :: TemplateWithTwoParams < int , int >"),
     crate::__CcTemplateInstN23test_namespace_bindings21TemplateWithTwoParamsIiiEE
@@ -706,7 +736,14 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>>
 
 /// Explicit class template specialization with definition should be imported
 /// even when not instantiated if there is a type alias for it.
-#[derive(Clone, Copy)]
+///
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "test_namespace_bindings :: MyStruct < char >")]
 pub struct __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {
@@ -714,49 +751,104 @@ pub struct __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {
 }
 impl !Send for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {}
 impl !Sync for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {}
+impl oops::CcClass for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("test_namespace_bindings :: MyStruct < char >"),
     crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE
 );
 
-impl Default for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {
+impl ::ctor::CtorNew<()> for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN23test_namespace_bindings8MyStructIcEC1Ev__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN23test_namespace_bindings8MyStructIcEC1Ev__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>>
+impl<'b> ::ctor::CtorNew<&'b Self> for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN23test_namespace_bindings8MyStructIcEC1ERKS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>
     for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE
 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN23test_namespace_bindings8MyStructIcEC1EOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(&mut tmp,__param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN23test_namespace_bindings8MyStructIcEC1EOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)>
+    for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE
+{
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {
+impl<'b> ::ctor::Assign<&'b Self> for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN23test_namespace_bindings8MyStructIcEaSERKS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(self,__param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>>
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>>
     for __CcTemplateInstN23test_namespace_bindings8MyStructIcEE
 {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN23test_namespace_bindings8MyStructIcEaSEOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(self,__param_0);
         }
@@ -771,6 +863,9 @@ pub struct __CcTemplateInst18MyTopLevelTemplateIN23test_namespace_bindings13Temp
 }
 impl !Send for __CcTemplateInst18MyTopLevelTemplateIN23test_namespace_bindings13TemplateParamEE {}
 impl !Sync for __CcTemplateInst18MyTopLevelTemplateIN23test_namespace_bindings13TemplateParamEE {}
+impl oops::CcClass for __CcTemplateInst18MyTopLevelTemplateIN23test_namespace_bindings13TemplateParamEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTopLevelTemplate < test_namespace_bindings :: TemplateParam >"),
     crate::__CcTemplateInst18MyTopLevelTemplateIN23test_namespace_bindings13TemplateParamEE
@@ -824,7 +919,13 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>>
 
 forward_declare::forward_declare!(pub __CcTemplateInst18MyTopLevelTemplateIiE = forward_declare::symbol!("__CcTemplateInst18MyTopLevelTemplateIiE"));
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(
     cc_type = "template_template_params :: MyTemplate < template_template_params :: Policy >"
@@ -834,6 +935,9 @@ pub struct __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE
 }
 impl !Send for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE {}
 impl !Sync for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE {}
+impl oops::CcClass for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!(
         "template_template_params :: MyTemplate < template_template_params :: Policy >"
@@ -841,46 +945,102 @@ forward_declare::unsafe_define!(
     crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE
 );
 
-impl Default for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE {
+impl ::ctor::CtorNew<()> for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEC1Ev__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEC1Ev__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>>
+impl<'b> ::ctor::CtorNew<&'b Self>
     for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE
 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEC1EOS2___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(&mut tmp,__param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEC1ERKS2___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)>
+    for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE
+{
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self>
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>
     for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE
 {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEC1EOS2___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)>
+    for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE
+{
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self>
+    for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE
+{
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEaSERKS2___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(self,__param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>>
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>>
     for __CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE
 {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEaSEOS2___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(self,__param_0);
         }
@@ -1169,6 +1329,15 @@ mod detail {
                 crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE,
             >,
         );
+        pub(crate) fn __rust_thunk___ZN23test_namespace_bindings8MyStructIcEC1ERKS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc<
+            'a,
+            'b,
+        >(
+            __this: &'a mut ::core::mem::MaybeUninit<
+                crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE,
+            >,
+            __param_0: &'b crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE,
+        );
         pub(crate) fn __rust_thunk___ZN23test_namespace_bindings8MyStructIcEC1EOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc<
             'a,
             'b,
@@ -1185,19 +1354,23 @@ mod detail {
             'a,
             'b,
         >(
-            __this: &'a mut crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE,
+            __this: ::core::pin::Pin<
+                &'a mut crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE,
+            >,
             __param_0: &'b crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE,
-        ) -> &'a mut crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE;
+        ) -> ::core::pin::Pin<&'a mut crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE>;
         pub(crate) fn __rust_thunk___ZN23test_namespace_bindings8MyStructIcEaSEOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc<
             'a,
             'b,
         >(
-            __this: &'a mut crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE,
+            __this: ::core::pin::Pin<
+                &'a mut crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE,
+            >,
             __param_0: ::ctor::RvalueReference<
                 'b,
                 crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE,
             >,
-        ) -> &'a mut crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE;
+        ) -> ::core::pin::Pin<&'a mut crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE>;
         pub(crate) fn __rust_thunk___ZN18MyTopLevelTemplateIN23test_namespace_bindings13TemplateParamEEC1Ev__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc<
             'a,
         >(
@@ -1219,6 +1392,15 @@ mod detail {
                 crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE,
             >,
         );
+        pub(crate) fn __rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEC1ERKS2___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc<
+            'a,
+            'b,
+        >(
+            __this: &'a mut ::core::mem::MaybeUninit<
+                crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE,
+            >,
+            __param_0: &'b crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE,
+        );
         pub(crate) fn __rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEC1EOS2___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc<
             'a,
             'b,
@@ -1235,19 +1417,23 @@ mod detail {
             'a,
             'b,
         >(
-            __this: &'a mut crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE,
+            __this: ::core::pin::Pin<&'a mut crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE>,
             __param_0: &'b crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE,
-        ) -> &'a mut crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE;
+        ) -> ::core::pin::Pin<
+            &'a mut crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE,
+        >;
         pub(crate) fn __rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEaSEOS2___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc<
             'a,
             'b,
         >(
-            __this: &'a mut crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE,
+            __this: ::core::pin::Pin<&'a mut crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE>,
             __param_0: ::ctor::RvalueReference<
                 'b,
                 crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE,
             >,
-        ) -> &'a mut crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE;
+        ) -> ::core::pin::Pin<
+            &'a mut crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE,
+        >;
         pub(crate) fn __rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEE9GetPolicyEv__2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc()
         -> ::core::ffi::c_int;
     }
@@ -1416,8 +1602,7 @@ const _: () = {
         ::core::mem::align_of::<crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE>()
             == 1
     );
-    static_assertions::assert_impl_all!(crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE: Clone);
-    static_assertions::assert_impl_all!(crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE: Copy);
+    static_assertions::assert_not_impl_any!(crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE: Copy);
     static_assertions::assert_not_impl_any!(crate::__CcTemplateInstN23test_namespace_bindings8MyStructIcEE: Drop);
 
     assert!(
@@ -1450,7 +1635,6 @@ const _: () = {
             crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE,
         >() == 1
     );
-    static_assertions::assert_impl_all!(crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE: Clone);
-    static_assertions::assert_impl_all!(crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE: Copy);
+    static_assertions::assert_not_impl_any!(crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE: Copy);
     static_assertions::assert_not_impl_any!(crate::__CcTemplateInstN24template_template_params10MyTemplateINS_6PolicyEEE: Drop);
 };
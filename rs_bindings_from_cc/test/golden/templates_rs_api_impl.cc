@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(sizeof(struct DifferentScope) == 1);
 static_assert(alignof(struct DifferentScope) == 1);
@@ -408,6 +409,13 @@ __rust_thunk___ZN23test_namespace_bindings8MyStructIcEC1Ev__2f_2fthird_5fparty_2
   crubit::construct_at(__this);
 }
 
+extern "C" void
+__rust_thunk___ZN23test_namespace_bindings8MyStructIcEC1ERKS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(
+    struct test_namespace_bindings::MyStruct<char>* __this,
+    const struct test_namespace_bindings::MyStruct<char>* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void
 __rust_thunk___ZN23test_namespace_bindings8MyStructIcEC1EOS1___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(
     struct test_namespace_bindings::MyStruct<char>* __this,
@@ -484,6 +492,15 @@ __rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEC1Ev__2f_2ft
   crubit::construct_at(__this);
 }
 
+extern "C" void
+__rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEC1ERKS2___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(
+    class template_template_params::MyTemplate<
+        template_template_params::Policy>* __this,
+    const class template_template_params::MyTemplate<
+        template_template_params::Policy>* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void
 __rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEEC1EOS2___2f_2fthird_5fparty_2fcrubit_2frs_5fbindings_5ffrom_5fcc_2ftest_2fgolden_3atemplates_5fcc(
     class template_template_params::MyTemplate<
@@ -519,4 +536,6 @@ __rust_thunk___ZN24template_template_params10MyTemplateINS_6PolicyEE9GetPolicyEv
       template_template_params::Policy>::GetPolicy();
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -26,6 +26,9 @@ pub struct TopLevel {
 }
 impl !Send for TopLevel {}
 impl !Sync for TopLevel {}
+impl oops::CcClass for TopLevel {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("TopLevel"), crate::TopLevel);
 
 // Error while generating bindings for item 'TopLevel::TopLevel':
@@ -64,6 +67,9 @@ pub mod test_namespace_bindings {
     }
     impl !Send for Inner {}
     impl !Sync for Inner {}
+    impl oops::CcClass for Inner {
+        const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+    }
     forward_declare::unsafe_define!(
         forward_declare::symbol!("test_namespace_bindings :: Inner"),
         crate::test_namespace_bindings::Inner
@@ -104,6 +110,9 @@ pub struct __CcTemplateInst10MyTemplateI8TopLevelE {
 }
 impl !Send for __CcTemplateInst10MyTemplateI8TopLevelE {}
 impl !Sync for __CcTemplateInst10MyTemplateI8TopLevelE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateI8TopLevelE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < TopLevel >"),
     crate::__CcTemplateInst10MyTemplateI8TopLevelE
@@ -142,6 +151,9 @@ pub struct __CcTemplateInst10MyTemplateIN23test_namespace_bindings5InnerEE {
 }
 impl !Send for __CcTemplateInst10MyTemplateIN23test_namespace_bindings5InnerEE {}
 impl !Sync for __CcTemplateInst10MyTemplateIN23test_namespace_bindings5InnerEE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateIN23test_namespace_bindings5InnerEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < test_namespace_bindings :: Inner >"),
     crate::__CcTemplateInst10MyTemplateIN23test_namespace_bindings5InnerEE
@@ -180,6 +192,9 @@ pub struct __CcTemplateInst10MyTemplateIS_I8TopLevelEE {
 }
 impl !Send for __CcTemplateInst10MyTemplateIS_I8TopLevelEE {}
 impl !Sync for __CcTemplateInst10MyTemplateIS_I8TopLevelEE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateIS_I8TopLevelEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < MyTemplate < TopLevel >>"),
     crate::__CcTemplateInst10MyTemplateIS_I8TopLevelEE
@@ -221,6 +236,9 @@ pub struct __CcTemplateInst10MyTemplateIS_IN23test_namespace_bindings5InnerEEE {
 }
 impl !Send for __CcTemplateInst10MyTemplateIS_IN23test_namespace_bindings5InnerEEE {}
 impl !Sync for __CcTemplateInst10MyTemplateIS_IN23test_namespace_bindings5InnerEEE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateIS_IN23test_namespace_bindings5InnerEEE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < MyTemplate < test_namespace_bindings :: Inner >>"),
     crate::__CcTemplateInst10MyTemplateIS_IN23test_namespace_bindings5InnerEEE
@@ -262,6 +280,9 @@ pub struct __CcTemplateInst10MyTemplateIbE {
 }
 impl !Send for __CcTemplateInst10MyTemplateIbE {}
 impl !Sync for __CcTemplateInst10MyTemplateIbE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateIbE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < bool >"),
     crate::__CcTemplateInst10MyTemplateIbE
@@ -300,6 +321,9 @@ pub struct __CcTemplateInst10MyTemplateIcE {
 }
 impl !Send for __CcTemplateInst10MyTemplateIcE {}
 impl !Sync for __CcTemplateInst10MyTemplateIcE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateIcE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < char >"),
     crate::__CcTemplateInst10MyTemplateIcE
@@ -338,6 +362,9 @@ pub struct __CcTemplateInst10MyTemplateIdE {
 }
 impl !Send for __CcTemplateInst10MyTemplateIdE {}
 impl !Sync for __CcTemplateInst10MyTemplateIdE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateIdE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < double >"),
     crate::__CcTemplateInst10MyTemplateIdE
@@ -376,6 +403,9 @@ pub struct __CcTemplateInst10MyTemplateIfE {
 }
 impl !Send for __CcTemplateInst10MyTemplateIfE {}
 impl !Sync for __CcTemplateInst10MyTemplateIfE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateIfE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < float >"),
     crate::__CcTemplateInst10MyTemplateIfE
@@ -414,6 +444,9 @@ pub struct __CcTemplateInst10MyTemplateIiE {
 }
 impl !Send for __CcTemplateInst10MyTemplateIiE {}
 impl !Sync for __CcTemplateInst10MyTemplateIiE {}
+impl oops::CcClass for __CcTemplateInst10MyTemplateIiE {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("MyTemplate < int >"),
     crate::__CcTemplateInst10MyTemplateIiE
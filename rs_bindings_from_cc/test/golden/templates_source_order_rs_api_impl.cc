@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(sizeof(struct TopLevel) == 1);
 static_assert(alignof(struct TopLevel) == 1);
@@ -111,4 +112,6 @@ __rust_thunk___ZN10MyTemplateIiE8processTEi__2f_2fthird_5fparty_2fcrubit_2frs_5f
   __this->processT(t);
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -26,6 +26,9 @@ pub mod ns {
     }
     impl !Send for Trivial {}
     impl !Sync for Trivial {}
+    impl oops::CcClass for Trivial {
+        const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+    }
     forward_declare::unsafe_define!(forward_declare::symbol!("ns :: Trivial"), crate::ns::Trivial);
 
     impl Default for Trivial {
@@ -116,6 +119,10 @@ pub mod ns {
 
     /// This struct is trivial, and therefore trivially relocatable etc., but still
     /// not safe to pass by reference as it is not final.
+    ///
+    /// This class is not `final`, so C++ code may derive from it. A reference to it may
+    /// refer to the base class subobject of a derived class, and copying it by value
+    /// slices off the derived part of the object.
     #[derive(Clone, Copy)]
     #[repr(C)]
     #[__crubit::annotate(cc_type = "ns :: TrivialNonfinal")]
@@ -124,6 +131,9 @@ pub mod ns {
     }
     impl !Send for TrivialNonfinal {}
     impl !Sync for TrivialNonfinal {}
+    impl oops::CcClass for TrivialNonfinal {
+        const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+    }
     forward_declare::unsafe_define!(
         forward_declare::symbol!("ns :: TrivialNonfinal"),
         crate::ns::TrivialNonfinal
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct ns::Trivial) == 4);
 static_assert(alignof(struct ns::Trivial) == 4);
@@ -79,4 +80,6 @@ __rust_thunk___ZN2ns27TakesTrivialNonfinalByValueENS_15TrivialNonfinalE(
   new (__return) auto(ns::TakesTrivialNonfinalByValue(std::move(*trivial)));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -7,7 +7,7 @@
 // Features: experimental, supported
 
 #![rustfmt::skip]
-#![feature(custom_inner_attributes, negative_impls, register_tool)]
+#![feature(custom_inner_attributes, impl_trait_in_assoc_type, negative_impls, register_tool)]
 #![allow(stable_features)]
 #![no_std]
 #![register_tool(__crubit)]
@@ -15,7 +15,13 @@
 #![allow(nonstandard_style)]
 #![deny(warnings)]
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "SomeStruct")]
 pub struct SomeStruct {
@@ -23,55 +29,115 @@ pub struct SomeStruct {
 }
 impl !Send for SomeStruct {}
 impl !Sync for SomeStruct {}
+impl oops::CcClass for SomeStruct {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("SomeStruct"), crate::SomeStruct);
 
-impl Default for SomeStruct {
+impl ::ctor::CtorNew<()> for SomeStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN10SomeStructC1Ev(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN10SomeStructC1Ev(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>> for SomeStruct {
+impl<'b> ::ctor::CtorNew<&'b Self> for SomeStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN10SomeStructC1EOS_(&mut tmp, __param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN10SomeStructC1ERKS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for SomeStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for SomeStruct {
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for SomeStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN10SomeStructC1EOS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for SomeStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self> for SomeStruct {
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN10SomeStructaSERKS_(self, __param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for SomeStruct {
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for SomeStruct {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN10SomeStructaSEOS_(self, __param_0);
         }
     }
 }
 
-// Error while generating bindings for item 'nested_type':
-// Can't generate bindings for nested_type, because it is unsupported: b/200067824: type definitions nested inside records are not yet supported
+pub mod some_struct {
+    pub type nested_type = ::core::ffi::c_int;
+}
 
 // Error while generating bindings for item 'SomeStruct':
 // Typedef only used to introduce a name in C. Not importing.
 
-#[derive(Clone, Copy)]
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
+/// Since a derived class may place its fields in the tail padding of this class, it
+/// is not `Unpin`, so that assigning to it can't overwrite them. Marking the class
+/// `final` lifts this restriction.
+#[::ctor::recursively_pinned]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "SomeOtherStruct")]
 pub struct SomeOtherStruct {
@@ -79,45 +145,98 @@ pub struct SomeOtherStruct {
 }
 impl !Send for SomeOtherStruct {}
 impl !Sync for SomeOtherStruct {}
+impl oops::CcClass for SomeOtherStruct {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("SomeOtherStruct"),
     crate::SomeOtherStruct
 );
 
-impl Default for SomeOtherStruct {
+impl ::ctor::CtorNew<()> for SomeOtherStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self>;
     #[inline(always)]
-    fn default() -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: ()) -> Self::CtorType {
+        let () = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN15SomeOtherStructC1Ev(&mut tmp);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN15SomeOtherStructC1Ev(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                    );
+                },
+            )
         }
     }
 }
 
-impl<'b> From<::ctor::RvalueReference<'b, Self>> for SomeOtherStruct {
+impl<'b> ::ctor::CtorNew<&'b Self> for SomeOtherStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn from(__param_0: ::ctor::RvalueReference<'b, Self>) -> Self {
-        let mut tmp = ::core::mem::MaybeUninit::<Self>::zeroed();
+    fn ctor_new(args: &'b Self) -> Self::CtorType {
+        let __param_0 = args;
         unsafe {
-            crate::detail::__rust_thunk___ZN15SomeOtherStructC1EOS_(&mut tmp, __param_0);
-            tmp.assume_init()
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN15SomeOtherStructC1ERKS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
         }
     }
 }
+impl<'b> ::ctor::CtorNew<(&'b Self,)> for SomeOtherStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (&'b Self,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<&'b Self>>::ctor_new(arg)
+    }
+}
 
-impl<'b> ::ctor::UnpinAssign<&'b Self> for SomeOtherStruct {
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for SomeOtherStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: &'b Self) {
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: ::core::pin::Pin<&mut ::core::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN15SomeOtherStructC1EOS_(
+                        ::core::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for SomeOtherStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
+
+impl<'b> ::ctor::Assign<&'b Self> for SomeOtherStruct {
+    #[inline(always)]
+    fn assign<'a>(self: ::core::pin::Pin<&'a mut Self>, __param_0: &'b Self) {
         unsafe {
             crate::detail::__rust_thunk___ZN15SomeOtherStructaSERKS_(self, __param_0);
         }
     }
 }
 
-impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for SomeOtherStruct {
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for SomeOtherStruct {
     #[inline(always)]
-    fn unpin_assign<'a>(&'a mut self, __param_0: ::ctor::RvalueReference<'b, Self>) {
+    fn assign<'a>(
+        self: ::core::pin::Pin<&'a mut Self>,
+        __param_0: ::ctor::RvalueReference<'b, Self>,
+    ) {
         unsafe {
             crate::detail::__rust_thunk___ZN15SomeOtherStructaSEOS_(self, __param_0);
         }
@@ -132,6 +251,9 @@ pub union SomeUnion {
 }
 impl !Send for SomeUnion {}
 impl !Sync for SomeUnion {}
+impl oops::CcClass for SomeUnion {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("SomeUnion"), crate::SomeUnion);
 
 impl Default for SomeUnion {
@@ -185,6 +307,9 @@ pub union SomeOtherUnion {
 }
 impl !Send for SomeOtherUnion {}
 impl !Sync for SomeOtherUnion {}
+impl oops::CcClass for SomeOtherUnion {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("SomeOtherUnion"), crate::SomeOtherUnion);
 
 impl Default for SomeOtherUnion {
@@ -228,7 +353,7 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for SomeOtherUni
 }
 
 #[inline(always)]
-pub fn FunctionUsingNestedType() -> ::core::ffi::c_int {
+pub fn FunctionUsingNestedType() -> crate::some_struct::nested_type {
     unsafe { crate::detail::__rust_thunk___Z23FunctionUsingNestedTypev() }
 }
 
@@ -239,33 +364,41 @@ mod detail {
         pub(crate) fn __rust_thunk___ZN10SomeStructC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::SomeStruct>,
         );
+        pub(crate) fn __rust_thunk___ZN10SomeStructC1ERKS_<'a, 'b>(
+            __this: &'a mut ::core::mem::MaybeUninit<crate::SomeStruct>,
+            __param_0: &'b crate::SomeStruct,
+        );
         pub(crate) fn __rust_thunk___ZN10SomeStructC1EOS_<'a, 'b>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::SomeStruct>,
             __param_0: ::ctor::RvalueReference<'b, crate::SomeStruct>,
         );
         pub(crate) fn __rust_thunk___ZN10SomeStructaSERKS_<'a, 'b>(
-            __this: &'a mut crate::SomeStruct,
+            __this: ::core::pin::Pin<&'a mut crate::SomeStruct>,
             __param_0: &'b crate::SomeStruct,
-        ) -> &'a mut crate::SomeStruct;
+        ) -> ::core::pin::Pin<&'a mut crate::SomeStruct>;
         pub(crate) fn __rust_thunk___ZN10SomeStructaSEOS_<'a, 'b>(
-            __this: &'a mut crate::SomeStruct,
+            __this: ::core::pin::Pin<&'a mut crate::SomeStruct>,
             __param_0: ::ctor::RvalueReference<'b, crate::SomeStruct>,
-        ) -> &'a mut crate::SomeStruct;
+        ) -> ::core::pin::Pin<&'a mut crate::SomeStruct>;
         pub(crate) fn __rust_thunk___ZN15SomeOtherStructC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::SomeOtherStruct>,
         );
+        pub(crate) fn __rust_thunk___ZN15SomeOtherStructC1ERKS_<'a, 'b>(
+            __this: &'a mut ::core::mem::MaybeUninit<crate::SomeOtherStruct>,
+            __param_0: &'b crate::SomeOtherStruct,
+        );
         pub(crate) fn __rust_thunk___ZN15SomeOtherStructC1EOS_<'a, 'b>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::SomeOtherStruct>,
             __param_0: ::ctor::RvalueReference<'b, crate::SomeOtherStruct>,
         );
         pub(crate) fn __rust_thunk___ZN15SomeOtherStructaSERKS_<'a, 'b>(
-            __this: &'a mut crate::SomeOtherStruct,
+            __this: ::core::pin::Pin<&'a mut crate::SomeOtherStruct>,
             __param_0: &'b crate::SomeOtherStruct,
-        ) -> &'a mut crate::SomeOtherStruct;
+        ) -> ::core::pin::Pin<&'a mut crate::SomeOtherStruct>;
         pub(crate) fn __rust_thunk___ZN15SomeOtherStructaSEOS_<'a, 'b>(
-            __this: &'a mut crate::SomeOtherStruct,
+            __this: ::core::pin::Pin<&'a mut crate::SomeOtherStruct>,
             __param_0: ::ctor::RvalueReference<'b, crate::SomeOtherStruct>,
-        ) -> &'a mut crate::SomeOtherStruct;
+        ) -> ::core::pin::Pin<&'a mut crate::SomeOtherStruct>;
         pub(crate) fn __rust_thunk___ZN9SomeUnionC1Ev<'a>(
             __this: &'a mut ::core::mem::MaybeUninit<crate::SomeUnion>,
         );
@@ -297,21 +430,19 @@ mod detail {
             __param_0: ::ctor::RvalueReference<'b, crate::SomeOtherUnion>,
         ) -> &'a mut crate::SomeOtherUnion;
         #[link_name = "_Z23FunctionUsingNestedTypev"]
-        pub(crate) fn __rust_thunk___Z23FunctionUsingNestedTypev() -> ::core::ffi::c_int;
+        pub(crate) fn __rust_thunk___Z23FunctionUsingNestedTypev() -> crate::some_struct::nested_type;
     }
 }
 
 const _: () = {
     assert!(::core::mem::size_of::<crate::SomeStruct>() == 1);
     assert!(::core::mem::align_of::<crate::SomeStruct>() == 1);
-    static_assertions::assert_impl_all!(crate::SomeStruct: Clone);
-    static_assertions::assert_impl_all!(crate::SomeStruct: Copy);
+    static_assertions::assert_not_impl_any!(crate::SomeStruct: Copy);
     static_assertions::assert_not_impl_any!(crate::SomeStruct: Drop);
 
     assert!(::core::mem::size_of::<crate::SomeOtherStruct>() == 1);
     assert!(::core::mem::align_of::<crate::SomeOtherStruct>() == 1);
-    static_assertions::assert_impl_all!(crate::SomeOtherStruct: Clone);
-    static_assertions::assert_impl_all!(crate::SomeOtherStruct: Copy);
+    static_assertions::assert_not_impl_any!(crate::SomeOtherStruct: Copy);
     static_assertions::assert_not_impl_any!(crate::SomeOtherStruct: Drop);
 
     assert!(::core::mem::size_of::<crate::SomeUnion>() == 1);
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(sizeof(struct SomeStruct) == 1);
 static_assert(alignof(struct SomeStruct) == 1);
@@ -26,6 +27,11 @@ extern "C" void __rust_thunk___ZN10SomeStructC1Ev(struct SomeStruct* __this) {
   crubit::construct_at(__this);
 }
 
+extern "C" void __rust_thunk___ZN10SomeStructC1ERKS_(
+    struct SomeStruct* __this, const struct SomeStruct* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void __rust_thunk___ZN10SomeStructC1EOS_(
     struct SomeStruct* __this, struct SomeStruct* __param_0) {
   crubit::construct_at(__this, std::move(*__param_0));
@@ -49,6 +55,11 @@ extern "C" void __rust_thunk___ZN15SomeOtherStructC1Ev(
   crubit::construct_at(__this);
 }
 
+extern "C" void __rust_thunk___ZN15SomeOtherStructC1ERKS_(
+    SomeOtherStruct* __this, const SomeOtherStruct* __param_0) {
+  crubit::construct_at(__this, *__param_0);
+}
+
 extern "C" void __rust_thunk___ZN15SomeOtherStructC1EOS_(
     SomeOtherStruct* __this, SomeOtherStruct* __param_0) {
   crubit::construct_at(__this, std::move(*__param_0));
@@ -108,4 +119,6 @@ extern "C" SomeOtherUnion* __rust_thunk___ZN14SomeOtherUnionaSEOS_(
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
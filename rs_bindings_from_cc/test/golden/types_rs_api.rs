@@ -29,6 +29,9 @@ pub struct SomeStruct {
 }
 impl !Send for SomeStruct {}
 impl !Sync for SomeStruct {}
+impl oops::CcClass for SomeStruct {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("SomeStruct"), crate::SomeStruct);
 
 impl Default for SomeStruct {
@@ -123,6 +126,9 @@ pub struct FieldTypeTestStruct {
 }
 impl !Send for FieldTypeTestStruct {}
 impl !Sync for FieldTypeTestStruct {}
+impl oops::CcClass for FieldTypeTestStruct {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("FieldTypeTestStruct"),
     crate::FieldTypeTestStruct
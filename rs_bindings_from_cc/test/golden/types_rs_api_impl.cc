@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(sizeof(struct SomeStruct) == 1);
 static_assert(alignof(struct SomeStruct) == 1);
@@ -123,4 +124,6 @@ extern "C" void* __rust_thunk___Z24FunctionWithVoidPointersPvPKv(
   return FunctionWithVoidPointers(__param_0, __param_1);
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
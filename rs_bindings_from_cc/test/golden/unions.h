@@ -40,7 +40,7 @@ union NonCopyUnion2 {
 };
 
 union UnionWithOpaqueField {
-  char constant_array_field_not_yet_supported[42];
+  char constant_array_field[42];
 };
 
 struct TrivialButInheritable {
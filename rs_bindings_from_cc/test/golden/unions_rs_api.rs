@@ -23,6 +23,9 @@ pub union EmptyUnion {
 }
 impl !Send for EmptyUnion {}
 impl !Sync for EmptyUnion {}
+impl oops::CcClass for EmptyUnion {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("EmptyUnion"), crate::EmptyUnion);
 
 impl Default for EmptyUnion {
@@ -74,6 +77,9 @@ pub struct Nontrivial {
 }
 impl !Send for Nontrivial {}
 impl !Sync for Nontrivial {}
+impl oops::CcClass for Nontrivial {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Nontrivial"), crate::Nontrivial);
 
 impl ::ctor::CtorNew<()> for Nontrivial {
@@ -119,6 +125,9 @@ impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for Nontrivial {
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned(PinnedDrop)]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "TriviallyCopyableButNontriviallyDestructible")]
@@ -127,6 +136,9 @@ pub struct TriviallyCopyableButNontriviallyDestructible {
 }
 impl !Send for TriviallyCopyableButNontriviallyDestructible {}
 impl !Sync for TriviallyCopyableButNontriviallyDestructible {}
+impl oops::CcClass for TriviallyCopyableButNontriviallyDestructible {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("TriviallyCopyableButNontriviallyDestructible"),
     crate::TriviallyCopyableButNontriviallyDestructible
@@ -184,6 +196,9 @@ pub union NonEmptyUnion {
 }
 impl !Send for NonEmptyUnion {}
 impl !Sync for NonEmptyUnion {}
+impl oops::CcClass for NonEmptyUnion {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("NonEmptyUnion"), crate::NonEmptyUnion);
 
 impl Default for NonEmptyUnion {
@@ -235,6 +250,9 @@ pub union NonCopyUnion {
 }
 impl !Send for NonCopyUnion {}
 impl !Sync for NonCopyUnion {}
+impl oops::CcClass for NonCopyUnion {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("NonCopyUnion"), crate::NonCopyUnion);
 
 #[repr(C)]
@@ -246,6 +264,9 @@ pub union NonCopyUnion2 {
 }
 impl !Send for NonCopyUnion2 {}
 impl !Sync for NonCopyUnion2 {}
+impl oops::CcClass for NonCopyUnion2 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("NonCopyUnion2"), crate::NonCopyUnion2);
 
 // Error while generating bindings for item 'NonCopyUnion2::NonCopyUnion2':
@@ -276,12 +297,13 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for NonCopyUnion
 #[repr(C)]
 #[__crubit::annotate(cc_type = "UnionWithOpaqueField")]
 pub union UnionWithOpaqueField {
-    /// Reason for representing this field as a blob of bytes:
-    /// Unsupported type 'char[42]': Unsupported clang::Type class 'ConstantArray'
-    pub(crate) constant_array_field_not_yet_supported: [::core::mem::MaybeUninit<u8>; 42],
+    pub constant_array_field: [::core::ffi::c_char; 42],
 }
 impl !Send for UnionWithOpaqueField {}
 impl !Sync for UnionWithOpaqueField {}
+impl oops::CcClass for UnionWithOpaqueField {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("UnionWithOpaqueField"),
     crate::UnionWithOpaqueField
@@ -327,6 +349,9 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for UnionWithOpa
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[derive(Clone, Copy)]
 #[repr(C)]
 #[__crubit::annotate(cc_type = "TrivialButInheritable")]
@@ -335,6 +360,9 @@ pub struct TrivialButInheritable {
 }
 impl !Send for TrivialButInheritable {}
 impl !Sync for TrivialButInheritable {}
+impl oops::CcClass for TrivialButInheritable {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("TrivialButInheritable"),
     crate::TrivialButInheritable
@@ -388,6 +416,9 @@ pub union UnionWithInheritable {
 }
 impl !Send for UnionWithInheritable {}
 impl !Sync for UnionWithInheritable {}
+impl oops::CcClass for UnionWithInheritable {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("UnionWithInheritable"),
     crate::UnionWithInheritable
@@ -441,6 +472,9 @@ pub union TypedefUnion {
 }
 impl !Send for TypedefUnion {}
 impl !Sync for TypedefUnion {}
+impl oops::CcClass for TypedefUnion {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("TypedefUnion"), crate::TypedefUnion);
 
 impl Default for TypedefUnion {
@@ -491,6 +525,9 @@ pub union TypedefUnionWithInheritable {
 }
 impl !Send for TypedefUnionWithInheritable {}
 impl !Sync for TypedefUnionWithInheritable {}
+impl oops::CcClass for TypedefUnionWithInheritable {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("TypedefUnionWithInheritable"),
     crate::TypedefUnionWithInheritable
@@ -739,12 +776,9 @@ const _: () = {
     static_assertions::assert_impl_all!(crate::UnionWithOpaqueField: Clone);
     static_assertions::assert_impl_all!(crate::UnionWithOpaqueField: Copy);
     static_assertions::assert_not_impl_any!(crate::UnionWithOpaqueField: Drop);
-    assert!(
-        ::core::mem::offset_of!(
-            crate::UnionWithOpaqueField,
-            constant_array_field_not_yet_supported
-        ) == 0
-    );
+    assert!(::core::mem::offset_of!(crate::UnionWithOpaqueField, constant_array_field) == 0);
+    assert!(::core::mem::size_of::<[::core::ffi::c_char; 42]>() == 42);
+    static_assertions::assert_impl_all!([::core::ffi::c_char; 42]: Copy);
 
     assert!(::core::mem::size_of::<crate::TrivialButInheritable>() == 4);
     assert!(::core::mem::align_of::<crate::TrivialButInheritable>() == 4);
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(sizeof(union EmptyUnion) == 1);
 static_assert(alignof(union EmptyUnion) == 1);
@@ -118,7 +119,7 @@ extern "C" union NonCopyUnion2* __rust_thunk___ZN13NonCopyUnion2aSEOS_(
 
 static_assert(sizeof(union UnionWithOpaqueField) == 42);
 static_assert(alignof(union UnionWithOpaqueField) == 1);
-static_assert(CRUBIT_OFFSET_OF(constant_array_field_not_yet_supported,
+static_assert(CRUBIT_OFFSET_OF(constant_array_field,
                                union UnionWithOpaqueField) == 0);
 
 extern "C" void __rust_thunk___ZN20UnionWithOpaqueFieldC1Ev(
@@ -252,4 +253,6 @@ __rust_thunk___ZN27TypedefUnionWithInheritableaSEOS_(
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -42,7 +42,8 @@ struct PackedLayout final {
 volatile int* MultipleReasons(volatile int* n);
 
 struct ContainingStruct final {
-  struct NestedStruct final {
+  // Structs with packed layout are unsupported.
+  struct __attribute__((packed)) NestedStruct final {
     void NonStaticMemberFunction();
     void StaticMemberFunction();
   };
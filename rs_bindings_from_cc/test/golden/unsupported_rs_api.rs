@@ -23,6 +23,9 @@ pub struct TrivialCustomType {
 }
 impl !Send for TrivialCustomType {}
 impl !Sync for TrivialCustomType {}
+impl oops::CcClass for TrivialCustomType {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("TrivialCustomType"),
     crate::TrivialCustomType
@@ -83,6 +86,9 @@ pub struct NontrivialCustomType {
 }
 impl !Send for NontrivialCustomType {}
 impl !Sync for NontrivialCustomType {}
+impl oops::CcClass for NontrivialCustomType {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("NontrivialCustomType"),
     crate::NontrivialCustomType
@@ -137,6 +143,9 @@ pub struct ContainingStruct {
 }
 impl !Send for ContainingStruct {}
 impl !Sync for ContainingStruct {}
+impl oops::CcClass for ContainingStruct {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("ContainingStruct"),
     crate::ContainingStruct
@@ -183,7 +192,7 @@ impl<'b> ::ctor::UnpinAssign<::ctor::RvalueReference<'b, Self>> for ContainingSt
 }
 
 // Error while generating bindings for item 'ContainingStruct::NestedStruct':
-// Nested classes are not supported yet
+// Records with packed layout are not supported
 
 mod detail {
     #[allow(unused_imports)]
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct TrivialCustomType) == 4);
 static_assert(alignof(struct TrivialCustomType) == 4);
@@ -73,4 +74,6 @@ extern "C" struct ContainingStruct* __rust_thunk___ZN16ContainingStructaSEOS_(
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
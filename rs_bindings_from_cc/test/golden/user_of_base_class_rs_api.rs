@@ -29,6 +29,9 @@ pub struct Derived2 {
 }
 impl !Send for Derived2 {}
 impl !Sync for Derived2 {}
+impl oops::CcClass for Derived2 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(forward_declare::symbol!("Derived2"), crate::Derived2);
 
 impl ::ctor::CtorNew<()> for Derived2 {
@@ -137,6 +140,9 @@ unsafe impl oops::Inherits<inheritance_cc::Base2> for crate::Derived2 {
     }
 }
 
+/// This class is not `final`, so C++ code may derive from it. A reference to it may
+/// refer to the base class subobject of a derived class, and copying it by value
+/// slices off the derived part of the object.
 #[::ctor::recursively_pinned]
 #[repr(C, align(8))]
 #[__crubit::annotate(cc_type = "VirtualDerived2")]
@@ -145,6 +151,9 @@ pub struct VirtualDerived2 {
 }
 impl !Send for VirtualDerived2 {}
 impl !Sync for VirtualDerived2 {}
+impl oops::CcClass for VirtualDerived2 {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: false };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("VirtualDerived2"),
     crate::VirtualDerived2
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 static_assert(CRUBIT_SIZEOF(struct Derived2) == 24);
 static_assert(alignof(struct Derived2) == 8);
@@ -97,4 +98,6 @@ __crubit_dynamic_upcast__15VirtualDerived2__to__12VirtualBase2___2f_2fthird_5fpa
   return from;
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
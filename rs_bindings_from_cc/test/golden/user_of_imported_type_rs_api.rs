@@ -32,6 +32,9 @@ pub struct UserOfImportedType {
 }
 impl !Send for UserOfImportedType {}
 impl !Sync for UserOfImportedType {}
+impl oops::CcClass for UserOfImportedType {
+    const CC_CLASS_INFO: oops::CcClassInfo = oops::CcClassInfo { is_final: true };
+}
 forward_declare::unsafe_define!(
     forward_declare::symbol!("UserOfImportedType"),
     crate::UserOfImportedType
@@ -18,6 +18,7 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 extern "C" void __rust_thunk___Z16UsesImportedTypeN2ns7TrivialE(
     struct ns::Trivial* __return, struct ns::Trivial* t) {
@@ -51,4 +52,6 @@ __rust_thunk___ZN18UserOfImportedTypeaSEOS_(
   return &__this->operator=(std::move(*__param_0));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
@@ -18,10 +18,13 @@
 
 #pragma clang diagnostic push
 #pragma clang diagnostic ignored "-Wthread-safety-analysis"
+#pragma GCC visibility push(hidden)
 
 extern "C" void __rust_thunk___Z23UseNontrivialCustomType20NontrivialCustomType(
     struct NontrivialCustomType* non_trivial_custom_type) {
   UseNontrivialCustomType(std::move(*non_trivial_custom_type));
 }
 
+#pragma GCC visibility pop
+
 #pragma clang diagnostic pop
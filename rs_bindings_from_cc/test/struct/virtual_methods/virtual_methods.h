@@ -0,0 +1,31 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#ifndef CRUBIT_RS_BINDINGS_FROM_CC_TEST_STRUCT_VIRTUAL_METHODS_VIRTUAL_METHODS_H_
+#define CRUBIT_RS_BINDINGS_FROM_CC_TEST_STRUCT_VIRTUAL_METHODS_VIRTUAL_METHODS_H_
+
+#pragma clang lifetime_elision
+
+class Shape {
+ public:
+  virtual ~Shape() = default;
+  virtual int Sides() const { return 0; }
+  virtual int Area() const = 0;
+};
+
+class Square final : public Shape {
+ public:
+  explicit Square(int side) : side_(side) {}
+  int Sides() const override { return 4; }
+  int Area() const override { return side_ * side_; }
+
+ private:
+  int side_;
+};
+
+// Returns a `Square` as a `Shape`, so that Rust only knows its static type.
+inline Shape* NewSquare(int side) { return new Square(side); }
+inline void DeleteShape(Shape* shape) { delete shape; }
+
+#endif  // CRUBIT_RS_BINDINGS_FROM_CC_TEST_STRUCT_VIRTUAL_METHODS_VIRTUAL_METHODS_H_
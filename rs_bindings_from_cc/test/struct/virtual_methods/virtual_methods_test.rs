@@ -0,0 +1,20 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#[cfg(test)]
+mod tests {
+    use virtual_methods::*;
+
+    #[test]
+    fn test_virtual_call_is_dispatched_dynamically() {
+        let square = NewSquare(3);
+        {
+            // SAFETY: `square` points to a live object until `DeleteShape` below.
+            let shape: &Shape = unsafe { &*square };
+            assert_eq!(shape.Sides(), 4);
+            assert_eq!(shape.Area(), 9);
+        }
+        unsafe { DeleteShape(square) };
+    }
+}
@@ -8,8 +8,13 @@
 #include <string>
 
 #include "absl/container/flat_hash_map.h"
+#include "absl/strings/str_cat.h"
 #include "absl/strings/string_view.h"
 #include "rs_bindings_from_cc/ir.h"
+#include "clang/AST/ASTContext.h"
+#include "clang/AST/DeclCXX.h"
+#include "clang/AST/DeclTemplate.h"
+#include "clang/AST/TemplateBase.h"
 #include "clang/AST/Type.h"
 
 namespace crubit {
@@ -60,6 +65,117 @@ std::optional<absl::string_view> MapKnownCcTypeToRsType(
   return it->second;
 }
 
+// A mapping of Abseil time types to their Rust equivalents.  Unlike the types
+// above, these are not layout-compatible with the Rust types, and so the
+// bindings convert them when they are passed across the FFI boundary.
+std::optional<MappedType> MapAbslTimeType(const clang::Type& cc_type) {
+  const clang::CXXRecordDecl* record_decl = cc_type.getAsCXXRecordDecl();
+  if (record_decl == nullptr) return std::nullopt;
+  std::string name = record_decl->getQualifiedNameAsString();
+  if (name == "absl::Duration") {
+    return MappedType::Simple(std::string(internal::kRustAbslDuration), name);
+  }
+  if (name == "absl::Time") {
+    return MappedType::Simple(std::string(internal::kRustAbslTime), name);
+  }
+  return std::nullopt;
+}
+
+// Returns the name of the `std::chrono` alias (e.g. "milliseconds") for
+// `duration_decl`, if it is a `std::chrono::duration` with a 64-bit signed
+// representation, and with a period that the bindings support.
+std::optional<absl::string_view> GetChronoDurationUnit(
+    const clang::CXXRecordDecl* duration_decl) {
+  const auto* specialization_decl =
+      clang::dyn_cast_or_null<clang::ClassTemplateSpecializationDecl>(
+          duration_decl);
+  if (specialization_decl == nullptr ||
+      specialization_decl->getQualifiedNameAsString() !=
+          "std::chrono::duration") {
+    return std::nullopt;
+  }
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() != 2 || args[0].getKind() != clang::TemplateArgument::Type ||
+      args[1].getKind() != clang::TemplateArgument::Type) {
+    return std::nullopt;
+  }
+  clang::QualType rep = args[0].getAsType();
+  if (!rep->isSignedIntegerType() ||
+      specialization_decl->getASTContext().getTypeSize(rep) != 64) {
+    return std::nullopt;
+  }
+  const auto* ratio_decl =
+      clang::dyn_cast_or_null<clang::ClassTemplateSpecializationDecl>(
+          args[1].getAsType()->getAsCXXRecordDecl());
+  if (ratio_decl == nullptr ||
+      ratio_decl->getQualifiedNameAsString() != "std::ratio") {
+    return std::nullopt;
+  }
+  const clang::TemplateArgumentList& ratio_args = ratio_decl->getTemplateArgs();
+  if (ratio_args.size() != 2 ||
+      ratio_args[0].getKind() != clang::TemplateArgument::Integral ||
+      ratio_args[1].getKind() != clang::TemplateArgument::Integral ||
+      ratio_args[0].getAsIntegral() != 1) {
+    return std::nullopt;
+  }
+  switch (ratio_args[1].getAsIntegral().getExtValue()) {
+    case 1:
+      return "seconds";
+    case 1'000:
+      return "milliseconds";
+    case 1'000'000:
+      return "microseconds";
+    case 1'000'000'000:
+      return "nanoseconds";
+    default:
+      return std::nullopt;
+  }
+}
+
+// A mapping of `std::chrono` durations and `std::chrono::system_clock` time
+// points to `core::time::Duration` and `std::time::SystemTime`.  Like the
+// Abseil types above, these are converted at the FFI boundary.
+std::optional<MappedType> MapChronoType(const clang::Type& cc_type) {
+  const clang::CXXRecordDecl* record_decl = cc_type.getAsCXXRecordDecl();
+  if (record_decl == nullptr) return std::nullopt;
+  if (std::optional<absl::string_view> unit =
+          GetChronoDurationUnit(record_decl)) {
+    return MappedType::Simple(
+        absl::StrCat(internal::kRustChronoDuration, " ", *unit),
+        absl::StrCat("std::chrono::", *unit));
+  }
+
+  const auto* specialization_decl =
+      clang::dyn_cast<clang::ClassTemplateSpecializationDecl>(record_decl);
+  if (specialization_decl == nullptr ||
+      specialization_decl->getQualifiedNameAsString() !=
+          "std::chrono::time_point") {
+    return std::nullopt;
+  }
+  const clang::TemplateArgumentList& args =
+      specialization_decl->getTemplateArgs();
+  if (args.size() != 2 || args[0].getKind() != clang::TemplateArgument::Type ||
+      args[1].getKind() != clang::TemplateArgument::Type) {
+    return std::nullopt;
+  }
+  // Only `system_clock` has a well-known epoch (the Unix epoch).
+  const clang::CXXRecordDecl* clock_decl =
+      args[0].getAsType()->getAsCXXRecordDecl();
+  if (clock_decl == nullptr ||
+      clock_decl->getQualifiedNameAsString() != "std::chrono::system_clock") {
+    return std::nullopt;
+  }
+  std::optional<absl::string_view> unit =
+      GetChronoDurationUnit(args[1].getAsType()->getAsCXXRecordDecl());
+  if (!unit.has_value()) return std::nullopt;
+  return MappedType::Simple(
+      absl::StrCat(internal::kRustChronoSystemTime, " ", *unit),
+      absl::StrCat("std::chrono::time_point<std::chrono::system_clock, "
+                   "std::chrono::",
+                   *unit, ">"));
+}
+
 }  // namespace
 
 std::optional<MappedType> GetTypeMapOverride(const clang::Type& cc_type) {
@@ -69,7 +185,10 @@ std::optional<MappedType> GetTypeMapOverride(const clang::Type& cc_type) {
   if (rust_type.has_value()) {
     return MappedType::Simple(std::string(*rust_type), type_string);
   }
-  return std::nullopt;
+  if (std::optional<MappedType> absl_time_type = MapAbslTimeType(cc_type)) {
+    return absl_time_type;
+  }
+  return MapChronoType(cc_type);
 }
 
 }  // namespace crubit
@@ -18,7 +18,8 @@ namespace crubit {
 // The return value is a fully-qualified Rust name, including builtin type
 // names.
 //
-// For example, C++ `int64_t` becomes Rust `i64`.
+// For example, C++ `int64_t` becomes Rust `i64`, and `absl::Duration` becomes
+// `::core::time::Duration`.
 //
 // To create a new type mapping, add the type to the hardcoded list
 // of types.
@@ -0,0 +1,172 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! # Rust representation of Abseil time types.
+//!
+//! The bindings generated by `rs_bindings_from_cc` map:
+//!
+//! * `absl::Duration` to `core::time::Duration`,
+//! * `absl::Time` to `absl_time::Time`.
+//!
+//! Neither type is layout-compatible with its C++ counterpart, so values are
+//! converted when they cross the FFI boundary. They are passed as a number of
+//! nanoseconds (since the Unix epoch, in the case of `absl::Time`), which
+//! means that:
+//!
+//! * Infinite durations and times are represented by `i64::MAX` / `i64::MIN`
+//!   nanoseconds. `absl::InfiniteDuration()` becomes `Duration::MAX`, and
+//!   `absl::InfiniteFuture()` / `absl::InfinitePast()` become
+//!   `Time::INFINITE_FUTURE` / `Time::INFINITE_PAST`.
+//! * Finite values that don't fit into `i64` nanoseconds (about 292 years)
+//!   saturate, and become infinite.
+//! * Negative `absl::Duration`s become `Duration::ZERO`, because
+//!   `core::time::Duration` can't be negative.
+//!
+//! The C++ side of the conversions lives in `support/internal/absl_time.h`.
+
+use core::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A point in time, corresponding to C++ `absl::Time`.
+///
+/// The time is stored as the number of nanoseconds since the Unix epoch, with
+/// `i64::MAX` and `i64::MIN` reserved for the infinite future and past.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    unix_nanos: i64,
+}
+
+impl Time {
+    /// The Unix epoch (corresponds to `absl::UnixEpoch()`).
+    pub const UNIX_EPOCH: Time = Time { unix_nanos: 0 };
+
+    /// A time that is later than all other times (corresponds to
+    /// `absl::InfiniteFuture()`).
+    pub const INFINITE_FUTURE: Time = Time { unix_nanos: i64::MAX };
+
+    /// A time that is earlier than all other times (corresponds to
+    /// `absl::InfinitePast()`).
+    pub const INFINITE_PAST: Time = Time { unix_nanos: i64::MIN };
+
+    /// Returns the time `nanos` nanoseconds after the Unix epoch.
+    pub const fn from_unix_nanos(nanos: i64) -> Time {
+        Time { unix_nanos: nanos }
+    }
+
+    /// Returns the number of nanoseconds since the Unix epoch.
+    pub const fn unix_nanos(self) -> i64 {
+        self.unix_nanos
+    }
+
+    /// Returns true if this is `INFINITE_FUTURE` or `INFINITE_PAST`.
+    pub const fn is_infinite(self) -> bool {
+        self.unix_nanos == i64::MAX || self.unix_nanos == i64::MIN
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to `self`, or `None`
+    /// if `earlier` is later than `self`.
+    pub fn duration_since(self, earlier: Time) -> Option<Duration> {
+        if self.is_infinite() || earlier.is_infinite() {
+            return (self >= earlier).then_some(Duration::MAX);
+        }
+        let nanos = self.unix_nanos.checked_sub(earlier.unix_nanos)?;
+        Some(Duration::from_nanos(u64::try_from(nanos).ok()?))
+    }
+
+    /// Converts this time into a `SystemTime`, or returns `None` if it is
+    /// infinite or can't be represented by `SystemTime`.
+    pub fn to_system_time(self) -> Option<SystemTime> {
+        if self.is_infinite() {
+            return None;
+        }
+        let offset = Duration::from_nanos(self.unix_nanos.unsigned_abs());
+        if self.unix_nanos >= 0 {
+            UNIX_EPOCH.checked_add(offset)
+        } else {
+            UNIX_EPOCH.checked_sub(offset)
+        }
+    }
+}
+
+/// Converts a `SystemTime` into a `Time`. Times that are too far from the
+/// Unix epoch saturate to `Time::INFINITE_FUTURE` / `Time::INFINITE_PAST`.
+impl From<SystemTime> for Time {
+    fn from(time: SystemTime) -> Time {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(after_epoch) => Time { unix_nanos: internal::duration_to_nanos(after_epoch) },
+            Err(before_epoch) => {
+                let nanos = match internal::duration_to_nanos(before_epoch.duration()) {
+                    i64::MAX => i64::MIN,
+                    nanos => -nanos,
+                };
+                Time { unix_nanos: nanos }
+            }
+        }
+    }
+}
+
+/// Conversions used by the generated bindings.
+#[doc(hidden)]
+pub mod internal {
+    use super::*;
+
+    pub fn duration_to_nanos(duration: Duration) -> i64 {
+        i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX)
+    }
+
+    pub fn duration_from_nanos(nanos: i64) -> Duration {
+        match nanos {
+            i64::MAX => Duration::MAX,
+            ..=0 => Duration::ZERO,
+            nanos => Duration::from_nanos(nanos as u64),
+        }
+    }
+
+    pub fn time_to_unix_nanos(time: Time) -> i64 {
+        time.unix_nanos()
+    }
+
+    pub fn time_from_unix_nanos(nanos: i64) -> Time {
+        Time::from_unix_nanos(nanos)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::internal::*;
+    use super::*;
+
+    #[test]
+    fn test_duration_round_trip() {
+        let duration = Duration::from_millis(1500);
+        assert_eq!(duration_to_nanos(duration), 1_500_000_000);
+        assert_eq!(duration_from_nanos(1_500_000_000), duration);
+    }
+
+    #[test]
+    fn test_duration_saturation() {
+        assert_eq!(duration_to_nanos(Duration::MAX), i64::MAX);
+        assert_eq!(duration_from_nanos(i64::MAX), Duration::MAX);
+        assert_eq!(duration_from_nanos(-5), Duration::ZERO);
+        assert_eq!(duration_from_nanos(i64::MIN), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_time() {
+        let time = Time::from_unix_nanos(2_000_000_000);
+        assert_eq!(time.duration_since(Time::UNIX_EPOCH), Some(Duration::from_secs(2)));
+        assert_eq!(Time::UNIX_EPOCH.duration_since(time), None);
+        assert_eq!(time.to_system_time(), Some(UNIX_EPOCH + Duration::from_secs(2)));
+        assert_eq!(Time::from(UNIX_EPOCH + Duration::from_secs(2)), time);
+        assert_eq!(Time::from(UNIX_EPOCH - Duration::from_secs(2)).unix_nanos(), -2_000_000_000);
+    }
+
+    #[test]
+    fn test_infinite_time() {
+        assert!(Time::INFINITE_FUTURE.is_infinite());
+        assert!(Time::INFINITE_PAST < Time::UNIX_EPOCH);
+        assert_eq!(Time::INFINITE_FUTURE.to_system_time(), None);
+        assert_eq!(Time::INFINITE_FUTURE.duration_since(Time::UNIX_EPOCH), Some(Duration::MAX));
+    }
+}
@@ -0,0 +1,237 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#ifndef CRUBIT_SUPPORT_ANNOTATIONS_H_
+#define CRUBIT_SUPPORT_ANNOTATIONS_H_
+
+#include "support/internal/attribute_macros.h"
+
+// Pins the symbol name of the C++ thunk that Crubit generates for a function.
+//
+// By default, thunk names are derived from the mangled name of the function
+// (and possibly from the name of its target), and may change between Crubit
+// releases. A pinned thunk is always named `symbol`, and is always generated
+// (even when the Rust bindings could call the function directly), so that the
+// compiled `rs_api_impl` object can be distributed as a prebuilt binary.
+//
+// `symbol` must be a valid C identifier, and must be unique within the
+// program.
+//
+// The names and signatures of the pinned thunks of a target can be written to
+// a JSON manifest (see `--stable_thunks_out`). When the manifest of a previous
+// release is passed back to `rs_bindings_from_cc` (see
+// `--stable_thunks_baseline`), bindings generation fails if a pinned thunk was
+// removed or its signature changed.
+//
+// For example, this C++ header:
+//
+// ```c++
+// CRUBIT_STABLE_THUNK("mylib_add_v1")
+// int Add(int x, int y);
+// ```
+//
+// Is bound to Rust through an `extern "C"` thunk named `mylib_add_v1`.
+#define CRUBIT_STABLE_THUNK(symbol) \
+  CRUBIT_INTERNAL_ANNOTATE("crubit_stable_thunk", symbol)
+
+// Splices the Rust code in the file at `path` into the bindings, next to the
+// bindings for the annotated struct or class.
+//
+// This allows hand-written extensions of the bindings (e.g. builders, or
+// implementations of Rust traits) to live next to the C++ type, rather than in
+// a separate wrapper crate. Relative paths are resolved against the directory
+// of the header. The file must be available when the bindings are generated
+// (e.g. by listing it in the `textual_hdrs` of the `cc_library`).
+//
+// For example, this C++ header:
+//
+// ```c++
+// struct CRUBIT_EXTRA_RUST_IMPL("point_ext.rs") Point final {
+//   int x;
+//   int y;
+// };
+// ```
+//
+// And this `point_ext.rs` file:
+//
+// ```rust
+// impl Point {
+//     pub fn manhattan_length(&self) -> i32 {
+//         self.x.abs() + self.y.abs()
+//     }
+// }
+// ```
+//
+// Give `Point` a `manhattan_length` method in Rust.
+#define CRUBIT_EXTRA_RUST_IMPL(path) \
+  CRUBIT_INTERNAL_ANNOTATE("crubit_extra_rust_impl", path)
+
+// Makes the Rust bindings for the annotated struct or class opaque.
+//
+// The bindings for an opaque type don't expose its fields, constructors, or
+// layout: the Rust type is an opaque handle which can only be used behind
+// pointers and references (e.g. `&T`, `Pin<&mut T>`, or `*mut T`). Its methods
+// still have bindings, as long as they don't take or return it by value.
+//
+// Since the bindings don't assert the size, alignment, or field offsets of the
+// type, they keep working when its private members change. This is useful for
+// pImpl-style classes, whose objects are always created and destroyed by C++.
+//
+// For example, this C++ header:
+//
+// ```c++
+// class CRUBIT_OPAQUE Connection {
+//  public:
+//   static Connection* Open(const char* address);
+//   bool IsOpen() const;
+//
+//  private:
+//   struct Impl;
+//   std::unique_ptr<Impl> impl_;
+// };
+// ```
+//
+// Is bound to a Rust `Connection` type which has no fields or `Default`/`Clone`
+// implementations, and which is returned as a `*mut Connection` by `Open`.
+#define CRUBIT_OPAQUE CRUBIT_INTERNAL_ANNOTATE("crubit_opaque")
+
+// Binds the annotated enum to a Rust `enum`, rather than to a newtype struct
+// with associated constants.
+//
+// A Rust `enum` can only hold the values of its enumerators, so it is undefined
+// behavior for C++ to pass any other value of the enum to Rust. In exchange,
+// Rust code can match on the enum exhaustively. Values of the underlying type
+// can be checked and converted with `TryFrom`.
+//
+// For example, this C++ header:
+//
+// ```c++
+// enum class CRUBIT_RUST_ENUM Color : int { kRed, kBlue, kGreen };
+// ```
+//
+// Is bound to a `#[repr(i32)] pub enum Color { kRed = 0, kBlue = 1, ... }`.
+#define CRUBIT_RUST_ENUM CRUBIT_INTERNAL_ANNOTATE("crubit_rust_enum")
+
+// Keeps the Rust code which uses the previous name of the annotated struct,
+// class, enum, type alias, or function compiling after it was renamed.
+//
+// In addition to the bindings for the declaration, the bindings contain an
+// alias named `old_name`: a re-export for types, and a `#[deprecated]` function
+// which forwards to the renamed function for functions. The annotation can be
+// removed once the Rust callers have migrated. The alias is not generated if
+// `old_name` is taken by another item.
+//
+// Only functions at namespace scope can be annotated (not member functions).
+//
+// For example, this C++ header:
+//
+// ```c++
+// struct CRUBIT_RENAMED_FROM("Dimensions") Size final {
+//   int width;
+//   int height;
+// };
+//
+// CRUBIT_RENAMED_FROM("ComputeArea")
+// int Area(Size size);
+// ```
+//
+// Is bound to a Rust `Size` struct and `Area` function, as well as to a
+// `Dimensions` re-export and a deprecated `ComputeArea` function.
+#define CRUBIT_RENAMED_FROM(old_name) \
+  CRUBIT_INTERNAL_ANNOTATE("crubit_renamed_from", old_name)
+
+// Marks a function as returning a reference to an object with static storage
+// duration, such as a global variable or a function-local `static`.
+//
+// Without the annotation, the reference is returned as a raw pointer (or as a
+// reference whose lifetime is tied to the parameters). With the annotation,
+// it is returned as a `&'static` reference instead, so that Rust callers can
+// use singletons without `unsafe` code.
+//
+// The annotation can only be applied to functions returning a `const` lvalue
+// reference, since the Rust reference is shared, and the referenced object must
+// not be mutated while Rust holds it. It is up to the C++ author to make sure
+// that the referenced object is never destroyed.
+//
+// For example, this C++ header:
+//
+// ```c++
+// CRUBIT_RETURNS_STATIC
+// const Config& DefaultConfig();
+// ```
+//
+// Is bound to `pub fn DefaultConfig() -> &'static Config`.
+#define CRUBIT_RETURNS_STATIC CRUBIT_INTERNAL_ANNOTATE("crubit_returns_static")
+
+// Generates a `for_each` method for a map-like class, which calls a Rust
+// closure with a reference to the key and the value of each entry.
+//
+// `begin` and `end` must be the names of `const` member functions returning
+// the iterators over the entries, which must have `first` and `second` members
+// (like `std::pair`). The class must declare the member types `key_type` and
+// `mapped_type` (like `std::map` and `absl::flat_hash_map`), which are the
+// types of `first` and `second`.
+//
+// This gives Rust read access to the entries of a C++ map without bindings for
+// the map or its iterators. A panic in the closure aborts the process. The
+// `for_each` method is not generated if the class has a method named
+// `for_each`.
+//
+// For example, this C++ header:
+//
+// ```c++
+// class CRUBIT_MAP_VISITOR("begin", "end") Registry final {
+//  public:
+//   using key_type = int;
+//   using mapped_type = Entry;
+//   absl::flat_hash_map<int, Entry>::const_iterator begin() const;
+//   absl::flat_hash_map<int, Entry>::const_iterator end() const;
+//   ...
+// };
+// ```
+//
+// Allows this Rust code:
+//
+// ```rust
+// registry.for_each(|id: &i32, entry: &Entry| { ... });
+// ```
+#define CRUBIT_MAP_VISITOR(begin, end) \
+  CRUBIT_INTERNAL_ANNOTATE("crubit_map_visitor", "begin=" begin, "end=" end)
+
+// Implements `Future` for the Rust bindings of the annotated struct or class,
+// so that functions returning it (e.g. a coroutine `Task<T>`) can be `.await`ed.
+//
+// `poll` must be the name of a non-overloaded member function with the
+// signature `bool()`, which resumes the awaitable, and returns whether it has
+// completed. `get` must be the name of a member function which returns the
+// result of a completed awaitable, which must be an `Unpin` type without
+// lifetimes in Rust.
+//
+// The awaitable is not aware of the Rust `Waker`, so a pending awaitable asks to
+// be polled again right away: the executor spins on `poll` until the awaitable
+// completes, which keeps a thread busy while the awaitable waits (e.g. on I/O).
+// The annotation is therefore only suitable for awaitables which complete
+// quickly, and it requires the `experimental` feature.
+//
+// For example, this C++ header:
+//
+// ```c++
+// class CRUBIT_AWAITABLE("Resume", "Get") IntTask final {
+//  public:
+//   bool Resume();
+//   int Get() const;
+//   ...
+// };
+// IntTask ComputeAsync();
+// ```
+//
+// Allows this Rust code:
+//
+// ```rust
+// let value: i32 = ComputeAsync().await;
+// ```
+#define CRUBIT_AWAITABLE(poll, get) \
+  CRUBIT_INTERNAL_ANNOTATE("crubit_awaitable", "poll=" poll, "get=" get)
+
+#endif  // CRUBIT_SUPPORT_ANNOTATIONS_H_
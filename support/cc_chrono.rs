@@ -0,0 +1,135 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! # Rust representation of `std::chrono` types.
+//!
+//! The bindings generated by `rs_bindings_from_cc` map:
+//!
+//! * `std::chrono::nanoseconds`, `microseconds`, `milliseconds` and `seconds`
+//!   to `core::time::Duration`,
+//! * `std::chrono::system_clock` time points with one of the durations above
+//!   (e.g. `std::chrono::system_clock::time_point`) to
+//!   `std::time::SystemTime` (re-exported as `cc_chrono::SystemTime`).
+//!
+//! Values are passed across the FFI boundary as their C++ tick count (i.e.
+//! the result of `count()`, or of `time_since_epoch().count()`), which means
+//! that:
+//!
+//! * Converting a Rust value into a C++ value with a coarser unit truncates it
+//!   (towards zero for durations, and towards the past for time points).
+//! * Rust values that don't fit into an `int64_t` tick count saturate.
+//! * Negative C++ durations become `Duration::ZERO`, because
+//!   `core::time::Duration` can't be negative.
+//! * C++ time points that can't be represented by `SystemTime` on the current
+//!   platform cause a panic.
+
+use core::time::Duration;
+pub use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+/// Conversions used by the generated bindings.
+///
+/// `NANOS_PER_TICK` is the length of a single tick of the C++ duration (e.g.
+/// `1_000_000` for `std::chrono::milliseconds`), and has to divide one second.
+#[doc(hidden)]
+pub mod internal {
+    use super::*;
+
+    const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+    /// Returns the number of whole ticks in `duration`, saturating to
+    /// `i64::MAX`.
+    fn whole_ticks<const NANOS_PER_TICK: u64>(duration: Duration) -> i64 {
+        i64::try_from(duration.as_nanos() / u128::from(NANOS_PER_TICK)).unwrap_or(i64::MAX)
+    }
+
+    fn duration_from_unsigned_ticks<const NANOS_PER_TICK: u64>(ticks: u64) -> Duration {
+        let ticks_per_sec = NANOS_PER_SEC / NANOS_PER_TICK;
+        let nanos = (ticks % ticks_per_sec) * NANOS_PER_TICK;
+        Duration::new(ticks / ticks_per_sec, nanos as u32)
+    }
+
+    pub fn duration_to_ticks<const NANOS_PER_TICK: u64>(duration: Duration) -> i64 {
+        whole_ticks::<NANOS_PER_TICK>(duration)
+    }
+
+    pub fn duration_from_ticks<const NANOS_PER_TICK: u64>(ticks: i64) -> Duration {
+        match u64::try_from(ticks) {
+            Ok(ticks) => duration_from_unsigned_ticks::<NANOS_PER_TICK>(ticks),
+            Err(_) => Duration::ZERO,
+        }
+    }
+
+    pub fn system_time_to_ticks<const NANOS_PER_TICK: u64>(time: SystemTime) -> i64 {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(since_epoch) => whole_ticks::<NANOS_PER_TICK>(since_epoch),
+            Err(before_epoch) => {
+                // Round away from the epoch, so that the result is truncated
+                // towards the past (like `std::chrono::floor`).
+                let before_epoch = before_epoch.duration();
+                let ticks = whole_ticks::<NANOS_PER_TICK>(before_epoch);
+                let ticks = if before_epoch.as_nanos() % u128::from(NANOS_PER_TICK) == 0 {
+                    ticks
+                } else {
+                    ticks.saturating_add(1)
+                };
+                ticks.checked_neg().unwrap_or(i64::MIN)
+            }
+        }
+    }
+
+    pub fn system_time_from_ticks<const NANOS_PER_TICK: u64>(ticks: i64) -> SystemTime {
+        let offset = duration_from_unsigned_ticks::<NANOS_PER_TICK>(ticks.unsigned_abs());
+        let time = if ticks >= 0 {
+            UNIX_EPOCH.checked_add(offset)
+        } else {
+            UNIX_EPOCH.checked_sub(offset)
+        };
+        time.expect("C++ time point is out of the range of `SystemTime`")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::internal::*;
+    use super::*;
+
+    const MILLIS: u64 = 1_000_000;
+    const SECONDS: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_duration_round_trip() {
+        assert_eq!(duration_to_ticks::<MILLIS>(Duration::from_millis(1500)), 1500);
+        assert_eq!(duration_from_ticks::<MILLIS>(1500), Duration::from_millis(1500));
+        assert_eq!(duration_to_ticks::<1>(Duration::from_nanos(7)), 7);
+        assert_eq!(duration_from_ticks::<SECONDS>(3), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_duration_precision_and_range() {
+        assert_eq!(duration_to_ticks::<MILLIS>(Duration::from_micros(1999)), 1);
+        assert_eq!(duration_to_ticks::<1>(Duration::MAX), i64::MAX);
+        assert_eq!(duration_from_ticks::<MILLIS>(-5), Duration::ZERO);
+        assert_eq!(duration_from_ticks::<SECONDS>(i64::MAX), Duration::from_secs(i64::MAX as u64));
+    }
+
+    #[test]
+    fn test_system_time() {
+        let time = UNIX_EPOCH + Duration::from_millis(1500);
+        assert_eq!(system_time_to_ticks::<MILLIS>(time), 1500);
+        assert_eq!(system_time_from_ticks::<MILLIS>(1500), time);
+        assert_eq!(
+            system_time_from_ticks::<MILLIS>(-1500),
+            UNIX_EPOCH - Duration::from_millis(1500)
+        );
+    }
+
+    #[test]
+    fn test_system_time_before_epoch_is_floored() {
+        let time = UNIX_EPOCH - Duration::from_micros(1500);
+        assert_eq!(system_time_to_ticks::<MILLIS>(time), -2);
+        let time = UNIX_EPOCH - Duration::from_millis(2);
+        assert_eq!(system_time_to_ticks::<MILLIS>(time), -2);
+    }
+}
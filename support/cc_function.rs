@@ -0,0 +1,92 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! # Rust closures passed to C++ as `std::function<R(Args...)>`.
+//!
+//! The bindings generated by `rs_bindings_from_cc` map `std::function<R(Args...)>`
+//! parameters to `impl Fn(Args...) -> R + Send + Sync + 'static`.
+//!
+//! The closure is boxed into a `Box<dyn Fn(Args...) -> R>`, whose ownership is
+//! passed to C++ as an opaque handle, together with a function calling the
+//! closure (generated for each parameter), and a function dropping it (see
+//! `crubit/support/internal/function.h`). Since a `std::function` can be
+//! copied, the closure is shared by all the copies, and is only dropped when
+//! the last copy is destroyed.
+
+use core::ffi::c_void;
+
+/// Conversions used by the generated bindings.
+#[doc(hidden)]
+pub mod internal {
+    use super::*;
+
+    /// Gives up the ownership of `closure`, and returns a handle to it (which
+    /// is a thin pointer, even though `closure` is usually a trait object).
+    ///
+    /// The handle must be dropped with `drop_raw::<F>`, or else the closure is
+    /// leaked.
+    pub fn into_raw<F: ?Sized>(closure: Box<F>) -> *mut c_void {
+        Box::into_raw(Box::new(closure)) as *mut c_void
+    }
+
+    /// Returns a reference to the closure held by `handle`.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must have been returned by `into_raw::<F>`, and must not have
+    /// been dropped. The reference must not outlive the handle.
+    pub unsafe fn get<'a, F: ?Sized>(handle: *mut c_void) -> &'a F {
+        unsafe { &*(handle as *const Box<F>) }
+    }
+
+    /// Drops the closure held by `handle`.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must have been returned by `into_raw::<F>`, and must not have
+    /// been dropped already.
+    pub unsafe extern "C" fn drop_raw<F: ?Sized>(handle: *mut c_void) {
+        drop(unsafe { Box::from_raw(handle as *mut Box<F>) });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::internal::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    type Closure = dyn Fn(i32) -> i32;
+
+    #[test]
+    fn test_call() {
+        let offset = 40;
+        let handle = into_raw::<Closure>(Box::new(move |x| x + offset));
+        assert_eq!(unsafe { get::<Closure>(handle) }(2), 42);
+        assert_eq!(unsafe { get::<Closure>(handle) }(-40), 0);
+        unsafe { drop_raw::<Closure>(handle) };
+    }
+
+    #[test]
+    fn test_call_fn_pointer() {
+        fn double(x: i32) -> i32 {
+            x * 2
+        }
+        let handle = into_raw::<Closure>(Box::new(double));
+        assert_eq!(unsafe { get::<Closure>(handle) }(21), 42);
+        unsafe { drop_raw::<Closure>(handle) };
+    }
+
+    #[test]
+    fn test_drop_raw() {
+        let calls = Rc::new(Cell::new(0));
+        let captured = calls.clone();
+        let handle = into_raw::<dyn Fn()>(Box::new(move || captured.set(captured.get() + 1)));
+        unsafe { get::<dyn Fn()>(handle)() };
+        assert_eq!(calls.get(), 1);
+        assert_eq!(Rc::strong_count(&calls), 2);
+        unsafe { drop_raw::<dyn Fn()>(handle) };
+        assert_eq!(Rc::strong_count(&calls), 1);
+    }
+}
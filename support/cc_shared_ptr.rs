@@ -0,0 +1,202 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! # Rust representation of `std::shared_ptr<T>`.
+//!
+//! The bindings generated by `rs_bindings_from_cc` map `std::shared_ptr<T>`
+//! parameters and return values to `Option<cc_shared_ptr::SharedPtr<T>>`
+//! (`None` corresponding to a null pointer).
+//!
+//! A `SharedPtr` holds a handle to a heap-allocated C++ `std::shared_ptr<T>`,
+//! which is opaque to Rust. The handle is managed through thunks generated
+//! for each function returning a `std::shared_ptr<T>` (see `SharedPtrOps`):
+//! cloning a `SharedPtr` copies the `std::shared_ptr<T>` (incrementing the
+//! reference count), and dropping it deletes the copy (decrementing the
+//! reference count, and deleting the object if it was the last owner).
+
+use core::ffi::c_void;
+use core::fmt;
+use core::ptr::NonNull;
+
+/// The operations on the handle of a `SharedPtr<T>` (i.e. on a heap-allocated
+/// C++ `std::shared_ptr<T>`).
+pub struct SharedPtrOps<T> {
+    /// Returns the pointer stored in the `std::shared_ptr<T>`.
+    pub get: unsafe fn(*mut c_void) -> *mut T,
+    /// Returns a handle to a new copy of the `std::shared_ptr<T>`.
+    pub clone: unsafe fn(*mut c_void) -> *mut c_void,
+    /// Deletes the `std::shared_ptr<T>`.
+    pub delete: unsafe fn(*mut c_void),
+}
+
+impl<T> Clone for SharedPtrOps<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SharedPtrOps<T> {}
+
+/// A pointer to a `T` whose ownership is shared with other `SharedPtr<T>`s (and
+/// C++ `std::shared_ptr<T>`s), corresponding to a non-null C++
+/// `std::shared_ptr<T>`.
+///
+/// The object is only accessible through shared references, since other owners
+/// may access it at the same time.
+pub struct SharedPtr<T> {
+    ptr: NonNull<T>,
+    handle: NonNull<c_void>,
+    ops: SharedPtrOps<T>,
+}
+
+impl<T> SharedPtr<T> {
+    /// Takes ownership of `handle`, which is accessed through `ops`.
+    ///
+    /// Returns `None` if `handle` is null.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must either be null, or be a handle which isn't owned by
+    /// anything else, and which can be passed to the functions of `ops`.
+    /// `ops.get(handle)` must return a non-null pointer to a valid `T`.
+    pub unsafe fn from_raw(handle: *mut c_void, ops: SharedPtrOps<T>) -> Option<Self> {
+        let handle = NonNull::new(handle)?;
+        let ptr = NonNull::new(unsafe { (ops.get)(handle.as_ptr()) })
+            .expect("a non-null `std::shared_ptr<T>` handle should point to an object");
+        Some(SharedPtr { ptr, handle, ops })
+    }
+
+    /// Returns the pointer to the object.
+    pub fn as_ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns a shared reference to the object.
+    // Not `AsRef`, so that the method can't be shadowed by (or shadow) a
+    // method of `T` through auto-deref.
+    #[allow(clippy::should_implement_trait)]
+    pub fn as_ref(&self) -> &T {
+        // SAFETY: `ptr` points to a valid `T`, which is kept alive by `self`.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Returns `true` if `a` and `b` point to the same object.
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        a.ptr == b.ptr
+    }
+
+    /// Gives up the ownership of the handle, and returns it.
+    ///
+    /// The handle must be deleted by C++ (e.g. by passing it back to a
+    /// `std::shared_ptr<T>` parameter), or else the object is leaked.
+    pub fn into_raw(self) -> *mut c_void {
+        let handle = self.handle.as_ptr();
+        core::mem::forget(self);
+        handle
+    }
+}
+
+impl<T> Clone for SharedPtr<T> {
+    fn clone(&self) -> Self {
+        // SAFETY: `handle` is owned by `self`, and is accessed through `ops` (see
+        // `from_raw`).
+        let handle = unsafe { (self.ops.clone)(self.handle.as_ptr()) };
+        let handle = NonNull::new(handle).expect("cloning a `std::shared_ptr<T>` should succeed");
+        SharedPtr { ptr: self.ptr, handle, ops: self.ops }
+    }
+}
+
+impl<T> Drop for SharedPtr<T> {
+    fn drop(&mut self) {
+        // SAFETY: `handle` is owned by `self`, and is deleted through `ops` (see
+        // `from_raw`).
+        unsafe { (self.ops.delete)(self.handle.as_ptr()) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SharedPtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedPtr").field(self.as_ref()).finish()
+    }
+}
+
+/// Conversions used by the generated bindings.
+#[doc(hidden)]
+pub mod internal {
+    use super::*;
+
+    /// Gives up the ownership of the handle (if any), and returns it (or null),
+    /// for constructing a `std::shared_ptr<T>` in C++.
+    pub fn into_raw_or_null<T>(ptr: Option<SharedPtr<T>>) -> *mut c_void {
+        ptr.map_or(core::ptr::null_mut(), SharedPtr::into_raw)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::internal::*;
+    use super::*;
+    use std::rc::Rc;
+
+    // An `Rc<i32>` stands in for the `std::shared_ptr<int>` in these tests.
+    unsafe fn get(handle: *mut c_void) -> *mut i32 {
+        Rc::as_ptr(unsafe { &*(handle as *const Rc<i32>) }) as *mut i32
+    }
+
+    unsafe fn clone(handle: *mut c_void) -> *mut c_void {
+        let rc = unsafe { &*(handle as *const Rc<i32>) };
+        Box::into_raw(Box::new(rc.clone())) as *mut c_void
+    }
+
+    unsafe fn delete(handle: *mut c_void) {
+        drop(unsafe { Box::from_raw(handle as *mut Rc<i32>) });
+    }
+
+    const OPS: SharedPtrOps<i32> = SharedPtrOps { get, clone, delete };
+
+    fn new_handle(rc: Rc<i32>) -> *mut c_void {
+        Box::into_raw(Box::new(rc)) as *mut c_void
+    }
+
+    #[test]
+    fn test_from_raw_null() {
+        assert!(unsafe { SharedPtr::from_raw(core::ptr::null_mut(), OPS) }.is_none());
+    }
+
+    #[test]
+    fn test_as_ref() {
+        let ptr = unsafe { SharedPtr::from_raw(new_handle(Rc::new(42)), OPS) }
+            .expect("the handle should not be null");
+        assert_eq!(*ptr.as_ref(), 42);
+        assert_eq!(format!("{ptr:?}"), "SharedPtr(42)");
+    }
+
+    #[test]
+    fn test_clone_and_drop() {
+        let rc = Rc::new(42);
+        let ptr = unsafe { SharedPtr::from_raw(new_handle(rc.clone()), OPS) }
+            .expect("the handle should not be null");
+        assert_eq!(Rc::strong_count(&rc), 2);
+        let cloned = ptr.clone();
+        assert_eq!(Rc::strong_count(&rc), 3);
+        assert!(SharedPtr::ptr_eq(&ptr, &cloned));
+        assert_eq!(cloned.as_ptr(), Rc::as_ptr(&rc) as *mut i32);
+        drop(ptr);
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(cloned);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_into_raw_or_null() {
+        assert!(into_raw_or_null::<i32>(None).is_null());
+        let rc = Rc::new(42);
+        let handle = new_handle(rc.clone());
+        let ptr = unsafe { SharedPtr::from_raw(handle, OPS) };
+        assert_eq!(into_raw_or_null(ptr), handle);
+        assert_eq!(Rc::strong_count(&rc), 2);
+        unsafe { delete(handle) };
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
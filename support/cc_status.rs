@@ -0,0 +1,226 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! # Rust representation of `absl::Status`.
+//!
+//! The bindings generated by `rs_bindings_from_cc` map the return values of
+//! functions returning:
+//!
+//! * `absl::Status` to `Result<(), cc_status::Status>`,
+//! * `absl::StatusOr<T>` to `Result<T, cc_status::Status>`.
+//!
+//! A non-OK status is copied into a `Status` by the thunk of the function, so
+//! the error holds the code and the message of the status, but not its
+//! payloads. Messages which aren't valid UTF-8 are converted lossily.
+//!
+//! The C++ side of the conversion lives in `support/internal/absl_status.h`.
+
+use core::ffi::c_int;
+use core::fmt;
+
+/// The canonical error codes, corresponding to C++ `absl::StatusCode`.
+///
+/// `absl::StatusCode::kOk` has no counterpart, since an OK status is mapped to
+/// `Ok(...)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StatusCode {
+    Cancelled,
+    Unknown,
+    InvalidArgument,
+    DeadlineExceeded,
+    NotFound,
+    AlreadyExists,
+    PermissionDenied,
+    ResourceExhausted,
+    FailedPrecondition,
+    Aborted,
+    OutOfRange,
+    Unimplemented,
+    Internal,
+    Unavailable,
+    DataLoss,
+    Unauthenticated,
+}
+
+impl StatusCode {
+    /// Returns the code with the value `code` of `absl::StatusCode`, or
+    /// `Unknown` if it isn't a canonical error code (like `absl::Status`, which
+    /// replaces unknown codes with `absl::StatusCode::kUnknown`).
+    pub fn from_raw(code: c_int) -> StatusCode {
+        match code {
+            1 => StatusCode::Cancelled,
+            3 => StatusCode::InvalidArgument,
+            4 => StatusCode::DeadlineExceeded,
+            5 => StatusCode::NotFound,
+            6 => StatusCode::AlreadyExists,
+            7 => StatusCode::PermissionDenied,
+            8 => StatusCode::ResourceExhausted,
+            9 => StatusCode::FailedPrecondition,
+            10 => StatusCode::Aborted,
+            11 => StatusCode::OutOfRange,
+            12 => StatusCode::Unimplemented,
+            13 => StatusCode::Internal,
+            14 => StatusCode::Unavailable,
+            15 => StatusCode::DataLoss,
+            16 => StatusCode::Unauthenticated,
+            _ => StatusCode::Unknown,
+        }
+    }
+
+    /// Returns the value of `absl::StatusCode` corresponding to this code.
+    pub fn raw(self) -> c_int {
+        match self {
+            StatusCode::Cancelled => 1,
+            StatusCode::Unknown => 2,
+            StatusCode::InvalidArgument => 3,
+            StatusCode::DeadlineExceeded => 4,
+            StatusCode::NotFound => 5,
+            StatusCode::AlreadyExists => 6,
+            StatusCode::PermissionDenied => 7,
+            StatusCode::ResourceExhausted => 8,
+            StatusCode::FailedPrecondition => 9,
+            StatusCode::Aborted => 10,
+            StatusCode::OutOfRange => 11,
+            StatusCode::Unimplemented => 12,
+            StatusCode::Internal => 13,
+            StatusCode::Unavailable => 14,
+            StatusCode::DataLoss => 15,
+            StatusCode::Unauthenticated => 16,
+        }
+    }
+}
+
+/// A non-OK status, returned by a C++ function as an `absl::Status` or
+/// `absl::StatusOr<T>`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Status {
+    code: StatusCode,
+    message: String,
+}
+
+impl Status {
+    /// Returns a status with the error code `code` and the message `message`.
+    pub fn new(code: StatusCode, message: impl Into<String>) -> Status {
+        Status { code, message: message.into() }
+    }
+
+    /// Returns the error code of the status.
+    pub fn code(&self) -> StatusCode {
+        self.code
+    }
+
+    /// Returns the message of the status.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Formats the status like `absl::Status::ToString()` (without payloads), e.g.
+/// `NOT_FOUND: no such file`.
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self.code {
+            StatusCode::Cancelled => "CANCELLED",
+            StatusCode::Unknown => "UNKNOWN",
+            StatusCode::InvalidArgument => "INVALID_ARGUMENT",
+            StatusCode::DeadlineExceeded => "DEADLINE_EXCEEDED",
+            StatusCode::NotFound => "NOT_FOUND",
+            StatusCode::AlreadyExists => "ALREADY_EXISTS",
+            StatusCode::PermissionDenied => "PERMISSION_DENIED",
+            StatusCode::ResourceExhausted => "RESOURCE_EXHAUSTED",
+            StatusCode::FailedPrecondition => "FAILED_PRECONDITION",
+            StatusCode::Aborted => "ABORTED",
+            StatusCode::OutOfRange => "OUT_OF_RANGE",
+            StatusCode::Unimplemented => "UNIMPLEMENTED",
+            StatusCode::Internal => "INTERNAL",
+            StatusCode::Unavailable => "UNAVAILABLE",
+            StatusCode::DataLoss => "DATA_LOSS",
+            StatusCode::Unauthenticated => "UNAUTHENTICATED",
+        };
+        write!(f, "{code}: {}", self.message)
+    }
+}
+
+impl std::error::Error for Status {}
+
+/// Conversions used by the generated bindings.
+#[doc(hidden)]
+pub mod internal {
+    use super::*;
+
+    /// Stores the status with the code `code` and the message of `size` bytes
+    /// at `data` into `*error`.
+    ///
+    /// This is passed to the C++ thunks as the `crubit::SetStatusError`
+    /// callback.
+    ///
+    /// # Safety
+    ///
+    /// `error` must point to a valid `Option<Status>`, and `data` must point to
+    /// `size` readable bytes (or be dangling, if `size` is 0).
+    pub unsafe extern "C" fn set_status_error(
+        error: *mut core::ffi::c_void,
+        code: c_int,
+        data: *const u8,
+        size: usize,
+    ) {
+        let message = if size == 0 {
+            String::new()
+        } else {
+            String::from_utf8_lossy(unsafe { core::slice::from_raw_parts(data, size) }).into_owned()
+        };
+        let status = Status::new(StatusCode::from_raw(code), message);
+        unsafe {
+            *(error as *mut Option<Status>) = Some(status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::internal::*;
+    use super::*;
+
+    #[test]
+    fn test_status_code_round_trip() {
+        for raw in 1..=16 {
+            assert_eq!(StatusCode::from_raw(raw).raw(), raw);
+        }
+        assert_eq!(StatusCode::from_raw(0), StatusCode::Unknown);
+        assert_eq!(StatusCode::from_raw(42), StatusCode::Unknown);
+    }
+
+    #[test]
+    fn test_set_status_error() {
+        let mut error: Option<Status> = None;
+        let message = b"no such file";
+        unsafe {
+            set_status_error(
+                &mut error as *mut Option<Status> as *mut core::ffi::c_void,
+                5,
+                message.as_ptr(),
+                message.len(),
+            );
+        }
+        let error = error.unwrap();
+        assert_eq!(error.code(), StatusCode::NotFound);
+        assert_eq!(error.message(), "no such file");
+        assert_eq!(error.to_string(), "NOT_FOUND: no such file");
+    }
+
+    #[test]
+    fn test_set_status_error_with_empty_message() {
+        let mut error: Option<Status> = None;
+        unsafe {
+            set_status_error(
+                &mut error as *mut Option<Status> as *mut core::ffi::c_void,
+                13,
+                core::ptr::null(),
+                0,
+            );
+        }
+        assert_eq!(error, Some(Status::new(StatusCode::Internal, "")));
+    }
+}
@@ -0,0 +1,159 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+//! # Rust representation of `std::unique_ptr<T>`.
+//!
+//! The bindings generated by `rs_bindings_from_cc` map `std::unique_ptr<T>`
+//! parameters and return values to `Option<cc_unique_ptr::UniquePtr<T>>`
+//! (`None` corresponding to a null pointer).
+//!
+//! Ownership is transferred across the FFI boundary: a returned pointer is
+//! released by the thunk, and deleted by `UniquePtr::drop` through a deleter
+//! thunk (which calls `delete` in C++). A `UniquePtr` passed to a function is
+//! converted back into a `std::unique_ptr<T>` by the thunk.
+
+use core::fmt;
+use core::ptr::NonNull;
+
+/// An owning pointer to a `T` allocated by C++, corresponding to a non-null
+/// C++ `std::unique_ptr<T>`.
+pub struct UniquePtr<T> {
+    ptr: NonNull<T>,
+    deleter: unsafe fn(*mut T),
+}
+
+impl<T> UniquePtr<T> {
+    /// Takes ownership of `ptr`, which is deleted by calling `deleter`.
+    ///
+    /// Returns `None` if `ptr` is null.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must either be null, or point to a valid `T` which isn't owned
+    /// by anything else, and which can be deleted by calling `deleter(ptr)`.
+    pub unsafe fn from_raw(ptr: *mut T, deleter: unsafe fn(*mut T)) -> Option<Self> {
+        Some(UniquePtr { ptr: NonNull::new(ptr)?, deleter })
+    }
+
+    /// Returns the pointer to the object, without giving up its ownership.
+    pub fn as_ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns a shared reference to the object.
+    // Not `AsRef`, so that the method can't be shadowed by (or shadow) a
+    // method of `T` through auto-deref.
+    #[allow(clippy::should_implement_trait)]
+    pub fn as_ref(&self) -> &T {
+        // SAFETY: `ptr` points to a valid `T` owned by `self`.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Returns a mutable reference to the object.
+    ///
+    /// Only available for `Unpin` types, since C++ objects which aren't
+    /// trivially relocatable must not be moved out of (or swapped behind) the
+    /// reference.
+    #[allow(clippy::should_implement_trait)]
+    pub fn as_mut(&mut self) -> &mut T
+    where
+        T: Unpin,
+    {
+        // SAFETY: `ptr` points to a valid `T` owned (exclusively) by `self`.
+        unsafe { self.ptr.as_mut() }
+    }
+
+    /// Returns a pinned mutable reference to the object.
+    pub fn as_pin_mut(&mut self) -> core::pin::Pin<&mut T> {
+        // SAFETY: the object is never moved by `UniquePtr`.
+        unsafe { core::pin::Pin::new_unchecked(self.ptr.as_mut()) }
+    }
+
+    /// Gives up the ownership of the object, and returns the pointer to it.
+    ///
+    /// The object must be deleted by C++ (e.g. by passing the pointer back to
+    /// a `std::unique_ptr<T>`), or else it is leaked.
+    pub fn into_raw(self) -> *mut T {
+        let ptr = self.ptr.as_ptr();
+        core::mem::forget(self);
+        ptr
+    }
+}
+
+impl<T> Drop for UniquePtr<T> {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` is owned by `self`, and is deleted by `deleter` (see
+        // `from_raw`).
+        unsafe { (self.deleter)(self.ptr.as_ptr()) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for UniquePtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UniquePtr").field(self.as_ref()).finish()
+    }
+}
+
+/// Conversions used by the generated bindings.
+#[doc(hidden)]
+pub mod internal {
+    use super::*;
+
+    /// Gives up the ownership of the object (if any), and returns the pointer
+    /// to it (or null), for constructing a `std::unique_ptr<T>` in C++.
+    pub fn into_raw_or_null<T>(ptr: Option<UniquePtr<T>>) -> *mut T {
+        ptr.map_or(core::ptr::null_mut(), UniquePtr::into_raw)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::internal::*;
+    use super::*;
+
+    unsafe fn delete_box(ptr: *mut i32) {
+        drop(unsafe { Box::from_raw(ptr) });
+    }
+
+    #[test]
+    fn test_from_raw_null() {
+        assert!(unsafe { UniquePtr::from_raw(core::ptr::null_mut(), delete_box) }.is_none());
+    }
+
+    #[test]
+    fn test_as_ref_and_as_mut() {
+        let mut ptr = unsafe { UniquePtr::from_raw(Box::into_raw(Box::new(1)), delete_box) }
+            .expect("the pointer should not be null");
+        *ptr.as_mut() += 1;
+        assert_eq!(*ptr.as_ref(), 2);
+        *ptr.as_pin_mut() += 1;
+        assert_eq!(*ptr.as_ref(), 3);
+        assert_eq!(format!("{ptr:?}"), "UniquePtr(3)");
+    }
+
+    #[test]
+    fn test_drop_calls_deleter() {
+        use core::cell::Cell;
+        std::thread_local! {
+            static DELETED: Cell<usize> = const { Cell::new(0) };
+        }
+        unsafe fn count_and_delete(ptr: *mut i32) {
+            DELETED.with(|deleted| deleted.set(deleted.get() + 1));
+            unsafe { delete_box(ptr) };
+        }
+        let ptr = unsafe { UniquePtr::from_raw(Box::into_raw(Box::new(1)), count_and_delete) };
+        assert_eq!(DELETED.with(Cell::get), 0);
+        drop(ptr);
+        assert_eq!(DELETED.with(Cell::get), 1);
+    }
+
+    #[test]
+    fn test_into_raw_or_null() {
+        assert!(into_raw_or_null::<i32>(None).is_null());
+        let raw = Box::into_raw(Box::new(42));
+        let ptr = unsafe { UniquePtr::from_raw(raw, delete_box) };
+        assert_eq!(into_raw_or_null(ptr), raw);
+        unsafe { delete_box(raw) };
+    }
+}
@@ -0,0 +1,39 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#ifndef THIRD_PARTY_CRUBIT_SUPPORT_INTERNAL_ABSL_STATUS_H_
+#define THIRD_PARTY_CRUBIT_SUPPORT_INTERNAL_ABSL_STATUS_H_
+
+#include <cstddef>
+
+#include "absl/status/status.h"
+#include "absl/strings/string_view.h"
+
+namespace crubit {
+
+// Stores a non-OK status with the code `code` and the message of
+// `message_size` bytes at `message` into `error`.
+//
+// Implemented in Rust by `cc_status::internal::set_status_error`, which is
+// passed to the thunks of functions returning `absl::Status` or
+// `absl::StatusOr<T>`.
+using SetStatusError = void(void* error, int code, const char* message,
+                            size_t message_size);
+
+// Returns true if `status` is OK. Otherwise, reports its code and message
+// through `set_error(error, ...)` and returns false.
+//
+// The Rust side of the conversion lives in `support/cc_status.rs`.
+inline bool ReportStatus(const absl::Status& status, void* error,
+                         SetStatusError* set_error) {
+  if (status.ok()) return true;
+  absl::string_view message = status.message();
+  set_error(error, static_cast<int>(status.code()), message.data(),
+            message.size());
+  return false;
+}
+
+}  // namespace crubit
+
+#endif  // THIRD_PARTY_CRUBIT_SUPPORT_INTERNAL_ABSL_STATUS_H_
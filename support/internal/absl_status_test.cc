@@ -0,0 +1,54 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#include "support/internal/absl_status.h"
+
+#include <cstddef>
+#include <string>
+
+#include "gtest/gtest.h"
+#include "absl/status/status.h"
+
+namespace crubit {
+namespace {
+
+struct ReportedError {
+  bool reported = false;
+  int code = 0;
+  std::string message;
+};
+
+void SetError(void* error, int code, const char* message,
+              size_t message_size) {
+  auto* reported = static_cast<ReportedError*>(error);
+  reported->reported = true;
+  reported->code = code;
+  reported->message = std::string(message, message_size);
+}
+
+TEST(AbslStatusTest, OkStatusIsNotReported) {
+  ReportedError error;
+  EXPECT_TRUE(ReportStatus(absl::OkStatus(), &error, &SetError));
+  EXPECT_FALSE(error.reported);
+}
+
+TEST(AbslStatusTest, ErrorIsReported) {
+  ReportedError error;
+  EXPECT_FALSE(
+      ReportStatus(absl::NotFoundError("no such file"), &error, &SetError));
+  EXPECT_TRUE(error.reported);
+  EXPECT_EQ(error.code, static_cast<int>(absl::StatusCode::kNotFound));
+  EXPECT_EQ(error.message, "no such file");
+}
+
+TEST(AbslStatusTest, EmptyMessage) {
+  ReportedError error;
+  EXPECT_FALSE(ReportStatus(absl::InternalError(""), &error, &SetError));
+  EXPECT_TRUE(error.reported);
+  EXPECT_EQ(error.code, static_cast<int>(absl::StatusCode::kInternal));
+  EXPECT_EQ(error.message, "");
+}
+
+}  // namespace
+}  // namespace crubit
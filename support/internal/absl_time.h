@@ -0,0 +1,53 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#ifndef THIRD_PARTY_CRUBIT_SUPPORT_INTERNAL_ABSL_TIME_H_
+#define THIRD_PARTY_CRUBIT_SUPPORT_INTERNAL_ABSL_TIME_H_
+
+#include <cstdint>
+#include <limits>
+
+#include "absl/time/time.h"
+
+namespace crubit {
+
+// `absl::Duration` and `absl::Time` are passed to and from Rust as a number of
+// nanoseconds (since the Unix epoch, in the case of `absl::Time`).
+//
+// Values that don't fit into an `int64_t` saturate to its min / max, which are
+// in turn used to represent the infinite durations and times.  The Rust side
+// of the conversions lives in `support/absl_time.rs`.
+
+inline std::int64_t AbslDurationToNanos(absl::Duration duration) {
+  if (duration == absl::InfiniteDuration()) {
+    return std::numeric_limits<std::int64_t>::max();
+  }
+  if (duration == -absl::InfiniteDuration()) {
+    return std::numeric_limits<std::int64_t>::min();
+  }
+  // Saturates if `duration` doesn't fit.
+  return absl::ToInt64Nanoseconds(duration);
+}
+
+inline absl::Duration AbslDurationFromNanos(std::int64_t nanos) {
+  if (nanos == std::numeric_limits<std::int64_t>::max()) {
+    return absl::InfiniteDuration();
+  }
+  if (nanos == std::numeric_limits<std::int64_t>::min()) {
+    return -absl::InfiniteDuration();
+  }
+  return absl::Nanoseconds(nanos);
+}
+
+inline std::int64_t AbslTimeToUnixNanos(absl::Time time) {
+  return AbslDurationToNanos(time - absl::UnixEpoch());
+}
+
+inline absl::Time AbslTimeFromUnixNanos(std::int64_t nanos) {
+  return absl::UnixEpoch() + AbslDurationFromNanos(nanos);
+}
+
+}  // namespace crubit
+
+#endif  // THIRD_PARTY_CRUBIT_SUPPORT_INTERNAL_ABSL_TIME_H_
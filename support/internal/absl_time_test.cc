@@ -0,0 +1,49 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#include "support/internal/absl_time.h"
+
+#include <cstdint>
+#include <limits>
+
+#include "gtest/gtest.h"
+#include "absl/time/time.h"
+
+namespace crubit {
+namespace {
+
+constexpr std::int64_t kMax = std::numeric_limits<std::int64_t>::max();
+constexpr std::int64_t kMin = std::numeric_limits<std::int64_t>::min();
+
+TEST(AbslTimeTest, DurationRoundTrip) {
+  EXPECT_EQ(AbslDurationToNanos(absl::Milliseconds(1500)), 1'500'000'000);
+  EXPECT_EQ(AbslDurationToNanos(absl::Nanoseconds(-7)), -7);
+  EXPECT_EQ(AbslDurationFromNanos(1'500'000'000), absl::Milliseconds(1500));
+  EXPECT_EQ(AbslDurationFromNanos(-7), absl::Nanoseconds(-7));
+}
+
+TEST(AbslTimeTest, InfiniteDuration) {
+  EXPECT_EQ(AbslDurationToNanos(absl::InfiniteDuration()), kMax);
+  EXPECT_EQ(AbslDurationToNanos(-absl::InfiniteDuration()), kMin);
+  EXPECT_EQ(AbslDurationFromNanos(kMax), absl::InfiniteDuration());
+  EXPECT_EQ(AbslDurationFromNanos(kMin), -absl::InfiniteDuration());
+}
+
+TEST(AbslTimeTest, DurationOverflowSaturates) {
+  EXPECT_EQ(AbslDurationToNanos(absl::Hours(24 * 365 * 1000)), kMax);
+  EXPECT_EQ(AbslDurationToNanos(-absl::Hours(24 * 365 * 1000)), kMin);
+}
+
+TEST(AbslTimeTest, Time) {
+  EXPECT_EQ(AbslTimeToUnixNanos(absl::UnixEpoch()), 0);
+  EXPECT_EQ(AbslTimeToUnixNanos(absl::FromUnixSeconds(2)), 2'000'000'000);
+  EXPECT_EQ(AbslTimeFromUnixNanos(2'000'000'000), absl::FromUnixSeconds(2));
+  EXPECT_EQ(AbslTimeToUnixNanos(absl::InfiniteFuture()), kMax);
+  EXPECT_EQ(AbslTimeToUnixNanos(absl::InfinitePast()), kMin);
+  EXPECT_EQ(AbslTimeFromUnixNanos(kMax), absl::InfiniteFuture());
+  EXPECT_EQ(AbslTimeFromUnixNanos(kMin), absl::InfinitePast());
+}
+
+}  // namespace
+}  // namespace crubit
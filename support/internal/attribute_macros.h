@@ -71,4 +71,86 @@
 #define CRUBIT_INTERNAL_SAME_ABI \
   CRUBIT_INTERNAL_ANNOTATE("crubit_internal_same_abi")
 
+// Marks a pointer parameter and a length parameter of a function as a single
+// buffer, which is exposed as a slice in Rust.
+//
+// `ptr` must be the name of a parameter which points to bytes (`char`,
+// `signed char`, `unsigned char`, `int8_t`, `uint8_t` or `void`), and `len`
+// must be the name of an integer parameter holding the number of bytes. The
+// attribute may be repeated to describe several buffers.
+//
+// The Rust bindings take a single `&[u8]` parameter (`&[i8]` for signed
+// bytes) in place of the two parameters, or `&mut [u8]` if the pointee is not
+// `const`. Because the slice can't be null, dangling, or (for `&mut`)
+// aliased, a function that has no other pointer parameters becomes safe to
+// call.
+//
+// For example, this C++ header:
+//
+// ```c++
+// CRUBIT_INTERNAL_BUFFER("data", "size")
+// void Write(const char* data, size_t size);
+// ```
+//
+// Becomes this Rust interface:
+//
+// ```rust
+// pub fn Write(data: &[u8]);
+// ```
+#define CRUBIT_INTERNAL_BUFFER(ptr, len) \
+  CRUBIT_INTERNAL_ANNOTATE("crubit_buffer", "ptr=" ptr, "len=" len)
+
+// Like `CRUBIT_INTERNAL_BUFFER`, for a scratch buffer that the function fills,
+// returning the number of bytes that it wrote.
+//
+// `ptr` must point to non-const bytes, and the function must return an unsigned
+// integer that is at most the value of `len`. At most one buffer of a function
+// may return its length, and the function can't also have an out parameter.
+//
+// The Rust bindings return the number of bytes as a `usize`, and check (in
+// debug builds) that it doesn't exceed the length of the slice.
+//
+// For example, this C++ header:
+//
+// ```c++
+// CRUBIT_INTERNAL_BUFFER_RETURNING_LEN("buf", "len")
+// size_t ReadInto(char* buf, size_t len);
+// ```
+//
+// Becomes this Rust interface:
+//
+// ```rust
+// pub fn ReadInto(buf: &mut [u8]) -> usize;
+// ```
+#define CRUBIT_INTERNAL_BUFFER_RETURNING_LEN(ptr, len)              \
+  CRUBIT_INTERNAL_ANNOTATE("crubit_buffer", "ptr=" ptr, "len=" len, \
+                           "returns_len")
+
+// Marks a pointer parameter of a function as an out parameter, through which
+// the function returns its result.
+//
+// `param` must be the name of a parameter of type `T*`, where `T` is
+// default-constructible. The function must return either `bool` (`true` on
+// success), or an enum whose zero value (e.g. `kOk`) indicates success. On
+// success, the function must leave a valid `T` in `*param`.
+//
+// The Rust bindings drop the parameter, and return `Option<T>` (for `bool`) or
+// `Result<T, E>` (for an enum `E`) instead. The bindings default-construct the
+// `T` before the call, and destroy it if the call fails.
+//
+// For example, this C++ header:
+//
+// ```c++
+// CRUBIT_INTERNAL_OUT_PARAM("out")
+// bool Lookup(int key, int* out);
+// ```
+//
+// Becomes this Rust interface:
+//
+// ```rust
+// pub fn Lookup(key: i32) -> Option<i32>;
+// ```
+#define CRUBIT_INTERNAL_OUT_PARAM(param) \
+  CRUBIT_INTERNAL_ANNOTATE("crubit_out_param", param)
+
 #endif  // CRUBIT_SUPPORT_INTERNAL_ATTRIBUTES_H_
@@ -0,0 +1,38 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#ifndef CRUBIT_SUPPORT_INTERNAL_EXCEPTIONS_H_
+#define CRUBIT_SUPPORT_INTERNAL_EXCEPTIONS_H_
+
+#include <cstdio>
+#include <cstdlib>
+#include <exception>
+
+namespace crubit {
+
+// Prints `note` (and the `what()` of the exception currently being handled, if
+// any) to stderr, and aborts the program.
+//
+// Exceptions must not unwind from C++ into Rust. When `rs_bindings_from_cc` is
+// run with `--thunk_exception_note`, the thunks catch exceptions and call this
+// function instead, so that an accidental exception results in an actionable
+// crash rather than in undefined behavior.
+[[noreturn]] inline void TerminateWithNote(const char* note) {
+  std::fprintf(stderr, "%s\n", note);
+#if defined(__cpp_exceptions)
+  if (std::exception_ptr exception = std::current_exception()) {
+    try {
+      std::rethrow_exception(exception);
+    } catch (const std::exception& e) {
+      std::fprintf(stderr, "what(): %s\n", e.what());
+    } catch (...) {
+    }
+  }
+#endif
+  std::abort();
+}
+
+}  // namespace crubit
+
+#endif  // CRUBIT_SUPPORT_INTERNAL_EXCEPTIONS_H_
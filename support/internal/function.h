@@ -0,0 +1,72 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#ifndef CRUBIT_SUPPORT_INTERNAL_FUNCTION_H_
+#define CRUBIT_SUPPORT_INTERNAL_FUNCTION_H_
+
+#include <functional>
+#include <memory>
+#include <utility>
+
+namespace crubit {
+
+// Rust closures passed as `std::function<R(Args...)>` (see
+// `support/cc_function.rs`). The ownership of a closure is passed across the
+// FFI boundary as an opaque `void*` handle, together with a function calling
+// the closure, and a function dropping it.
+//
+// These are used by the thunks of functions taking a `std::function`.
+
+// The function dropping a Rust closure.
+using RustFunctionDrop = void (*)(void* closure);
+
+// A copyable C++ callable which calls a Rust closure. The copies share the
+// ownership of the closure, which is dropped with the last copy.
+template <typename R, typename... Args>
+class RustCallable {
+ public:
+  using Call = R (*)(void* closure, Args... args);
+
+  RustCallable(void* closure, Call call, RustFunctionDrop drop)
+      : closure_(closure, drop), call_(call) {}
+
+  R operator()(Args... args) const {
+    return call_(closure_.get(), std::forward<Args>(args)...);
+  }
+
+ private:
+  std::shared_ptr<void> closure_;
+  Call call_;
+};
+
+namespace internal {
+
+template <typename Function>
+struct RustFunctionTraits;
+
+template <typename R, typename... Args>
+struct RustFunctionTraits<std::function<R(Args...)>> {
+  using Callable = RustCallable<R, Args...>;
+};
+
+}  // namespace internal
+
+// The function calling a Rust closure passed as a `Function` (i.e. as a
+// `std::function<R(Args...)>`): `R (*)(void* closure, Args... args)`.
+template <typename Function>
+using RustFunctionCall =
+    typename internal::RustFunctionTraits<Function>::Callable::Call;
+
+// Returns a `Function` (i.e. a `std::function<R(Args...)>`) which calls the
+// Rust `closure` through `call`, and takes the ownership of `closure`.
+template <typename Function>
+Function MakeRustFunction(void* closure, RustFunctionCall<Function> call,
+                          RustFunctionDrop drop) {
+  using Callable = typename internal::RustFunctionTraits<Function>::Callable;
+  return Function(Callable(closure, call, drop));
+}
+
+}  // namespace crubit
+
+#endif  // CRUBIT_SUPPORT_INTERNAL_FUNCTION_H_
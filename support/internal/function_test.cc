@@ -0,0 +1,51 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#include "support/internal/function.h"
+
+#include <functional>
+
+#include "gtest/gtest.h"
+
+namespace crubit {
+namespace {
+
+// A heap-allocated `int` (the offset added by the closure) stands in for the
+// Rust closure in these tests.
+int AddOffset(void* closure, int x) { return *static_cast<int*>(closure) + x; }
+
+int drop_count = 0;
+
+void DropOffset(void* closure) {
+  ++drop_count;
+  delete static_cast<int*>(closure);
+}
+
+TEST(FunctionTest, Call) {
+  drop_count = 0;
+  {
+    std::function<int(int)> f = MakeRustFunction<std::function<int(int)>>(
+        new int(40), AddOffset, DropOffset);
+    EXPECT_EQ(f(2), 42);
+    EXPECT_EQ(f(-40), 0);
+  }
+  EXPECT_EQ(drop_count, 1);
+}
+
+TEST(FunctionTest, CopiesShareTheClosure) {
+  drop_count = 0;
+  std::function<int(int)> copy;
+  {
+    std::function<int(int)> f = MakeRustFunction<std::function<int(int)>>(
+        new int(40), AddOffset, DropOffset);
+    copy = f;
+  }
+  EXPECT_EQ(drop_count, 0);
+  EXPECT_EQ(copy(2), 42);
+  copy = nullptr;
+  EXPECT_EQ(drop_count, 1);
+}
+
+}  // namespace
+}  // namespace crubit
@@ -0,0 +1,56 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#ifndef CRUBIT_SUPPORT_INTERNAL_INITIALIZER_LIST_H_
+#define CRUBIT_SUPPORT_INTERNAL_INITIALIZER_LIST_H_
+
+#include <cstddef>
+#include <cstdlib>
+#include <initializer_list>
+#include <utility>
+
+namespace crubit {
+
+// The maximum number of elements that `CallWithInitializerList` can pass to a
+// function. Must match `MAX_INITIALIZER_LIST_SIZE` in
+// rs_bindings_from_cc/generate_bindings/generate_func.rs.
+inline constexpr size_t kMaxInitializerListSize = 16;
+
+namespace internal_initializer_list {
+
+template <typename T, typename F, size_t... kIndices>
+decltype(auto) Call(F& f, const T* data, std::index_sequence<kIndices...>) {
+  return f(std::initializer_list<T>{data[kIndices]...});
+}
+
+}  // namespace internal_initializer_list
+
+// Calls `f` with a `std::initializer_list<T>` holding copies of the `size`
+// elements at `data`, and returns the result.
+//
+// A `std::initializer_list` can only be created from a braced list, whose size
+// is known at compile time, so this dispatches to a braced list of each size up
+// to `kMaxInitializerListSize`. Larger sizes abort the program, so the Rust
+// bindings check the size of the slice (and panic) before calling the thunk.
+//
+// This is used by the thunks of functions taking a `std::initializer_list<T>`,
+// which Rust calls with the contents of a `&[T]`.
+template <size_t kSize = 0, typename T, typename F>
+decltype(auto) CallWithInitializerList(const T* data, size_t size, F&& f) {
+  if constexpr (kSize == kMaxInitializerListSize) {
+    if (size != kSize) std::abort();
+    return internal_initializer_list::Call(f, data,
+                                           std::make_index_sequence<kSize>());
+  } else {
+    if (size == kSize) {
+      return internal_initializer_list::Call(f, data,
+                                             std::make_index_sequence<kSize>());
+    }
+    return CallWithInitializerList<kSize + 1>(data, size, f);
+  }
+}
+
+}  // namespace crubit
+
+#endif  // CRUBIT_SUPPORT_INTERNAL_INITIALIZER_LIST_H_
@@ -0,0 +1,61 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#include "support/internal/initializer_list.h"
+
+#include <initializer_list>
+#include <vector>
+
+#include "gmock/gmock.h"
+#include "gtest/gtest.h"
+
+namespace crubit {
+namespace {
+
+using ::testing::ElementsAre;
+using ::testing::IsEmpty;
+
+std::vector<int> ToVector(std::initializer_list<int> list) { return list; }
+
+TEST(InitializerListTest, Empty) {
+  std::vector<int> result = CallWithInitializerList(
+      static_cast<const int*>(nullptr), 0,
+      [](std::initializer_list<int> list) { return ToVector(list); });
+  EXPECT_THAT(result, IsEmpty());
+}
+
+TEST(InitializerListTest, Elements) {
+  const int data[] = {1, 2, 3};
+  std::vector<int> result = CallWithInitializerList(
+      data, 3, [](std::initializer_list<int> list) { return ToVector(list); });
+  EXPECT_THAT(result, ElementsAre(1, 2, 3));
+}
+
+TEST(InitializerListTest, MaxSize) {
+  int data[kMaxInitializerListSize];
+  for (size_t i = 0; i < kMaxInitializerListSize; ++i) data[i] = i;
+  size_t size = CallWithInitializerList(
+      data, kMaxInitializerListSize,
+      [](std::initializer_list<int> list) { return list.size(); });
+  EXPECT_EQ(size, kMaxInitializerListSize);
+}
+
+TEST(InitializerListTest, ReturnsReference) {
+  int value = 0;
+  int& result = CallWithInitializerList(
+      &value, 1,
+      [&](std::initializer_list<int>) -> int& { return value; });
+  EXPECT_EQ(&result, &value);
+}
+
+TEST(InitializerListDeathTest, TooLarge) {
+  int data[kMaxInitializerListSize + 1] = {};
+  EXPECT_DEATH(CallWithInitializerList(
+                   data, kMaxInitializerListSize + 1,
+                   [](std::initializer_list<int> list) { return list.size(); }),
+               "");
+}
+
+}  // namespace
+}  // namespace crubit
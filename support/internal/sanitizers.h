@@ -0,0 +1,43 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#ifndef CRUBIT_SUPPORT_INTERNAL_SANITIZERS_H_
+#define CRUBIT_SUPPORT_INTERNAL_SANITIZERS_H_
+
+#include <cstddef>
+
+#include "absl/base/attributes.h"
+#include "absl/base/config.h"
+
+#ifdef ABSL_HAVE_MEMORY_SANITIZER
+#include <sanitizer/msan_interface.h>
+#endif
+
+// Opts a C++ function that calls into Rust out of ASan and MSan checks.
+//
+// Rust code is typically not instrumented, so sanitizers can't track the
+// memory that it writes. Without this attribute, MSan would report values
+// returned by Rust thunks as uninitialized. Note that MSan still instruments
+// functions with this attribute to avoid false positives in their callers
+// (e.g. their return values are considered initialized).
+#define CRUBIT_INTERNAL_NO_SANITIZE \
+  ABSL_ATTRIBUTE_NO_SANITIZE_ADDRESS ABSL_ATTRIBUTE_NO_SANITIZE_MEMORY
+
+namespace crubit {
+
+// Marks `size` bytes at `ptr` as initialized for MSan. Used for out-params
+// (e.g. `ReturnValueSlot`) that have been written by uninstrumented Rust code.
+// No-op when not building with MSan.
+inline void MsanUnpoison(const volatile void* ptr, std::size_t size) {
+#ifdef ABSL_HAVE_MEMORY_SANITIZER
+  __msan_unpoison(ptr, size);
+#else
+  (void)ptr;
+  (void)size;
+#endif
+}
+
+}  // namespace crubit
+
+#endif  // CRUBIT_SUPPORT_INTERNAL_SANITIZERS_H_
@@ -0,0 +1,58 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#ifndef CRUBIT_SUPPORT_INTERNAL_SHARED_PTR_H_
+#define CRUBIT_SUPPORT_INTERNAL_SHARED_PTR_H_
+
+#include <memory>
+#include <utility>
+
+namespace crubit {
+
+// Handles to heap-allocated `std::shared_ptr<T>`s, which represent a
+// `std::shared_ptr<T>` in Rust (see `support/cc_shared_ptr.rs`). The handles
+// are passed across the FFI boundary as `void*`, and only ever point to
+// objects of type `SharedPtr` (i.e. `std::shared_ptr<T>`).
+//
+// These are used by the thunks of functions taking or returning a
+// `std::shared_ptr<T>`.
+
+// Returns a new handle to `ptr`, or null if `ptr` doesn't point to an object.
+template <typename SharedPtr>
+void* NewSharedPtrHandle(SharedPtr ptr) {
+  if (ptr == nullptr) return nullptr;
+  return new SharedPtr(std::move(ptr));
+}
+
+// Deletes `handle` (if not null), and returns the `std::shared_ptr<T>` it
+// held (or an empty one).
+template <typename SharedPtr>
+SharedPtr TakeSharedPtrHandle(void* handle) {
+  if (handle == nullptr) return SharedPtr();
+  std::unique_ptr<SharedPtr> owned(static_cast<SharedPtr*>(handle));
+  return std::move(*owned);
+}
+
+// Returns the pointer stored in the `std::shared_ptr<T>` held by `handle`.
+template <typename SharedPtr>
+typename SharedPtr::element_type* SharedPtrHandleGet(void* handle) {
+  return static_cast<SharedPtr*>(handle)->get();
+}
+
+// Returns a new handle to a copy of the `std::shared_ptr<T>` held by `handle`
+// (which increments the reference count).
+template <typename SharedPtr>
+void* SharedPtrHandleClone(void* handle) {
+  return new SharedPtr(*static_cast<SharedPtr*>(handle));
+}
+
+// Deletes `handle` (which decrements the reference count).
+template <typename SharedPtr>
+void SharedPtrHandleDelete(void* handle) {
+  delete static_cast<SharedPtr*>(handle);
+}
+
+}  // namespace crubit
+
+#endif  // CRUBIT_SUPPORT_INTERNAL_SHARED_PTR_H_
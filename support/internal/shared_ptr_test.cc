@@ -0,0 +1,48 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#include "support/internal/shared_ptr.h"
+
+#include <memory>
+
+#include "gtest/gtest.h"
+
+namespace crubit {
+namespace {
+
+using SharedInt = std::shared_ptr<int>;
+
+TEST(SharedPtrTest, NullHandle) {
+  EXPECT_EQ(NewSharedPtrHandle(SharedInt()), nullptr);
+  EXPECT_EQ(TakeSharedPtrHandle<SharedInt>(nullptr), nullptr);
+}
+
+TEST(SharedPtrTest, RoundTrip) {
+  SharedInt ptr = std::make_shared<int>(42);
+  void* handle = NewSharedPtrHandle(ptr);
+  ASSERT_NE(handle, nullptr);
+  EXPECT_EQ(ptr.use_count(), 2);
+  EXPECT_EQ(SharedPtrHandleGet<SharedInt>(handle), ptr.get());
+
+  SharedInt taken = TakeSharedPtrHandle<SharedInt>(handle);
+  EXPECT_EQ(taken, ptr);
+  EXPECT_EQ(ptr.use_count(), 2);
+}
+
+TEST(SharedPtrTest, CloneAndDelete) {
+  SharedInt ptr = std::make_shared<int>(42);
+  void* handle = NewSharedPtrHandle(ptr);
+  void* cloned = SharedPtrHandleClone<SharedInt>(handle);
+  EXPECT_NE(cloned, handle);
+  EXPECT_EQ(SharedPtrHandleGet<SharedInt>(cloned), ptr.get());
+  EXPECT_EQ(ptr.use_count(), 3);
+
+  SharedPtrHandleDelete<SharedInt>(handle);
+  EXPECT_EQ(ptr.use_count(), 2);
+  SharedPtrHandleDelete<SharedInt>(cloned);
+  EXPECT_EQ(ptr.use_count(), 1);
+}
+
+}  // namespace
+}  // namespace crubit
@@ -202,6 +202,24 @@ unsafe impl<T> Inherits<T> for T {
     }
 }
 
+/// Properties of a C++ class which are not otherwise visible from Rust.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CcClassInfo {
+    /// Whether the class is `final` (or a `union`), and so can't be used as a
+    /// base class.
+    ///
+    /// A reference to a class which is not `final` may refer to the base class
+    /// subobject of a derived class.
+    pub is_final: bool,
+}
+
+/// Implemented by the Crubit bindings of every C++ class, so that macros and
+/// generic code can inspect the properties of the class, e.g.
+/// `<T as CcClass>::CC_CLASS_INFO.is_final`.
+pub trait CcClass {
+    const CC_CLASS_INFO: CcClassInfo;
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,87 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#ifndef CRUBIT_SUPPORT_RS_STD_MAP_VIEW_H_
+#define CRUBIT_SUPPORT_RS_STD_MAP_VIEW_H_
+
+#include <cstdlib>
+#include <memory>
+#include <type_traits>
+
+#include "absl/base/optimization.h"
+
+namespace rs_std {
+
+// `rs_std::MapView<K, V>` is a read-only view of a Rust `HashMap<K, V>` or
+// `BTreeMap<K, V>`.  It is returned by the C++ bindings of Rust functions which
+// return a shared reference to a map.
+//
+// The view doesn't own the map: like the Rust reference that it was created
+// from, it must not outlive the map, and the map must not be mutated while the
+// view is used.
+//
+// The lookups are implemented in Rust, so they use the `Hash` and `Eq` (or
+// `Ord`) implementations of the Rust key type.
+template <typename K, typename V>
+class MapView final {
+ public:
+  // The Rust thunks which implement the lookups for a specific map type.
+  // `map` is the `&HashMap<K, V>` (or `&BTreeMap<K, V>`) of the view.
+  struct VTable {
+    bool (*contains)(const void* map, const K* key);
+    // Returns `nullptr` if `key` is not in the map.
+    const V* (*get)(const void* map, const K* key);
+    void (*for_each)(const void* map, void* callback,
+                     void (*visit)(void* callback, const K* key,
+                                   const V* value));
+  };
+
+  // Creates a view of `map`.  Intended to only be used by the generated
+  // bindings.
+  constexpr MapView(const void* map, const VTable* vtable)
+      : map_(map), vtable_(vtable) {}
+
+  constexpr MapView(const MapView&) = default;
+  constexpr MapView& operator=(const MapView&) = default;
+  ~MapView() = default;
+
+  // Returns whether the map contains `key`.
+  //
+  // This function mimics Rust's `HashMap::contains_key`.
+  bool contains(const K& key) const { return vtable_->contains(map_, &key); }
+
+  // Returns the value for `key`.  Terminates the process if the map doesn't
+  // contain `key` (C++ bindings generated by Crubit assume `-fno-exceptions`,
+  // so this doesn't throw `std::out_of_range` like `std::map::at`).
+  const V& at(const K& key) const {
+    const V* value = vtable_->get(map_, &key);
+    if (ABSL_PREDICT_FALSE(value == nullptr)) {
+      std::abort();
+    }
+    return *value;
+  }
+
+  // Calls `callback(key, value)` for each entry of the map, in the iteration
+  // order of the Rust map (which is unspecified for a `HashMap`).
+  //
+  // `callback` must not unwind (e.g. throw a C++ exception), because it is
+  // called from Rust.
+  template <typename F>
+  void ForEach(F&& callback) const {
+    using Callback = std::remove_reference_t<F>;
+    vtable_->for_each(map_, const_cast<void*>(static_cast<const void*>(
+                                std::addressof(callback))),
+                      [](void* callback, const K* key, const V* value) {
+                        (*static_cast<Callback*>(callback))(*key, *value);
+                      });
+  }
+
+ private:
+  const void* map_;
+  const VTable* vtable_;
+};
+
+}  // namespace rs_std
+
+#endif  // CRUBIT_SUPPORT_RS_STD_MAP_VIEW_H_
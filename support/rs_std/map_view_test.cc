@@ -0,0 +1,86 @@
+// Part of the Crubit project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+#include "support/rs_std/map_view.h"
+
+#include <map>
+#include <type_traits>
+#include <utility>
+#include <vector>
+
+#include "gtest/gtest.h"
+
+namespace {
+
+using Map = std::map<int, float>;
+using View = rs_std::MapView<int, float>;
+
+static_assert(std::is_trivially_copyable_v<View>);
+static_assert(std::is_trivially_destructible_v<View>);
+
+// A `VTable` which implements the lookups for a C++ `std::map` (instead of the
+// Rust thunks that are used by the generated bindings).
+bool Contains(const void* map, const int* key) {
+  return static_cast<const Map*>(map)->count(*key) != 0;
+}
+
+const float* Get(const void* map, const int* key) {
+  const Map& m = *static_cast<const Map*>(map);
+  auto it = m.find(*key);
+  return it == m.end() ? nullptr : &it->second;
+}
+
+void ForEach(const void* map, void* callback,
+             void (*visit)(void*, const int*, const float*)) {
+  for (const auto& [key, value] : *static_cast<const Map*>(map)) {
+    visit(callback, &key, &value);
+  }
+}
+
+constexpr View::VTable kVTable = {&Contains, &Get, &ForEach};
+
+TEST(MapViewTest, Contains) {
+  Map map = {{1, 1.5f}, {2, 2.5f}};
+  View view(&map, &kVTable);
+  EXPECT_TRUE(view.contains(1));
+  EXPECT_TRUE(view.contains(2));
+  EXPECT_FALSE(view.contains(3));
+}
+
+TEST(MapViewTest, At) {
+  Map map = {{1, 1.5f}, {2, 2.5f}};
+  View view(&map, &kVTable);
+  EXPECT_EQ(view.at(1), 1.5f);
+  EXPECT_EQ(&view.at(2), &map.at(2));
+}
+
+TEST(MapViewDeathTest, AtMissingKey) {
+  Map map = {{1, 1.5f}};
+  View view(&map, &kVTable);
+  EXPECT_DEATH(view.at(3), "");
+}
+
+TEST(MapViewTest, ForEach) {
+  Map map = {{1, 1.5f}, {2, 2.5f}};
+  View view(&map, &kVTable);
+  std::vector<std::pair<int, float>> entries;
+  view.ForEach([&](const int& key, const float& value) {
+    entries.emplace_back(key, value);
+  });
+  EXPECT_EQ(entries,
+            (std::vector<std::pair<int, float>>{{1, 1.5f}, {2, 2.5f}}));
+}
+
+TEST(MapViewTest, ForEachWithConstCallback) {
+  Map map = {{1, 1.5f}, {2, 2.5f}};
+  View view(&map, &kVTable);
+  float sum = 0;
+  const auto callback = [&sum](const int&, const float& value) {
+    sum += value;
+  };
+  view.ForEach(callback);
+  EXPECT_EQ(sum, 4.0f);
+}
+
+}  // namespace